@@ -0,0 +1,162 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::size_history::now_secs;
+
+// What kind of operation an activity entry is about
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ActivityKind {
+    Backup,  // Safety snapshots and bundle exports
+    Restore, // Recovered saves, snapshots and Steam Cloud copies put back
+    Delete,  // Removed prefixes and pruned snapshots
+    Import,  // Bundles and dropped saves copied into prefixes
+}
+
+// One operation the app performed, kept so users can look up what was done and when
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ActivityEntry {
+    pub time: u64, // Unix seconds
+    pub kind: ActivityKind,
+    #[serde(default)]
+    pub app_id: Option<String>,
+    #[serde(default)]
+    pub game_name: Option<String>,
+    pub summary: String,
+    #[serde(default)]
+    pub paths: Vec<PathBuf>,
+    #[serde(default)]
+    pub bytes: Option<u64>,
+}
+
+impl ActivityEntry {
+    pub fn new(kind: ActivityKind, summary: impl Into<String>) -> Self {
+        Self {
+            time: now_secs(),
+            kind,
+            app_id: None,
+            game_name: None,
+            summary: summary.into(),
+            paths: Vec::new(),
+            bytes: None,
+        }
+    }
+
+    // The game is looked up in the config for its name, so the log stays
+    // readable after the prefix is gone
+    pub fn game(mut self, config: &Config, app_id: &str) -> Self {
+        self.app_id = Some(app_id.to_string());
+        self.game_name = config.display_name(app_id).map(str::to_string);
+        self
+    }
+
+    pub fn paths(mut self, paths: impl IntoIterator<Item = PathBuf>) -> Self {
+        self.paths = paths.into_iter().collect();
+        self
+    }
+
+    pub fn bytes(mut self, bytes: u64) -> Self {
+        self.bytes = Some(bytes);
+        self
+    }
+
+    // Case-insensitive search over everything shown for the entry. Every word
+    // of `query` has to match somewhere.
+    pub fn matches(&self, query: &str) -> bool {
+        let mut haystack = vec![self.summary.to_lowercase(), format!("{:?}", self.kind).to_lowercase()];
+        haystack.extend(self.app_id.iter().cloned());
+        haystack.extend(self.game_name.iter().map(|name| name.to_lowercase()));
+        haystack.extend(self.paths.iter().map(|path| path.to_string_lossy().to_lowercase()));
+        query
+            .to_lowercase()
+            .split_whitespace()
+            .all(|word| haystack.iter().any(|text| text.contains(word)))
+    }
+}
+
+// Appends `entry` to the activity log. The operation already happened, so a
+// log that can't be written is reported but doesn't fail it.
+pub fn record(config: &Config, entry: ActivityEntry) {
+    if let Err(e) = append(&config.activity_log_path(), &entry) {
+        eprintln!("Failed to record activity \"{}\": {:#}", entry.summary, e);
+    }
+}
+
+// One JSON object per line, so recording never has to rewrite the whole file
+fn append(path: &Path, entry: &ActivityEntry) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).context(format!("Failed to create {}", parent.display()))?;
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .context(format!("Failed to open activity log {}", path.display()))?;
+    writeln!(file, "{}", serde_json::to_string(entry)?)
+        .context(format!("Failed to write activity log {}", path.display()))
+}
+
+// Every entry in the log at `path`, newest first. Lines that can't be parsed
+// (e.g. cut off by a crash) are skipped.
+pub fn load(path: &Path) -> Vec<ActivityEntry> {
+    let Ok(content) = fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    let mut entries: Vec<ActivityEntry> = content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| match serde_json::from_str(line) {
+            Ok(entry) => Some(entry),
+            Err(e) => {
+                eprintln!("Skipping unreadable activity entry in {}: {}", path.display(), e);
+                None
+            }
+        })
+        .collect();
+    entries.reverse();
+    entries
+}
+
+pub fn clear(path: &Path) -> Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            Err(e).context(format!("Failed to remove activity log {}", path.display()))
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::Fixture;
+
+    #[test]
+    fn log_is_read_back_newest_first_and_searched() {
+        let fixture = Fixture::new();
+        let config = fixture.config();
+        record(&config, ActivityEntry::new(ActivityKind::Backup, "Cleanup").game(&config, "1000").bytes(42));
+        record(
+            &config,
+            ActivityEntry::new(ActivityKind::Delete, "Removed prefix").paths([fixture.compatdata_path().join("2000")]),
+        );
+        // A line cut off halfway doesn't lose the others
+        let path = config.activity_log_path();
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        write!(file, "{{\"time\": 1").unwrap();
+
+        let entries = load(&path);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].kind, ActivityKind::Delete);
+        assert_eq!(entries[1].bytes, Some(42));
+        assert!(entries[0].matches("removed 2000"));
+        assert!(entries[1].matches("BACKUP 1000"));
+        assert!(!entries[1].matches("removed"));
+
+        clear(&path).unwrap();
+        assert!(load(&path).is_empty());
+    }
+}
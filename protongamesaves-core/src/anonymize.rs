@@ -0,0 +1,222 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+use crate::config::Config;
+use crate::steam;
+
+// Bundles meant to be shared publicly can have the account IDs in their paths
+// replaced: Steam account IDs (the userdata folder names of this machine, and
+// any 64-bit Steam ID) and whatever folder a manifest path puts at
+// `<storeUserId>`. Each ID becomes `account-1`, `account-2`... and the
+// renamed paths are listed in REPORT_FILE_NAME, so whoever imports the saves
+// knows where to put their own IDs back in.
+//
+// File contents are left alone, only names are changed.
+
+// Written into the bundle, next to its bundle.json
+pub const REPORT_FILE_NAME: &str = "anonymized.txt";
+// Numbers shorter than this aren't taken for an account ID inside a longer
+// name, "slot1" has no account in it
+const MIN_ID_DIGITS: usize = 6;
+
+pub struct Scrubber {
+    ids: Vec<String>, // Account IDs seen so far, `account-<index + 1>` each
+    renamed: Vec<(usize, String)>, // Which ID and where, for the report
+}
+
+impl Scrubber {
+    // Knows the Steam accounts of this machine, other IDs are added as
+    // they're found
+    pub fn new(config: &Config) -> Self {
+        let mut ids = Vec::new();
+        for account in steam::accounts(config) {
+            if let Ok(id) = account.id.parse::<u64>() {
+                ids.push((id + steam::STEAM_ID64_BASE).to_string());
+            }
+            ids.push(account.id);
+        }
+        Self { ids, renamed: Vec::new() }
+    }
+
+    // Learn the ID in `drive_c_path` that `manifest_path` has `<storeUserId>` for
+    pub fn learn_store_user_id(&mut self, manifest_path: &str, drive_c_path: &Path) {
+        if let Some(id) = store_user_id(manifest_path, drive_c_path) {
+            if !self.ids.contains(&id) {
+                self.ids.push(id);
+            }
+        }
+    }
+
+    fn index_of(&mut self, id: &str) -> Option<usize> {
+        if let Some(index) = self.ids.iter().position(|known| known == id) {
+            return Some(index);
+        }
+        if !is_steam_id64(id) {
+            return None;
+        }
+        self.ids.push(id.to_string());
+        Some(self.ids.len() - 1)
+    }
+
+    // `name` with its account IDs replaced, and the first ID's index if any
+    fn scrub_name(&mut self, name: &str) -> (String, Option<usize>) {
+        if let Some(index) = self.ids.iter().position(|id| id == name) {
+            return (alias(index), Some(index));
+        }
+        let mut scrubbed = String::new();
+        let mut found = None;
+        let mut rest = name;
+        while let Some(start) = rest.find(|c: char| c.is_ascii_digit()) {
+            scrubbed.push_str(&rest[..start]);
+            let digits = rest[start..].find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len() - start);
+            let run = &rest[start..start + digits];
+            match self.index_of(run).filter(|_| run.len() >= MIN_ID_DIGITS) {
+                Some(index) => {
+                    scrubbed.push_str(&alias(index));
+                    found = found.or(Some(index));
+                }
+                None => scrubbed.push_str(run),
+            }
+            rest = &rest[start + digits..];
+        }
+        scrubbed.push_str(rest);
+        (scrubbed, found)
+    }
+
+    // Account IDs in a user ID or similar, e.g. of Steam settings
+    pub fn scrub_id(&mut self, id: &str, place: &str) -> String {
+        let (scrubbed, found) = self.scrub_name(id);
+        if let Some(index) = found {
+            self.renamed.push((index, place.to_string()));
+        }
+        scrubbed
+    }
+
+    // Every folder of `path` scrubbed, `place` says where it's from in the report
+    pub fn scrub_path(&mut self, path: &Path, place: &str) -> PathBuf {
+        let mut scrubbed = PathBuf::new();
+        let mut found = None;
+        for component in path.components() {
+            let (name, index) = self.scrub_name(&component.as_os_str().to_string_lossy());
+            scrubbed.push(name);
+            found = found.or(index);
+        }
+        if let Some(index) = found {
+            self.renamed.push((index, format!("{}: {}", place, scrubbed.display())));
+        }
+        scrubbed
+    }
+
+    // Rename the files and folders below `dir`, a folder of the bundle at
+    // `relative` inside it
+    pub fn scrub_tree(&mut self, dir: &Path, relative: &Path) -> Result<()> {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return Ok(());
+        };
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let (scrubbed, found) = self.scrub_name(&name);
+            let mut path = entry.path();
+            if let Some(index) = found {
+                let target = dir.join(&scrubbed);
+                fs::rename(&path, &target).context(format!("Failed to rename {}", path.display()))?;
+                self.renamed.push((index, relative.join(&scrubbed).display().to_string()));
+                path = target;
+            }
+            if entry.file_type().is_ok_and(|file_type| file_type.is_dir()) {
+                self.scrub_tree(&path, &relative.join(&scrubbed))?;
+            }
+        }
+        Ok(())
+    }
+
+    // What REPORT_FILE_NAME says, without the IDs that were replaced
+    pub fn report(&self) -> String {
+        let mut report = String::from(
+            "Account IDs in this bundle were replaced before sharing it. Saves that\n\
+             need them may only load again once they're back: rename the folders and\n\
+             files below to the IDs of your own accounts, and change the save locations\n\
+             in bundle.json to match them. On Steam the account ID is the name of your\n\
+             folder in Steam's userdata folder.\n",
+        );
+        if self.renamed.is_empty() {
+            report.push_str("\nNothing needed renaming.\n");
+        }
+        for index in 0..self.ids.len() {
+            let places: Vec<&String> = self.renamed.iter().filter(|(id, _)| *id == index).map(|(_, place)| place).collect();
+            if places.is_empty() {
+                continue;
+            }
+            report.push_str(&format!("\n{}\n", alias(index)));
+            for place in places {
+                report.push_str(&format!("  {}\n", place));
+            }
+        }
+        report
+    }
+}
+
+fn alias(index: usize) -> String {
+    format!("account-{}", index + 1)
+}
+
+// Individual accounts' 64-bit Steam IDs: 17 digits, the account ID on top of
+// the base
+fn is_steam_id64(digits: &str) -> bool {
+    digits.len() == 17
+        && digits
+            .parse::<u64>()
+            .is_ok_and(|id| id >= steam::STEAM_ID64_BASE && id - steam::STEAM_ID64_BASE < 1 << 32)
+}
+
+// The name of the folder in `drive_c_path` that `<storeUserId>` in the
+// manifest path stands for, counted from the end of the path. Paths with
+// `**` globs can span any number of folders, there's no telling.
+fn store_user_id(manifest_path: &str, drive_c_path: &Path) -> Option<String> {
+    let parts: Vec<&str> = manifest_path.split('/').filter(|part| !part.is_empty()).collect();
+    let index = parts.iter().position(|part| part.contains("<storeUserId>"))?;
+    let after = &parts[index + 1..];
+    if after.iter().any(|part| part.contains("**")) {
+        return None;
+    }
+    let components: Vec<String> = drive_c_path
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy().to_string())
+        .collect();
+    let name = components.get(components.len().checked_sub(after.len() + 1)?)?;
+    let (prefix, suffix) = parts[index].split_once("<storeUserId>")?;
+    let id = name.strip_prefix(prefix)?.strip_suffix(suffix)?;
+    (!id.is_empty() && !id.contains('<')).then(|| id.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{write_file, Fixture};
+
+    #[test]
+    fn replaces_account_ids_in_names() {
+        let fixture = Fixture::new();
+        let config = fixture.config();
+        fs::create_dir_all(config.steam_userdata_path().join("12345678")).unwrap();
+        let mut scrubber = Scrubber::new(&config);
+
+        scrubber.learn_store_user_id("<winAppData>/Studio/user_<storeUserId>/*.sav", Path::new("users/steamuser/AppData/Roaming/Studio/user_ab12cd/slot.sav"));
+        let path = scrubber.scrub_path(Path::new("users/steamuser/AppData/Roaming/Studio/ab12cd"), "0");
+        assert_eq!(path, Path::new("users/steamuser/AppData/Roaming/Studio/account-3"));
+        assert_eq!(scrubber.scrub_id("12345678", "Steam settings"), "account-2");
+
+        let tree = fixture.root.join("tree");
+        write_file(&tree.join("76561198000000001/profile_12345678.cfg"), "");
+        write_file(&tree.join("slot1.sav"), "");
+        scrubber.scrub_tree(&tree, Path::new("1000 - Lonely Hero/0")).unwrap();
+        assert!(tree.join("account-4/profile_account-2.cfg").exists());
+        assert!(tree.join("slot1.sav").exists());
+
+        let report = scrubber.report();
+        assert!(report.contains("account-4\n  1000 - Lonely Hero/0/account-4\n"));
+        assert!(!report.contains("12345678") && !report.contains("76561198000000001") && !report.contains("ab12cd"));
+    }
+}
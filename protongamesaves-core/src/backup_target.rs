@@ -0,0 +1,301 @@
+use anyhow::{anyhow, bail, Context, Result};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use crate::config::{BackupTargetSettings, PrivacySettings};
+use crate::fsutil;
+use crate::net::{self, NetworkFeature};
+
+// Somewhere a finished save bundle can be stored. The bundle is always built in
+// a local folder first by `bundle::export_bundle`, a target only has to copy
+// that folder, so local folders, servers and GVfs mounts are handled the same.
+// Stores run on worker threads, they may talk to the network.
+pub trait BackupTarget: Send {
+    // Shown in the export dialog and in messages, e.g. "NAS (SFTP)"
+    fn describe(&self) -> String;
+
+    // Copies `bundle_dir` into the target under the bundle's folder name (with
+    // a suffix if that's taken), returns where it ended up
+    fn store(&self, bundle_dir: &Path) -> Result<String>;
+
+    // Targets that are a local folder get the bundle written straight into it,
+    // without a temporary copy first
+    fn local_path(&self) -> Option<&Path> {
+        None
+    }
+}
+
+// The target for one destination set up in Settings. The privacy settings are
+// copied in since stores run off the main thread.
+pub fn from_settings(settings: &BackupTargetSettings, privacy: PrivacySettings) -> Box<dyn BackupTarget> {
+    match settings.clone() {
+        BackupTargetSettings::Local { name, path } => Box::new(LocalTarget { name, path }),
+        BackupTargetSettings::Sftp { name, host, port, user, path } => Box::new(SftpTarget { name, host, port, user, path, privacy }),
+        BackupTargetSettings::WebDav { name, url, user, password } => Box::new(WebDavTarget { name, url, user, password, privacy }),
+    }
+}
+
+fn bundle_name(bundle_dir: &Path) -> Result<String> {
+    bundle_dir
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .ok_or_else(|| anyhow!("{} has no folder name", bundle_dir.display()))
+}
+
+// `name`, or `name-2`, `name-3`… for the first one `taken` says is free
+fn free_name(name: &str, mut taken: impl FnMut(&str) -> Result<bool>) -> Result<String> {
+    let mut candidate = name.to_string();
+    let mut suffix = 2;
+    while taken(&candidate)? {
+        candidate = format!("{}-{}", name, suffix);
+        suffix += 1;
+    }
+    Ok(candidate)
+}
+
+// A folder on this machine, e.g. a mounted NAS share or an external drive
+pub struct LocalTarget {
+    name: String,
+    path: PathBuf,
+}
+
+impl LocalTarget {
+    pub fn new(name: &str, path: PathBuf) -> Self {
+        Self { name: name.to_string(), path }
+    }
+}
+
+impl BackupTarget for LocalTarget {
+    fn describe(&self) -> String {
+        format!("{} ({})", self.name, self.path.display())
+    }
+
+    fn store(&self, bundle_dir: &Path) -> Result<String> {
+        if !self.path.is_dir() {
+            bail!("Backup folder {} does not exist, is the drive connected?", self.path.display());
+        }
+        fsutil::ensure_free_space([(self.path.clone(), fsutil::disk_usage(bundle_dir))])?;
+        let name = free_name(&bundle_name(bundle_dir)?, |name| Ok(self.path.join(name).exists()))?;
+        let destination = self.path.join(name);
+        fsutil::copy_recursive(bundle_dir, &destination)
+            .context(format!("Failed to copy the bundle to {}", destination.display()))?;
+        Ok(destination.display().to_string())
+    }
+
+    fn local_path(&self) -> Option<&Path> {
+        Some(&self.path)
+    }
+}
+
+// A server reached with the system's sftp client. It runs in batch mode, so
+// logging in has to work without a password prompt (SSH key or agent).
+pub struct SftpTarget {
+    name: String,
+    host: String,
+    port: u16,
+    user: String,
+    path: String,
+    privacy: PrivacySettings,
+}
+
+impl SftpTarget {
+    fn destination(&self) -> String {
+        if self.user.is_empty() {
+            self.host.clone()
+        } else {
+            format!("{}@{}", self.user, self.host)
+        }
+    }
+
+    // Runs sftp commands, one per line. A leading `-` makes sftp carry on when
+    // that command fails.
+    fn run_batch(&self, commands: &[String]) -> Result<bool> {
+        net::check_allowed(&self.privacy, NetworkFeature::BackupUploads, &self.host)?;
+        println!("Running sftp on {}: {:?}", self.destination(), commands);
+        let mut child = Command::new("sftp")
+            // A server or user starting with - can't pass for an option after --
+            .args(["-b", "-", "-o", "BatchMode=yes", "-P", &self.port.to_string(), "--", &self.destination()])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .context("Failed to run sftp, is OpenSSH installed?")?;
+        if let Some(mut stdin) = child.stdin.take() {
+            for command in commands {
+                writeln!(stdin, "{}", command)?;
+            }
+        }
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            eprintln!("sftp on {} failed: {}", self.destination(), String::from_utf8_lossy(&output.stderr).trim());
+        }
+        Ok(output.status.success())
+    }
+
+    fn remote_path(&self, name: &str) -> String {
+        let folder = self.path.trim_end_matches('/');
+        if folder.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}/{}", folder, name)
+        }
+    }
+}
+
+impl BackupTarget for SftpTarget {
+    fn describe(&self) -> String {
+        format!("{} (SFTP {})", self.name, self.destination())
+    }
+
+    fn store(&self, bundle_dir: &Path) -> Result<String> {
+        // `ls` fails for a folder that isn't there
+        let name = free_name(&bundle_name(bundle_dir)?, |name| {
+            self.run_batch(&[format!("ls {}", sftp_quote(&self.remote_path(name)))])
+        })?;
+        let remote = self.remote_path(&name);
+        let mut commands = Vec::new();
+        if !self.path.trim_end_matches('/').is_empty() {
+            commands.push(format!("-mkdir {}", sftp_quote(self.path.trim_end_matches('/'))));
+        }
+        // Into a folder that isn't there yet, so sftp creates it with the
+        // bundle's contents instead of putting the bundle inside it
+        commands.push(format!("put -r {} {}", sftp_quote(&bundle_dir.to_string_lossy()), sftp_quote(&remote)));
+        if !self.run_batch(&commands)? {
+            bail!("Uploading the bundle to {} failed, see the log for sftp's error", self.destination());
+        }
+        Ok(format!("sftp://{}/{}", self.destination(), remote))
+    }
+}
+
+// sftp batch files take double-quoted arguments with backslash escapes
+fn sftp_quote(arg: &str) -> String {
+    format!("\"{}\"", arg.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+// A WebDAV folder, e.g. Nextcloud or ownCloud (use an app password there)
+pub struct WebDavTarget {
+    name: String,
+    url: String,
+    user: String,
+    password: String,
+    privacy: PrivacySettings,
+}
+
+impl WebDavTarget {
+    fn send(&self, client: &reqwest::blocking::Client, method: &str, url: &str, body: Option<Vec<u8>>) -> Result<reqwest::StatusCode> {
+        let method = reqwest::Method::from_bytes(method.as_bytes())?;
+        let mut request = net::request(client, &self.privacy, NetworkFeature::BackupUploads, method, url)?;
+        if !self.user.is_empty() {
+            request = request.basic_auth(&self.user, Some(&self.password));
+        }
+        if let Some(body) = body {
+            request = request.body(body);
+        }
+        let response = request.send().context(format!("Failed to send request to {}", url))?;
+        Ok(response.status())
+    }
+
+    fn upload_recursive(&self, client: &reqwest::blocking::Client, src: &Path, url: &str) -> Result<()> {
+        if src.is_dir() {
+            let status = self.send(client, "MKCOL", url, None)?;
+            if !status.is_success() {
+                bail!("Failed to create folder {} ({})", url, status);
+            }
+            for entry in fs::read_dir(src)? {
+                let entry = entry?;
+                let child = format!("{}/{}", url, encode_segment(&entry.file_name().to_string_lossy()));
+                self.upload_recursive(client, &entry.path(), &child)?;
+            }
+        } else {
+            let content = fs::read(src).context(format!("Failed to read {}", src.display()))?;
+            let status = self.send(client, "PUT", url, Some(content))?;
+            if !status.is_success() {
+                bail!("Failed to upload {} ({})", src.display(), status);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl BackupTarget for WebDavTarget {
+    fn describe(&self) -> String {
+        format!("{} (WebDAV)", self.name)
+    }
+
+    fn store(&self, bundle_dir: &Path) -> Result<String> {
+        let client = reqwest::blocking::Client::new();
+        let base = self.url.trim_end_matches('/');
+        // MKCOL on a folder that already exists fails with 405, so creating
+        // the bundle folder doubles as the check for a free name
+        let name = free_name(&bundle_name(bundle_dir)?, |name| {
+            let url = format!("{}/{}", base, encode_segment(name));
+            let status = self.send(&client, "MKCOL", &url, None)?;
+            match status.as_u16() {
+                405 => Ok(true),
+                _ if status.is_success() => Ok(false),
+                _ => bail!("Failed to create folder {} ({})", url, status),
+            }
+        })?;
+        let url = format!("{}/{}", base, encode_segment(&name));
+        for entry in fs::read_dir(bundle_dir)? {
+            let entry = entry?;
+            let child = format!("{}/{}", url, encode_segment(&entry.file_name().to_string_lossy()));
+            self.upload_recursive(&client, &entry.path(), &child)?;
+        }
+        println!("Uploaded bundle {} to {}", bundle_dir.display(), url);
+        Ok(url)
+    }
+}
+
+// Percent-encodes one path segment of a URL, everything but unreserved characters
+fn encode_segment(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{write_file, Fixture};
+
+    #[test]
+    fn local_target_keeps_earlier_bundles() {
+        let fixture = Fixture::new();
+        let bundle_dir = fixture.root.join("temp/proton-game-saves-bundle-2024-01-01");
+        write_file(&bundle_dir.join("bundle.json"), "{}");
+        let backups = fixture.root.join("backups");
+        fs::create_dir_all(&backups).unwrap();
+        let settings = BackupTargetSettings::Local { name: "Drive".to_string(), path: backups.clone() };
+        let target = from_settings(&settings, PrivacySettings::default());
+
+        target.store(&bundle_dir).unwrap();
+        let second = target.store(&bundle_dir).unwrap();
+        assert!(second.ends_with("proton-game-saves-bundle-2024-01-01-2"));
+        assert!(backups.join("proton-game-saves-bundle-2024-01-01/bundle.json").is_file());
+        assert_eq!(target.local_path(), Some(backups.as_path()));
+    }
+
+    #[test]
+    fn remote_targets_respect_offline_mode() {
+        let fixture = Fixture::new();
+        let bundle_dir = fixture.root.join("proton-game-saves-bundle-2024-01-01");
+        write_file(&bundle_dir.join("bundle.json"), "{}");
+        let privacy = PrivacySettings { offline_mode: true, ..Default::default() };
+        let settings = BackupTargetSettings::WebDav {
+            name: "Cloud".to_string(),
+            url: "https://example.invalid/dav".to_string(),
+            user: String::new(),
+            password: String::new(),
+        };
+        let error = from_settings(&settings, privacy).store(&bundle_dir).unwrap_err();
+        assert!(error.to_string().contains("Offline mode"));
+        assert_eq!(encode_segment("Saves & Co/1"), "Saves%20%26%20Co%2F1");
+    }
+}
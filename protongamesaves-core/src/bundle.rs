@@ -0,0 +1,453 @@
+use crate::activity::{self, ActivityEntry, ActivityKind};
+use crate::anonymize::{self, Scrubber};
+use crate::compatdata;
+use crate::config::Config;
+use crate::fsutil;
+use crate::ignore_patterns::IgnorePatterns;
+use crate::manifest::{self, ManifestInputs};
+use crate::registry;
+use crate::report;
+use crate::shader_cache;
+use crate::snapshot::Snapshot;
+use crate::steam_settings::{self, LaunchOptions, StoredSettings};
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// A bundle is a plain folder meant for moving every save to a new machine:
+//
+//   proton-game-saves-bundle-2024-05-01/
+//     bundle.json                 <- BundleManifest
+//     1245620 - ELDEN RING/0/...  <- one numbered copy per save location
+//     1245620 - ELDEN RING/registry.reg  <- registry keys holding saves, if any
+//     1245620 - ELDEN RING/steam-settings/0/...  <- controller layouts etc., if chosen
+//
+// Locations are stored relative to drive_c so they can be fanned back out
+// into prefixes wherever Steam lives on the other machine.
+const BUNDLE_MANIFEST: &str = "bundle.json";
+const BUNDLE_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+pub struct BundleManifest {
+    pub version: u32,
+    pub created: u64, // Unix timestamp (seconds)
+    pub games: Vec<BundleGame>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct BundleGame {
+    pub app_id: String,
+    pub name: String,
+    pub folder: String, // Subfolder of the bundle holding this game's locations
+    pub locations: Vec<BundleLocation>,
+    // Manifest registry keys exported into `registry::EXPORT_FILE_NAME`
+    #[serde(default)]
+    pub registry_keys: Vec<String>,
+    // Folders in `steam_settings::EXPORT_FOLDER`, empty unless the bundle
+    // was made with Steam settings included
+    #[serde(default)]
+    pub steam_settings: Vec<StoredSettings>,
+    #[serde(default)]
+    pub launch_options: Vec<LaunchOptions>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct BundleLocation {
+    pub manifest_path: String,
+    pub drive_c_path: PathBuf, // Relative to the prefix's drive_c
+    pub stored_name: String,
+    pub size_bytes: u64,
+}
+
+// One game to export, with the save locations that exist on disk
+#[derive(Clone)]
+pub struct ExportGame {
+    pub app_id: String,
+    pub name: String,
+    pub locations: Vec<ExportLocation>,
+    pub registry_keys: Vec<String>, // From the manifest, see `registry::manifest_keys`
+}
+
+#[derive(Clone)]
+pub struct ExportLocation {
+    pub manifest_path: String,
+    pub path: PathBuf,
+    pub size_bytes: u64,
+}
+
+// Result of importing a bundle
+pub struct ImportSummary {
+    pub restored_games: usize,
+    pub missing_prefixes: Vec<String>, // "Name (App ID)" of games without a prefix here
+    // "Name (App ID)" of games whose registry saves were left out, the
+    // manifest here doesn't know the App ID
+    pub unlisted_registry: Vec<String>,
+    // "Name: options" of games that had launch options, Steam has to be told
+    // about them again in the game's properties
+    pub launch_options: Vec<String>,
+}
+
+// Bytes an export of `games` will take, from the sizes computed during the scan
+pub fn estimate_size(games: &[ExportGame]) -> u64 {
+    games
+        .iter()
+        .flat_map(|game| game.locations.iter())
+        .map(|location| location.size_bytes)
+        .sum()
+}
+
+// Copy all `games` into a new bundle folder inside `parent`, returns the bundle
+// path. With `anonymize` account IDs are taken out of the names, see `anonymize`.
+pub fn export_bundle(config: &Config, games: &[ExportGame], parent: &Path, anonymize: bool) -> Result<PathBuf> {
+    fsutil::ensure_free_space([(parent.to_path_buf(), estimate_size(games))])?;
+    let created = now_secs();
+    let date = &report::format_timestamp_utc(created)[..10];
+    let mut bundle_dir = parent.join(format!("proton-game-saves-bundle-{}", date));
+    let mut suffix = 2;
+    while bundle_dir.exists() {
+        bundle_dir = parent.join(format!("proton-game-saves-bundle-{}-{}", date, suffix));
+        suffix += 1;
+    }
+    fs::create_dir_all(&bundle_dir)
+        .context(format!("Failed to create bundle folder {}", bundle_dir.display()))?;
+
+    let mut manifest = BundleManifest {
+        version: BUNDLE_VERSION,
+        created,
+        games: Vec::new(),
+    };
+
+    // IDs from every location are known before any name is scrubbed
+    let mut scrubber = anonymize.then(|| Scrubber::new(config));
+    if let Some(scrubber) = &mut scrubber {
+        for game in games {
+            let drive_c = config.drive_c_path(&game.app_id);
+            for location in &game.locations {
+                if let Ok(relative) = location.path.strip_prefix(&drive_c) {
+                    scrubber.learn_store_user_id(&location.manifest_path, relative);
+                }
+            }
+        }
+    }
+
+    // Save folders are copied after the loop, a few at a time (see
+    // `Config::io_limits`): source, destination and game name
+    let mut copies: Vec<(PathBuf, PathBuf, String, IgnorePatterns)> = Vec::new();
+    for game in games {
+        let drive_c = config.drive_c_path(&game.app_id);
+        let folder = format!("{} - {}", game.app_id, fsutil::sanitize_file_name(&game.name));
+        let mut locations = Vec::new();
+
+        for location in &game.locations {
+            let Ok(relative) = location.path.strip_prefix(&drive_c) else {
+                eprintln!("Skipping {}, it's outside the prefix", location.path.display());
+                continue;
+            };
+            let stored_name = locations.len().to_string();
+            copies.push((location.path.clone(), bundle_dir.join(&folder).join(&stored_name), game.name.clone(), config.ignore_patterns_for(&game.app_id)));
+            let drive_c_path = match &mut scrubber {
+                Some(scrubber) => scrubber.scrub_path(relative, &format!("{}/{} (save location in bundle.json)", folder, stored_name)),
+                None => relative.to_path_buf(),
+            };
+            locations.push(BundleLocation {
+                manifest_path: location.manifest_path.clone(),
+                drive_c_path,
+                stored_name,
+                size_bytes: location.size_bytes,
+            });
+        }
+
+        // Only keys the prefix has, games that never wrote them have none to export
+        let mut registry_keys = Vec::new();
+        if !game.registry_keys.is_empty() {
+            let pfx = config.prefix_path(&game.app_id).join("pfx");
+            if let Some(reg) = registry::export(&pfx, &game.registry_keys)? {
+                let game_dir = bundle_dir.join(&folder);
+                fs::create_dir_all(&game_dir)?;
+                fs::write(game_dir.join(registry::EXPORT_FILE_NAME), reg)
+                    .context(format!("Failed to export registry saves of {}", game.name))?;
+                registry_keys = game.registry_keys.clone();
+            }
+        }
+
+        let (mut steam_settings, mut launch_options) = (Vec::new(), Vec::new());
+        if config.bundle_steam_settings() {
+            steam_settings = steam_settings::export(config, &game.app_id, &bundle_dir.join(&folder).join(steam_settings::EXPORT_FOLDER))
+                .context(format!("Failed to export Steam settings of {}", game.name))?;
+            launch_options = steam_settings::launch_options(config, &game.app_id);
+            if let Some(scrubber) = &mut scrubber {
+                let place = format!("{} (Steam settings account in bundle.json)", folder);
+                for settings in &mut steam_settings {
+                    settings.account_id = scrubber.scrub_id(&settings.account_id, &place);
+                }
+                for options in &mut launch_options {
+                    options.account_id = scrubber.scrub_id(&options.account_id, &place);
+                }
+            }
+        }
+
+        if !locations.is_empty() || !registry_keys.is_empty() || !steam_settings.is_empty() {
+            manifest.games.push(BundleGame {
+                app_id: game.app_id.clone(),
+                name: game.name.clone(),
+                folder,
+                locations,
+                registry_keys,
+                steam_settings,
+                launch_options,
+            });
+        }
+    }
+
+    let copied = fsutil::parallel_map(copies, config.io_limits().copy_threads, |(source, dest, name, ignored)| {
+        let skip = |entry: &Path| shader_cache::is_cache_file(entry) || ignored.skips(&source, entry);
+        fsutil::copy_recursive_except(&source, &dest, &skip).context(format!("Failed to export saves of {}", name))
+    });
+    copied.into_iter().collect::<Result<Vec<()>>>()?;
+    if let Some(scrubber) = &mut scrubber {
+        for game in &manifest.games {
+            scrubber.scrub_tree(&bundle_dir.join(&game.folder), Path::new(&game.folder))?;
+        }
+        fs::write(bundle_dir.join(anonymize::REPORT_FILE_NAME), scrubber.report())
+            .context(format!("Failed to write {}", anonymize::REPORT_FILE_NAME))?;
+    }
+
+    fs::write(
+        bundle_dir.join(BUNDLE_MANIFEST),
+        serde_json::to_string_pretty(&manifest)?,
+    )?;
+    println!(
+        "Exported {} games to bundle {}",
+        manifest.games.len(),
+        bundle_dir.display()
+    );
+    activity::record(
+        config,
+        ActivityEntry::new(ActivityKind::Backup, format!("Exported {} games to a save bundle", manifest.games.len()))
+            .paths([bundle_dir.clone()])
+            .bytes(estimate_size(games)),
+    );
+    Ok(bundle_dir)
+}
+
+pub fn read_bundle(bundle_dir: &Path) -> Result<BundleManifest> {
+    let path = bundle_dir.join(BUNDLE_MANIFEST);
+    let content = fs::read_to_string(&path)
+        .context(format!("{} is not a save bundle (no {})", bundle_dir.display(), BUNDLE_MANIFEST))?;
+    let manifest: BundleManifest = serde_json::from_str(&content)
+        .context(format!("Failed to parse {}", path.display()))?;
+    if manifest.version > BUNDLE_VERSION {
+        bail!("This bundle was made by a newer version of the app (format {})", manifest.version);
+    }
+    Ok(manifest)
+}
+
+// Copy every game in the bundle back into its prefix. Saves already in the
+// way get a safety snapshot first; games without a prefix are skipped.
+pub fn import_bundle(config: &Config, bundle_dir: &Path, manifest: &BundleManifest) -> Result<ImportSummary> {
+    let mut summary = ImportSummary {
+        restored_games: 0,
+        missing_prefixes: Vec::new(),
+        unlisted_registry: Vec::new(),
+        launch_options: Vec::new(),
+    };
+
+    // The bundle's list of registry keys could name any key, only the ones
+    // this machine's manifest has for the App ID are written
+    let local_manifest = if manifest.games.iter().any(|game| !game.registry_keys.is_empty()) {
+        manifest::parse_manifest_inputs(&ManifestInputs::new(config), |_| {})
+            .map_err(|e| eprintln!("Not importing registry saves, the manifest couldn't be loaded: {:#}", e))
+            .ok()
+    } else {
+        None
+    };
+    let mut registry_checked: HashSet<&str> = HashSet::new();
+
+    // Don't let a tampered bundle write outside the bundle or the prefix. All
+    // games are checked before any is imported, so a bad entry can't leave
+    // the ones before it half done.
+    for game in &manifest.games {
+        let folder = Path::new(&game.folder);
+        let unsafe_path = !compatdata::is_app_id(&game.app_id)
+            || !fsutil::is_plain_relative(folder)
+            || folder.components().count() != 1
+            || game.locations.iter().any(|location| {
+                !fsutil::is_plain_relative(&location.drive_c_path) || !fsutil::is_plain_relative(Path::new(&location.stored_name))
+            })
+            || game.steam_settings.iter().any(|settings| !fsutil::is_plain_relative(Path::new(&settings.stored_name)));
+        if unsafe_path {
+            bail!("The bundle entry for {} contains an invalid path", game.name);
+        }
+        if game.registry_keys.is_empty() {
+            continue;
+        }
+        let Some((_, entry)) = local_manifest.as_ref().and_then(|data| manifest::find_game_for_prefix_by_steam_id(data, &game.app_id)) else {
+            continue;
+        };
+        let listed = registry::manifest_keys(entry);
+        if let Some(key) = game.registry_keys.iter().find(|key| !listed.contains(key)) {
+            bail!("The bundle entry for {} lists registry key {}, which isn't one of the game's save keys", game.name, key);
+        }
+        registry_checked.insert(&game.app_id);
+    }
+
+    // What's replaced in the prefixes is freed again, games without a prefix
+    // are skipped anyway
+    let prefixes = manifest.games.iter().map(|game| (game, config.drive_c_path(&game.app_id))).filter(|(_, drive_c)| drive_c.exists());
+    fsutil::ensure_free_space(prefixes.flat_map(|(game, drive_c)| {
+        game.locations.iter().map(move |location| {
+            let target = drive_c.join(&location.drive_c_path);
+            let growth = location.size_bytes.saturating_sub(fsutil::disk_usage(&target));
+            (target, growth)
+        })
+    }))?;
+
+    for game in &manifest.games {
+        let drive_c = config.drive_c_path(&game.app_id);
+        if !drive_c.exists() {
+            summary.missing_prefixes.push(format!("{} ({})", game.name, game.app_id));
+            continue;
+        }
+
+        let registry_keys: &[String] = if registry_checked.contains(game.app_id.as_str()) {
+            &game.registry_keys
+        } else {
+            if !game.registry_keys.is_empty() {
+                summary.unlisted_registry.push(format!("{} ({})", game.name, game.app_id));
+            }
+            &[]
+        };
+        let targets: Vec<PathBuf> = game
+            .locations
+            .iter()
+            .map(|location| drive_c.join(&location.drive_c_path))
+            .collect();
+        // The registry files and Steam settings are snapshotted along with the saves
+        let pfx = config.prefix_path(&game.app_id).join("pfx");
+        let mut snapshot_paths = targets.clone();
+        snapshot_paths.extend(registry::hive_files(&pfx, registry_keys));
+        snapshot_paths.extend(steam_settings::import_targets(config, &game.app_id, &game.steam_settings).into_iter().map(|(_, target)| target));
+        Snapshot::create(config, &game.app_id, "Before importing a save bundle", &snapshot_paths)
+            .context(format!("Failed to snapshot current saves of {}, not importing it", game.name))?;
+
+        for (location, target) in game.locations.iter().zip(&targets) {
+            let stored = bundle_dir.join(&game.folder).join(&location.stored_name);
+            fsutil::copy_recursive(&stored, target)
+                .context(format!("Failed to import saves of {}", game.name))?;
+        }
+        if !registry_keys.is_empty() {
+            let reg_path = bundle_dir.join(&game.folder).join(registry::EXPORT_FILE_NAME);
+            let reg = fs::read_to_string(&reg_path).context(format!("Failed to read {}", reg_path.display()))?;
+            registry::import(&pfx, registry_keys, &reg)
+                .context(format!("Failed to import registry saves of {}", game.name))?;
+        }
+        if !game.steam_settings.is_empty() {
+            let settings_dir = bundle_dir.join(&game.folder).join(steam_settings::EXPORT_FOLDER);
+            steam_settings::import(config, &game.app_id, &settings_dir, &game.steam_settings)
+                .context(format!("Failed to import Steam settings of {}", game.name))?;
+        }
+        // Only the first account's, they're usually the same
+        if let Some(options) = game.launch_options.first() {
+            summary.launch_options.push(format!("{}: {}", game.name, options.options));
+        }
+        summary.restored_games += 1;
+        activity::record(
+            config,
+            ActivityEntry::new(ActivityKind::Import, format!("Imported saves from bundle {}", bundle_dir.display()))
+                .game(config, &game.app_id)
+                .paths(targets.iter().cloned())
+                .bytes(game.locations.iter().map(|location| location.size_bytes).sum()),
+        );
+    }
+
+    println!(
+        "Imported {} games from bundle {}",
+        summary.restored_games,
+        bundle_dir.display()
+    );
+    Ok(summary)
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{write_file, Fixture, MINI_MANIFEST};
+
+    #[test]
+    fn rejects_tampered_bundles_before_importing() {
+        let fixture = Fixture::new();
+        fixture.add_prefix("1000");
+        let save = fixture.add_file("1000", "AppData/Roaming/LonelyHero/Saves/slot1.sav", "current");
+        let bundle_dir = fixture.root.join("bundle");
+        write_file(&bundle_dir.join("1000/0/slot1.sav"), "from the bundle");
+        let game = |app_id: &str| BundleGame {
+            app_id: app_id.to_string(),
+            name: "Lonely Hero".to_string(),
+            folder: "1000".to_string(),
+            locations: vec![BundleLocation {
+                manifest_path: String::new(),
+                drive_c_path: PathBuf::from("users/steamuser/AppData/Roaming/LonelyHero/Saves"),
+                stored_name: "0".to_string(),
+                size_bytes: 15,
+            }],
+            registry_keys: Vec::new(),
+            steam_settings: Vec::new(),
+            launch_options: Vec::new(),
+        };
+        let manifest = BundleManifest { version: 1, created: 0, games: vec![game("1000"), game("../..")] };
+
+        assert!(import_bundle(&fixture.config(), &bundle_dir, &manifest).is_err());
+        // The valid entry before it wasn't imported either
+        assert_eq!(fs::read_to_string(&save).unwrap(), "current");
+    }
+
+    #[test]
+    fn only_imports_registry_keys_the_manifest_lists() {
+        let fixture = Fixture::new();
+        fixture.write_manifest(&format!(
+            "{}\"Lonely Hero Registry\":\n  registry:\n    \"HKEY_CURRENT_USER/Software/LonelyHero\":\n      tags:\n        - save\n  steam:\n    id: 2000\n",
+            MINI_MANIFEST
+        ));
+        let pfx = fixture.add_prefix("2000").join("pfx");
+        let user_reg = "WINE REGISTRY Version 2\n\n[Software\\\\Microsoft\\\\Windows\\\\CurrentVersion\\\\Run] 1700000000\n\n";
+        write_file(&pfx.join("user.reg"), user_reg);
+        let bundle_dir = fixture.root.join("bundle");
+        write_file(
+            &bundle_dir.join("2000").join(registry::EXPORT_FILE_NAME),
+            "Windows Registry Editor Version 5.00\n\n[HKEY_CURRENT_USER\\Software\\Microsoft\\Windows\\CurrentVersion\\Run]\n\"Evil\"=\"calc.exe\"\n",
+        );
+        let game = BundleGame {
+            app_id: "2000".to_string(),
+            name: "Lonely Hero Registry".to_string(),
+            folder: "2000".to_string(),
+            locations: Vec::new(),
+            registry_keys: vec![
+                "HKEY_CURRENT_USER\\Software\\LonelyHero".to_string(),
+                "HKEY_CURRENT_USER\\Software\\Microsoft\\Windows\\CurrentVersion\\Run".to_string(),
+            ],
+            steam_settings: Vec::new(),
+            launch_options: Vec::new(),
+        };
+        let manifest = BundleManifest { version: 1, created: 0, games: vec![game] };
+        assert!(import_bundle(&fixture.config(), &bundle_dir, &manifest).is_err());
+        assert_eq!(fs::read_to_string(pfx.join("user.reg")).unwrap(), user_reg);
+
+        // An App ID the manifest doesn't know gets no registry keys at all
+        let mut manifest = manifest;
+        manifest.games[0].app_id = "4242".to_string();
+        fixture.add_prefix("4242");
+        let summary = import_bundle(&fixture.config(), &bundle_dir, &manifest).unwrap();
+        assert_eq!(summary.unlisted_registry, vec!["Lonely Hero Registry (4242)".to_string()]);
+        assert_eq!(summary.restored_games, 1);
+    }
+}
@@ -0,0 +1,132 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::activity::{self, ActivityEntry, ActivityKind};
+use crate::compatdata::PrefixData;
+use crate::config::Config;
+use crate::fsutil;
+use crate::snapshot::Snapshot;
+use crate::steam;
+
+const SECS_PER_MONTH: u64 = 30 * 24 * 60 * 60;
+
+// Which prefixes the cleanup wizard offers to remove. A prefix has to meet
+// every criterion that's switched on.
+#[derive(Clone, Copy)]
+pub struct CleanupCriteria {
+    pub uninstalled: bool,
+    pub not_played_months: Option<u64>,
+    pub min_size: Option<u64>, // Bytes
+}
+
+// A prefix the criteria picked, everything the preview and the removal need
+pub struct CleanupCandidate {
+    pub game_id: String,
+    pub prefix_path: PathBuf,
+    pub installed: bool,
+    pub last_played: Option<u64>, // Unix timestamp (seconds)
+    pub save_paths: Vec<PathBuf>, // Backed up first when asked to
+    pub size: u64,                // Filled in by `measure`
+}
+
+pub enum CleanupOutcome {
+    Removed { size: u64, snapshot: Option<Snapshot> },
+    Failed(String),
+}
+
+// Candidates from a scan that match the criteria, except for the size which
+// has to be measured first (see `measure`). `save_paths` adds save locations
+// known from the manifest to the ones the scan found.
+pub fn find_candidates(
+    config: &Config,
+    prefixes: &[PrefixData],
+    criteria: &CleanupCriteria,
+    save_paths: impl Fn(&str) -> Vec<PathBuf>,
+) -> Vec<CleanupCandidate> {
+    let installed = steam::installed_app_ids(config);
+    let now = now_secs();
+    prefixes
+        .iter()
+        .filter(|prefix| !(criteria.uninstalled && is_installed(&installed, &prefix.game_id)))
+        .filter(|prefix| {
+            criteria.not_played_months.is_none_or(|months| {
+                prefix.last_played.is_none_or(|last_played| now.saturating_sub(last_played) >= months * SECS_PER_MONTH)
+            })
+        })
+        .map(|prefix| {
+            let mut paths: Vec<PathBuf> = prefix
+                .save_locations
+                .iter()
+                .flat_map(|location| location.entries.iter().map(|entry| entry.path.clone()))
+                .collect();
+            for path in save_paths(&prefix.game_id) {
+                if path.exists() && !paths.iter().any(|known| path.starts_with(known)) {
+                    paths.push(path);
+                }
+            }
+            CleanupCandidate {
+                game_id: prefix.game_id.clone(),
+                prefix_path: prefix._path.clone(),
+                installed: is_installed(&installed, &prefix.game_id),
+                last_played: prefix.last_played,
+                save_paths: paths,
+                size: 0,
+            }
+        })
+        .collect()
+}
+
+// Shortcuts have no app manifest to tell, they never count as uninstalled
+fn is_installed(installed: &HashSet<String>, game_id: &str) -> bool {
+    steam::is_shortcut_app_id(game_id) || installed.contains(game_id)
+}
+
+// Measure every candidate and drop the ones below the size criterion, biggest
+// first. Walks whole prefixes, so it's meant for a worker thread.
+pub fn measure(candidates: Vec<CleanupCandidate>, min_size: Option<u64>) -> Vec<CleanupCandidate> {
+    let mut measured: Vec<CleanupCandidate> = candidates
+        .into_iter()
+        .map(|mut candidate| {
+            candidate.size = fsutil::disk_usage(&candidate.prefix_path);
+            candidate
+        })
+        .filter(|candidate| min_size.is_none_or(|min_size| candidate.size >= min_size))
+        .collect();
+    measured.sort_by_key(|candidate| std::cmp::Reverse(candidate.size));
+    measured
+}
+
+// Remove one prefix, after putting its saves in a safety snapshot when
+// `back_up_saves` is set. Nothing is removed if the snapshot fails.
+pub fn remove(config: &Config, candidate: &CleanupCandidate, back_up_saves: bool) -> CleanupOutcome {
+    let snapshot = if back_up_saves && !candidate.save_paths.is_empty() {
+        match Snapshot::create(config, &candidate.game_id, "Cleanup", &candidate.save_paths) {
+            Ok(snapshot) => Some(snapshot),
+            Err(e) => return CleanupOutcome::Failed(format!("{:#}", e.context("Could not back up the saves, not removed"))),
+        }
+    } else {
+        None
+    };
+    match fs::remove_dir_all(&candidate.prefix_path) {
+        Ok(()) => {
+            println!("Cleanup removed {} ({} bytes)", candidate.prefix_path.display(), candidate.size);
+            activity::record(
+                config,
+                ActivityEntry::new(ActivityKind::Delete, "Removed prefix during cleanup")
+                    .game(config, &candidate.game_id)
+                    .paths([candidate.prefix_path.clone()])
+                    .bytes(candidate.size),
+            );
+            CleanupOutcome::Removed { size: candidate.size, snapshot }
+        }
+        Err(e) => CleanupOutcome::Failed(e.to_string()),
+    }
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
@@ -0,0 +1,107 @@
+use crate::activity::{self, ActivityEntry, ActivityKind};
+use crate::config::Config;
+use crate::fsutil;
+use crate::snapshot::Snapshot;
+use crate::steam;
+use crate::vdf;
+use anyhow::{bail, Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Games using the Steam Cloud API keep their synced files outside the prefix:
+//
+//   userdata/<account id>/<app id>/remotecache.vdf   <- what Steam last synced
+//   userdata/<account id>/<app id>/remote/<file>     <- the synced copies
+//
+// Files synced with Auto-Cloud (root other than 0) only live in the prefix,
+// so there's no second copy of them to offer.
+const REMOTE_CACHE_FILE: &str = "remotecache.vdf";
+const REMOTE_DIR: &str = "remote";
+const DEFAULT_ROOT: &str = "0";
+
+// One Steam Cloud copy of a save file
+pub struct CloudFile {
+    pub account_id: String,
+    pub name: String, // Path relative to the remote folder, as the game names it
+    pub path: PathBuf,
+    pub size: u64,
+    pub synced: Option<u64>, // Unix timestamp (seconds) of the cloud version
+}
+
+// Cloud copies of `app_id` for the Steam accounts picked in Settings
+pub fn list_cloud_files(config: &Config, app_id: &str) -> Vec<CloudFile> {
+    let mut files = Vec::new();
+    for account_id in steam::active_account_ids(config) {
+        let app_dir = config.steam_userdata_path().join(&account_id).join(app_id);
+        match read_remote_cache(&app_dir, app_id) {
+            Ok(mut account_files) => {
+                for file in &mut account_files {
+                    file.account_id = account_id.clone();
+                }
+                files.append(&mut account_files);
+            }
+            Err(e) if app_dir.join(REMOTE_CACHE_FILE).exists() => {
+                eprintln!("Skipping Steam Cloud files of account {}: {:#}", account_id, e)
+            }
+            Err(_) => {}
+        }
+    }
+    files.sort_by(|a, b| a.name.cmp(&b.name).then(a.account_id.cmp(&b.account_id)));
+    files
+}
+
+fn read_remote_cache(app_dir: &Path, app_id: &str) -> Result<Vec<CloudFile>> {
+    let path = app_dir.join(REMOTE_CACHE_FILE);
+    let content = fs::read_to_string(&path).context(format!("Failed to read {}", path.display()))?;
+    let root = vdf::parse(&content).context(format!("Failed to parse {}", path.display()))?;
+    let Some(entries) = root.table(app_id) else {
+        return Ok(Vec::new());
+    };
+
+    let mut files = Vec::new();
+    for (name, value) in entries.iter() {
+        // Tables are files, plain values are things like ChangeNumber
+        let vdf::VdfValue::Table(entry) = value else {
+            continue;
+        };
+        if entry.str("root").unwrap_or(DEFAULT_ROOT) != DEFAULT_ROOT || !fsutil::is_plain_relative(Path::new(name)) {
+            continue;
+        }
+        let file_path = app_dir.join(REMOTE_DIR).join(name);
+        if !file_path.is_file() {
+            continue;
+        }
+        files.push(CloudFile {
+            account_id: String::new(),
+            name: name.to_string(),
+            size: entry
+                .str("size")
+                .and_then(|size| size.parse().ok())
+                .unwrap_or_else(|| fsutil::disk_usage(&file_path)),
+            synced: entry.str("remotetime").or(entry.str("time")).and_then(|time| time.parse().ok()),
+            path: file_path,
+        });
+    }
+    Ok(files)
+}
+
+// Copy a cloud copy into `target_dir` (a save location in the prefix), keeping
+// its relative path. Whatever it replaces gets a safety snapshot first.
+pub fn restore_cloud_file(config: &Config, app_id: &str, file: &CloudFile, target_dir: &Path) -> Result<PathBuf> {
+    if !fsutil::is_plain_relative(Path::new(&file.name)) {
+        bail!("Invalid cloud file name {}", file.name);
+    }
+    let target = target_dir.join(&file.name);
+    Snapshot::create(config, app_id, "Before restoring a Steam Cloud copy", std::slice::from_ref(&target))
+        .context("Failed to snapshot the current file, not restoring")?;
+    fsutil::copy_recursive(&file.path, &target)?;
+    println!("Restored cloud copy {} to {}", file.path.display(), target.display());
+    activity::record(
+        config,
+        ActivityEntry::new(ActivityKind::Restore, format!("Restored Steam Cloud copy {}", file.name))
+            .game(config, app_id)
+            .paths([target.clone()])
+            .bytes(fsutil::disk_usage(&target)),
+    );
+    Ok(target)
+}
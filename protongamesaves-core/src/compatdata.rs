@@ -0,0 +1,857 @@
+use crate::config::{Config, DRIVE_C_PREFIX};
+use crate::fsutil;
+use crate::i18n::gettext_f;
+use crate::launchers;
+use crate::shader_cache::{self, ShaderCache};
+use crate::steam::{self, CompatTool, CompatToolMapping};
+use anyhow::{Context, Result, anyhow};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::fs;
+use std::process::Command;
+
+// Represents a save location for a game
+#[derive(Serialize, Deserialize)]
+pub struct SaveLocation {
+    pub path: PathBuf,
+    pub relative_path: String,
+    pub entries: Vec<SaveEntry>,
+}
+
+// Represents a single save folder entry
+#[derive(Serialize, Deserialize)]
+pub struct SaveEntry {
+    pub name: String,
+    pub path: PathBuf,
+}
+
+// Diagnostics for a prefix, shown in the "Prefix Info" dialog
+pub struct PrefixHealth {
+    pub proton_version: Option<String>,    // First line of `config_info`
+    pub prefix_version: Option<String>,    // Contents of the `version` file
+    pub arch: Option<String>,              // From the `#arch=` line in system.reg
+    pub broken_symlinks: Vec<PathBuf>,
+    pub missing_shell_folders: Vec<(String, PathBuf)>, // user.reg shell folder -> missing path
+    pub launchers: Vec<&'static str>, // Game launchers installed in the prefix, see `launchers`
+    pub virtual_desktop: Option<String>, // Size of the Wine virtual desktop, e.g. "1920x1080", if it's on
+    pub shader_caches: Vec<ShaderCache>, // Kept apart from saves, see `shader_cache`
+}
+
+// Numeric order for App IDs, so 9 comes before 10
+pub fn compare_app_ids(a: &str, b: &str) -> std::cmp::Ordering {
+    match (a.parse::<u64>(), b.parse::<u64>()) {
+        (Ok(a), Ok(b)) => a.cmp(&b),
+        _ => a.cmp(b),
+    }
+}
+
+// Order Proton builds by their version numbers, so "Proton 7.0-6" comes before
+// "Proton 10.0-1". Builds without numbers (Experimental) go last.
+pub fn compare_proton_builds(a: &str, b: &str) -> std::cmp::Ordering {
+    let numbers = |build: &str| -> Vec<u64> {
+        build
+            .split(|c: char| !c.is_ascii_digit())
+            .filter_map(|part| part.parse().ok())
+            .collect()
+    };
+    let (a_numbers, b_numbers) = (numbers(a), numbers(b));
+    a_numbers
+        .is_empty()
+        .cmp(&b_numbers.is_empty())
+        .then_with(|| a_numbers.cmp(&b_numbers))
+        .then_with(|| a.cmp(b))
+}
+
+// First non-empty line of a small text file like `config_info` or `version`
+fn read_first_line(path: &Path) -> Option<String> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| content.lines().next().map(|l| l.trim().to_string()))
+        .filter(|line| !line.is_empty())
+}
+
+// A library whose compatdata couldn't be scanned, e.g. on an unplugged drive
+#[derive(Serialize, Deserialize)]
+pub struct LibraryError {
+    pub compatdata_path: PathBuf,
+    pub message: String,
+}
+
+// Prefixes found in all libraries, plus the libraries that failed
+#[derive(Default, Serialize, Deserialize)]
+pub struct ScanResult {
+    pub prefixes: Vec<PrefixData>,
+    pub failed: Vec<LibraryError>,
+}
+
+// Bump when PrefixData changes, so older scan caches are ignored instead of
+// failing to load
+const SCAN_CACHE_VERSION: u32 = 1;
+
+impl ScanResult {
+    // The last scan, shown on startup while a fresh one runs. None before the
+    // first scan or if the cache is from another version.
+    pub fn load_cached(path: &Path) -> Option<Self> {
+        let content = fs::read_to_string(path).ok()?;
+        match serde_json::from_str::<(u32, ScanResult)>(&content) {
+            Ok((SCAN_CACHE_VERSION, result)) => Some(result),
+            Ok(_) => None,
+            Err(e) => {
+                eprintln!("Ignoring unreadable scan cache {}: {}", path.display(), e);
+                None
+            }
+        }
+    }
+
+    pub fn save_cached(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context(format!("Failed to create {}", parent.display()))?;
+        }
+        let content = serde_json::to_string(&(SCAN_CACHE_VERSION, self))?;
+        fs::write(path, content).context(format!("Failed to write scan cache to {}", path.display()))
+    }
+}
+
+// Tools that can be launched against a prefix from its row
+#[derive(Clone, Copy)]
+pub enum PrefixTool {
+    Winecfg,
+    Regedit,
+    Protontricks,
+}
+
+// Represents a Proton prefix
+#[derive(Serialize, Deserialize)]
+pub struct PrefixData {
+    pub game_id: String,
+    pub _path: PathBuf,
+    pub _drive_c_path: PathBuf,
+    pub user_path: PathBuf,
+    pub save_locations: Vec<SaveLocation>,
+    pub compat_tool: Option<CompatTool>, // From Steam's CompatToolMapping, filled in by the scan
+    pub last_played: Option<u64>, // Unix timestamp (seconds) from Steam's localconfig.vdf, filled in by the scan
+    pub proton_build: Option<String>, // Proton build that last set up the prefix, see `read_proton_build`
+}
+
+impl PrefixData {
+    // Create a new PrefixData for a game ID, in whichever library holds its prefix
+    pub fn new(config: &Config, game_id: &str) -> Self {
+        Self::at(config.prefix_path(game_id), game_id)
+    }
+
+    // Create a PrefixData for the prefix folder at `prefix_path`
+    pub fn at(prefix_path: PathBuf, game_id: &str) -> Self {
+        let drive_c_path = prefix_path.join("pfx/drive_c");
+        let user_path = drive_c_path.join("users/steamuser");
+        
+        // Initialize with empty save locations - they'll be populated when needed
+        let save_locations = Vec::new();
+        
+        Self {
+            game_id: game_id.to_string(),
+            _path: prefix_path,
+            _drive_c_path: drive_c_path,
+            user_path,
+            save_locations,
+            compat_tool: None,
+            last_played: None,
+            proton_build: None,
+        }
+    }
+    
+    // Scan for save locations (the save paths and ignored folders come from the config).
+    // Each save path is only listed one level deep, so adding big roots like
+    // Documents or ProgramData doesn't slow the scan down much.
+    pub fn scan_save_locations(&mut self, config: &Config) -> Result<()> {
+        self.scan_save_locations_in(config.save_paths(), config.ignore_dirs())
+    }
+
+    // `scan_save_locations` with the config's lists already taken out, for
+    // scanning on worker threads
+    fn scan_save_locations_in(&mut self, save_paths: &[String], ignore_dirs: &[String]) -> Result<()> {
+        self.save_locations.clear();
+        
+        for rel_path in save_paths {
+            let full_path = match rel_path.strip_prefix(DRIVE_C_PREFIX) {
+                Some(drive_c_rel) => self._drive_c_path.join(drive_c_rel),
+                None => self.user_path.join(rel_path),
+            };
+            
+            if full_path.exists() && full_path.is_dir() {
+                let mut entries = Vec::new();
+                
+                // Scan for game-specific folders
+                if let Ok(dir_entries) = fs::read_dir(&full_path) {
+                    for entry_result in dir_entries {
+                        if let Ok(entry) = entry_result {
+                            let entry_path = entry.path();
+                            let file_name = entry.file_name();
+                            let name = file_name.to_string_lossy().to_string();
+                            
+                            // Nested save paths (Documents/My Games inside Documents) get their own location
+                            let is_other_save_path = save_paths.contains(&format!("{}/{}", rel_path, name));
+                            
+                            if entry_path.is_dir() && !ignore_dirs.contains(&name) && !is_other_save_path {
+                                entries.push(SaveEntry {
+                                    name,
+                                    path: entry_path,
+                                });
+                            }
+                        }
+                    }
+                }
+                
+                // Sort entries by name
+                entries.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+                
+                self.save_locations.push(SaveLocation {
+                    path: full_path,
+                    relative_path: rel_path.to_string(),
+                    entries,
+                });
+            }
+        }
+        
+        Ok(())
+    }
+    
+    // Gather version info and look for common signs of a broken prefix
+    pub fn check_health(&self) -> PrefixHealth {
+        let pfx_path = self._path.join("pfx");
+        let arch = fs::read_to_string(pfx_path.join("system.reg"))
+            .ok()
+            .and_then(|content| {
+                content
+                    .lines()
+                    .take(10) // The header is at the very top of the file
+                    .find_map(|line| line.strip_prefix("#arch=").map(|a| a.trim().to_string()))
+            });
+
+        // Proton symlinks its DLLs into system32/syswow64, those dangle once the
+        // Proton build that created the prefix is removed
+        let mut broken_symlinks = Vec::new();
+        for dir in ["windows/system32", "windows/syswow64"] {
+            collect_broken_symlinks(&self._drive_c_path.join(dir), 0, &mut broken_symlinks);
+        }
+        collect_broken_symlinks(&self.user_path, HEALTH_SCAN_MAX_DEPTH, &mut broken_symlinks);
+        collect_broken_symlinks(&pfx_path.join("dosdevices"), 0, &mut broken_symlinks);
+
+        PrefixHealth {
+            proton_version: read_first_line(&self._path.join("config_info")),
+            prefix_version: read_first_line(&self._path.join("version")),
+            arch,
+            broken_symlinks,
+            missing_shell_folders: self.missing_shell_folders(&pfx_path),
+            launchers: launchers::installed(&self._drive_c_path),
+            virtual_desktop: read_virtual_desktop(&pfx_path),
+            shader_caches: shader_cache::find(&self._path),
+        }
+    }
+
+    // Shell folders in user.reg (AppData, Documents, ...) pointing at paths that don't exist
+    fn missing_shell_folders(&self, pfx_path: &Path) -> Vec<(String, PathBuf)> {
+        let Ok(content) = fs::read_to_string(pfx_path.join("user.reg")) else {
+            return Vec::new();
+        };
+
+        let mut missing = Vec::new();
+        let mut in_section = false;
+        for line in content.lines() {
+            if line.starts_with('[') {
+                in_section = line.starts_with(SHELL_FOLDERS_KEY);
+                continue;
+            }
+            if !in_section {
+                continue;
+            }
+            // Values look like: "AppData"="C:\\users\\steamuser\\AppData\\Roaming"
+            let Some((name, value)) = line.split_once("\"=\"") else {
+                continue;
+            };
+            let name = name.trim_start_matches('"').to_string();
+            let value = value.trim_end_matches('"').replace("\\\\", "\\");
+            let Some(windows_path) = value.strip_prefix("C:\\").or_else(|| value.strip_prefix("c:\\")) else {
+                continue;
+            };
+            let path = self._drive_c_path.join(windows_path.replace('\\', "/"));
+            if !path.exists() {
+                missing.push((name, path));
+            }
+        }
+        missing
+    }
+
+    // Name of the Proton build that created or last upgraded the prefix. Proton
+    // writes its own version file into `config_info`, e.g. "1687194177 proton-8.0-3"
+    // (build timestamp first). Prefixes without one fall back to the `version`
+    // file, which only has the prefix format, e.g. "8.0-104".
+    pub fn read_proton_build(&self) -> Option<String> {
+        if let Some(line) = read_first_line(&self._path.join("config_info")) {
+            return Some(match line.split_once(' ') {
+                Some((timestamp, name)) if timestamp.chars().all(|c| c.is_ascii_digit()) => name.trim().to_string(),
+                _ => line,
+            });
+        }
+        read_first_line(&self._path.join("version"))
+    }
+
+    // Install folder of the Proton build that last ran the prefix. Line two of
+    // `config_info` is a path inside it, e.g. ".../common/Proton 8.0/files/share/fonts/"
+    pub fn proton_dir(&self) -> Option<PathBuf> {
+        let content = fs::read_to_string(self._path.join("config_info")).ok()?;
+        let line = content.lines().nth(1)?;
+        // Older builds keep wine in dist/ instead of files/
+        let index = line.find("/files/").or_else(|| line.find("/dist/"))?;
+        let dir = PathBuf::from(&line[..index]);
+        dir.is_dir().then_some(dir)
+    }
+
+    // Start winecfg, regedit or protontricks for this prefix
+    pub fn launch_tool(&self, tool: PrefixTool) -> Result<()> {
+        if let PrefixTool::Protontricks = tool {
+            // protontricks finds the prefix and Proton build from the App ID by itself
+            for (program, args) in [
+                ("protontricks", vec![self.game_id.as_str(), "--gui"]),
+                ("flatpak", vec!["run", "com.github.Matoking.protontricks", self.game_id.as_str(), "--gui"]),
+            ] {
+                if Command::new(program).args(&args).spawn().is_ok() {
+                    return Ok(());
+                }
+            }
+            return Err(anyhow!("protontricks is not installed"));
+        }
+
+        // Using any other wine could upgrade the prefix and break the game, so
+        // only ever use the Proton build the prefix belongs to
+        let proton_dir = self
+            .proton_dir()
+            .ok_or_else(|| anyhow!("Couldn't find the Proton build this prefix was created with. Run the game once to repair config_info."))?;
+        let dist = ["files", "dist"]
+            .iter()
+            .map(|d| proton_dir.join(d))
+            .find(|d| d.join("bin/wine").exists())
+            .ok_or_else(|| anyhow!("No wine binary found in {}", proton_dir.display()))?;
+
+        let program = match tool {
+            PrefixTool::Winecfg => "winecfg",
+            PrefixTool::Regedit => "regedit",
+            PrefixTool::Protontricks => unreachable!(),
+        };
+        let bin = dist.join("bin");
+        let path = std::env::var("PATH").unwrap_or_default();
+        Command::new(bin.join("wine"))
+            .arg(program)
+            .env("WINEPREFIX", self._path.join("pfx"))
+            .env("WINELOADER", bin.join("wine"))
+            .env("WINESERVER", bin.join("wineserver"))
+            .env("WINEDLLPATH", format!("{}:{}", dist.join("lib64/wine").display(), dist.join("lib/wine").display()))
+            .env("PATH", format!("{}:{}", bin.display(), path))
+            .spawn()?;
+        println!("Launched {} for prefix {} with {}", program, self.game_id, proton_dir.display());
+        Ok(())
+    }
+
+    // Delete the entire prefix directory
+    pub fn _delete(&self) -> Result<()> {
+        let prefix_path = &self._path; // Use the prefixed field
+        println!("Attempting to delete prefix directory: {}", prefix_path.display());
+        
+        if !prefix_path.exists() {
+            return Err(anyhow!("Prefix path does not exist"));
+        }
+        
+        fs::remove_dir_all(prefix_path)?;
+        Ok(())
+    }
+}
+
+const SHELL_FOLDERS_KEY: &str = "[Software\\\\Microsoft\\\\Windows\\\\CurrentVersion\\\\Explorer\\\\Shell Folders]";
+const HEALTH_SCAN_MAX_DEPTH: usize = 4;
+
+// The virtual desktop winecfg turns on, from user.reg. "Desktop" names the
+// desktop to use, its size is under Explorer\Desktops:
+//
+//   [Software\\Wine\\Explorer] ... "Desktop"="Default"
+//   [Software\\Wine\\Explorer\\Desktops] ... "Default"="1920x1080"
+fn read_virtual_desktop(pfx_path: &Path) -> Option<String> {
+    let content = fs::read_to_string(pfx_path.join("user.reg")).ok()?;
+    let mut section = "";
+    let mut desktop = None;
+    let mut sizes = Vec::new();
+    for line in content.lines() {
+        if line.starts_with('[') {
+            section = line.split(']').next().unwrap_or_default();
+            continue;
+        }
+        let Some((name, value)) = line.split_once("\"=\"") else {
+            continue;
+        };
+        let (name, value) = (name.trim_start_matches('"'), value.trim_end_matches('"'));
+        match section {
+            "[Software\\\\Wine\\\\Explorer" if name == "Desktop" => desktop = Some(value.to_string()),
+            "[Software\\\\Wine\\\\Explorer\\\\Desktops" => sizes.push((name.to_string(), value.to_string())),
+            _ => {}
+        }
+    }
+    let desktop = desktop?;
+    sizes.into_iter().find(|(name, _)| *name == desktop).map(|(_, size)| size)
+}
+
+// Recursively collect symlinks whose target is missing, up to `max_depth` levels down
+fn collect_broken_symlinks(dir: &Path, max_depth: usize, found: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_symlink() {
+            // exists() follows the link, so false means it's dangling
+            if !path.exists() {
+                found.push(path);
+            }
+        } else if file_type.is_dir() && max_depth > 0 {
+            collect_broken_symlinks(&path, max_depth - 1, found);
+        }
+    }
+}
+
+// Folders under steamuser every prefix starts with
+const STEAMUSER_DIRS: &[&str] = &["AppData/Local", "AppData/LocalLow", "AppData/Roaming", "Documents", "Saved Games"];
+
+// Lay out the folders of a prefix down to steamuser, returns the steamuser directory
+pub fn create_prefix_skeleton(prefix: &Path) -> Result<PathBuf> {
+    let user_path = prefix.join("pfx/drive_c/users/steamuser");
+    for dir in STEAMUSER_DIRS {
+        fs::create_dir_all(user_path.join(dir))?;
+    }
+    fs::create_dir_all(prefix.join("pfx/drive_c/ProgramData"))?;
+    Ok(user_path)
+}
+
+// Steam App IDs are plain numbers, which also keeps one from pointing a path
+// outside compatdata
+pub fn is_app_id(app_id: &str) -> bool {
+    !app_id.is_empty() && app_id.chars().all(|c| c.is_ascii_digit())
+}
+
+// An empty prefix for a game that hasn't run under Proton yet, so saves can be
+// put in place before its first launch. Without a `version` file Proton treats
+// it as new and sets the rest of it up, keeping the files already there.
+pub fn create_empty_prefix(compatdata_path: &Path, app_id: &str) -> Result<PathBuf> {
+    let app_id = app_id.trim();
+    if !is_app_id(app_id) {
+        return Err(anyhow!("App IDs are numbers, \"{}\" isn't one", app_id));
+    }
+    let prefix = compatdata_path.join(app_id);
+    if prefix.exists() {
+        return Err(anyhow!("{} already exists", prefix.display()));
+    }
+    let user_path = create_prefix_skeleton(&prefix)?;
+    println!("Created empty prefix {}", prefix.display());
+    Ok(user_path)
+}
+
+// What a repair leaves of a prefix besides the save locations
+const REPAIR_KEPT_DIR: &str = "pfx/drive_c/users";
+
+// Delete Wine's part of the prefix at `prefix_path` so Proton sets it up again
+// on the next launch, the fix for a broken prefix that keeps the saves. Without
+// the `version` file Proton treats it as new, like `create_empty_prefix`.
+// Keeps drive_c/users and the `keep` paths inside the prefix (save folders in
+// ProgramData and the like), the registry goes with the rest, so snapshot it
+// together with the saves first. Returns the bytes removed.
+pub fn repair_prefix(prefix_path: &Path, keep: &[PathBuf]) -> Result<u64> {
+    if !prefix_path.join("pfx").is_dir() {
+        return Err(anyhow!("{} is not a Proton prefix", prefix_path.display()));
+    }
+    let mut kept = vec![prefix_path.join(REPAIR_KEPT_DIR)];
+    kept.extend(keep.iter().filter(|path| path.starts_with(prefix_path)).cloned());
+    let before = fsutil::disk_usage(prefix_path);
+    remove_all_but(prefix_path, &kept)?;
+    let removed = before.saturating_sub(fsutil::disk_usage(prefix_path));
+    println!("Repaired prefix {}, removed {} bytes", prefix_path.display(), removed);
+    Ok(removed)
+}
+
+// Delete what's inside `dir` except the `kept` paths and the folders leading to them
+fn remove_all_but(dir: &Path, kept: &[PathBuf]) -> Result<()> {
+    let entries = fs::read_dir(dir).map_err(|e| anyhow!("Failed to read {}: {}", dir.display(), e))?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if kept.contains(&path) {
+            continue;
+        }
+        // Not following symlinks, dosdevices links to / among others
+        let is_dir = entry.file_type().is_ok_and(|file_type| file_type.is_dir());
+        let result = if is_dir && kept.iter().any(|kept| kept.starts_with(&path)) {
+            remove_all_but(&path, kept)
+        } else if is_dir {
+            fs::remove_dir_all(&path).map_err(Into::into)
+        } else {
+            fs::remove_file(&path).map_err(Into::into)
+        };
+        result.context(format!("Failed to remove {}", path.display()))?;
+    }
+    Ok(())
+}
+
+// Copy the whole prefix `prefix_path` to `new_app_id` in the same library, e.g.
+// to try another Proton version on a copy. Files go through std's fs::copy,
+// which uses copy_file_range, so btrfs and XFS share the data instead of
+// writing it twice. Copies into a temporary name first so a failed copy never
+// looks like a real prefix. Blocks for a while on big prefixes, meant for a
+// worker thread.
+pub fn duplicate_prefix(prefix_path: &Path, new_app_id: &str) -> Result<PathBuf> {
+    let new_app_id = new_app_id.trim();
+    if !is_app_id(new_app_id) {
+        return Err(anyhow!("App IDs are numbers, \"{}\" isn't one", new_app_id));
+    }
+    let Some(compatdata_path) = prefix_path.parent() else {
+        return Err(anyhow!("{} is not inside a compatdata folder", prefix_path.display()));
+    };
+    let target = compatdata_path.join(new_app_id);
+    if target.exists() {
+        return Err(anyhow!("{} already exists", target.display()));
+    }
+    let partial = compatdata_path.join(format!(".{}.partial", new_app_id));
+    let _ = fs::remove_dir_all(&partial);
+    if let Err(e) = fsutil::copy_recursive(prefix_path, &partial) {
+        let _ = fs::remove_dir_all(&partial);
+        return Err(e.context(format!("Failed to copy {}", prefix_path.display())));
+    }
+    fs::rename(&partial, &target).map_err(|e| anyhow!("Failed to move the copy to {}: {}", target.display(), e))?;
+    println!("Duplicated prefix {} to {}", prefix_path.display(), target.display());
+    Ok(target)
+}
+
+// Scan the prefixes of every library. Libraries that fail (e.g. an unplugged
+// drive) are listed in `failed` instead of stopping the scan. Only needs the
+// config, so it can run on a worker thread with its own.
+pub fn scan_libraries(config: &Config) -> ScanResult {
+    println!("Scanning compatdata...");
+    let mut result = ScanResult::default();
+    for (index, compatdata_path) in config.compatdata_paths().into_iter().enumerate() {
+        if !config.is_library_scanned(&compatdata_path) {
+            println!("Skipping library {}, switched off in the scan options", compatdata_path.display());
+            continue;
+        }
+        // The main library must have a compatdata folder, other libraries
+        // only get one once a Proton game is installed there
+        match scan_library(config, &compatdata_path, index == 0) {
+            Ok(mut prefixes) => result.prefixes.append(&mut prefixes),
+            Err(e) => {
+                eprintln!("Error scanning library {}: {}", compatdata_path.display(), e);
+                result.failed.push(LibraryError { compatdata_path, message: e.to_string() });
+            }
+        }
+    }
+    result.prefixes.sort_by(|a, b| compare_app_ids(&a.game_id, &b.game_id));
+    println!("Finished scanning compatdata.");
+    result
+}
+
+// Scan the prefixes of one library's compatdata folder
+pub fn scan_library(config: &Config, compatdata_path: &Path, required: bool) -> Result<Vec<PrefixData>> {
+    if !compatdata_path.exists() {
+        // steamapps/compatdata -> the library folder itself
+        let library_path = compatdata_path.parent().and_then(Path::parent);
+        if !required && library_path.is_some_and(Path::exists) {
+            return Ok(Vec::new());
+        }
+        return Err(anyhow!(gettext_f("Compatdata path does not exist: {}", &[&compatdata_path.display().to_string()])));
+    }
+
+    let game_ids = list_game_ids(compatdata_path)?;
+    if game_ids.is_empty() {
+        println!("No Proton prefixes found in {}", compatdata_path.display());
+        return Ok(Vec::new());
+    }
+
+    println!("Found {} potential prefixes in {}. Scanning for saves...", game_ids.len(), compatdata_path.display());
+    Ok(scan_prefixes(config, compatdata_path, game_ids))
+}
+
+// Read the prefixes `game_ids` of the library at `compatdata_path`: their
+// compat tool, last play time, Proton build and save locations. Several
+// prefixes are read at once, up to the configured scan threads.
+pub fn scan_prefixes(config: &Config, compatdata_path: &Path, game_ids: Vec<String>) -> Vec<PrefixData> {
+    let compat_tools = CompatToolMapping::load_or_default(config);
+    let last_played = steam::last_played(config);
+    let (save_paths, ignore_dirs) = (config.save_paths(), config.ignore_dirs());
+    fsutil::parallel_map(game_ids, config.io_limits().scan_threads, |game_id| {
+        let mut prefix_data = PrefixData::at(compatdata_path.join(&game_id), &game_id);
+        prefix_data.compat_tool = compat_tools.tool_for(&game_id);
+        prefix_data.last_played = last_played.get(&game_id).copied();
+        prefix_data.proton_build = prefix_data.read_proton_build();
+        // A prefix whose saves can't be listed is still worth showing
+        if let Err(e) = prefix_data.scan_save_locations_in(save_paths, ignore_dirs) {
+            eprintln!("Error scanning saves for game ID {}: {}", game_id, e);
+        }
+        prefix_data
+    })
+}
+
+// Get all game IDs from the compatdata directory
+pub fn list_game_ids(compatdata_path: &Path) -> Result<Vec<String>> {
+    if !compatdata_path.exists() {
+        return Err(anyhow!("Compatdata path does not exist"));
+    }
+    
+    let mut game_ids = Vec::new();
+    
+    if let Ok(entries) = fs::read_dir(compatdata_path) {
+        for entry_result in entries {
+            if let Ok(entry) = entry_result {
+                let path = entry.path();
+                
+                if path.is_dir() {
+                    if let Some(game_id) = path.file_name() {
+                        let game_id = game_id.to_string_lossy().to_string();
+                        
+                        // Check if this is a valid prefix (has pfx directory), hidden
+                        // ones are copies still in progress (see `duplicate_prefix`)
+                        if path.join("pfx").exists() && !game_id.starts_with('.') {
+                            game_ids.push(game_id);
+                        }
+                    }
+                }
+            }
+        }
+    }
+    
+    // Sort game IDs numerically if possible, ensuring total order
+    game_ids.sort_by(|a, b| {
+        // Try to convert to integers for numerical sorting
+        match (a.parse::<u64>(), b.parse::<u64>()) {
+            (Ok(a_num), Ok(b_num)) => a_num.cmp(&b_num),
+            (Ok(_), Err(_)) => std::cmp::Ordering::Less,    // Numeric comes before non-numeric
+            (Err(_), Ok(_)) => std::cmp::Ordering::Greater, // Non-numeric comes after numeric
+            (Err(_), Err(_)) => a.cmp(b),                   // Both non-numeric, use string comparison
+        }
+    });
+    
+    Ok(game_ids)
+}
+
+// Open a path in the default file manager
+pub fn open_in_file_manager(path: &Path) -> Result<()> {
+    if !path.exists() {
+        return Err(anyhow!("Path does not exist"));
+    }
+    
+    Command::new("xdg-open")
+        .arg(path)
+        .spawn()?;
+    
+    Ok(())
+}
+
+// Terminals to try, in order, when $TERMINAL isn't set. They all start in the
+// working directory they're spawned with.
+const TERMINALS: &[&str] = &[
+    "xdg-terminal-exec",
+    "kgx",
+    "gnome-terminal",
+    "konsole",
+    "xfce4-terminal",
+    "alacritty",
+    "kitty",
+    "foot",
+    "xterm",
+];
+
+// Open a terminal emulator with `path` as its working directory
+pub fn open_in_terminal(path: &Path) -> Result<()> {
+    if !path.is_dir() {
+        return Err(anyhow!("Path is not a folder"));
+    }
+
+    let preferred = std::env::var("TERMINAL").ok().filter(|t| !t.is_empty());
+    for terminal in preferred.iter().map(String::as_str).chain(TERMINALS.iter().copied()) {
+        if Command::new(terminal).current_dir(path).spawn().is_ok() {
+            return Ok(());
+        }
+    }
+
+    Err(anyhow!("No terminal emulator found, set $TERMINAL to choose one"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{write_file, Fixture};
+
+    #[test]
+    fn repair_keeps_users_and_saves() {
+        let fixture = Fixture::new();
+        let prefix = fixture.add_prefix("1000");
+        let save = fixture.add_file("1000", "AppData/Roaming/LonelyHero/Saves/slot1.sav", "save");
+        let drive_c = prefix.join("pfx/drive_c");
+        let program_data_save = drive_c.join("ProgramData/Game");
+        write_file(&program_data_save.join("save.dat"), "save");
+        write_file(&drive_c.join("ProgramData/Other/cache.bin"), "cache");
+        write_file(&drive_c.join("windows/system32/broken.dll"), "dll");
+        write_file(&prefix.join("pfx/system.reg"), "registry");
+        write_file(&prefix.join("version"), "9.0-1");
+        std::os::unix::fs::symlink("/", prefix.join("pfx/dosdevices")).unwrap();
+
+        assert!(repair_prefix(&prefix, std::slice::from_ref(&program_data_save)).unwrap() > 0);
+        assert!(save.exists());
+        assert!(program_data_save.join("save.dat").exists());
+        assert!(!drive_c.join("ProgramData/Other").exists());
+        assert!(!drive_c.join("windows").exists());
+        assert!(!prefix.join("pfx/system.reg").exists());
+        assert!(!prefix.join("pfx/dosdevices").exists());
+        assert!(!prefix.join("version").exists());
+        assert!(repair_prefix(&fixture.root.join("missing"), &[]).is_err());
+    }
+
+    #[test]
+    fn lists_prefixes_in_numeric_order() {
+        let fixture = Fixture::new();
+        for app_id in ["20", "100", "9"] {
+            fixture.add_prefix(app_id);
+        }
+        // Not prefixes: no pfx folder, a copy in progress, a stray file
+        fs::create_dir_all(fixture.compatdata_path().join("55")).unwrap();
+        fs::create_dir_all(fixture.compatdata_path().join(".77.partial/pfx")).unwrap();
+        write_file(&fixture.compatdata_path().join("notes.txt"), "");
+
+        let game_ids = list_game_ids(&fixture.compatdata_path()).unwrap();
+        assert_eq!(game_ids, ["9", "20", "100"]);
+    }
+
+    #[test]
+    fn missing_compatdata_is_an_error() {
+        let fixture = Fixture::new();
+        assert!(list_game_ids(&fixture.compatdata_path()).is_err());
+    }
+
+    #[test]
+    fn scans_save_locations_one_level_deep() {
+        let fixture = Fixture::new();
+        fixture.add_prefix("1000");
+        fixture.add_file("1000", "AppData/Roaming/LonelyHero/Saves/slot1.sav", "save");
+        fixture.add_file("1000", "AppData/Roaming/Microsoft/ignored.txt", "");
+        fixture.add_file("1000", "Documents/My Games/Folder Only/save.dat", "save");
+
+        let config = fixture.config();
+        let prefixes = scan_prefixes(&config, &fixture.compatdata_path(), vec!["1000".to_string()]);
+        let entries = |relative_path: &str| -> Vec<String> {
+            prefixes[0]
+                .save_locations
+                .iter()
+                .find(|location| location.relative_path == relative_path)
+                .map(|location| location.entries.iter().map(|entry| entry.name.clone()).collect())
+                .unwrap_or_default()
+        };
+        assert_eq!(entries("AppData/Roaming"), ["LonelyHero"]);
+        // Nested save paths get their own location instead of showing up in the parent
+        assert!(entries("Documents").is_empty());
+        assert_eq!(entries("Documents/My Games"), ["Folder Only"]);
+    }
+
+    #[test]
+    fn prefix_path_finds_the_library_holding_it() {
+        let fixture = Fixture::new();
+        let config = fixture.config();
+        // Not in any library: where a new prefix would go
+        assert_eq!(config.prefix_path("1000"), fixture.compatdata_path().join("1000"));
+        let prefix = fixture.add_prefix("1000");
+        assert_eq!(PrefixData::new(&config, "1000")._path, prefix);
+        assert!(!config.is_on_removable_media(&prefix));
+
+        // A card from another Steam Deck isn't in libraryfolders.vdf
+        let card = fixture.root.join("app/media/mmcblk0p1");
+        let card_prefix = card.join("steamapps/compatdata/2000");
+        create_prefix_skeleton(&card_prefix).unwrap();
+        fs::create_dir_all(fixture.root.join("app/media/deck/Empty/steamapps")).unwrap();
+        let config = fixture.config();
+        assert_eq!(config.compatdata_paths().last(), Some(&card.join("steamapps/compatdata")));
+        assert_eq!(config.prefix_path("2000"), card_prefix);
+        assert!(config.is_on_removable_media(&config.prefix_path("2000")));
+    }
+
+    #[test]
+    fn reads_proton_build() {
+        let fixture = Fixture::new();
+        let prefix = fixture.add_prefix("1000");
+        let prefix_data = PrefixData::at(prefix.clone(), "1000");
+        assert_eq!(prefix_data.read_proton_build(), None);
+
+        write_file(&prefix.join("version"), "8.0-104\n");
+        assert_eq!(prefix_data.read_proton_build().as_deref(), Some("8.0-104"));
+        write_file(&prefix.join("config_info"), "1687194177 proton-8.0-3\n/x/Proton 8.0/files/share/fonts/\n");
+        assert_eq!(prefix_data.read_proton_build().as_deref(), Some("proton-8.0-3"));
+        write_file(&prefix.join("config_info"), "Proton 7.0-6\n");
+        assert_eq!(prefix_data.read_proton_build().as_deref(), Some("Proton 7.0-6"));
+    }
+
+    #[test]
+    fn orders_proton_builds_by_version() {
+        let mut builds = vec!["Proton Experimental", "Proton 10.0-1", "Proton 7.0-6", "Proton 8.0-5", "Proton 8.0-10"];
+        builds.sort_by(|a, b| compare_proton_builds(a, b));
+        assert_eq!(builds, ["Proton 7.0-6", "Proton 8.0-5", "Proton 8.0-10", "Proton 10.0-1", "Proton Experimental"]);
+    }
+
+    #[test]
+    fn empty_prefix_needs_a_numeric_unused_app_id() {
+        let fixture = Fixture::new();
+        fs::create_dir_all(fixture.compatdata_path()).unwrap();
+        assert!(create_empty_prefix(&fixture.compatdata_path(), "abc").is_err());
+        let user_path = create_empty_prefix(&fixture.compatdata_path(), " 1000 ").unwrap();
+        assert!(user_path.join("AppData/Roaming").is_dir());
+        assert!(create_empty_prefix(&fixture.compatdata_path(), "1000").is_err());
+    }
+
+    #[test]
+    fn duplicates_a_prefix() {
+        let fixture = Fixture::new();
+        let prefix = fixture.add_prefix("1000");
+        fixture.add_file("1000", "AppData/Roaming/LonelyHero/Saves/slot1.sav", "save");
+
+        let copy = duplicate_prefix(&prefix, "2000").unwrap();
+        assert_eq!(copy, fixture.compatdata_path().join("2000"));
+        let save = copy.join("pfx/drive_c/users/steamuser/AppData/Roaming/LonelyHero/Saves/slot1.sav");
+        assert_eq!(fs::read_to_string(save).unwrap(), "save");
+        assert!(duplicate_prefix(&prefix, "2000").is_err());
+        assert_eq!(list_game_ids(&fixture.compatdata_path()).unwrap(), ["1000", "2000"]);
+    }
+
+    #[test]
+    fn skips_libraries_switched_off() {
+        let fixture = Fixture::new();
+        fixture.add_prefix("1000");
+        let mut config = fixture.config();
+        config.set_library_scanned(&fixture.compatdata_path(), false).unwrap();
+        let result = scan_libraries(&config);
+        assert!(result.prefixes.is_empty());
+        assert!(result.failed.is_empty());
+
+        config.set_library_scanned(&fixture.compatdata_path(), true).unwrap();
+        assert_eq!(scan_libraries(&config).prefixes.len(), 1);
+    }
+
+    #[test]
+    fn cached_scan_round_trips() {
+        let fixture = Fixture::new();
+        fixture.add_prefix("1000");
+        fixture.add_file("1000", "AppData/Roaming/LonelyHero/Saves/slot1.sav", "save");
+        let config = fixture.config();
+        let path = config.scan_cache_path();
+        assert!(ScanResult::load_cached(&path).is_none());
+
+        scan_libraries(&config).save_cached(&path).unwrap();
+        let cached = ScanResult::load_cached(&path).unwrap();
+        assert_eq!(cached.prefixes.len(), 1);
+        assert_eq!(cached.prefixes[0].game_id, "1000");
+        assert!(cached.prefixes[0].save_locations.iter().any(|location| location.relative_path == "AppData/Roaming"));
+
+        // A cache from another version is ignored, not misread
+        fs::write(&path, "[0, {\"prefixes\": [], \"failed\": []}]").unwrap();
+        assert!(ScanResult::load_cached(&path).is_none());
+    }
+}
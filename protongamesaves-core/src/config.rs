@@ -0,0 +1,1504 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::fs;
+use std::rc::Rc;
+use anyhow::{Result, anyhow};
+use dirs;
+use serde::{Serialize, Deserialize};
+
+use crate::demo;
+use crate::ignore_patterns::{self, IgnorePatterns};
+use crate::save_editor;
+use crate::steam;
+
+const DEFAULT_MANIFEST_URL: &str = "https://raw.githubusercontent.com/mtkennerly/ludusavi-manifest/master/data/manifest.yaml";
+
+// Folders under steamuser that get scanned for per-game save folders, or
+// under the whole C: drive when they start with DRIVE_C_PREFIX
+pub const DEFAULT_SAVE_PATHS: &[&str] = &[
+    "AppData/Local",
+    "AppData/LocalLow",
+    "AppData/Roaming",
+    "Saved Games",
+    "Documents",
+    "Documents/My Games",
+    "drive_c/ProgramData",
+];
+pub const DRIVE_C_PREFIX: &str = "drive_c/";
+
+// Vendor/system folders inside the save paths that never belong to a game
+pub const DEFAULT_IGNORE_DIRS: &[&str] = &[
+    "Microsoft",
+    "Temp",
+    "Packages",
+    "ConnectedDevicesPlatform",
+    "Comms",
+    "Apps",
+    // Wine's shell folders inside Documents, can point into the real home directory
+    "My Music",
+    "My Pictures",
+    "My Videos",
+    "Package Cache", // Installer caches in ProgramData
+];
+
+// Use serde for easier loading/saving
+#[derive(Serialize, Deserialize, Clone)] 
+pub struct ConfigData {
+    steam_path: PathBuf,
+    manifest_url: String,
+    first_run: bool,
+    // Extra manifest URLs or local YAML files, merged after the main manifest
+    #[serde(default)]
+    manifest_sources: Vec<String>,
+    #[serde(default)]
+    scan_options: ScanOptions,
+    // Libraries (by compatdata folder) and manifest sources switched off in
+    // the scan options, left out until switched on again
+    #[serde(default)]
+    skipped_libraries: Vec<PathBuf>,
+    #[serde(default)]
+    skipped_manifest_sources: Vec<String>,
+    #[serde(default)]
+    privacy: PrivacySettings,
+    #[serde(default)]
+    retention: RetentionPolicy,
+    // Per-game retention, keyed by App ID; games without an entry use `retention`
+    #[serde(default)]
+    retention_overrides: HashMap<String, RetentionPolicy>,
+    #[serde(default = "default_save_paths")]
+    save_paths: Vec<String>,
+    #[serde(default = "default_ignore_dirs")]
+    ignore_dirs: Vec<String>,
+    // Junk inside save folders left out of sizes, snapshots and bundles, see `ignore_patterns`
+    #[serde(default = "default_ignore_patterns")]
+    ignore_patterns: Vec<String>,
+    // More patterns for single games, on top of the global ones, keyed by App ID
+    #[serde(default)]
+    game_ignore_patterns: HashMap<String, Vec<String>>,
+    // Names set by the user, keyed by App ID; win over the manifest's name
+    #[serde(default)]
+    display_names: HashMap<String, String>,
+    // Sorting, search text and open rows of each page's list, keyed by page
+    // ("games", "compatdata", "manifest")
+    #[serde(default)]
+    view_states: HashMap<String, ViewState>,
+    // Starred App IDs, shown in their own group at the top of the Games page
+    #[serde(default)]
+    favorites: Vec<String>,
+    // App IDs hidden from the Compatdata and Games pages, e.g. prefixes Steam
+    // made for Proton itself or for redistributables
+    #[serde(default)]
+    hidden_prefixes: Vec<String>,
+    // Manifest file of another tool (ludusavi) to use instead of our own cache
+    #[serde(default)]
+    shared_manifest_path: Option<PathBuf>,
+    // Manifest game picked by the user for prefixes that didn't match one, keyed by App ID
+    #[serde(default)]
+    game_assignments: HashMap<String, String>,
+    // Extra save paths in manifest syntax (e.g. "<winDocuments>/My Game"), keyed by App ID
+    #[serde(default)]
+    custom_save_paths: HashMap<String, Vec<String>>,
+    // Folder shared by a sync tool (e.g. Syncthing) that save locations get linked into
+    #[serde(default)]
+    sync_folder: Option<PathBuf>,
+    #[serde(default)]
+    snapshot_compression: SnapshotCompression,
+    // Where safety snapshots are kept when not in the data dir, e.g. moved to
+    // an SD card, see `snapshot::move_snapshots`
+    #[serde(default)]
+    snapshot_root: Option<PathBuf>,
+    #[serde(default)]
+    window_state: WindowState,
+    #[serde(default)]
+    save_quota: SaveQuota,
+    // Per-game limit in MB, keyed by App ID; 0 switches the warning off for that game
+    #[serde(default)]
+    save_quota_overrides: HashMap<String, u64>,
+    // Offered as destinations when exporting a save bundle
+    #[serde(default)]
+    backup_targets: Vec<BackupTargetSettings>,
+    #[serde(default)]
+    confirmations: ConfirmationLevel,
+    // Save editor command per file extension (lower case, no dot), see `save_editor`
+    #[serde(default)]
+    editor_commands: HashMap<String, String>,
+    // Whether save bundles also carry controller layouts and other Steam
+    // settings of each game, see `steam_settings`
+    #[serde(default)]
+    bundle_steam_settings: bool,
+    #[serde(default)]
+    prefix_backup: PrefixBackupOptions,
+    #[serde(default)]
+    io_limits: IoLimits,
+    // Which account's userdata (cloud copies, Steam settings) is looked at
+    #[serde(default)]
+    steam_account: SteamAccountChoice,
+}
+
+impl ConfigData {
+    // Settings for a fresh config, everything but the Steam path at its default
+    fn new(steam_path: PathBuf, first_run: bool) -> Self {
+        Self {
+            steam_path,
+            manifest_url: DEFAULT_MANIFEST_URL.to_string(),
+            first_run,
+            manifest_sources: Vec::new(),
+            scan_options: ScanOptions::default(),
+            skipped_libraries: Vec::new(),
+            skipped_manifest_sources: Vec::new(),
+            privacy: PrivacySettings::default(),
+            retention: RetentionPolicy::default(),
+            retention_overrides: HashMap::new(),
+            save_paths: default_save_paths(),
+            ignore_dirs: default_ignore_dirs(),
+            ignore_patterns: default_ignore_patterns(),
+            game_ignore_patterns: HashMap::new(),
+            display_names: HashMap::new(),
+            view_states: HashMap::new(),
+            favorites: Vec::new(),
+            hidden_prefixes: Vec::new(),
+            shared_manifest_path: None,
+            game_assignments: HashMap::new(),
+            custom_save_paths: HashMap::new(),
+            sync_folder: None,
+            snapshot_compression: SnapshotCompression::default(),
+            snapshot_root: None,
+            window_state: WindowState::default(),
+            save_quota: SaveQuota::default(),
+            save_quota_overrides: HashMap::new(),
+            backup_targets: Vec::new(),
+            confirmations: ConfirmationLevel::default(),
+            editor_commands: HashMap::new(),
+            bundle_steam_settings: false,
+            prefix_backup: PrefixBackupOptions::default(),
+            io_limits: IoLimits::default(),
+            steam_account: SteamAccountChoice::default(),
+        }
+    }
+}
+
+fn default_save_paths() -> Vec<String> {
+    DEFAULT_SAVE_PATHS.iter().map(|s| s.to_string()).collect()
+}
+
+fn default_ignore_dirs() -> Vec<String> {
+    DEFAULT_IGNORE_DIRS.iter().map(|s| s.to_string()).collect()
+}
+
+fn default_ignore_patterns() -> Vec<String> {
+    ignore_patterns::DEFAULT_IGNORE_PATTERNS.iter().map(|s| s.to_string()).collect()
+}
+
+// What a refresh does, chosen from the popover on the refresh button
+#[derive(Serialize, Deserialize, Clone, Copy)]
+#[serde(default)]
+pub struct ScanOptions {
+    pub match_manifest: bool,  // Identify games for the Games page
+    pub recompute_sizes: bool, // Otherwise reuse sizes from the previous scan
+    pub deep_scan: bool,       // Check every manifest path for prefixes the folder heuristics missed
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        Self {
+            match_manifest: true,
+            recompute_sizes: true,
+            deep_scan: false,
+        }
+    }
+}
+
+// How a page's list was last sorted and filtered, restored on the next start
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct ViewState {
+    pub sort_key: String, // Page specific, e.g. "name" or "size"; empty means the page's default
+    pub descending: bool,
+    pub filter: String, // Search entry text
+    pub favorites_only: bool, // Games page only
+    pub not_played_only: bool, // Compatdata page only
+    pub group_by_proton: bool, // Compatdata page only
+    pub group_by_library: bool, // Compatdata page only
+    pub show_hidden: bool, // Compatdata page only
+    pub expanded: Vec<String>, // App IDs of the rows that were open
+}
+
+// Size and page of the main window when it was last closed
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct WindowState {
+    pub width: i32,
+    pub height: i32,
+    pub maximized: bool,
+    pub page: String, // Name of the sidebar page, e.g. "games"; empty for the first one
+}
+
+impl Default for WindowState {
+    fn default() -> Self {
+        Self {
+            width: 900,
+            height: 700,
+            maximized: false,
+            page: String::new(),
+        }
+    }
+}
+
+// Switches for everything that talks to the network, enforced in `net`
+#[derive(Serialize, Deserialize, Clone, Copy)]
+#[serde(default)]
+pub struct PrivacySettings {
+    pub offline_mode: bool,     // Master switch, blocks every request
+    pub manifest_updates: bool, // Downloading the manifest and remote manifest sources
+    pub backup_uploads: bool,   // Sending bundles to SFTP and WebDAV destinations
+    pub lan_sync: bool,         // Sharing and syncing saves with machines on the local network
+}
+
+impl Default for PrivacySettings {
+    fn default() -> Self {
+        Self {
+            offline_mode: false,
+            // On by default, the Games page can't match anything without a manifest
+            manifest_updates: true,
+            // Opt-in, switched on in the privacy settings before first use
+            backup_uploads: false,
+            lan_sync: false,
+        }
+    }
+}
+
+// How many safety snapshots to keep for a game and for how long
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(default)]
+pub struct RetentionPolicy {
+    pub max_snapshots: u32, // 0 = no limit
+    pub max_age_days: u32,  // 0 = never expire
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self {
+            max_snapshots: 5,
+            max_age_days: 7,
+        }
+    }
+}
+
+// How many prefixes are scanned and how many save folders copied at once.
+// 1 keeps HDDs and SD cards from seeking back and forth, fast SSDs can take more.
+pub const MAX_IO_THREADS: usize = 32;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(default)]
+pub struct IoLimits {
+    pub scan_threads: usize,
+    pub copy_threads: usize,
+}
+
+impl Default for IoLimits {
+    fn default() -> Self {
+        Self {
+            scan_threads: 4,
+            copy_threads: 2,
+        }
+    }
+}
+
+// What full prefix backups leave out, see `prefix_backup`
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct PrefixBackupOptions {
+    pub include_shader_caches: bool,
+    pub skip_system_dirs: bool, // Wine's windows and Common Files folders
+    pub excludes: Vec<String>,  // Extra tar patterns, e.g. "*.log"
+}
+
+// Size above which a save location gets a warning, see `Config::save_quota_for`
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(default)]
+pub struct SaveQuota {
+    pub max_mb: u64,  // 0 = no limit
+    pub notify: bool, // Also send a desktop notification when a scan finds one over it
+}
+
+impl Default for SaveQuota {
+    fn default() -> Self {
+        Self {
+            max_mb: 0,
+            notify: true,
+        }
+    }
+}
+
+// WebDAV passwords by destination name, next to config.json
+const PASSWORDS_FILE: &str = "passwords.json";
+
+// A destination for save bundles set up in Settings, see `backup_target`
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum BackupTargetSettings {
+    Local {
+        name: String,
+        path: PathBuf,
+    },
+    // Through the system's sftp client, logging in with an SSH key or agent
+    Sftp {
+        name: String,
+        host: String,
+        port: u16,
+        user: String,
+        path: String, // Folder on the server, relative to the login folder unless absolute
+    },
+    WebDav {
+        name: String,
+        url: String, // Of the folder bundles go into, e.g. a Nextcloud files URL
+        user: String,
+        // Kept out of config.json, in PASSWORDS_FILE next to it that only the
+        // user can read. An app password is best.
+        #[serde(default, skip_serializing)]
+        password: String,
+    },
+}
+
+impl BackupTargetSettings {
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Local { name, .. } | Self::Sftp { name, .. } | Self::WebDav { name, .. } => name,
+        }
+    }
+}
+
+// Compressor for new safety snapshots, run through the system's tar
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Compression {
+    #[default]
+    None,
+    Zstd,
+    Gzip,
+}
+
+impl Compression {
+    pub const ALL: [Compression; 3] = [Compression::None, Compression::Zstd, Compression::Gzip];
+
+    // Name of the compressor, shown as is
+    pub fn name(self) -> Option<&'static str> {
+        match self {
+            Compression::None => None,
+            Compression::Zstd => Some("zstd"),
+            Compression::Gzip => Some("gzip"),
+        }
+    }
+
+    // Levels the compressor accepts and the one it uses by default
+    pub fn level_range(self) -> (u32, u32) {
+        match self {
+            Compression::None => (0, 0),
+            Compression::Zstd => (1, 19),
+            Compression::Gzip => (1, 9),
+        }
+    }
+    pub fn default_level(self) -> u32 {
+        match self {
+            Compression::None => 0,
+            Compression::Zstd => 3,
+            Compression::Gzip => 6,
+        }
+    }
+}
+
+// Whose Steam userdata is used when several people share the machine, see
+// `steam::active_account_ids`
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Default, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum SteamAccountChoice {
+    #[default]
+    All,
+    SignedIn, // Whoever logged into Steam last, follows account switches
+    Account(String), // userdata folder name (32-bit account ID)
+}
+
+// Which destructive actions ask before going ahead
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfirmationLevel {
+    #[default]
+    Always,
+    DeletionsOnly, // Replacing saves doesn't ask, it's undone with the safety snapshot
+    Never,
+}
+
+impl ConfirmationLevel {
+    pub const ALL: [ConfirmationLevel; 3] = [ConfirmationLevel::Always, ConfirmationLevel::DeletionsOnly, ConfirmationLevel::Never];
+}
+
+// What an action asking for confirmation does to the user's files
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ConfirmAction {
+    Delete,  // Removes files: prefixes, snapshots, history
+    Replace, // Overwrites saves, after taking a safety snapshot
+}
+
+// How new safety snapshots are stored, existing ones stay as they are
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(default)]
+pub struct SnapshotCompression {
+    pub method: Compression,
+    pub level: u32, // Clamped to the method's range when used
+}
+
+impl Default for SnapshotCompression {
+    fn default() -> Self {
+        // Plain copies, they can be browsed in a file manager
+        Self {
+            method: Compression::None,
+            level: 0,
+        }
+    }
+}
+
+// What changed, passed to listeners registered with `Config::connect_changed`
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ConfigKey {
+    SteamPath,
+    ManifestUrl,
+    ManifestSources,
+    ManifestCache, // The cached manifest file was replaced on disk
+    ScanOptions,
+    Privacy,
+    Retention,
+    ScanLocations, // Save paths or ignored folders
+    DisplayNames,
+    Favorites,
+    HiddenPrefixes,
+    GameAssignments, // Manifest games or custom save paths set for a prefix
+    SaveQuota,
+    SteamAccount,
+}
+
+// The settings and paths of a `Config` without its listeners, which unlike the
+// config itself can go to another thread. See `Config::detach`.
+#[derive(Clone)]
+pub struct DetachedConfig {
+    data: ConfigData,
+    config_path: PathBuf,
+    cache_path: PathBuf,
+    data_dir: PathBuf,
+    libraries: Vec<PathBuf>,
+    media_root: PathBuf,
+}
+
+impl DetachedConfig {
+    pub fn privacy(&self) -> PrivacySettings {
+        self.data.privacy
+    }
+    // A config for the thread it's called on, nothing is read from disk.
+    // Settings changed through it aren't seen by the app, so it's meant for
+    // reading only.
+    pub fn to_config(&self) -> Config {
+        Config {
+            data: self.data.clone(),
+            config_path: self.config_path.clone(),
+            cache_path: self.cache_path.clone(),
+            data_dir: self.data_dir.clone(),
+            listeners: Vec::new(),
+            dispatcher: None,
+            libraries: self.libraries.clone(),
+            media_root: self.media_root.clone(),
+            skipped_confirmations: HashSet::new(),
+        }
+    }
+}
+
+type ConfigListener = Rc<dyn Fn(ConfigKey)>;
+// Schedules a listener call to run later, see `Config::set_dispatcher`
+type ConfigDispatcher = Rc<dyn Fn(Box<dyn FnOnce()>)>;
+
+pub struct Config {
+    data: ConfigData,
+    config_path: PathBuf,
+    cache_path: PathBuf,
+    data_dir: PathBuf,
+    listeners: Vec<ConfigListener>,
+    dispatcher: Option<ConfigDispatcher>,
+    // Extra Steam library folders from libraryfolders.vdf, see `reload_libraries`
+    libraries: Vec<PathBuf>,
+    // Where removable drives like SD cards get mounted
+    media_root: PathBuf,
+    // Confirmations the user chose not to see again until the app restarts
+    skipped_confirmations: HashSet<String>,
+}
+
+impl Config {
+    pub fn new() -> Self {
+        if demo::is_enabled() {
+            return Self::demo();
+        }
+
+        let config_dir = dirs::config_dir()
+            .unwrap_or_else(|| PathBuf::from(".config")) // Fallback
+            .join("proton_game_saves");
+        let cache_dir = dirs::cache_dir()
+            .unwrap_or_else(|| PathBuf::from(".cache")) // Fallback
+            .join("proton_game_saves");
+        let data_dir = dirs::data_dir()
+            .unwrap_or_else(|| PathBuf::from(".local/share")) // Fallback
+            .join("proton_game_saves");
+
+        let config_path = config_dir.join("config.json");
+        let cache_path = cache_dir.join("manifest.yaml");
+
+        // Ensure directories exist
+        let _ = fs::create_dir_all(&config_dir);
+        let _ = fs::create_dir_all(&cache_dir);
+
+        // Load or create default config data
+        let data = Self::load_config_data(&config_path).unwrap_or_else(|| {
+            let default_steam_path = dirs::home_dir()
+                .map(|home| home.join(".steam"))
+                .unwrap_or_else(|| PathBuf::from("."));
+            ConfigData::new(default_steam_path, true)
+        });
+        
+        // Create the Config struct
+        let mut config = Self {
+            data,
+            config_path,
+            cache_path,
+            data_dir,
+            listeners: Vec::new(),
+            dispatcher: None,
+            libraries: Vec::new(),
+            media_root: PathBuf::from("/run/media"),
+            skipped_confirmations: HashSet::new(),
+        };
+        config.reload_libraries();
+
+        // Save immediately if it was newly created
+        if Self::load_config_data(&config.config_path).is_none() {
+            let _ = config.save_config();
+        }
+
+        config
+    }
+
+    // Demo mode keeps config, manifest and snapshots inside the generated
+    // fixture so the real library and settings are never touched
+    fn demo() -> Self {
+        Self::at_root(&demo::fixture_root(), demo::steam_path(), demo::manifest_path())
+    }
+
+    // Config, snapshots and parsed manifest all under `root`, for the demo and
+    // the test fixtures. `steam_path` and `manifest_path` can point anywhere.
+    pub fn at_root(root: &Path, steam_path: PathBuf, manifest_path: PathBuf) -> Self {
+        let config_path = root.join("config.json");
+        let data = Self::load_config_data(&config_path)
+            .unwrap_or_else(|| ConfigData::new(steam_path, false));
+
+        let mut config = Self {
+            data,
+            config_path,
+            cache_path: manifest_path,
+            data_dir: root.join("data"),
+            listeners: Vec::new(),
+            dispatcher: None,
+            libraries: Vec::new(),
+            media_root: root.join("media"),
+            skipped_confirmations: HashSet::new(),
+        };
+        config.reload_libraries();
+        if Self::load_config_data(&config.config_path).is_none() {
+            let _ = config.save_config();
+        }
+        config
+    }
+
+    // For work on another thread with the settings as they are now
+    pub fn detach(&self) -> DetachedConfig {
+        DetachedConfig {
+            data: self.data.clone(),
+            config_path: self.config_path.clone(),
+            cache_path: self.cache_path.clone(),
+            data_dir: self.data_dir.clone(),
+            libraries: self.libraries.clone(),
+            media_root: self.media_root.clone(),
+        }
+    }
+
+    pub fn is_demo(&self) -> bool {
+        self.config_path.starts_with(demo::fixture_root())
+    }
+
+    // --- Path Getters ---
+    pub fn steam_path(&self) -> &Path {
+        &self.data.steam_path
+    }
+    pub fn manifest_url(&self) -> &str {
+        &self.data.manifest_url
+    }
+    // The shared manifest while it exists, our own cache otherwise
+    pub fn manifest_cache_path(&self) -> &Path {
+        match &self.data.shared_manifest_path {
+            Some(shared) if shared.exists() => shared,
+            _ => &self.cache_path,
+        }
+    }
+    pub fn sync_folder(&self) -> Option<&Path> {
+        self.data.sync_folder.as_deref()
+    }
+    pub fn backup_targets(&self) -> &[BackupTargetSettings] {
+        &self.data.backup_targets
+    }
+    pub fn shared_manifest_path(&self) -> Option<&Path> {
+        self.data.shared_manifest_path.as_deref()
+    }
+    // Whether downloads may replace the manifest in use. The other tool's file
+    // is only updated when we're allowed to write it, otherwise it's read-only.
+    pub fn manifest_cache_writable(&self) -> bool {
+        let path = self.manifest_cache_path();
+        path == self.cache_path || fs::OpenOptions::new().write(true).open(path).is_ok()
+    }
+    pub fn manifest_sources(&self) -> &[String] {
+        &self.data.manifest_sources
+    }
+    // Where a remote manifest source gets cached, keyed by a hash of its URL
+    // (FNV-1a so the file name stays stable across builds)
+    pub fn manifest_source_cache_path(&self, source: &str) -> PathBuf {
+        let hash = source
+            .bytes()
+            .fold(0xcbf29ce484222325u64, |h, b| (h ^ b as u64).wrapping_mul(0x100000001b3));
+        self.cache_path.with_file_name(format!("source-{:016x}.yaml", hash))
+    }
+    // The merged manifest after parsing, always next to our own cache
+    pub fn parsed_manifest_cache_path(&self) -> PathBuf {
+        self.cache_path.with_file_name("manifest-parsed.json")
+    }
+    // Kept in the data dir rather than the cache so clearing caches doesn't lose them
+    pub fn safety_snapshots_path(&self) -> PathBuf {
+        self.data.snapshot_root.clone().unwrap_or_else(|| self.data_dir.join("safety"))
+    }
+    pub fn config_path(&self) -> &Path {
+        &self.config_path
+    }
+    // Save sizes of past scans, see `size_history`
+    pub fn size_history_path(&self) -> PathBuf {
+        self.data_dir.join("size_history.json")
+    }
+    // What the last scan found, to show what changed, see `scan_changes`
+    pub fn last_scan_path(&self) -> PathBuf {
+        self.data_dir.join("last_scan.json")
+    }
+    // Prefixes found by the last scan, shown on startup while the next one runs
+    pub fn scan_cache_path(&self) -> PathBuf {
+        self.cache_path.with_file_name("scan.json")
+    }
+    // Measured sizes of whole prefixes, a cache next to the parsed manifest, see `prefix_sizes`
+    pub fn prefix_sizes_cache_path(&self) -> PathBuf {
+        self.cache_path.with_file_name("prefix-sizes.json")
+    }
+    // Log of backups, restores, deletions and imports, see `activity`
+    pub fn activity_log_path(&self) -> PathBuf {
+        self.data_dir.join("activity.jsonl")
+    }
+    pub fn scan_options(&self) -> ScanOptions {
+        self.data.scan_options
+    }
+    // `compatdata_path` being one of `compatdata_paths`
+    pub fn is_library_scanned(&self, compatdata_path: &Path) -> bool {
+        !self.data.skipped_libraries.iter().any(|skipped| skipped == compatdata_path)
+    }
+    pub fn is_manifest_source_scanned(&self, source: &str) -> bool {
+        !self.data.skipped_manifest_sources.iter().any(|skipped| skipped == source)
+    }
+    pub fn privacy(&self) -> PrivacySettings {
+        self.data.privacy
+    }
+    pub fn retention(&self) -> RetentionPolicy {
+        self.data.retention
+    }
+    pub fn retention_overrides(&self) -> &HashMap<String, RetentionPolicy> {
+        &self.data.retention_overrides
+    }
+    // Size limit for save locations, and the per-game limits (in MB) over it
+    pub fn save_quota(&self) -> SaveQuota {
+        self.data.save_quota
+    }
+    pub fn save_quota_overrides(&self) -> &HashMap<String, u64> {
+        &self.data.save_quota_overrides
+    }
+    // Limit in bytes for each save location of `game_id`, `None` if there's none
+    pub fn save_quota_for(&self, game_id: &str) -> Option<u64> {
+        let max_mb = self
+            .data
+            .save_quota_overrides
+            .get(game_id)
+            .copied()
+            .unwrap_or(self.data.save_quota.max_mb);
+        (max_mb > 0).then_some(max_mb * 1_000_000)
+    }
+    // The policy that applies to one game's snapshots
+    pub fn retention_for(&self, game_id: &str) -> RetentionPolicy {
+        self.data
+            .retention_overrides
+            .get(game_id)
+            .copied()
+            .unwrap_or(self.data.retention)
+    }
+    pub fn confirmation_level(&self) -> ConfirmationLevel {
+        self.data.confirmations
+    }
+    pub fn steam_account(&self) -> &SteamAccountChoice {
+        &self.data.steam_account
+    }
+    pub fn set_steam_account(&mut self, choice: SteamAccountChoice) -> Result<()> {
+        self.data.steam_account = choice;
+        self.save_config()?;
+        self.notify(ConfigKey::SteamAccount);
+        Ok(())
+    }
+    pub fn set_confirmation_level(&mut self, level: ConfirmationLevel) -> Result<()> {
+        self.data.confirmations = level;
+        self.save_config()
+    }
+    // Whether the confirmation `id` (e.g. "delete-prefix") should be shown
+    // for `action`, from the setting and "don't ask again" for this session
+    pub fn needs_confirmation(&self, action: ConfirmAction, id: &str) -> bool {
+        if self.skipped_confirmations.contains(id) {
+            return false;
+        }
+        match self.data.confirmations {
+            ConfirmationLevel::Always => true,
+            ConfirmationLevel::DeletionsOnly => action == ConfirmAction::Delete,
+            ConfirmationLevel::Never => false,
+        }
+    }
+    pub fn skip_confirmation_for_session(&mut self, id: &str) {
+        self.skipped_confirmations.insert(id.to_string());
+    }
+    pub fn snapshot_compression(&self) -> SnapshotCompression {
+        self.data.snapshot_compression
+    }
+    pub fn bundle_steam_settings(&self) -> bool {
+        self.data.bundle_steam_settings
+    }
+    pub fn prefix_backup_options(&self) -> &PrefixBackupOptions {
+        &self.data.prefix_backup
+    }
+    pub fn io_limits(&self) -> IoLimits {
+        self.data.io_limits
+    }
+    pub fn save_paths(&self) -> &[String] {
+        &self.data.save_paths
+    }
+    pub fn ignore_dirs(&self) -> &[String] {
+        &self.data.ignore_dirs
+    }
+    pub fn ignore_patterns(&self) -> &[String] {
+        &self.data.ignore_patterns
+    }
+    // Only the game's own patterns, without the global ones
+    pub fn game_ignore_patterns(&self, app_id: &str) -> &[String] {
+        self.data.game_ignore_patterns.get(app_id).map(Vec::as_slice).unwrap_or_default()
+    }
+    // Everything left out of the save locations of `app_id`
+    pub fn ignore_patterns_for(&self, app_id: &str) -> IgnorePatterns {
+        let mut patterns = self.data.ignore_patterns.clone();
+        patterns.extend_from_slice(self.game_ignore_patterns(app_id));
+        IgnorePatterns::new(&patterns)
+    }
+    // The editor set for files like `path`, if any
+    pub fn editor_command_for(&self, path: &Path) -> Option<&str> {
+        let extension = save_editor::extension_key(path)?;
+        self.data.editor_commands.get(&extension).map(String::as_str)
+    }
+    // "extension: command" entries for the list in Settings
+    pub fn editor_command_entries(&self) -> Vec<String> {
+        let mut entries: Vec<String> = self
+            .data
+            .editor_commands
+            .iter()
+            .map(|(extension, command)| save_editor::format_entry(extension, command))
+            .collect();
+        entries.sort();
+        entries
+    }
+    pub fn display_name(&self, app_id: &str) -> Option<&str> {
+        self.data.display_names.get(app_id).map(String::as_str)
+    }
+    pub fn is_first_run(&self) -> bool {
+        self.data.first_run
+    }
+    pub fn compatdata_path(&self) -> PathBuf {
+        self.data.steam_path.join("steam/steamapps/compatdata")
+    }
+    // Steam client settings, holds CompatToolMapping among other things
+    pub fn steam_config_path(&self) -> PathBuf {
+        self.data.steam_path.join("steam/config/config.vdf")
+    }
+    // Accounts that logged into Steam on this machine, with their names
+    pub fn steam_login_users_path(&self) -> PathBuf {
+        self.data.steam_path.join("steam/config/loginusers.vdf")
+    }
+    // Per-account data, one folder per Steam account ID
+    pub fn steam_userdata_path(&self) -> PathBuf {
+        self.data.steam_path.join("steam/userdata")
+    }
+    // Controller layouts, one folder per Steam account ID
+    pub fn steam_controller_configs_path(&self) -> PathBuf {
+        self.data.steam_path.join("steam/steamapps/common/Steam Controller Configs")
+    }
+    // Lists the Steam library folders, other drives included
+    pub fn steam_library_folders_path(&self) -> PathBuf {
+        self.data.steam_path.join("steam/steamapps/libraryfolders.vdf")
+    }
+    // compatdata of every library, the main one first. Proton puts a game's
+    // prefix into the library the game is installed in.
+    pub fn compatdata_paths(&self) -> Vec<PathBuf> {
+        let mut paths = vec![self.compatdata_path()];
+        paths.extend(self.libraries.iter().map(|library| library.join("steamapps/compatdata")));
+        paths
+    }
+    // The prefix folder of a game, in whichever library has it
+    pub fn prefix_path(&self, game_id: &str) -> PathBuf {
+        self.compatdata_paths()
+            .into_iter()
+            .map(|compatdata| compatdata.join(game_id))
+            .find(|path| path.is_dir())
+            .unwrap_or_else(|| self.compatdata_path().join(game_id))
+    }
+    pub fn drive_c_path(&self, game_id: &str) -> PathBuf {
+        self.prefix_path(game_id)
+            .join("pfx/drive_c")
+    }
+
+    // Re-read libraryfolders.vdf, e.g. before a scan in case a library was
+    // added, and look for libraries on SD cards and other removable drives
+    // Steam doesn't list
+    pub fn reload_libraries(&mut self) {
+        self.libraries = steam::library_folders(self);
+        let mut known = self.libraries.clone();
+        known.push(self.steam_path().join("steam"));
+        let removable = steam::removable_libraries(&self.media_root, &known);
+        self.libraries.extend(removable);
+    }
+    // Whether `path` is on an SD card or another removable drive
+    pub fn is_on_removable_media(&self, path: &Path) -> bool {
+        path.starts_with(&self.media_root)
+    }
+
+    // --- Setters that save --- 
+    pub fn set_steam_path(&mut self, path: PathBuf) -> Result<()> {
+        if !path.exists() {
+            return Err(anyhow!("Steam path does not exist"));
+        }
+        self.data.steam_path = path;
+        self.save_config()?;
+        self.reload_libraries();
+        self.notify(ConfigKey::SteamPath);
+        Ok(())
+    }
+    pub fn set_manifest_url(&mut self, url: String) -> Result<()> {
+        // Basic validation (could be more robust)
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            return Err(anyhow!("Invalid URL format"));
+        }
+        self.data.manifest_url = url;
+        self.save_config()?;
+        self.notify(ConfigKey::ManifestUrl);
+        Ok(())
+    }
+    pub fn add_manifest_source(&mut self, source: String) -> Result<()> {
+        let source = source.trim().to_string();
+        if source.is_empty() {
+            return Err(anyhow!("Manifest source is empty"));
+        }
+        if !is_remote_source(&source) && !Path::new(&source).is_file() {
+            return Err(anyhow!("Manifest source must be an http(s) URL or an existing file"));
+        }
+        if self.data.manifest_sources.contains(&source) {
+            return Err(anyhow!("Manifest source is already added"));
+        }
+        self.data.manifest_sources.push(source);
+        self.save_config()?;
+        self.notify(ConfigKey::ManifestSources);
+        Ok(())
+    }
+    pub fn remove_manifest_source(&mut self, source: &str) -> Result<()> {
+        self.data.manifest_sources.retain(|s| s != source);
+        self.data.skipped_manifest_sources.retain(|s| s != source);
+        self.save_config()?;
+        self.notify(ConfigKey::ManifestSources);
+        Ok(())
+    }
+    pub fn set_scan_options(&mut self, options: ScanOptions) -> Result<()> {
+        self.data.scan_options = options;
+        self.save_config()?;
+        self.notify(ConfigKey::ScanOptions);
+        Ok(())
+    }
+    // Like the other scan options, applies from the next refresh
+    pub fn set_library_scanned(&mut self, compatdata_path: &Path, scanned: bool) -> Result<()> {
+        self.data.skipped_libraries.retain(|skipped| skipped != compatdata_path);
+        if !scanned {
+            self.data.skipped_libraries.push(compatdata_path.to_path_buf());
+        }
+        self.save_config()?;
+        self.notify(ConfigKey::ScanOptions);
+        Ok(())
+    }
+    // The manifest is merged again without (or with) it right away
+    pub fn set_manifest_source_scanned(&mut self, source: &str, scanned: bool) -> Result<()> {
+        self.data.skipped_manifest_sources.retain(|skipped| skipped != source);
+        if !scanned {
+            self.data.skipped_manifest_sources.push(source.to_string());
+        }
+        self.save_config()?;
+        self.notify(ConfigKey::ManifestSources);
+        Ok(())
+    }
+    pub fn set_privacy(&mut self, privacy: PrivacySettings) -> Result<()> {
+        self.data.privacy = privacy;
+        self.save_config()?;
+        self.notify(ConfigKey::Privacy);
+        Ok(())
+    }
+    pub fn set_retention(&mut self, policy: RetentionPolicy) -> Result<()> {
+        self.data.retention = policy;
+        self.save_config()?;
+        self.notify(ConfigKey::Retention);
+        Ok(())
+    }
+    // `None` removes the override so the game follows the global policy again
+    pub fn set_retention_override(&mut self, game_id: &str, policy: Option<RetentionPolicy>) -> Result<()> {
+        match policy {
+            Some(policy) => self.data.retention_overrides.insert(game_id.to_string(), policy),
+            None => self.data.retention_overrides.remove(game_id),
+        };
+        self.save_config()?;
+        self.notify(ConfigKey::Retention);
+        Ok(())
+    }
+    pub fn set_save_quota(&mut self, quota: SaveQuota) -> Result<()> {
+        self.data.save_quota = quota;
+        self.save_config()?;
+        self.notify(ConfigKey::SaveQuota);
+        Ok(())
+    }
+    // `None` removes the override so the game uses the global limit again
+    pub fn set_save_quota_override(&mut self, game_id: &str, max_mb: Option<u64>) -> Result<()> {
+        match max_mb {
+            Some(max_mb) => self.data.save_quota_overrides.insert(game_id.to_string(), max_mb),
+            None => self.data.save_quota_overrides.remove(game_id),
+        };
+        self.save_config()?;
+        self.notify(ConfigKey::SaveQuota);
+        Ok(())
+    }
+    // Paths are relative to the steamuser folder, e.g. "Documents/My Games",
+    // or to drive_c with a "drive_c/" prefix, e.g. "drive_c/ProgramData"
+    pub fn set_save_paths(&mut self, paths: Vec<String>) -> Result<()> {
+        let paths = clean_list(paths);
+        if let Some(bad) = paths.iter().find(|p| p.starts_with('/') || p.split('/').any(|part| part == "..")) {
+            return Err(anyhow!("Save path must be relative to the steamuser folder: {}", bad));
+        }
+        self.data.save_paths = paths;
+        self.save_config()?;
+        self.notify(ConfigKey::ScanLocations);
+        Ok(())
+    }
+    // Folder names (not paths) skipped inside every save path
+    pub fn set_ignore_dirs(&mut self, dirs: Vec<String>) -> Result<()> {
+        let dirs = clean_list(dirs);
+        if let Some(bad) = dirs.iter().find(|d| d.contains('/')) {
+            return Err(anyhow!("Ignored folders are plain folder names, not paths: {}", bad));
+        }
+        self.data.ignore_dirs = dirs;
+        self.save_config()?;
+        self.notify(ConfigKey::ScanLocations);
+        Ok(())
+    }
+    // Glob patterns inside save folders, e.g. "*.log" or "CrashDumps/**"
+    pub fn set_ignore_patterns(&mut self, patterns: Vec<String>) -> Result<()> {
+        let patterns = clean_list(patterns);
+        patterns.iter().try_for_each(|pattern| ignore_patterns::validate(pattern))?;
+        self.data.ignore_patterns = patterns;
+        self.save_config()?;
+        self.notify(ConfigKey::ScanLocations);
+        Ok(())
+    }
+    // An empty list leaves the game with the global patterns only
+    pub fn set_game_ignore_patterns(&mut self, app_id: &str, patterns: Vec<String>) -> Result<()> {
+        let patterns = clean_list(patterns);
+        patterns.iter().try_for_each(|pattern| ignore_patterns::validate(pattern))?;
+        if patterns.is_empty() {
+            self.data.game_ignore_patterns.remove(app_id);
+        } else {
+            self.data.game_ignore_patterns.insert(app_id.to_string(), patterns);
+        }
+        self.save_config()?;
+        self.notify(ConfigKey::ScanLocations);
+        Ok(())
+    }
+    // A later entry for the same extension replaces the earlier one
+    pub fn set_editor_command_entries(&mut self, entries: Vec<String>) -> Result<()> {
+        let mut commands = HashMap::new();
+        for entry in clean_list(entries) {
+            let (extension, command) = save_editor::parse_entry(&entry)?;
+            commands.insert(extension, command);
+        }
+        self.data.editor_commands = commands;
+        self.save_config()
+    }
+    // `None` (or an empty name) goes back to the name from the manifest
+    pub fn set_display_name(&mut self, app_id: &str, name: Option<String>) -> Result<()> {
+        match name.map(|n| n.trim().to_string()).filter(|n| !n.is_empty()) {
+            Some(name) => self.data.display_names.insert(app_id.to_string(), name),
+            None => self.data.display_names.remove(app_id),
+        };
+        self.save_config()?;
+        self.notify(ConfigKey::DisplayNames);
+        Ok(())
+    }
+    pub fn is_favorite(&self, app_id: &str) -> bool {
+        self.data.favorites.iter().any(|id| id == app_id)
+    }
+    pub fn set_favorite(&mut self, app_id: &str, favorite: bool) -> Result<()> {
+        self.data.favorites.retain(|id| id != app_id);
+        if favorite {
+            self.data.favorites.push(app_id.to_string());
+        }
+        self.save_config()?;
+        self.notify(ConfigKey::Favorites);
+        Ok(())
+    }
+    pub fn is_prefix_hidden(&self, app_id: &str) -> bool {
+        self.data.hidden_prefixes.iter().any(|id| id == app_id)
+    }
+    pub fn set_prefix_hidden(&mut self, app_id: &str, hidden: bool) -> Result<()> {
+        self.data.hidden_prefixes.retain(|id| id != app_id);
+        if hidden {
+            self.data.hidden_prefixes.push(app_id.to_string());
+        }
+        self.save_config()?;
+        self.notify(ConfigKey::HiddenPrefixes);
+        Ok(())
+    }
+    pub fn game_assignment(&self, app_id: &str) -> Option<&str> {
+        self.data.game_assignments.get(app_id).map(String::as_str)
+    }
+    // `None` goes back to matching the prefix automatically
+    pub fn set_game_assignment(&mut self, app_id: &str, game_name: Option<String>) -> Result<()> {
+        match game_name {
+            Some(name) => self.data.game_assignments.insert(app_id.to_string(), name),
+            None => self.data.game_assignments.remove(app_id),
+        };
+        self.save_config()?;
+        self.notify(ConfigKey::GameAssignments);
+        Ok(())
+    }
+    pub fn custom_save_paths(&self, app_id: &str) -> &[String] {
+        self.data.custom_save_paths.get(app_id).map(Vec::as_slice).unwrap_or_default()
+    }
+    pub fn set_custom_save_paths(&mut self, app_id: &str, paths: Vec<String>) -> Result<()> {
+        let paths = clean_list(paths);
+        if paths.is_empty() {
+            self.data.custom_save_paths.remove(app_id);
+        } else {
+            self.data.custom_save_paths.insert(app_id.to_string(), paths);
+        }
+        self.save_config()?;
+        self.notify(ConfigKey::GameAssignments);
+        Ok(())
+    }
+    pub fn view_state(&self, page: &str) -> ViewState {
+        self.data.view_states.get(page).cloned().unwrap_or_default()
+    }
+    // Only the page itself cares, so this doesn't notify listeners
+    pub fn set_view_state(&mut self, page: &str, state: ViewState) -> Result<()> {
+        self.data.view_states.insert(page.to_string(), state);
+        self.save_config()
+    }
+    // Remembers whether a page's row for `app_id` is open, also without notifying
+    pub fn set_row_expanded(&mut self, page: &str, app_id: &str, expanded: bool) -> Result<()> {
+        let state = self.data.view_states.entry(page.to_string()).or_default();
+        let was_expanded = state.expanded.iter().any(|id| id == app_id);
+        if expanded == was_expanded {
+            return Ok(());
+        }
+        if expanded {
+            state.expanded.push(app_id.to_string());
+        } else {
+            state.expanded.retain(|id| id != app_id);
+        }
+        self.save_config()
+    }
+    pub fn window_state(&self) -> &WindowState {
+        &self.data.window_state
+    }
+    // Saved when the window closes, nothing else listens for it
+    pub fn set_window_state(&mut self, state: WindowState) -> Result<()> {
+        self.data.window_state = state;
+        self.save_config()
+    }
+    pub fn set_shared_manifest_path(&mut self, path: Option<PathBuf>) -> Result<()> {
+        if let Some(path) = &path {
+            if !path.is_file() {
+                return Err(anyhow!("Manifest file {} does not exist", path.display()));
+            }
+        }
+        self.data.shared_manifest_path = path;
+        self.save_config()?;
+        self.notify(ConfigKey::ManifestCache);
+        Ok(())
+    }
+    // Linked locations keep pointing at the old folder, only new links use this one
+    pub fn set_sync_folder(&mut self, path: Option<PathBuf>) -> Result<()> {
+        if let Some(path) = &path {
+            if !path.is_dir() {
+                return Err(anyhow!("Sync folder {} does not exist", path.display()));
+            }
+        }
+        self.data.sync_folder = path;
+        self.save_config()
+    }
+    // Names tell the targets apart in the export dialog, so they have to be unique
+    pub fn add_backup_target(&mut self, target: BackupTargetSettings) -> Result<()> {
+        validate_backup_target(&target, &self.data.backup_targets)?;
+        self.data.backup_targets.push(target);
+        self.save_config()
+    }
+    pub fn remove_backup_target(&mut self, name: &str) -> Result<()> {
+        self.data.backup_targets.retain(|t| t.name() != name);
+        self.save_config()
+    }
+    // Snapshots already taken stay where they are, `snapshot::move_snapshots`
+    // moves them first. None goes back to the data dir.
+    pub fn set_snapshot_root(&mut self, path: Option<PathBuf>) -> Result<()> {
+        if let Some(path) = &path {
+            if !path.is_dir() {
+                return Err(anyhow!("Snapshot folder {} does not exist", path.display()));
+            }
+        }
+        self.data.snapshot_root = path;
+        self.save_config()
+    }
+    // Only applies to snapshots taken from now on
+    pub fn set_snapshot_compression(&mut self, compression: SnapshotCompression) -> Result<()> {
+        self.data.snapshot_compression = compression;
+        self.save_config()
+    }
+    pub fn set_bundle_steam_settings(&mut self, include: bool) -> Result<()> {
+        self.data.bundle_steam_settings = include;
+        self.save_config()
+    }
+    pub fn set_prefix_backup_options(&mut self, mut options: PrefixBackupOptions) -> Result<()> {
+        options.excludes = clean_list(options.excludes);
+        self.data.prefix_backup = options;
+        self.save_config()
+    }
+    pub fn set_io_limits(&mut self, limits: IoLimits) -> Result<()> {
+        self.data.io_limits = IoLimits {
+            scan_threads: limits.scan_threads.clamp(1, MAX_IO_THREADS),
+            copy_threads: limits.copy_threads.clamp(1, MAX_IO_THREADS),
+        };
+        self.save_config()
+    }
+    pub fn mark_first_run_complete(&mut self) -> Result<()> {
+        self.data.first_run = false;
+        self.save_config()
+    }
+
+    // --- Export/Import ---
+    // The whole configuration into a JSON file, to set up another machine the
+    // same way (a Steam Deck next to the desktop). Same format as config.json,
+    // minus the window size and, unless `include_steam_path`, the Steam folder,
+    // which rarely is the same elsewhere. WebDAV passwords aren't in config.json
+    // and are only added with `include_passwords`, the file is then as secret
+    // as the passwords.
+    pub fn export_settings(&self, path: &Path, include_steam_path: bool, include_passwords: bool) -> Result<()> {
+        let mut value = serde_json::to_value(&self.data)?;
+        if let Some(object) = value.as_object_mut() {
+            for key in ["first_run", "window_state"] {
+                object.remove(key);
+            }
+            if !include_steam_path {
+                object.remove("steam_path");
+            }
+            if let (true, Some(serde_json::Value::Array(targets))) = (include_passwords, object.get_mut("backup_targets")) {
+                for (exported, target) in targets.iter_mut().zip(&self.data.backup_targets) {
+                    if let (Some(exported), BackupTargetSettings::WebDav { password, .. }) = (exported.as_object_mut(), target) {
+                        exported.insert("password".to_string(), password.clone().into());
+                    }
+                }
+            }
+        }
+        fs::write(path, serde_json::to_string_pretty(&value)?)
+            .map_err(|e| anyhow!("Failed to write {}: {}", path.display(), e))?;
+        println!("Exported settings to {}", path.display());
+        Ok(())
+    }
+
+    // Replace the configuration with one exported by `export_settings` (or a
+    // config.json). What the file leaves out stays as it is here, and a Steam
+    // folder that doesn't exist on this machine is ignored.
+    pub fn import_settings(&mut self, path: &Path) -> Result<()> {
+        let content = fs::read_to_string(path).map_err(|e| anyhow!("Failed to read {}: {}", path.display(), e))?;
+        let imported: serde_json::Value =
+            serde_json::from_str(&content).map_err(|e| anyhow!("{} is not a settings file: {}", path.display(), e))?;
+        let serde_json::Value::Object(imported) = imported else {
+            return Err(anyhow!("{} is not a settings file", path.display()));
+        };
+        let mut value = serde_json::to_value(&self.data)?;
+        if let Some(object) = value.as_object_mut() {
+            let keep_steam_path = !imported
+                .get("steam_path")
+                .and_then(|steam_path| steam_path.as_str())
+                .is_some_and(|steam_path| Path::new(steam_path).exists());
+            for (key, imported_value) in imported {
+                if ["first_run", "window_state"].contains(&key.as_str()) || (key == "steam_path" && keep_steam_path) {
+                    continue;
+                }
+                object.insert(key, imported_value);
+            }
+        }
+        let data: ConfigData =
+            serde_json::from_value(value).map_err(|e| anyhow!("{} has invalid settings: {}", path.display(), e))?;
+        // Checked like ones added in Settings, the file may come from anywhere
+        for (index, target) in data.backup_targets.iter().enumerate() {
+            validate_backup_target(target, &data.backup_targets[..index])
+                .map_err(|e| anyhow!("{} has an invalid backup destination: {}", path.display(), e))?;
+        }
+        self.data = data;
+        self.save_config()?;
+        self.reload_libraries();
+        println!("Imported settings from {}", path.display());
+        // Nearly everything may have changed
+        for key in [
+            ConfigKey::SteamPath,
+            ConfigKey::ManifestUrl,
+            ConfigKey::ManifestSources,
+            ConfigKey::ScanOptions,
+            ConfigKey::Privacy,
+            ConfigKey::Retention,
+            ConfigKey::ScanLocations,
+            ConfigKey::DisplayNames,
+            ConfigKey::Favorites,
+            ConfigKey::HiddenPrefixes,
+            ConfigKey::GameAssignments,
+            ConfigKey::SaveQuota,
+            ConfigKey::SteamAccount,
+        ] {
+            self.notify(key);
+        }
+        Ok(())
+    }
+
+    // --- Change Notifications ---
+    // Called whenever a setting changes, so every window and page sees edits
+    // made anywhere else without passing callbacks around
+    pub fn connect_changed<F: Fn(ConfigKey) + 'static>(&mut self, listener: F) {
+        self.listeners.push(Rc::new(listener));
+    }
+    // A GUI runs listeners from its main loop rather than right away, as the
+    // setter that triggered them still holds the RefCell borrow of the config.
+    // Without a dispatcher they're called directly.
+    pub fn set_dispatcher<F: Fn(Box<dyn FnOnce()>) + 'static>(&mut self, dispatcher: F) {
+        self.dispatcher = Some(Rc::new(dispatcher));
+    }
+    pub fn notify(&self, key: ConfigKey) {
+        for listener in &self.listeners {
+            let listener = listener.clone();
+            match &self.dispatcher {
+                Some(dispatcher) => dispatcher(Box::new(move || listener(key))),
+                None => listener(key),
+            }
+        }
+    }
+
+    // --- Load/Save Logic --- 
+    fn load_config_data(path: &Path) -> Option<ConfigData> {
+        if !path.exists() {
+            return None;
+        }
+        let mut data: ConfigData = fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())?;
+
+        // Passwords older versions left in config.json move to their own file
+        let inline_passwords = data.backup_targets.iter().any(|target| matches!(target, BackupTargetSettings::WebDav { password, .. } if !password.is_empty()));
+        let passwords: HashMap<String, String> = fs::read_to_string(path.with_file_name(PASSWORDS_FILE))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        for target in &mut data.backup_targets {
+            if let BackupTargetSettings::WebDav { name, password, .. } = target {
+                if let Some(stored) = passwords.get(name) {
+                    *password = stored.clone();
+                }
+            }
+        }
+        if inline_passwords {
+            println!("Moving WebDAV passwords out of {}", path.display());
+            if let Err(e) = write_config_files(path, &data) {
+                eprintln!("Failed to move WebDAV passwords out of {}: {}", path.display(), e);
+            }
+        }
+        Some(data)
+    }
+    fn save_config(&self) -> Result<()> {
+        write_config_files(&self.config_path, &self.data)
+    }
+}
+
+// Whether `target` can be added next to `existing`
+fn validate_backup_target(target: &BackupTargetSettings, existing: &[BackupTargetSettings]) -> Result<()> {
+    let name = target.name().trim();
+    if name.is_empty() {
+        return Err(anyhow!("The backup destination needs a name"));
+    }
+    if existing.iter().any(|t| t.name() == name) {
+        return Err(anyhow!("A backup destination named {} already exists", name));
+    }
+    match target {
+        BackupTargetSettings::Local { path, .. } if !path.is_dir() => {
+            Err(anyhow!("Folder {} does not exist", path.display()))
+        }
+        BackupTargetSettings::Sftp { host, .. } if host.trim().is_empty() => {
+            Err(anyhow!("The SFTP destination needs a server"))
+        }
+        // sftp would take these for options
+        BackupTargetSettings::Sftp { host, user, .. } if host.starts_with('-') || user.starts_with('-') => {
+            Err(anyhow!("The SFTP server and user can't start with -"))
+        }
+        BackupTargetSettings::WebDav { url, .. } if !is_remote_source(url) => {
+            Err(anyhow!("The WebDAV address must be an http(s) URL"))
+        }
+        _ => Ok(()),
+    }
+}
+
+// config.json, and the WebDAV passwords in PASSWORDS_FILE next to it,
+// readable only by the user
+fn write_config_files(config_path: &Path, data: &ConfigData) -> Result<()> {
+    use std::io::Write;
+    use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+
+    fs::write(config_path, serde_json::to_string_pretty(data)?)?;
+    let passwords: HashMap<&str, &str> = data
+        .backup_targets
+        .iter()
+        .filter_map(|target| match target {
+            BackupTargetSettings::WebDav { name, password, .. } if !password.is_empty() => Some((name.as_str(), password.as_str())),
+            _ => None,
+        })
+        .collect();
+    let passwords_path = config_path.with_file_name(PASSWORDS_FILE);
+    if passwords.is_empty() {
+        if passwords_path.exists() {
+            fs::remove_file(&passwords_path)?;
+        }
+        return Ok(());
+    }
+    let mut file = fs::OpenOptions::new().write(true).create(true).truncate(true).mode(0o600).open(&passwords_path)?;
+    // The mode only applies to a new file
+    file.set_permissions(fs::Permissions::from_mode(0o600))?;
+    file.write_all(serde_json::to_string_pretty(&passwords)?.as_bytes())?;
+    Ok(())
+}
+
+// Manifest sources starting with http(s) are downloaded, anything else is a local file
+pub fn is_remote_source(source: &str) -> bool {
+    source.starts_with("http://") || source.starts_with("https://")
+}
+
+// Trim entries, drop empty ones and duplicates, keeping the order
+fn clean_list(items: Vec<String>) -> Vec<String> {
+    let mut cleaned: Vec<String> = Vec::new();
+    for item in items {
+        let item = item.trim().trim_end_matches('/').to_string();
+        if !item.is_empty() && !cleaned.contains(&item) {
+            cleaned.push(item);
+        }
+    }
+    cleaned
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::Fixture;
+
+    #[test]
+    fn exports_and_imports_settings() {
+        let desktop = Fixture::new();
+        let mut config = desktop.config();
+        config.set_favorite("1000", true).unwrap();
+        config.set_display_name("1000", Some("My Hero".to_string())).unwrap();
+        let target = BackupTargetSettings::WebDav {
+            name: "Cloud".to_string(),
+            url: "https://example.invalid/dav".to_string(),
+            user: "me".to_string(),
+            password: "hunter2".to_string(),
+        };
+        config.add_backup_target(target.clone()).unwrap();
+        let exported = desktop.root.join("settings.json");
+        config.export_settings(&exported, false, false).unwrap();
+        assert!(!fs::read_to_string(&exported).unwrap().contains("hunter2"));
+        config.export_settings(&exported, false, true).unwrap();
+
+        let deck = Fixture::new();
+        let mut other = deck.config();
+        other.import_settings(&exported).unwrap();
+        assert!(other.is_favorite("1000"));
+        assert_eq!(other.display_name("1000"), Some("My Hero"));
+        assert_eq!(other.backup_targets(), &[target]);
+        // Left out of the export, so it stays
+        assert_eq!(other.steam_path(), deck.steam_path());
+        assert!(other.import_settings(&desktop.root.join("missing.json")).is_err());
+    }
+
+    #[test]
+    fn keeps_webdav_passwords_out_of_config_json() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let fixture = Fixture::new();
+        let mut config = fixture.config();
+        let target = BackupTargetSettings::WebDav {
+            name: "Cloud".to_string(),
+            url: "https://example.invalid/dav".to_string(),
+            user: "me".to_string(),
+            password: "hunter2".to_string(),
+        };
+        config.add_backup_target(target.clone()).unwrap();
+        let app_dir = fixture.root.join("app");
+        assert!(!fs::read_to_string(app_dir.join("config.json")).unwrap().contains("hunter2"));
+        let passwords = app_dir.join(PASSWORDS_FILE);
+        assert_eq!(fs::metadata(&passwords).unwrap().permissions().mode() & 0o777, 0o600);
+        assert_eq!(fixture.config().backup_targets(), &[target]);
+        let sftp = BackupTargetSettings::Sftp {
+            name: "NAS".to_string(),
+            host: "-oProxyCommand=true".to_string(),
+            port: 22,
+            user: String::new(),
+            path: String::new(),
+        };
+        assert!(config.add_backup_target(sftp).is_err());
+    }
+
+    #[test]
+    fn rejects_imported_settings_with_a_bad_backup_destination() {
+        let fixture = Fixture::new();
+        let mut config = fixture.config();
+        let settings = fixture.root.join("settings.json");
+        fs::write(
+            &settings,
+            r#"{"favorites":["1000"],"backup_targets":[{"kind":"sftp","name":"NAS","host":"-oProxyCommand=touch /tmp/pwned","port":22,"user":"","path":""}]}"#,
+        )
+        .unwrap();
+        assert!(config.import_settings(&settings).is_err());
+        // Nothing from the file is taken
+        assert!(config.backup_targets().is_empty());
+        assert!(!config.is_favorite("1000"));
+        assert!(fixture.config().backup_targets().is_empty());
+    }
+}
@@ -0,0 +1,34 @@
+// Links that open the app on one game, for docs, scripts and launchers:
+//
+//   proton-game-saves://game/<App ID>
+//
+// `--appid <App ID>` on the command line is turned into the same link, so
+// both reach a running instance the same way.
+
+pub const SCHEME: &str = "proton-game-saves";
+
+pub fn game_uri(app_id: &str) -> String {
+    format!("{}://game/{}", SCHEME, app_id)
+}
+
+// The App ID a link is for. App IDs are numbers, shortcuts of non-Steam games
+// included, anything else isn't a link of ours.
+pub fn parse_game_uri(uri: &str) -> Option<String> {
+    let rest = uri.strip_prefix(SCHEME)?.strip_prefix("://")?;
+    let app_id = rest.strip_prefix("game/")?.trim_end_matches('/');
+    (!app_id.is_empty() && app_id.chars().all(|c| c.is_ascii_digit())).then(|| app_id.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_game_links() {
+        assert_eq!(parse_game_uri(&game_uri("1245620")), Some("1245620".to_string()));
+        assert_eq!(parse_game_uri("proton-game-saves://game/400/"), Some("400".to_string()));
+        assert_eq!(parse_game_uri("proton-game-saves://game/"), None);
+        assert_eq!(parse_game_uri("proton-game-saves://game/../etc"), None);
+        assert_eq!(parse_game_uri("steam://rungameid/400"), None);
+    }
+}
@@ -0,0 +1,212 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::compatdata;
+
+// Set by `--demo` (or by the user) to run against a generated sample library
+pub const DEMO_ENV_VAR: &str = "PROTON_GAME_SAVES_DEMO";
+
+// A fake game in the fixture: App ID, manifest name, manifest save path and
+// the files (relative to steamuser) that get created for it
+struct DemoGame {
+    app_id: &'static str,
+    name: &'static str,
+    manifest_path: &'static str,
+    files: &'static [(&'static str, usize)],
+}
+
+const DEMO_GAMES: &[DemoGame] = &[
+    DemoGame {
+        app_id: "1245620",
+        name: "ELDEN RING",
+        manifest_path: "<winAppData>/EldenRing",
+        files: &[
+            ("AppData/Roaming/EldenRing/76561190000000001/ER0000.sl2", 28_000),
+            ("AppData/Roaming/EldenRing/76561190000000001/ER0000.sl2.bak", 28_000),
+            ("AppData/Roaming/EldenRing/GraphicsConfig.xml", 2_000),
+        ],
+    },
+    DemoGame {
+        app_id: "1091500",
+        name: "Cyberpunk 2077",
+        manifest_path: "<home>/Saved Games/CD Projekt Red/Cyberpunk 2077",
+        files: &[
+            ("Saved Games/CD Projekt Red/Cyberpunk 2077/AutoSave-0/sav.dat", 120_000),
+            ("Saved Games/CD Projekt Red/Cyberpunk 2077/AutoSave-0/screenshot.png", 40_000),
+            ("Saved Games/CD Projekt Red/Cyberpunk 2077/ManualSave-1/sav.dat", 118_000),
+        ],
+    },
+    DemoGame {
+        app_id: "367520",
+        name: "Hollow Knight",
+        manifest_path: "<winLocalAppDataLow>/Team Cherry/Hollow Knight",
+        files: &[
+            ("AppData/LocalLow/Team Cherry/Hollow Knight/user1.dat", 9_000),
+            ("AppData/LocalLow/Team Cherry/Hollow Knight/user1.dat.bak1", 9_000),
+        ],
+    },
+    DemoGame {
+        app_id: "413150",
+        name: "Stardew Valley",
+        manifest_path: "<winAppData>/StardewValley/Saves",
+        files: &[
+            ("AppData/Roaming/StardewValley/Saves/Farm_123456/Farm_123456", 700_000),
+            ("AppData/Roaming/StardewValley/Saves/Farm_123456/SaveGameInfo", 4_000),
+            ("AppData/Roaming/StardewValley/ErrorLogs/startup.log", 1_500),
+        ],
+    },
+    // Found through the Documents/My Games scan root
+    DemoGame {
+        app_id: "489830",
+        name: "The Elder Scrolls V: Skyrim Special Edition",
+        manifest_path: "<winDocuments>/My Games/Skyrim Special Edition/Saves",
+        files: &[
+            ("Documents/My Games/Skyrim Special Edition/Saves/Save1.ess", 350_000),
+            ("Documents/My Games/Skyrim Special Edition/Skyrim.ini", 3_000),
+        ],
+    },
+];
+
+// Prefixes that exist but don't belong to any manifest game
+const DEMO_UNMATCHED_PREFIXES: &[&str] = &["228980", "3658110"];
+
+pub fn is_enabled() -> bool {
+    std::env::var_os(DEMO_ENV_VAR).is_some_and(|v| !v.is_empty() && v != "0")
+}
+
+// Where the fixture lives; regenerated on every demo launch
+pub fn fixture_root() -> PathBuf {
+    std::env::temp_dir().join("proton_game_saves_demo")
+}
+
+// Steam directory inside the fixture (what Config::steam_path points at)
+pub fn steam_path() -> PathBuf {
+    fixture_root().join("steam-root")
+}
+
+pub fn manifest_path() -> PathBuf {
+    fixture_root().join("manifest.yaml")
+}
+
+// Build a fresh sample library: compatdata prefixes with save files plus a matching manifest
+pub fn generate_fixture() -> Result<()> {
+    let root = fixture_root();
+    if root.exists() {
+        fs::remove_dir_all(&root).context(format!("Failed to clear {}", root.display()))?;
+    }
+
+    let compatdata = steam_path().join("steam/steamapps/compatdata");
+    for game in DEMO_GAMES {
+        let user_path = create_prefix(&compatdata, game.app_id)?;
+        for (relative, size) in game.files {
+            write_sample_file(&user_path.join(relative), *size)?;
+        }
+    }
+    for app_id in DEMO_UNMATCHED_PREFIXES {
+        create_prefix(&compatdata, app_id)?;
+    }
+
+    write_compat_tool_mapping()?;
+    write_last_played()?;
+
+    let mut manifest = String::new();
+    for game in DEMO_GAMES {
+        manifest.push_str(&format!(
+            "\"{}\":\n  files:\n    \"{}\":\n      tags:\n        - save\n  steam:\n    id: {}\n",
+            game.name, game.manifest_path, game.app_id
+        ));
+    }
+    fs::write(manifest_path(), manifest)?;
+
+    println!("Generated demo library at {}", root.display());
+    Ok(())
+}
+
+// Lay out the skeleton Proton leaves behind, returns the steamuser directory
+fn create_prefix(compatdata: &Path, app_id: &str) -> Result<PathBuf> {
+    let prefix = compatdata.join(app_id);
+    let pfx = prefix.join("pfx");
+    let user_path = compatdata::create_prefix_skeleton(&prefix)?;
+    fs::create_dir_all(pfx.join("drive_c/windows/system32"))?;
+
+    fs::write(prefix.join("version"), "8.0-104\n")?;
+    fs::write(prefix.join("config_info"), "Proton 8.0-5\n/demo/Proton 8.0/files/share/fonts/\n")?;
+    fs::write(
+        pfx.join("system.reg"),
+        "WINE REGISTRY Version 2\n;; All keys relative to \\\\Machine\n\n#arch=win64\n",
+    )?;
+    fs::write(
+        pfx.join("user.reg"),
+        "WINE REGISTRY Version 2\n;; All keys relative to \\\\User\\\\S-1-5-21-0-0-0-1000\n\n#arch=win64\n\n\
+         [Software\\\\Microsoft\\\\Windows\\\\CurrentVersion\\\\Explorer\\\\Shell Folders] 1700000000\n\
+         \"AppData\"=\"C:\\\\users\\\\steamuser\\\\AppData\\\\Roaming\"\n\
+         \"Personal\"=\"C:\\\\users\\\\steamuser\\\\Documents\"\n",
+    )?;
+
+    Ok(user_path)
+}
+
+// Steam's config.vdf with a mix of per-game and default compat tools
+fn write_compat_tool_mapping() -> Result<()> {
+    let mapping = [
+        ("0", "proton_9"),
+        ("1245620", "proton_experimental"),
+        ("1091500", "GE-Proton9-7"),
+        ("489830", "proton_8"),
+    ];
+    let mut entries = String::new();
+    for (app_id, tool) in mapping {
+        entries.push_str(&format!(
+            "\t\t\t\t\t\"{}\"\n\t\t\t\t\t{{\n\t\t\t\t\t\t\"name\"\t\t\"{}\"\n\t\t\t\t\t\t\"config\"\t\t\"\"\n\t\t\t\t\t\t\"priority\"\t\t\"250\"\n\t\t\t\t\t}}\n",
+            app_id, tool
+        ));
+    }
+    let content = format!(
+        "\"InstallConfigStore\"\n{{\n\t\"Software\"\n\t{{\n\t\t\"Valve\"\n\t\t{{\n\t\t\t\"Steam\"\n\t\t\t{{\n\t\t\t\t\"CompatToolMapping\"\n\t\t\t\t{{\n{}\t\t\t\t}}\n\t\t\t}}\n\t\t}}\n\t}}\n}}\n",
+        entries
+    );
+
+    let path = steam_path().join("steam/config/config.vdf");
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, content)?;
+    Ok(())
+}
+
+// An account's localconfig.vdf with recently and long ago played games, one
+// matched game and the unmatched prefixes have no play time, for the cleanup filter
+fn write_last_played() -> Result<()> {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let days_ago = [("1245620", 0), ("1091500", 3), ("367520", 45), ("413150", 400)];
+    let mut entries = String::new();
+    for (app_id, days) in days_ago {
+        entries.push_str(&format!(
+            "\t\t\t\t\t\"{}\"\n\t\t\t\t\t{{\n\t\t\t\t\t\t\"LastPlayed\"\t\t\"{}\"\n\t\t\t\t\t}}\n",
+            app_id,
+            now.saturating_sub(days * 24 * 60 * 60)
+        ));
+    }
+    let content = format!(
+        "\"UserLocalConfigStore\"\n{{\n\t\"Software\"\n\t{{\n\t\t\"Valve\"\n\t\t{{\n\t\t\t\"Steam\"\n\t\t\t{{\n\t\t\t\t\"apps\"\n\t\t\t\t{{\n{}\t\t\t\t}}\n\t\t\t}}\n\t\t}}\n\t}}\n}}\n",
+        entries
+    );
+
+    let path = steam_path().join("steam/userdata/12345678/config/localconfig.vdf");
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, content)?;
+    Ok(())
+}
+
+fn write_sample_file(path: &Path, size: usize) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, vec![b'#'; size]).context(format!("Failed to write {}", path.display()))
+}
@@ -0,0 +1,372 @@
+use anyhow::{Context, Result};
+use std::ffi::CString;
+use std::fmt;
+use std::fs;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::{symlink, MetadataExt};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+use std::thread;
+
+// Room kept free on top of what a copy needs, for metadata files and the
+// filesystem itself
+const SPACE_MARGIN: u64 = 16 * 1024 * 1024;
+
+// Copy a file or directory tree to `dest`, overwriting files that already exist.
+// Symlinks are recreated as symlinks (Proton prefixes are full of them) rather than followed.
+// Files keep their modification time, so a restored save still shows when it was written.
+pub fn copy_recursive(src: &Path, dest: &Path) -> Result<()> {
+    copy_recursive_except(src, dest, &|_| false)
+}
+
+// `copy_recursive`, leaving out files and folders for which `skip` is true
+// (e.g. shader caches, see `shader_cache`)
+pub fn copy_recursive_except(src: &Path, dest: &Path, skip: &dyn Fn(&Path) -> bool) -> Result<()> {
+    let metadata = fs::symlink_metadata(src)
+        .context(format!("Failed to read {}", src.display()))?;
+
+    if metadata.file_type().is_symlink() {
+        let target = fs::read_link(src)?;
+        if fs::symlink_metadata(dest).is_ok() {
+            fs::remove_file(dest)?;
+        }
+        symlink(&target, dest).context(format!("Failed to create symlink {}", dest.display()))?;
+    } else if metadata.is_dir() {
+        fs::create_dir_all(dest).context(format!("Failed to create {}", dest.display()))?;
+        for entry in fs::read_dir(src)? {
+            let entry = entry?;
+            if !skip(&entry.path()) {
+                copy_recursive_except(&entry.path(), &dest.join(entry.file_name()), skip)?;
+            }
+        }
+    } else {
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(src, dest).context(format!(
+            "Failed to copy {} to {}",
+            src.display(),
+            dest.display()
+        ))?;
+        if let Ok(modified) = metadata.modified() {
+            // Only cosmetic for most copies, not worth failing over
+            if let Err(e) = fs::File::open(dest).and_then(|file| file.set_modified(modified)) {
+                eprintln!("Failed to keep the modification time of {}: {}", dest.display(), e);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// Newest modification time (Unix seconds) of a file or anything inside a directory
+pub fn last_modified(path: &Path) -> Option<u64> {
+    let metadata = fs::symlink_metadata(path).ok()?;
+    let own = metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs());
+
+    if !metadata.is_dir() {
+        return own;
+    }
+
+    fs::read_dir(path)
+        .ok()?
+        .flatten()
+        .filter_map(|entry| last_modified(&entry.path()))
+        .chain(own)
+        .max()
+}
+
+// Total size in bytes of a file or directory tree, without following symlinks
+pub fn disk_usage(path: &Path) -> u64 {
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+    fs::read_dir(path)
+        .map(|entries| entries.flatten().map(|entry| disk_usage(&entry.path())).sum())
+        .unwrap_or(0)
+}
+
+// A file or folder directly inside a folder, for browsing it in the app
+pub struct FolderEntry {
+    pub name: String,
+    pub path: PathBuf,
+    pub is_dir: bool,
+    pub size: u64,
+    pub modified: Option<u64>, // Unix timestamp (seconds), newest inside for folders
+}
+
+// What's directly inside `dir`, folders first, then by name
+pub fn list_folder(dir: &Path) -> Vec<FolderEntry> {
+    let Ok(dir_entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut entries: Vec<FolderEntry> = dir_entries
+        .flatten()
+        .map(|entry| {
+            let path = entry.path();
+            FolderEntry {
+                name: entry.file_name().to_string_lossy().to_string(),
+                is_dir: path.is_dir(),
+                size: disk_usage(&path),
+                modified: last_modified(&path),
+                path,
+            }
+        })
+        .collect();
+    entries.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase())));
+    entries
+}
+
+// Bytes copying `src` over `dest` adds on disk, what it replaces is freed
+pub fn copy_growth(src: &Path, dest: &Path) -> u64 {
+    disk_usage(src).saturating_sub(disk_usage(dest))
+}
+
+// A copy that was refused up front because it wouldn't fit. The UI looks for
+// it in error chains to explain what to do instead of offering a bug report.
+#[derive(Debug)]
+pub struct NotEnoughSpace {
+    pub path: PathBuf, // The folder that was checked, on the full filesystem
+    pub needed: u64,
+    pub available: u64,
+}
+
+impl fmt::Display for NotEnoughSpace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Not enough free space for {}: {} bytes are needed but only {} are free",
+            self.path.display(),
+            self.needed,
+            self.available
+        )
+    }
+}
+
+impl std::error::Error for NotEnoughSpace {}
+
+// Bytes unprivileged users can still write on the filesystem holding `path`
+pub fn available_space(path: &Path) -> Result<u64> {
+    let c_path = CString::new(path.as_os_str().as_bytes())?;
+    let mut stats: libc::statvfs = unsafe { std::mem::zeroed() };
+    // SAFETY: c_path is NUL-terminated and stats is a statvfs for it to fill in
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stats) } != 0 {
+        return Err(std::io::Error::last_os_error()).context(format!("Failed to read the free space of {}", path.display()));
+    }
+    Ok(stats.f_bavail as u64 * stats.f_frsize as u64)
+}
+
+// Checks that `copies` (destination and bytes written there) fit before
+// anything is written, so a full disk stops an operation up front instead of
+// halfway through with partial data left behind. Copies to the same
+// filesystem are added up. Destinations may not exist yet, their closest
+// existing parent is checked. If the free space can't be read the copy goes
+// ahead, it fails the usual way if it really doesn't fit.
+pub fn ensure_free_space(copies: impl IntoIterator<Item = (PathBuf, u64)>) -> Result<()> {
+    // (device, folder checked, bytes)
+    let mut filesystems: Vec<(u64, PathBuf, u64)> = Vec::new();
+    for (destination, bytes) in copies {
+        let Some((existing, metadata)) = destination
+            .ancestors()
+            .find_map(|ancestor| fs::metadata(ancestor).ok().map(|metadata| (ancestor, metadata)))
+        else {
+            continue;
+        };
+        match filesystems.iter_mut().find(|(device, _, _)| *device == metadata.dev()) {
+            Some((_, _, needed)) => *needed += bytes,
+            None => filesystems.push((metadata.dev(), existing.to_path_buf(), bytes)),
+        }
+    }
+
+    for (_, path, needed) in filesystems {
+        if needed == 0 {
+            continue;
+        }
+        match available_space(&path) {
+            Ok(available) if available < needed.saturating_add(SPACE_MARGIN) => {
+                eprintln!("Refusing to copy {} bytes to {}, only {} are free", needed, path.display(), available);
+                return Err(NotEnoughSpace { path, needed, available }.into());
+            }
+            Ok(_) => {}
+            Err(e) => eprintln!("{:#}, copying anyway", e),
+        }
+    }
+    Ok(())
+}
+
+// What kind of drive a folder is on, to tell Steam libraries apart
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DriveKind {
+    Ssd,
+    Hdd,
+    Removable, // SD card or USB drive
+    Unknown, // Network shares, or a device sysfs doesn't describe (e.g. btrfs subvolumes)
+}
+
+// Reads the drive behind `path` from sysfs
+pub fn drive_kind(path: &Path) -> DriveKind {
+    match fs::metadata(path) {
+        Ok(metadata) => drive_kind_in(Path::new("/sys"), metadata.dev()),
+        Err(_) => DriveKind::Unknown,
+    }
+}
+
+// /sys/dev/block/<major>:<minor> is the partition, the disk's `queue` and
+// `removable` are one folder up for partitions and right there for whole disks
+fn drive_kind_in(sys: &Path, dev: u64) -> DriveKind {
+    let block = sys.join(format!("dev/block/{}:{}", libc::major(dev), libc::minor(dev)));
+    let read_flag = |name: &str| {
+        [block.join(name), block.join("..").join(name)]
+            .iter()
+            .find_map(|path| fs::read_to_string(path).ok())
+            .map(|value| value.trim() == "1")
+    };
+    if read_flag("removable") == Some(true) {
+        return DriveKind::Removable;
+    }
+    match read_flag("queue/rotational") {
+        Some(true) => DriveKind::Hdd,
+        Some(false) => DriveKind::Ssd,
+        None => DriveKind::Unknown,
+    }
+}
+
+// Game names can contain characters that aren't allowed in file names elsewhere
+pub fn sanitize_file_name(name: &str) -> String {
+    name.chars()
+        .map(|c| if matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|') { '_' } else { c })
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+// A non-empty relative path without `..`, `.` or a root, so joining it can't escape the base
+pub fn is_plain_relative(path: &Path) -> bool {
+    path.components().next().is_some() && path.components().all(|c| matches!(c, Component::Normal(_)))
+}
+
+// The path as it's spelled on disk when it only differs from `path` in upper
+// and lower case. Windows doesn't care about case, so games and the manifest
+// don't either: "AppData/Roaming/Game" may exist as "AppData/Roaming/game".
+pub fn find_case_insensitive(path: &Path) -> Option<PathBuf> {
+    let mut found = PathBuf::new();
+    for component in path.components() {
+        let Component::Normal(name) = component else {
+            found.push(component);
+            continue;
+        };
+        let exact = found.join(name);
+        if fs::symlink_metadata(&exact).is_ok() {
+            found = exact;
+            continue;
+        }
+        let wanted = name.to_string_lossy().to_lowercase();
+        let entry = fs::read_dir(&found)
+            .ok()?
+            .flatten()
+            .find(|entry| entry.file_name().to_string_lossy().to_lowercase() == wanted)?;
+        found.push(entry.file_name());
+    }
+    Some(found)
+}
+
+// Run `f` over `items` on up to `threads` threads, results in the order of
+// `items`. With one thread (the setting for HDDs and SD cards, see
+// `Config::io_limits`) everything runs one after the other on the caller.
+pub fn parallel_map<T: Send, R: Send>(items: Vec<T>, threads: usize, f: impl Fn(T) -> R + Sync) -> Vec<R> {
+    let threads = threads.clamp(1, items.len().max(1));
+    if threads == 1 {
+        return items.into_iter().map(f).collect();
+    }
+    let queue = Mutex::new(items.into_iter().enumerate());
+    let results = Mutex::new(Vec::new());
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| loop {
+                // Holding the lock only to take the next item
+                let next = queue.lock().unwrap_or_else(|e| e.into_inner()).next();
+                let Some((index, item)) = next else {
+                    break;
+                };
+                let result = f(item);
+                results.lock().unwrap_or_else(|e| e.into_inner()).push((index, result));
+            });
+        }
+    });
+    let mut results = results.into_inner().unwrap_or_else(|e| e.into_inner());
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{write_file, Fixture};
+
+    #[test]
+    fn refuses_copies_that_dont_fit() {
+        let fixture = Fixture::new();
+        let save = fixture.root.join("save/slot.sav");
+        write_file(&save, "save");
+        let destination = fixture.root.join("not/created/yet");
+        assert!(ensure_free_space([(destination.clone(), copy_growth(&save, &destination))]).is_ok());
+
+        // Copies to one filesystem count together
+        let half = u64::MAX / 2;
+        let error = ensure_free_space([(destination.clone(), half), (fixture.root.clone(), half)])
+            .context("Failed to back up")
+            .unwrap_err();
+        let space = error.downcast_ref::<NotEnoughSpace>().unwrap();
+        assert_eq!(space.path, fixture.root);
+        assert_eq!(space.needed, half * 2);
+    }
+
+    #[test]
+    fn lists_folders_first() {
+        let fixture = Fixture::new();
+        let saves = fixture.root.join("saves");
+        write_file(&saves.join("b.sav"), "save");
+        write_file(&saves.join("Slots/a.sav"), "slot");
+        write_file(&saves.join("A.cfg"), "config");
+        let names: Vec<String> = list_folder(&saves).into_iter().map(|entry| entry.name).collect();
+        assert_eq!(names, ["Slots", "A.cfg", "b.sav"]);
+        assert_eq!(list_folder(&saves)[0].size, 4);
+        assert!(list_folder(&saves.join("missing")).is_empty());
+    }
+
+    #[test]
+    fn parallel_map_keeps_the_order() {
+        let items: Vec<u64> = (0..50).collect();
+        let doubled = parallel_map(items.clone(), 4, |n| n * 2);
+        assert_eq!(doubled, items.iter().map(|n| n * 2).collect::<Vec<_>>());
+        assert_eq!(parallel_map(Vec::<u64>::new(), 4, |n| n), Vec::<u64>::new());
+    }
+
+    #[test]
+    fn reads_drive_kinds_from_sysfs() {
+        let fixture = Fixture::new();
+        let sys = fixture.root.join("sys");
+        // A partition of a spinning disk, a whole SSD and an SD card reader
+        write_file(&sys.join("block/sda/queue/rotational"), "1\n");
+        write_file(&sys.join("block/sda/removable"), "0\n");
+        fs::create_dir_all(sys.join("block/sda/sda1")).unwrap();
+        write_file(&sys.join("block/nvme0n1/queue/rotational"), "0\n");
+        write_file(&sys.join("block/mmcblk0/removable"), "1\n");
+        fs::create_dir_all(sys.join("dev/block")).unwrap();
+        symlink("../../block/sda/sda1", sys.join("dev/block/8:1")).unwrap();
+        symlink("../../block/nvme0n1", sys.join("dev/block/259:0")).unwrap();
+        symlink("../../block/mmcblk0", sys.join("dev/block/179:0")).unwrap();
+
+        assert_eq!(drive_kind_in(&sys, libc::makedev(8, 1)), DriveKind::Hdd);
+        assert_eq!(drive_kind_in(&sys, libc::makedev(259, 0)), DriveKind::Ssd);
+        assert_eq!(drive_kind_in(&sys, libc::makedev(179, 0)), DriveKind::Removable);
+        assert_eq!(drive_kind_in(&sys, libc::makedev(0, 42)), DriveKind::Unknown);
+    }
+}
@@ -0,0 +1,87 @@
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+
+// Gettext domain all UI strings are looked up in
+pub const GETTEXT_PACKAGE: &str = "proton-game-saves";
+
+// Provided by libc (glibc ships libintl)
+extern "C" {
+    fn bindtextdomain(domainname: *const c_char, dirname: *const c_char) -> *mut c_char;
+    fn bind_textdomain_codeset(domainname: *const c_char, codeset: *const c_char) -> *mut c_char;
+    fn dgettext(domainname: *const c_char, msgid: *const c_char) -> *mut c_char;
+}
+
+// Point gettext at the compiled translation catalogs. GTK has already called
+// setlocale() during init, so this only needs to bind our domain. Programs
+// without GTK have to call setlocale() themselves to get translations.
+pub fn init() {
+    let locale_dir = find_locale_dir();
+
+    let (Ok(domain), Ok(dir), Ok(codeset)) = (
+        CString::new(GETTEXT_PACKAGE),
+        CString::new(locale_dir.as_os_str().as_bytes()),
+        CString::new("UTF-8"),
+    ) else {
+        eprintln!("Invalid locale directory: {}", locale_dir.display());
+        return;
+    };
+
+    unsafe {
+        bindtextdomain(domain.as_ptr(), dir.as_ptr());
+        bind_textdomain_codeset(domain.as_ptr(), codeset.as_ptr());
+    }
+
+    println!("Loading translations from: {}", locale_dir.display());
+}
+
+fn find_locale_dir() -> PathBuf {
+    // Try sources in order:
+    // 1) Explicit override (useful for translators testing a catalog)
+    // 2) Development path (`make locale`)
+    // 3) Flatpak install path
+    // 4) System locale dir
+    if let Some(dir) = std::env::var_os("PROTON_GAME_SAVES_LOCALEDIR") {
+        return PathBuf::from(dir);
+    }
+
+    // The workspace's target folder, one up from this crate
+    let dev_path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../target/locale");
+    let flatpak_path = Path::new("/app/share/locale");
+
+    if dev_path.exists() {
+        dev_path
+    } else if flatpak_path.exists() {
+        flatpak_path.to_path_buf()
+    } else {
+        PathBuf::from("/usr/share/locale")
+    }
+}
+
+// Translate a UI string
+pub fn gettext(msgid: &str) -> String {
+    let (Ok(domain), Ok(c_msgid)) = (CString::new(GETTEXT_PACKAGE), CString::new(msgid)) else {
+        return msgid.to_string();
+    };
+    // Points into the catalog, or at `c_msgid` when there's no translation,
+    // so it's copied before `c_msgid` goes away
+    unsafe { CStr::from_ptr(dgettext(domain.as_ptr(), c_msgid.as_ptr())).to_string_lossy().into_owned() }
+}
+
+// Translate a string containing `{}` placeholders, filled in order from `args`.
+// Translators may move the placeholders but must keep the same number of them.
+pub fn gettext_f(msgid: &str, args: &[&str]) -> String {
+    fill_placeholders(&gettext(msgid), args)
+}
+
+fn fill_placeholders(template: &str, args: &[&str]) -> String {
+    let mut parts = template.split("{}");
+    let mut result = parts.next().unwrap_or_default().to_string();
+    let mut args = args.iter();
+    for part in parts {
+        result.push_str(args.next().copied().unwrap_or_default());
+        result.push_str(part);
+    }
+    result
+}
@@ -0,0 +1,146 @@
+use anyhow::{bail, Result};
+use std::fs;
+use std::path::{Component, Path};
+
+// Glob patterns for junk that games write next to their saves, left out of
+// sizes, safety snapshots and bundles. They're matched against paths inside a
+// save location:
+//
+//   *.log             <- no slash: any file or folder with a matching name, at any depth
+//   CrashDumps/**     <- with a slash: from the top of the save location
+//   **/Cache          <- ** stands for any number of folders, none included
+//
+// * and ? don't match across a slash. Set globally and per game, see
+// `Config::ignore_patterns_for`.
+pub const DEFAULT_IGNORE_PATTERNS: &[&str] = &["*.log", "*.dmp", "CrashDumps/**"];
+
+#[derive(Clone)]
+pub struct IgnorePatterns {
+    // Each pattern split at its slashes
+    patterns: Vec<Vec<String>>,
+}
+
+impl IgnorePatterns {
+    pub fn new<S: AsRef<str>>(patterns: &[S]) -> Self {
+        let patterns = patterns
+            .iter()
+            .map(|pattern| pattern.as_ref().trim().trim_matches('/'))
+            .filter(|pattern| !pattern.is_empty())
+            .map(|pattern| {
+                let parts: Vec<String> = pattern.split('/').filter(|part| !part.is_empty()).map(str::to_string).collect();
+                // A plain name matches at any depth
+                if parts.len() == 1 && parts[0] != "**" {
+                    vec!["**".to_string(), parts[0].clone()]
+                } else {
+                    parts
+                }
+            })
+            .collect();
+        Self { patterns }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    // Whether `relative` (a path inside a save location) is left out
+    pub fn matches(&self, relative: &Path) -> bool {
+        let parts: Vec<String> = relative
+            .components()
+            .filter_map(|component| match component {
+                Component::Normal(name) => Some(name.to_string_lossy().to_string()),
+                _ => None,
+            })
+            .collect();
+        !parts.is_empty() && self.patterns.iter().any(|pattern| matches_parts(pattern, &parts))
+    }
+
+    // Whether `path` inside the save location at `root` is left out
+    pub fn skips(&self, root: &Path, path: &Path) -> bool {
+        path.strip_prefix(root).is_ok_and(|relative| self.matches(relative))
+    }
+
+    // Size of the save location at `root` without what's left out. Like
+    // `fsutil::disk_usage`, but a symlinked location is measured by its files.
+    pub fn disk_usage(&self, root: &Path) -> u64 {
+        self.usage_below(root, root)
+    }
+
+    fn usage_below(&self, root: &Path, path: &Path) -> u64 {
+        let metadata = if path == root { fs::metadata(path) } else { fs::symlink_metadata(path) };
+        let Ok(metadata) = metadata else {
+            return 0;
+        };
+        if !metadata.is_dir() {
+            return metadata.len();
+        }
+        fs::read_dir(path)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|entry| entry.path())
+                    .filter(|entry| !self.skips(root, entry))
+                    .map(|entry| self.usage_below(root, &entry))
+                    .sum()
+            })
+            .unwrap_or(0)
+    }
+}
+
+// Patterns are relative and stay inside the save location
+pub fn validate(pattern: &str) -> Result<()> {
+    if pattern.starts_with('/') || pattern.split('/').any(|part| part == "..") {
+        bail!("Ignore patterns are relative to the save folder: {}", pattern);
+    }
+    Ok(())
+}
+
+fn matches_parts(pattern: &[String], parts: &[String]) -> bool {
+    match pattern.split_first() {
+        None => parts.is_empty(),
+        Some((first, rest)) if first == "**" => (0..=parts.len()).any(|skipped| matches_parts(rest, &parts[skipped..])),
+        Some((first, rest)) => parts
+            .split_first()
+            .is_some_and(|(part, parts)| {
+                let (first, part): (Vec<char>, Vec<char>) = (first.chars().collect(), part.chars().collect());
+                matches_name(&first, &part) && matches_parts(rest, parts)
+            }),
+    }
+}
+
+// One path component against one part of a pattern, with * and ?
+fn matches_name(pattern: &[char], name: &[char]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some(('*', rest)) => (0..=name.len()).any(|skipped| matches_name(rest, &name[skipped..])),
+        Some(('?', rest)) => !name.is_empty() && matches_name(rest, &name[1..]),
+        Some((c, rest)) => name.first() == Some(c) && matches_name(rest, &name[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{write_file, Fixture};
+
+    #[test]
+    fn leaves_out_matching_files() {
+        let patterns = IgnorePatterns::new(&["*.log", "CrashDumps/**", "Saved/Cache?"]);
+        assert!(patterns.matches(Path::new("game.log")));
+        assert!(patterns.matches(Path::new("Logs/old/game.log")));
+        assert!(patterns.matches(Path::new("CrashDumps")));
+        assert!(patterns.matches(Path::new("CrashDumps/2024/dump.dmp")));
+        assert!(patterns.matches(Path::new("Saved/Cache1")));
+        assert!(!patterns.matches(Path::new("Saved/Cache")));
+        assert!(!patterns.matches(Path::new("Profiles/CrashDumps")));
+        assert!(!patterns.matches(Path::new("save.log.bak")));
+        assert!(validate("../outside").is_err());
+
+        let fixture = Fixture::new();
+        let root = fixture.root.join("saves");
+        write_file(&root.join("slot1.sav"), "12345");
+        write_file(&root.join("output.log"), "a long log file");
+        write_file(&root.join("CrashDumps/crash.dmp"), "dump");
+        assert_eq!(patterns.disk_usage(&root), 5);
+    }
+}
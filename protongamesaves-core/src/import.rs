@@ -0,0 +1,121 @@
+use crate::activity::{self, ActivityEntry, ActivityKind};
+use crate::config::Config;
+use crate::fsutil;
+use crate::snapshot::Snapshot;
+use anyhow::{bail, Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Importing saves dropped onto a game: a folder is used as it is, archives are
+// unpacked with the system's tar/unzip into a temporary folder first
+const TAR_EXTENSIONS: [&str; 6] = [".tar", ".tar.gz", ".tgz", ".tar.xz", ".tar.zst", ".tar.bz2"];
+const ZIP_EXTENSION: &str = ".zip";
+
+// Folder holding the saves to import, removes its temporary copy when dropped
+pub struct ImportSource {
+    pub root: PathBuf,
+    pub name: String, // Name of what was dropped, for the UI
+    temp_dir: Option<PathBuf>,
+}
+
+impl Drop for ImportSource {
+    fn drop(&mut self) {
+        if let Some(temp_dir) = &self.temp_dir {
+            let _ = fs::remove_dir_all(temp_dir);
+        }
+    }
+}
+
+pub fn is_archive(path: &Path) -> bool {
+    let name = path.file_name().map(|n| n.to_string_lossy().to_lowercase()).unwrap_or_default();
+    name.ends_with(ZIP_EXTENSION) || TAR_EXTENSIONS.iter().any(|ext| name.ends_with(ext))
+}
+
+// Turn a dropped folder or archive into a folder to copy from
+pub fn prepare_source(path: &Path) -> Result<ImportSource> {
+    let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    if path.is_dir() {
+        return Ok(ImportSource { root: path.to_path_buf(), name, temp_dir: None });
+    }
+    if !is_archive(path) {
+        bail!("{} is neither a folder nor a zip or tar archive", name);
+    }
+
+    let stamp = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis()).unwrap_or_default();
+    let temp_dir = std::env::temp_dir().join(format!("proton_game_saves_import_{}", stamp));
+    fs::create_dir_all(&temp_dir).context(format!("Failed to create {}", temp_dir.display()))?;
+    // Owns the temp dir from here on, so every error below cleans it up
+    let mut source = ImportSource { root: temp_dir.clone(), name, temp_dir: Some(temp_dir.clone()) };
+
+    let lower = source.name.to_lowercase();
+    let (program, status) = if lower.ends_with(ZIP_EXTENSION) {
+        ("unzip", Command::new("unzip").arg("-q").arg(path).arg("-d").arg(&temp_dir).status())
+    } else {
+        ("tar", Command::new("tar").arg("-xf").arg(path).arg("-C").arg(&temp_dir).status())
+    };
+    match status {
+        Ok(status) if status.success() => {}
+        Ok(status) => bail!("{} failed to unpack {} ({})", program, source.name, status),
+        Err(e) => bail!("Couldn't run {} to unpack the archive: {}", program, e),
+    }
+
+    // Archives usually wrap everything in a single top level folder
+    let entries: Vec<PathBuf> = fs::read_dir(&temp_dir)?.flatten().map(|e| e.path()).collect();
+    if let [only] = entries.as_slice() {
+        if only.is_dir() {
+            source.root = only.clone();
+        }
+    }
+    println!("Unpacked {} to {}", path.display(), source.root.display());
+    Ok(source)
+}
+
+// Index of the save location the dropped folder belongs in, if its name or its
+// top level entries line up with one of them
+pub fn match_location(source: &ImportSource, locations: &[PathBuf]) -> Option<usize> {
+    let root_name = source.root.file_name()?.to_string_lossy().to_lowercase();
+    if let Some(index) = locations.iter().position(|location| {
+        location.file_name().map(|n| n.to_string_lossy().to_lowercase()) == Some(root_name.clone())
+    }) {
+        return Some(index);
+    }
+
+    // Otherwise the location whose current files share the most names with the drop
+    let dropped = entry_names(&source.root);
+    locations
+        .iter()
+        .enumerate()
+        .map(|(index, location)| (index, entry_names(location).iter().filter(|n| dropped.contains(n)).count()))
+        .filter(|(_, shared)| *shared > 0)
+        .max_by_key(|(_, shared)| *shared)
+        .map(|(index, _)| index)
+}
+
+fn entry_names(dir: &Path) -> Vec<String> {
+    fs::read_dir(dir)
+        .map(|entries| entries.flatten().map(|e| e.file_name().to_string_lossy().to_lowercase()).collect())
+        .unwrap_or_default()
+}
+
+// Copy the contents of `source` into `target`, after a safety snapshot of it
+pub fn import_into(config: &Config, app_id: &str, source: &ImportSource, target: &Path) -> Result<()> {
+    fsutil::ensure_free_space([(target.to_path_buf(), fsutil::copy_growth(&source.root, target))])?;
+    Snapshot::create(config, app_id, "Before importing dropped saves", &[target.to_path_buf()])
+        .context("Failed to snapshot the current saves, not importing")?;
+    fs::create_dir_all(target).context(format!("Failed to create {}", target.display()))?;
+    for entry in fs::read_dir(&source.root)? {
+        let entry = entry?;
+        fsutil::copy_recursive(&entry.path(), &target.join(entry.file_name()))?;
+    }
+    println!("Imported {} into {}", source.name, target.display());
+    activity::record(
+        config,
+        ActivityEntry::new(ActivityKind::Import, format!("Imported {}", source.name))
+            .game(config, app_id)
+            .paths([target.to_path_buf()])
+            .bytes(fsutil::disk_usage(target)),
+    );
+    Ok(())
+}
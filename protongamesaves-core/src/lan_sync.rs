@@ -0,0 +1,704 @@
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, UdpSocket};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::bundle::{self, ExportGame};
+use crate::config::{DetachedConfig, PrivacySettings};
+use crate::fsutil;
+use crate::net::{self, NetworkFeature};
+
+// Two running copies of the app syncing saves directly, e.g. a desktop and a
+// Steam Deck on the same network:
+//
+// - The machine being synced with shares itself from the LAN sync dialog. It
+//   answers UDP broadcasts on DISCOVERY_PORT with its name and the TCP port of
+//   its server, and shows a pairing code.
+// - The other machine finds it, and every request it sends carries that code.
+//   Requests are one JSON line. Files follow as a JSON header line each, all
+//   headers first and then the bytes, so the receiving side knows what's
+//   coming before it writes anything.
+// - Saves travel as bundles (see `bundle`), so whatever is replaced gets a
+//   safety snapshot on the receiving side like any bundle import. Bundles
+//   pushed to the sharing machine are only taken once its user accepts them.
+//
+// Nothing is encrypted, the pairing code only keeps other machines on the
+// network out. Sharing stops after MAX_WRONG_CODES wrong ones, so it can't be
+// guessed by trying them all. Meant for a home network, not the internet.
+pub const DISCOVERY_PORT: u16 = 47630;
+const DISCOVERY_MAGIC: &str = "proton-game-saves-discover";
+const PROTOCOL_VERSION: u32 = 2;
+// Long enough for a slow Wi-Fi client to answer
+const DISCOVERY_TIMEOUT: Duration = Duration::from_secs(2);
+// A peer that stops sending in the middle of a file
+const IO_TIMEOUT: Duration = Duration::from_secs(60);
+// Wrong pairing codes before sharing stops, across all connections
+const MAX_WRONG_CODES: usize = 5;
+// Longest message line, the game list of a big library fits many times over
+const MAX_LINE_BYTES: u64 = 1 << 20;
+// Most files and bytes taken in one transfer, far more than the saves of a
+// big library
+const MAX_TREE_FILES: usize = 100_000;
+const MAX_TREE_BYTES: u64 = 64 << 30;
+// How long the sharing machine's user has to accept a push. Shorter than
+// IO_TIMEOUT, so the pushing side is still waiting for the answer.
+const CONFIRM_TIMEOUT: Duration = Duration::from_secs(45);
+// File times differing by less than this count as the same save, some file
+// systems (FAT on SD cards) only keep two-second precision
+const SAME_TIME_SECS: u64 = 2;
+
+// Another machine sharing its saves
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Peer {
+    pub name: String,
+    pub address: SocketAddr,
+}
+
+// A game with saves on one side, for comparing the two machines
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SyncGame {
+    pub app_id: String,
+    pub name: String,
+    pub size: u64,
+    pub modified: Option<u64>, // Newest save file, Unix seconds
+}
+
+// Which way a game's saves should be copied
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SyncDirection {
+    Send,    // Newer here
+    Receive, // Newer on the other machine
+    InSync,
+}
+
+// What the server did, shown in the sharing machine's dialog
+pub enum ServerEvent {
+    Sent { peer: String, games: usize },
+    Received { peer: String, games: usize },
+    Refused { peer: String }, // Wrong pairing code
+    Failed { peer: String, error: String },
+    Stopped,                  // Too many wrong pairing codes, sharing is off
+    // The peer wants to send saves, `answer` takes whether to accept them.
+    // Declined if nothing is answered within CONFIRM_TIMEOUT.
+    PushOffered { peer: String, folders: Vec<String>, bytes: u64, answer: mpsc::Sender<bool> },
+}
+
+#[derive(Serialize, Deserialize)]
+struct Request {
+    version: u32,
+    code: String,
+    #[serde(flatten)]
+    operation: Operation,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+enum Operation {
+    List,
+    Fetch { app_ids: Vec<String> },
+    Push { files: usize },
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "result", rename_all = "lowercase")]
+enum Response {
+    Games { games: Vec<SyncGame> },
+    Files { files: usize },
+    Ready, // Accepted, send the file contents
+    Imported { games: usize, missing: Vec<String> },
+    Error { message: String },
+}
+
+#[derive(Serialize, Deserialize)]
+struct FileHeader {
+    path: String, // Relative, '/' separated
+    size: u64,
+    modified: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Announcement {
+    name: String,
+    port: u16,
+}
+
+// Name other machines see, the host name when there is one
+pub fn machine_name() -> String {
+    fs::read_to_string("/etc/hostname")
+        .ok()
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .or_else(|| std::env::var("HOSTNAME").ok())
+        .unwrap_or_else(|| "Proton Game Saves".to_string())
+}
+
+// Six digits, typed on the other machine to pair. From the kernel's random
+// source, a code derived from the time could be worked out by others.
+pub fn pairing_code() -> Result<String> {
+    let mut bytes = [0u8; 8];
+    fs::File::open("/dev/urandom")
+        .and_then(|mut random| random.read_exact(&mut bytes))
+        .context("Failed to generate a pairing code")?;
+    // The modulo bias over 64 bits is far too small to matter
+    Ok(format!("{:06}", u64::from_le_bytes(bytes) % 1_000_000))
+}
+
+// Newest file in the game's save locations, folders don't count as their time
+// changes whenever a file is added
+fn newest_file_time(path: &Path) -> Option<u64> {
+    let metadata = fs::symlink_metadata(path).ok()?;
+    if !metadata.is_dir() {
+        return metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs());
+    }
+    fs::read_dir(path).ok()?.flatten().filter_map(|entry| newest_file_time(&entry.path())).max()
+}
+
+pub fn describe_games(games: &[ExportGame]) -> Vec<SyncGame> {
+    games
+        .iter()
+        .map(|game| SyncGame {
+            app_id: game.app_id.clone(),
+            name: game.name.clone(),
+            size: game.locations.iter().map(|location| location.size_bytes).sum(),
+            modified: game.locations.iter().filter_map(|location| newest_file_time(&location.path)).max(),
+        })
+        .collect()
+}
+
+pub fn direction(local: &SyncGame, remote: &SyncGame) -> SyncDirection {
+    match (local.modified, remote.modified) {
+        (Some(local), Some(remote)) if local.abs_diff(remote) < SAME_TIME_SECS => SyncDirection::InSync,
+        (Some(local), Some(remote)) if local > remote => SyncDirection::Send,
+        (Some(_), Some(_)) | (None, Some(_)) => SyncDirection::Receive,
+        (Some(_), None) => SyncDirection::Send,
+        (None, None) => SyncDirection::InSync,
+    }
+}
+
+// --- Sharing this machine ---
+
+// Stops the server (and the discovery responder) when dropped
+pub struct ServerHandle {
+    pub port: u16,
+    pub discoverable: bool, // False if the discovery port was taken, e.g. by another copy
+    stop: Arc<AtomicBool>,
+}
+
+impl Drop for ServerHandle {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+// What every connection of a server shares
+struct Server {
+    config: DetachedConfig,
+    games: Vec<ExportGame>,
+    code: String,
+    events: mpsc::Sender<ServerEvent>,
+    wrong_codes: AtomicUsize,
+    stop: Arc<AtomicBool>,
+}
+
+// Start sharing `games` with machines that know `code`. The app's config
+// can't leave the main thread, bundles are made and imported with `config`
+// detached from it.
+pub fn start_server(config: DetachedConfig, games: Vec<ExportGame>, code: String, events: mpsc::Sender<ServerEvent>) -> Result<ServerHandle> {
+    net::check_allowed(&config.privacy(), NetworkFeature::LanSync, "the local network")?;
+    let listener = TcpListener::bind(("0.0.0.0", 0)).context("Failed to start the LAN sync server")?;
+    listener.set_nonblocking(true)?;
+    let port = listener.local_addr()?.port();
+    let stop = Arc::new(AtomicBool::new(false));
+
+    let discoverable = match UdpSocket::bind(("0.0.0.0", DISCOVERY_PORT)) {
+        Ok(socket) => {
+            let stop = stop.clone();
+            thread::spawn(move || answer_discovery(socket, port, stop));
+            true
+        }
+        Err(e) => {
+            eprintln!("LAN sync discovery unavailable, port {} is taken: {}", DISCOVERY_PORT, e);
+            false
+        }
+    };
+
+    let server = Arc::new(Server { config, games, code, events, wrong_codes: AtomicUsize::new(0), stop: stop.clone() });
+    thread::spawn(move || {
+        while !server.stop.load(Ordering::Relaxed) {
+            match listener.accept() {
+                // Each on its own thread, a peer that goes quiet can't hold up the others
+                Ok((stream, address)) => {
+                    let server = server.clone();
+                    thread::spawn(move || {
+                        let peer = address.ip().to_string();
+                        if let Err(e) = handle_connection(stream, &server, &peer) {
+                            eprintln!("LAN sync with {} failed: {:#}", peer, e);
+                            let _ = server.events.send(ServerEvent::Failed { peer, error: format!("{:#}", e) });
+                        }
+                    });
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => thread::sleep(Duration::from_millis(100)),
+                Err(e) => {
+                    eprintln!("LAN sync server stopped: {}", e);
+                    break;
+                }
+            }
+        }
+        println!("LAN sync server on port {} stopped", port);
+    });
+    println!("LAN sync server listening on port {}", port);
+    Ok(ServerHandle { port, discoverable, stop })
+}
+
+fn answer_discovery(socket: UdpSocket, port: u16, stop: Arc<AtomicBool>) {
+    let _ = socket.set_read_timeout(Some(Duration::from_millis(200)));
+    let announcement = serde_json::to_vec(&Announcement { name: machine_name(), port }).unwrap_or_default();
+    let mut buffer = [0u8; 256];
+    while !stop.load(Ordering::Relaxed) {
+        let Ok((length, sender)) = socket.recv_from(&mut buffer) else {
+            continue; // Timed out, check whether to stop
+        };
+        if &buffer[..length] == DISCOVERY_MAGIC.as_bytes() {
+            let _ = socket.send_to(&announcement, sender);
+        }
+    }
+}
+
+fn handle_connection(stream: TcpStream, server: &Server, peer: &str) -> Result<()> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(IO_TIMEOUT))?;
+    let mut writer = stream.try_clone()?;
+    let mut reader = BufReader::new(stream);
+    let request: Request = read_json(&mut reader)?;
+    if request.version != PROTOCOL_VERSION {
+        return write_json(&mut writer, &Response::Error { message: format!("Unsupported protocol version {}, update both machines", request.version) });
+    }
+    // A connection accepted just before sharing stopped is refused as well
+    if server.stop.load(Ordering::Relaxed) {
+        return write_json(&mut writer, &Response::Error { message: "Sharing has stopped".to_string() });
+    }
+    if request.code != server.code {
+        let _ = server.events.send(ServerEvent::Refused { peer: peer.to_string() });
+        if server.wrong_codes.fetch_add(1, Ordering::Relaxed) + 1 >= MAX_WRONG_CODES {
+            println!("LAN sync stopped after {} wrong pairing codes", MAX_WRONG_CODES);
+            server.stop.store(true, Ordering::Relaxed);
+            let _ = server.events.send(ServerEvent::Stopped);
+        }
+        return write_json(&mut writer, &Response::Error { message: "Wrong pairing code".to_string() });
+    }
+
+    match request.operation {
+        Operation::List => write_json(&mut writer, &Response::Games { games: describe_games(&server.games) }),
+        Operation::Fetch { app_ids } => {
+            let selected: Vec<ExportGame> = server.games.iter().filter(|game| app_ids.contains(&game.app_id)).cloned().collect();
+            let temp_dir = temp_dir("send");
+            let result = bundle::export_bundle(&server.config.to_config(), &selected, &temp_dir, false).and_then(|bundle_dir| send_tree(&mut writer, &bundle_dir));
+            let _ = fs::remove_dir_all(&temp_dir);
+            result?;
+            let _ = server.events.send(ServerEvent::Sent { peer: peer.to_string(), games: selected.len() });
+            Ok(())
+        }
+        Operation::Push { files } => {
+            let headers = match read_headers(&mut reader, files) {
+                Ok(headers) => headers,
+                Err(e) => {
+                    let _ = write_json(&mut writer, &Response::Error { message: format!("{:#}", e) });
+                    return Err(e);
+                }
+            };
+            let (answer, answered) = mpsc::channel();
+            let _ = server.events.send(ServerEvent::PushOffered {
+                peer: peer.to_string(),
+                folders: top_folders(&headers),
+                bytes: headers.iter().map(|header| header.size).sum(),
+                answer,
+            });
+            // A closed dialog drops the sender, which declines as well
+            if !answered.recv_timeout(CONFIRM_TIMEOUT).unwrap_or(false) {
+                println!("LAN sync push from {} declined", peer);
+                return write_json(&mut writer, &Response::Error { message: "Declined on the other machine".to_string() });
+            }
+            write_json(&mut writer, &Response::Ready)?;
+
+            let temp_dir = temp_dir("receive");
+            let result = receive_contents(&mut reader, &headers, &temp_dir).and_then(|()| {
+                let manifest = bundle::read_bundle(&temp_dir)?;
+                bundle::import_bundle(&server.config.to_config(), &temp_dir, &manifest)
+            });
+            let _ = fs::remove_dir_all(&temp_dir);
+            let response = match result {
+                Ok(summary) => {
+                    let _ = server.events.send(ServerEvent::Received { peer: peer.to_string(), games: summary.restored_games });
+                    Response::Imported { games: summary.restored_games, missing: summary.missing_prefixes }
+                }
+                Err(e) => Response::Error { message: format!("{:#}", e) },
+            };
+            write_json(&mut writer, &response)
+        }
+    }
+}
+
+// --- Syncing with another machine ---
+
+// Machines sharing their saves on the local network right now
+pub fn discover(privacy: &PrivacySettings) -> Result<Vec<Peer>> {
+    net::check_allowed(privacy, NetworkFeature::LanSync, "the local network")?;
+    let socket = UdpSocket::bind(("0.0.0.0", 0)).context("Failed to open a socket for discovery")?;
+    socket.set_broadcast(true)?;
+    socket.set_read_timeout(Some(Duration::from_millis(200)))?;
+    socket
+        .send_to(DISCOVERY_MAGIC.as_bytes(), ("255.255.255.255", DISCOVERY_PORT))
+        .context("Failed to send the discovery broadcast")?;
+
+    let mut peers: Vec<Peer> = Vec::new();
+    let mut buffer = [0u8; 512];
+    let started = SystemTime::now();
+    while started.elapsed().unwrap_or_default() < DISCOVERY_TIMEOUT {
+        let Ok((length, sender)) = socket.recv_from(&mut buffer) else {
+            continue;
+        };
+        let Ok(announcement) = serde_json::from_slice::<Announcement>(&buffer[..length]) else {
+            continue;
+        };
+        let peer = Peer { name: announcement.name, address: SocketAddr::new(sender.ip(), announcement.port) };
+        if !peers.contains(&peer) {
+            peers.push(peer);
+        }
+    }
+    println!("LAN sync discovery found {} machines", peers.len());
+    Ok(peers)
+}
+
+fn connect(privacy: &PrivacySettings, peer: &SocketAddr, code: &str, operation: Operation) -> Result<(BufReader<TcpStream>, TcpStream)> {
+    net::check_allowed(privacy, NetworkFeature::LanSync, &peer.to_string())?;
+    let stream = TcpStream::connect_timeout(peer, Duration::from_secs(5)).context(format!("Failed to connect to {}", peer))?;
+    stream.set_read_timeout(Some(IO_TIMEOUT))?;
+    let mut writer = stream.try_clone()?;
+    write_json(&mut writer, &Request { version: PROTOCOL_VERSION, code: code.to_string(), operation })?;
+    Ok((BufReader::new(stream), writer))
+}
+
+fn refused(response: Response) -> anyhow::Error {
+    match response {
+        Response::Error { message } => anyhow!("The other machine refused: {}", message),
+        _ => anyhow!("Unexpected answer from the other machine"),
+    }
+}
+
+pub fn list_games(privacy: &PrivacySettings, peer: &SocketAddr, code: &str) -> Result<Vec<SyncGame>> {
+    let (mut reader, _) = connect(privacy, peer, code, Operation::List)?;
+    match read_json(&mut reader)? {
+        Response::Games { games } => Ok(games),
+        other => Err(refused(other)),
+    }
+}
+
+// Receive the saves of `app_ids` as a bundle inside `dest`, to import with
+// `bundle::import_bundle`. Returns the bundle folder.
+pub fn fetch(privacy: &PrivacySettings, peer: &SocketAddr, code: &str, app_ids: Vec<String>, dest: &Path) -> Result<PathBuf> {
+    let (mut reader, _) = connect(privacy, peer, code, Operation::Fetch { app_ids })?;
+    match read_json(&mut reader)? {
+        Response::Files { files } => {
+            receive_tree(&mut reader, files, dest)?;
+            Ok(dest.to_path_buf())
+        }
+        other => Err(refused(other)),
+    }
+}
+
+// Send a bundle made with `bundle::export_bundle`, the other machine imports
+// it. Returns how many games it imported and the ones it has no prefix for.
+pub fn push(privacy: &PrivacySettings, peer: &SocketAddr, code: &str, bundle_dir: &Path) -> Result<(usize, Vec<String>)> {
+    let headers = file_headers(bundle_dir)?;
+    let (mut reader, mut writer) = connect(privacy, peer, code, Operation::Push { files: headers.len() })?;
+    send_headers(&mut writer, &headers)?;
+    // Its user is asked first
+    match read_json(&mut reader)? {
+        Response::Ready => send_contents(&mut writer, bundle_dir, &headers)?,
+        other => return Err(refused(other)),
+    }
+    match read_json(&mut reader)? {
+        Response::Imported { games, missing } => Ok((games, missing)),
+        other => Err(refused(other)),
+    }
+}
+
+// --- Wire format ---
+
+fn write_json<T: Serialize>(writer: &mut impl Write, value: &T) -> Result<()> {
+    let mut line = serde_json::to_vec(value)?;
+    line.push(b'\n');
+    writer.write_all(&line)?;
+    writer.flush()?;
+    Ok(())
+}
+
+fn read_json<T: for<'de> Deserialize<'de>>(reader: &mut impl BufRead) -> Result<T> {
+    let mut line = String::new();
+    // Not an endless line filling up the memory
+    let length = reader.by_ref().take(MAX_LINE_BYTES).read_line(&mut line)?;
+    if length == 0 {
+        bail!("The other machine closed the connection");
+    }
+    if !line.ends_with('\n') && length as u64 == MAX_LINE_BYTES {
+        bail!("The other machine sent a message that's too long");
+    }
+    serde_json::from_str(&line).context("Unreadable message from the other machine")
+}
+
+// Every file below `root` as a relative path, symlinks skipped (bundles don't
+// need them and they could point anywhere on the receiving side)
+fn list_files(root: &Path) -> Result<Vec<PathBuf>> {
+    fn walk(root: &Path, relative: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+        for entry in fs::read_dir(root.join(relative))? {
+            let entry = entry?;
+            let path = relative.join(entry.file_name());
+            let file_type = entry.file_type()?;
+            if file_type.is_dir() {
+                walk(root, &path, files)?;
+            } else if file_type.is_file() {
+                files.push(path);
+            }
+        }
+        Ok(())
+    }
+    let mut files = Vec::new();
+    walk(root, Path::new(""), &mut files)?;
+    Ok(files)
+}
+
+// Headers of every file below `root`, with the sizes they have now
+fn file_headers(root: &Path) -> Result<Vec<FileHeader>> {
+    list_files(root)?
+        .into_iter()
+        .map(|relative| {
+            let path = root.join(&relative);
+            let metadata = fs::metadata(&path).context(format!("Failed to read {}", path.display()))?;
+            Ok(FileHeader {
+                path: relative.to_string_lossy().to_string(),
+                size: metadata.len(),
+                modified: metadata.modified().ok().and_then(|t| t.duration_since(UNIX_EPOCH).ok()).map(|d| d.as_secs()),
+            })
+        })
+        .collect()
+}
+
+fn send_tree(writer: &mut impl Write, root: &Path) -> Result<()> {
+    let headers = file_headers(root)?;
+    write_json(writer, &Response::Files { files: headers.len() })?;
+    send_headers(writer, &headers)?;
+    send_contents(writer, root, &headers)
+}
+
+fn send_headers(writer: &mut impl Write, headers: &[FileHeader]) -> Result<()> {
+    for header in headers {
+        write_json(writer, header)?;
+    }
+    Ok(())
+}
+
+fn send_contents(writer: &mut impl Write, root: &Path, headers: &[FileHeader]) -> Result<()> {
+    for header in headers {
+        let path = root.join(&header.path);
+        let mut file = fs::File::open(&path).context(format!("Failed to read {}", path.display()))?;
+        let copied = std::io::copy(&mut (&mut file).take(header.size), writer)?;
+        if copied != header.size {
+            bail!("{} changed while it was being sent", path.display());
+        }
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+fn receive_tree(reader: &mut impl BufRead, files: usize, dest: &Path) -> Result<()> {
+    let headers = read_headers(reader, files)?;
+    receive_contents(reader, &headers, dest)
+}
+
+// The headers of `files` files, refused if there are too many of them or
+// they'd write outside the destination
+fn read_headers(reader: &mut impl BufRead, files: usize) -> Result<Vec<FileHeader>> {
+    if files > MAX_TREE_FILES {
+        bail!("The other machine wants to send {} files, more than the {} allowed", files, MAX_TREE_FILES);
+    }
+    let mut headers: Vec<FileHeader> = Vec::with_capacity(files);
+    let mut total: u64 = 0;
+    for _ in 0..files {
+        let header: FileHeader = read_json(reader)?;
+        if !fsutil::is_plain_relative(Path::new(&header.path)) {
+            bail!("The other machine sent an invalid path {}", header.path);
+        }
+        total = total.saturating_add(header.size);
+        if total > MAX_TREE_BYTES {
+            bail!("The other machine wants to send more than the {} bytes allowed", MAX_TREE_BYTES);
+        }
+        headers.push(header);
+    }
+    Ok(headers)
+}
+
+fn receive_contents(reader: &mut impl BufRead, headers: &[FileHeader], dest: &Path) -> Result<()> {
+    fsutil::ensure_free_space([(dest.to_path_buf(), headers.iter().map(|header| header.size).sum())])?;
+    for header in headers {
+        let path = dest.join(&header.path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context(format!("Failed to create {}", parent.display()))?;
+        }
+        let mut file = fs::File::create(&path).context(format!("Failed to write {}", path.display()))?;
+        let copied = std::io::copy(&mut reader.by_ref().take(header.size), &mut file)?;
+        if copied != header.size {
+            bail!("The connection broke off while receiving {}", header.path);
+        }
+        if let Some(modified) = header.modified {
+            let _ = file.set_modified(UNIX_EPOCH + Duration::from_secs(modified));
+        }
+    }
+    Ok(())
+}
+
+// Folders at the top of a bundle, one per game ("1000 - Lonely Hero")
+fn top_folders(headers: &[FileHeader]) -> Vec<String> {
+    let mut folders: Vec<String> = headers
+        .iter()
+        .filter_map(|header| header.path.split_once('/').map(|(folder, _)| folder.to_string()))
+        .collect();
+    folders.sort();
+    folders.dedup();
+    folders
+}
+
+// A fresh folder for one transfer
+pub fn temp_dir(purpose: &str) -> PathBuf {
+    let stamp = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or_default();
+    std::env::temp_dir().join(format!("proton_game_saves_lan_{}_{}", purpose, stamp))
+}
+
+// Games both machines have, keyed by App ID, for the comparison list
+pub fn pair_games(local: Vec<SyncGame>, remote: Vec<SyncGame>) -> Vec<(SyncGame, SyncGame)> {
+    let mut remote: HashMap<String, SyncGame> = remote.into_iter().map(|game| (game.app_id.clone(), game)).collect();
+    local
+        .into_iter()
+        .filter_map(|game| remote.remove(&game.app_id).map(|other| (game, other)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::write_file;
+    use crate::test_support::Fixture;
+
+    fn game(app_id: &str, modified: Option<u64>) -> SyncGame {
+        SyncGame { app_id: app_id.to_string(), name: app_id.to_string(), size: 0, modified }
+    }
+
+    #[test]
+    fn newer_side_wins() {
+        assert_eq!(direction(&game("1", Some(100)), &game("1", Some(50))), SyncDirection::Send);
+        assert_eq!(direction(&game("1", Some(50)), &game("1", Some(100))), SyncDirection::Receive);
+        assert_eq!(direction(&game("1", Some(100)), &game("1", Some(101))), SyncDirection::InSync);
+        assert_eq!(direction(&game("1", None), &game("1", Some(1))), SyncDirection::Receive);
+        let paired = pair_games(vec![game("1", None), game("2", None)], vec![game("2", None), game("3", None)]);
+        assert_eq!(paired.len(), 1);
+        assert_eq!(paired[0].0.app_id, "2");
+    }
+
+    #[test]
+    fn file_trees_survive_the_wire_format() {
+        let fixture = Fixture::new();
+        let source = fixture.root.join("source");
+        write_file(&source.join("bundle.json"), "{}");
+        write_file(&source.join("1000 - Lonely Hero/0/slot1.sav"), "save data");
+
+        let mut wire = Vec::new();
+        send_tree(&mut wire, &source).unwrap();
+        let mut reader = BufReader::new(wire.as_slice());
+        let Response::Files { files } = read_json(&mut reader).unwrap() else {
+            panic!("Expected a file list");
+        };
+        let dest = fixture.root.join("dest");
+        receive_tree(&mut reader, files, &dest).unwrap();
+        assert_eq!(fs::read_to_string(dest.join("1000 - Lonely Hero/0/slot1.sav")).unwrap(), "save data");
+
+        // A path escaping the destination is refused
+        let mut wire = Vec::new();
+        write_json(&mut wire, &FileHeader { path: "../evil".to_string(), size: 0, modified: None }).unwrap();
+        assert!(receive_tree(&mut BufReader::new(wire.as_slice()), 1, &dest).is_err());
+
+        // And more than a transfer may hold
+        assert!(read_headers(&mut BufReader::new([].as_slice()), MAX_TREE_FILES + 1).is_err());
+        let mut wire = Vec::new();
+        write_json(&mut wire, &FileHeader { path: "big".to_string(), size: MAX_TREE_BYTES + 1, modified: None }).unwrap();
+        assert!(read_headers(&mut BufReader::new(wire.as_slice()), 1).is_err());
+
+        // So is a line that never ends
+        let endless = vec![b'x'; MAX_LINE_BYTES as usize + 1];
+        assert!(read_json::<FileHeader>(&mut BufReader::new(endless.as_slice())).is_err());
+    }
+
+    #[test]
+    fn stops_sharing_after_wrong_pairing_codes() {
+        let fixture = Fixture::new();
+        let mut config = fixture.config();
+        config.set_privacy(PrivacySettings { offline_mode: true, ..Default::default() }).unwrap();
+        assert!(start_server(config.detach(), Vec::new(), "123456".to_string(), mpsc::channel().0).is_err());
+
+        let privacy = PrivacySettings { lan_sync: true, ..Default::default() };
+        config.set_privacy(privacy).unwrap();
+        let (sender, events) = mpsc::channel();
+        let server = start_server(config.detach(), Vec::new(), "123456".to_string(), sender).unwrap();
+        let address = SocketAddr::from(([127, 0, 0, 1], server.port));
+        assert!(list_games(&privacy, &address, "123456").unwrap().is_empty());
+        for _ in 0..MAX_WRONG_CODES {
+            assert!(list_games(&privacy, &address, "000000").is_err());
+        }
+        let stopped = std::iter::from_fn(|| events.recv_timeout(Duration::from_secs(5)).ok()).any(|event| matches!(event, ServerEvent::Stopped));
+        assert!(stopped);
+        // The right code doesn't help anymore
+        assert!(list_games(&privacy, &address, "123456").is_err());
+        assert_eq!(pairing_code().unwrap().len(), 6);
+    }
+
+    #[test]
+    fn pushes_wait_for_the_sharing_side_to_accept() {
+        let fixture = Fixture::new();
+        let mut config = fixture.config();
+        let privacy = PrivacySettings { lan_sync: true, ..Default::default() };
+        config.set_privacy(privacy).unwrap();
+        let (sender, events) = mpsc::channel();
+        let server = start_server(config.detach(), Vec::new(), "123456".to_string(), sender).unwrap();
+        let address = SocketAddr::from(([127, 0, 0, 1], server.port));
+        let bundle_dir = fixture.root.join("bundle");
+        let manifest = bundle::BundleManifest { version: 1, created: 0, games: Vec::new() };
+        write_file(&bundle_dir.join("bundle.json"), &serde_json::to_string(&manifest).unwrap());
+        write_file(&bundle_dir.join("1000 - Lonely Hero/0/slot1.sav"), "save data");
+
+        for accept in [false, true] {
+            let dir = bundle_dir.clone();
+            let pushing = thread::spawn(move || push(&privacy, &address, "123456", &dir));
+            let Ok(ServerEvent::PushOffered { folders, bytes, answer, .. }) = events.recv_timeout(Duration::from_secs(5)) else {
+                panic!("Expected the push to be offered");
+            };
+            assert_eq!(folders, vec!["1000 - Lonely Hero".to_string()]);
+            assert_eq!(bytes, fs::metadata(bundle_dir.join("bundle.json")).unwrap().len() + 9);
+            answer.send(accept).unwrap();
+            let pushed = pushing.join().unwrap();
+            if accept {
+                assert_eq!(pushed.unwrap(), (0, Vec::new()));
+            } else {
+                assert!(pushed.is_err());
+            }
+        }
+    }
+}
@@ -0,0 +1,253 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::fsutil;
+use crate::manifest::{GameEntry, GameFileRule, ManifestData, Store};
+
+// A game launcher that's commonly installed inside a Proton prefix, e.g. when
+// Steam starts a Ubisoft or EA game through its own launcher. The manifest
+// writes such saves as `<root>/...` (the launcher's folder) or `<base>/...`
+// (the game's install folder below it) for rules limited to that store.
+pub struct Launcher {
+    pub name: &'static str,
+    pub stores: &'static [Store], // Manifest stores its rules are for
+    pub roots: &'static [&'static str], // Default install folders, relative to drive_c
+    pub games_dir: &'static str, // Below the root, where the launcher installs games
+    // Below the root, where saves are kept as `<storeUserId>/<game ID>`
+    pub save_ids_dir: Option<&'static str>,
+}
+
+pub const LAUNCHERS: &[Launcher] = &[
+    Launcher {
+        name: "Ubisoft Connect",
+        stores: &[Store::Uplay],
+        roots: &["Program Files (x86)/Ubisoft/Ubisoft Game Launcher", "Program Files/Ubisoft/Ubisoft Game Launcher"],
+        games_dir: "games",
+        save_ids_dir: Some("savegames"),
+    },
+    // Older manifest entries still call the EA app's games "origin"
+    Launcher {
+        name: "EA app",
+        stores: &[Store::Ea, Store::Origin],
+        roots: &["Program Files/EA Games"],
+        games_dir: "",
+        save_ids_dir: None,
+    },
+    Launcher {
+        name: "Origin",
+        stores: &[Store::Origin],
+        roots: &["Program Files (x86)/Origin Games", "Program Files/Origin Games"],
+        games_dir: "",
+        save_ids_dir: None,
+    },
+];
+
+// Placeholders only a launcher root can fill in
+const LAUNCHER_PLACEHOLDERS: [&str; 4] = ["<root>", "<base>", "<game>", "<storeUserId>"];
+
+// Folder of `launcher` in the prefix with drive_c at `drive_c`, in any case
+fn launcher_root(launcher: &Launcher, drive_c: &Path) -> Option<PathBuf> {
+    launcher.roots.iter().map(|root| drive_c.join(root)).find_map(|root| {
+        if root.is_dir() {
+            Some(root)
+        } else {
+            fsutil::find_case_insensitive(&root)
+        }
+    })
+}
+
+// The launcher for `store` installed in the prefix and its folder
+pub fn find_root(drive_c: &Path, store: &Store) -> Option<(&'static Launcher, PathBuf)> {
+    LAUNCHERS
+        .iter()
+        .filter(|launcher| launcher.stores.contains(store))
+        .find_map(|launcher| launcher_root(launcher, drive_c).map(|root| (launcher, root)))
+}
+
+// Names of the launchers installed in the prefix, for the prefix info
+pub fn installed(drive_c: &Path) -> Vec<&'static str> {
+    LAUNCHERS
+        .iter()
+        .filter(|launcher| launcher_root(launcher, drive_c).is_some())
+        .map(|launcher| launcher.name)
+        .collect()
+}
+
+fn rule_stores(rule: &GameFileRule) -> impl Iterator<Item = &Store> {
+    rule.when.iter().flatten().filter_map(|constraint| constraint.store.as_ref())
+}
+
+// Fills in the launcher placeholders of `manifest_path` for the prefix with
+// drive_c at `drive_c`, the other placeholders are left for
+// `manifest::resolve_manifest_path_in`. None if the path has none, the rule
+// isn't for a launcher store, or no such launcher is installed in the prefix.
+pub fn resolve_launcher_path(manifest_path: &str, rule: &GameFileRule, entry: &GameEntry, drive_c: &Path) -> Option<String> {
+    if !LAUNCHER_PLACEHOLDERS.iter().any(|placeholder| manifest_path.contains(placeholder)) {
+        return None;
+    }
+    let (launcher, root) = rule_stores(rule).find_map(|store| find_root(drive_c, store))?;
+    let games = root.join(launcher.games_dir);
+    let mut resolved = manifest_path.to_string();
+
+    if resolved.contains("<base>") || resolved.contains("<game>") {
+        // The manifest can list several install folder names, use the one
+        // that's there
+        let install_dirs: Vec<&String> = entry._install_dir.iter().flat_map(|dirs| dirs.keys()).collect();
+        let install_dir = install_dirs
+            .iter()
+            .find(|dir| games.join(dir).exists())
+            .or_else(|| install_dirs.first())?;
+        resolved = resolved
+            .replace("<base>", &games.join(install_dir).to_string_lossy())
+            .replace("<game>", install_dir);
+    }
+    resolved = resolved.replace("<root>", &root.to_string_lossy());
+    if resolved.contains("<storeUserId>") {
+        resolved = fill_store_user_id(&resolved)?;
+    }
+    Some(resolved)
+}
+
+// Launchers keep saves per account in a folder named after the account ID.
+// Picks the account that has the rest of the path, or the first one.
+fn fill_store_user_id(path: &str) -> Option<String> {
+    let (before, after) = path.split_once("<storeUserId>")?;
+    let mut accounts: Vec<String> = fs::read_dir(before)
+        .ok()?
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .collect();
+    accounts.sort();
+    // Globs can't be checked with exists(), only the part before them
+    let fixed_rest = after.split('*').next().unwrap_or_default();
+    let account = accounts
+        .iter()
+        .find(|account| Path::new(&format!("{}{}{}", before, account, fixed_rest)).exists())
+        .or_else(|| accounts.first())?;
+    Some(format!("{}{}{}", before, account, after).replace("<storeUserId>", account))
+}
+
+// Game IDs of the launcher save folders in the prefix, most recently saved first
+fn save_ids(drive_c: &Path) -> Vec<(&'static Launcher, String)> {
+    let mut found: Vec<(SystemTime, &'static Launcher, String)> = Vec::new();
+    for launcher in LAUNCHERS {
+        let Some(ids_dir) = launcher.save_ids_dir else {
+            continue;
+        };
+        let Some(root) = launcher_root(launcher, drive_c) else {
+            continue;
+        };
+        let Ok(accounts) = fs::read_dir(root.join(ids_dir)) else {
+            continue;
+        };
+        for account in accounts.flatten() {
+            let Ok(games) = fs::read_dir(account.path()) else {
+                continue;
+            };
+            for game in games.flatten() {
+                let modified = game.metadata().and_then(|meta| meta.modified()).unwrap_or(SystemTime::UNIX_EPOCH);
+                found.push((modified, launcher, game.file_name().to_string_lossy().to_string()));
+            }
+        }
+    }
+    found.sort_by_key(|(modified, _, _)| std::cmp::Reverse(*modified));
+    found.into_iter().map(|(_, launcher, id)| (launcher, id)).collect()
+}
+
+// The manifest game whose launcher save folder is in the prefix, found by the
+// store's game ID in the folder name (e.g. savegames/<account>/635). One
+// launcher prefix can hold several games, the one saved last wins.
+pub fn find_game_by_save_ids<'a>(manifest: &'a ManifestData, drive_c: &Path) -> Option<(String, &'a GameEntry)> {
+    for (launcher, id) in save_ids(drive_c) {
+        let Some(ids_dir) = launcher.save_ids_dir else {
+            continue;
+        };
+        let pattern = format!("<root>/{}/<storeUserId>/{}", ids_dir, id);
+        let mut matches: Vec<(&'a String, &'a GameEntry)> = manifest
+            .games
+            .iter()
+            .filter(|(_, entry)| {
+                entry.files.iter().flatten().any(|(path, rule)| {
+                    let for_launcher = rule_stores(rule).any(|store| launcher.stores.contains(store));
+                    let rest = path.strip_prefix(&pattern);
+                    for_launcher && rest.is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+                })
+            })
+            .collect();
+        matches.sort_by(|a, b| a.0.cmp(b.0));
+        if let Some((name, entry)) = matches.first() {
+            println!("Found {} save folder {} of '{}'", launcher.name, id, name);
+            return Some(((*name).clone(), *entry));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manifest::{self, ManifestInputs};
+    use crate::test_support::{write_file, Fixture};
+
+    const LAUNCHER_MANIFEST: &str = r#"
+"Launcher Hero":
+  files:
+    "<root>/savegames/<storeUserId>/635":
+      tags:
+        - save
+      when:
+        - store: uplay
+    "<root>/userdata/<storeUserId>/8000/remote":
+      when:
+        - store: steam
+  steam:
+    id: 8000
+"Installed Elsewhere":
+  files:
+    "<base>/Saves":
+      when:
+        - store: ea
+        - store: prime
+  installDir:
+    Elsewhere: {}
+"#;
+
+    #[test]
+    fn resolves_launcher_paths_and_finds_games_by_save_id() {
+        let fixture = Fixture::new();
+        let prefix = fixture.add_prefix("9000");
+        let drive_c = prefix.join("pfx/drive_c");
+        let ubisoft = drive_c.join("Program Files (x86)/Ubisoft/Ubisoft Game Launcher");
+        write_file(&ubisoft.join("savegames/0b1c-user/635/1.save"), "save");
+        write_file(&drive_c.join("Program Files/EA Games/Elsewhere/Saves/slot.sav"), "save");
+        fixture.write_manifest(LAUNCHER_MANIFEST);
+        // An unknown store in `when` doesn't cost the entry
+        let manifest = manifest::parse_manifest_inputs(&ManifestInputs::new(&fixture.config()), |_| {}).unwrap();
+        assert_eq!(manifest.games.len(), 2);
+
+        let (name, entry) = find_game_by_save_ids(&manifest, &drive_c).unwrap();
+        assert_eq!(name, "Launcher Hero");
+        let files = entry.files.as_ref().unwrap();
+        let rule = &files["<root>/savegames/<storeUserId>/635"];
+        assert_eq!(
+            manifest::resolve_manifest_rule_in("<root>/savegames/<storeUserId>/635", rule, entry, &prefix, "9000"),
+            Some(ubisoft.join("savegames/0b1c-user/635"))
+        );
+        // Steam's own root isn't inside the prefix
+        let steam_rule = &files["<root>/userdata/<storeUserId>/8000/remote"];
+        assert_eq!(
+            manifest::resolve_manifest_rule_in("<root>/userdata/<storeUserId>/8000/remote", steam_rule, entry, &prefix, "9000"),
+            None
+        );
+
+        let elsewhere = &manifest.games["Installed Elsewhere"];
+        let rule = &elsewhere.files.as_ref().unwrap()["<base>/Saves"];
+        assert_eq!(
+            manifest::resolve_manifest_rule_in("<base>/Saves", rule, elsewhere, &prefix, "9000"),
+            Some(drive_c.join("Program Files/EA Games/Elsewhere/Saves"))
+        );
+        assert_eq!(installed(&drive_c), vec!["Ubisoft Connect", "EA app"]);
+    }
+}
@@ -0,0 +1,45 @@
+// Everything Proton Game Saves does that doesn't need a window: finding
+// prefixes and their saves, matching them against the ludusavi manifest, the
+// configuration, and safety snapshots, bundles and other backups. The GTK app
+// is built on top of it, a command line tool or another program can use the
+// same APIs without pulling in GTK.
+
+pub mod activity;
+pub mod anonymize;
+pub mod backup_target;
+pub mod bundle;
+pub mod cleanup;
+pub mod cloud;
+pub mod compatdata;
+pub mod config;
+pub mod deep_link;
+pub mod demo;
+pub mod fsutil;
+pub mod i18n;
+pub mod ignore_patterns;
+pub mod import;
+pub mod lan_sync;
+pub mod launchers;
+pub mod manifest;
+pub mod net;
+pub mod prefix_backup;
+pub mod prefix_sizes;
+pub mod preview;
+pub mod recover;
+pub mod registry;
+pub mod report;
+pub mod save_editor;
+pub mod scan_changes;
+pub mod screenshots;
+pub mod shader_cache;
+pub mod size_history;
+pub mod snapshot;
+pub mod steam;
+pub mod steam_settings;
+pub mod steamos;
+pub mod sync_link;
+pub mod tar_stream;
+#[cfg(test)]
+mod test_support;
+pub mod trim;
+pub mod vdf;
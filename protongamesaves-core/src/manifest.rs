@@ -0,0 +1,917 @@
+use crate::config::{is_remote_source, Config, ConfigKey, PrivacySettings};
+use crate::net::{self, NetworkFeature};
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_yaml;
+use std::collections::HashMap;
+use std::fs; // Explicitly import serde_yaml
+use std::io::Read;
+use std::path::{Path, PathBuf}; // Ensure Path and PathBuf are imported
+use crate::compatdata::PrefixData; // Need PrefixData for the new function
+use crate::launchers;
+
+// --- Enums based on schema (can be expanded) ---
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Os {
+    Windows,
+    Linux,
+    Mac,
+    Dos,
+    // Anything newer, so one unknown value doesn't make the whole entry unreadable
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Store {
+    Steam,
+    Gog,
+    Epic,
+    Origin,
+    Uplay,
+    Ea,
+    Microsoft,
+    #[serde(other)]
+    Other,
+}
+
+// --- Constraint Structs ---
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FileConstraint {
+    #[serde(default)]
+    pub os: Option<Os>,
+    #[serde(default)]
+    pub store: Option<Store>, // Launcher paths (`<root>`) depend on it, see `launchers`
+}
+
+// --- ID Structs ---
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GameSteamInfo {
+    #[serde(default)]
+    pub id: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GameGogInfo {
+    pub _id: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)] // New struct for nested IDs
+#[serde(rename_all = "camelCase")]
+pub struct IdField {
+    pub _flatpak: Option<String>,
+    pub _gog_extra: Option<Vec<u32>>,
+    pub _lutris: Option<String>,
+    #[serde(default)]
+    pub steam_extra: Option<Vec<u32>>, // Other App IDs of the same game (editions, demos)
+}
+
+// --- Main Manifest Structs ---
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GameFileRule {
+    // Removed incorrect 'path' field
+    pub _tags: Option<Vec<String>>,
+    #[serde(default)]
+    pub when: Option<Vec<FileConstraint>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GameEntry {
+    pub files: Option<HashMap<String, GameFileRule>>, // Key is the path string
+    #[serde(rename = "installDir")]
+    pub _install_dir: Option<HashMap<String, serde_yaml::Value>>,
+    pub _launch: Option<HashMap<String, serde_yaml::Value>>,
+    pub registry: Option<HashMap<String, serde_yaml::Value>>, // Registry keys holding saves, see `registry`
+    pub steam: Option<GameSteamInfo>,
+    pub _gog: Option<GameGogInfo>, // Added GOG info
+    pub id: Option<IdField>,       // Added nested ID field
+    // Removed top-level steam_extra - it's now inside 'id'
+    pub _alias: Option<String>,                 // Added alias
+    pub _cloud: Option<HashMap<String, bool>>,  // Added cloud info
+    pub _notes: Option<Vec<serde_yaml::Value>>, // Added notes
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ManifestData {
+    #[serde(flatten)]
+    pub games: HashMap<String, GameEntry>, // Map game name to its entry
+    // Steam App ID -> game name, built once after all sources are merged
+    #[serde(skip)]
+    steam_ids: HashMap<String, String>,
+    // Entries left out because they couldn't be read, kept in `ParsedCache`
+    #[serde(skip)]
+    pub skipped: Vec<SkippedEntry>,
+}
+
+// A manifest entry (or a whole extra source) that failed to parse. The rest
+// of the manifest is used without it.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SkippedEntry {
+    pub source: String, // Manifest URL or file it came from
+    pub name: String,   // Game name, empty when the whole source was skipped
+    pub error: String,
+}
+
+impl ManifestData {
+    // Index every game by its Steam App IDs. When several entries claim the
+    // same ID, one with save paths or registry keys wins, then the first name
+    // alphabetically.
+    fn build_steam_index(&mut self) {
+        let has_files = |name: &str| {
+            self.games.get(name).is_some_and(|entry| {
+                entry.files.as_ref().is_some_and(|files| !files.is_empty())
+                    || entry.registry.as_ref().is_some_and(|registry| !registry.is_empty())
+            })
+        };
+        let mut steam_ids: HashMap<String, String> = HashMap::new();
+        for (name, entry) in &self.games {
+            let main_id = entry.steam.as_ref().and_then(|steam| steam.id);
+            let extra_ids = entry.id.iter().flat_map(|id| id.steam_extra.iter().flatten().copied());
+            for app_id in main_id.into_iter().chain(extra_ids) {
+                match steam_ids.get_mut(&app_id.to_string()) {
+                    Some(current) => {
+                        let better = (has_files(name), std::cmp::Reverse(name.as_str()))
+                            > (has_files(current), std::cmp::Reverse(current.as_str()));
+                        if better {
+                            *current = name.clone();
+                        }
+                    }
+                    None => {
+                        steam_ids.insert(app_id.to_string(), name.clone());
+                    }
+                }
+            }
+        }
+        println!("Indexed {} Steam App IDs from the manifest", steam_ids.len());
+        self.steam_ids = steam_ids;
+    }
+}
+
+// --- ludusavi custom games (the `customGames` list in ludusavi's config.yaml) ---
+#[derive(Debug, Deserialize)]
+struct CustomGame {
+    name: String,
+    #[serde(default)]
+    files: Vec<String>,
+    // "override" (default) replaces the manifest entry, "extend" adds to it
+    #[serde(default)]
+    integration: Option<String>,
+}
+
+// Games contributed by one additional manifest source
+struct ManifestSource {
+    games: HashMap<String, GameEntry>,
+    extend: Vec<(String, GameEntry)>,
+}
+
+impl ManifestSource {
+    fn from_custom_games(custom_games: Vec<CustomGame>) -> Self {
+        let mut games = HashMap::new();
+        let mut extend = Vec::new();
+        for custom in custom_games {
+            let files = custom
+                .files
+                .into_iter()
+                .map(|path| (path, GameFileRule { _tags: None, when: None }))
+                .collect();
+            let entry = GameEntry::with_files(files);
+            if custom.integration.as_deref() == Some("extend") {
+                extend.push((custom.name, entry));
+            } else {
+                games.insert(custom.name, entry);
+            }
+        }
+        Self { games, extend }
+    }
+}
+
+impl GameEntry {
+    // Entry with only save paths, used for custom games
+    pub fn with_files(files: HashMap<String, GameFileRule>) -> Self {
+        Self {
+            files: Some(files),
+            _install_dir: None,
+            _launch: None,
+            registry: None,
+            steam: None,
+            _gog: None,
+            id: None,
+            _alias: None,
+            _cloud: None,
+            _notes: None,
+        }
+    }
+}
+
+pub fn download_manifest(config: &Config) -> Result<()> {
+    ManifestDownload::new(config).run(|_| {})?;
+    config.notify(ConfigKey::ManifestCache);
+    Ok(())
+}
+
+// How far a `ManifestDownload` got, reported while it runs
+pub enum DownloadProgress {
+    Started { index: usize, count: usize, url: String }, // `index` counts from 0
+    Received { bytes: u64, total: Option<u64> },         // `total` if the server sent a length
+}
+
+// What downloading needs from the config, so it can run on a worker thread.
+// Listeners aren't told about the new cache, call
+// `config.notify(ConfigKey::ManifestCache)` once `run` succeeds.
+pub struct ManifestDownload {
+    privacy: PrivacySettings,
+    targets: Vec<(String, PathBuf)>, // URL and the file it's written to
+    includes_main: bool,             // The first target is the main manifest
+}
+
+impl ManifestDownload {
+    pub fn new(config: &Config) -> Self {
+        let mut targets = Vec::new();
+        let includes_main = config.manifest_cache_writable();
+        if includes_main {
+            targets.push((config.manifest_url().to_string(), config.manifest_cache_path().to_path_buf()));
+        } else {
+            println!(
+                "Shared manifest {} is read-only, leaving its updates to the tool that owns it",
+                config.manifest_cache_path().display()
+            );
+        }
+
+        // Refresh any additional remote sources as well; local files are read in place
+        for source in config.manifest_sources() {
+            if is_remote_source(source) {
+                targets.push((source.clone(), config.manifest_source_cache_path(source)));
+            }
+        }
+        Self { privacy: config.privacy(), targets, includes_main }
+    }
+
+    // Fails if the main manifest couldn't be downloaded. An additional source
+    // that's unreachable is only logged, it keeps its previous copy and the
+    // others are still downloaded.
+    pub fn run(&self, progress: impl Fn(DownloadProgress)) -> Result<()> {
+        let mut main_error = None;
+        for (index, (url, cache_path)) in self.targets.iter().enumerate() {
+            progress(DownloadProgress::Started { index, count: self.targets.len(), url: url.clone() });
+            if let Err(e) = download_to(&self.privacy, url, cache_path, &progress) {
+                if index == 0 && self.includes_main {
+                    main_error = Some(e);
+                } else {
+                    eprintln!("Skipping manifest source {}: {:#}", url, e);
+                }
+            }
+        }
+        main_error.map_or(Ok(()), Err)
+    }
+}
+
+// Where ludusavi keeps its copy of the manifest, native or Flatpak install
+pub fn ludusavi_manifest_path() -> Option<PathBuf> {
+    let native = dirs::config_dir().map(|dir| dir.join("ludusavi/manifest.yaml"));
+    let flatpak = dirs::home_dir()
+        .map(|home| home.join(".var/app/com.github.mtkennerly.ludusavi/config/ludusavi/manifest.yaml"));
+    [native, flatpak].into_iter().flatten().find(|path| path.is_file())
+}
+
+fn download_to(privacy: &PrivacySettings, url: &str, cache_path: &Path, progress: &impl Fn(DownloadProgress)) -> Result<()> {
+    println!(
+        "Downloading manifest from {} to {}",
+        url,
+        cache_path.display()
+    );
+
+    let mut response = net::get_with_privacy(privacy, NetworkFeature::ManifestUpdates, url)?;
+
+    if !response.status().is_success() {
+        bail!("Failed to download manifest {}: HTTP {}", url, response.status());
+    }
+
+    // Read in chunks so the progress can be shown, the manifest is several MB
+    let total = response.content_length();
+    let mut content = Vec::new();
+    let mut chunk = [0u8; 64 * 1024];
+    loop {
+        let read = response.read(&mut chunk).context("Failed to read response body")?;
+        if read == 0 {
+            break;
+        }
+        content.extend_from_slice(&chunk[..read]);
+        progress(DownloadProgress::Received { bytes: content.len() as u64, total });
+    }
+
+    fs::write(cache_path, content).context(format!(
+        "Failed to write manifest to {}",
+        cache_path.display()
+    ))?;
+
+    Ok(())
+}
+
+// --- Manifest Parsing Logic ---
+
+// Bump when the manifest structs change, so old parsed caches are ignored
+const PARSED_CACHE_VERSION: u32 = 4;
+
+// What parsing needs from the config, so it can run on a worker thread
+pub struct ManifestInputs {
+    cache_path: PathBuf,
+    sources: Vec<(String, PathBuf)>, // Source as configured, and the file read for it
+    parsed_cache_path: PathBuf,
+}
+
+impl ManifestInputs {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            cache_path: config.manifest_cache_path().to_path_buf(),
+            sources: config
+                .manifest_sources()
+                .iter()
+                .filter(|source| config.is_manifest_source_scanned(source))
+                .map(|source| {
+                    let path = if is_remote_source(source) {
+                        config.manifest_source_cache_path(source)
+                    } else {
+                        PathBuf::from(source)
+                    };
+                    (source.clone(), path)
+                })
+                .collect(),
+            parsed_cache_path: config.parsed_manifest_cache_path(),
+        }
+    }
+
+    // Size and modification time of every file read, in order. A parsed cache
+    // made from the same stamps is still current.
+    fn stamps(&self) -> Vec<FileStamp> {
+        std::iter::once(&self.cache_path)
+            .chain(self.sources.iter().map(|(_, path)| path))
+            .map(|path| {
+                let metadata = fs::metadata(path).ok();
+                FileStamp {
+                    path: path.clone(),
+                    size: metadata.as_ref().map(|m| m.len()),
+                    modified_nanos: metadata
+                        .and_then(|m| m.modified().ok())
+                        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                        .map(|d| d.as_nanos()),
+                }
+            })
+            .collect()
+    }
+}
+
+// Steps of a parse, reported while it runs
+pub enum ParseProgress {
+    LoadingParsedCache,
+    ParsingManifest,
+    MergingSource { index: usize, count: usize },
+    SavingParsedCache,
+}
+
+#[derive(Serialize, Deserialize, PartialEq)]
+struct FileStamp {
+    path: PathBuf,
+    size: Option<u64>,
+    modified_nanos: Option<u128>,
+}
+
+// The merged manifest as JSON, which loads many times faster than the YAML
+#[derive(Serialize, Deserialize)]
+struct ParsedCache {
+    version: u32,
+    stamps: Vec<FileStamp>,
+    skipped: Vec<SkippedEntry>,
+    manifest: ManifestData,
+}
+
+// Parse the cached manifest and merge the additional sources, or load the
+// result of doing so last time if none of the files changed since
+pub fn parse_manifest_inputs(inputs: &ManifestInputs, progress: impl Fn(ParseProgress)) -> Result<ManifestData> {
+    let cache_path = &inputs.cache_path;
+    if !cache_path.exists() {
+        bail!(
+            "Manifest cache file does not exist at {}. Please download it first.",
+            cache_path.display()
+        );
+    }
+
+    let stamps = inputs.stamps();
+    progress(ParseProgress::LoadingParsedCache);
+    if let Some(mut data) = load_parsed_cache(&inputs.parsed_cache_path, &stamps) {
+        println!("Loaded parsed manifest from {}", inputs.parsed_cache_path.display());
+        data.build_steam_index();
+        return Ok(data);
+    }
+
+    progress(ParseProgress::ParsingManifest);
+    let content = fs::read_to_string(cache_path).context(format!(
+        "Failed to read manifest cache file at {}",
+        cache_path.display()
+    ))?;
+
+    // Only a file that isn't YAML at all fails here, broken entries are
+    // skipped one by one below
+    let mut skipped = Vec::new();
+    let main_source = cache_path.display().to_string();
+    let mut data = match serde_yaml::from_str::<serde_yaml::Value>(&content) {
+        Ok(value) => ManifestData {
+            games: parse_games(value, &main_source, &mut skipped)?,
+            steam_ids: HashMap::new(),
+            skipped: Vec::new(),
+        },
+        Err(e) => {
+            eprintln!("Detailed YAML parsing error: {:?}", e); // Print the specific error
+                                                               // Optionally print location if available
+            if let Some(location) = e.location() {
+                eprintln!(
+                    "Error location: line {}, column {}",
+                    location.line(),
+                    location.column()
+                );
+            }
+            // Return the generic error context for anyhow
+            return Err(e).context("Failed to parse manifest YAML data (see detailed error above)");
+        }
+    };
+
+    // Merge additional sources in order, later ones overriding earlier ones.
+    // A broken extra source shouldn't take the main manifest down with it.
+    for (index, (source, path)) in inputs.sources.iter().enumerate() {
+        progress(ParseProgress::MergingSource { index, count: inputs.sources.len() });
+        match parse_manifest_source(path, source, &mut skipped) {
+            Ok(source_data) => {
+                println!("Merging {} games from manifest source {}", source_data.games.len(), source);
+                merge_manifest(&mut data, source_data);
+            }
+            Err(e) => {
+                eprintln!("Skipping manifest source {}: {:#}", source, e);
+                skipped.push(SkippedEntry { source: source.clone(), name: String::new(), error: format!("{:#}", e) });
+            }
+        }
+    }
+    if !skipped.is_empty() {
+        eprintln!("Skipped {} manifest entries that couldn't be read", skipped.len());
+    }
+
+    progress(ParseProgress::SavingParsedCache);
+    let parsed = ParsedCache { version: PARSED_CACHE_VERSION, stamps, skipped, manifest: data };
+    if let Err(e) = save_parsed_cache(&inputs.parsed_cache_path, &parsed) {
+        eprintln!("Failed to save parsed manifest to {}: {:#}", inputs.parsed_cache_path.display(), e);
+    }
+    let mut data = parsed.manifest;
+    data.skipped = parsed.skipped;
+    data.build_steam_index();
+    Ok(data)
+}
+
+// Deserializes each game of a manifest on its own, so one malformed entry
+// only costs that game. Entries that fail are added to `skipped`.
+fn parse_games(value: serde_yaml::Value, source: &str, skipped: &mut Vec<SkippedEntry>) -> Result<HashMap<String, GameEntry>> {
+    let serde_yaml::Value::Mapping(entries) = value else {
+        bail!("{} is not a manifest, expected a list of games by name", source);
+    };
+    let mut games = HashMap::with_capacity(entries.len());
+    for (key, entry) in entries {
+        let Some(name) = key.as_str().map(str::to_string) else {
+            skipped.push(SkippedEntry { source: source.to_string(), name: format!("{:?}", key), error: "Game name is not a string".to_string() });
+            continue;
+        };
+        match serde_yaml::from_value::<GameEntry>(entry) {
+            Ok(entry) => {
+                games.insert(name, entry);
+            }
+            Err(e) => {
+                eprintln!("Skipping manifest entry {} from {}: {}", name, source, e);
+                skipped.push(SkippedEntry { source: source.to_string(), name, error: e.to_string() });
+            }
+        }
+    }
+    Ok(games)
+}
+
+fn load_parsed_cache(path: &Path, stamps: &[FileStamp]) -> Option<ManifestData> {
+    let file = fs::File::open(path).ok()?;
+    let cache: ParsedCache = match serde_json::from_reader(std::io::BufReader::new(file)) {
+        Ok(cache) => cache,
+        Err(e) => {
+            eprintln!("Ignoring unreadable parsed manifest {}: {}", path.display(), e);
+            return None;
+        }
+    };
+    (cache.version == PARSED_CACHE_VERSION && cache.stamps == stamps).then(|| ManifestData {
+        skipped: cache.skipped,
+        ..cache.manifest
+    })
+}
+
+fn save_parsed_cache(path: &Path, cache: &ParsedCache) -> Result<()> {
+    let file = fs::File::create(path).context(format!("Failed to create {}", path.display()))?;
+    serde_json::to_writer(std::io::BufWriter::new(file), cache)?;
+    Ok(())
+}
+
+// Reads one additional source, either a plain manifest or a ludusavi config
+// with `customGames`. Broken games are skipped like in the main manifest.
+fn parse_manifest_source(path: &Path, source: &str, skipped: &mut Vec<SkippedEntry>) -> Result<ManifestSource> {
+    if !path.exists() {
+        bail!("{} does not exist. Please download it first.", path.display());
+    }
+
+    let content = fs::read_to_string(path)
+        .context(format!("Failed to read manifest source at {}", path.display()))?;
+    let value: serde_yaml::Value = serde_yaml::from_str(&content)
+        .context(format!("Failed to parse YAML in {}", path.display()))?;
+
+    if let Some(custom_games) = value.get("customGames") {
+        let serde_yaml::Value::Sequence(entries) = custom_games else {
+            bail!("customGames in {} is not a list", path.display());
+        };
+        let mut parsed = Vec::with_capacity(entries.len());
+        for entry in entries {
+            match serde_yaml::from_value::<CustomGame>(entry.clone()) {
+                Ok(custom) => parsed.push(custom),
+                Err(e) => {
+                    let name = entry.get("name").and_then(|name| name.as_str()).unwrap_or_default();
+                    eprintln!("Skipping custom game {} from {}: {}", name, source, e);
+                    skipped.push(SkippedEntry { source: source.to_string(), name: name.to_string(), error: e.to_string() });
+                }
+            }
+        }
+        Ok(ManifestSource::from_custom_games(parsed))
+    } else {
+        Ok(ManifestSource {
+            games: parse_games(value, source, skipped)?,
+            extend: Vec::new(),
+        })
+    }
+}
+
+// Applies a parsed source on top of the merged manifest
+fn merge_manifest(data: &mut ManifestData, source: ManifestSource) {
+    for (name, entry) in source.games {
+        data.games.insert(name, entry);
+    }
+    // "extend" custom games add their paths to an existing entry instead of replacing it
+    for (name, entry) in source.extend {
+        match data.games.get_mut(&name) {
+            Some(existing) => {
+                let files = existing.files.get_or_insert_with(HashMap::new);
+                files.extend(entry.files.unwrap_or_default());
+            }
+            None => {
+                data.games.insert(name, entry);
+            }
+        }
+    }
+}
+
+// --- Placeholder Resolution ---
+
+/// Resolves manifest path placeholders relative to a specific Proton prefix.
+/// Returns None if a required placeholder is unresolvable in the context.
+pub fn resolve_manifest_path(manifest_path: &str, config: &Config, game_id: &str) -> Option<PathBuf> {
+    resolve_manifest_path_in(manifest_path, &config.prefix_path(game_id), game_id)
+}
+
+/// Same as `resolve_manifest_path` for the prefix folder at `prefix_path`,
+/// without looking up which library holds it.
+pub fn resolve_manifest_path_in(manifest_path: &str, prefix_path: &Path, game_id: &str) -> Option<PathBuf> {
+    let drive_c = prefix_path.join("pfx/drive_c");
+    let user = drive_c.join("users/steamuser");
+    let os_user_name = "steamuser"; // Always steamuser in Proton
+
+    // Early return for unsupported placeholders we can't easily resolve
+    if manifest_path.contains("<base>") || 
+       manifest_path.contains("<root>") || 
+       manifest_path.contains("<game>") ||
+       manifest_path.contains("<storeUserId>") {
+        // Log this maybe? println!("Skipping manifest path with currently unsupported placeholder: {}", manifest_path);
+        return None;
+    }
+
+    let mut resolved = manifest_path.to_string();
+
+    // Replace placeholders - order might matter slightly if placeholders are nested (unlikely based on schema)
+    // Windows specific paths
+    resolved = resolved.replace("<winAppData>", &user.join("AppData/Roaming").to_string_lossy());
+    resolved = resolved.replace("<winLocalAppData>", &user.join("AppData/Local").to_string_lossy());
+    resolved = resolved.replace("<winLocalAppDataLow>", &user.join("AppData/LocalLow").to_string_lossy());
+    resolved = resolved.replace("<winDocuments>", &user.join("Documents").to_string_lossy());
+    resolved = resolved.replace("<winPublic>", &drive_c.join("users/Public").to_string_lossy());
+    resolved = resolved.replace("<winProgramData>", &drive_c.join("ProgramData").to_string_lossy());
+    resolved = resolved.replace("<winDir>", &drive_c.join("windows").to_string_lossy());
+    
+    // Common paths
+    resolved = resolved.replace("<home>", &user.to_string_lossy());
+    resolved = resolved.replace("<osUserName>", os_user_name);
+    resolved = resolved.replace("<storeGameId>", game_id);
+
+    // Linux/XDG paths - unlikely to be used with win* paths but handle defensively
+    // We map them inside the prefix for consistency, though games using them might not store saves there.
+    resolved = resolved.replace("<xdgData>", &user.join(".local/share").to_string_lossy());
+    resolved = resolved.replace("<xdgConfig>", &user.join(".config").to_string_lossy());
+
+    // Check if any placeholders remain unresolved (basic check)
+    if resolved.contains('<') {
+        // println!("Warning: Path may still contain unresolved placeholders: {}", resolved);
+        // Decide if we should return None or the partially resolved path.
+        // Let's return None for now if it looks like placeholders are left.
+        return None; 
+    }
+
+    Some(PathBuf::from(resolved))
+}
+
+/// Resolves one save path of `entry` for a prefix. Unlike `resolve_manifest_path`
+/// it knows the rule's store, so paths of launchers installed in the prefix
+/// (`<root>`, `<base>`) can be resolved too.
+pub fn resolve_manifest_rule(manifest_path: &str, rule: &GameFileRule, entry: &GameEntry, config: &Config, game_id: &str) -> Option<PathBuf> {
+    resolve_manifest_rule_in(manifest_path, rule, entry, &config.prefix_path(game_id), game_id)
+}
+
+/// Same as `resolve_manifest_rule` for the prefix folder at `prefix_path`.
+pub fn resolve_manifest_rule_in(manifest_path: &str, rule: &GameFileRule, entry: &GameEntry, prefix_path: &Path, game_id: &str) -> Option<PathBuf> {
+    match launchers::resolve_launcher_path(manifest_path, rule, entry, &prefix_path.join("pfx/drive_c")) {
+        Some(resolved) => resolve_manifest_path_in(&resolved, prefix_path, game_id),
+        None => resolve_manifest_path_in(manifest_path, prefix_path, game_id),
+    }
+}
+
+/// Looks the prefix's App ID up in the manifest's Steam IDs, the quick way to
+/// identify most games. Non-Steam shortcuts and games without an ID fall through.
+pub fn find_game_for_prefix_by_steam_id<'a>(
+    manifest: &'a ManifestData,
+    game_id: &str,
+) -> Option<(String, &'a GameEntry)> {
+    let name = manifest.steam_ids.get(game_id)?;
+    manifest.games.get(name).map(|entry| (name.clone(), entry))
+}
+
+/// Identifies a prefix holding a launcher such as Ubisoft Connect by the store's
+/// game IDs in its save folders, e.g. a non-Steam shortcut to the launcher.
+pub fn find_game_for_prefix_by_launcher_ids<'a>(
+    manifest: &'a ManifestData,
+    prefix_data: &PrefixData,
+) -> Option<(String, &'a GameEntry)> {
+    launchers::find_game_by_save_ids(manifest, &prefix_data._drive_c_path)
+}
+
+/// Tries to identify a game in the manifest by matching resolved manifest paths
+/// against paths found within a specific prefix's save locations.
+pub fn find_game_for_prefix_by_path<'a>(
+    manifest: &'a ManifestData,
+    prefix_data: &PrefixData,
+) -> Option<(String, &'a GameEntry)> {
+    // Iterate through locations found in the prefix scan
+    for save_loc in &prefix_data.save_locations {
+        for entry in &save_loc.entries {
+            let found_path = &entry.path; // The actual path found on disk
+
+            // Normalize the found path once
+            let normalized_found = found_path
+                .as_path()
+                .to_string_lossy()
+                .trim_end_matches('/')
+                .to_lowercase();
+            if normalized_found.is_empty() { continue; } // Skip empty paths
+
+            // Now, iterate through the manifest to see if this path matches any rule
+            for (manifest_game_name, manifest_entry) in &manifest.games {
+                if let Some(files) = &manifest_entry.files {
+                    for manifest_path_str in files.keys() {
+                        // Resolve the manifest path string using the prefix's game_id
+                        if let Some(resolved_manifest_path) = resolve_manifest_path_in(
+                            manifest_path_str,
+                            &prefix_data._path,
+                            &prefix_data.game_id,
+                        ) {
+                            // Normalize the resolved manifest path
+                            let normalized_manifest = resolved_manifest_path
+                                .as_path()
+                                .to_string_lossy()
+                                .trim_end_matches('/')
+                                .to_lowercase();
+
+                            // Check if the normalized manifest path starts with the normalized found path
+                            if !normalized_manifest.is_empty() && normalized_manifest.starts_with(&normalized_found) {
+                                // Found a match! Return the game name and entry
+                                return Some((manifest_game_name.clone(), manifest_entry));
+                            }
+                        }
+                    }
+                }
+            } // End manifest game iteration
+        } // End save entry iteration
+    } // End save location iteration
+
+    // If no match was found after checking all paths and manifest entries
+    None
+}
+
+/// Slow fallback for prefixes the folder heuristics couldn't identify: resolves every
+/// manifest path for this prefix and picks the game with the most save paths that exist.
+pub fn find_game_for_prefix_by_existing_paths<'a>(
+    manifest: &'a ManifestData,
+    game_id: &str,
+    config: &Config,
+) -> Option<(String, &'a GameEntry)> {
+    // Looked up once, finding the library means checking every one of them
+    let prefix_path = config.prefix_path(game_id);
+    let mut best: Option<(usize, &'a String, &'a GameEntry)> = None;
+
+    for (manifest_game_name, manifest_entry) in &manifest.games {
+        let Some(files) = &manifest_entry.files else {
+            continue;
+        };
+
+        let existing = files
+            .iter()
+            // Globs can't be checked with a plain exists(), and a bare placeholder
+            // like "<winDocuments>" would match every prefix
+            .filter(|(path, _)| !path.contains('*') && has_path_below_placeholder(path))
+            .filter_map(|(path, rule)| resolve_manifest_rule_in(path, rule, manifest_entry, &prefix_path, game_id))
+            .filter(|resolved| resolved.exists())
+            .count();
+
+        if existing == 0 {
+            continue;
+        }
+        let is_better = match best {
+            None => true,
+            Some((count, name, _)) => existing > count || (existing == count && manifest_game_name < name),
+        };
+        if is_better {
+            best = Some((existing, manifest_game_name, manifest_entry));
+        }
+    }
+
+    best.map(|(_, name, entry)| (name.clone(), entry))
+}
+
+// True if the path names something inside its placeholder root, e.g. "<winAppData>/Game"
+fn has_path_below_placeholder(manifest_path: &str) -> bool {
+    manifest_path
+        .rsplit_once('>')
+        .map(|(_, rest)| !rest.trim_matches('/').is_empty())
+        .unwrap_or(false)
+}
+
+// --- Contributing entries upstream ---
+
+/// Where new manifest entries and fixes are requested
+pub const MANIFEST_NEW_ISSUE_URL: &str = "https://github.com/mtkennerly/ludusavi-manifest/issues/new";
+
+/// A manifest entry in ludusavi-manifest's own format for save paths the user
+/// added by hand, ready to paste into an issue. `app_id` is only included when
+/// it's a real Steam App ID: non-Steam shortcuts get IDs with the top bit set.
+pub fn contribution_snippet(name: &str, app_id: &str, paths: &[String]) -> String {
+    let mut files = serde_yaml::Mapping::new();
+    for path in paths {
+        let mut rule = serde_yaml::Mapping::new();
+        rule.insert("tags".into(), vec!["save"].into());
+        let mut when = serde_yaml::Mapping::new();
+        when.insert("os".into(), "windows".into());
+        rule.insert("when".into(), vec![serde_yaml::Value::from(when)].into());
+        files.insert(path.as_str().into(), rule.into());
+    }
+    let mut entry = serde_yaml::Mapping::new();
+    entry.insert("files".into(), files.into());
+    if let Some(id) = app_id.parse::<u32>().ok().filter(|id| id & 0x8000_0000 == 0) {
+        let mut steam = serde_yaml::Mapping::new();
+        steam.insert("id".into(), id.into());
+        entry.insert("steam".into(), steam.into());
+    }
+    let mut games = serde_yaml::Mapping::new();
+    games.insert(name.into(), entry.into());
+    serde_yaml::to_string(&games).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compatdata;
+    use crate::test_support::Fixture;
+
+    fn scan(fixture: &Fixture, config: &Config, app_id: &str) -> PrefixData {
+        compatdata::scan_prefixes(config, &fixture.compatdata_path(), vec![app_id.to_string()])
+            .pop()
+            .unwrap()
+    }
+
+    #[test]
+    fn resolves_placeholders_inside_the_prefix() {
+        let fixture = Fixture::new();
+        let prefix = fixture.add_prefix("1000");
+        let user = prefix.join("pfx/drive_c/users/steamuser");
+        assert_eq!(
+            resolve_manifest_path_in("<winAppData>/LonelyHero", &prefix, "1000"),
+            Some(user.join("AppData/Roaming/LonelyHero"))
+        );
+        assert_eq!(
+            resolve_manifest_path_in("<winProgramData>/<storeGameId>", &prefix, "1000"),
+            Some(prefix.join("pfx/drive_c/ProgramData/1000"))
+        );
+        // The same through the config, which has to find the library first
+        assert_eq!(
+            resolve_manifest_path("<home>/Saved Games", &fixture.config(), "1000"),
+            Some(user.join("Saved Games"))
+        );
+        assert_eq!(resolve_manifest_path_in("<base>/saves", &prefix, "1000"), None);
+        assert_eq!(resolve_manifest_path_in("<unknownPlaceholder>/saves", &prefix, "1000"), None);
+    }
+
+    #[test]
+    fn parses_the_manifest_and_indexes_steam_ids() {
+        let fixture = Fixture::new();
+        let manifest = fixture.manifest();
+        assert_eq!(manifest.games.len(), 5);
+        let (name, _) = find_game_for_prefix_by_steam_id(&manifest, "1000").unwrap();
+        assert_eq!(name, "Lonely Hero");
+        // An entry with save paths wins over another one with the same App ID
+        let (name, _) = find_game_for_prefix_by_steam_id(&manifest, "3000").unwrap();
+        assert_eq!(name, "Shared Id Game");
+        assert!(find_game_for_prefix_by_steam_id(&manifest, "9999").is_none());
+
+        // Parsing again loads the cached result, which has to match
+        let cached = fixture.manifest();
+        assert_eq!(cached.games.len(), manifest.games.len());
+        assert!(find_game_for_prefix_by_steam_id(&cached, "1000").is_some());
+    }
+
+    #[test]
+    fn unparsable_manifest_is_an_error() {
+        let fixture = Fixture::new();
+        fixture.write_manifest("\"Broken\": [unclosed");
+        assert!(parse_manifest_inputs(&ManifestInputs::new(&fixture.config()), |_| {}).is_err());
+    }
+
+    #[test]
+    fn matches_a_prefix_by_its_save_folder() {
+        let fixture = Fixture::new();
+        fixture.add_prefix("5000");
+        fixture.add_file("5000", "Documents/My Games/Folder Only/save.dat", "save");
+        let config = fixture.config();
+        let manifest = fixture.manifest();
+
+        let prefix_data = scan(&fixture, &config, "5000");
+        assert!(find_game_for_prefix_by_steam_id(&manifest, "5000").is_none());
+        let (name, _) = find_game_for_prefix_by_path(&manifest, &prefix_data).unwrap();
+        assert_eq!(name, "Folder Only Game");
+    }
+
+    #[test]
+    fn matches_a_prefix_by_existing_paths() {
+        let fixture = Fixture::new();
+        fixture.add_prefix("6000");
+        // Deeper than the folder heuristics look
+        fixture.add_file("6000", "AppData/Roaming/LonelyHero/Saves/slot1.sav", "save");
+        fixture.add_prefix("7000");
+        let config = fixture.config();
+        let manifest = fixture.manifest();
+
+        let (name, _) = find_game_for_prefix_by_existing_paths(&manifest, "6000", &config).unwrap();
+        assert_eq!(name, "Lonely Hero");
+        // An empty prefix only has the bare placeholder folders, those don't count
+        assert!(find_game_for_prefix_by_existing_paths(&manifest, "7000", &config).is_none());
+    }
+
+    #[test]
+    fn skips_malformed_entries_and_keeps_the_rest() {
+        let fixture = Fixture::new();
+        let broken = "\"Broken Game\":\n  steam:\n    id: not-a-number\n\"Odd Files\":\n  files: 42\n";
+        fixture.write_manifest(&format!("{}{}", crate::test_support::MINI_MANIFEST, broken));
+
+        let manifest = fixture.manifest();
+        assert!(manifest.games.contains_key("Lonely Hero"));
+        assert!(find_game_for_prefix_by_steam_id(&manifest, "1000").is_some());
+        let mut skipped: Vec<&str> = manifest.skipped.iter().map(|entry| entry.name.as_str()).collect();
+        skipped.sort();
+        assert_eq!(skipped, ["Broken Game", "Odd Files"]);
+
+        // The second parse comes from the parsed cache and still knows what was skipped
+        assert_eq!(fixture.manifest().skipped.len(), 2);
+    }
+
+    #[test]
+    fn contribution_snippet_parses_as_a_manifest_entry() {
+        let paths = vec!["<winDocuments>/My Game: Part 2".to_string(), "<winAppData>/Studio/Game".to_string()];
+        let snippet = contribution_snippet("My Game: Part 2", "1234", &paths);
+        let games: HashMap<String, GameEntry> = serde_yaml::from_str(&snippet).unwrap();
+        let entry = &games["My Game: Part 2"];
+        assert_eq!(entry.steam.as_ref().and_then(|steam| steam.id), Some(1234));
+        let files = entry.files.as_ref().unwrap();
+        assert_eq!(files.len(), 2);
+        let when = files["<winDocuments>/My Game: Part 2"].when.as_ref().unwrap();
+        assert_eq!(when[0].os, Some(Os::Windows));
+
+        // A non-Steam shortcut's ID means nothing to the manifest
+        let snippet = contribution_snippet("Shortcut", "3123456789", &paths);
+        let games: HashMap<String, GameEntry> = serde_yaml::from_str(&snippet).unwrap();
+        assert!(games["Shortcut"].steam.is_none());
+    }
+}
@@ -0,0 +1,67 @@
+use crate::config::{Config, PrivacySettings};
+use anyhow::{bail, Context, Result};
+
+// Everything in the app that may touch the network. All requests go through
+// `get` or `request` so offline mode and the per-feature switches can't be bypassed.
+#[derive(Clone, Copy)]
+pub enum NetworkFeature {
+    ManifestUpdates,
+    BackupUploads, // To backup destinations set up in Settings
+    LanSync,       // Other copies of the app on the local network
+}
+
+impl NetworkFeature {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::ManifestUpdates => "manifest updates",
+            Self::BackupUploads => "backup uploads",
+            Self::LanSync => "LAN sync",
+        }
+    }
+
+    fn is_allowed(&self, privacy: &PrivacySettings) -> bool {
+        match self {
+            Self::ManifestUpdates => privacy.manifest_updates,
+            Self::BackupUploads => privacy.backup_uploads,
+            Self::LanSync => privacy.lan_sync,
+        }
+    }
+}
+
+// Blocking GET request, refused if offline mode is on or the feature is switched off
+pub fn get(config: &Config, feature: NetworkFeature, url: &str) -> Result<reqwest::blocking::Response> {
+    get_with_privacy(&config.privacy(), feature, url)
+}
+
+// `get` for worker threads, with the settings copied out of the config beforehand
+pub fn get_with_privacy(privacy: &PrivacySettings, feature: NetworkFeature, url: &str) -> Result<reqwest::blocking::Response> {
+    check_allowed(privacy, feature, url)?;
+    println!("Network request ({}): {}", feature.name(), url);
+    reqwest::blocking::get(url).context(format!("Failed to send request to {}", url))
+}
+
+// Fails like `get` when the network may not be used, for anything else that
+// connects to a server (e.g. the sftp client)
+pub fn check_allowed(privacy: &PrivacySettings, feature: NetworkFeature, server: &str) -> Result<()> {
+    if privacy.offline_mode {
+        bail!("Offline mode is enabled, not contacting {}", server);
+    }
+    if !feature.is_allowed(privacy) {
+        bail!("Network access for {} is disabled in the privacy settings", feature.name());
+    }
+    Ok(())
+}
+
+// A request with any method (WebDAV needs MKCOL and PUT), to be sent by the
+// caller. Checked against the privacy settings like `get`.
+pub fn request(
+    client: &reqwest::blocking::Client,
+    privacy: &PrivacySettings,
+    feature: NetworkFeature,
+    method: reqwest::Method,
+    url: &str,
+) -> Result<reqwest::blocking::RequestBuilder> {
+    check_allowed(privacy, feature, url)?;
+    println!("Network request ({}): {} {}", feature.name(), method, url);
+    Ok(client.request(method, url))
+}
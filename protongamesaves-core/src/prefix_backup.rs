@@ -0,0 +1,179 @@
+use crate::config::{Compression, PrefixBackupOptions, SnapshotCompression};
+use crate::fsutil;
+use crate::snapshot;
+use anyhow::{bail, Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+// A full prefix backup is the whole compatdata/<app id> folder in one tar
+// archive, for games whose saves the manifest doesn't know about:
+//
+//   1245620 - ELDEN RING prefix.tar.zst
+//     1245620/pfx/...         <- everything but the excluded patterns
+//     1245620/version ...
+//
+// Compressed like safety snapshots. Blocks for a long while on big prefixes,
+// meant for a worker thread.
+const SHADER_CACHE_PATTERNS: [&str; 2] = ["*.dxvk-cache", "vkd3d-proton.cache*"];
+// Folders Wine fills in itself, left out on request. Restoring keeps the ones
+// of the current prefix when the archive has none.
+const SYSTEM_DIRS: [&str; 3] = [
+    "pfx/drive_c/windows",
+    "pfx/drive_c/Program Files/Common Files",
+    "pfx/drive_c/Program Files (x86)/Common Files",
+];
+
+// File extension of backups made with `compression`
+pub fn archive_extension(compression: Compression) -> &'static str {
+    match compression {
+        Compression::None => ".tar",
+        Compression::Zstd => ".tar.zst",
+        Compression::Gzip => ".tar.gz",
+    }
+}
+
+// tar patterns for what `options` leaves out of the prefix of `app_id`
+fn exclude_patterns(app_id: &str, options: &PrefixBackupOptions) -> Vec<String> {
+    let mut patterns: Vec<String> = options.excludes.clone();
+    if !options.include_shader_caches {
+        patterns.extend(SHADER_CACHE_PATTERNS.iter().map(|pattern| pattern.to_string()));
+    }
+    if options.skip_system_dirs {
+        patterns.extend(SYSTEM_DIRS.iter().map(|dir| format!("{}/{}", app_id, dir)));
+    }
+    patterns
+}
+
+// Archive the prefix at `prefix_path` into `archive`, returns its size
+pub fn backup(prefix_path: &Path, archive: &Path, compression: SnapshotCompression, options: &PrefixBackupOptions) -> Result<u64> {
+    let (Some(compatdata_path), Some(app_id)) = (prefix_path.parent(), prefix_path.file_name()) else {
+        bail!("{} is not inside a compatdata folder", prefix_path.display());
+    };
+    let app_id = app_id.to_string_lossy().to_string();
+    if compression.method == Compression::None {
+        if let Some(parent) = archive.parent() {
+            fsutil::ensure_free_space([(parent.to_path_buf(), fsutil::disk_usage(prefix_path))])?;
+        }
+    }
+
+    // Written next to the target first, so a failed backup never looks finished
+    let partial = archive.with_file_name(format!(
+        ".{}.partial",
+        archive.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default()
+    ));
+    let mut command = Command::new("tar");
+    command.arg("-c");
+    if compression.method != Compression::None {
+        let (min, max) = compression.method.level_range();
+        command.arg("-I").arg(format!("{} -{}", snapshot::compressor(compression.method), compression.level.clamp(min, max)));
+    }
+    command.arg("-f").arg(&partial);
+    for pattern in exclude_patterns(&app_id, options) {
+        command.arg(format!("--exclude={}", pattern));
+    }
+    command.arg("-C").arg(compatdata_path).arg(&app_id);
+    if let Err(e) = snapshot::run_tar(&mut command) {
+        let _ = fs::remove_file(&partial);
+        return Err(e.context(format!("Failed to back up {}", prefix_path.display())));
+    }
+    fs::rename(&partial, archive).context(format!("Failed to move the backup to {}", archive.display()))?;
+    let size = fsutil::disk_usage(archive);
+    println!("Backed up prefix {} to {} ({} bytes)", prefix_path.display(), archive.display(), size);
+    Ok(size)
+}
+
+// Replace the prefix at `prefix_path` with the one in `archive`. The archive
+// is unpacked next to it first, the old prefix is only removed once the new
+// one is in place.
+pub fn restore(archive: &Path, prefix_path: &Path) -> Result<()> {
+    let (Some(compatdata_path), Some(app_id)) = (prefix_path.parent(), prefix_path.file_name()) else {
+        bail!("{} is not inside a compatdata folder", prefix_path.display());
+    };
+    let app_id = app_id.to_string_lossy().to_string();
+    // Hidden names, so scans never pick these up as prefixes
+    let staging = compatdata_path.join(format!(".{}.restoring", app_id));
+    let replaced = compatdata_path.join(format!(".{}.replaced", app_id));
+    let _ = fs::remove_dir_all(&staging);
+    fs::create_dir_all(&staging).context(format!("Failed to create {}", staging.display()))?;
+
+    let unpacked = unpack(archive, &staging);
+    let restored = match unpacked {
+        Ok(restored) => restored,
+        Err(e) => {
+            let _ = fs::remove_dir_all(&staging);
+            return Err(e);
+        }
+    };
+
+    let _ = fs::remove_dir_all(&replaced);
+    let had_prefix = prefix_path.exists();
+    if had_prefix {
+        fs::rename(prefix_path, &replaced).context(format!("Failed to move {} aside", prefix_path.display()))?;
+    }
+    if let Err(e) = fs::rename(&restored, prefix_path) {
+        if had_prefix {
+            let _ = fs::rename(&replaced, prefix_path);
+        }
+        let _ = fs::remove_dir_all(&staging);
+        bail!("Failed to move the restored prefix into place: {}", e);
+    }
+    if had_prefix {
+        // Backups without Wine's own folders get the current ones
+        for dir in SYSTEM_DIRS {
+            let (old, new) = (replaced.join(dir), prefix_path.join(dir));
+            if old.exists() && !new.exists() {
+                if let Some(parent) = new.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::rename(&old, &new).context(format!("Failed to keep {}", old.display()))?;
+            }
+        }
+        if let Err(e) = fs::remove_dir_all(&replaced) {
+            eprintln!("Failed to remove the replaced prefix {}: {}", replaced.display(), e);
+        }
+    }
+    let _ = fs::remove_dir_all(&staging);
+    println!("Restored prefix {} from {}", prefix_path.display(), archive.display());
+    Ok(())
+}
+
+// Unpack `archive` into `staging`, returns the prefix folder inside
+fn unpack(archive: &Path, staging: &Path) -> Result<PathBuf> {
+    // tar recognizes gzip and zstd archives on its own when unpacking
+    snapshot::run_tar(Command::new("tar").arg("-x").arg("-f").arg(archive).arg("-C").arg(staging))
+        .context(format!("Failed to unpack {}", archive.display()))?;
+    let folders: Vec<PathBuf> = fs::read_dir(staging)?.flatten().map(|entry| entry.path()).filter(|path| path.is_dir()).collect();
+    match folders.as_slice() {
+        [folder] if folder.join("pfx").is_dir() => Ok(folder.clone()),
+        _ => bail!("{} is not a full prefix backup", archive.display()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{write_file, Fixture};
+
+    #[test]
+    fn backs_up_and_restores_the_whole_prefix() {
+        let fixture = Fixture::new();
+        let prefix = fixture.add_prefix("1000");
+        fixture.add_file("1000", "Obscure/place/save.dat", "before");
+        fixture.add_file("1000", "AppData/Local/Game/Game.dxvk-cache", "cache");
+        let windows_file = prefix.join("pfx/drive_c/windows/system32/kernel32.dll");
+        write_file(&windows_file, "dll");
+
+        let archive = fixture.root.join("1000 prefix.tar");
+        let options = PrefixBackupOptions { skip_system_dirs: true, ..Default::default() };
+        backup(&prefix, &archive, SnapshotCompression { method: Compression::None, level: 0 }, &options).unwrap();
+
+        let save = fixture.add_file("1000", "Obscure/place/save.dat", "after");
+        restore(&archive, &prefix).unwrap();
+        assert_eq!(fs::read_to_string(&save).unwrap(), "before");
+        // Left out of the backup: the cache is gone, Wine's folder is kept from before
+        assert!(!prefix.join("pfx/drive_c/users/steamuser/AppData/Local/Game/Game.dxvk-cache").exists());
+        assert!(windows_file.exists());
+        assert_eq!(crate::compatdata::list_game_ids(&fixture.compatdata_path()).unwrap(), ["1000"]);
+    }
+}
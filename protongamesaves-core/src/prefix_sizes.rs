@@ -0,0 +1,129 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use crate::fsutil;
+
+// One measured prefix and the stamp it was measured at
+#[derive(Serialize, Deserialize, Clone, Copy)]
+struct CachedSize {
+    stamp: u64,
+    bytes: u64,
+}
+
+// Sizes of whole prefixes, remembered between scans and app starts since
+// measuring one means walking tens of thousands of files. Wine rewrites
+// user.reg whenever a program in the prefix exits, so its modification time
+// says when a prefix has to be measured again.
+#[derive(Serialize, Deserialize, Default)]
+pub struct PrefixSizeCache {
+    prefixes: HashMap<PathBuf, CachedSize>,
+}
+
+impl PrefixSizeCache {
+    // A missing or unreadable file starts an empty cache
+    pub fn load(path: &Path) -> Self {
+        let Ok(content) = fs::read_to_string(path) else {
+            return Self::default();
+        };
+        serde_json::from_str(&content).unwrap_or_else(|e| {
+            eprintln!("Ignoring unreadable prefix size cache {}: {}", path.display(), e);
+            Self::default()
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context(format!("Failed to create {}", parent.display()))?;
+        }
+        let content = serde_json::to_string(self)?;
+        fs::write(path, content).context(format!("Failed to write prefix size cache to {}", path.display()))
+    }
+
+    // The remembered size, if the prefix wasn't used since it was measured
+    pub fn cached(&self, prefix_path: &Path) -> Option<u64> {
+        let stamp = stamp(prefix_path)?;
+        self.prefixes
+            .get(prefix_path)
+            .filter(|cached| cached.stamp == stamp)
+            .map(|cached| cached.bytes)
+    }
+
+    // The remembered size, or a new measurement
+    pub fn measure(&mut self, prefix_path: &Path) -> u64 {
+        if let Some(bytes) = self.cached(prefix_path) {
+            return bytes;
+        }
+        let bytes = fsutil::disk_usage(prefix_path);
+        if let Some(stamp) = stamp(prefix_path) {
+            self.prefixes.insert(prefix_path.to_path_buf(), CachedSize { stamp, bytes });
+        }
+        bytes
+    }
+
+    // Forget prefixes that are gone, so the file doesn't keep growing
+    pub fn retain_existing(&mut self) {
+        self.prefixes.retain(|path, _| path.exists());
+    }
+}
+
+// Nanoseconds so two runs within one second still count as a change
+fn stamp(prefix_path: &Path) -> Option<u64> {
+    let modified = fs::metadata(prefix_path.join("pfx/user.reg")).and_then(|meta| meta.modified()).ok()?;
+    modified.duration_since(UNIX_EPOCH).ok().map(|d| d.as_nanos() as u64)
+}
+
+// Sizes of `prefixes` (App ID, prefix folder) through the cache file at
+// `cache_path`, which is updated with whatever had to be measured. Prefixes
+// not in the cache are measured `threads` at a time.
+pub fn measure_all(cache_path: &Path, prefixes: &[(String, PathBuf)], threads: usize) -> Vec<(String, u64)> {
+    let mut cache = PrefixSizeCache::load(cache_path);
+    let uncached: Vec<&PathBuf> = prefixes.iter().map(|(_, path)| path).filter(|path| cache.cached(path).is_none()).collect();
+    let measured = fsutil::parallel_map(uncached, threads, |path| (path, fsutil::disk_usage(path)));
+    for (path, bytes) in measured {
+        if let Some(stamp) = stamp(path) {
+            cache.prefixes.insert(path.clone(), CachedSize { stamp, bytes });
+        }
+    }
+    let sizes = prefixes
+        .iter()
+        .map(|(game_id, path)| (game_id.clone(), cache.measure(path)))
+        .collect();
+    cache.retain_existing();
+    if let Err(e) = cache.save(cache_path) {
+        eprintln!("Failed to save prefix sizes: {:#}", e);
+    }
+    sizes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{write_file, Fixture};
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn prefix_is_measured_again_once_wine_ran() {
+        let fixture = Fixture::new();
+        let prefix = fixture.add_prefix("1000");
+        let user_reg = prefix.join("pfx/user.reg");
+        write_file(&user_reg, "WINE REGISTRY Version 2\n");
+        let cache_path = fixture.root.join("prefix-sizes.json");
+        let prefixes = vec![("1000".to_string(), prefix.clone())];
+
+        let first = measure_all(&cache_path, &prefixes, 2)[0].1;
+        assert!(first > 0);
+        // A file added without running anything isn't noticed, that's the cache
+        write_file(&prefix.join("pfx/drive_c/big.bin"), &"x".repeat(4096));
+        assert_eq!(measure_all(&cache_path, &prefixes, 2)[0].1, first);
+        assert_eq!(PrefixSizeCache::load(&cache_path).cached(&prefix), Some(first));
+
+        let later = SystemTime::now() + Duration::from_secs(5);
+        fs::File::options().write(true).open(&user_reg).unwrap().set_modified(later).unwrap();
+        assert!(PrefixSizeCache::load(&cache_path).cached(&prefix).is_none());
+        assert_eq!(measure_all(&cache_path, &prefixes, 2)[0].1, first + 4096);
+    }
+}
@@ -0,0 +1,124 @@
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use crate::fsutil;
+
+// Previews are built on demand, keep them quick even for big save folders
+const MAX_FILES: usize = 50;
+const MAX_DEPTH: usize = 4;
+const MAX_TEXT_BYTES: u64 = 16 * 1024;
+
+// Screenshots and thumbnails games store next to their save slots
+pub(crate) const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "bmp", "gif", "webp"];
+// Config and save formats that are readable as they are
+const TEXT_EXTENSIONS: &[&str] = &["json", "ini", "cfg", "conf", "txt", "xml", "toml", "yaml", "yml", "lua", "log"];
+
+// What of a file can be shown
+pub enum PreviewContent {
+    Image,
+    Text { text: String, truncated: bool },
+    // Binary saves only get their size and timestamp
+    Other,
+}
+
+pub struct PreviewFile {
+    pub path: PathBuf,
+    pub name: String, // Shown path, starting with the name given to `preview`
+    pub size: u64,
+    pub modified: Option<u64>, // Unix timestamp (seconds)
+    pub content: PreviewContent,
+}
+
+// Files of a save location (a folder or a single file), newest first, with
+// what can be shown of each. `name` is used for `path` itself, snapshots store
+// their copies under numbered names.
+pub fn preview(path: &Path, name: &str) -> Vec<PreviewFile> {
+    let mut files = Vec::new();
+    collect_files(path, PathBuf::from(name), 0, &mut files);
+    let mut files: Vec<(PathBuf, PathBuf, Option<u64>)> = files
+        .into_iter()
+        .map(|(path, name)| {
+            let modified = fsutil::last_modified(&path);
+            (path, name, modified)
+        })
+        .collect();
+    files.sort_by(|a, b| b.2.cmp(&a.2).then(a.1.cmp(&b.1)));
+    files.truncate(MAX_FILES);
+
+    files
+        .into_iter()
+        .map(|(path, name, modified)| PreviewFile {
+            size: fsutil::disk_usage(&path),
+            content: read_content(&path),
+            name: name.display().to_string(),
+            path,
+            modified,
+        })
+        .collect()
+}
+
+// Regular files at or below `path`, without following symlinks
+fn collect_files(path: &Path, name: PathBuf, depth: usize, files: &mut Vec<(PathBuf, PathBuf)>) {
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        return;
+    };
+    if metadata.is_file() {
+        files.push((path.to_path_buf(), name));
+        return;
+    }
+    if !metadata.is_dir() || depth >= MAX_DEPTH {
+        return;
+    }
+    let Ok(entries) = fs::read_dir(path) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        collect_files(&entry.path(), name.join(entry.file_name()), depth + 1, files);
+    }
+}
+
+fn read_content(path: &Path) -> PreviewContent {
+    let extension = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    if IMAGE_EXTENSIONS.contains(&extension.as_str()) {
+        return PreviewContent::Image;
+    }
+    if !TEXT_EXTENSIONS.contains(&extension.as_str()) {
+        return PreviewContent::Other;
+    }
+
+    let Ok(file) = fs::File::open(path) else {
+        return PreviewContent::Other;
+    };
+    let mut bytes = Vec::new();
+    if file.take(MAX_TEXT_BYTES + 1).read_to_end(&mut bytes).is_err() {
+        return PreviewContent::Other;
+    }
+    let truncated = bytes.len() as u64 > MAX_TEXT_BYTES;
+    bytes.truncate(MAX_TEXT_BYTES as usize);
+    // Some games use .json or .ini for binary data, those aren't worth showing
+    let text = match String::from_utf8(bytes) {
+        Ok(text) => text,
+        Err(e) if truncated && e.utf8_error().error_len().is_none() => {
+            // Cut in the middle of a character, keep what's before it
+            let valid = e.utf8_error().valid_up_to();
+            let mut bytes = e.into_bytes();
+            bytes.truncate(valid);
+            String::from_utf8(bytes).unwrap_or_default()
+        }
+        Err(_) => return PreviewContent::Other,
+    };
+
+    // Minified JSON is one long line, reformat it when the whole file was read
+    if extension == "json" && !truncated {
+        if let Ok(value) = serde_json::from_str::<serde_json::Value>(&text) {
+            if let Ok(pretty) = serde_json::to_string_pretty(&value) {
+                return PreviewContent::Text { text: pretty, truncated };
+            }
+        }
+    }
+    PreviewContent::Text { text, truncated }
+}
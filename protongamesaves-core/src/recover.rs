@@ -0,0 +1,288 @@
+use crate::activity::{self, ActivityEntry, ActivityKind};
+use crate::cloud::{self, CloudFile};
+use crate::config::Config;
+use crate::fsutil;
+use crate::snapshot::{self, Snapshot, SnapshotItem};
+use anyhow::{bail, Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+// Heuristic candidates are only hints, keep the list short enough to read
+const RECENT_DAYS: u64 = 7;
+const MAX_RECENT_FILES: usize = 30;
+const MAX_SCAN_DEPTH: usize = 8;
+
+// Extensions games (and editors) use for the previous version of a file,
+// "save.dat.bak" is a copy of "save.dat"
+const BACKUP_EXTENSIONS: &[&str] = &["bak", "backup", "old", "prev"];
+// Names of files a game writes next to the real save, without a fixed original
+const BACKUP_NAME_HINTS: &[&str] = &["autosave", "backup", "quicksave"];
+// Folders under steamuser that only hold throwaway files
+const SKIPPED_FOLDERS: &[&str] = &["Temp", "INetCache", "CrashDumps", "Package Cache"];
+
+// A game to recover saves for, with every save location the manifest names
+// (existing or not, a lost save often means a deleted folder)
+pub struct RecoverGame {
+    pub app_id: String,
+    pub name: String,
+    pub locations: Vec<PathBuf>,
+}
+
+// Where a recoverable copy of the save comes from
+pub enum RecoverySource {
+    Snapshot(Snapshot),
+    Cloud(CloudFile),
+    // A backup the game wrote itself, restored over the file it was made from
+    BackupFile { path: PathBuf, original: PathBuf },
+    // Any other file in the prefix, copied into a chosen save location
+    PrefixFile { path: PathBuf },
+}
+
+pub struct RecoveryCandidate {
+    pub source: RecoverySource,
+    pub size: u64,
+    pub modified: Option<u64>, // Unix timestamp (seconds)
+}
+
+impl RecoveryCandidate {
+    // Short name for lists
+    pub fn label(&self) -> String {
+        match &self.source {
+            RecoverySource::Snapshot(snapshot) => snapshot.title().to_string(),
+            RecoverySource::Cloud(file) => file.name.clone(),
+            RecoverySource::BackupFile { path, .. } | RecoverySource::PrefixFile { path } => file_name(path),
+        }
+    }
+
+    // The copy's own files, with the name each is shown under in a preview
+    pub fn preview_paths(&self) -> Vec<(PathBuf, String)> {
+        match &self.source {
+            RecoverySource::Snapshot(snapshot) => snapshot
+                .items
+                .iter()
+                .map(|item| (snapshot.stored_path(item), file_name(&item.original_path)))
+                .collect(),
+            RecoverySource::Cloud(file) => vec![(file.path.clone(), file.name.clone())],
+            RecoverySource::BackupFile { path, .. } | RecoverySource::PrefixFile { path } => vec![(path.clone(), file_name(path))],
+        }
+    }
+
+    // Whether the user has to pick which save location it's restored into
+    pub fn needs_target(&self) -> bool {
+        matches!(self.source, RecoverySource::Cloud(_) | RecoverySource::PrefixFile { .. })
+    }
+
+    // Paths the restore overwrites, these get the safety snapshot
+    pub fn destinations(&self, target_dir: Option<&Path>) -> Vec<PathBuf> {
+        match &self.source {
+            RecoverySource::Snapshot(snapshot) => snapshot.items.iter().map(|item| item.original_path.clone()).collect(),
+            RecoverySource::Cloud(file) => target_dir.map(|dir| dir.join(&file.name)).into_iter().collect(),
+            RecoverySource::BackupFile { original, .. } => vec![original.clone()],
+            RecoverySource::PrefixFile { path } => target_dir
+                .zip(path.file_name())
+                .map(|(dir, name)| dir.join(name))
+                .into_iter()
+                .collect(),
+        }
+    }
+
+    // Snapshot what's there now, then copy the candidate back. Returns the
+    // restored paths.
+    pub fn restore(&self, config: &Config, app_id: &str, target_dir: Option<&Path>) -> Result<Vec<PathBuf>> {
+        if self.needs_target() && target_dir.is_none() {
+            bail!("No save folder chosen to restore into");
+        }
+        if let (RecoverySource::Cloud(file), Some(target_dir)) = (&self.source, target_dir) {
+            // Takes its own snapshot
+            return Ok(vec![cloud::restore_cloud_file(config, app_id, file, target_dir)?]);
+        }
+
+        let destinations = self.destinations(target_dir);
+        // Snapshots check by themselves once they're unpacked
+        if let RecoverySource::BackupFile { path, .. } | RecoverySource::PrefixFile { path } = &self.source {
+            fsutil::ensure_free_space(destinations.iter().map(|destination| (destination.clone(), fsutil::copy_growth(path, destination))))?;
+        }
+        Snapshot::create_unpruned(config, app_id, "Before recovering a save", &destinations)
+            .context("Failed to snapshot the current saves, not restoring")?;
+        let result = match &self.source {
+            RecoverySource::Snapshot(snapshot) => snapshot.restore(),
+            RecoverySource::BackupFile { path, .. } | RecoverySource::PrefixFile { path } => {
+                destinations.iter().try_for_each(|destination| {
+                    fsutil::copy_recursive(path, destination)?;
+                    println!("Recovered {} to {}", path.display(), destination.display());
+                    Ok(())
+                })
+            }
+            RecoverySource::Cloud(_) => Ok(()),
+        };
+        // Pruned only now, the new snapshot may push the one being restored past
+        // the retention limits
+        if let Err(e) = snapshot::prune(config) {
+            eprintln!("Failed to prune old safety snapshots: {}", e);
+        }
+        result?;
+        activity::record(
+            config,
+            ActivityEntry::new(ActivityKind::Restore, format!("Recovered {}", self.label()))
+                .game(config, app_id)
+                .paths(destinations.iter().cloned())
+                .bytes(destinations.iter().map(|path| fsutil::disk_usage(path)).sum()),
+        );
+        Ok(destinations)
+    }
+}
+
+// Restore a single file or folder of a snapshot item, e.g. the one save slot
+// that got corrupted. What it replaces is snapshotted first, like `restore`.
+pub fn restore_snapshot_path(config: &Config, app_id: &str, snapshot: &Snapshot, item: &SnapshotItem, relative: &Path) -> Result<PathBuf> {
+    let destination = snapshot.original_path_of(item, relative);
+    Snapshot::create_unpruned(config, app_id, "Before restoring a file", std::slice::from_ref(&destination))
+        .context("Failed to snapshot the current file, not restoring")?;
+    let result = snapshot.restore_path(item, relative);
+    if let Err(e) = snapshot::prune(config) {
+        eprintln!("Failed to prune old safety snapshots: {}", e);
+    }
+    if let Ok(restored) = &result {
+        activity::record(
+            config,
+            ActivityEntry::new(ActivityKind::Restore, format!("Restored {} from a safety snapshot", file_name(restored)))
+                .game(config, app_id)
+                .paths([restored.clone()])
+                .bytes(fsutil::disk_usage(restored)),
+        );
+    }
+    result
+}
+
+// Everything that might hold a lost save of `game`: safety snapshots, Steam
+// Cloud copies, backup files in its save folders and recently written files
+// elsewhere in its prefix. Each group is sorted newest first.
+pub fn find_candidates(config: &Config, game: &RecoverGame) -> Vec<RecoveryCandidate> {
+    let mut candidates: Vec<RecoveryCandidate> = snapshot::list_snapshots(config)
+        .into_iter()
+        .filter(|snapshot| snapshot.game_id == game.app_id)
+        .map(|snapshot| RecoveryCandidate {
+            size: snapshot.size(),
+            modified: Some(snapshot.created),
+            source: RecoverySource::Snapshot(snapshot),
+        })
+        .collect();
+
+    let mut cloud_files: Vec<RecoveryCandidate> = cloud::list_cloud_files(config, &game.app_id)
+        .into_iter()
+        .map(|file| RecoveryCandidate {
+            size: file.size,
+            modified: file.synced,
+            source: RecoverySource::Cloud(file),
+        })
+        .collect();
+    sort_newest_first(&mut cloud_files);
+    candidates.append(&mut cloud_files);
+
+    let mut backups = Vec::new();
+    for location in &game.locations {
+        let mut files = Vec::new();
+        collect_files(location, 0, &mut files);
+        for path in files {
+            if let Some(source) = backup_source(path) {
+                backups.push(candidate_for(source));
+            }
+        }
+    }
+    sort_newest_first(&mut backups);
+    candidates.append(&mut backups);
+
+    let user_path = config.drive_c_path(&game.app_id).join("users/steamuser");
+    let newer_than = now_secs().saturating_sub(RECENT_DAYS * 24 * 60 * 60);
+    let mut files = Vec::new();
+    collect_files(&user_path, 0, &mut files);
+    let mut recent: Vec<RecoveryCandidate> = files
+        .into_iter()
+        .filter(|path| !game.locations.iter().any(|location| path.starts_with(location)))
+        .map(|path| candidate_for(RecoverySource::PrefixFile { path }))
+        .filter(|candidate| candidate.modified.is_some_and(|modified| modified >= newer_than))
+        .collect();
+    sort_newest_first(&mut recent);
+    recent.truncate(MAX_RECENT_FILES);
+    candidates.append(&mut recent);
+
+    println!("Found {} recovery candidates for {}", candidates.len(), game.app_id);
+    candidates
+}
+
+// Backup files restore over the file they were made from when that can be told
+// from the name, other autosave/backup files need a save folder picked
+fn backup_source(path: PathBuf) -> Option<RecoverySource> {
+    let file_name = path.file_name()?.to_string_lossy().to_string();
+    if let Some(original) = file_name.strip_suffix('~') {
+        let original = path.with_file_name(original);
+        return Some(RecoverySource::BackupFile { path, original });
+    }
+    let name = file_name.to_lowercase();
+    let extension = path.extension().map(|e| e.to_string_lossy().to_lowercase());
+    if extension.is_some_and(|e| BACKUP_EXTENSIONS.contains(&e.as_str())) {
+        let original = path.with_extension("");
+        // "save.bak" has nothing left to tell which file it belongs to
+        if original.extension().is_some() {
+            return Some(RecoverySource::BackupFile { path, original });
+        }
+        return Some(RecoverySource::PrefixFile { path });
+    }
+    if BACKUP_NAME_HINTS.iter().any(|hint| name.contains(hint)) {
+        return Some(RecoverySource::PrefixFile { path });
+    }
+    None
+}
+
+fn candidate_for(source: RecoverySource) -> RecoveryCandidate {
+    let path = match &source {
+        RecoverySource::BackupFile { path, .. } | RecoverySource::PrefixFile { path } => path.clone(),
+        _ => PathBuf::new(),
+    };
+    RecoveryCandidate {
+        size: fsutil::disk_usage(&path),
+        modified: fsutil::last_modified(&path),
+        source,
+    }
+}
+
+// Regular files below `dir`, not following symlinks (prefixes link back into
+// the Linux home folder)
+fn collect_files(dir: &Path, depth: usize, files: &mut Vec<PathBuf>) {
+    if depth > MAX_SCAN_DEPTH {
+        return;
+    }
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_dir() {
+            if !SKIPPED_FOLDERS.iter().any(|skipped| entry.file_name() == *skipped) {
+                collect_files(&entry.path(), depth + 1, files);
+            }
+        } else if file_type.is_file() {
+            files.push(entry.path());
+        }
+    }
+}
+
+pub fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+fn sort_newest_first(candidates: &mut [RecoveryCandidate]) {
+    candidates.sort_by_key(|candidate| std::cmp::Reverse(candidate.modified));
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
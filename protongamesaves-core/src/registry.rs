@@ -0,0 +1,272 @@
+use anyhow::{anyhow, bail, Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::config::Config;
+use crate::manifest::GameEntry;
+use crate::snapshot::Snapshot;
+
+// Some games keep their progress in the Windows registry instead of files. The
+// manifest lists those keys (e.g. `HKEY_CURRENT_USER/Software/Studio/Game`),
+// Wine stores them in the prefix's user.reg and system.reg:
+//
+//   [Software\\Studio\\Game] 1700000000
+//   #time=1d9a...
+//   "Progress"=dword:00000003
+//
+// They're exported into a regedit style .reg file and imported back by
+// replacing the keys in those files. Wine rewrites them when the prefix shuts
+// down, so imports only stick while the game isn't running.
+
+// Name of the exported keys in a save bundle
+pub const EXPORT_FILE_NAME: &str = "registry.reg";
+const REG_HEADER: &str = "Windows Registry Editor Version 5.00";
+
+// The Wine registry files and the hive each one holds
+const HIVES: [(&str, &str); 2] = [("HKEY_CURRENT_USER", "user.reg"), ("HKEY_LOCAL_MACHINE", "system.reg")];
+
+// One `[key]` section and the value lines below it
+struct Section {
+    hive: &'static str,
+    key: String, // Below the hive, with single backslashes
+    written: Option<String>, // Wine's timestamp after the key, None for imported keys
+    lines: Vec<String>,
+}
+
+impl Section {
+    // `key` is `root` or one of its subkeys
+    fn is_under(&self, hive: &str, root: &str) -> bool {
+        let key = self.key.to_lowercase();
+        let root = root.to_lowercase();
+        self.hive == hive && (key == root || key.starts_with(&format!("{}\\", root)))
+    }
+}
+
+// Registry keys the manifest lists for the game, e.g.
+// `HKEY_CURRENT_USER\Software\Studio\Game`. Keys in hives Wine doesn't keep
+// per prefix are left out.
+pub fn manifest_keys(entry: &GameEntry) -> Vec<String> {
+    let mut keys: Vec<String> = parse_keys(entry.registry.iter().flat_map(|registry| registry.keys()))
+        .into_iter()
+        .map(|(hive, key)| format!("{}\\{}", hive, key))
+        .collect();
+    keys.sort();
+    keys
+}
+
+// Keys as (hive, key below it), with `/` or `\` between the parts
+fn parse_keys<S: AsRef<str>>(keys: impl IntoIterator<Item = S>) -> Vec<(&'static str, String)> {
+    let mut keys: Vec<(&'static str, String)> = keys.into_iter().filter_map(|key| parse_key(&key.as_ref().replace('/', "\\"))).collect();
+    keys.sort();
+    keys
+}
+
+// "HKEY_CURRENT_USER\Software\Game" (or HKCU\…) into its hive and the rest
+fn parse_key(key: &str) -> Option<(&'static str, String)> {
+    let (hive, rest) = key.split_once('\\').unwrap_or((key, ""));
+    let hive = match hive.to_uppercase().as_str() {
+        "HKEY_CURRENT_USER" | "HKCU" => HIVES[0].0,
+        "HKEY_LOCAL_MACHINE" | "HKLM" => HIVES[1].0,
+        _ => return None,
+    };
+    let rest = rest.trim_matches('\\');
+    (!rest.is_empty()).then(|| (hive, rest.to_string()))
+}
+
+fn hive_file(pfx: &Path, hive: &str) -> PathBuf {
+    let file = HIVES.iter().find(|(name, _)| *name == hive).map(|(_, file)| *file).unwrap_or("user.reg");
+    pfx.join(file)
+}
+
+// The registry files `keys` live in, for a safety snapshot before an import
+pub fn hive_files(pfx: &Path, keys: &[String]) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = parse_keys(keys).iter().map(|(hive, _)| hive_file(pfx, hive)).collect();
+    files.dedup();
+    files
+}
+
+// Sections of a Wine registry file, the header lines before them are kept apart
+fn parse_wine(hive: &'static str, content: &str) -> (Vec<String>, Vec<Section>) {
+    let mut header = Vec::new();
+    let mut sections: Vec<Section> = Vec::new();
+    for line in content.lines() {
+        if let Some(rest) = line.strip_prefix('[') {
+            // `[Software\\Game] 1700000000`, the number is when it was written
+            let (key, written) = rest.rsplit_once(']').unwrap_or((rest, ""));
+            let written = Some(written.trim().to_string()).filter(|written| !written.is_empty());
+            sections.push(Section { hive, key: key.replace("\\\\", "\\"), written, lines: Vec::new() });
+        } else if let Some(section) = sections.last_mut() {
+            if !line.is_empty() {
+                section.lines.push(line.to_string());
+            }
+        } else {
+            header.push(line.to_string());
+        }
+    }
+    (header, sections)
+}
+
+fn write_wine(header: &[String], sections: &[Section]) -> String {
+    let now = now_secs().to_string();
+    let mut content = header.join("\n");
+    content.push('\n');
+    for section in sections {
+        let written = section.written.as_deref().unwrap_or(&now);
+        content.push_str(&format!("\n[{}] {}\n", section.key.replace('\\', "\\\\"), written));
+        for line in &section.lines {
+            content.push_str(line);
+            content.push('\n');
+        }
+    }
+    content
+}
+
+// The game's keys in the prefix with pfx at `pfx` as a .reg file, None if the
+// prefix has none of them (the game hasn't written them yet)
+pub fn export(pfx: &Path, keys: &[String]) -> Result<Option<String>> {
+    let keys = parse_keys(keys);
+    let mut content = format!("{}\n", REG_HEADER);
+    let mut found = 0;
+    for (hive, _) in HIVES {
+        if !keys.iter().any(|(key_hive, _)| *key_hive == hive) {
+            continue;
+        }
+        let path = hive_file(pfx, hive);
+        let Ok(file) = fs::read_to_string(&path) else {
+            continue;
+        };
+        let (_, sections) = parse_wine(hive, &file);
+        for section in sections.iter().filter(|section| keys.iter().any(|(key_hive, root)| section.is_under(key_hive, root))) {
+            content.push_str(&format!("\n[{}\\{}]\n", hive, section.key));
+            // `#time=` and other comments are Wine's own bookkeeping
+            for line in section.lines.iter().filter(|line| !line.starts_with('#')) {
+                content.push_str(line);
+                content.push('\n');
+            }
+            found += 1;
+        }
+    }
+    println!("Exported {} registry keys from {}", found, pfx.display());
+    Ok((found > 0).then_some(content))
+}
+
+// Sections of a .reg file made by `export` or regedit
+fn parse_reg(content: &str) -> Result<Vec<Section>> {
+    let mut lines = content.trim_start_matches('\u{feff}').lines();
+    if lines.next().map(str::trim) != Some(REG_HEADER) {
+        bail!("Not a registry file, it doesn't start with \"{}\"", REG_HEADER);
+    }
+    let mut sections: Vec<Section> = Vec::new();
+    for line in lines {
+        let line = line.trim_end_matches('\r');
+        if let Some(rest) = line.strip_prefix('[') {
+            let key = rest.trim_end().strip_suffix(']').ok_or_else(|| anyhow!("Broken key line in the registry file: {}", line))?;
+            if key.starts_with('-') {
+                bail!("The registry file deletes keys ({}), that's not supported", key);
+            }
+            let (hive, key) = parse_key(key).ok_or_else(|| anyhow!("Unsupported registry key {}", key))?;
+            sections.push(Section { hive, key, written: None, lines: Vec::new() });
+        } else if let Some(section) = sections.last_mut() {
+            if !line.trim().is_empty() {
+                section.lines.push(line.to_string());
+            }
+        }
+    }
+    Ok(sections)
+}
+
+// Replaces the game's keys in the prefix with the ones in `reg`, a .reg file
+// from `export`. Only keys below the game's own `keys` are written, each one
+// found in the file replaces that key with all its subkeys. Returns the
+// number of keys written. Take a safety snapshot of `hive_files` first.
+pub fn import(pfx: &Path, keys: &[String], reg: &str) -> Result<usize> {
+    let keys = parse_keys(keys);
+    let mut imported = parse_reg(reg)?;
+    if let Some(foreign) = imported.iter().find(|section| !keys.iter().any(|(hive, root)| section.is_under(hive, root))) {
+        bail!("The registry file has {}\\{}, which isn't one of this game's keys", foreign.hive, foreign.key);
+    }
+
+    let mut written = 0;
+    for (hive, _) in HIVES {
+        let (sections, rest): (Vec<Section>, Vec<Section>) = imported.into_iter().partition(|section| section.hive == hive);
+        imported = rest;
+        if sections.is_empty() {
+            continue;
+        }
+        let path = hive_file(pfx, hive);
+        let content = fs::read_to_string(&path).context(format!("Failed to read {}", path.display()))?;
+        let (header, mut existing) = parse_wine(hive, &content);
+        // The keys being replaced go completely, so subkeys the saved copy
+        // didn't have don't linger
+        let replaced: Vec<&(&'static str, String)> = keys
+            .iter()
+            .filter(|(key_hive, root)| sections.iter().any(|section| section.is_under(key_hive, root)))
+            .collect();
+        existing.retain(|section| !replaced.iter().any(|(key_hive, root)| section.is_under(key_hive, root)));
+        written += sections.len();
+        existing.extend(sections);
+
+        // Written next to it and renamed over, a failed write leaves the old file
+        let temp = path.with_extension("reg.tmp");
+        fs::write(&temp, write_wine(&header, &existing)).context(format!("Failed to write {}", temp.display()))?;
+        fs::rename(&temp, &path).context(format!("Failed to replace {}", path.display()))?;
+    }
+    println!("Imported {} registry keys into {}", written, pfx.display());
+    Ok(written)
+}
+
+// Imports the .reg file at `reg_path` into the game's prefix, after a safety
+// snapshot of the registry files it changes
+pub fn import_file(config: &Config, app_id: &str, keys: &[String], reg_path: &Path) -> Result<usize> {
+    let reg = fs::read_to_string(reg_path).context(format!("Failed to read {}", reg_path.display()))?;
+    let pfx = config.prefix_path(app_id).join("pfx");
+    Snapshot::create(config, app_id, "Before importing registry saves", &hive_files(&pfx, keys))
+        .context("Failed to snapshot the prefix's registry, not importing")?;
+    import(&pfx, keys, &reg)
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{write_file, Fixture};
+
+    const USER_REG: &str = "WINE REGISTRY Version 2\n;; All keys relative to \\\\User\\\\S-1-5-21-0-0-0-1000\n\n#arch=win64\n\n\
+        [Software\\\\Other] 1700000000\n\"Keep\"=\"me\"\n\n\
+        [Software\\\\Studio\\\\Game] 1700000000\n#time=1d9a\n\"Progress\"=dword:00000003\n\n\
+        [Software\\\\Studio\\\\Game\\\\Slots] 1700000000\n\"Slot1\"=hex:01,02,\\\n  03,04\n";
+
+    #[test]
+    fn exports_and_imports_game_keys() {
+        let fixture = Fixture::new();
+        let pfx = fixture.add_prefix("5000").join("pfx");
+        write_file(&pfx.join("user.reg"), USER_REG);
+        let keys = vec!["HKEY_CURRENT_USER/Software/Studio/Game".to_string()];
+
+        let reg = export(&pfx, &keys).unwrap().unwrap();
+        assert!(reg.starts_with(REG_HEADER));
+        assert!(reg.contains("[HKEY_CURRENT_USER\\Software\\Studio\\Game\\Slots]\n\"Slot1\"=hex:01,02,\\\n  03,04\n"));
+        assert!(!reg.contains("Other") && !reg.contains("#time"));
+
+        // The game changes its progress and adds a key, importing puts it back
+        let changed = USER_REG.replace("dword:00000003", "dword:00000009") + "\n[Software\\\\Studio\\\\Game\\\\New] 1700000001\n\"X\"=\"y\"\n";
+        write_file(&pfx.join("user.reg"), &changed);
+        assert_eq!(import(&pfx, &keys, &reg).unwrap(), 2);
+        let restored = fs::read_to_string(pfx.join("user.reg")).unwrap();
+        assert!(restored.starts_with("WINE REGISTRY Version 2\n"));
+        assert!(restored.contains("\"Progress\"=dword:00000003") && !restored.contains("00000009"));
+        assert!(!restored.contains("Game\\\\New") && restored.contains("\"Keep\"=\"me\""));
+
+        // Keys of other games aren't written
+        let foreign = format!("{}\n\n[HKEY_CURRENT_USER\\Software\\Other]\n\"Keep\"=\"no\"\n", REG_HEADER);
+        assert!(import(&pfx, &keys, &foreign).is_err());
+        assert_eq!(export(&pfx, &["HKCU\\Software\\Missing".to_string()]).unwrap(), None);
+    }
+}
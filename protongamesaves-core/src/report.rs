@@ -0,0 +1,91 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+// One save location of one game, as written to an inventory report
+#[derive(Serialize)]
+pub struct ReportEntry {
+    pub game_name: String,
+    pub app_id: String,
+    pub manifest_path: String,
+    pub save_path: String,
+    pub exists: bool,
+    pub size_bytes: u64,
+    pub last_modified: Option<String>, // ISO 8601 (UTC)
+}
+
+// Output format, picked from the file extension the user chose
+pub enum ReportFormat {
+    Csv,
+    Json,
+}
+
+impl ReportFormat {
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("json") => Self::Json,
+            _ => Self::Csv,
+        }
+    }
+}
+
+pub fn write_report(entries: &[ReportEntry], path: &Path) -> Result<()> {
+    let content = match ReportFormat::from_path(path) {
+        ReportFormat::Json => serde_json::to_string_pretty(entries)?,
+        ReportFormat::Csv => to_csv(entries),
+    };
+    fs::write(path, content).context(format!("Failed to write report to {}", path.display()))?;
+    println!("Wrote report with {} entries to {}", entries.len(), path.display());
+    Ok(())
+}
+
+fn to_csv(entries: &[ReportEntry]) -> String {
+    let mut csv = String::from("game_name,app_id,manifest_path,save_path,exists,size_bytes,last_modified\n");
+    for entry in entries {
+        let fields = [
+            csv_field(&entry.game_name),
+            csv_field(&entry.app_id),
+            csv_field(&entry.manifest_path),
+            csv_field(&entry.save_path),
+            entry.exists.to_string(),
+            entry.size_bytes.to_string(),
+            entry.last_modified.clone().unwrap_or_default(),
+        ];
+        csv.push_str(&fields.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+// Quote a field if it contains anything CSV treats specially
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+// Format a Unix timestamp as ISO 8601 in UTC, e.g. 2024-05-01T13:37:00Z
+pub fn format_timestamp_utc(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+    let (hour, minute, second) = (rem / 3600, (rem % 3600) / 60, rem % 60);
+
+    // Civil-from-days (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year, month, day, hour, minute, second
+    )
+}
@@ -0,0 +1,74 @@
+use anyhow::{anyhow, bail, Context, Result};
+use std::path::Path;
+use std::process::Command;
+
+// External editors for save files, set per file extension in Settings, e.g. a
+// hex editor for `.sav` or a save editor for one game's `.ess`. Entries are
+// written "extension: command", `{file}` in the command is replaced by the
+// file, otherwise the file is added at the end.
+
+// What an editor is looked up by: the extension in lower case, without the dot
+pub fn extension_key(path: &Path) -> Option<String> {
+    path.extension().map(|extension| extension.to_string_lossy().to_lowercase())
+}
+
+// An "extension: command" entry from Settings, e.g. ".sav: imhex"
+pub fn parse_entry(entry: &str) -> Result<(String, String)> {
+    let (extension, command) = entry
+        .split_once(':')
+        .ok_or_else(|| anyhow!("Editor entries are written \"extension: command\", e.g. \"sav: imhex\", not {}", entry))?;
+    let extension = extension.trim().trim_start_matches('.').to_lowercase();
+    let command = command.trim();
+    if extension.is_empty() || extension.contains(['/', '.', ' ']) {
+        bail!("Not a file extension: {}", extension);
+    }
+    if command.is_empty() {
+        bail!("No editor command given for .{} files", extension);
+    }
+    Ok((extension, command.to_string()))
+}
+
+pub fn format_entry(extension: &str, command: &str) -> String {
+    format!("{}: {}", extension, command)
+}
+
+// Program and arguments for opening `path` with `command`. Arguments are split
+// at spaces, no shell is involved so the file name can't be misread.
+pub fn command_line(command: &str, path: &Path) -> Vec<String> {
+    let file = path.to_string_lossy();
+    let mut args: Vec<String> = command.split_whitespace().map(|arg| arg.replace("{file}", &file)).collect();
+    if !command.contains("{file}") {
+        args.push(file.to_string());
+    }
+    args
+}
+
+// Starts the editor, doesn't wait for it to be closed
+pub fn open(command: &str, path: &Path) -> Result<()> {
+    let args = command_line(command, path);
+    let (program, args) = args.split_first().ok_or_else(|| anyhow!("The editor command is empty"))?;
+    println!("Opening {} with {}", path.display(), program);
+    Command::new(program)
+        .args(args)
+        .spawn()
+        .context(format!("Failed to start {}, is it installed?", program))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_entries_and_builds_command_lines() {
+        assert_eq!(parse_entry(" .SAV :  imhex ").unwrap(), ("sav".to_string(), "imhex".to_string()));
+        assert!(parse_entry("imhex").is_err());
+        assert!(parse_entry("sav:").is_err());
+        assert!(parse_entry("tar.gz: file-roller").is_err());
+
+        let path = Path::new("/saves/Slot 1.sav");
+        assert_eq!(extension_key(path), Some("sav".to_string()));
+        assert_eq!(command_line("imhex", path), ["imhex", "/saves/Slot 1.sav"]);
+        assert_eq!(command_line("editor --open={file} --ro", path), ["editor", "--open=/saves/Slot 1.sav", "--ro"]);
+    }
+}
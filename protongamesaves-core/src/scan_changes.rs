@@ -0,0 +1,125 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+// What the last scan found, kept as JSON in the data dir, so the next scan
+// (also after a restart) can say what changed since: prefixes that appeared or
+// went away, and games whose saves were written in between.
+#[derive(Serialize, Deserialize, Default)]
+pub struct ScanRecord {
+    prefixes: Vec<String>, // App IDs, sorted
+    saves: HashMap<String, SaveState>, // Keyed by App ID
+}
+
+// Enough of a game's saves to notice they were written
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub struct SaveState {
+    pub bytes: u64,
+    pub modified: Option<u64>, // Newest file, Unix seconds
+}
+
+// One matched game of the current scan
+pub struct GameSaves {
+    pub app_id: String,
+    pub name: String,
+    pub state: SaveState,
+}
+
+#[derive(Default, Debug)]
+pub struct ScanChanges {
+    pub new_prefixes: Vec<String>, // App IDs
+    pub removed_prefixes: Vec<String>,
+    pub changed_saves: Vec<String>, // Game names, sorted
+}
+
+impl ScanChanges {
+    pub fn is_empty(&self) -> bool {
+        self.new_prefixes.is_empty() && self.removed_prefixes.is_empty() && self.changed_saves.is_empty()
+    }
+}
+
+impl ScanRecord {
+    // None before the first scan, or if the file can't be read; there's
+    // nothing to compare with then
+    pub fn load(path: &Path) -> Option<Self> {
+        let content = fs::read_to_string(path).ok()?;
+        serde_json::from_str(&content)
+            .map_err(|e| eprintln!("Ignoring unreadable scan record {}: {}", path.display(), e))
+            .ok()
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context(format!("Failed to create {}", parent.display()))?;
+        }
+        fs::write(path, serde_json::to_string(self)?).context(format!("Failed to write scan record to {}", path.display()))
+    }
+
+    // The record of a scan that found `prefixes`. Without `saves` (games
+    // weren't matched this time) the previous record's saves are kept.
+    pub fn new(prefixes: &[String], saves: Option<&[GameSaves]>, previous: Option<&ScanRecord>) -> Self {
+        let mut prefixes = prefixes.to_vec();
+        prefixes.sort();
+        let saves = match saves {
+            Some(saves) => saves.iter().map(|game| (game.app_id.clone(), game.state)).collect(),
+            None => previous.map(|previous| previous.saves.clone()).unwrap_or_default(),
+        };
+        Self { prefixes, saves }
+    }
+
+    // What's different in `current` compared to this earlier record. Games
+    // only count as changed if they were matched both times.
+    pub fn changes(&self, current: &ScanRecord, names: &[GameSaves]) -> ScanChanges {
+        let mut changed_saves: Vec<String> = names
+            .iter()
+            .filter(|game| {
+                let before = self.saves.get(&game.app_id);
+                let now = current.saves.get(&game.app_id);
+                before.is_some() && now.is_some() && before != now
+            })
+            .map(|game| game.name.clone())
+            .collect();
+        changed_saves.sort_by_key(|name| name.to_lowercase());
+        ScanChanges {
+            new_prefixes: current.prefixes.iter().filter(|id| !self.prefixes.contains(id)).cloned().collect(),
+            removed_prefixes: self.prefixes.iter().filter(|id| !current.prefixes.contains(id)).cloned().collect(),
+            changed_saves,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::Fixture;
+
+    fn game(app_id: &str, name: &str, bytes: u64, modified: u64) -> GameSaves {
+        GameSaves { app_id: app_id.to_string(), name: name.to_string(), state: SaveState { bytes, modified: Some(modified) } }
+    }
+
+    #[test]
+    fn reports_new_removed_and_changed() {
+        let fixture = Fixture::new();
+        let path = fixture.root.join("data/last_scan.json");
+        assert!(ScanRecord::load(&path).is_none());
+
+        let ids = |ids: &[&str]| ids.iter().map(|id| id.to_string()).collect::<Vec<String>>();
+        let first = [game("1000", "Lonely Hero", 10, 100), game("2000", "Quiet Game", 5, 100)];
+        ScanRecord::new(&ids(&["1000", "2000", "3000"]), Some(&first), None).save(&path).unwrap();
+        let previous = ScanRecord::load(&path).unwrap();
+
+        // A save written, a prefix deleted, a new game installed
+        let second = [game("1000", "Lonely Hero", 10, 200), game("2000", "Quiet Game", 5, 100), game("4000", "New Game", 1, 300)];
+        let current = ScanRecord::new(&ids(&["1000", "2000", "4000"]), Some(&second), Some(&previous));
+        let changes = previous.changes(&current, &second);
+        assert_eq!(changes.new_prefixes, ["4000"]);
+        assert_eq!(changes.removed_prefixes, ["3000"]);
+        assert_eq!(changes.changed_saves, ["Lonely Hero"]);
+
+        // Without matching, the saves of the last record carry over
+        let unmatched = ScanRecord::new(&ids(&["1000", "2000", "4000"]), None, Some(&current));
+        assert!(current.changes(&unmatched, &second).is_empty());
+    }
+}
@@ -0,0 +1,135 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::fsutil;
+use crate::preview::IMAGE_EXTENSIONS;
+
+// Fewer images than this are likely thumbnails of save slots, not screenshots
+pub const MIN_SCREENSHOTS: usize = 3;
+// Save folders aren't deep, this keeps a stray link to a big tree from taking long
+const MAX_DEPTH: usize = 6;
+
+// An image found under a save location
+#[derive(Clone)]
+pub struct Screenshot {
+    pub path: PathBuf,
+    pub relative: PathBuf, // Below the save location, keeps exported copies apart
+    pub size: u64,
+    pub modified: Option<u64>, // Unix timestamp (seconds)
+}
+
+pub fn is_image(path: &Path) -> bool {
+    path.extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .is_some_and(|extension| IMAGE_EXTENSIONS.contains(&extension.as_str()))
+}
+
+// How many images are at or below `location`, counted during the scan to tell
+// which save folders hold screenshots
+pub fn count_screenshots(location: &Path) -> usize {
+    let mut images = Vec::new();
+    collect_images(location, PathBuf::new(), 0, &mut images);
+    images.len()
+}
+
+// Every image at or below `location`, newest first
+pub fn find_screenshots(location: &Path) -> Vec<Screenshot> {
+    let mut images = Vec::new();
+    collect_images(location, PathBuf::new(), 0, &mut images);
+    let mut screenshots: Vec<Screenshot> = images
+        .into_iter()
+        .map(|(path, relative)| Screenshot {
+            size: fsutil::disk_usage(&path),
+            modified: fsutil::last_modified(&path),
+            path,
+            relative,
+        })
+        .collect();
+    screenshots.sort_by(|a, b| b.modified.cmp(&a.modified).then(a.relative.cmp(&b.relative)));
+    screenshots
+}
+
+// Without following symlinks, like the size calculation
+fn collect_images(path: &Path, relative: PathBuf, depth: usize, images: &mut Vec<(PathBuf, PathBuf)>) {
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        return;
+    };
+    if metadata.is_file() {
+        if is_image(path) {
+            // A location that is a single image file keeps its own name
+            let relative = if relative.as_os_str().is_empty() {
+                path.file_name().map(PathBuf::from).unwrap_or_default()
+            } else {
+                relative
+            };
+            images.push((path.to_path_buf(), relative));
+        }
+        return;
+    }
+    if !metadata.is_dir() || depth >= MAX_DEPTH {
+        return;
+    }
+    let Ok(entries) = fs::read_dir(path) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        collect_images(&entry.path(), relative.join(entry.file_name()), depth + 1, images);
+    }
+}
+
+// Where a game's screenshots are exported to by default, a folder per game in ~/Pictures
+pub fn default_export_dir(game_name: &str) -> PathBuf {
+    dirs::picture_dir()
+        .or_else(|| dirs::home_dir().map(|home| home.join("Pictures")))
+        .unwrap_or_else(|| PathBuf::from("Pictures"))
+        .join("Proton Game Saves")
+        .join(fsutil::sanitize_file_name(game_name))
+}
+
+// Copies the screenshots into `dest`, keeping their folders below the save
+// location. Files already exported with the same size are skipped, so
+// exporting again only adds the new ones. Returns how many were copied.
+pub fn export(screenshots: &[Screenshot], dest: &Path) -> Result<usize> {
+    let mut copied = 0;
+    for screenshot in screenshots {
+        let target = dest.join(&screenshot.relative);
+        if fs::metadata(&target).is_ok_and(|metadata| metadata.len() == screenshot.size) {
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).context(format!("Failed to create {}", parent.display()))?;
+        }
+        fs::copy(&screenshot.path, &target)
+            .context(format!("Failed to copy {} to {}", screenshot.path.display(), target.display()))?;
+        copied += 1;
+    }
+    println!("Exported {} screenshots to {}", copied, dest.display());
+    Ok(copied)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::Fixture;
+
+    #[test]
+    fn finds_and_exports_images_only() {
+        let fixture = Fixture::new();
+        fixture.add_prefix("1000");
+        let saves = "AppData/Roaming/LonelyHero/Saves";
+        fixture.add_file("1000", &format!("{}/slot1.sav", saves), "save");
+        fixture.add_file("1000", &format!("{}/shots/one.png", saves), "png");
+        fixture.add_file("1000", &format!("{}/shots/two.JPG", saves), "jpg");
+        let location = fixture.compatdata_path().join("1000/pfx/drive_c/users/steamuser").join(saves);
+
+        assert_eq!(count_screenshots(&location), 2);
+        let screenshots = find_screenshots(&location);
+        let dest = fixture.root.join("Pictures/Lonely Hero");
+        assert_eq!(export(&screenshots, &dest).unwrap(), 2);
+        assert!(dest.join("shots/one.png").is_file());
+        assert!(!dest.join("slot1.sav").exists());
+        // Already there, nothing new to copy
+        assert_eq!(export(&screenshots, &dest).unwrap(), 0);
+    }
+}
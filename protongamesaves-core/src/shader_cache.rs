@@ -0,0 +1,93 @@
+use crate::fsutil;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// DXVK and vkd3d-proton write shader caches that can grow to hundreds of MB
+// and are rebuilt on their own, so they're listed apart from saves and left
+// out of snapshots and bundles:
+//
+//   <anything>/<game>.dxvk-cache                     <- DXVK state cache, next to the game or in a save folder
+//   <anything>/vkd3d-proton.cache(.write)            <- vkd3d-proton pipeline cache
+//   steamapps/shadercache/<app id>/                  <- Steam's own, DXVK_state_cache and fozpipelines inside
+const DXVK_EXTENSION: &str = "dxvk-cache";
+const VKD3D_PREFIX: &str = "vkd3d-proton.cache";
+// Deep enough for AppData/Local/<Company>/<Game>/Cache, the Windows folder
+// itself has no caches and is skipped
+const MAX_DEPTH: usize = 8;
+
+pub struct ShaderCache {
+    pub path: PathBuf,
+    pub size: u64,
+}
+
+// Whether a file is a shader cache by its name
+pub fn is_cache_file(path: &Path) -> bool {
+    let name = path.file_name().map(|name| name.to_string_lossy()).unwrap_or_default();
+    name.starts_with(VKD3D_PREFIX) || path.extension().is_some_and(|extension| extension == DXVK_EXTENSION)
+}
+
+// Shader caches of the prefix at `prefix_path` (compatdata/<app id>), the
+// ones inside it and Steam's folder for the game in the same library
+pub fn find(prefix_path: &Path) -> Vec<ShaderCache> {
+    let mut found = Vec::new();
+    let drive_c = prefix_path.join("pfx/drive_c");
+    if let Ok(entries) = fs::read_dir(&drive_c) {
+        for entry in entries.flatten() {
+            if !entry.file_name().eq_ignore_ascii_case("windows") {
+                collect(&entry.path(), MAX_DEPTH, &mut found);
+            }
+        }
+    }
+    // compatdata/<app id> -> steamapps/shadercache/<app id>
+    let steam_cache = prefix_path
+        .parent()
+        .and_then(Path::parent)
+        .zip(prefix_path.file_name())
+        .map(|(steamapps, app_id)| steamapps.join("shadercache").join(app_id));
+    if let Some(path) = steam_cache.filter(|path| path.is_dir()) {
+        let size = fsutil::disk_usage(&path);
+        found.push(ShaderCache { path, size });
+    }
+    found
+}
+
+fn collect(path: &Path, depth: usize, found: &mut Vec<ShaderCache>) {
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        return;
+    };
+    if metadata.is_file() && is_cache_file(path) {
+        found.push(ShaderCache { path: path.to_path_buf(), size: metadata.len() });
+    } else if metadata.is_dir() && depth > 0 {
+        let Ok(entries) = fs::read_dir(path) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            collect(&entry.path(), depth - 1, found);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{write_file, Fixture};
+
+    #[test]
+    fn finds_caches_inside_and_next_to_the_prefix() {
+        let fixture = Fixture::new();
+        let prefix = fixture.add_prefix("1000");
+        fixture.add_file("1000", "AppData/Local/LonelyHero/LonelyHero.dxvk-cache", "cache");
+        fixture.add_file("1000", "AppData/Local/LonelyHero/vkd3d-proton.cache.write", "cache");
+        fixture.add_file("1000", "AppData/Local/LonelyHero/save.dat", "save");
+        let steam_cache = fixture.steam_path().join("steam/steamapps/shadercache/1000");
+        write_file(&steam_cache.join("DXVK_state_cache/game.dxvk-cache"), "cache");
+
+        let mut found: Vec<PathBuf> = find(&prefix).into_iter().map(|cache| cache.path).collect();
+        found.sort();
+        assert_eq!(found.len(), 3);
+        assert!(found.iter().any(|path| path.ends_with("LonelyHero.dxvk-cache")));
+        assert!(found.iter().any(|path| path.ends_with("vkd3d-proton.cache.write")));
+        assert!(found.contains(&steam_cache));
+        assert!(!is_cache_file(Path::new("save.dat")));
+    }
+}
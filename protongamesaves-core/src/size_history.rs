@@ -0,0 +1,135 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Scans this close together update the last point instead of adding one, so
+// refreshing a few times in a row doesn't flood the history
+const MERGE_WINDOW_SECS: u64 = 3600;
+// Oldest points are dropped past this, about a year and a half of daily scans
+const MAX_POINTS: usize = 500;
+
+// One measurement of a game's saves
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct SizePoint {
+    pub time: u64, // Unix seconds
+    pub bytes: u64,
+}
+
+// Save size of every matched game at each scan, kept as JSON in the data dir.
+// Shows which games' saves keep growing, like ones writing a screenshot with
+// every save.
+#[derive(Serialize, Deserialize, Default)]
+pub struct SizeHistory {
+    games: HashMap<String, Vec<SizePoint>>, // Keyed by App ID, oldest first
+}
+
+impl SizeHistory {
+    // A missing or unreadable file starts a new history
+    pub fn load(path: &Path) -> Self {
+        let Ok(content) = fs::read_to_string(path) else {
+            return Self::default();
+        };
+        serde_json::from_str(&content).unwrap_or_else(|e| {
+            eprintln!("Ignoring unreadable size history {}: {}", path.display(), e);
+            Self::default()
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context(format!("Failed to create {}", parent.display()))?;
+        }
+        let content = serde_json::to_string(self)?;
+        fs::write(path, content).context(format!("Failed to write size history to {}", path.display()))
+    }
+
+    pub fn record(&mut self, app_id: &str, bytes: u64, time: u64) {
+        let points = self.games.entry(app_id.to_string()).or_default();
+        match points.last_mut() {
+            Some(last) if time.saturating_sub(last.time) < MERGE_WINDOW_SECS => *last = SizePoint { time, bytes },
+            _ => points.push(SizePoint { time, bytes }),
+        }
+        if points.len() > MAX_POINTS {
+            points.drain(..points.len() - MAX_POINTS);
+        }
+    }
+
+    pub fn points(&self, app_id: &str) -> &[SizePoint] {
+        self.games.get(app_id).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    // How much the saves changed since `since`, measured from the last point
+    // before it (or the first one after it) to the latest. `None` without two
+    // points to compare.
+    pub fn growth_since(&self, app_id: &str, since: u64) -> Option<i64> {
+        let points = self.points(app_id);
+        let latest = points.last()?;
+        let start = points
+            .iter()
+            .rev()
+            .find(|point| point.time <= since)
+            .or_else(|| points.first())?;
+        (start.time < latest.time).then(|| latest.bytes as i64 - start.bytes as i64)
+    }
+}
+
+// Adds the sizes of one scan to the history file at `path`
+pub fn record_scan(path: &Path, sizes: &[(String, u64)]) -> Result<()> {
+    let mut history = SizeHistory::load(path);
+    let now = now_secs();
+    for (app_id, bytes) in sizes {
+        history.record(app_id, *bytes, now);
+    }
+    history.save(path)
+}
+
+pub fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::Fixture;
+
+    #[test]
+    fn scans_close_together_update_the_last_point() {
+        let mut history = SizeHistory::default();
+        history.record("1000", 100, 10_000);
+        history.record("1000", 150, 10_000 + 60);
+        assert_eq!(history.points("1000"), [SizePoint { time: 10_060, bytes: 150 }]);
+        history.record("1000", 400, 10_000 + 2 * MERGE_WINDOW_SECS);
+        assert_eq!(history.points("1000").len(), 2);
+        assert!(history.points("2000").is_empty());
+    }
+
+    #[test]
+    fn growth_is_measured_from_before_the_window() {
+        let mut history = SizeHistory::default();
+        let day = 86_400;
+        history.record("1000", 100, day);
+        history.record("1000", 300, 5 * day);
+        history.record("1000", 1000, 10 * day);
+        assert_eq!(history.growth_since("1000", 6 * day), Some(700));
+        // Nothing that old, the whole history counts
+        assert_eq!(history.growth_since("1000", 0), Some(900));
+        history.record("2000", 50, day);
+        assert_eq!(history.growth_since("2000", 0), None);
+    }
+
+    #[test]
+    fn history_survives_a_round_trip() {
+        let fixture = Fixture::new();
+        let path = fixture.root.join("data/size_history.json");
+        record_scan(&path, &[("1000".to_string(), 42)]).unwrap();
+        let history = SizeHistory::load(&path);
+        assert_eq!(history.points("1000").len(), 1);
+        assert_eq!(history.points("1000")[0].bytes, 42);
+    }
+}
@@ -0,0 +1,761 @@
+use crate::activity::{self, ActivityEntry, ActivityKind};
+use crate::config::{Compression, Config, SnapshotCompression};
+use crate::fsutil;
+use crate::shader_cache;
+use crate::steam::{self, CompatToolMapping};
+use crate::tar_stream;
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Safety snapshots are only meant to bridge an accidental deletion, so they
+// are pruned automatically according to the retention policy in Config
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+const METADATA_FILE: &str = "snapshot.json";
+
+// A copy of save locations taken right before a destructive operation
+#[derive(Serialize, Deserialize)]
+pub struct Snapshot {
+    pub game_id: String,
+    #[serde(default)]
+    pub game_name: Option<String>, // Custom display name at the time, if one was set
+    pub created: u64, // Unix timestamp (seconds)
+    pub reason: String,
+    // Given by the user in the snapshot browser, e.g. "Before NG+"
+    #[serde(default)]
+    pub label: Option<String>,
+    // Compat tool the game was set to use when the snapshot was taken, to warn
+    // about restoring into a prefix running a different Proton version
+    #[serde(default)]
+    pub compat_tool: Option<String>,
+    // Steam build ID of the game at the time, some games can't load saves
+    // written by a much older or newer version. None for non-Steam games.
+    #[serde(default)]
+    pub build_id: Option<u64>,
+    // Compressed snapshots keep `files` as one tar archive, see `compress`
+    #[serde(default)]
+    pub compression: Compression,
+    #[serde(default)]
+    pub uncompressed_size: Option<u64>, // Size of `files` before compressing
+    pub items: Vec<SnapshotItem>,
+    #[serde(skip)]
+    pub dir: PathBuf,
+}
+
+// One saved path inside a snapshot
+#[derive(Serialize, Deserialize)]
+pub struct SnapshotItem {
+    pub original_path: PathBuf,
+    pub stored_name: String, // Name of the copy inside the snapshot's `files` dir
+}
+
+// A file or folder inside a stored path, to restore on its own
+pub struct SnapshotEntry {
+    pub name: String,
+    pub relative: PathBuf, // Inside the stored copy of its item
+    pub is_dir: bool,
+    pub size: u64,
+    pub modified: Option<u64>, // Unix timestamp (seconds)
+}
+
+// What restoring a snapshot would do to the files there now. Files that only
+// exist there now are left alone by a restore, they don't count.
+#[derive(Debug, PartialEq, Eq)]
+pub struct RestoreImpact {
+    pub overwritten: usize, // Files replaced by the stored copy
+    pub added: usize,       // Stored files that aren't there anymore
+    pub size_change: i64,   // Bytes, negative when the stored files are smaller
+}
+
+impl Snapshot {
+    // Copy `paths` into a new safety snapshot for `game_id`
+    pub fn create(config: &Config, game_id: &str, reason: &str, paths: &[PathBuf]) -> Result<Self> {
+        let snapshot = Self::create_unpruned(config, game_id, reason, paths)?;
+
+        // Apply the retention policy now that there's a new one; being the newest
+        // it's never pruned itself
+        if let Err(e) = prune(config) {
+            eprintln!("Failed to prune old safety snapshots: {}", e);
+        }
+
+        Ok(snapshot)
+    }
+
+    // Like `create`, without applying the retention policy. For restoring an old
+    // snapshot, which pruning could otherwise delete before it's copied back.
+    pub fn create_unpruned(config: &Config, game_id: &str, reason: &str, paths: &[PathBuf]) -> Result<Self> {
+        // Symlinked locations are copied by their files
+        let size = paths.iter().filter_map(|path| fs::canonicalize(path).ok()).map(|path| fsutil::disk_usage(&path)).sum();
+        fsutil::ensure_free_space([(config.safety_snapshots_path(), size)])
+            .context("The safety snapshot doesn't fit")?;
+
+        let created = now_secs();
+        let dir = new_snapshot_dir(&config.safety_snapshots_path(), &format!("{}-{}", game_id, created))?;
+        let files_dir = dir.join("files");
+        fs::create_dir(&files_dir)
+            .context(format!("Failed to create snapshot directory {}", files_dir.display()))?;
+
+        // Locations are copied a few at a time, see `Config::io_limits`
+        let ignored = config.ignore_patterns_for(game_id);
+        let existing: Vec<(usize, &PathBuf)> = paths.iter().filter(|p| p.exists()).enumerate().collect();
+        let copied = fsutil::parallel_map(existing, config.io_limits().copy_threads, |(index, path)| -> Result<SnapshotItem> {
+            let stored_name = index.to_string();
+            // A location linked into a sync folder is snapshotted by its files, not the link
+            let source = if path.is_symlink() { fs::canonicalize(path)? } else { path.clone() };
+            // Shader caches are rebuilt by the game, not worth the space, and
+            // neither are logs and the like the user set to be ignored
+            let skip = |entry: &Path| shader_cache::is_cache_file(entry) || ignored.skips(&source, entry);
+            fsutil::copy_recursive_except(&source, &files_dir.join(&stored_name), &skip)
+                .context(format!("Failed to snapshot {}", path.display()))?;
+            Ok(SnapshotItem {
+                original_path: path.clone(),
+                stored_name,
+            })
+        });
+        let items = match copied.into_iter().collect::<Result<Vec<SnapshotItem>>>() {
+            Ok(items) => items,
+            Err(e) => {
+                // Don't leave a half-written snapshot behind
+                let _ = fs::remove_dir_all(&dir);
+                return Err(e);
+            }
+        };
+
+        let mut snapshot = Self {
+            game_id: game_id.to_string(),
+            game_name: config.display_name(game_id).map(str::to_string),
+            created,
+            reason: reason.to_string(),
+            label: None,
+            compat_tool: CompatToolMapping::load_or_default(config)
+                .tool_for(game_id)
+                .map(|tool| tool.name),
+            build_id: steam::build_id(config, game_id),
+            compression: Compression::None,
+            uncompressed_size: None,
+            items,
+            dir,
+        };
+        let compression = config.snapshot_compression();
+        if compression.method != Compression::None {
+            let size = fsutil::disk_usage(&files_dir);
+            // A plain copy still does its job, better than no snapshot at all
+            match compress(&snapshot.dir, compression) {
+                Ok(()) => {
+                    snapshot.compression = compression.method;
+                    snapshot.uncompressed_size = Some(size);
+                }
+                Err(e) => eprintln!("Failed to compress snapshot {}, keeping it uncompressed: {:#}", snapshot.dir.display(), e),
+            }
+        }
+        fs::write(
+            snapshot.dir.join(METADATA_FILE),
+            serde_json::to_string_pretty(&snapshot)?,
+        )?;
+
+        println!(
+            "Created safety snapshot of {} paths at {}",
+            snapshot.items.len(),
+            snapshot.dir.display()
+        );
+        activity::record(
+            config,
+            ActivityEntry::new(ActivityKind::Backup, format!("Safety snapshot: {}", reason))
+                .game(config, game_id)
+                .paths(snapshot.items.iter().map(|item| item.original_path.clone()))
+                .bytes(snapshot.size()),
+        );
+        Ok(snapshot)
+    }
+
+    // What lists show for it, the label if it has one
+    pub fn title(&self) -> &str {
+        self.label.as_deref().unwrap_or(&self.reason)
+    }
+
+    // Label it, or remove the label with an empty one. Kept in its
+    // METADATA_FILE.
+    pub fn set_label(&mut self, label: &str) -> Result<()> {
+        let label = label.trim();
+        self.label = (!label.is_empty()).then(|| label.to_string());
+        let path = self.dir.join(METADATA_FILE);
+        fs::write(&path, serde_json::to_string_pretty(self)?).context(format!("Failed to write {}", path.display()))
+    }
+
+    // Whether the label, reason or game name contain `query`, ignoring case
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.to_lowercase();
+        [self.label.as_deref(), Some(&self.reason), self.game_name.as_deref(), Some(&self.game_id)]
+            .into_iter()
+            .flatten()
+            .any(|text| text.to_lowercase().contains(&query))
+    }
+
+    // Whether the game was updated since this snapshot was taken, `current`
+    // being the build ID installed now. A snapshot of a newer build only
+    // comes from rolling the game back on purpose, no warning for that.
+    // Unknown builds on either side are taken as unchanged.
+    pub fn taken_under_older_build(&self, current: Option<u64>) -> bool {
+        matches!((self.build_id, current), (Some(snapshot), Some(current)) if snapshot < current)
+    }
+
+    // Disk space taken by this snapshot
+    pub fn size(&self) -> u64 {
+        fsutil::disk_usage(&self.dir)
+    }
+
+    // How many times smaller compressing made it, for compressed snapshots
+    pub fn compression_ratio(&self) -> Option<f64> {
+        let uncompressed = self.uncompressed_size?;
+        let size = self.size();
+        (size > 0).then(|| uncompressed as f64 / size as f64)
+    }
+
+    // Where the copy of `item` is kept inside this snapshot. Compressed
+    // snapshots are unpacked for this, into a temporary folder.
+    pub fn stored_path(&self, item: &SnapshotItem) -> PathBuf {
+        let files_dir = self.files_dir().unwrap_or_else(|e| {
+            eprintln!("Failed to unpack snapshot {}: {:#}", self.dir.display(), e);
+            self.dir.join("files")
+        });
+        files_dir.join(&item.stored_name)
+    }
+
+    // The folder holding the stored copies, unpacking the archive first if
+    // this snapshot is compressed. Stays unpacked until the snapshot is pruned
+    // or the temporary folder is cleared.
+    fn files_dir(&self) -> Result<PathBuf> {
+        if archive_name(self.compression).is_none() {
+            return Ok(self.dir.join("files"));
+        }
+        let unpacked = self.unpacked_dir();
+        if unpacked.join("files").is_dir() {
+            return Ok(unpacked.join("files"));
+        }
+        // Unpack next to it and rename, a failed unpack never looks complete
+        let partial = unpacked.with_extension("partial");
+        let _ = fs::remove_dir_all(&partial);
+        fs::create_dir_all(&partial).context(format!("Failed to create {}", partial.display()))?;
+        if let Err(e) = run_tar(Command::new("tar").arg("-x").arg("-I").arg(compressor(self.compression)).arg("-f").arg(self.archive_path()).arg("-C").arg(&partial)) {
+            let _ = fs::remove_dir_all(&partial);
+            return Err(e.context(format!("Failed to unpack {}", self.archive_path().display())));
+        }
+        let _ = fs::remove_dir_all(&unpacked);
+        fs::rename(&partial, &unpacked).context(format!("Failed to move {} into place", partial.display()))?;
+        Ok(unpacked.join("files"))
+    }
+
+    fn archive_path(&self) -> PathBuf {
+        self.dir.join(archive_name(self.compression).unwrap_or("files"))
+    }
+
+    fn unpacked_dir(&self) -> PathBuf {
+        std::env::temp_dir()
+            .join("proton_game_saves_snapshots")
+            .join(self.dir.file_name().unwrap_or_default())
+    }
+
+    // Copy every stored path back to where it came from
+    pub fn restore(&self) -> Result<()> {
+        // Unpacking errors are clearer than "failed to restore"
+        self.files_dir()?;
+        fsutil::ensure_free_space(
+            self.items
+                .iter()
+                .map(|item| (item.original_path.clone(), fsutil::copy_growth(&self.stored_path(item), &item.original_path))),
+        )?;
+        for item in &self.items {
+            let stored = self.stored_path(item);
+            fsutil::copy_recursive(&stored, &item.original_path).context(format!(
+                "Failed to restore {}",
+                item.original_path.display()
+            ))?;
+        }
+        println!("Restored safety snapshot {}", self.dir.display());
+        Ok(())
+    }
+
+    // Every file and folder stored, with the index of the item it belongs
+    // to. Compressed snapshots are read from the archive's headers, nothing
+    // gets unpacked for it. The stored copy itself has an empty `relative`.
+    pub fn stored_entries(&self) -> Result<Vec<(usize, SnapshotEntry)>> {
+        if archive_name(self.compression).is_none() {
+            let mut entries = Vec::new();
+            for (index, item) in self.items.iter().enumerate() {
+                walk_stored(&self.dir.join("files").join(&item.stored_name), Path::new(""), index, &mut entries);
+            }
+            return Ok(entries);
+        }
+        let listed = tar_stream::list(&self.archive_path(), compressor(self.compression))
+            .context(format!("Failed to read {}", self.archive_path().display()))?;
+        Ok(listed
+            .into_iter()
+            .filter_map(|entry| {
+                let mut components = entry.path.strip_prefix("files").ok()?.components();
+                let stored_name = components.next()?.as_os_str().to_string_lossy().to_string();
+                let index = self.items.iter().position(|item| item.stored_name == stored_name)?;
+                let relative = components.as_path().to_path_buf();
+                let name = relative.file_name().unwrap_or(stored_name.as_ref()).to_string_lossy().to_string();
+                Some((index, SnapshotEntry { name, relative, is_dir: entry.is_dir, size: entry.size, modified: entry.modified }))
+            })
+            .collect())
+    }
+
+    // How many files a restore would replace and add, and by how much it
+    // changes their size, from the stored sizes against what's there now
+    pub fn restore_impact(&self) -> Result<RestoreImpact> {
+        let mut impact = RestoreImpact { overwritten: 0, added: 0, size_change: 0 };
+        for (index, entry) in self.stored_entries()? {
+            let Some(item) = self.items.get(index).filter(|_| !entry.is_dir) else {
+                continue;
+            };
+            // Followed, a save location linked into a sync folder is restored by its files
+            match fs::metadata(self.original_path_of(item, &entry.relative)) {
+                Ok(metadata) if metadata.is_file() => {
+                    impact.overwritten += 1;
+                    impact.size_change += entry.size as i64 - metadata.len() as i64;
+                }
+                _ => {
+                    impact.added += 1;
+                    impact.size_change += entry.size as i64;
+                }
+            }
+        }
+        Ok(impact)
+    }
+
+    // What's directly inside `relative` of the stored copy of `item`, folders
+    // first. An empty `relative` lists the top of the copy. Read from the
+    // archive for compressed snapshots, see `stored_children`.
+    pub fn entries(&self, item: &SnapshotItem, relative: &Path) -> Vec<SnapshotEntry> {
+        let Some(index) = self.items.iter().position(|other| other.stored_name == item.stored_name) else {
+            return Vec::new();
+        };
+        match self.stored_entries() {
+            Ok(stored) => stored_children(&stored, index, relative),
+            Err(e) => {
+                eprintln!("Failed to list snapshot {}: {:#}", self.dir.display(), e);
+                Vec::new()
+            }
+        }
+    }
+
+    // One stored file to read, e.g. for its preview. Compressed snapshots
+    // only have that file taken out of the archive, into a temporary folder,
+    // unless the whole snapshot is unpacked already.
+    pub fn stored_file(&self, item: &SnapshotItem, relative: &Path) -> Result<PathBuf> {
+        if !relative.as_os_str().is_empty() && !fsutil::is_plain_relative(relative) {
+            bail!("{} is not a path inside the snapshot", relative.display());
+        }
+        let mut in_files = PathBuf::from(&item.stored_name);
+        if !relative.as_os_str().is_empty() {
+            in_files.push(relative);
+        }
+        if archive_name(self.compression).is_none() {
+            return Ok(self.dir.join("files").join(in_files));
+        }
+        let unpacked = self.unpacked_dir().join("files").join(&in_files);
+        if unpacked.is_file() {
+            return Ok(unpacked);
+        }
+        // Taken out again each time, a half-written copy is never reused
+        let copy = self.preview_dir().join(&in_files);
+        let _ = fs::remove_file(&copy);
+        if !tar_stream::extract_file(&self.archive_path(), compressor(self.compression), &Path::new("files").join(&in_files), &copy)? {
+            bail!("{} is not in snapshot {}", relative.display(), self.dir.display());
+        }
+        Ok(copy)
+    }
+
+    // Files taken out one at a time by `stored_file`
+    fn preview_dir(&self) -> PathBuf {
+        self.unpacked_dir().with_extension("preview")
+    }
+
+    // Where `relative` of the stored copy of `item` goes back to
+    pub fn original_path_of(&self, item: &SnapshotItem, relative: &Path) -> PathBuf {
+        if relative.as_os_str().is_empty() {
+            item.original_path.clone()
+        } else {
+            item.original_path.join(relative)
+        }
+    }
+
+    // Copy just `relative` of the stored copy of `item` back, leaving the rest
+    // of the save alone. Returns the restored path.
+    pub fn restore_path(&self, item: &SnapshotItem, relative: &Path) -> Result<PathBuf> {
+        if !relative.as_os_str().is_empty() && !fsutil::is_plain_relative(relative) {
+            bail!("{} is not a path inside the snapshot", relative.display());
+        }
+        self.files_dir()?;
+        let stored = self.stored_path(item).join(relative);
+        let destination = self.original_path_of(item, relative);
+        fsutil::ensure_free_space([(destination.clone(), fsutil::copy_growth(&stored, &destination))])?;
+        fsutil::copy_recursive(&stored, &destination)
+            .context(format!("Failed to restore {}", destination.display()))?;
+        println!("Restored {} from safety snapshot {}", destination.display(), self.dir.display());
+        Ok(destination)
+    }
+
+    fn load(dir: &Path) -> Option<Self> {
+        let content = fs::read_to_string(dir.join(METADATA_FILE)).ok()?;
+        let mut snapshot: Self = serde_json::from_str(&content).ok()?;
+        snapshot.dir = dir.to_path_buf();
+        Some(snapshot)
+    }
+}
+
+// The entry at `relative` of item `index` in `stored` (from
+// `Snapshot::stored_entries`), folders with the size of everything in them
+pub fn stored_entry(stored: &[(usize, SnapshotEntry)], index: usize, relative: &Path) -> Option<SnapshotEntry> {
+    stored
+        .iter()
+        .find(|(other, entry)| *other == index && entry.relative == relative)
+        .map(|(_, entry)| with_total_size(stored, index, entry))
+}
+
+// What's directly inside `relative` of item `index` in `stored`, folders
+// first, then by name like `fsutil::list_folder`
+pub fn stored_children(stored: &[(usize, SnapshotEntry)], index: usize, relative: &Path) -> Vec<SnapshotEntry> {
+    let mut children: Vec<SnapshotEntry> = stored
+        .iter()
+        .filter(|(other, entry)| *other == index && !entry.relative.as_os_str().is_empty() && entry.relative.parent() == Some(relative))
+        .map(|(_, entry)| with_total_size(stored, index, entry))
+        .collect();
+    children.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase())));
+    children
+}
+
+fn with_total_size(stored: &[(usize, SnapshotEntry)], index: usize, entry: &SnapshotEntry) -> SnapshotEntry {
+    let size = if entry.is_dir {
+        stored
+            .iter()
+            .filter(|(other, inside)| *other == index && !inside.is_dir && inside.relative.starts_with(&entry.relative))
+            .map(|(_, inside)| inside.size)
+            .sum()
+    } else {
+        entry.size
+    };
+    SnapshotEntry { name: entry.name.clone(), relative: entry.relative.clone(), is_dir: entry.is_dir, size, modified: entry.modified }
+}
+
+// `path` and everything below it, for `Snapshot::stored_entries`
+fn walk_stored(path: &Path, relative: &Path, index: usize, entries: &mut Vec<(usize, SnapshotEntry)>) {
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        return;
+    };
+    entries.push((
+        index,
+        SnapshotEntry {
+            name: path.file_name().unwrap_or_default().to_string_lossy().to_string(),
+            relative: relative.to_path_buf(),
+            is_dir: metadata.is_dir(),
+            size: if metadata.is_dir() { 0 } else { metadata.len() },
+            modified: metadata.modified().ok().and_then(|t| t.duration_since(UNIX_EPOCH).ok()).map(|d| d.as_secs()),
+        },
+    ));
+    if metadata.is_dir() {
+        for entry in fs::read_dir(path).into_iter().flatten().flatten() {
+            walk_stored(&entry.path(), &relative.join(entry.file_name()), index, entries);
+        }
+    }
+}
+
+// All safety snapshots, newest first
+pub fn list_snapshots(config: &Config) -> Vec<Snapshot> {
+    let mut snapshots: Vec<Snapshot> = fs::read_dir(config.safety_snapshots_path())
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|entry| Snapshot::load(&entry.path()))
+                .collect()
+        })
+        .unwrap_or_default();
+    snapshots.sort_by_key(|s| std::cmp::Reverse(s.created));
+    snapshots
+}
+
+// Snapshots outside their game's retention policy: too old, or beyond the
+// newest `max_snapshots` for that game
+pub fn prunable_snapshots(config: &Config) -> Vec<Snapshot> {
+    let now = now_secs();
+    let mut kept_per_game: HashMap<String, u32> = HashMap::new();
+    list_snapshots(config)
+        .into_iter()
+        .filter(|snapshot| {
+            let policy = config.retention_for(&snapshot.game_id);
+            let kept = kept_per_game.entry(snapshot.game_id.clone()).or_default();
+            let too_old = policy.max_age_days > 0
+                && now.saturating_sub(snapshot.created) > policy.max_age_days as u64 * SECS_PER_DAY;
+            let too_many = policy.max_snapshots > 0 && *kept >= policy.max_snapshots;
+            if too_old || too_many {
+                return true;
+            }
+            *kept += 1;
+            false
+        })
+        .collect()
+}
+
+// Delete every prunable snapshot, returns the number of bytes freed
+pub fn prune(config: &Config) -> Result<u64> {
+    let mut freed = 0;
+    for snapshot in prunable_snapshots(config) {
+        let size = snapshot.size();
+        println!("Pruning safety snapshot {}", snapshot.dir.display());
+        fs::remove_dir_all(&snapshot.dir)?;
+        let _ = fs::remove_dir_all(snapshot.unpacked_dir());
+        let _ = fs::remove_dir_all(snapshot.preview_dir());
+        freed += size;
+        activity::record(
+            config,
+            ActivityEntry::new(ActivityKind::Delete, format!("Pruned safety snapshot: {}", snapshot.reason))
+                .game(config, &snapshot.game_id)
+                .paths([snapshot.dir.clone()])
+                .bytes(size),
+        );
+    }
+    Ok(freed)
+}
+
+// Move every snapshot into `folder` and keep new ones there too, e.g. off a
+// disk SteamOS updates wipe (see `steamos`). All of them are copied before
+// any is deleted, so a failure leaves them where they were. Returns how many
+// were moved.
+pub fn move_snapshots(config: &mut Config, folder: &Path) -> Result<usize> {
+    let old_root = config.safety_snapshots_path();
+    if folder == old_root {
+        return Ok(0);
+    }
+    if folder.starts_with(&old_root) {
+        bail!("{} is inside the snapshot folder {}", folder.display(), old_root.display());
+    }
+    fs::create_dir_all(folder).context(format!("Failed to create {}", folder.display()))?;
+    let snapshots = list_snapshots(config);
+    fsutil::ensure_free_space([(folder.to_path_buf(), snapshots.iter().map(Snapshot::size).sum())])?;
+
+    let mut copied: Vec<PathBuf> = Vec::new();
+    let copy_all = |copied: &mut Vec<PathBuf>| -> Result<()> {
+        for snapshot in &snapshots {
+            let dest = folder.join(snapshot.dir.file_name().unwrap_or_default());
+            if dest.exists() {
+                bail!("{} already has a snapshot called {}", folder.display(), dest.display());
+            }
+            copied.push(dest.clone());
+            fsutil::copy_recursive(&snapshot.dir, &dest)?;
+        }
+        Ok(())
+    };
+    if let Err(e) = copy_all(&mut copied).and_then(|_| config.set_snapshot_root(Some(folder.to_path_buf()))) {
+        // Only the copies made here, never a snapshot that was there already
+        for dest in copied {
+            let _ = fs::remove_dir_all(dest);
+        }
+        return Err(e);
+    }
+
+    for snapshot in &snapshots {
+        if let Err(e) = fs::remove_dir_all(&snapshot.dir) {
+            eprintln!("Failed to remove the old copy {}: {}", snapshot.dir.display(), e);
+        }
+    }
+    println!("Moved {} safety snapshots from {} to {}", snapshots.len(), old_root.display(), folder.display());
+    Ok(snapshots.len())
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+// Name of the archive `files` is packed into, `None` for plain copies
+fn archive_name(method: Compression) -> Option<&'static str> {
+    match method {
+        Compression::None => None,
+        Compression::Zstd => Some("files.tar.zst"),
+        Compression::Gzip => Some("files.tar.gz"),
+    }
+}
+
+// Program tar pipes the archive through; tar adds `-d` itself when unpacking
+pub(crate) fn compressor(method: Compression) -> &'static str {
+    match method {
+        Compression::None => "cat",
+        Compression::Zstd => "zstd",
+        Compression::Gzip => "gzip",
+    }
+}
+
+// A folder of its own for a new snapshot: `name`, or `name-2`, `name-3`, ...
+// when another snapshot of the game was taken in the same second
+fn new_snapshot_dir(snapshots_path: &Path, name: &str) -> Result<PathBuf> {
+    fs::create_dir_all(snapshots_path)
+        .context(format!("Failed to create snapshot directory {}", snapshots_path.display()))?;
+    let mut dir = snapshots_path.join(name);
+    let mut counter = 1;
+    loop {
+        match fs::create_dir(&dir) {
+            Ok(()) => return Ok(dir),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                counter += 1;
+                dir = snapshots_path.join(format!("{}-{}", name, counter));
+            }
+            Err(e) => return Err(anyhow::Error::new(e).context(format!("Failed to create snapshot directory {}", dir.display()))),
+        }
+    }
+}
+
+// Pack the `files` folder of a new snapshot into an archive and remove the
+// folder. Uses the system's tar with zstd or gzip, like importing archives does.
+fn compress(dir: &Path, compression: SnapshotCompression) -> Result<()> {
+    let Some(name) = archive_name(compression.method) else {
+        return Ok(());
+    };
+    let (min, max) = compression.method.level_range();
+    let program = format!("{} -{}", compressor(compression.method), compression.level.clamp(min, max));
+    let archive = dir.join(name);
+    if let Err(e) = run_tar(Command::new("tar").arg("-c").arg("-I").arg(&program).arg("-f").arg(&archive).arg("-C").arg(dir).arg("files")) {
+        let _ = fs::remove_file(&archive);
+        return Err(e);
+    }
+    fs::remove_dir_all(dir.join("files")).context(format!("Failed to remove {} after compressing it", dir.join("files").display()))
+}
+
+pub(crate) fn run_tar(command: &mut Command) -> Result<()> {
+    match command.status() {
+        Ok(status) if status.success() => Ok(()),
+        Ok(status) => bail!("tar failed ({})", status),
+        Err(e) => bail!("Couldn't run tar: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{write_file, Fixture};
+
+    #[test]
+    fn records_the_game_build() {
+        let fixture = Fixture::new();
+        fixture.add_prefix("1000");
+        let save = fixture.add_file("1000", "AppData/Roaming/LonelyHero/Saves/slot1.sav", "save");
+        let app_manifest = fixture.compatdata_path().parent().unwrap().join("appmanifest_1000.acf");
+        write_file(&app_manifest, "\"AppState\"\n{\n\t\"appid\"\t\t\"1000\"\n\t\"buildid\"\t\t\"12000\"\n}\n");
+        let config = fixture.config();
+
+        let snapshot = Snapshot::create(&config, "1000", "Test", &[save]).unwrap();
+        assert_eq!(snapshot.build_id, Some(12000));
+        assert_eq!(Snapshot::load(&snapshot.dir).unwrap().build_id, Some(12000));
+        assert!(!snapshot.taken_under_older_build(steam::build_id(&config, "1000")));
+        assert!(snapshot.taken_under_older_build(Some(13000)));
+        // The game was rolled back since, no warning
+        assert!(!snapshot.taken_under_older_build(Some(11000)));
+        assert!(!snapshot.taken_under_older_build(None));
+    }
+
+    #[test]
+    fn snapshots_in_the_same_second_get_their_own_folders() {
+        let fixture = Fixture::new();
+        fixture.add_prefix("1000");
+        let save = fixture.add_file("1000", "AppData/Roaming/LonelyHero/Saves/slot1.sav", "save");
+        let config = fixture.config();
+        fs::create_dir_all(config.safety_snapshots_path().join(format!("1000-{}", now_secs()))).unwrap();
+
+        let first = Snapshot::create(&config, "1000", "First", std::slice::from_ref(&save)).unwrap();
+        let second = Snapshot::create(&config, "1000", "Second", &[save]).unwrap();
+        assert_ne!(first.dir, second.dir);
+        assert_eq!(Snapshot::load(&first.dir).unwrap().reason, "First");
+        assert_eq!(Snapshot::load(&second.dir).unwrap().reason, "Second");
+    }
+
+    #[test]
+    fn moves_snapshots_to_another_folder() {
+        let fixture = Fixture::new();
+        fixture.add_prefix("1000");
+        let save = fixture.add_file("1000", "AppData/Roaming/LonelyHero/Saves/slot1.sav", "save");
+        let mut config = fixture.config();
+        let old = Snapshot::create(&config, "1000", "Test", &[save]).unwrap();
+
+        let folder = fixture.root.join("card/snapshots");
+        assert_eq!(move_snapshots(&mut config, &folder).unwrap(), 1);
+        assert!(!old.dir.exists());
+        assert_eq!(config.safety_snapshots_path(), folder);
+        let moved = list_snapshots(&config);
+        assert_eq!(moved.len(), 1);
+        assert_eq!(moved[0].reason, "Test");
+        // The setting is saved, not just changed in memory
+        assert_eq!(fixture.config().safety_snapshots_path(), folder);
+        assert!(move_snapshots(&mut config, &folder.join("inside")).is_err());
+    }
+
+    #[test]
+    fn keeps_labels_in_the_metadata() {
+        let fixture = Fixture::new();
+        fixture.add_prefix("1000");
+        let save = fixture.add_file("1000", "AppData/Roaming/LonelyHero/Saves/slot1.sav", "save");
+        let mut snapshot = Snapshot::create(&fixture.config(), "1000", "Before deleting", &[save]).unwrap();
+
+        snapshot.set_label("  Before NG+ ").unwrap();
+        let loaded = Snapshot::load(&snapshot.dir).unwrap();
+        assert_eq!(loaded.label.as_deref(), Some("Before NG+"));
+        assert_eq!(loaded.title(), "Before NG+");
+        assert!(loaded.matches("ng+") && loaded.matches("deleting") && !loaded.matches("mod"));
+        snapshot.set_label("").unwrap();
+        assert_eq!(Snapshot::load(&snapshot.dir).unwrap().title(), "Before deleting");
+    }
+
+    #[test]
+    fn previews_what_a_restore_changes() {
+        let fixture = Fixture::new();
+        fixture.add_prefix("1000");
+        let slot1 = fixture.add_file("1000", "AppData/Roaming/LonelyHero/Saves/slot1.sav", "save");
+        let slot2 = fixture.add_file("1000", "AppData/Roaming/LonelyHero/Saves/slot2.sav", "save");
+        let saves = slot1.parent().unwrap().to_path_buf();
+        let mut config = fixture.config();
+
+        for method in [Compression::None, Compression::Gzip] {
+            config.set_snapshot_compression(SnapshotCompression { method, level: 1 }).unwrap();
+            write_file(&slot1, "save");
+            write_file(&slot2, "save");
+            let _ = fs::remove_file(saves.join("new.sav"));
+            let snapshot = Snapshot::create(&config, "1000", "Test", std::slice::from_ref(&saves)).unwrap();
+            assert_eq!(snapshot.compression, method);
+
+            write_file(&slot1, "changed!!");
+            fs::remove_file(&slot2).unwrap();
+            write_file(&saves.join("new.sav"), "only here");
+            let impact = snapshot.restore_impact().unwrap();
+            assert_eq!(impact, RestoreImpact { overwritten: 1, added: 1, size_change: -5 + 4 });
+        }
+    }
+
+    #[test]
+    fn browses_compressed_snapshots_without_unpacking_them() {
+        let fixture = Fixture::new();
+        fixture.add_prefix("1000");
+        let slot1 = fixture.add_file("1000", "AppData/Roaming/LonelyHero/Saves/slot1.sav", "save");
+        fixture.add_file("1000", "AppData/Roaming/LonelyHero/Saves/Backup/slot1.bak", "old save");
+        let saves = slot1.parent().unwrap().to_path_buf();
+        let mut config = fixture.config();
+        config.set_snapshot_compression(SnapshotCompression { method: Compression::Gzip, level: 1 }).unwrap();
+        let snapshot = Snapshot::create(&config, "1000", "Test", std::slice::from_ref(&saves)).unwrap();
+        let item = &snapshot.items[0];
+
+        let stored = snapshot.stored_entries().unwrap();
+        assert_eq!(stored_entry(&stored, 0, Path::new("")).unwrap().size, 12);
+        let names: Vec<String> = snapshot.entries(item, Path::new("")).into_iter().map(|entry| entry.name).collect();
+        assert_eq!(names, ["Backup", "slot1.sav"]);
+        assert_eq!(stored_children(&stored, 0, Path::new("Backup"))[0].relative, Path::new("Backup/slot1.bak"));
+
+        let copy = snapshot.stored_file(item, Path::new("Backup/slot1.bak")).unwrap();
+        assert_eq!(fs::read_to_string(copy).unwrap(), "old save");
+        assert!(!snapshot.unpacked_dir().exists());
+        assert!(snapshot.stored_file(item, Path::new("../slot1.sav")).is_err());
+    }
+}
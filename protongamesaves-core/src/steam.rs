@@ -0,0 +1,377 @@
+use crate::config::{Config, SteamAccountChoice};
+use crate::i18n::gettext_f;
+use crate::vdf;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// App ID Steam uses in CompatToolMapping for the global "Steam Play for all other titles" tool
+const DEFAULT_TOOL_APP_ID: &str = "0";
+
+// Which compatibility tool (Proton build) each App ID is set to use, from
+// CompatToolMapping in Steam's config.vdf
+#[derive(Default)]
+pub struct CompatToolMapping {
+    tools: HashMap<String, String>, // App ID -> internal tool name, e.g. "proton_experimental"
+    default_tool: Option<String>,
+}
+
+// The tool a game runs with, and whether that's only because of the global default
+#[derive(Serialize, Deserialize)]
+pub struct CompatTool {
+    pub name: String,
+    pub is_default: bool,
+}
+
+impl CompatToolMapping {
+    pub fn load(config: &Config) -> Result<Self> {
+        let path = config.steam_config_path();
+        let content = fs::read_to_string(&path)
+            .context(format!("Failed to read {}", path.display()))?;
+        let root = vdf::parse(&content).context(format!("Failed to parse {}", path.display()))?;
+
+        let mut mapping = Self::default();
+        let Some(table) = root.path(&["InstallConfigStore", "Software", "Valve", "Steam", "CompatToolMapping"]) else {
+            return Ok(mapping);
+        };
+        for (app_id, value) in table.iter() {
+            let vdf::VdfValue::Table(entry) = value else {
+                continue;
+            };
+            // An empty name means the game was switched back to "no forced tool"
+            let Some(name) = entry.str("name").filter(|name| !name.is_empty()) else {
+                continue;
+            };
+            if app_id == DEFAULT_TOOL_APP_ID {
+                mapping.default_tool = Some(name.to_string());
+            } else {
+                mapping.tools.insert(app_id.to_string(), name.to_string());
+            }
+        }
+        Ok(mapping)
+    }
+
+    // Same as `load`, but a missing or broken config.vdf just means no mapping
+    pub fn load_or_default(config: &Config) -> Self {
+        Self::load(config).unwrap_or_else(|e| {
+            eprintln!("Could not read compat tool mapping: {:#}", e);
+            Self::default()
+        })
+    }
+
+    pub fn tool_for(&self, app_id: &str) -> Option<CompatTool> {
+        match self.tools.get(app_id) {
+            Some(name) => Some(CompatTool { name: name.clone(), is_default: false }),
+            None => self.default_tool.as_ref().map(|name| CompatTool { name: name.clone(), is_default: true }),
+        }
+    }
+}
+
+impl CompatTool {
+    // Human readable name for Valve's internal tool names, custom tools
+    // (GE-Proton9-7, ...) are already readable and kept as they are
+    pub fn display_name(&self) -> String {
+        display_tool_name(&self.name)
+    }
+
+    // For the UI, e.g. "Proton Experimental" or "Proton 9.0 (default)"
+    pub fn label(&self) -> String {
+        if self.is_default {
+            gettext_f("{} (default)", &[&self.display_name()])
+        } else {
+            self.display_name()
+        }
+    }
+}
+
+pub fn display_tool_name(name: &str) -> String {
+    let Some(rest) = name.strip_prefix("proton_") else {
+        return name.to_string();
+    };
+    match rest {
+        "experimental" => "Proton Experimental".to_string(),
+        "hotfix" => "Proton Hotfix".to_string(),
+        // proton_9 -> 9.0, proton_63 -> 6.3, proton_513 -> 5.13, proton_10 -> 10.0
+        // (there never was a Proton 1.x, so a leading 1 is a two digit major)
+        digits if !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()) => {
+            let major_len = if digits.starts_with('1') { digits.len().min(2) } else { 1 };
+            let (major, minor) = digits.split_at(major_len);
+            format!("Proton {}.{}", major, if minor.is_empty() { "0" } else { minor })
+        }
+        _ => name.to_string(),
+    }
+}
+
+// App IDs installed in any Steam library, from the `appmanifest_<App ID>.acf`
+// files next to each library's compatdata folder
+pub fn installed_app_ids(config: &Config) -> HashSet<String> {
+    let mut installed = HashSet::new();
+    for compatdata_path in config.compatdata_paths() {
+        let Some(steamapps) = compatdata_path.parent() else {
+            continue;
+        };
+        let Ok(entries) = fs::read_dir(steamapps) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if let Some(app_id) = name.strip_prefix("appmanifest_").and_then(|rest| rest.strip_suffix(".acf")) {
+                installed.insert(app_id.to_string());
+            }
+        }
+    }
+    installed
+}
+
+// Build ID of the installed version of `app_id`, from its app manifest:
+//
+//   "AppState" { "appid" "1245620" ... "buildid" "13303042" ... }
+//
+// Steam numbers builds across all games in the order they're published, so a
+// higher build ID is a newer version of the game, but how much newer can't be told.
+pub fn build_id(config: &Config, app_id: &str) -> Option<u64> {
+    config.compatdata_paths().iter().find_map(|compatdata_path| {
+        let path = compatdata_path.parent()?.join(format!("appmanifest_{}.acf", app_id));
+        let content = fs::read_to_string(&path).ok()?;
+        match vdf::parse(&content) {
+            Ok(root) => root.table("AppState")?.str("buildid")?.parse().ok(),
+            Err(e) => {
+                eprintln!("Failed to parse {}: {:#}", path.display(), e);
+                None
+            }
+        }
+    })
+}
+
+// Non-Steam games added as shortcuts get IDs with the top bit set, they never
+// have an app manifest
+pub fn is_shortcut_app_id(app_id: &str) -> bool {
+    app_id.parse::<u64>().is_ok_and(|id| id >= 1 << 31)
+}
+
+// 64-bit Steam IDs of individual accounts start here, userdata folders are
+// named by what comes on top of it
+pub const STEAM_ID64_BASE: u64 = 76561197960265728;
+
+// A Steam account that used this machine
+pub struct SteamAccount {
+    pub id: String, // Name of its userdata folder
+    pub name: Option<String>, // Persona name, if Steam remembers the login
+    pub signed_in: bool, // Logged in last, usually the one running Steam now
+}
+
+impl SteamAccount {
+    pub fn label(&self) -> String {
+        match &self.name {
+            Some(name) => format!("{} ({})", name, self.id),
+            None => self.id.clone(),
+        }
+    }
+}
+
+// Accounts with a userdata folder, named from loginusers.vdf:
+//
+//   "users" { "76561197960287930" { "AccountName" "gaben" "PersonaName" "Gabe" "MostRecent" "1" ... } }
+//
+// Accounts that were removed from the login screen have no name.
+pub fn accounts(config: &Config) -> Vec<SteamAccount> {
+    let Ok(entries) = fs::read_dir(config.steam_userdata_path()) else {
+        return Vec::new();
+    };
+    let mut ids: Vec<String> = entries
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .filter(|name| name.parse::<u64>().is_ok_and(|id| id > 0))
+        .collect();
+    ids.sort();
+
+    let path = config.steam_login_users_path();
+    let logins = fs::read_to_string(&path).ok().and_then(|content| match vdf::parse(&content) {
+        Ok(root) => Some(root),
+        Err(e) => {
+            eprintln!("Failed to parse {}: {:#}", path.display(), e);
+            None
+        }
+    });
+    let users = logins.as_ref().and_then(|root| root.table("users"));
+    ids.into_iter()
+        .map(|id| {
+            let login = users.and_then(|users| {
+                users.iter().find_map(|(steam_id, value)| {
+                    let vdf::VdfValue::Table(login) = value else {
+                        return None;
+                    };
+                    let account_id = steam_id.parse::<u64>().ok()?.checked_sub(STEAM_ID64_BASE)?;
+                    (account_id.to_string() == id).then_some(login)
+                })
+            });
+            SteamAccount {
+                name: login.and_then(|login| login.str("PersonaName").or_else(|| login.str("AccountName"))).map(str::to_string),
+                signed_in: login.and_then(|login| login.str("MostRecent")) == Some("1"),
+                id,
+            }
+        })
+        .collect()
+}
+
+// The userdata folders cloud copies, Steam settings and play times come from,
+// per the account picked in Settings. Without a known signed-in account that
+// is every account.
+pub fn active_account_ids(config: &Config) -> Vec<String> {
+    let accounts = accounts(config);
+    let signed_in = accounts.iter().find(|account| account.signed_in).map(|account| account.id.clone());
+    match (config.steam_account(), signed_in) {
+        (SteamAccountChoice::Account(id), _) => accounts.into_iter().filter(|account| &account.id == id).map(|account| account.id).collect(),
+        (SteamAccountChoice::SignedIn, Some(id)) => vec![id],
+        _ => accounts.into_iter().map(|account| account.id).collect(),
+    }
+}
+
+// When each App ID was last played, from the localconfig.vdf of the active
+// Steam accounts on this machine (the latest one wins):
+//
+//   "UserLocalConfigStore" { "Software" { "Valve" { "Steam" { "apps" { "1245620" { "LastPlayed" "1700000000" ... } } } } } }
+pub fn last_played(config: &Config) -> HashMap<String, u64> {
+    let mut last_played: HashMap<String, u64> = HashMap::new();
+    for account_id in active_account_ids(config) {
+        let path = config.steam_userdata_path().join(account_id).join("config/localconfig.vdf");
+        let Ok(content) = fs::read_to_string(&path) else {
+            continue;
+        };
+        let root = match vdf::parse(&content) {
+            Ok(root) => root,
+            Err(e) => {
+                eprintln!("Failed to parse {}: {:#}", path.display(), e);
+                continue;
+            }
+        };
+        let Some(apps) = root.path(&["UserLocalConfigStore", "Software", "Valve", "Steam", "apps"]) else {
+            continue;
+        };
+        for (app_id, value) in apps.iter() {
+            let vdf::VdfValue::Table(entry) = value else {
+                continue;
+            };
+            // 0 is written for games that were never started
+            let Some(timestamp) = entry.str("LastPlayed").and_then(|t| t.parse::<u64>().ok()).filter(|t| *t > 0) else {
+                continue;
+            };
+            let latest = last_played.entry(app_id.to_string()).or_insert(timestamp);
+            *latest = (*latest).max(timestamp);
+        }
+    }
+    last_played
+}
+
+// Library folders besides the main Steam folder, from libraryfolders.vdf:
+//
+//   "libraryfolders" { "0" { "path" "/home/user/.local/share/Steam" ... } "1" { "path" "/mnt/games" ... } }
+//
+// Older Steam versions stored the path directly as the value ("1" "/mnt/games").
+// A library on a drive that isn't mounted is still listed, so scans can report it.
+pub fn library_folders(config: &Config) -> Vec<PathBuf> {
+    let path = config.steam_library_folders_path();
+    let Ok(content) = fs::read_to_string(&path) else {
+        return Vec::new();
+    };
+    let root = match vdf::parse(&content) {
+        Ok(root) => root,
+        Err(e) => {
+            eprintln!("Failed to parse {}: {:#}", path.display(), e);
+            return Vec::new();
+        }
+    };
+    let Some(table) = root.table("libraryfolders") else {
+        return Vec::new();
+    };
+
+    // The main library shows up in the list too, usually through a different (non-symlinked) path
+    let main_library = config.steam_path().join("steam");
+    let main_canonical = main_library.canonicalize().unwrap_or(main_library);
+    let mut libraries: Vec<PathBuf> = Vec::new();
+    for (key, value) in table.iter() {
+        if !key.chars().all(|c| c.is_ascii_digit()) {
+            continue; // "contentstatsid" and friends
+        }
+        let library = match value {
+            vdf::VdfValue::Table(entry) => entry.str("path").map(PathBuf::from),
+            vdf::VdfValue::Str(path) => Some(PathBuf::from(path)),
+        };
+        let Some(library) = library.filter(|library| !library.as_os_str().is_empty()) else {
+            continue;
+        };
+        let canonical = library.canonicalize().unwrap_or_else(|_| library.clone());
+        if canonical != main_canonical && !libraries.contains(&library) {
+            libraries.push(library);
+        }
+    }
+    libraries
+}
+
+// Libraries on removable drives that libraryfolders.vdf doesn't list, e.g. a
+// microSD card formatted on another Steam Deck. SteamOS mounts cards at
+// /run/media/mmcblk0p1 or /run/media/<user>/<label>, so mounts one and two
+// levels below `media_root` are looked at, and a `SteamLibrary` folder on them
+// (what Steam names new libraries on desktops). Only libraries with a
+// compatdata folder count, `known` ones are left out.
+pub fn removable_libraries(media_root: &Path, known: &[PathBuf]) -> Vec<PathBuf> {
+    let known: Vec<PathBuf> = known.iter().map(|library| library.canonicalize().unwrap_or_else(|_| library.clone())).collect();
+    let subfolders = |dir: &Path| -> Vec<PathBuf> {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return Vec::new();
+        };
+        let mut folders: Vec<PathBuf> = entries.flatten().map(|entry| entry.path()).filter(|path| path.is_dir()).collect();
+        folders.sort();
+        folders
+    };
+
+    let mut mounts = Vec::new();
+    for folder in subfolders(media_root) {
+        mounts.extend(subfolders(&folder));
+        mounts.push(folder);
+    }
+    let mut libraries: Vec<PathBuf> = Vec::new();
+    for library in mounts.into_iter().flat_map(|mount| [mount.join("SteamLibrary"), mount]) {
+        if !library.join("steamapps/compatdata").is_dir() {
+            continue;
+        }
+        let canonical = library.canonicalize().unwrap_or_else(|_| library.clone());
+        if !known.contains(&canonical) && !libraries.contains(&library) {
+            println!("Found Steam library on removable storage: {}", library.display());
+            libraries.push(library);
+        }
+    }
+    libraries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{write_file, Fixture};
+
+    #[test]
+    fn scopes_userdata_to_the_picked_account() {
+        let fixture = Fixture::new();
+        let mut config = fixture.config();
+        for account_id in ["12345678", "87654321"] {
+            fs::create_dir_all(config.steam_userdata_path().join(account_id)).unwrap();
+        }
+        // 76561197960265728 + 87654321
+        write_file(
+            &config.steam_login_users_path(),
+            r#""users" { "76561198047920049" { "AccountName" "sam" "PersonaName" "Sam" "MostRecent" "1" } }"#,
+        );
+
+        let accounts = accounts(&config);
+        assert_eq!(accounts.iter().map(|account| account.label()).collect::<Vec<_>>(), ["12345678", "Sam (87654321)"]);
+        assert!(accounts[1].signed_in);
+        assert_eq!(active_account_ids(&config), ["12345678", "87654321"]);
+        config.set_steam_account(SteamAccountChoice::SignedIn).unwrap();
+        assert_eq!(active_account_ids(&config), ["87654321"]);
+        config.set_steam_account(SteamAccountChoice::Account("12345678".to_string())).unwrap();
+        assert_eq!(active_account_ids(&config), ["12345678"]);
+    }
+}
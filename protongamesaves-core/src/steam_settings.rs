@@ -0,0 +1,204 @@
+use crate::compatdata;
+use crate::config::Config;
+use crate::fsutil;
+use crate::steam;
+use crate::vdf;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Besides saves, Steam keeps settings of its own for each game, once per account:
+//
+//   userdata/<account id>/<app id>/...                                  <- Steam Input and other per-game settings
+//   steamapps/common/Steam Controller Configs/<account id>/config/<app id>/  <- controller layouts
+//   userdata/<account id>/config/localconfig.vdf                        <- launch options, among others
+//
+// The folders are copied into bundles as they are. The Steam Cloud copies in
+// `remote` are saves, not settings, and are left to Steam (see `cloud`).
+// localconfig.vdf is rewritten by Steam whenever it exits, so launch options
+// are only carried along for the user to set again.
+pub const EXPORT_FOLDER: &str = "steam-settings";
+const SKIPPED_ENTRIES: [&str; 2] = ["remote", "remotecache.vdf"];
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum SettingsKind {
+    Userdata,
+    ControllerConfig,
+}
+
+// A settings folder as stored in a bundle, under `EXPORT_FOLDER`
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct StoredSettings {
+    pub account_id: String,
+    pub kind: SettingsKind,
+    pub stored_name: String,
+}
+
+// Launch options set for a game on one account
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct LaunchOptions {
+    pub account_id: String,
+    pub options: String,
+}
+
+fn settings_dir(config: &Config, account_id: &str, kind: SettingsKind, app_id: &str) -> PathBuf {
+    match kind {
+        SettingsKind::Userdata => config.steam_userdata_path().join(account_id).join(app_id),
+        SettingsKind::ControllerConfig => config.steam_controller_configs_path().join(account_id).join("config").join(app_id),
+    }
+}
+
+// Settings folders of `app_id` that have anything besides cloud saves in them
+pub fn game_dirs(config: &Config, app_id: &str) -> Vec<(String, SettingsKind, PathBuf)> {
+    let mut dirs = Vec::new();
+    for account_id in steam::active_account_ids(config) {
+        for kind in [SettingsKind::Userdata, SettingsKind::ControllerConfig] {
+            let dir = settings_dir(config, &account_id, kind, app_id);
+            if settings_entries(&dir).next().is_some() {
+                dirs.push((account_id.clone(), kind, dir));
+            }
+        }
+    }
+    dirs
+}
+
+fn settings_entries(dir: &Path) -> impl Iterator<Item = fs::DirEntry> {
+    fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| !SKIPPED_ENTRIES.contains(&entry.file_name().to_string_lossy().as_ref()))
+}
+
+// Launch options of `app_id` on every account that set any
+pub fn launch_options(config: &Config, app_id: &str) -> Vec<LaunchOptions> {
+    let mut found = Vec::new();
+    for account_id in steam::active_account_ids(config) {
+        let path = config.steam_userdata_path().join(&account_id).join("config/localconfig.vdf");
+        let Ok(content) = fs::read_to_string(&path) else {
+            continue;
+        };
+        let root = match vdf::parse(&content) {
+            Ok(root) => root,
+            Err(e) => {
+                eprintln!("Failed to parse {}: {:#}", path.display(), e);
+                continue;
+            }
+        };
+        let options = root
+            .path(&["UserLocalConfigStore", "Software", "Valve", "Steam", "apps", app_id])
+            .and_then(|app| app.str("LaunchOptions"))
+            .map(str::trim)
+            .filter(|options| !options.is_empty());
+        if let Some(options) = options {
+            found.push(LaunchOptions { account_id, options: options.to_string() });
+        }
+    }
+    found
+}
+
+// Copy the settings folders of `app_id` into `dest`, one numbered folder each
+pub fn export(config: &Config, app_id: &str, dest: &Path) -> Result<Vec<StoredSettings>> {
+    let mut stored = Vec::new();
+    for (account_id, kind, dir) in game_dirs(config, app_id) {
+        let stored_name = stored.len().to_string();
+        let target = dest.join(&stored_name);
+        fs::create_dir_all(&target).context(format!("Failed to create {}", target.display()))?;
+        for entry in settings_entries(&dir) {
+            fsutil::copy_recursive(&entry.path(), &target.join(entry.file_name()))
+                .context(format!("Failed to export Steam settings from {}", dir.display()))?;
+        }
+        stored.push(StoredSettings { account_id, kind, stored_name });
+    }
+    Ok(stored)
+}
+
+// The account on this machine that settings exported from `account_id` go to:
+// the same one if it's here, otherwise the only account there is. With several
+// other accounts it's unclear whose settings they are, so they're skipped.
+fn local_account(accounts: &[String], account_id: &str) -> Option<String> {
+    if accounts.iter().any(|id| id == account_id) {
+        return Some(account_id.to_string());
+    }
+    match accounts {
+        [only] => Some(only.clone()),
+        _ => None,
+    }
+}
+
+// Where `import` writes each stored folder, for taking a snapshot first.
+// Folders without a matching account are left out.
+pub fn import_targets(config: &Config, app_id: &str, stored: &[StoredSettings]) -> Vec<(StoredSettings, PathBuf)> {
+    // The App ID ends up in the path, see `import`
+    if !compatdata::is_app_id(app_id) {
+        return Vec::new();
+    }
+    let accounts = steam::active_account_ids(config);
+    stored
+        .iter()
+        .filter_map(|settings| {
+            let account_id = local_account(&accounts, &settings.account_id)?;
+            Some((settings.clone(), settings_dir(config, &account_id, settings.kind, app_id)))
+        })
+        .collect()
+}
+
+// Copy the settings stored in `source` (a bundle's `EXPORT_FOLDER`) back over
+// the current ones, returns how many folders were written
+pub fn import(config: &Config, app_id: &str, source: &Path, stored: &[StoredSettings]) -> Result<usize> {
+    // It comes from the bundle as well
+    if !compatdata::is_app_id(app_id) {
+        anyhow::bail!("Invalid App ID {} in the bundle", app_id);
+    }
+    let targets = import_targets(config, app_id, stored);
+    for (settings, target) in &targets {
+        // Don't let a tampered bundle read from outside of it
+        if !fsutil::is_plain_relative(Path::new(&settings.stored_name)) {
+            anyhow::bail!("Invalid Steam settings folder {} in the bundle", settings.stored_name);
+        }
+        fsutil::copy_recursive(&source.join(&settings.stored_name), target)
+            .context(format!("Failed to import Steam settings into {}", target.display()))?;
+    }
+    if targets.len() < stored.len() {
+        eprintln!("Skipped {} Steam settings folders of {}, no matching account", stored.len() - targets.len(), app_id);
+    }
+    Ok(targets.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{write_file, Fixture};
+
+    #[test]
+    fn exports_settings_without_cloud_saves() {
+        let fixture = Fixture::new();
+        let config = fixture.config();
+        let userdata = config.steam_userdata_path().join("12345678");
+        write_file(&userdata.join("1000/local.vdf"), "settings");
+        write_file(&userdata.join("1000/remote/save.dat"), "cloud save");
+        write_file(&userdata.join("1000/remotecache.vdf"), "");
+        write_file(&config.steam_controller_configs_path().join("12345678/config/1000/controller_neptune.vdf"), "layout");
+        write_file(
+            &userdata.join("config/localconfig.vdf"),
+            r#""UserLocalConfigStore" { "Software" { "Valve" { "Steam" { "apps" { "1000" { "LaunchOptions" "PROTON_LOG=1 %command%" } } } } } }"#,
+        );
+
+        let options = launch_options(&config, "1000");
+        assert_eq!(options.len(), 1);
+        assert_eq!(options[0].options, "PROTON_LOG=1 %command%");
+
+        let dest = fixture.root.join("bundle");
+        let stored = export(&config, "1000", &dest).unwrap();
+        assert_eq!(stored.iter().map(|settings| settings.kind).collect::<Vec<_>>(), [SettingsKind::Userdata, SettingsKind::ControllerConfig]);
+        assert!(dest.join("0/local.vdf").exists());
+        assert!(!dest.join("0/remote").exists());
+
+        // Into another machine's only account
+        fs::remove_dir_all(&userdata).unwrap();
+        fs::create_dir_all(config.steam_userdata_path().join("87654321")).unwrap();
+        assert_eq!(import(&config, "1000", &dest, &stored).unwrap(), 2);
+        assert_eq!(fs::read_to_string(config.steam_userdata_path().join("87654321/1000/local.vdf")).unwrap(), "settings");
+    }
+}
@@ -0,0 +1,119 @@
+use std::fs;
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+
+use crate::config::{BackupTargetSettings, Config};
+
+// SteamOS updates replace the whole system partition, only /home and other
+// drives (SD cards, USB drives) are kept. Anything the app keeps elsewhere,
+// say a backup destination picked under /opt or snapshots moved to /var, is
+// gone after the next update. This finds those places so the user can be
+// warned and move them in time.
+
+// Where drives get mounted by hand or by fstab, other than the media root
+// SteamOS mounts SD cards at
+const MOUNT_DIRS: &[&str] = &["/mnt", "/media"];
+
+// Something the app writes to, for the warning
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Location {
+    Config,
+    Snapshots,
+    SyncFolder,
+    BackupTarget(String), // Name of a local backup destination
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AtRisk {
+    pub location: Location,
+    pub path: PathBuf,
+}
+
+// Whether this is SteamOS, from os-release
+pub fn is_steamos() -> bool {
+    fs::read_to_string("/etc/os-release").is_ok_and(|content| is_steamos_release(&content))
+}
+
+fn is_steamos_release(content: &str) -> bool {
+    content.lines().any(|line| {
+        line.strip_prefix("ID=")
+            .is_some_and(|id| id.trim_matches(|c| c == '"' || c == '\'') == "steamos")
+    })
+}
+
+// Places the next SteamOS update would wipe. Only worth asking on SteamOS,
+// and never in the demo, which lives in a temporary folder on purpose.
+pub fn locations_at_risk(config: &Config) -> Vec<AtRisk> {
+    match dirs::home_dir() {
+        Some(home) if !config.is_demo() => locations_at_risk_in(config, &home),
+        _ => Vec::new(),
+    }
+}
+
+fn locations_at_risk_in(config: &Config, home: &Path) -> Vec<AtRisk> {
+    let mut locations = vec![
+        (Location::Config, config.config_path().to_path_buf()),
+        (Location::Snapshots, config.safety_snapshots_path()),
+    ];
+    if let Some(sync_folder) = config.sync_folder() {
+        locations.push((Location::SyncFolder, sync_folder.to_path_buf()));
+    }
+    for target in config.backup_targets() {
+        // Remote destinations are another machine's business
+        if let BackupTargetSettings::Local { name, path } = target {
+            locations.push((Location::BackupTarget(name.clone()), path.clone()));
+        }
+    }
+    locations
+        .into_iter()
+        .filter(|(_, path)| is_wiped_by_updates(config, path, home))
+        .map(|(location, path)| AtRisk { location, path })
+        .collect()
+}
+
+fn is_wiped_by_updates(config: &Config, path: &Path, home: &Path) -> bool {
+    if path.starts_with(home) || path.starts_with("/home") || config.is_on_removable_media(path) {
+        return false;
+    }
+    !MOUNT_DIRS.iter().any(|dir| path.starts_with(dir) && is_on_another_drive(path, Path::new(dir)))
+}
+
+// Whether something is mounted between `path` and `mount_dir`, i.e. the
+// closest existing folder isn't on the same filesystem as `mount_dir` itself
+fn is_on_another_drive(path: &Path, mount_dir: &Path) -> bool {
+    let device = |path: &Path| fs::metadata(path).ok().map(|metadata| metadata.dev());
+    let Some(closest) = path.ancestors().find_map(device) else {
+        return false;
+    };
+    device(mount_dir).is_some_and(|mount_dir| mount_dir != closest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::Fixture;
+
+    #[test]
+    fn finds_places_steamos_updates_wipe() {
+        assert!(is_steamos_release("NAME=\"SteamOS\"\nID=steamos\nID_LIKE=arch\n"));
+        assert!(!is_steamos_release("NAME=\"Arch Linux\"\nID=arch\n"));
+
+        let fixture = Fixture::new();
+        let mut config = fixture.config();
+        // Everything in the fixture counts as the home folder
+        assert!(locations_at_risk_in(&config, &fixture.root).is_empty());
+
+        config.set_sync_folder(Some(PathBuf::from("/usr"))).unwrap();
+        let card = fixture.root.join("app/media/card");
+        fs::create_dir_all(&card).unwrap();
+        for (name, path) in [("Card", card), ("System", PathBuf::from("/usr"))] {
+            config.add_backup_target(BackupTargetSettings::Local { name: name.to_string(), path }).unwrap();
+        }
+        let home = fixture.root.join("home");
+        let at_risk: Vec<Location> = locations_at_risk_in(&config, &home).into_iter().map(|risk| risk.location).collect();
+        assert_eq!(
+            at_risk,
+            [Location::Config, Location::Snapshots, Location::SyncFolder, Location::BackupTarget("System".to_string())]
+        );
+    }
+}
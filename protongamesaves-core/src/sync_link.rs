@@ -0,0 +1,111 @@
+use anyhow::{bail, Context, Result};
+use std::fs;
+use std::os::unix::fs::symlink;
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::fsutil;
+use crate::snapshot::Snapshot;
+
+// Save locations moved into the sync folder (e.g. a Syncthing share) with a
+// symlink left in the prefix, so an external tool syncs them between machines.
+// Inside the sync folder every game gets `<App ID>/` followed by the location's
+// path inside its prefix, e.g. `1245620/drive_c/users/steamuser/AppData/...`,
+// the same on every machine.
+
+// Where the location `path` of the prefix `prefix_path` goes in the sync folder
+pub fn sync_target(sync_folder: &Path, prefix_path: &Path, path: &Path) -> Option<PathBuf> {
+    let app_id = prefix_path.file_name()?;
+    let relative = path.strip_prefix(prefix_path).ok()?;
+    let relative = relative.strip_prefix("pfx").unwrap_or(relative);
+    fsutil::is_plain_relative(relative).then(|| sync_folder.join(app_id).join(relative))
+}
+
+// The sync folder copy a location links to, if it's been linked
+pub fn linked_target(path: &Path) -> Option<PathBuf> {
+    if !fs::symlink_metadata(path).ok()?.file_type().is_symlink() {
+        return None;
+    }
+    fs::read_link(path).ok()
+}
+
+// Move the location into the sync folder and link to it. When the sync folder
+// already has it (synced from another machine), that copy wins and the local
+// files go into a safety snapshot instead. Returns the sync folder path.
+pub fn link(config: &Config, prefix_path: &Path, path: &Path) -> Result<PathBuf> {
+    let Some(sync_folder) = config.sync_folder() else {
+        bail!("No sync folder is set, choose one in Settings first");
+    };
+    if linked_target(path).is_some() {
+        bail!("{} is already a link", path.display());
+    }
+    let Some(target) = sync_target(sync_folder, prefix_path, path) else {
+        bail!("{} is not inside the prefix {}", path.display(), prefix_path.display());
+    };
+    let app_id = prefix_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    if target.exists() {
+        if path.exists() {
+            Snapshot::create(config, &app_id, "Before linking to the sync folder", &[path.to_path_buf()])
+                .context("Failed to snapshot the local saves, not linking")?;
+            remove_path(path)?;
+            println!("Using the copy of {} already in the sync folder", path.display());
+        }
+    } else if path.exists() {
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).context(format!("Failed to create {}", parent.display()))?;
+        }
+        move_path(path, &target)?;
+    } else {
+        bail!("{} does not exist, there is nothing to link", path.display());
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).context(format!("Failed to create {}", parent.display()))?;
+    }
+    symlink(&target, path).context(format!("Failed to create symlink {}", path.display()))?;
+    println!("Linked {} to {}", path.display(), target.display());
+    Ok(target)
+}
+
+// Replace the link with a copy of the files. The sync folder keeps its copy,
+// other machines may still use it. Returns the sync folder path.
+pub fn unlink(path: &Path) -> Result<PathBuf> {
+    let Some(target) = linked_target(path) else {
+        bail!("{} is not linked to a sync folder", path.display());
+    };
+    fs::remove_file(path).context(format!("Failed to remove symlink {}", path.display()))?;
+    if let Err(e) = fsutil::copy_recursive(&target, path) {
+        // Put the link back rather than leave a half-copied save
+        let _ = remove_path(path);
+        let _ = symlink(&target, path);
+        return Err(e.context(format!("Failed to copy {} back", target.display())));
+    }
+    println!("Unlinked {} from {}", path.display(), target.display());
+    Ok(target)
+}
+
+// Rename when both are on the same filesystem, copy and delete otherwise
+fn move_path(from: &Path, to: &Path) -> Result<()> {
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    if let Err(e) = fsutil::copy_recursive(from, to) {
+        let _ = remove_path(to);
+        return Err(e.context(format!("Failed to move {} to {}", from.display(), to.display())));
+    }
+    remove_path(from)
+}
+
+fn remove_path(path: &Path) -> Result<()> {
+    let metadata = fs::symlink_metadata(path).context(format!("Failed to read {}", path.display()))?;
+    if metadata.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    }
+    .context(format!("Failed to remove {}", path.display()))
+}
@@ -0,0 +1,220 @@
+use anyhow::{bail, Context, Result};
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+
+// Reads the tar archives of compressed snapshots straight from the
+// decompressor's output, so one can be listed or have a single file taken out
+// without unpacking all of it. Only what GNU tar writes for files and folders
+// is understood: ustar headers, GNU long names and pax paths. Anything else
+// (links, devices) is skipped.
+
+const BLOCK: usize = 512;
+// Long names and pax headers are tiny, anything bigger isn't one
+const MAX_META_BYTES: u64 = 1 << 20;
+
+pub struct TarEntry {
+    pub path: PathBuf, // As stored, e.g. `files/0/slot1.sav`
+    pub is_dir: bool,
+    pub size: u64,
+    pub modified: Option<u64>, // Unix timestamp (seconds)
+}
+
+// Every file and folder in `archive`, which `compressor` (zstd, gzip)
+// decompresses with `-dc`
+pub fn list(archive: &Path, compressor: &str) -> Result<Vec<TarEntry>> {
+    let mut entries = Vec::new();
+    read_archive(archive, compressor, |entry, _| {
+        entries.push(TarEntry { path: entry.path.clone(), is_dir: entry.is_dir, size: entry.size, modified: entry.modified });
+        Ok(true)
+    })?;
+    Ok(entries)
+}
+
+// Copy the file at `path` in `archive` to `dest`, reading no further than
+// it. False if the archive has no such file.
+pub fn extract_file(archive: &Path, compressor: &str, path: &Path, dest: &Path) -> Result<bool> {
+    let mut found = false;
+    read_archive(archive, compressor, |entry, content| {
+        if entry.is_dir || entry.path != path {
+            return Ok(true);
+        }
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent).context(format!("Failed to create {}", parent.display()))?;
+        }
+        let mut file = fs::File::create(dest).context(format!("Failed to write {}", dest.display()))?;
+        io::copy(content, &mut file).context(format!("Failed to unpack {}", path.display()))?;
+        if let Some(modified) = entry.modified {
+            let _ = file.set_modified(std::time::UNIX_EPOCH + std::time::Duration::from_secs(modified));
+        }
+        found = true;
+        Ok(false)
+    })?;
+    Ok(found)
+}
+
+fn read_archive(archive: &Path, compressor: &str, visit: impl FnMut(&TarEntry, &mut dyn Read) -> Result<bool>) -> Result<()> {
+    let mut child = spawn(archive, compressor)?;
+    let Some(stdout) = child.stdout.take() else {
+        bail!("{} has no output", compressor);
+    };
+    let result = read_entries(stdout, visit);
+    // Stopped early on purpose, or on an error: the rest isn't needed
+    let finished = result.as_ref().is_ok_and(|&read_all| read_all);
+    if !finished {
+        let _ = child.kill();
+    }
+    let status = child.wait().context(format!("Failed to run {}", compressor))?;
+    result?;
+    if finished && !status.success() {
+        bail!("Failed to decompress {} ({})", archive.display(), status);
+    }
+    Ok(())
+}
+
+fn spawn(archive: &Path, compressor: &str) -> Result<Child> {
+    Command::new(compressor)
+        .arg("-dc")
+        .arg(archive)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .context(format!("Couldn't run {}", compressor))
+}
+
+// Calls `visit` with each entry and a reader over its contents, until it
+// returns false. Returns whether the whole archive was read.
+fn read_entries(mut reader: impl Read, mut visit: impl FnMut(&TarEntry, &mut dyn Read) -> Result<bool>) -> Result<bool> {
+    let mut header = [0u8; BLOCK];
+    let mut long_name: Option<String> = None;
+    loop {
+        if !read_block(&mut reader, &mut header)? || header.iter().all(|&byte| byte == 0) {
+            return Ok(true);
+        }
+        let size = number(&header[124..136]).context("Broken tar header")?;
+        let padding = (BLOCK as u64 - size % BLOCK as u64) % BLOCK as u64;
+        let kind = header[156];
+
+        if matches!(kind, b'L' | b'x' | b'g') {
+            if size > MAX_META_BYTES {
+                bail!("Broken tar header, {} bytes of metadata", size);
+            }
+            let mut meta = Vec::new();
+            (&mut reader).take(size).read_to_end(&mut meta)?;
+            skip(&mut reader, padding)?;
+            match kind {
+                b'L' => long_name = Some(text(&meta)),
+                b'x' => long_name = pax_path(&meta).or(long_name),
+                _ => {}
+            }
+            continue;
+        }
+
+        let name = long_name.take().unwrap_or_else(|| {
+            let (name, prefix) = (text(&header[0..100]), text(&header[345..500]));
+            if prefix.is_empty() { name } else { format!("{}/{}", prefix, name) }
+        });
+        let entry = TarEntry {
+            path: PathBuf::from(name.trim_end_matches('/')),
+            is_dir: kind == b'5',
+            size: if kind == b'5' { 0 } else { size },
+            modified: number(&header[136..148]),
+        };
+        let mut content = (&mut reader).take(size);
+        if matches!(kind, b'0' | 0 | b'5') && !visit(&entry, &mut content)? {
+            return Ok(false);
+        }
+        // Whatever `visit` didn't read
+        io::copy(&mut content, &mut io::sink())?;
+        skip(&mut reader, padding)?;
+    }
+}
+
+// False at the end of the stream
+fn read_block(reader: &mut impl Read, block: &mut [u8; BLOCK]) -> Result<bool> {
+    let mut filled = 0;
+    while filled < BLOCK {
+        match reader.read(&mut block[filled..])? {
+            0 if filled == 0 => return Ok(false),
+            0 => bail!("The archive ends in the middle of a header"),
+            read => filled += read,
+        }
+    }
+    Ok(true)
+}
+
+fn skip(reader: &mut impl Read, bytes: u64) -> Result<()> {
+    let skipped = io::copy(&mut reader.take(bytes), &mut io::sink())?;
+    if skipped != bytes {
+        bail!("The archive ends in the middle of a file");
+    }
+    Ok(())
+}
+
+// Up to the first NUL
+fn text(field: &[u8]) -> String {
+    let end = field.iter().position(|&byte| byte == 0).unwrap_or(field.len());
+    String::from_utf8_lossy(&field[..end]).to_string()
+}
+
+// Octal digits, or big-endian binary when the top bit is set (GNU tar does
+// that for sizes over 8 GB)
+fn number(field: &[u8]) -> Option<u64> {
+    if field.first().is_some_and(|&byte| byte & 0x80 != 0) {
+        return Some(field[1..].iter().fold(0u64, |value, &byte| (value << 8) | byte as u64));
+    }
+    let digits = text(field);
+    let digits = digits.trim_matches(|c: char| c == ' ' || c == '\0');
+    if digits.is_empty() {
+        return Some(0);
+    }
+    u64::from_str_radix(digits, 8).ok()
+}
+
+// The `path` record of a pax header: "<length> path=<value>\n" records
+fn pax_path(meta: &[u8]) -> Option<String> {
+    let text = String::from_utf8_lossy(meta);
+    text.lines().find_map(|record| {
+        let (_, field) = record.split_once(' ')?;
+        field.strip_prefix("path=").map(str::to_string)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{write_file, Fixture};
+
+    #[test]
+    fn reads_archives_without_unpacking_them() {
+        let fixture = Fixture::new();
+        let long_name = format!("{}/slot1.sav", "deep".repeat(40));
+        write_file(&fixture.root.join("files/0").join(&long_name), "long save");
+        write_file(&fixture.root.join("files/1"), "single file");
+        let archive = fixture.root.join("files.tar.gz");
+        let status = Command::new("tar")
+            .arg("-c")
+            .arg("-I")
+            .arg("gzip")
+            .arg("-f")
+            .arg(&archive)
+            .arg("-C")
+            .arg(&fixture.root)
+            .arg("files")
+            .status()
+            .unwrap();
+        assert!(status.success());
+
+        let entries = list(&archive, "gzip").unwrap();
+        let long = entries.iter().find(|entry| entry.path == Path::new("files/0").join(&long_name)).unwrap();
+        assert_eq!(long.size, 9);
+        assert!(!long.is_dir);
+        assert!(entries.iter().any(|entry| entry.path == Path::new("files/0") && entry.is_dir));
+
+        let dest = fixture.root.join("out/1");
+        assert!(extract_file(&archive, "gzip", Path::new("files/1"), &dest).unwrap());
+        assert_eq!(fs::read_to_string(&dest).unwrap(), "single file");
+        assert!(!extract_file(&archive, "gzip", Path::new("files/missing"), &dest).unwrap());
+    }
+}
@@ -0,0 +1,126 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::compatdata;
+use crate::config::Config;
+use crate::manifest::{self, ManifestData, ManifestInputs};
+
+// A small manifest covering the cases the matching has to tell apart: a game
+// found by App ID, one only found by its save folder, one sharing an App ID
+// with an entry that has no save paths, and one that is never installed
+pub const MINI_MANIFEST: &str = r#"
+"Lonely Hero":
+  files:
+    "<winAppData>/LonelyHero/Saves":
+      tags:
+        - save
+  steam:
+    id: 1000
+"Folder Only Game":
+  files:
+    "<winDocuments>/My Games/Folder Only":
+      tags:
+        - save
+"Shared Id Game":
+  files:
+    "<winLocalAppData>/SharedId":
+      tags:
+        - save
+  steam:
+    id: 3000
+"Shared Id Soundtrack":
+  steam:
+    id: 3000
+"Not Installed Game":
+  files:
+    "<winAppData>/NotInstalled":
+      tags:
+        - save
+  steam:
+    id: 4000
+"#;
+
+// Keeps fixtures of tests running in parallel apart
+static NEXT_FIXTURE: AtomicUsize = AtomicUsize::new(0);
+
+// A fake Steam install in a fresh temp folder, removed again on drop. Prefixes
+// and save files are added per test, the manifest is `MINI_MANIFEST` unless a
+// test writes its own.
+pub struct Fixture {
+    pub root: PathBuf,
+}
+
+impl Fixture {
+    pub fn new() -> Self {
+        let root = std::env::temp_dir().join(format!(
+            "proton_game_saves_test_{}_{}",
+            std::process::id(),
+            NEXT_FIXTURE.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("app")).expect("Failed to create the fixture folder");
+        let fixture = Self { root };
+        fixture.write_manifest(MINI_MANIFEST);
+        fixture
+    }
+
+    // What Config::steam_path points at, same layout as the demo fixture
+    pub fn steam_path(&self) -> PathBuf {
+        self.root.join("steam-root")
+    }
+
+    pub fn compatdata_path(&self) -> PathBuf {
+        self.steam_path().join("steam/steamapps/compatdata")
+    }
+
+    pub fn manifest_path(&self) -> PathBuf {
+        self.root.join("manifest.yaml")
+    }
+
+    pub fn config(&self) -> Config {
+        Config::at_root(&self.root.join("app"), self.steam_path(), self.manifest_path())
+    }
+
+    pub fn write_manifest(&self, content: &str) {
+        fs::write(self.manifest_path(), content).expect("Failed to write the fixture manifest");
+    }
+
+    // Parse the fixture manifest the way the app does
+    pub fn manifest(&self) -> ManifestData {
+        manifest::parse_manifest_inputs(&ManifestInputs::new(&self.config()), |_| {})
+            .expect("Failed to parse the fixture manifest")
+    }
+
+    // An empty prefix in the main library, returns its folder
+    pub fn add_prefix(&self, app_id: &str) -> PathBuf {
+        let prefix = self.compatdata_path().join(app_id);
+        compatdata::create_prefix_skeleton(&prefix).expect("Failed to create the fixture prefix");
+        prefix
+    }
+
+    // A file at `relative` (to steamuser) in the prefix of `app_id`, which has
+    // to exist already
+    pub fn add_file(&self, app_id: &str, relative: &str, content: &str) -> PathBuf {
+        let path = self
+            .compatdata_path()
+            .join(app_id)
+            .join("pfx/drive_c/users/steamuser")
+            .join(relative);
+        write_file(&path, content);
+        path
+    }
+}
+
+impl Drop for Fixture {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.root);
+    }
+}
+
+pub fn write_file(path: &Path, content: &str) {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).expect("Failed to create the fixture folder");
+    }
+    fs::write(path, content).expect("Failed to write the fixture file");
+}
@@ -0,0 +1,201 @@
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::activity::{self, ActivityEntry, ActivityKind};
+use crate::config::Config;
+use crate::fsutil;
+use crate::shader_cache;
+use crate::snapshot::Snapshot;
+
+// Things inside save folders that are obviously not saves, offered for
+// deletion by the "Trim" action of a game:
+//
+//   CrashDumps/, Crashes/, *.dmp, *.mdmp   <- crash reports
+//   Logs/, *.log                           <- logs
+//   Cache/, GPUCache/, *.dxvk-cache, ...   <- caches the game rebuilds
+//   autosave_12.sav, AutoSave3/, ...       <- autosaves beyond the newest few in a folder
+//
+// Only suggestions, the user picks what actually goes from a preview.
+// Autosaves are still saves, they're put in a safety snapshot before deleting.
+pub const KEEP_AUTOSAVES: usize = 3;
+const CRASH_DIRS: [&str; 4] = ["crashdumps", "crashes", "crashreports", "crash reports"];
+const CRASH_EXTENSIONS: [&str; 2] = ["dmp", "mdmp"];
+const LOG_DIRS: [&str; 1] = ["logs"];
+const LOG_EXTENSIONS: [&str; 1] = ["log"];
+const CACHE_DIRS: [&str; 6] = ["cache", "caches", "gpucache", "shadercache", "webcache", "code cache"];
+const AUTOSAVE_MARKERS: [&str; 3] = ["autosave", "auto_save", "auto-save"];
+// Save folders are shallow, this is plenty
+const MAX_DEPTH: usize = 8;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum TrimKind {
+    CrashDump,
+    Log,
+    Cache,
+    OldAutosave,
+}
+
+#[derive(Clone, Debug)]
+pub struct TrimSuggestion {
+    pub path: PathBuf,
+    pub kind: TrimKind,
+    pub size: u64,
+}
+
+// What looks like junk in the save `locations` of a game, biggest first.
+// Walks the folders, meant for a worker thread.
+pub fn suggest(locations: &[PathBuf], keep_autosaves: usize) -> Vec<TrimSuggestion> {
+    let mut found = Vec::new();
+    for location in locations {
+        if location.is_dir() {
+            collect(location, MAX_DEPTH, keep_autosaves, &mut found);
+        }
+    }
+    found.sort_by_key(|suggestion| std::cmp::Reverse(suggestion.size));
+    found
+}
+
+// The kind of junk `path` is by its name, autosaves aside
+fn kind_of(path: &Path, is_dir: bool) -> Option<TrimKind> {
+    let name = path.file_name()?.to_string_lossy().to_lowercase();
+    let extension = path.extension().map(|extension| extension.to_string_lossy().to_lowercase()).unwrap_or_default();
+    if is_dir {
+        if CRASH_DIRS.contains(&name.as_str()) {
+            Some(TrimKind::CrashDump)
+        } else if LOG_DIRS.contains(&name.as_str()) {
+            Some(TrimKind::Log)
+        } else if CACHE_DIRS.contains(&name.as_str()) {
+            Some(TrimKind::Cache)
+        } else {
+            None
+        }
+    } else if CRASH_EXTENSIONS.contains(&extension.as_str()) {
+        Some(TrimKind::CrashDump)
+    } else if LOG_EXTENSIONS.contains(&extension.as_str()) {
+        Some(TrimKind::Log)
+    } else if shader_cache::is_cache_file(path) {
+        Some(TrimKind::Cache)
+    } else {
+        None
+    }
+}
+
+fn is_autosave(path: &Path) -> bool {
+    let name = path.file_name().map(|name| name.to_string_lossy().to_lowercase()).unwrap_or_default();
+    AUTOSAVE_MARKERS.iter().any(|marker| name.contains(marker))
+}
+
+fn collect(dir: &Path, depth: usize, keep_autosaves: usize, found: &mut Vec<TrimSuggestion>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    // Autosaves of this folder, newest first once sorted
+    let mut autosaves: Vec<(Option<u64>, PathBuf)> = Vec::new();
+    let mut subdirs = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(metadata) = fs::symlink_metadata(&path) else {
+            continue;
+        };
+        if metadata.is_symlink() {
+            continue;
+        }
+        if let Some(kind) = kind_of(&path, metadata.is_dir()) {
+            found.push(TrimSuggestion { size: fsutil::disk_usage(&path), path, kind });
+        } else if is_autosave(&path) {
+            autosaves.push((fsutil::last_modified(&path), path));
+        } else if metadata.is_dir() && depth > 0 {
+            subdirs.push(path);
+        }
+    }
+    autosaves.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| b.1.cmp(&a.1)));
+    for (_, path) in autosaves.into_iter().skip(keep_autosaves) {
+        found.push(TrimSuggestion { size: fsutil::disk_usage(&path), path, kind: TrimKind::OldAutosave });
+    }
+    for subdir in subdirs {
+        collect(&subdir, depth - 1, keep_autosaves, found);
+    }
+}
+
+// Delete the picked `suggestions` of `app_id`, returns the bytes freed. The
+// old autosaves go into a safety snapshot first, nothing is deleted if that fails.
+pub fn trim(config: &Config, app_id: &str, suggestions: &[TrimSuggestion]) -> Result<u64> {
+    let autosaves: Vec<PathBuf> = suggestions
+        .iter()
+        .filter(|suggestion| suggestion.kind == TrimKind::OldAutosave)
+        .map(|suggestion| suggestion.path.clone())
+        .collect();
+    if !autosaves.is_empty() {
+        Snapshot::create(config, app_id, "Before trimming old autosaves", &autosaves)
+            .context("Could not back up the old autosaves, nothing was deleted")?;
+    }
+
+    let mut freed = 0;
+    let mut failed: HashMap<PathBuf, String> = HashMap::new();
+    for suggestion in suggestions {
+        let result = if suggestion.path.is_dir() { fs::remove_dir_all(&suggestion.path) } else { fs::remove_file(&suggestion.path) };
+        match result {
+            Ok(()) => freed += suggestion.size,
+            Err(e) => {
+                failed.insert(suggestion.path.clone(), e.to_string());
+            }
+        }
+    }
+    activity::record(
+        config,
+        ActivityEntry::new(ActivityKind::Delete, "Trimmed save folders")
+            .game(config, app_id)
+            .paths(suggestions.iter().filter(|suggestion| !failed.contains_key(&suggestion.path)).map(|suggestion| suggestion.path.clone()))
+            .bytes(freed),
+    );
+    println!("Trimmed {} items ({} bytes) from the saves of {}", suggestions.len() - failed.len(), freed, app_id);
+    if let Some((path, error)) = failed.iter().next() {
+        anyhow::bail!("Failed to delete {} of the items, e.g. {}: {}", failed.len(), path.display(), error);
+    }
+    Ok(freed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{write_file, Fixture};
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn suggests_junk_and_old_autosaves() {
+        let fixture = Fixture::new();
+        let saves = fixture.root.join("saves");
+        write_file(&saves.join("slot1.sav"), "save");
+        write_file(&saves.join("CrashDumps/crash.dmp"), "dump");
+        write_file(&saves.join("Profile/game.log"), "log");
+        write_file(&saves.join("Profile/GPUCache/data_0"), "cache");
+        for index in 0..5 {
+            let path = saves.join(format!("autosave_{}.sav", index));
+            write_file(&path, "autosave");
+            let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000 + index);
+            fs::File::options().write(true).open(&path).unwrap().set_modified(modified).unwrap();
+        }
+
+        let suggestions = suggest(std::slice::from_ref(&saves), 3);
+        let kind_of = |relative: &str| suggestions.iter().find(|suggestion| suggestion.path == saves.join(relative)).map(|suggestion| suggestion.kind);
+        assert_eq!(kind_of("CrashDumps"), Some(TrimKind::CrashDump));
+        assert_eq!(kind_of("Profile/game.log"), Some(TrimKind::Log));
+        assert_eq!(kind_of("Profile/GPUCache"), Some(TrimKind::Cache));
+        // The two oldest autosaves, the newest three stay
+        assert_eq!(kind_of("autosave_0.sav"), Some(TrimKind::OldAutosave));
+        assert_eq!(kind_of("autosave_1.sav"), Some(TrimKind::OldAutosave));
+        assert_eq!(kind_of("autosave_2.sav"), None);
+        assert_eq!(kind_of("slot1.sav"), None);
+        assert_eq!(suggestions.len(), 5);
+
+        let config = fixture.config();
+        trim(&config, "1000", &suggestions).unwrap();
+        assert!(saves.join("slot1.sav").exists());
+        assert!(saves.join("autosave_4.sav").exists());
+        assert!(!saves.join("CrashDumps").exists());
+        assert!(!saves.join("autosave_0.sav").exists());
+        assert_eq!(crate::snapshot::list_snapshots(&config).len(), 1);
+    }
+}
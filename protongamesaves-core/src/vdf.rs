@@ -0,0 +1,173 @@
+use anyhow::{bail, Result};
+
+// Minimal parser for Valve's text KeyValues format (config.vdf, libraryfolders.vdf, ...):
+//
+//   "InstallConfigStore"
+//   {
+//       "Software" { "Valve" { ... } }
+//   }
+//
+// Keys are looked up case-insensitively since Steam isn't consistent about
+// casing ("Valve" vs "valve").
+
+#[derive(Debug, Clone)]
+pub enum VdfValue {
+    Str(String),
+    Table(VdfTable),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct VdfTable {
+    entries: Vec<(String, VdfValue)>,
+}
+
+impl VdfTable {
+    pub fn get(&self, key: &str) -> Option<&VdfValue> {
+        self.entries
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, v)| v)
+    }
+
+    pub fn table(&self, key: &str) -> Option<&VdfTable> {
+        match self.get(key) {
+            Some(VdfValue::Table(table)) => Some(table),
+            _ => None,
+        }
+    }
+
+    pub fn str(&self, key: &str) -> Option<&str> {
+        match self.get(key) {
+            Some(VdfValue::Str(value)) => Some(value),
+            _ => None,
+        }
+    }
+
+    // Follow nested tables, e.g. &["InstallConfigStore", "Software", "Valve"]
+    pub fn path(&self, keys: &[&str]) -> Option<&VdfTable> {
+        keys.iter().try_fold(self, |table, key| table.table(key))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &VdfValue)> {
+        self.entries.iter().map(|(k, v)| (k.as_str(), v))
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Str(String),
+    Open,
+    Close,
+}
+
+pub fn parse(input: &str) -> Result<VdfTable> {
+    let tokens = tokenize(input)?;
+    let mut pos = 0;
+    let root = parse_table(&tokens, &mut pos, false)?;
+    Ok(root)
+}
+
+// Parse `key value` / `key { ... }` pairs until a closing brace (or the end for the root)
+fn parse_table(tokens: &[Token], pos: &mut usize, nested: bool) -> Result<VdfTable> {
+    let mut table = VdfTable::default();
+    loop {
+        match tokens.get(*pos) {
+            None if nested => bail!("Unexpected end of file, missing '}}'"),
+            None => return Ok(table),
+            Some(Token::Close) if nested => {
+                *pos += 1;
+                return Ok(table);
+            }
+            Some(Token::Close) => bail!("Unexpected '}}'"),
+            Some(Token::Open) => bail!("Unexpected '{{' without a key"),
+            Some(Token::Str(key)) => {
+                *pos += 1;
+                let value = match tokens.get(*pos) {
+                    Some(Token::Open) => {
+                        *pos += 1;
+                        VdfValue::Table(parse_table(tokens, pos, true)?)
+                    }
+                    Some(Token::Str(value)) => {
+                        *pos += 1;
+                        VdfValue::Str(value.clone())
+                    }
+                    _ => bail!("Missing value for key '{}'", key),
+                };
+                table.entries.push((key.clone(), value));
+            }
+        }
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '/' => {
+                // `// comment` until the end of the line
+                chars.next();
+                if chars.peek() != Some(&'/') {
+                    bail!("Unexpected '/'");
+                }
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+            }
+            '{' => {
+                chars.next();
+                tokens.push(Token::Open);
+            }
+            '}' => {
+                chars.next();
+                tokens.push(Token::Close);
+            }
+            '"' => {
+                chars.next();
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        None => bail!("Unterminated string"),
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some('n') => value.push('\n'),
+                            Some('t') => value.push('\t'),
+                            Some(other) => value.push(other),
+                            None => bail!("Unterminated string"),
+                        },
+                        Some(other) => value.push(other),
+                    }
+                }
+                tokens.push(Token::Str(value));
+            }
+            '[' => {
+                // Platform conditionals like [$WIN32] apply to the previous pair; ignore them
+                for c in chars.by_ref() {
+                    if c == ']' {
+                        break;
+                    }
+                }
+            }
+            _ => {
+                // Unquoted token
+                let mut value = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || matches!(c, '{' | '}' | '"') {
+                        break;
+                    }
+                    value.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::Str(value));
+            }
+        }
+    }
+
+    Ok(tokens)
+}
@@ -12,8 +12,8 @@ OLD_VERSION=$(grep '^version = ' Cargo.toml | sed 's/version = "\(.*\)"/\1/')
 
 echo "🔄 Bumping version from $OLD_VERSION to $NEW_VERSION..."
 
-# Update Cargo.toml
-sed -i "s/^version = \".*\"/version = \"$NEW_VERSION\"/" Cargo.toml
+# Update Cargo.toml of the app and of the core library
+sed -i "s/^version = \".*\"/version = \"$NEW_VERSION\"/" Cargo.toml protongamesaves-core/Cargo.toml
 
 # Update PKGBUILD
 sed -i "s/^pkgver=.*/pkgver=$NEW_VERSION/" packaging/aur/PKGBUILD
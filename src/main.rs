@@ -1,39 +1,38 @@
 use adw::prelude::*;
 use gtk::glib;
-use once_cell::sync::Lazy;
-use std::collections::HashSet;
 
-// Constants for ignored directories and save paths
-static IGNORE_DIRS: Lazy<HashSet<&'static str>> = Lazy::new(|| {
-    HashSet::from([
-        "Microsoft",
-        "Temp",
-        "Packages",
-        "ConnectedDevicesPlatform",
-        "Comms",
-        "Apps",
-    ])
-});
-
-static SAVE_PATHS: Lazy<Vec<&'static str>> = Lazy::new(|| {
-    vec![
-        "AppData/Local",
-        "AppData/LocalLow",
-        "AppData/Roaming",
-        "Saved Games",
-    ]
-});
+// The GTK-free core, re-exported so `crate::config` and friends keep working
+use protongamesaves_core::{
+    activity, anonymize, backup_target, bundle, cleanup, cloud, compatdata, config, deep_link, demo, fsutil, i18n, ignore_patterns,
+    import, lan_sync, manifest, prefix_backup, prefix_sizes, preview, recover, registry, report, save_editor,
+    scan_changes, screenshots, size_history, snapshot, steam, steamos, sync_link, trim,
+};
 
 // Import our application modules
-mod ui;
-mod compatdata;
-mod config;
-mod manifest;
+mod mounts;
 mod styles;
+mod ui;
 
 fn main() -> glib::ExitCode {
+    // `--demo` runs against a generated sample library instead of the real Steam install.
+    // GApplication doesn't know the flag, so take it out before handing the args over.
+    let mut args: Vec<String> = std::env::args().collect();
+    if let Some(pos) = args.iter().position(|arg| arg == "--demo") {
+        args.remove(pos);
+        std::env::set_var(demo::DEMO_ENV_VAR, "1");
+    }
+    if demo::is_enabled() {
+        if let Err(e) = demo::generate_fixture() {
+            eprintln!("Failed to generate demo library: {}", e);
+            return glib::ExitCode::FAILURE;
+        }
+    }
+
     // Initialize GTK
     adw::init().expect("Failed to initialize libadwaita");
+
+    // Load translations for UI strings
+    i18n::init();
     
     // Load application CSS
     styles::load_app_css();
@@ -42,31 +41,92 @@ fn main() -> glib::ExitCode {
     let app = adw::Application::builder()
         .application_id("io.github.rahatzamancse.ProtonGameSaves")
         .build();
-        
+    // One instance per user: launching the app again, or opening a link, hands
+    // the arguments to the running instance over D-Bus, which raises its
+    // window instead of starting a second process racing it for the config
+    let mut flags = gtk::gio::ApplicationFlags::HANDLES_OPEN | gtk::gio::ApplicationFlags::HANDLES_COMMAND_LINE;
+    // Don't hand the demo over to an already running (real) instance
+    if demo::is_enabled() {
+        flags |= gtk::gio::ApplicationFlags::NON_UNIQUE;
+    }
+    app.set_flags(flags);
+    app.add_main_option(
+        "appid",
+        glib::Char::from(b'\0'),
+        glib::OptionFlags::NONE,
+        glib::OptionArg::String,
+        &i18n::gettext("Open the game with this App ID"),
+        Some("APP_ID"),
+    );
+
     // Connect to the activate signal
     app.connect_activate(|app| {
-        // Create config to check if it's first run
-        let config = std::rc::Rc::new(std::cell::RefCell::new(config::Config::new()));
+        // Already running, bring the window (or the welcome dialog) back up
+        if let Some(window) = app.windows().first() {
+            window.present();
+            return;
+        }
+
+        // One config shared by every window and dialog, they get told about changes through `Config::connect_changed`
+        let mut config = config::Config::new();
+        config.set_dispatcher(|call| {
+            glib::idle_add_local_once(call);
+        });
+        let config = std::rc::Rc::new(std::cell::RefCell::new(config));
         
         if config.borrow().is_first_run() {
             // Show welcome dialog first
             let welcome = ui::welcome_dialog::WelcomeDialog::new(
                 Some(app), 
                 config.clone(),
-                glib::clone!(@weak app => move || {
+                glib::clone!(@weak app, @strong config => move || {
                     // After welcome is complete, show main window
-                    let window = ui::window::ProtonSavesWindow::new(&app);
+                    let window = ui::window::ProtonSavesWindow::new(&app, config);
                     window.present();
                 })
             );
             welcome.present();
         } else {
             // Show main window directly
-            let window = ui::window::ProtonSavesWindow::new(app);
+            let window = ui::window::ProtonSavesWindow::new(app, config);
             window.present();
         }
     });
     
+    // Arguments of this launch or of a later one, in the running instance:
+    // `--appid <App ID>` and proton-game-saves://game/<App ID> links open
+    // that game, anything else just raises the window
+    app.connect_command_line(|app, command_line| {
+        let mut app_ids: Vec<String> = command_line.options_dict().lookup::<String>("appid").ok().flatten().into_iter().collect();
+        for arg in command_line.arguments().iter().skip(1) {
+            let arg = arg.to_string_lossy();
+            match deep_link::parse_game_uri(&arg) {
+                Some(app_id) => app_ids.push(app_id),
+                None => eprintln!("Ignoring unknown argument {}", arg),
+            }
+        }
+        open_games(app, &app_ids);
+        0
+    });
+    // Links opened through D-Bus, e.g. by a desktop that activates apps that way
+    app.connect_open(|app, files, _| {
+        let app_ids: Vec<String> = files.iter().filter_map(|file| deep_link::parse_game_uri(&file.uri())).collect();
+        open_games(app, &app_ids);
+    });
+
     // Run the application
-    app.run()
-} 
\ No newline at end of file
+    app.run_with_args(&args)
+}
+
+// Show a window, then each game in it once the lists have it
+fn open_games(app: &adw::Application, app_ids: &[String]) {
+    app.activate();
+    // Not there while the welcome dialog is still open
+    if app.lookup_action("open-game").is_none() {
+        return;
+    }
+    for app_id in app_ids {
+        println!("Opening game {}", app_id.trim());
+        app.activate_action("open-game", Some(&app_id.trim().to_variant()));
+    }
+}
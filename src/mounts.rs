@@ -0,0 +1,77 @@
+use anyhow::{anyhow, Context, Result};
+use gtk::gio;
+use gtk::prelude::*;
+use std::fs;
+use std::path::Path;
+
+use crate::backup_target::BackupTarget;
+
+// Export destinations that need gio, kept out of the core library so it stays
+// free of GTK. The bundle itself is written by `bundle::export_bundle`.
+
+// Mounted locations a bundle can be exported to besides local folders, e.g.
+// Google Drive or Nextcloud from GNOME Online Accounts, as name and root
+pub fn mounted_locations() -> Vec<(String, gio::File)> {
+    gio::VolumeMonitor::get()
+        .mounts()
+        .into_iter()
+        .map(|mount| (mount.name().to_string(), mount.default_location()))
+        .collect()
+}
+
+// A folder inside a mount without a local path, picked in the file dialog
+pub struct GioTarget {
+    parent: gio::File,
+}
+
+impl GioTarget {
+    pub fn new(parent: gio::File) -> Self {
+        Self { parent }
+    }
+}
+
+impl BackupTarget for GioTarget {
+    fn describe(&self) -> String {
+        self.parent.uri().to_string()
+    }
+
+    fn store(&self, bundle_dir: &Path) -> Result<String> {
+        upload_bundle(bundle_dir, &self.parent)
+    }
+}
+
+// Copy a finished bundle folder into `parent`, a GVfs location without a local
+// path (so only gio can write to it). Returns the URI of the uploaded bundle.
+// Talks to the network for cloud mounts, run it on a worker thread.
+pub fn upload_bundle(bundle_dir: &Path, parent: &gio::File) -> Result<String> {
+    let name = bundle_dir
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .ok_or_else(|| anyhow!("{} has no folder name", bundle_dir.display()))?;
+    let mut destination = parent.child(&name);
+    let mut suffix = 2;
+    while destination.query_exists(gio::Cancellable::NONE) {
+        destination = parent.child(format!("{}-{}", name, suffix));
+        suffix += 1;
+    }
+    upload_recursive(bundle_dir, &destination)?;
+    println!("Uploaded bundle {} to {}", bundle_dir.display(), destination.uri());
+    Ok(destination.uri().to_string())
+}
+
+fn upload_recursive(src: &Path, destination: &gio::File) -> Result<()> {
+    if src.is_dir() {
+        destination
+            .make_directory(gio::Cancellable::NONE)
+            .context(format!("Failed to create {}", destination.uri()))?;
+        for entry in fs::read_dir(src)? {
+            let entry = entry?;
+            upload_recursive(&entry.path(), &destination.child(entry.file_name()))?;
+        }
+    } else {
+        gio::File::for_path(src)
+            .copy(destination, gio::FileCopyFlags::NONE, gio::Cancellable::NONE, None)
+            .context(format!("Failed to upload {}", src.display()))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,234 @@
+use adw::prelude::*;
+use adw::{ActionRow, ExpanderRow, MessageDialog};
+use gtk::{glib, Box, Button, DropDown, Label, ListBox, Orientation, ScrolledWindow, SearchEntry, SelectionMode};
+use humansize::{format_size, DECIMAL};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::activity::{self, ActivityEntry, ActivityKind};
+use crate::compatdata;
+use crate::config::{Config, ConfirmAction, ViewState};
+use crate::i18n::{gettext, gettext_f};
+use crate::report;
+use crate::ui::{confirm, error_dialog};
+
+// Years of use add up to thousands of entries, only the newest matches get a row
+const MAX_RESULTS: usize = 200;
+
+// Kinds in the filter drop-down, after "Everything"
+const KIND_FILTERS: [ActivityKind; 4] = [ActivityKind::Backup, ActivityKind::Restore, ActivityKind::Delete, ActivityKind::Import];
+
+// Every backup, restore, deletion and import the app performed, searchable, to
+// check what was done to a game's saves and when
+pub struct ActivityPage {
+    widget: Box,
+    config: Rc<RefCell<Config>>,
+    list: ListBox,
+    status_label: Label,
+    search_entry: SearchEntry,
+    kind_filter: DropDown,
+    badge: Label, // Sidebar count of entries in the log
+    entries: RefCell<Vec<ActivityEntry>>, // Newest first
+}
+
+impl ActivityPage {
+    pub fn new(config: Rc<RefCell<Config>>, badge: Label) -> Rc<Self> {
+        let container = Box::new(Orientation::Vertical, 12);
+        container.set_margin_start(12);
+        container.set_margin_end(12);
+        container.set_margin_top(12);
+        container.set_margin_bottom(12);
+
+        let header = Label::new(Some(&gettext("Activity")));
+        header.add_css_class("title-1");
+        container.append(&header);
+        let description = Label::new(Some(&gettext("Everything the app backed up, restored, deleted or imported")));
+        description.add_css_class("subtitle-1");
+        container.append(&description);
+
+        let filter_box = Box::new(Orientation::Horizontal, 6);
+        filter_box.set_margin_top(12);
+        let search_entry = SearchEntry::new();
+        search_entry.set_placeholder_text(Some(&gettext("🔍 Search Games, App IDs or Paths...")));
+        search_entry.add_css_class("emoji");
+        search_entry.set_hexpand(true);
+        filter_box.append(&search_entry);
+        let kind_names: Vec<String> = std::iter::once(gettext("Everything"))
+            .chain(KIND_FILTERS.iter().map(|kind| kind_label(*kind)))
+            .collect();
+        let kind_names: Vec<&str> = kind_names.iter().map(String::as_str).collect();
+        let kind_filter = DropDown::from_strings(&kind_names);
+        kind_filter.set_tooltip_text(Some(&gettext("Show only one kind of operation")));
+        filter_box.append(&kind_filter);
+        let clear_button = Button::from_icon_name("user-trash-symbolic");
+        clear_button.set_tooltip_text(Some(&gettext("Clear History")));
+        filter_box.append(&clear_button);
+        container.append(&filter_box);
+
+        let status_label = Label::new(None);
+        status_label.add_css_class("dim-label");
+        status_label.set_wrap(true);
+        container.append(&status_label);
+
+        let list = ListBox::new();
+        list.set_selection_mode(SelectionMode::None);
+        list.add_css_class("boxed-list");
+        list.set_valign(gtk::Align::Start);
+        let scroll = ScrolledWindow::builder()
+            .hscrollbar_policy(gtk::PolicyType::Never)
+            .vexpand(true)
+            .child(&list)
+            .build();
+        container.append(&scroll);
+        search_entry.set_key_capture_widget(Some(&container));
+
+        let page = Rc::new(Self {
+            widget: container,
+            config: config.clone(),
+            list,
+            status_label,
+            search_entry: search_entry.clone(),
+            kind_filter: kind_filter.clone(),
+            badge,
+            entries: RefCell::new(Vec::new()),
+        });
+        // Restore the last search before connecting, so restoring doesn't save it again
+        search_entry.set_text(&config.borrow().view_state("activity").filter);
+        search_entry.connect_search_changed(glib::clone!(@weak page => move |entry| {
+            let state = ViewState {
+                filter: entry.text().to_string(),
+                ..page.config.borrow().view_state("activity")
+            };
+            if let Err(e) = page.config.borrow_mut().set_view_state("activity", state) {
+                eprintln!("Error saving view state of activity page: {}", e);
+            }
+            page.refresh();
+        }));
+        kind_filter.connect_selected_notify(glib::clone!(@weak page => move |_| page.refresh()));
+        clear_button.connect_clicked(glib::clone!(@weak page => move |button| page.confirm_clear(button)));
+        page.reload();
+        page
+    }
+
+    pub fn widget(&self) -> &Box {
+        &self.widget
+    }
+
+    // Read the log again, called when the page is shown since any dialog may
+    // have added to it in the meantime
+    pub fn reload(&self) {
+        let entries = activity::load(&self.config.borrow().activity_log_path());
+        self.badge.set_label(&if entries.is_empty() { String::new() } else { entries.len().to_string() });
+        self.entries.replace(entries);
+        self.refresh();
+    }
+
+    // Rebuild the list from the loaded entries, the search and the kind filter
+    fn refresh(&self) {
+        while let Some(child) = self.list.first_child() {
+            self.list.remove(&child);
+        }
+        let entries = self.entries.borrow();
+        let query = self.search_entry.text();
+        let kind = (self.kind_filter.selected() as usize).checked_sub(1).and_then(|index| KIND_FILTERS.get(index));
+        let matches: Vec<&ActivityEntry> = entries
+            .iter()
+            .filter(|entry| kind.is_none_or(|kind| entry.kind == *kind))
+            .filter(|entry| entry.matches(&query))
+            .collect();
+
+        self.status_label.set_label(&if entries.is_empty() {
+            gettext("Nothing recorded yet. Backups, restores, deletions and imports show up here.")
+        } else if matches.is_empty() {
+            gettext("No entries match the search")
+        } else if matches.len() > MAX_RESULTS {
+            gettext_f("Showing the newest {} of {} entries", &[&MAX_RESULTS.to_string(), &matches.len().to_string()])
+        } else {
+            gettext_f("{} entries", &[&matches.len().to_string()])
+        });
+        for entry in matches.into_iter().take(MAX_RESULTS) {
+            self.list.append(&Self::create_entry_row(entry));
+        }
+    }
+
+    fn create_entry_row(entry: &ActivityEntry) -> ExpanderRow {
+        let game = match (&entry.game_name, &entry.app_id) {
+            (Some(name), Some(app_id)) => Some(format!("{} ({})", name, app_id)),
+            (None, Some(app_id)) => Some(gettext_f("Game ID {}", &[app_id])),
+            _ => None,
+        };
+        let mut subtitle = vec![report::format_timestamp_utc(entry.time), kind_label(entry.kind)];
+        subtitle.extend(game);
+        subtitle.extend(entry.bytes.map(|bytes| format_size(bytes, DECIMAL)));
+
+        let row = ExpanderRow::builder()
+            .title(glib::markup_escape_text(&entry.summary))
+            .subtitle(glib::markup_escape_text(&subtitle.join(" · ")))
+            .build();
+        row.add_prefix(&gtk::Image::from_icon_name(kind_icon(entry.kind)));
+        row.set_enable_expansion(!entry.paths.is_empty());
+
+        for path in &entry.paths {
+            let path_row = ActionRow::builder()
+                .title(glib::markup_escape_text(&path.display().to_string()))
+                .build();
+            path_row.add_css_class("monospace");
+            // Deleted prefixes and pruned snapshots are gone, nothing to open
+            if path.exists() {
+                let open_button = Button::from_icon_name("folder-open-symbolic");
+                open_button.set_tooltip_text(Some(&gettext("Open in File Manager")));
+                open_button.set_valign(gtk::Align::Center);
+                open_button.add_css_class("flat");
+                let path = path.clone();
+                open_button.connect_clicked(move |_| {
+                    if let Err(e) = compatdata::open_in_file_manager(&path) {
+                        eprintln!("Failed to open {}: {}", path.display(), e);
+                    }
+                });
+                path_row.add_suffix(&open_button);
+            }
+            row.add_row(&path_row);
+        }
+        row
+    }
+
+    fn confirm_clear(self: &Rc<Self>, button: &Button) {
+        let dialog = MessageDialog::builder()
+            .heading(gettext("Clear Activity History?"))
+            .body(gettext("Removes the record of every operation. Backups and snapshots themselves are kept."))
+            .modal(true)
+            .build();
+        dialog.set_transient_for(button.root().and_downcast::<gtk::Window>().as_ref());
+        dialog.add_response("cancel", &gettext("Cancel"));
+        dialog.add_response("clear", &gettext("Clear"));
+        dialog.set_response_appearance("clear", adw::ResponseAppearance::Destructive);
+        dialog.set_default_response(Some("cancel"));
+        dialog.set_close_response("cancel");
+        let parent = dialog.transient_for();
+        confirm::run(&self.config, dialog, ConfirmAction::Delete, "clear-activity", "clear", glib::clone!(@weak self as page => move || {
+            if let Err(e) = activity::clear(&page.config.borrow().activity_log_path()) {
+                eprintln!("Error clearing activity history: {:#}", e);
+                error_dialog::show(parent.as_ref(), &gettext("Error Clearing History"), &e);
+            }
+            page.reload();
+        }));
+    }
+}
+
+fn kind_label(kind: ActivityKind) -> String {
+    match kind {
+        ActivityKind::Backup => gettext("Backup"),
+        ActivityKind::Restore => gettext("Restore"),
+        ActivityKind::Delete => gettext("Deletion"),
+        ActivityKind::Import => gettext("Import"),
+    }
+}
+
+fn kind_icon(kind: ActivityKind) -> &'static str {
+    match kind {
+        ActivityKind::Backup => "document-save-symbolic",
+        ActivityKind::Restore => "edit-undo-symbolic",
+        ActivityKind::Delete => "user-trash-symbolic",
+        ActivityKind::Import => "document-open-symbolic",
+    }
+}
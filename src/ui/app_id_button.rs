@@ -0,0 +1,28 @@
+use adw::prelude::*;
+use gtk::{glib, Align, Button};
+use std::time::Duration;
+
+use crate::i18n::{gettext, gettext_f};
+
+// Row button copying a game's App ID, for Steam, ProtonDB or a
+// proton-game-saves://game/<App ID> link back to the row
+pub fn new(app_id: &str) -> Button {
+    let button = Button::from_icon_name("edit-copy-symbolic");
+    let tooltip = gettext_f("Copy App ID {}", &[app_id]);
+    button.set_tooltip_text(Some(&tooltip));
+    button.set_valign(Align::Center);
+    button.add_css_class("flat");
+    let app_id = app_id.to_string();
+    button.connect_clicked(move |button| {
+        button.clipboard().set_text(&app_id);
+        // Confirms the copy for a moment, then back to the copy icon
+        button.set_icon_name("object-select-symbolic");
+        button.set_tooltip_text(Some(&gettext("Copied")));
+        let tooltip = tooltip.clone();
+        glib::timeout_add_local_once(Duration::from_secs(2), glib::clone!(@weak button => move || {
+            button.set_icon_name("edit-copy-symbolic");
+            button.set_tooltip_text(Some(&tooltip));
+        }));
+    });
+    button
+}
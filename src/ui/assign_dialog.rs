@@ -0,0 +1,142 @@
+use adw::prelude::*;
+use adw::MessageDialog;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::config::Config;
+use crate::i18n::{gettext, gettext_f};
+use crate::manifest::ManifestData;
+
+// The manifest has tens of thousands of games, only list the best matches
+const MAX_RESULTS: usize = 50;
+
+// Pick the manifest game an unmatched prefix belongs to. Saving goes through
+// the config, whose change notification rescans.
+pub fn show_assign(parent: Option<&gtk::Window>, config: Rc<RefCell<Config>>, app_id: &str, manifest: Rc<ManifestData>) {
+    let dialog = MessageDialog::builder()
+        .heading(gettext("Assign a Manifest Game"))
+        .body(gettext_f("Search the manifest for the game of App ID {}. Its save locations are then shown on the Games page.", &[app_id]))
+        .modal(true)
+        .build();
+    if let Some(parent) = parent {
+        dialog.set_transient_for(Some(parent));
+    }
+
+    let content = gtk::Box::new(gtk::Orientation::Vertical, 12);
+    let search_entry = gtk::SearchEntry::new();
+    search_entry.set_placeholder_text(Some(&gettext("Game name")));
+    content.append(&search_entry);
+
+    let results = gtk::ListBox::new();
+    results.set_selection_mode(gtk::SelectionMode::Single);
+    results.add_css_class("boxed-list");
+    let scrolled = gtk::ScrolledWindow::builder()
+        .hscrollbar_policy(gtk::PolicyType::Never)
+        .min_content_height(240)
+        .child(&results)
+        .build();
+    content.append(&scrolled);
+    dialog.set_extra_child(Some(&content));
+
+    dialog.add_response("cancel", &gettext("Cancel"));
+    dialog.add_response("assign", &gettext("Assign"));
+    dialog.set_response_appearance("assign", adw::ResponseAppearance::Suggested);
+    dialog.set_response_enabled("assign", false);
+    dialog.set_close_response("cancel");
+
+    // Game names of the rows currently listed, in order
+    let listed: Rc<RefCell<Vec<String>>> = Rc::new(RefCell::new(Vec::new()));
+    let matcher = SkimMatcherV2::default();
+    let listed_clone = listed.clone();
+    let dialog_clone = dialog.clone();
+    let results_clone = results.clone();
+    search_entry.connect_search_changed(move |entry| {
+        let results = &results_clone;
+        while let Some(child) = results.first_child() {
+            results.remove(&child);
+        }
+        dialog_clone.set_response_enabled("assign", false);
+        let query = entry.text().to_lowercase();
+        let mut names: Vec<(i64, &String)> = if query.is_empty() {
+            Vec::new()
+        } else {
+            manifest
+                .games
+                .keys()
+                .filter_map(|name| matcher.fuzzy_match(&name.to_lowercase(), &query).map(|score| (score, name)))
+                .collect()
+        };
+        names.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(b.1)));
+        names.truncate(MAX_RESULTS);
+
+        let mut listed = listed_clone.borrow_mut();
+        listed.clear();
+        for (_, name) in names {
+            results.append(&adw::ActionRow::builder().title(gtk::glib::markup_escape_text(name).as_str()).build());
+            listed.push(name.clone());
+        }
+    });
+    let dialog_clone = dialog.clone();
+    results.connect_row_selected(move |_, row| dialog_clone.set_response_enabled("assign", row.is_some()));
+
+    let app_id = app_id.to_string();
+    dialog.connect_response(None, move |dialog, response| {
+        if response != "assign" {
+            return;
+        }
+        let selected = results
+            .selected_row()
+            .and_then(|row| listed.borrow().get(row.index() as usize).cloned());
+        if let Err(e) = config.borrow_mut().set_game_assignment(&app_id, selected) {
+            eprintln!("Error saving game assignment: {}", e);
+        }
+        dialog.close();
+    });
+
+    dialog.present();
+}
+
+// Add a save path for a prefix by hand, for games the manifest doesn't know.
+// Paths use the manifest's placeholders so they resolve inside the prefix.
+pub fn show_custom_path(parent: Option<&gtk::Window>, config: Rc<RefCell<Config>>, app_id: &str) {
+    let dialog = MessageDialog::builder()
+        .heading(gettext("Add a Save Path"))
+        .body(gettext_f(
+            "Folder or file where App ID {} keeps its saves, e.g. <winDocuments>/My Game or <winAppData>/Studio/Game. Other placeholders: <winLocalAppData>, <winLocalAppDataLow>, <winProgramData>, <home>.",
+            &[app_id],
+        ))
+        .modal(true)
+        .build();
+    if let Some(parent) = parent {
+        dialog.set_transient_for(Some(parent));
+    }
+
+    let entry = gtk::Entry::builder()
+        .placeholder_text("<winDocuments>/My Game")
+        .activates_default(true)
+        .build();
+    dialog.set_extra_child(Some(&entry));
+
+    dialog.add_response("cancel", &gettext("Cancel"));
+    dialog.add_response("add", &gettext("Add"));
+    dialog.set_response_appearance("add", adw::ResponseAppearance::Suggested);
+    dialog.set_default_response(Some("add"));
+    dialog.set_close_response("cancel");
+
+    let app_id = app_id.to_string();
+    dialog.connect_response(None, move |dialog, response| {
+        if response != "add" {
+            return;
+        }
+        let mut paths = config.borrow().custom_save_paths(&app_id).to_vec();
+        paths.push(entry.text().to_string());
+        if let Err(e) = config.borrow_mut().set_custom_save_paths(&app_id, paths) {
+            eprintln!("Error saving custom save paths: {}", e);
+        }
+        dialog.close();
+    });
+
+    dialog.present();
+}
@@ -0,0 +1,374 @@
+use adw::prelude::*;
+use adw::{ActionRow, MessageDialog, NavigationPage, NavigationView, PreferencesGroup, PreferencesPage, StatusPage};
+use gtk::{gio, glib};
+use humansize::{format_size, DECIMAL};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use crate::cleanup::{self, CleanupCandidate, CleanupCriteria, CleanupOutcome};
+use crate::compatdata::ScanResult;
+use crate::config::{Config, ConfirmAction};
+use crate::i18n::{gettext, gettext_f};
+use crate::recover::RecoverGame;
+use crate::ui::compatdata_page::CompatDataPage;
+use crate::ui::confirm;
+
+const BYTES_PER_MB: u64 = 1_000_000;
+
+// Names and save locations of the matched games, from the Games page
+struct KnownGames {
+    names: HashMap<String, String>,
+    locations: HashMap<String, Vec<PathBuf>>,
+}
+
+// Guided cleanup of old prefixes: pick the criteria, review exactly what would
+// be removed (a dry run), then remove the checked prefixes and see what happened
+pub fn show(parent: &impl IsA<gtk::Window>, config: Rc<RefCell<Config>>, scan_result: Rc<RefCell<ScanResult>>, games: Vec<RecoverGame>) {
+    let window = adw::Window::builder()
+        .transient_for(parent)
+        .modal(true)
+        .title(gettext("Clean Up Prefixes"))
+        .default_width(560)
+        .default_height(640)
+        .build();
+    let known = Rc::new(KnownGames {
+        names: games.iter().map(|game| (game.app_id.clone(), game.name.clone())).collect(),
+        locations: games.into_iter().map(|game| (game.app_id, game.locations)).collect(),
+    });
+    let navigation = NavigationView::new();
+    navigation.add(&criteria_page(&window, &navigation, config, scan_result, known));
+    window.set_content(Some(&navigation));
+    window.present();
+}
+
+// Wraps page content in a header bar
+fn wizard_page(title: &str, content: &impl IsA<gtk::Widget>) -> NavigationPage {
+    let toolbar = adw::ToolbarView::new();
+    toolbar.add_top_bar(&adw::HeaderBar::new());
+    toolbar.set_content(Some(content));
+    NavigationPage::builder().title(title).child(&toolbar).build()
+}
+
+fn pill_button(label: &str, style: &str) -> gtk::Button {
+    let button = gtk::Button::builder()
+        .label(label)
+        .halign(gtk::Align::Center)
+        .margin_top(12)
+        .build();
+    button.add_css_class(style);
+    button.add_css_class("pill");
+    button
+}
+
+// An expander whose switch turns the criterion on, with the amount inside
+fn criterion_row(title: &str, subtitle: &str, spin_title: &str, min: f64, max: f64, value: f64) -> (adw::ExpanderRow, adw::SpinRow) {
+    let row = adw::ExpanderRow::builder()
+        .title(title)
+        .subtitle(subtitle)
+        .show_enable_switch(true)
+        .enable_expansion(false)
+        .build();
+    let spin_row = adw::SpinRow::with_range(min, max, 1.0);
+    spin_row.set_title(spin_title);
+    spin_row.set_value(value);
+    row.add_row(&spin_row);
+    (row, spin_row)
+}
+
+// Step 1: what counts as unneeded
+fn criteria_page(
+    window: &adw::Window,
+    navigation: &NavigationView,
+    config: Rc<RefCell<Config>>,
+    scan_result: Rc<RefCell<ScanResult>>,
+    known: Rc<KnownGames>,
+) -> NavigationPage {
+    let page = PreferencesPage::new();
+    let group = PreferencesGroup::builder()
+        .title(gettext("Criteria"))
+        .description(gettext("Prefixes meeting every criterion that's on are listed for review. Nothing is removed yet."))
+        .build();
+    page.add(&group);
+
+    let uninstalled_row = adw::SwitchRow::builder()
+        .title(gettext("Game Uninstalled"))
+        .subtitle(gettext("No Steam library has the game installed"))
+        .active(true)
+        .build();
+    group.add(&uninstalled_row);
+    let (not_played_row, months_row) = criterion_row(
+        &gettext("Not Played Recently"),
+        &gettext("Includes games Steam has no play time for"),
+        &gettext("Months Since Last Played"),
+        1.0,
+        120.0,
+        6.0,
+    );
+    group.add(&not_played_row);
+    let (size_row, megabytes_row) = criterion_row(
+        &gettext("Bigger Than"),
+        &gettext("Only prefixes taking up at least this much space"),
+        &gettext("Size in MB"),
+        1.0,
+        1_000_000.0,
+        500.0,
+    );
+    group.add(&size_row);
+
+    let preview_button = pill_button(&gettext("Preview Cleanup"), "suggested-action");
+    let button_group = PreferencesGroup::new();
+    button_group.add(&preview_button);
+    page.add(&button_group);
+
+    let window = window.clone();
+    let navigation_clone = navigation.clone();
+    preview_button.connect_clicked(move |_| {
+        let criteria = CleanupCriteria {
+            uninstalled: uninstalled_row.is_active(),
+            not_played_months: not_played_row.enables_expansion().then(|| months_row.value() as u64),
+            min_size: size_row.enables_expansion().then(|| megabytes_row.value() as u64 * BYTES_PER_MB),
+        };
+        let candidates = cleanup::find_candidates(&config.borrow(), &scan_result.borrow().prefixes, &criteria, |game_id| {
+            known.locations.get(game_id).cloned().unwrap_or_default()
+        });
+        navigation_clone.push(&preview_page(&window, &navigation_clone, config.clone(), known.clone(), candidates, criteria));
+    });
+
+    wizard_page(&gettext("Clean Up Prefixes"), &page)
+}
+
+fn display_name(config: &Config, known: &KnownGames, game_id: &str) -> String {
+    config
+        .display_name(game_id)
+        .or_else(|| known.names.get(game_id).map(String::as_str))
+        .map(str::to_string)
+        .unwrap_or_else(|| gettext_f("Game ID: {}", &[game_id]))
+}
+
+// Step 2: the dry run, every prefix that would go and the space it frees.
+// Sizes are measured in the background, whole prefixes can be big.
+fn preview_page(
+    window: &adw::Window,
+    navigation: &NavigationView,
+    config: Rc<RefCell<Config>>,
+    known: Rc<KnownGames>,
+    candidates: Vec<CleanupCandidate>,
+    criteria: CleanupCriteria,
+) -> NavigationPage {
+    let spinner = gtk::Spinner::builder()
+        .spinning(true)
+        .width_request(32)
+        .height_request(32)
+        .halign(gtk::Align::Center)
+        .build();
+    let measuring = StatusPage::builder()
+        .title(gettext("Measuring Prefixes…"))
+        .child(&spinner)
+        .build();
+    let stack = gtk::Stack::new();
+    stack.add_named(&measuring, Some("measuring"));
+    let page = wizard_page(&gettext("Review Cleanup"), &stack);
+
+    let window = window.clone();
+    let navigation = navigation.clone();
+    glib::MainContext::default().spawn_local(async move {
+        let measured = gio::spawn_blocking(move || cleanup::measure(candidates, criteria.min_size)).await;
+        let Ok(candidates) = measured else {
+            eprintln!("Measuring prefixes for cleanup failed");
+            return;
+        };
+        let content = review_content(&window, &navigation, config, known, candidates);
+        stack.add_named(&content, Some("review"));
+        stack.set_visible_child_name("review");
+    });
+    page
+}
+
+fn review_content(
+    window: &adw::Window,
+    navigation: &NavigationView,
+    config: Rc<RefCell<Config>>,
+    known: Rc<KnownGames>,
+    candidates: Vec<CleanupCandidate>,
+) -> gtk::Widget {
+    if candidates.is_empty() {
+        return StatusPage::builder()
+            .icon_name("emblem-ok-symbolic")
+            .title(gettext("Nothing to Clean Up"))
+            .description(gettext("No prefix meets all of the chosen criteria."))
+            .build()
+            .upcast();
+    }
+
+    let page = PreferencesPage::new();
+    let total: u64 = candidates.iter().map(|candidate| candidate.size).sum();
+    let group = PreferencesGroup::builder()
+        .title(gettext("Prefixes to Remove"))
+        .description(gettext_f(
+            "{} prefixes taking up {}. Uncheck the ones to keep. Steam creates a new prefix the next time a game is launched, anything stored in the old one is gone.",
+            &[&candidates.len().to_string(), &format_size(total, DECIMAL)],
+        ))
+        .build();
+    page.add(&group);
+
+    let candidates: Rc<Vec<CleanupCandidate>> = Rc::new(candidates);
+    let checks: Rc<Vec<gtk::CheckButton>> = Rc::new(candidates.iter().map(|_| gtk::CheckButton::builder().active(true).valign(gtk::Align::Center).build()).collect());
+    for (candidate, check) in candidates.iter().zip(checks.iter()) {
+        let mut subtitle = vec![format_size(candidate.size, DECIMAL)];
+        subtitle.push(match candidate.last_played {
+            Some(last_played) => CompatDataPage::last_played_label(last_played),
+            None => gettext("Never played"),
+        });
+        if !candidate.installed {
+            subtitle.push(gettext("Not installed"));
+        }
+        subtitle.push(candidate.prefix_path.display().to_string());
+        let row = ActionRow::builder()
+            .title(glib::markup_escape_text(&display_name(&config.borrow(), &known, &candidate.game_id)).as_str())
+            .subtitle(glib::markup_escape_text(&subtitle.join(" | ")).as_str())
+            .subtitle_lines(3)
+            .activatable_widget(check)
+            .build();
+        row.add_prefix(check);
+        group.add(&row);
+    }
+
+    let options_group = PreferencesGroup::new();
+    let backup_row = adw::SwitchRow::builder()
+        .title(gettext("Back Up Saves First"))
+        .subtitle(gettext("Detected save folders go into a safety snapshot, kept as long as the snapshot settings allow"))
+        .active(true)
+        .build();
+    options_group.add(&backup_row);
+    page.add(&options_group);
+
+    let remove_button = pill_button("", "destructive-action");
+    let button_group = PreferencesGroup::new();
+    button_group.add(&remove_button);
+    page.add(&button_group);
+
+    // The button always says what it's about to do
+    let selected = {
+        let candidates = candidates.clone();
+        let checks = checks.clone();
+        move || -> Vec<usize> { (0..candidates.len()).filter(|index| checks[*index].is_active()).collect() }
+    };
+    let update_button = {
+        let candidates = candidates.clone();
+        let selected = selected.clone();
+        let remove_button = remove_button.clone();
+        move || {
+            let selected = selected();
+            let size: u64 = selected.iter().map(|index| candidates[*index].size).sum();
+            remove_button.set_label(&gettext_f("Remove {} Prefixes ({})", &[&selected.len().to_string(), &format_size(size, DECIMAL)]));
+            remove_button.set_sensitive(!selected.is_empty());
+        }
+    };
+    update_button();
+    for check in checks.iter() {
+        let update_button = update_button.clone();
+        check.connect_toggled(move |_| update_button());
+    }
+
+    let window = window.clone();
+    let navigation = navigation.clone();
+    remove_button.connect_clicked(move |_| {
+        let selected = selected();
+        let dialog = MessageDialog::builder()
+            .transient_for(&window)
+            .modal(true)
+            .heading(gettext_f("Remove {} Prefixes?", &[&selected.len().to_string()]))
+            .body(if backup_row.is_active() {
+                gettext("Their saves are kept in safety snapshots, everything else in them is deleted permanently.")
+            } else {
+                gettext("Everything in them is deleted permanently, saves included.")
+            })
+            .build();
+        dialog.add_response("cancel", &gettext("Cancel"));
+        dialog.add_response("remove", &gettext("Remove"));
+        dialog.set_response_appearance("remove", adw::ResponseAppearance::Destructive);
+        dialog.set_close_response("cancel");
+        let window = window.clone();
+        let navigation = navigation.clone();
+        let config = config.clone();
+        let known = known.clone();
+        let candidates = candidates.clone();
+        let back_up_saves = backup_row.is_active();
+        confirm::run(&config.clone(), dialog, ConfirmAction::Delete, "cleanup-remove", "remove", move || {
+            let results: Vec<(&CleanupCandidate, CleanupOutcome)> = selected
+                .iter()
+                .map(|index| {
+                    let candidate = &candidates[*index];
+                    (candidate, cleanup::remove(&config.borrow(), candidate, back_up_saves))
+                })
+                .collect();
+            navigation.push(&result_page(&window, &config.borrow(), &known, &results));
+            WidgetExt::activate_action(&window, "app.refresh", None).ok();
+        });
+    });
+
+    page.upcast()
+}
+
+// Step 3: what was removed, what wasn't and why
+fn result_page(window: &adw::Window, config: &Config, known: &KnownGames, results: &[(&CleanupCandidate, CleanupOutcome)]) -> NavigationPage {
+    let freed: u64 = results
+        .iter()
+        .filter_map(|(_, outcome)| match outcome {
+            CleanupOutcome::Removed { size, .. } => Some(*size),
+            CleanupOutcome::Failed(_) => None,
+        })
+        .sum();
+    let removed = results.iter().filter(|(_, outcome)| matches!(outcome, CleanupOutcome::Removed { .. })).count();
+    let failed = results.len() - removed;
+
+    let page = PreferencesPage::new();
+    let summary = StatusPage::builder()
+        .icon_name(if failed == 0 { "emblem-ok-symbolic" } else { "dialog-warning-symbolic" })
+        .title(gettext("Cleanup Finished"))
+        .description(if failed == 0 {
+            gettext_f("Removed {} prefixes and freed {}.", &[&removed.to_string(), &format_size(freed, DECIMAL)])
+        } else {
+            gettext_f(
+                "Removed {} prefixes and freed {}. {} could not be removed.",
+                &[&removed.to_string(), &format_size(freed, DECIMAL), &failed.to_string()],
+            )
+        })
+        .build();
+    let summary_group = PreferencesGroup::new();
+    summary_group.add(&summary);
+    page.add(&summary_group);
+
+    let group = PreferencesGroup::builder().title(gettext("Details")).build();
+    for (candidate, outcome) in results {
+        let (icon, detail) = match outcome {
+            CleanupOutcome::Removed { size, snapshot: Some(snapshot) } => (
+                "user-trash-symbolic",
+                gettext_f("Freed {}, saves kept in snapshot {}", &[&format_size(*size, DECIMAL), &snapshot.dir.display().to_string()]),
+            ),
+            CleanupOutcome::Removed { size, snapshot: None } => ("user-trash-symbolic", gettext_f("Freed {}", &[&format_size(*size, DECIMAL)])),
+            CleanupOutcome::Failed(error) => ("dialog-error-symbolic", gettext_f("Not removed: {}", &[error])),
+        };
+        let row = ActionRow::builder()
+            .title(glib::markup_escape_text(&display_name(config, known, &candidate.game_id)).as_str())
+            .subtitle(glib::markup_escape_text(&detail).as_str())
+            .subtitle_lines(3)
+            .build();
+        row.add_prefix(&gtk::Image::from_icon_name(icon));
+        group.add(&row);
+    }
+    page.add(&group);
+
+    let done_button = pill_button(&gettext("Done"), "suggested-action");
+    done_button.connect_clicked(glib::clone!(@weak window => move |_| window.close()));
+    let button_group = PreferencesGroup::new();
+    button_group.add(&done_button);
+    page.add(&button_group);
+
+    // Going back would offer to remove prefixes that are already gone
+    let result_page = wizard_page(&gettext("Cleanup Finished"), &page);
+    result_page.set_can_pop(false);
+    result_page
+}
@@ -0,0 +1,128 @@
+use adw::prelude::*;
+use adw::{ActionRow, MessageDialog};
+use humansize::{format_size, DECIMAL};
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use crate::cloud::{self, CloudFile};
+use crate::config::Config;
+use crate::i18n::{gettext, gettext_f};
+use crate::report;
+use crate::steam;
+use crate::ui::error_dialog;
+use crate::ui::inhibit::Inhibitor;
+
+// List the Steam Cloud copies of a game's saves and copy one back into one of
+// its save locations, e.g. when the local file got corrupted
+pub fn show(
+    parent: Option<&gtk::Window>,
+    config: Rc<RefCell<Config>>,
+    app_id: &str,
+    name: &str,
+    files: Vec<CloudFile>,
+    targets: Vec<PathBuf>,
+) {
+    let dialog = MessageDialog::builder()
+        .heading(gettext("Steam Cloud Copies"))
+        .body(gettext_f(
+            "Files Steam last synced for {}. Restoring one replaces the file in the chosen save folder, after taking a safety snapshot.",
+            &[name],
+        ))
+        .modal(true)
+        .build();
+    if let Some(parent) = parent {
+        dialog.set_transient_for(Some(parent));
+    }
+
+    let content = gtk::Box::new(gtk::Orientation::Vertical, 12);
+
+    // Save folder the files get restored into
+    let target_labels: Vec<String> = targets.iter().map(|p| p.display().to_string()).collect();
+    let target_refs: Vec<&str> = target_labels.iter().map(|s| s.as_str()).collect();
+    let target_row = adw::ComboRow::builder()
+        .title(gettext("Restore Into"))
+        .model(&gtk::StringList::new(&target_refs))
+        .build();
+    let target_list = gtk::ListBox::new();
+    target_list.set_selection_mode(gtk::SelectionMode::None);
+    target_list.add_css_class("boxed-list");
+    target_list.append(&target_row);
+    content.append(&target_list);
+
+    let file_list = gtk::ListBox::new();
+    file_list.set_selection_mode(gtk::SelectionMode::None);
+    file_list.add_css_class("boxed-list");
+    let targets = Rc::new(targets);
+    let accounts = steam::accounts(&config.borrow());
+    for file in files {
+        let mut subtitle = format_size(file.size, DECIMAL);
+        if let Some(synced) = file.synced {
+            subtitle.push_str(&format!(" | {}", report::format_timestamp_utc(synced)));
+        }
+        let account = match accounts.iter().find(|account| account.id == file.account_id) {
+            Some(account) => account.label(),
+            None => file.account_id.clone(),
+        };
+        subtitle.push_str(&format!(" | {}", gettext_f("Account {}", &[&account])));
+
+        let row = ActionRow::builder()
+            .title(gtk::glib::markup_escape_text(&file.name).as_str())
+            .subtitle(gtk::glib::markup_escape_text(&subtitle).as_str())
+            .build();
+        let restore_button = gtk::Button::with_label(&gettext("Restore"));
+        restore_button.set_valign(gtk::Align::Center);
+        restore_button.set_sensitive(!targets.is_empty());
+        let config_clone = config.clone();
+        let app_id = app_id.to_string();
+        let targets_clone = targets.clone();
+        let row_target = target_row.clone();
+        let dialog_clone = dialog.clone();
+        restore_button.connect_clicked(move |_| {
+            let Some(target_dir) = targets_clone.get(row_target.selected() as usize) else {
+                return;
+            };
+            let inhibitor = Inhibitor::new(&dialog_clone, &gettext("Restoring a game save"));
+            let restored = cloud::restore_cloud_file(&config_clone.borrow(), &app_id, &file, target_dir);
+            drop(inhibitor);
+            match restored {
+                Ok(target) => {
+                    let result = MessageDialog::builder()
+                        .heading(gettext("Cloud Copy Restored"))
+                        .body(gettext_f("Restored to {}", &[&target.display().to_string()]))
+                        .transient_for(&dialog_clone)
+                        .modal(true)
+                        .build();
+                    result.add_response("ok", &gettext("OK"));
+                    result.present();
+                }
+                Err(e) => {
+                    eprintln!("Error restoring cloud copy {}: {:#}", file.name, e);
+                    error_dialog::show(Some(dialog_clone.upcast_ref()), &gettext("Restore Failed"), &e);
+                }
+            }
+        });
+        row.add_suffix(&restore_button);
+        file_list.append(&row);
+    }
+
+    let scrolled = gtk::ScrolledWindow::builder()
+        .hscrollbar_policy(gtk::PolicyType::Never)
+        .propagate_natural_height(true)
+        .max_content_height(360)
+        .child(&file_list)
+        .build();
+    content.append(&scrolled);
+
+    if targets.is_empty() {
+        let hint = gtk::Label::new(Some(&gettext("None of this game's save folders exist, run the game once to create them.")));
+        hint.set_wrap(true);
+        hint.add_css_class("dim-label");
+        content.append(&hint);
+    }
+
+    dialog.set_extra_child(Some(&content));
+    dialog.add_response("close", &gettext("Close"));
+    dialog.set_close_response("close");
+    dialog.present();
+}
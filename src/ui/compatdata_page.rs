@@ -6,30 +6,111 @@ use gtk::{
 };
  
 use gtk;
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::path::{Path, PathBuf};
 use std::rc::Rc;
-use fuzzy_matcher::FuzzyMatcher;
 use fuzzy_matcher::skim::SkimMatcherV2;
-use std::collections::HashMap;
-use anyhow::{Result, anyhow}; // Import anyhow
+use humansize::{format_size, DECIMAL};
+use std::collections::{HashMap, HashSet};
 
-use crate::compatdata::{self, PrefixData};
-use crate::config::Config;
+use crate::activity::{self, ActivityEntry, ActivityKind};
+use crate::compatdata::{self, LibraryError, PrefixData, PrefixHealth, PrefixTool};
+use crate::config::{Config, ConfirmAction, ViewState};
+use crate::fsutil::{self, DriveKind};
+use crate::prefix_sizes::{self, PrefixSizeCache};
+use crate::ui::keynav;
+use crate::ui::sort_bar::{self, SortBar};
+use crate::ui::{app_id_button, confirm, error_dialog, location_menu, prefix_backup_dialog, rename_dialog, search_match};
+use crate::snapshot::Snapshot;
+use crate::i18n::{gettext, gettext_f};
+
+// Cap on listed problem entries in the prefix info dialog
+const MAX_PROBLEM_ROWS: usize = 100;
+// Prefixes not played for this long are shown by the cleanup filter
+const NOT_PLAYED_DAYS: u64 = 182;
+
+// What the sort bar can order prefix rows by
+struct RowSortKeys {
+    game_id: String,
+    name: String, // Display name, or the App ID without one
+    tool: String,
+    last_played: Option<u64>,
+    proton_build: Option<String>,
+    proton_missing: bool, // The build's install folder is gone, e.g. an old Proton that was removed
+    size: Option<u64>, // Whole prefix, filled in once measured
+    hidden: bool, // Only listed while "Show Hidden Prefixes" is on
+    library: PathBuf, // Compatdata folder holding the prefix
+    library_index: usize, // Its place in the config's library list, the main one first
+    title: String, // Row title without markup, highlighted while searching
+    search_text: String, // Lowercase, what the search entry matches
+}
+
+// What prefix rows are filtered by, shared by the list's filter function and
+// the "N of M prefixes" count
+#[derive(Clone)]
+struct PrefixFilter {
+    query: Rc<RefCell<String>>, // Lowercase text of the search entry
+    matcher: Rc<SkimMatcherV2>,
+    // Fuzzy score of every matching prefix by App ID, only while searching
+    scores: Rc<RefCell<HashMap<String, i64>>>,
+    not_played_toggle: gtk::ToggleButton,
+    hidden_toggle: gtk::ToggleButton,
+}
+
+impl PrefixFilter {
+    fn matches(&self, keys: &RowSortKeys) -> bool {
+        (!keys.hidden || self.hidden_toggle.is_active())
+            && (!self.not_played_toggle.is_active() || CompatDataPage::not_played_recently(keys.last_played))
+            && (self.query.borrow().is_empty() || self.scores.borrow().contains_key(&keys.game_id))
+    }
+
+    // Score the rows against a new search text
+    fn set_query(&self, query: String, row_sort_keys: &HashMap<ListBoxRow, RowSortKeys>) {
+        *self.scores.borrow_mut() = if query.is_empty() {
+            HashMap::new()
+        } else {
+            row_sort_keys
+                .values()
+                .filter_map(|keys| {
+                    let score = search_match::score(&self.matcher, &[&keys.title.to_lowercase(), &keys.search_text], &query)?;
+                    Some((keys.game_id.clone(), score))
+                })
+                .collect()
+        };
+        *self.query.borrow_mut() = query;
+    }
+}
+
+// Header row above the prefixes of one library while grouping by library,
+// clicking it collapses the library
+struct LibraryHeader {
+    compatdata_path: PathBuf,
+    index: usize,
+    drive: String, // "Internal SSD" and the like
+}
 
 pub struct CompatDataPage {
     widget: Box,
     window: adw::ApplicationWindow,
     config: Rc<RefCell<Config>>,
+    toast_overlay: adw::ToastOverlay,
     listbox: ListBox, // Keep using ListBox directly
     search_entry: SearchEntry,
-    matcher: Rc<SkimMatcherV2>,
+    not_played_toggle: gtk::ToggleButton,
+    filter: PrefixFilter,
+    match_count_label: Label,
+    row_sort_keys: Rc<RefCell<HashMap<gtk::ListBoxRow, RowSortKeys>>>,
+    library_headers: Rc<RefCell<HashMap<gtk::ListBoxRow, LibraryHeader>>>,
+    collapsed_libraries: Rc<RefCell<HashSet<PathBuf>>>, // Kept across refreshes
+    sort_bar: SortBar,
+    // Bumped on every update, so a slow size measurement can't land on newer rows
+    generation: Rc<Cell<u32>>,
     // Store detected directories (AppID -> Path)
     _detected_dirs: Rc<RefCell<HashMap<String, PathBuf>>>, 
 }
 
 impl CompatDataPage {
-    pub fn new(window: adw::ApplicationWindow, config: Rc<RefCell<Config>>) -> Self {
+    pub fn new(window: adw::ApplicationWindow, config: Rc<RefCell<Config>>, toast_overlay: adw::ToastOverlay) -> Self {
         // --- Widget Setup --- 
         let widget = Box::new(Orientation::Vertical, 12);
         // Set margins individually
@@ -38,19 +119,73 @@ impl CompatDataPage {
         widget.set_margin_top(12);
         widget.set_margin_bottom(12);
         
-        let header = Label::new(Some("Proton Compatdata Folders"));
+        let header = Label::new(Some(&gettext("Proton Compatdata Folders")));
         header.add_css_class("title-1");
         widget.append(&header);
-        let description = Label::new(Some("Manage your Proton prefixes and game save files"));
+        let description = Label::new(Some(&gettext("Manage your Proton prefixes and game save files")));
         description.add_css_class("subtitle-1");
         widget.append(&description);
 
         let search_entry = SearchEntry::new();
-        search_entry.set_placeholder_text(Some("🔍 Search Game IDs or Save Folders..."));
+        search_entry.set_placeholder_text(Some(&gettext("🔍 Search Game IDs or Save Folders...")));
         search_entry.set_margin_top(12);
         search_entry.set_margin_bottom(6);
         search_entry.add_css_class("emoji");
-        widget.append(&search_entry);
+        search_entry.set_hexpand(true);
+
+        let sort_bar = SortBar::new(
+            config.clone(),
+            "compatdata",
+            &[
+                ("app_id", gettext("App ID")),
+                ("name", gettext("Name")),
+                ("proton", gettext("Proton Version")),
+                ("last_played", gettext("Last Played")),
+                ("size", gettext("Size")),
+            ],
+            &search_entry,
+        );
+        let not_played_toggle = gtk::ToggleButton::builder()
+            .icon_name("document-open-recent-symbolic")
+            .tooltip_text(gettext("Show Only Prefixes Not Played in 6+ Months"))
+            .valign(Align::Center)
+            .active(config.borrow().view_state("compatdata").not_played_only)
+            .build();
+        not_played_toggle.add_css_class("flat");
+        let group_toggle = gtk::ToggleButton::builder()
+            .icon_name("view-list-symbolic")
+            .tooltip_text(gettext("Group by Proton Build"))
+            .valign(Align::Center)
+            .active(config.borrow().view_state("compatdata").group_by_proton)
+            .build();
+        group_toggle.add_css_class("flat");
+        let library_toggle = gtk::ToggleButton::builder()
+            .icon_name("drive-harddisk-symbolic")
+            .tooltip_text(gettext("Group by Library"))
+            .valign(Align::Center)
+            .active(config.borrow().view_state("compatdata").group_by_library)
+            .build();
+        library_toggle.add_css_class("flat");
+        let hidden_toggle = gtk::ToggleButton::builder()
+            .icon_name("view-reveal-symbolic")
+            .tooltip_text(gettext("Show Hidden Prefixes"))
+            .valign(Align::Center)
+            .active(config.borrow().view_state("compatdata").show_hidden)
+            .build();
+        hidden_toggle.add_css_class("flat");
+        let search_box = Box::new(Orientation::Horizontal, 6);
+        search_box.append(&search_entry);
+        search_box.append(&not_played_toggle);
+        search_box.append(&group_toggle);
+        search_box.append(&library_toggle);
+        search_box.append(&hidden_toggle);
+        search_box.append(sort_bar.widget());
+        widget.append(&search_box);
+        let match_count_label = Label::new(None);
+        match_count_label.set_halign(Align::Start);
+        match_count_label.add_css_class("dim-label");
+        match_count_label.add_css_class("caption");
+        widget.append(&match_count_label);
 
         let scroll = ScrolledWindow::new();
         scroll.set_vexpand(true);
@@ -61,30 +196,225 @@ impl CompatDataPage {
         listbox.set_selection_mode(SelectionMode::None);
         listbox.add_css_class("boxed-list");
         scroll.set_child(Some(&listbox));
+        keynav::add_expander_keys(&listbox);
+        // Typing anywhere on the page goes to the search entry
+        search_entry.set_key_capture_widget(Some(&widget));
         
-        let matcher = Rc::new(SkimMatcherV2::default());
+        let filter = PrefixFilter {
+            query: Rc::new(RefCell::new(search_entry.text().to_lowercase())),
+            matcher: Rc::new(SkimMatcherV2::default()),
+            scores: Rc::new(RefCell::new(HashMap::new())),
+            not_played_toggle: not_played_toggle.clone(),
+            hidden_toggle: hidden_toggle.clone(),
+        };
         let detected_dirs = Rc::new(RefCell::new(HashMap::new()));
 
         let page = Self {
             widget,
             window: window.clone(),
             config,
+            toast_overlay,
             listbox: listbox.clone(), // Clone for struct
             search_entry: search_entry.clone(),
-            matcher,
+            not_played_toggle: not_played_toggle.clone(),
+            filter,
+            match_count_label,
+            row_sort_keys: Rc::new(RefCell::new(HashMap::new())),
+            library_headers: Rc::new(RefCell::new(HashMap::new())),
+            collapsed_libraries: Rc::new(RefCell::new(HashSet::new())),
+            sort_bar: sort_bar.clone(),
+            generation: Rc::new(Cell::new(0)),
             _detected_dirs: detected_dirs.clone(),
         };
+
+        // Rows are kept in the order chosen in the sort bar, inside groups of
+        // the same Proton build (oldest first) or the same library (in the
+        // order Steam lists them) while grouping is on
+        let row_sort_keys = page.row_sort_keys.clone();
+        let library_headers = page.library_headers.clone();
+        let sort_bar_clone = sort_bar.clone();
+        let group_toggle_clone = group_toggle.clone();
+        let library_toggle_clone = library_toggle.clone();
+        let scores = page.filter.scores.clone();
+        page.listbox.set_sort_func(move |a, b| {
+            let row_sort_keys = row_sort_keys.borrow();
+            if library_toggle_clone.is_active() {
+                // A library's header sorts right before its prefixes
+                let headers = library_headers.borrow();
+                let library_of = |row: &ListBoxRow| {
+                    headers
+                        .get(row)
+                        .map(|header| (header.index, false))
+                        .or_else(|| row_sort_keys.get(row).map(|keys| (keys.library_index, true)))
+                };
+                if let (Some(a), Some(b)) = (library_of(a), library_of(b)) {
+                    if a != b {
+                        return a.cmp(&b).into();
+                    }
+                }
+            }
+            // Rows without keys (library errors, placeholder) stay on top
+            let (a, b) = match (row_sort_keys.get(a), row_sort_keys.get(b)) {
+                (Some(a), Some(b)) => (a, b),
+                (None, Some(_)) => return gtk::Ordering::Smaller,
+                (Some(_), None) => return gtk::Ordering::Larger,
+                (None, None) => return gtk::Ordering::Equal,
+            };
+            let ordering = match sort_bar_clone.key() {
+                "name" => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+                "proton" => a.tool.cmp(&b.tool),
+                "last_played" => a.last_played.cmp(&b.last_played),
+                "size" => a.size.cmp(&b.size),
+                _ => std::cmp::Ordering::Equal,
+            }
+            .then_with(|| compatdata::compare_app_ids(&a.game_id, &b.game_id));
+            let ordering = if sort_bar_clone.descending() { ordering.reverse() } else { ordering };
+            // While searching the closest matches go first, the sort bar decides between equals
+            let scores = scores.borrow();
+            let ordering = scores.get(&b.game_id).cmp(&scores.get(&a.game_id)).then(ordering);
+            if !group_toggle_clone.is_active() {
+                return ordering.into();
+            }
+            // Prefixes without a known build go last
+            let group_ordering = match (&a.proton_build, &b.proton_build) {
+                (Some(a), Some(b)) => compatdata::compare_proton_builds(a, b),
+                (a, b) => b.is_some().cmp(&a.is_some()),
+            };
+            group_ordering.then(ordering).into()
+        });
+        let listbox_clone = page.listbox.clone();
+        sort_bar.connect_changed(move || listbox_clone.invalidate_sort());
+
+        // A header above the first prefix of every Proton build while grouping
+        let row_sort_keys = page.row_sort_keys.clone();
+        let group_toggle_clone = group_toggle.clone();
+        page.listbox.set_header_func(move |row, before| {
+            let row_sort_keys = row_sort_keys.borrow();
+            let keys = row_sort_keys.get(row).filter(|_| group_toggle_clone.is_active());
+            let starts_group = keys.is_some_and(|keys| {
+                before
+                    .and_then(|before| row_sort_keys.get(before))
+                    .is_none_or(|before| before.proton_build != keys.proton_build)
+            });
+            row.set_header(keys.filter(|_| starts_group).map(|keys| {
+                let title = match &keys.proton_build {
+                    Some(build) if keys.proton_missing => gettext_f("{} (no longer installed)", &[build]),
+                    Some(build) => build.clone(),
+                    None => gettext("Unknown Proton Build"),
+                };
+                let label = Label::new(Some(&title));
+                label.set_halign(Align::Start);
+                label.set_margin_start(12);
+                label.set_margin_top(12);
+                label.set_margin_bottom(6);
+                label.add_css_class("heading");
+                label
+            }).as_ref());
+        });
+        // One grouping at a time
+        let listbox_clone = page.listbox.clone();
+        let config_clone = page.config.clone();
+        let library_toggle_clone = library_toggle.clone();
+        group_toggle.connect_toggled(move |toggle| {
+            if toggle.is_active() {
+                library_toggle_clone.set_active(false);
+            }
+            listbox_clone.invalidate_sort();
+            listbox_clone.invalidate_headers();
+            let state = ViewState {
+                group_by_proton: toggle.is_active(),
+                ..config_clone.borrow().view_state("compatdata")
+            };
+            if let Err(e) = config_clone.borrow_mut().set_view_state("compatdata", state) {
+                eprintln!("Error saving view state of compatdata page: {}", e);
+            }
+        });
+
+        let listbox_clone = page.listbox.clone();
+        let config_clone = page.config.clone();
+        let group_toggle_clone = group_toggle.clone();
+        library_toggle.connect_toggled(move |toggle| {
+            if toggle.is_active() {
+                group_toggle_clone.set_active(false);
+            }
+            listbox_clone.invalidate_sort();
+            listbox_clone.invalidate_filter();
+            let state = ViewState {
+                group_by_library: toggle.is_active(),
+                ..config_clone.borrow().view_state("compatdata")
+            };
+            if let Err(e) = config_clone.borrow_mut().set_view_state("compatdata", state) {
+                eprintln!("Error saving view state of compatdata page: {}", e);
+            }
+        });
+
+        // Cleanup filter: prefixes Steam has no recent play time for, including
+        // never played ones (uninstalled games, leftovers of other tools)
+        // Hidden prefixes only show with their own toggle, and prefixes of a
+        // collapsed library not at all
+        let row_sort_keys = page.row_sort_keys.clone();
+        let library_headers = page.library_headers.clone();
+        let collapsed_libraries = page.collapsed_libraries.clone();
+        let filter = page.filter.clone();
+        let library_toggle_clone = library_toggle.clone();
+        page.listbox.set_filter_func(move |row| {
+            let grouping = library_toggle_clone.is_active();
+            if library_headers.borrow().contains_key(row) {
+                return grouping;
+            }
+            // Library errors and the placeholder have no keys and always show
+            row_sort_keys.borrow().get(row).is_none_or(|keys| {
+                filter.matches(keys) && !(grouping && collapsed_libraries.borrow().contains(&keys.library))
+            })
+        });
+        let listbox_clone = page.listbox.clone();
+        let config_clone = page.config.clone();
+        let match_count_label = page.match_count_label.clone();
+        let row_sort_keys = page.row_sort_keys.clone();
+        let filter = page.filter.clone();
+        hidden_toggle.connect_toggled(move |toggle| {
+            listbox_clone.invalidate_filter();
+            Self::update_match_count(&match_count_label, &row_sort_keys.borrow(), &filter);
+            let state = ViewState {
+                show_hidden: toggle.is_active(),
+                ..config_clone.borrow().view_state("compatdata")
+            };
+            if let Err(e) = config_clone.borrow_mut().set_view_state("compatdata", state) {
+                eprintln!("Error saving view state of compatdata page: {}", e);
+            }
+        });
+        let listbox_clone = page.listbox.clone();
+        let config_clone = page.config.clone();
+        let match_count_label = page.match_count_label.clone();
+        let row_sort_keys = page.row_sort_keys.clone();
+        let filter = page.filter.clone();
+        not_played_toggle.connect_toggled(move |toggle| {
+            listbox_clone.invalidate_filter();
+            Self::update_match_count(&match_count_label, &row_sort_keys.borrow(), &filter);
+            let state = ViewState {
+                not_played_only: toggle.is_active(),
+                ..config_clone.borrow().view_state("compatdata")
+            };
+            if let Err(e) = config_clone.borrow_mut().set_view_state("compatdata", state) {
+                eprintln!("Error saving view state of compatdata page: {}", e);
+            }
+        });
         
-        // --- Connect Search Signal for Manual Filtering ---
+        // Search goes through the filter function too, by each row's search text
         let listbox_clone = page.listbox.clone();
-        let matcher_clone = page.matcher.clone();
+        let match_count_label = page.match_count_label.clone();
+        let row_sort_keys = page.row_sort_keys.clone();
+        let filter = page.filter.clone();
         page.search_entry.connect_search_changed(move |entry| {
-            let query = entry.text().to_lowercase();
-            Self::filter_listbox(&listbox_clone, &matcher_clone, &query);
+            filter.set_query(entry.text().to_lowercase(), &row_sort_keys.borrow());
+            listbox_clone.invalidate_filter();
+            listbox_clone.invalidate_sort();
+            Self::highlight_titles(&row_sort_keys.borrow(), &filter);
+            Self::update_match_count(&match_count_label, &row_sort_keys.borrow(), &filter);
         });
 
         // --- Initial Data Load --- 
-        // Defer initial load to the window's refresh button emit_clicked()
+        // Defer initial load to the window, it shows the cached scan and starts a fresh one
         // We don't call scan or update_listbox here anymore.
         
         page
@@ -94,55 +424,51 @@ impl CompatDataPage {
         &self.widget
     }
 
-    // Renamed from refresh_listbox_public - now just scans data
-    pub fn scan_compatdata(&self) -> Result<Vec<PrefixData>> {
-        println!("Scanning compatdata...");
-        let config_borrow = self.config.borrow();
-        let compatdata_path = config_borrow.compatdata_path();
-        let mut scanned_prefixes = Vec::new();
-
-        if !compatdata_path.exists() {
-            // Return error instead of modifying UI here
-            return Err(anyhow!("Compatdata path does not exist: {}", compatdata_path.display()));
-        }
-        
-        let game_ids = compatdata::list_game_ids(&config_borrow)?;
-
-        if game_ids.is_empty() {
-            println!("No Proton prefixes found in {}", compatdata_path.display());
-            // Return Ok with empty vec, not an error
-            return Ok(scanned_prefixes); 
+    // Clear the search, then expand and focus the row of `game_id` (scrolling
+    // it into view). False if there's no prefix for it.
+    pub fn reveal(&self, game_id: &str) -> bool {
+        self.search_entry.set_text("");
+        self.filter.set_query(String::new(), &self.row_sort_keys.borrow());
+        self.listbox.invalidate_filter();
+        self.listbox.invalidate_sort();
+        Self::highlight_titles(&self.row_sort_keys.borrow(), &self.filter);
+        Self::update_match_count(&self.match_count_label, &self.row_sort_keys.borrow(), &self.filter);
+        let row = self.row_sort_keys.borrow().iter().find(|(_, keys)| keys.game_id == game_id).map(|(row, keys)| (row.clone(), keys.last_played));
+        let Some((row, last_played)) = row else {
+            return false;
+        };
+        if !Self::not_played_recently(last_played) {
+            self.not_played_toggle.set_active(false);
         }
-
-        println!("Found {} potential prefixes. Scanning for saves...", game_ids.len());
-        for game_id in game_ids {
-            let mut prefix_data = PrefixData::new(&config_borrow, &game_id);
-            // Scan save locations for this prefix
-            if let Err(e) = prefix_data.scan_save_locations() {
-                 eprintln!("Error scanning saves for game ID {}: {}", game_id, e);
-                 // Decide whether to skip this prefix or continue without saves
-                 // Let's include it anyway, maybe manifest matching works differently
-            }
-            scanned_prefixes.push(prefix_data);
+        if let Some(expander) = row.downcast_ref::<ExpanderRow>() {
+            expander.set_expanded(true);
         }
-        println!("Finished scanning compatdata.");
-        Ok(scanned_prefixes)
+        row.grab_focus();
+        true
     }
-    
+
     // New function to update UI from scanned data
-    pub fn update_listbox(&self, prefixes: &[PrefixData]) { // Accept slice
+    pub fn update_listbox(&self, prefixes: &[PrefixData], failed: &[LibraryError]) { // Accept slice
          println!("Updating CompatDataPage listbox with {} prefixes...", prefixes.len());
          // Clear existing items
          while let Some(child) = self.listbox.first_child() {
              self.listbox.remove(&child);
          }
+         self.row_sort_keys.borrow_mut().clear();
+         self.library_headers.borrow_mut().clear();
 
          // Clear the internal detected_dirs map (if we still need it?)
          // For now, let's assume it's not the primary source of truth anymore
          // self.detected_dirs.borrow_mut().clear(); 
 
+         // Libraries that couldn't be scanned go first, each with its own retry
+         for error in failed {
+             self.listbox.append(&Self::create_library_error_row(error));
+         }
+
+         Self::update_match_count(&self.match_count_label, &self.row_sort_keys.borrow(), &self.filter);
          if prefixes.is_empty() {
-             let placeholder_label = Label::new(Some("No Proton prefixes found.")); // Simpler message
+             let placeholder_label = Label::new(Some(&gettext("No Proton prefixes found."))); // Simpler message
              placeholder_label.set_margin_start(12);
              placeholder_label.set_margin_end(12);
              placeholder_label.set_margin_top(12);
@@ -153,37 +479,237 @@ impl CompatDataPage {
              return;
          }
 
-         let config_borrow = self.config.borrow(); // Borrow once
+         let compatdata_paths = self.config.borrow().compatdata_paths();
+         let mut libraries: Vec<PathBuf> = prefixes.iter().filter_map(|prefix| prefix._path.parent().map(Path::to_path_buf)).collect();
+         libraries.sort();
+         libraries.dedup();
+         let library_index = |library: &Path| compatdata_paths.iter().position(|path| path == library).unwrap_or(usize::MAX);
+         for library in &libraries {
+             let (row, header) = self.create_library_header(library, library_index(library));
+             self.library_headers.borrow_mut().insert(row.clone().upcast(), header);
+             self.listbox.append(&row);
+         }
+
          for prefix_data in prefixes {
              // Populate the detected_dirs map (maybe still useful?)
              // self.detected_dirs.borrow_mut().insert(prefix_data.game_id.clone(), prefix_data.path.clone());
 
-             // Pass the borrowed config, not the Rc<RefCell>
-             let row = Self::create_game_prefix_expander_row(&self.listbox, &config_borrow, &self.window, prefix_data);
+             let (row, title, search_text) = Self::create_game_prefix_expander_row(&self.listbox, &self.config, &self.window, &self.toast_overlay, prefix_data);
+             let game_id = prefix_data.game_id.clone();
+             let sort_keys = RowSortKeys {
+                 name: self.config.borrow().display_name(&game_id).unwrap_or(&game_id).to_string(),
+                 tool: prefix_data.compat_tool.as_ref().map(|tool| tool.display_name()).unwrap_or_default(),
+                 last_played: prefix_data.last_played,
+                 proton_build: prefix_data.proton_build.clone(),
+                 // Only config_info names an install folder to look for
+                 proton_missing: prefix_data._path.join("config_info").exists() && prefix_data.proton_dir().is_none(),
+                 size: None,
+                 hidden: self.config.borrow().is_prefix_hidden(&game_id),
+                 library: prefix_data._path.parent().map(Path::to_path_buf).unwrap_or_default(),
+                 library_index: prefix_data._path.parent().map(library_index).unwrap_or(usize::MAX),
+                 title,
+                 search_text: search_text.to_lowercase(),
+                 game_id,
+             };
+             self.row_sort_keys.borrow_mut().insert(row.clone().upcast(), sort_keys);
              self.listbox.append(&row); 
          }
+
+         // Keep the restored or typed search applied to the new rows
+         self.filter.set_query(self.search_entry.text().to_lowercase(), &self.row_sort_keys.borrow());
+         self.listbox.invalidate_filter();
+         self.listbox.invalidate_sort();
+         Self::highlight_titles(&self.row_sort_keys.borrow(), &self.filter);
+         Self::update_match_count(&self.match_count_label, &self.row_sort_keys.borrow(), &self.filter);
+         self.show_prefix_sizes(prefixes);
          println!("CompatDataPage listbox updated.");
     }
 
-    // Filter ListBox children based on search query
-    fn filter_listbox(listbox: &ListBox, matcher: &SkimMatcherV2, query: &str) {
-        let mut child = listbox.first_child();
-        while let Some(row_widget) = child {
-            // Get the text from the widget name
-            let searchable_text = row_widget.widget_name().to_string(); 
-            
-            let visible = query.is_empty() || 
-                          matcher.fuzzy_match(&searchable_text.to_lowercase(), query).is_some();
-            row_widget.set_visible(visible);
-            
-            child = row_widget.next_sibling();
+    // Sizes of unused prefixes are known from the cache right away, the rest
+    // are measured in the background and filled in when done
+    fn show_prefix_sizes(&self, prefixes: &[PrefixData]) {
+        let generation = self.generation.get() + 1;
+        self.generation.set(generation);
+        let cache_path = self.config.borrow().prefix_sizes_cache_path();
+        let threads = self.config.borrow().io_limits().scan_threads;
+        let cache = PrefixSizeCache::load(&cache_path);
+        let mut to_measure = Vec::new();
+        let mut cached = Vec::new();
+        for prefix in prefixes {
+            match cache.cached(&prefix._path) {
+                Some(size) => cached.push((prefix.game_id.clone(), size)),
+                None => to_measure.push((prefix.game_id.clone(), prefix._path.clone())),
+            }
         }
+        Self::apply_prefix_sizes(&self.row_sort_keys, &cached);
+        Self::update_library_headers(&self.library_headers, &self.row_sort_keys);
+        if to_measure.is_empty() {
+            self.listbox.invalidate_sort();
+            return;
+        }
+
+        let row_sort_keys = self.row_sort_keys.clone();
+        let library_headers = self.library_headers.clone();
+        let current_generation = self.generation.clone();
+        let listbox = self.listbox.clone();
+        let sort_bar = self.sort_bar.clone();
+        gtk::glib::MainContext::default().spawn_local(async move {
+            let measured = gtk::gio::spawn_blocking(move || prefix_sizes::measure_all(&cache_path, &to_measure, threads)).await;
+            if current_generation.get() != generation {
+                return;
+            }
+            let Ok(measured) = measured else {
+                eprintln!("Measuring prefix sizes failed");
+                return;
+            };
+            Self::apply_prefix_sizes(&row_sort_keys, &measured);
+            Self::update_library_headers(&library_headers, &row_sort_keys);
+            if sort_bar.key() == "size" {
+                listbox.invalidate_sort();
+            }
+        });
     }
 
-    // Creates the ExpanderRow and sets its widget name for searching
-    fn create_game_prefix_expander_row(listbox: &ListBox, config: &Config, window: &adw::ApplicationWindow, prefix_data: &PrefixData) -> ExpanderRow {
+    fn apply_prefix_sizes(row_sort_keys: &RefCell<HashMap<gtk::ListBoxRow, RowSortKeys>>, sizes: &[(String, u64)]) {
+        let sizes: HashMap<&str, u64> = sizes.iter().map(|(game_id, size)| (game_id.as_str(), *size)).collect();
+        for (row, keys) in row_sort_keys.borrow_mut().iter_mut() {
+            let Some(size) = sizes.get(keys.game_id.as_str()) else {
+                continue;
+            };
+            keys.size = Some(*size);
+            if let Some(expander) = row.downcast_ref::<ExpanderRow>() {
+                let size = format_size(*size, DECIMAL);
+                let subtitle = expander.subtitle();
+                expander.set_subtitle(&if subtitle.is_empty() { size } else { format!("{} | {}", subtitle, size) });
+            }
+        }
+    }
+
+    fn create_library_header(&self, compatdata_path: &Path, index: usize) -> (ActionRow, LibraryHeader) {
+        // steamapps/compatdata -> the library folder itself
+        let library_path = compatdata_path.parent().and_then(Path::parent).unwrap_or(compatdata_path);
+        let title = if index == 0 { gettext("Main Library") } else { library_path.display().to_string() };
+        let drive = match fsutil::drive_kind(compatdata_path) {
+            _ if self.config.borrow().is_on_removable_media(compatdata_path) => gettext("💾 SD Card"),
+            DriveKind::Removable => gettext("💾 Removable Drive"),
+            DriveKind::Ssd => gettext("Internal SSD"),
+            DriveKind::Hdd => gettext("Hard Drive"),
+            DriveKind::Unknown => gettext("Unknown Drive"),
+        };
+        let row = ActionRow::builder()
+            .title(gtk::glib::markup_escape_text(&title).as_str())
+            .activatable(true)
+            .build();
+        row.set_tooltip_text(Some(&library_path.display().to_string()));
+        row.add_css_class("library-header");
+        row.add_css_class("emoji");
+        row.add_prefix(&gtk::Image::from_icon_name("drive-harddisk-symbolic"));
+        let collapsed = self.collapsed_libraries.borrow().contains(compatdata_path);
+        let arrow = gtk::Image::from_icon_name(if collapsed { "pan-end-symbolic" } else { "pan-down-symbolic" });
+        row.add_suffix(&arrow);
+
+        let listbox = self.listbox.clone();
+        let collapsed_libraries = self.collapsed_libraries.clone();
+        let compatdata_path_clone = compatdata_path.to_path_buf();
+        row.connect_activated(move |_| {
+            let mut collapsed_libraries = collapsed_libraries.borrow_mut();
+            let collapsed = !collapsed_libraries.remove(&compatdata_path_clone);
+            if collapsed {
+                collapsed_libraries.insert(compatdata_path_clone.clone());
+            }
+            drop(collapsed_libraries);
+            arrow.set_icon_name(Some(if collapsed { "pan-end-symbolic" } else { "pan-down-symbolic" }));
+            listbox.invalidate_filter();
+        });
+        let header = LibraryHeader { compatdata_path: compatdata_path.to_path_buf(), index, drive };
+        (row, header)
+    }
+
+    // Prefix count and total size under each library header, sizes that
+    // aren't measured yet are left out
+    fn update_library_headers(library_headers: &RefCell<HashMap<gtk::ListBoxRow, LibraryHeader>>, row_sort_keys: &RefCell<HashMap<gtk::ListBoxRow, RowSortKeys>>) {
+        let row_sort_keys = row_sort_keys.borrow();
+        for (row, header) in library_headers.borrow().iter() {
+            let prefixes: Vec<&RowSortKeys> = row_sort_keys.values().filter(|keys| keys.library == header.compatdata_path).collect();
+            let size: u64 = prefixes.iter().filter_map(|keys| keys.size).sum();
+            let subtitle = gettext_f("{} | {} prefixes | {}", &[&header.drive, &prefixes.len().to_string(), &format_size(size, DECIMAL)]);
+            if let Some(row) = row.downcast_ref::<ActionRow>() {
+                row.set_subtitle(&subtitle);
+            }
+        }
+    }
+
+    // True when Steam recorded no play within `NOT_PLAYED_DAYS`, or none at all
+    fn not_played_recently(last_played: Option<u64>) -> bool {
+        last_played.is_none_or(|last_played| now_secs().saturating_sub(last_played) >= NOT_PLAYED_DAYS * SECS_PER_DAY)
+    }
+
+    // "Last played 3 days ago" and the like, months and years for old ones
+    pub fn last_played_label(last_played: u64) -> String {
+        let days = now_secs().saturating_sub(last_played) / SECS_PER_DAY;
+        match days {
+            0 => gettext("Last played today"),
+            1 => gettext("Last played yesterday"),
+            2..=59 => gettext_f("Last played {} days ago", &[&days.to_string()]),
+            60..=729 => gettext_f("Last played {} months ago", &[&(days / 30).to_string()]),
+            _ => gettext_f("Last played {} years ago", &[&(days / 365).to_string()]),
+        }
+    }
+
+    fn create_library_error_row(error: &LibraryError) -> ActionRow {
+        let row = ActionRow::builder()
+            .title(gettext("Library Unavailable"))
+            .subtitle(gtk::glib::markup_escape_text(&error.message).as_str())
+            .build();
+        row.add_css_class("error");
+        row.add_prefix(&gtk::Image::from_icon_name("dialog-warning-symbolic"));
+
+        let retry_button = Button::with_label(&gettext("Retry"));
+        retry_button.set_valign(Align::Center);
+        retry_button.set_tooltip_text(Some(&gettext("Scan only this library again")));
+        retry_button.set_action_name(Some("app.retry-library"));
+        retry_button.set_action_target_value(Some(&error.compatdata_path.to_string_lossy().to_variant()));
+        row.add_suffix(&retry_button);
+        row
+    }
+
+    // "N of M prefixes" below the search entry, M being every listed prefix
+    fn update_match_count(label: &Label, row_sort_keys: &HashMap<ListBoxRow, RowSortKeys>, filter: &PrefixFilter) {
+        let total = row_sort_keys.len();
+        let shown = row_sort_keys.values().filter(|keys| filter.matches(keys)).count();
+        label.set_visible(total > 0);
+        label.set_label(&if shown == total {
+            gettext_f("{} prefixes", &[&total.to_string()])
+        } else {
+            gettext_f("{} of {} prefixes", &[&shown.to_string(), &total.to_string()])
+        });
+    }
+
+    // Bold the characters of each row title the search matched
+    fn highlight_titles(row_sort_keys: &HashMap<ListBoxRow, RowSortKeys>, filter: &PrefixFilter) {
+        let query = filter.query.borrow();
+        for (row, keys) in row_sort_keys {
+            let Some(expander) = row.downcast_ref::<ExpanderRow>() else {
+                continue;
+            };
+            let markup = Some(query.as_str())
+                .filter(|query| !query.is_empty())
+                .and_then(|query| search_match::highlight(&filter.matcher, &keys.title, query))
+                .unwrap_or_else(|| gtk::glib::markup_escape_text(&keys.title).to_string());
+            expander.set_title(&format!("🎮 {}", markup));
+        }
+    }
+
+    // Creates the ExpanderRow, with its title (without markup) and the text
+    // the search matches
+    fn create_game_prefix_expander_row(listbox: &ListBox, config_rc: &Rc<RefCell<Config>>, window: &adw::ApplicationWindow, toast_overlay: &adw::ToastOverlay, prefix_data: &PrefixData) -> (ExpanderRow, String, String) {
+        let config = config_rc.borrow();
         let game_id = &prefix_data.game_id;
-        let mut searchable_text = format!("Game ID: {}", game_id);
+        let mut searchable_text = gettext_f("Game ID: {}", &[game_id]);
+        let display_name = config.display_name(game_id).map(str::to_string);
+        if let Some(name) = &display_name {
+            searchable_text.push_str(&format!(" {} ", name));
+        }
         for loc in &prefix_data.save_locations {
             searchable_text.push_str(&format!(" {} ", loc.relative_path));
             for entry in &loc.entries {
@@ -191,16 +717,37 @@ impl CompatDataPage {
             }
         }
         
+        // With a custom name the App ID moves into the subtitle
+        let title = display_name.clone().unwrap_or_else(|| gettext_f("Game ID: {}", &[game_id]));
         let expander_row = ExpanderRow::builder()
-            .title(format!("🎮 Game ID: {}", game_id))
+            .title(format!("🎮 {}", gtk::glib::markup_escape_text(&title)))
             .show_enable_switch(false)
             .build();
-        
-        expander_row.set_widget_name(&searchable_text);
+        sort_bar::remember_expanded(&expander_row, config_rc, "compatdata", game_id);
+        let mut subtitle_parts = Vec::new();
+        if display_name.is_some() {
+            subtitle_parts.push(gettext_f("Game ID: {}", &[game_id]));
+        }
+        if let Some(tool) = &prefix_data.compat_tool {
+            subtitle_parts.push(tool.label());
+            searchable_text.push_str(&format!(" {} ", tool.display_name()));
+        }
+        if let Some(last_played) = prefix_data.last_played {
+            subtitle_parts.push(Self::last_played_label(last_played));
+        }
+        if let Some(build) = &prefix_data.proton_build {
+            subtitle_parts.push(gettext_f("Prefix from {}", &[&gtk::glib::markup_escape_text(build)]));
+            searchable_text.push_str(&format!(" {} ", build));
+        }
+        if config.is_on_removable_media(&prefix_data._path) {
+            subtitle_parts.push(gettext("💾 On SD Card"));
+            searchable_text.push_str(&format!(" {} ", gettext("SD Card")));
+        }
+        expander_row.set_subtitle(&subtitle_parts.join(" | "));
 
         let drive_c_path = config.drive_c_path(game_id);
         let open_drive_c_button = Button::from_icon_name("folder-open-symbolic");
-        open_drive_c_button.set_tooltip_text(Some("Open drive_c Folder"));
+        open_drive_c_button.set_tooltip_text(Some(&gettext("Open drive_c Folder")));
         open_drive_c_button.set_valign(Align::Center);
         let drive_c_path_clone = drive_c_path.clone();
         let window_clone = window.clone();
@@ -208,25 +755,72 @@ impl CompatDataPage {
             Self::open_file_manager(&window_clone, &drive_c_path_clone);
         });
         expander_row.add_suffix(&open_drive_c_button);
+        let rename_button = Button::from_icon_name("document-edit-symbolic");
+        rename_button.set_tooltip_text(Some(&gettext("Rename Game")));
+        rename_button.set_valign(Align::Center);
+        let config_clone = config_rc.clone();
+        let game_id_clone = game_id.to_string();
+        let window_clone = window.clone();
+        let current_name = display_name.clone().unwrap_or_default();
+        rename_button.connect_clicked(move |_| {
+            rename_dialog::show(Some(window_clone.upcast_ref()), config_clone.clone(), &game_id_clone, &current_name);
+        });
+        expander_row.add_suffix(&rename_button);
+        expander_row.add_suffix(&Self::create_tools_menu_button(window, config_rc, toast_overlay, game_id));
+        // Hiding saves in the config, whose change notification lists the prefixes again
+        let hidden = config.is_prefix_hidden(game_id);
+        if hidden {
+            expander_row.add_css_class("dim-label");
+        }
+        let hide_button = Button::from_icon_name(if hidden { "view-reveal-symbolic" } else { "view-conceal-symbolic" });
+        hide_button.set_tooltip_text(Some(&if hidden { gettext("Unhide Prefix") } else { gettext("Hide Prefix") }));
+        hide_button.set_valign(Align::Center);
+        let config_clone = config_rc.clone();
+        let game_id_clone = game_id.to_string();
+        hide_button.connect_clicked(move |_| {
+            if let Err(e) = config_clone.borrow_mut().set_prefix_hidden(&game_id_clone, !hidden) {
+                eprintln!("Error hiding prefix {}: {}", game_id_clone, e);
+            }
+        });
+        expander_row.add_suffix(&hide_button);
+        expander_row.add_suffix(&app_id_button::new(game_id));
+        let info_button = Button::from_icon_name("dialog-information-symbolic");
+        info_button.set_tooltip_text(Some(&gettext("Prefix Info")));
+        info_button.set_valign(Align::Center);
+        let config_clone = config_rc.clone();
+        let game_id_clone = game_id.to_string();
+        let window_clone = window.clone();
+        info_button.connect_clicked(move |_| {
+            // Checked on demand, walking system32 for every row during a scan would be slow
+            let config = config_clone.borrow();
+            let prefix = PrefixData::new(&config, &game_id_clone);
+            Self::show_prefix_info(&window_clone, &config.prefix_path(&game_id_clone), &game_id_clone, &prefix.check_health());
+        });
+        expander_row.add_suffix(&info_button);
         let delete_button = Button::from_icon_name("user-trash-symbolic");
-        delete_button.set_tooltip_text(Some("Delete Prefix"));
+        delete_button.set_tooltip_text(Some(&gettext("Delete Prefix")));
         delete_button.add_css_class("destructive-action");
         delete_button.set_valign(Align::Center);
-        let prefix_path = config.compatdata_path().join(game_id);
         let game_id_clone = game_id.to_string();
-        let prefix_path_clone = prefix_path.clone();
+        // Detected save folders get snapshotted before deletion so it can be undone
+        let save_paths: Vec<PathBuf> = prefix_data.save_locations.iter()
+            .flat_map(|loc| loc.entries.iter().map(|entry| entry.path.clone()))
+            .collect();
+        let config_clone = config_rc.clone();
         let window_clone = window.clone();
+        let toast_overlay_clone = toast_overlay.clone();
         let listbox_weak = listbox.downgrade(); 
         let row_weak = expander_row.downgrade(); 
         delete_button.connect_clicked(move |_| {
             if let (Some(row_strong), Some(listbox_strong)) = (row_weak.upgrade(), listbox_weak.upgrade()) {
                  let list_box_row = row_strong.upcast::<ListBoxRow>(); 
-                 Self::delete_prefix(&window_clone, &prefix_path_clone, &game_id_clone, &listbox_strong, &list_box_row);
+                 Self::delete_prefix(&window_clone, &config_clone, &toast_overlay_clone, &game_id_clone, &save_paths, &listbox_strong, &list_box_row);
             }
         });
         expander_row.add_suffix(&delete_button);
 
         // --- Add Save Location Rows Directly to ExpanderRow --- 
+        let prefix_path = config.prefix_path(game_id);
         let mut found_any_saves = false;
         for save_loc in &prefix_data.save_locations {
              if !save_loc.entries.is_empty() {
@@ -238,7 +832,7 @@ impl CompatDataPage {
                     .build();
 
                 let open_button = Button::from_icon_name("document-open-symbolic");
-                open_button.set_tooltip_text(Some("Open Location"));
+                open_button.set_tooltip_text(Some(&gettext("Open Location")));
                 open_button.set_valign(Align::Center);
                 let path_clone = save_loc.path.clone();
                 let window_clone = window.clone();
@@ -246,6 +840,7 @@ impl CompatDataPage {
                     Self::open_file_manager(&window_clone, &path_clone);
                 });
                 save_loc_row.add_suffix(&open_button);
+                location_menu::attach(&save_loc_row, save_loc.path.clone(), prefix_path.clone(), config_rc, false);
                 expander_row.add_row(&save_loc_row); 
 
                 // Rows for the specific game save folders within that location
@@ -257,7 +852,7 @@ impl CompatDataPage {
                     game_save_row.set_margin_start(24); 
                     
                     let open_save_button = Button::from_icon_name("document-open-symbolic");
-                    open_save_button.set_tooltip_text(Some("Open Save Folder"));
+                    open_save_button.set_tooltip_text(Some(&gettext("Open Save Folder")));
                     open_save_button.set_valign(Align::Center);
                     let entry_path = entry.path.clone();
                     let window_clone = window.clone();
@@ -265,13 +860,14 @@ impl CompatDataPage {
                         Self::open_file_manager(&window_clone, &entry_path);
                     });
                     game_save_row.add_suffix(&open_save_button);
+                    location_menu::attach(&game_save_row, entry.path.clone(), prefix_path.clone(), config_rc, true);
                     expander_row.add_row(&game_save_row); 
                 }
             }
         }
 
         if !found_any_saves {
-             let no_saves_label = Label::new(Some("🤷 No known save folders found"));
+             let no_saves_label = Label::new(Some(&gettext("🤷 No known save folders found")));
             no_saves_label.set_halign(Align::Center);
             no_saves_label.set_css_classes(&["dim-label", "emoji"]);
             no_saves_label.set_margin_top(12);
@@ -282,45 +878,329 @@ impl CompatDataPage {
             expander_row.add_row(&placeholder_row);
         }
         
-        expander_row
+        (expander_row, title, searchable_text)
     }
+    // Menu with winecfg, regedit and protontricks for one prefix, plus duplicating
+    // it and backing up or restoring all of it
+    fn create_tools_menu_button(window: &adw::ApplicationWindow, config_rc: &Rc<RefCell<Config>>, toast_overlay: &adw::ToastOverlay, game_id: &str) -> gtk::MenuButton {
+        let actions = gtk::gio::SimpleActionGroup::new();
+        for (name, tool) in [
+            ("winecfg", PrefixTool::Winecfg),
+            ("regedit", PrefixTool::Regedit),
+            ("protontricks", PrefixTool::Protontricks),
+        ] {
+            let action = gtk::gio::SimpleAction::new(name, None);
+            let config_clone = config_rc.clone();
+            let game_id_clone = game_id.to_string();
+            let window_clone = window.clone();
+            action.connect_activate(move |_, _| {
+                let prefix = PrefixData::new(&config_clone.borrow(), &game_id_clone);
+                if let Err(e) = prefix.launch_tool(tool) {
+                    eprintln!("Failed to launch {} for {}: {}", name, game_id_clone, e);
+                    error_dialog::show(Some(window_clone.upcast_ref()), &gettext_f("Couldn't Start {}", &[name]), &e);
+                }
+            });
+            actions.add_action(&action);
+        }
+        let duplicate_action = gtk::gio::SimpleAction::new("duplicate", None);
+        let config_clone = config_rc.clone();
+        let game_id_clone = game_id.to_string();
+        let window_clone = window.clone();
+        let toast_overlay_clone = toast_overlay.clone();
+        duplicate_action.connect_activate(move |_, _| {
+            let prefix_path = config_clone.borrow().prefix_path(&game_id_clone);
+            Self::duplicate_prefix(&window_clone, &toast_overlay_clone, &game_id_clone, prefix_path);
+        });
+        actions.add_action(&duplicate_action);
+        let repair_action = gtk::gio::SimpleAction::new("repair", None);
+        let config_clone = config_rc.clone();
+        let game_id_clone = game_id.to_string();
+        let window_clone = window.clone();
+        let toast_overlay_clone = toast_overlay.clone();
+        repair_action.connect_activate(move |_, _| {
+            prefix_backup_dialog::repair(&window_clone, &config_clone, &toast_overlay_clone, &game_id_clone);
+        });
+        actions.add_action(&repair_action);
+        for (name, restore) in [("backup", false), ("restore", true)] {
+            let action = gtk::gio::SimpleAction::new(name, None);
+            let config_clone = config_rc.clone();
+            let game_id_clone = game_id.to_string();
+            let window_clone = window.clone();
+            let toast_overlay_clone = toast_overlay.clone();
+            action.connect_activate(move |_, _| {
+                if restore {
+                    prefix_backup_dialog::restore(&window_clone, &config_clone, &toast_overlay_clone, &game_id_clone);
+                } else {
+                    prefix_backup_dialog::backup(&window_clone, &config_clone, &toast_overlay_clone, &game_id_clone);
+                }
+            });
+            actions.add_action(&action);
+        }
+
+        let menu = gtk::gio::Menu::new();
+        menu.append(Some(&gettext("Wine Configuration")), Some("prefix-tools.winecfg"));
+        menu.append(Some(&gettext("Registry Editor")), Some("prefix-tools.regedit"));
+        menu.append(Some(&gettext("Protontricks")), Some("prefix-tools.protontricks"));
+        let copy_section = gtk::gio::Menu::new();
+        copy_section.append(Some(&gettext("Duplicate Prefix…")), Some("prefix-tools.duplicate"));
+        copy_section.append(Some(&gettext("Back Up Whole Prefix…")), Some("prefix-tools.backup"));
+        copy_section.append(Some(&gettext("Restore Whole Prefix…")), Some("prefix-tools.restore"));
+        menu.append_section(None, &copy_section);
+        let repair_section = gtk::gio::Menu::new();
+        repair_section.append(Some(&gettext("Repair Prefix…")), Some("prefix-tools.repair"));
+        menu.append_section(None, &repair_section);
+
+        let menu_button = gtk::MenuButton::builder()
+            .icon_name("applications-system-symbolic")
+            .tooltip_text(gettext("Prefix Tools"))
+            .menu_model(&menu)
+            .valign(Align::Center)
+            .build();
+        menu_button.insert_action_group("prefix-tools", Some(&actions));
+        menu_button
+    }
+
     fn open_file_manager(window: &adw::ApplicationWindow, path: &Path) {
         if let Err(err) = compatdata::open_in_file_manager(path) {
-            Self::show_error_dialog(window, &format!("Path does not exist: {}", err));
+            error_dialog::show(Some(window.upcast_ref()), &gettext("Couldn't Open the Folder"), &err);
         }
     }
-    fn delete_prefix(window: &adw::ApplicationWindow, prefix_path: &Path, game_id: &str, listbox: &ListBox, row: &gtk::ListBoxRow) { 
+    fn delete_prefix(window: &adw::ApplicationWindow, config: &Rc<RefCell<Config>>, toast_overlay: &adw::ToastOverlay, game_id: &str, save_paths: &[PathBuf], listbox: &ListBox, row: &gtk::ListBoxRow) { 
         let dialog = MessageDialog::builder()
             .transient_for(window)
-            .heading(&format!("🗑️ Delete Prefix for Game ID {}?", game_id))
-            .body("This will permanently delete the prefix folder and all save files. This action cannot be undone.")
+            .heading(gettext_f("🗑️ Delete Prefix for Game ID {}?", &[game_id]))
+            .body(gettext("This will delete the prefix folder. Detected save folders are kept in a safety snapshot for a week so the deletion can be undone; everything else is removed permanently."))
             .build();
-        dialog.add_response("cancel", "Cancel");
-        dialog.add_response("delete", "Delete");
+        dialog.add_response("cancel", &gettext("Cancel"));
+        dialog.add_response("delete", &gettext("Delete"));
         dialog.set_response_appearance("delete", adw::ResponseAppearance::Destructive);
         let window_clone = window.clone();
-        let prefix_path_clone = prefix_path.to_path_buf();
+        let config_clone = config.clone();
+        let toast_overlay_clone = toast_overlay.clone();
+        let game_id_clone = game_id.to_string();
+        let save_paths_clone = save_paths.to_vec();
         let row_clone = row.clone(); 
         let listbox_clone = listbox.clone(); 
-        dialog.connect_response(None, move |dialog, response| {
-            if response == "delete" {
-                if let Err(err) = std::fs::remove_dir_all(&prefix_path_clone) {
-                    Self::show_error_dialog(&window_clone, &format!("Error deleting prefix: {}", err));
-                } else {
-                    listbox_clone.remove(&row_clone);
+        dialog.set_close_response("cancel");
+        confirm::run(config, dialog, ConfirmAction::Delete, "delete-prefix", "delete", move || {
+            let prefix_path = config_clone.borrow().prefix_path(&game_id_clone);
+            let snapshot = Snapshot::create(&config_clone.borrow(), &game_id_clone, "Delete prefix", &save_paths_clone);
+            match snapshot {
+                Err(err) => {
+                    // Refuse to delete anything we couldn't back up first
+                    error_dialog::show(Some(window_clone.upcast_ref()), &gettext("Prefix Not Deleted"), &err.context(gettext("Could not create a safety snapshot, the prefix was not deleted")));
+                }
+                Ok(snapshot) => {
+                    if let Err(err) = std::fs::remove_dir_all(&prefix_path) {
+                        error_dialog::show(Some(window_clone.upcast_ref()), &gettext("Error Deleting Prefix"), &err.into());
+                    } else {
+                        let config = config_clone.borrow();
+                        activity::record(
+                            &config,
+                            ActivityEntry::new(ActivityKind::Delete, "Deleted prefix")
+                                .game(&config, &game_id_clone)
+                                .paths([prefix_path.clone()]),
+                        );
+                        listbox_clone.remove(&row_clone);
+                        Self::show_undo_toast(&window_clone, &config_clone, &toast_overlay_clone, &game_id_clone, snapshot);
+                    }
                 }
             }
-            dialog.destroy();
+        });
+    }
+    // Ask for the App ID of the copy, then copy the prefix in the background
+    fn duplicate_prefix(window: &adw::ApplicationWindow, toast_overlay: &adw::ToastOverlay, game_id: &str, prefix_path: PathBuf) {
+        let dialog = MessageDialog::builder()
+            .transient_for(window)
+            .heading(gettext_f("Duplicate Prefix for Game ID {}?", &[game_id]))
+            .body(gettext("Copies the whole prefix to another App ID in the same library, e.g. to move a game to another Steam account or to try a Proton version on a copy. The original is left as it is."))
+            .build();
+        let app_id_row = adw::EntryRow::builder()
+            .title(gettext("New App ID"))
+            .input_purpose(gtk::InputPurpose::Digits)
+            .build();
+        let list = ListBox::new();
+        list.set_selection_mode(SelectionMode::None);
+        list.add_css_class("boxed-list");
+        list.append(&app_id_row);
+        dialog.set_extra_child(Some(&list));
+        dialog.add_response("cancel", &gettext("Cancel"));
+        dialog.add_response("duplicate", &gettext("Duplicate"));
+        dialog.set_response_appearance("duplicate", adw::ResponseAppearance::Suggested);
+        dialog.set_default_response(Some("duplicate"));
+        dialog.set_close_response("cancel");
+        dialog.set_response_enabled("duplicate", false);
+        let game_id_clone = game_id.to_string();
+        let dialog_weak = dialog.downgrade();
+        app_id_row.connect_changed(move |row| {
+            let text = row.text();
+            let valid = !text.is_empty() && text.chars().all(|c| c.is_ascii_digit()) && text != game_id_clone;
+            if let Some(dialog) = dialog_weak.upgrade() {
+                dialog.set_response_enabled("duplicate", valid);
+            }
+        });
+
+        let window_clone = window.clone();
+        let toast_overlay_clone = toast_overlay.clone();
+        dialog.connect_response(Some("duplicate"), move |_, _| {
+            let new_app_id = app_id_row.text().to_string();
+            let window = window_clone.clone();
+            let toast_overlay = toast_overlay_clone.clone();
+            let prefix_path = prefix_path.clone();
+            let progress_toast = adw::Toast::builder()
+                .title(gettext_f("Duplicating prefix to Game ID {}…", &[&new_app_id]))
+                .timeout(0)
+                .build();
+            toast_overlay.add_toast(progress_toast.clone());
+            gtk::glib::MainContext::default().spawn_local(async move {
+                let app_id = new_app_id.clone();
+                let result = gtk::gio::spawn_blocking(move || compatdata::duplicate_prefix(&prefix_path, &app_id)).await;
+                progress_toast.dismiss();
+                match result {
+                    Ok(Ok(_)) => {
+                        toast_overlay.add_toast(adw::Toast::new(&gettext_f("Prefix duplicated to Game ID {}", &[&new_app_id])));
+                        let _ = WidgetExt::activate_action(&window, "app.refresh", None);
+                    }
+                    Ok(Err(err)) => {
+                        eprintln!("Error duplicating prefix to {}: {:#}", new_app_id, err);
+                        error_dialog::show(Some(window.upcast_ref()), &gettext("Error Duplicating Prefix"), &err);
+                    }
+                    Err(_) => eprintln!("Duplicating the prefix panicked"),
+                }
+            });
         });
         dialog.present();
     }
-     fn show_error_dialog(window: &adw::ApplicationWindow, message: &str) {
+    // Dialog listing version info and detected problems for a prefix
+    fn show_prefix_info(window: &adw::ApplicationWindow, prefix_path: &Path, game_id: &str, health: &PrefixHealth) {
+        let list = ListBox::new();
+        list.set_selection_mode(SelectionMode::None);
+        list.add_css_class("boxed-list");
+
+        let unknown = gettext("Unknown");
+        let info_row = |title: String, subtitle: &str| {
+            let row = ActionRow::builder()
+                .title(title)
+                .subtitle(subtitle)
+                .subtitle_selectable(true)
+                .use_markup(false)
+                .build();
+            list.append(&row);
+        };
+        info_row(gettext("Proton Version"), health.proton_version.as_deref().unwrap_or(&unknown));
+        info_row(gettext("Prefix Version"), health.prefix_version.as_deref().unwrap_or(&unknown));
+        info_row(gettext("Architecture"), health.arch.as_deref().unwrap_or(&unknown));
+        if !health.launchers.is_empty() {
+            info_row(gettext("Game Launchers"), &health.launchers.join(", "));
+        }
+        info_row(gettext("Virtual Desktop"), &health.virtual_desktop.clone().unwrap_or_else(|| gettext("Off")));
+
+        // Problems go into expanders, there can be hundreds of broken DLL links
+        let problem_row = |title: String, items: Vec<String>| {
+            let ok = items.is_empty();
+            let row = ExpanderRow::builder()
+                .title(title)
+                .subtitle(if ok { gettext("None found") } else { items.len().to_string() })
+                .enable_expansion(!ok)
+                .build();
+            if !ok {
+                row.add_css_class("warning");
+            }
+            for item in items.into_iter().take(MAX_PROBLEM_ROWS) {
+                let child = ActionRow::builder()
+                    .title(item)
+                    .use_markup(false)
+                    .css_classes(vec!["compact"])
+                    .build();
+                row.add_row(&child);
+            }
+            list.append(&row);
+        };
+        let relative = |path: &Path| {
+            path.strip_prefix(prefix_path).unwrap_or(path).display().to_string()
+        };
+        problem_row(gettext("Broken Symlinks"), health.broken_symlinks.iter().map(|p| relative(p)).collect());
+        problem_row(
+            gettext("Missing Shell Folders"),
+            health.missing_shell_folders.iter().map(|(name, path)| format!("{}: {}", name, relative(path))).collect(),
+        );
+
+        // Not a problem, only listed apart from the saves since snapshots and
+        // bundles leave them out
+        let cache_size: u64 = health.shader_caches.iter().map(|cache| cache.size).sum();
+        let cache_row = ExpanderRow::builder()
+            .title(gettext("Shader Caches"))
+            .subtitle(if health.shader_caches.is_empty() {
+                gettext("None found")
+            } else {
+                gettext_f("{} ({}), not backed up", &[&health.shader_caches.len().to_string(), &format_size(cache_size, DECIMAL)])
+            })
+            .enable_expansion(!health.shader_caches.is_empty())
+            .build();
+        for cache in health.shader_caches.iter().take(MAX_PROBLEM_ROWS) {
+            let child = ActionRow::builder()
+                .title(relative(&cache.path))
+                .subtitle(format_size(cache.size, DECIMAL))
+                .use_markup(false)
+                .css_classes(vec!["compact"])
+                .build();
+            cache_row.add_row(&child);
+        }
+        list.append(&cache_row);
+
+        let scroll = ScrolledWindow::builder()
+            .child(&list)
+            .propagate_natural_height(true)
+            .max_content_height(400)
+            .min_content_width(420)
+            .build();
+
         let dialog = MessageDialog::builder()
             .transient_for(window)
-            .heading("Error")
-            .body(message)
+            .heading(gettext_f("Prefix Info for Game ID {}", &[game_id]))
+            .extra_child(&scroll)
             .build();
-        dialog.add_response("ok", "OK");
+        dialog.add_response("ok", &gettext("OK"));
         dialog.present();
     }
+    // Offer to bring the snapshotted save folders back after a deletion
+    fn show_undo_toast(window: &adw::ApplicationWindow, config: &Rc<RefCell<Config>>, toast_overlay: &adw::ToastOverlay, game_id: &str, snapshot: Snapshot) {
+        let toast = adw::Toast::builder()
+            .title(gettext_f("Prefix for Game ID {} deleted", &[game_id]))
+            .button_label(gettext("Undo"))
+            .timeout(10)
+            .build();
+        let window_clone = window.clone();
+        let config = config.clone();
+        let game_id = game_id.to_string();
+        toast.connect_button_clicked(move |_| {
+            match snapshot.restore() {
+                Ok(_) => {
+                    let config = config.borrow();
+                    activity::record(
+                        &config,
+                        ActivityEntry::new(ActivityKind::Restore, "Undid prefix deletion")
+                            .game(&config, &game_id)
+                            .paths(snapshot.items.iter().map(|item| item.original_path.clone()))
+                            .bytes(snapshot.size()),
+                    );
+                    // Rescan so the restored prefix shows up again
+                    let _ = WidgetExt::activate_action(&window_clone, "app.refresh", None);
+                }
+                Err(err) => {
+                    error_dialog::show(Some(window_clone.upcast_ref()), &gettext("Failed to Restore Safety Snapshot"), &err);
+                }
+            }
+        });
+        toast_overlay.add_toast(toast);
+    }
+}
+
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
 }
@@ -0,0 +1,47 @@
+use adw::prelude::*;
+use adw::MessageDialog;
+use gtk::glib;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::config::{Config, ConfirmAction};
+use crate::i18n::gettext;
+
+// Shows `dialog` unless the confirmation setting or an earlier "don't ask
+// again" skips it, and says whether to go ahead: true if `response` was chosen
+// or nothing was asked. `id` names the confirmation for "don't ask again".
+pub async fn ask(config: &Rc<RefCell<Config>>, dialog: MessageDialog, action: ConfirmAction, id: &str, response: &str) -> bool {
+    if !config.borrow().needs_confirmation(action, id) {
+        println!("Skipping confirmation {}", id);
+        return true;
+    }
+
+    // Below whatever the dialog already shows
+    let check = gtk::CheckButton::with_label(&gettext("Don't ask again until the app is restarted"));
+    match dialog.extra_child() {
+        Some(extra) => {
+            dialog.set_extra_child(None::<&gtk::Widget>);
+            let content = gtk::Box::new(gtk::Orientation::Vertical, 12);
+            content.append(&extra);
+            content.append(&check);
+            dialog.set_extra_child(Some(&content));
+        }
+        None => dialog.set_extra_child(Some(&check)),
+    }
+
+    let confirmed = dialog.choose_future().await == response;
+    if confirmed && check.is_active() {
+        config.borrow_mut().skip_confirmation_for_session(id);
+    }
+    confirmed
+}
+
+// `ask` for callers that aren't async: runs `proceed` once confirmed
+pub fn run(config: &Rc<RefCell<Config>>, dialog: MessageDialog, action: ConfirmAction, id: &'static str, response: &'static str, proceed: impl FnOnce() + 'static) {
+    let config = config.clone();
+    glib::MainContext::default().spawn_local(async move {
+        if ask(&config, dialog, action, id, response).await {
+            proceed();
+        }
+    });
+}
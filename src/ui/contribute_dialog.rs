@@ -0,0 +1,97 @@
+use adw::prelude::*;
+use adw::MessageDialog;
+use gtk::glib;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::i18n::{gettext, gettext_f};
+use crate::manifest;
+
+// Turn save paths added by hand into a ludusavi manifest entry, so the fix
+// reaches everyone using the manifest. The snippet can be copied, or an issue
+// on ludusavi-manifest opened with it filled in.
+pub fn show(parent: Option<&gtk::Window>, name: Option<&str>, app_id: &str, paths: Vec<String>) {
+    let dialog = MessageDialog::builder()
+        .heading(gettext("Contribute to the Manifest"))
+        .body(gettext("The game data manifest is shared with ludusavi and other tools. Send the save paths you added, checked against a real save, so the game is found for everyone."))
+        .modal(true)
+        .build();
+    dialog.set_transient_for(parent);
+
+    let content = gtk::Box::new(gtk::Orientation::Vertical, 12);
+    let name_entry = gtk::Entry::builder()
+        .placeholder_text(gettext("Game name as on Steam or PCGamingWiki"))
+        .text(name.unwrap_or_default())
+        .build();
+    content.append(&name_entry);
+
+    let snippet_view = gtk::TextView::builder()
+        .editable(false)
+        .monospace(true)
+        .wrap_mode(gtk::WrapMode::WordChar)
+        .top_margin(6)
+        .bottom_margin(6)
+        .left_margin(6)
+        .right_margin(6)
+        .build();
+    let scrolled = gtk::ScrolledWindow::builder()
+        .hscrollbar_policy(gtk::PolicyType::Never)
+        .min_content_height(160)
+        .child(&snippet_view)
+        .build();
+    scrolled.add_css_class("card");
+    content.append(&scrolled);
+
+    let buttons = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+    buttons.set_halign(gtk::Align::Center);
+    let copy_button = gtk::Button::with_label(&gettext("Copy Snippet"));
+    buttons.append(&copy_button);
+    let issue_button = gtk::LinkButton::with_label(manifest::MANIFEST_NEW_ISSUE_URL, &gettext("Open Issue"));
+    issue_button.set_tooltip_text(Some(&gettext("Open a new issue on ludusavi-manifest with the entry filled in")));
+    buttons.append(&issue_button);
+    content.append(&buttons);
+    dialog.set_extra_child(Some(&content));
+    dialog.add_response("close", &gettext("Close"));
+
+    // The snippet follows the name, the issue link follows the snippet
+    let snippet = Rc::new(RefCell::new(String::new()));
+    let app_id = app_id.to_string();
+    let update = glib::clone!(@weak snippet_view, @weak issue_button, @weak copy_button, @strong snippet => move |name: &str| {
+        let name = name.trim();
+        let text = manifest::contribution_snippet(name, &app_id, &paths);
+        snippet_view.buffer().set_text(&text);
+        issue_button.set_uri(&issue_url(name, &app_id, &text));
+        issue_button.set_sensitive(!name.is_empty());
+        copy_button.set_sensitive(!name.is_empty());
+        copy_button.set_label(&gettext("Copy Snippet"));
+        snippet.replace(text);
+    });
+    update(&name_entry.text());
+    name_entry.connect_changed(move |entry| update(&entry.text()));
+    copy_button.connect_clicked(move |button| {
+        button.clipboard().set_text(&snippet.borrow());
+        button.set_label(&gettext("Copied"));
+    });
+
+    dialog.present();
+}
+
+// Not translated, it's meant for the manifest's issue tracker
+fn issue_url(name: &str, app_id: &str, snippet: &str) -> String {
+    let title = format!("Save locations for {}", name);
+    let body = format!(
+        "Save locations I found for **{}** (Steam App ID {}) running through Proton, added by hand in Proton Game Saves:\n\n```yaml\n{}```\n",
+        name, app_id, snippet
+    );
+    format!(
+        "{}?title={}&body={}",
+        manifest::MANIFEST_NEW_ISSUE_URL,
+        glib::Uri::escape_string(&title, None, true),
+        glib::Uri::escape_string(&body, None, true)
+    )
+}
+
+// The name to start with, games only known by their custom paths have none
+pub fn initial_name(name: &str, app_id: &str) -> Option<String> {
+    (name != gettext_f("Game ID: {}", &[app_id])).then(|| name.to_string())
+}
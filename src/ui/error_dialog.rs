@@ -0,0 +1,106 @@
+use adw::prelude::*;
+use adw::MessageDialog;
+use gtk::glib;
+use humansize::{format_size, DECIMAL};
+
+use crate::fsutil::NotEnoughSpace;
+use crate::i18n::{gettext, gettext_f};
+
+const NEW_ISSUE_URL: &str = "https://github.com/rahatzamancse/protongamesaves/issues/new";
+
+// Dialog for a failed operation: the error up top, every cause of its anyhow
+// chain under "Details", and buttons to copy those or open a GitHub issue with
+// them filled in
+pub fn show(parent: Option<&gtk::Window>, heading: &str, error: &anyhow::Error) {
+    // A full disk isn't a bug, say what to do about it instead
+    if let Some(space) = error.downcast_ref::<NotEnoughSpace>() {
+        show_not_enough_space(parent, heading, space);
+        return;
+    }
+    let chain: Vec<String> = error.chain().map(|cause| cause.to_string()).collect();
+    let message = chain.first().cloned().unwrap_or_default();
+    let dialog = MessageDialog::builder().heading(heading).body(&message).modal(true).build();
+    dialog.set_transient_for(parent);
+
+    let details = details_text(heading, &chain);
+    let content = gtk::Box::new(gtk::Orientation::Vertical, 12);
+
+    // Causes past the first line of the chain, they're often the useful part
+    if chain.len() > 1 {
+        let causes = gtk::Label::builder()
+            .label(chain[1..].join("\n"))
+            .selectable(true)
+            .wrap(true)
+            .wrap_mode(gtk::pango::WrapMode::WordChar)
+            .xalign(0.0)
+            .build();
+        causes.add_css_class("monospace");
+        let expander = gtk::Expander::new(Some(&gettext("Details")));
+        expander.set_child(Some(&causes));
+        content.append(&expander);
+    }
+
+    let buttons = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+    buttons.set_halign(gtk::Align::Center);
+    let copy_button = gtk::Button::with_label(&gettext("Copy Details"));
+    let details_clone = details.clone();
+    copy_button.connect_clicked(move |button| {
+        button.clipboard().set_text(&details_clone);
+        button.set_label(&gettext("Copied"));
+    });
+    buttons.append(&copy_button);
+    let report_button = gtk::LinkButton::with_label(&issue_url(&message, &details), &gettext("Report Issue"));
+    report_button.set_tooltip_text(Some(&gettext("Open a new issue on GitHub with the details filled in. They may contain folder paths, check them before posting.")));
+    buttons.append(&report_button);
+    content.append(&buttons);
+
+    dialog.set_extra_child(Some(&content));
+    dialog.add_response("ok", &gettext("OK"));
+    dialog.present();
+}
+
+// The copy was refused before anything was written, so nothing needs cleaning up
+fn show_not_enough_space(parent: Option<&gtk::Window>, heading: &str, space: &NotEnoughSpace) {
+    let dialog = MessageDialog::builder()
+        .heading(heading)
+        .body(gettext_f(
+            "There is not enough free space on the drive holding {}: this needs {}, but only {} are free. Nothing was copied. Free up some space there or choose another destination, then try again.",
+            &[&space.path.display().to_string(), &format_size(space.needed, DECIMAL), &format_size(space.available, DECIMAL)],
+        ))
+        .modal(true)
+        .build();
+    dialog.set_transient_for(parent);
+    dialog.add_response("ok", &gettext("OK"));
+    dialog.present();
+}
+
+// What gets copied and put into the issue: the operation, the whole chain
+// and where the app runs. Not translated, it's meant for the bug tracker.
+fn details_text(heading: &str, chain: &[String]) -> String {
+    let mut text = format!("{}\n\nError: {}\n", heading, chain.first().map(String::as_str).unwrap_or_default());
+    if chain.len() > 1 {
+        text.push_str("\nCaused by:\n");
+        for cause in &chain[1..] {
+            text.push_str(&format!("    {}\n", cause));
+        }
+    }
+    let install = if std::env::var_os("FLATPAK_ID").is_some() { "Flatpak" } else { "native" };
+    text.push_str(&format!(
+        "\nProton Game Saves {} ({}), {} {}\n",
+        env!("CARGO_PKG_VERSION"),
+        install,
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    ));
+    text
+}
+
+fn issue_url(message: &str, details: &str) -> String {
+    let body = format!("**What were you doing?**\n\n\n**Error details**\n\n```\n{}```\n", details);
+    format!(
+        "{}?title={}&body={}",
+        NEW_ISSUE_URL,
+        glib::Uri::escape_string(message, None, true),
+        glib::Uri::escape_string(&body, None, true)
+    )
+}
@@ -0,0 +1,405 @@
+use adw::prelude::*;
+use adw::{ActionRow, MessageDialog, NavigationPage, NavigationView, PreferencesGroup, PreferencesPage, StatusPage};
+use gtk::glib;
+use humansize::{format_size, DECIMAL};
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use crate::config::Config;
+use crate::fsutil;
+use crate::i18n::{gettext, gettext_f};
+use crate::report;
+use crate::snapshot::{self, Snapshot, SnapshotEntry};
+use crate::ui::{error_dialog, preview_pane};
+
+// Read-only browser for save locations and safety snapshots: folders open into
+// their own page, files into their preview. Nothing can be changed from here,
+// restoring goes through the recover wizard.
+
+// Where browsing starts: a save location, or a snapshot with its stored copies
+pub struct BrowseRoot {
+    pub title: String,
+    pub subtitle: String,
+    // Files or folders under it with the name shown for each, empty for
+    // snapshots
+    pub paths: Vec<(PathBuf, String)>,
+    // Set for snapshots, they can be labelled from the list. Their stored
+    // copies are browsed as listed in the snapshot, compressed ones straight
+    // from the archive without unpacking it.
+    pub snapshot: Option<Rc<RefCell<Snapshot>>>,
+}
+
+impl BrowseRoot {
+    // A snapshot's label shows as soon as it's set
+    fn current_title(&self) -> String {
+        match &self.snapshot {
+            Some(snapshot) => {
+                let snapshot = snapshot.borrow();
+                let name = snapshot.game_name.clone().unwrap_or_else(|| gettext_f("Game ID {}", &[&snapshot.game_id]));
+                format!("{} – {}", name, snapshot.title())
+            }
+            None => self.title.clone(),
+        }
+    }
+
+    // Labelled snapshots still say why they were taken
+    fn current_subtitle(&self) -> String {
+        match self.snapshot.as_ref().map(|snapshot| snapshot.borrow()) {
+            Some(snapshot) if snapshot.label.is_some() => format!("{} | {}", snapshot.reason, self.subtitle),
+            _ => self.subtitle.clone(),
+        }
+    }
+
+    // `query` is lower case
+    fn matches(&self, query: &str) -> bool {
+        query.is_empty()
+            || self.current_title().to_lowercase().contains(query)
+            || self.current_subtitle().to_lowercase().contains(query)
+            || self.snapshot.as_ref().is_some_and(|snapshot| snapshot.borrow().matches(query))
+    }
+}
+
+// Browse one save location, from its row's menu
+pub fn show_location(parent: Option<&gtk::Window>, path: PathBuf, config: &Rc<RefCell<Config>>) {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.display().to_string());
+    let root = BrowseRoot {
+        title: name.clone(),
+        subtitle: path.display().to_string(),
+        paths: vec![(path, name)],
+        snapshot: None,
+    };
+    show(parent, &gettext_f("Files of {}", &[&root.title]), vec![root], config);
+}
+
+// Browse every safety snapshot, newest first
+pub fn show_snapshots(parent: Option<&gtk::Window>, config: &Rc<RefCell<Config>>) {
+    let roots = snapshot::list_snapshots(&config.borrow())
+        .into_iter()
+        .map(|snapshot| {
+            let subtitle = format!("{} | {}", report::format_timestamp_utc(snapshot.created), format_size(snapshot.size(), DECIMAL));
+            let mut root = BrowseRoot {
+                title: String::new(),
+                subtitle,
+                paths: Vec::new(),
+                snapshot: Some(Rc::new(RefCell::new(snapshot))),
+            };
+            root.title = root.current_title();
+            root
+        })
+        .collect();
+    show(parent, &gettext("Safety Snapshots"), roots, config);
+}
+
+pub fn show(parent: Option<&gtk::Window>, title: &str, roots: Vec<BrowseRoot>, config: &Rc<RefCell<Config>>) {
+    let window = adw::Window::builder()
+        .modal(true)
+        .title(title)
+        .default_width(560)
+        .default_height(600)
+        .build();
+    window.set_transient_for(parent);
+    let navigation = NavigationView::new();
+    // A single location skips the list of roots
+    let first_page = match roots.as_slice() {
+        [root] => paths_page(&navigation, config, root),
+        _ => roots_page(&navigation, config, title, Rc::new(roots)),
+    };
+    navigation.add(&first_page);
+    window.set_content(Some(&navigation));
+    window.present();
+}
+
+fn browser_page(title: &str, content: &impl IsA<gtk::Widget>) -> NavigationPage {
+    let toolbar = adw::ToolbarView::new();
+    toolbar.add_top_bar(&adw::HeaderBar::new());
+    toolbar.set_content(Some(content));
+    NavigationPage::builder().title(title).child(&toolbar).build()
+}
+
+fn empty_page(title: &str, heading: &str) -> NavigationPage {
+    let status = StatusPage::builder()
+        .icon_name("folder-symbolic")
+        .title(heading)
+        .build();
+    browser_page(title, &status)
+}
+
+fn roots_page(navigation: &NavigationView, config: &Rc<RefCell<Config>>, title: &str, roots: Rc<Vec<BrowseRoot>>) -> NavigationPage {
+    if roots.is_empty() {
+        return empty_page(title, &gettext("Nothing to Browse"));
+    }
+    let content = gtk::Box::new(gtk::Orientation::Vertical, 12);
+    content.set_margin_start(12);
+    content.set_margin_end(12);
+    content.set_margin_top(12);
+    content.set_margin_bottom(12);
+    let search_entry = gtk::SearchEntry::new();
+    search_entry.set_placeholder_text(Some(&gettext("Search Labels, Reasons or Games...")));
+    content.append(&search_entry);
+    let list = gtk::ListBox::new();
+    list.set_selection_mode(gtk::SelectionMode::None);
+    list.add_css_class("boxed-list");
+    list.set_valign(gtk::Align::Start);
+
+    for (index, root) in roots.iter().enumerate() {
+        let row = ActionRow::builder()
+            .title(glib::markup_escape_text(&root.current_title()).as_str())
+            .subtitle(glib::markup_escape_text(&root.current_subtitle()).as_str())
+            .activatable(true)
+            .build();
+        row.add_prefix(&gtk::Image::from_icon_name("folder-symbolic"));
+        if root.snapshot.is_some() {
+            let label_button = gtk::Button::builder()
+                .icon_name("document-edit-symbolic")
+                .tooltip_text(gettext("Label Snapshot"))
+                .valign(gtk::Align::Center)
+                .build();
+            label_button.add_css_class("flat");
+            label_button.connect_clicked(glib::clone!(@weak row, @strong roots => move |_| ask_label(&row, &roots, index)));
+            row.add_suffix(&label_button);
+        }
+        row.add_suffix(&gtk::Image::from_icon_name("go-next-symbolic"));
+        row.connect_activated(glib::clone!(@weak navigation, @strong config, @strong roots => move |_| {
+            navigation.push(&paths_page(&navigation, &config, &roots[index]));
+        }));
+        list.append(&row);
+    }
+
+    // Rows are in the same order as `roots`
+    list.set_filter_func(glib::clone!(@weak search_entry, @strong roots => @default-return true, move |row| {
+        let query = search_entry.text().to_lowercase();
+        roots.get(row.index() as usize).is_none_or(|root| root.matches(query.trim()))
+    }));
+    search_entry.connect_search_changed(glib::clone!(@weak list => move |_| list.invalidate_filter()));
+    let scrolled = gtk::ScrolledWindow::builder()
+        .hscrollbar_policy(gtk::PolicyType::Never)
+        .vexpand(true)
+        .child(&list)
+        .build();
+    content.append(&scrolled);
+    browser_page(title, &content)
+}
+
+// Label the snapshot of `roots[index]`, e.g. "Before NG+", shown in its row
+fn ask_label(row: &ActionRow, roots: &Rc<Vec<BrowseRoot>>, index: usize) {
+    let Some(snapshot) = roots.get(index).and_then(|root| root.snapshot.clone()) else {
+        return;
+    };
+    let window = row.root().and_downcast::<gtk::Window>();
+    let dialog = MessageDialog::new(
+        window.as_ref(),
+        Some(&gettext("Label Snapshot")),
+        Some(&gettext("Shown instead of why the snapshot was taken, and found by the search. Leave it empty to remove the label.")),
+    );
+    let label_row = adw::EntryRow::builder()
+        .title(gettext("Label"))
+        .text(snapshot.borrow().label.clone().unwrap_or_default())
+        .build();
+    let list = gtk::ListBox::new();
+    list.set_selection_mode(gtk::SelectionMode::None);
+    list.add_css_class("boxed-list");
+    list.append(&label_row);
+    dialog.set_extra_child(Some(&list));
+    dialog.add_response("cancel", &gettext("Cancel"));
+    dialog.add_response("save", &gettext("Save"));
+    dialog.set_response_appearance("save", adw::ResponseAppearance::Suggested);
+    dialog.set_default_response(Some("save"));
+    dialog.set_close_response("cancel");
+    dialog.connect_response(None, glib::clone!(@weak row, @strong roots => move |_, response| {
+        if response != "save" {
+            return;
+        }
+        let labelled = snapshot.borrow_mut().set_label(&label_row.text());
+        if let Err(e) = labelled {
+            eprintln!("Failed to label snapshot {}: {:#}", snapshot.borrow().dir.display(), e);
+            error_dialog::show(window.as_ref(), &gettext("Failed to Label Snapshot"), &e);
+            return;
+        }
+        row.set_title(glib::markup_escape_text(&roots[index].current_title()).as_str());
+        row.set_subtitle(glib::markup_escape_text(&roots[index].current_subtitle()).as_str());
+    }));
+    dialog.present();
+}
+
+// The stored copies of a snapshot, or straight into the one location
+fn paths_page(navigation: &NavigationView, config: &Rc<RefCell<Config>>, root: &BrowseRoot) -> NavigationPage {
+    if let Some(snapshot) = &root.snapshot {
+        return snapshot_page(navigation, config, root, snapshot);
+    }
+    let paths = &root.paths;
+    if let [(path, name)] = paths.as_slice() {
+        return entry_page(navigation, config, path, name);
+    }
+    if paths.is_empty() {
+        return empty_page(&root.title, &gettext("Nothing Stored"));
+    }
+    let page = PreferencesPage::new();
+    let group = PreferencesGroup::builder()
+        .title(glib::markup_escape_text(&root.title).as_str())
+        .build();
+    for (path, name) in paths {
+        let entry = fsutil::FolderEntry {
+            name: name.clone(),
+            is_dir: path.is_dir(),
+            size: fsutil::disk_usage(path),
+            modified: fsutil::last_modified(path),
+            path: path.clone(),
+        };
+        group.add(&entry_row(navigation, config, entry));
+    }
+    page.add(&group);
+    browser_page(&root.title, &page)
+}
+
+// What's inside a folder, or the preview of a file
+fn entry_page(navigation: &NavigationView, config: &Rc<RefCell<Config>>, path: &Path, name: &str) -> NavigationPage {
+    if !path.is_dir() {
+        return preview_page(config, path, name);
+    }
+
+    let entries = fsutil::list_folder(path);
+    if entries.is_empty() {
+        return empty_page(name, &gettext("Empty Folder"));
+    }
+    let total: u64 = entries.iter().map(|entry| entry.size).sum();
+    let page = PreferencesPage::new();
+    let group = PreferencesGroup::builder()
+        .title(glib::markup_escape_text(&path.display().to_string()).as_str())
+        .description(gettext_f("{} items, {}", &[&entries.len().to_string(), &format_size(total, DECIMAL)]))
+        .build();
+    for entry in entries {
+        group.add(&entry_row(navigation, config, entry));
+    }
+    page.add(&group);
+    browser_page(name, &page)
+}
+
+fn preview_page(config: &Rc<RefCell<Config>>, path: &Path, name: &str) -> NavigationPage {
+    let content = gtk::Box::new(gtk::Orientation::Vertical, 12);
+    content.set_margin_start(12);
+    content.set_margin_end(12);
+    content.set_margin_top(12);
+    content.set_margin_bottom(12);
+    content.append(&preview_pane::build(&[(path.to_path_buf(), name.to_string())], Some(&config.borrow())));
+    let scrolled = gtk::ScrolledWindow::builder()
+        .hscrollbar_policy(gtk::PolicyType::Never)
+        .vexpand(true)
+        .child(&content)
+        .build();
+    browser_page(name, &scrolled)
+}
+
+// Same look for files on disk and in snapshots
+fn item_row(name: &str, is_dir: bool, size: u64, modified: Option<u64>) -> ActionRow {
+    let mut subtitle = Vec::new();
+    if let Some(modified) = modified {
+        subtitle.push(report::format_timestamp_utc(modified));
+    }
+    subtitle.push(format_size(size, DECIMAL));
+    let row = ActionRow::builder()
+        .title(glib::markup_escape_text(name).as_str())
+        .subtitle(subtitle.join(" | "))
+        .activatable(true)
+        .build();
+    row.add_prefix(&gtk::Image::from_icon_name(if is_dir { "folder-symbolic" } else { "text-x-generic-symbolic" }));
+    row.add_suffix(&gtk::Image::from_icon_name("go-next-symbolic"));
+    row
+}
+
+fn entry_row(navigation: &NavigationView, config: &Rc<RefCell<Config>>, entry: fsutil::FolderEntry) -> ActionRow {
+    let row = item_row(&entry.name, entry.is_dir, entry.size, entry.modified);
+    let (path, name) = (entry.path, entry.name);
+    row.connect_activated(glib::clone!(@weak navigation, @strong config => move |_| {
+        navigation.push(&entry_page(&navigation, &config, &path, &name));
+    }));
+    row
+}
+
+// A snapshot's stored files as listed in it, read once when it's opened.
+// Compressed snapshots are listed from the archive's headers and only the
+// files that get previewed are taken out of it, see `Snapshot::stored_file`.
+struct StoredTree {
+    snapshot: Rc<RefCell<Snapshot>>,
+    entries: Vec<(usize, SnapshotEntry)>,
+}
+
+// The stored copies of a snapshot, or straight into the only one
+fn snapshot_page(navigation: &NavigationView, config: &Rc<RefCell<Config>>, root: &BrowseRoot, snapshot: &Rc<RefCell<Snapshot>>) -> NavigationPage {
+    let listed = snapshot.borrow().stored_entries();
+    let entries = match listed {
+        Ok(entries) => entries,
+        Err(e) => {
+            eprintln!("Failed to read snapshot {}: {:#}", snapshot.borrow().dir.display(), e);
+            return empty_page(&root.title, &gettext("The Snapshot Can't Be Read"));
+        }
+    };
+    let tree = Rc::new(StoredTree { snapshot: snapshot.clone(), entries });
+    let items: Vec<(usize, SnapshotEntry, String)> = snapshot
+        .borrow()
+        .items
+        .iter()
+        .enumerate()
+        .filter_map(|(index, item)| {
+            let entry = snapshot::stored_entry(&tree.entries, index, Path::new(""))?;
+            Some((index, entry, item.original_path.display().to_string()))
+        })
+        .collect();
+    if let [(index, entry, name)] = items.as_slice() {
+        return stored_page(navigation, config, &tree, *index, entry, name);
+    }
+    if items.is_empty() {
+        return empty_page(&root.title, &gettext("Nothing Stored"));
+    }
+    let page = PreferencesPage::new();
+    let group = PreferencesGroup::builder()
+        .title(glib::markup_escape_text(&root.title).as_str())
+        .build();
+    for (index, entry, name) in items {
+        group.add(&stored_row(navigation, config, &tree, index, entry, name));
+    }
+    page.add(&group);
+    browser_page(&root.title, &page)
+}
+
+// What's inside a stored folder, or the preview of a stored file
+fn stored_page(navigation: &NavigationView, config: &Rc<RefCell<Config>>, tree: &Rc<StoredTree>, index: usize, entry: &SnapshotEntry, name: &str) -> NavigationPage {
+    let snapshot = tree.snapshot.borrow();
+    let item = &snapshot.items[index];
+    if !entry.is_dir {
+        return match snapshot.stored_file(item, &entry.relative) {
+            Ok(path) => preview_page(config, &path, name),
+            Err(e) => {
+                eprintln!("Failed to read {} from snapshot {}: {:#}", entry.relative.display(), snapshot.dir.display(), e);
+                empty_page(name, &gettext("The File Can't Be Read"))
+            }
+        };
+    }
+
+    let entries = snapshot::stored_children(&tree.entries, index, &entry.relative);
+    if entries.is_empty() {
+        return empty_page(name, &gettext("Empty Folder"));
+    }
+    let page = PreferencesPage::new();
+    let group = PreferencesGroup::builder()
+        .title(glib::markup_escape_text(&snapshot.original_path_of(item, &entry.relative).display().to_string()).as_str())
+        .description(gettext_f("{} items, {}", &[&entries.len().to_string(), &format_size(entry.size, DECIMAL)]))
+        .build();
+    for child in entries {
+        let name = child.name.clone();
+        group.add(&stored_row(navigation, config, tree, index, child, name));
+    }
+    page.add(&group);
+    browser_page(name, &page)
+}
+
+fn stored_row(navigation: &NavigationView, config: &Rc<RefCell<Config>>, tree: &Rc<StoredTree>, index: usize, entry: SnapshotEntry, name: String) -> ActionRow {
+    let row = item_row(&name, entry.is_dir, entry.size, entry.modified);
+    row.connect_activated(glib::clone!(@weak navigation, @strong config, @strong tree => move |_| {
+        navigation.push(&stored_page(&navigation, &config, &tree, index, &entry, &name));
+    }));
+    row
+}
@@ -9,12 +9,24 @@ use std::cell::RefCell;
 use std::path::PathBuf; // Import PathBuf
 use std::process::Command;
 use std::rc::Rc;
-use std::{collections::HashMap, fs}; // For storing game data & fs operations
-use fuzzy_matcher::FuzzyMatcher;
+use std::collections::HashMap; // For storing game data
 use fuzzy_matcher::skim::SkimMatcherV2;
 
-use crate::compatdata::PrefixData; // Import PrefixData
-use crate::config::Config;
+use crate::bundle::{ExportGame, ExportLocation};
+use crate::cloud;
+use crate::compatdata::{self, PrefixData}; // Import PrefixData
+use crate::config::{Config, ScanOptions, ViewState};
+use crate::fsutil;
+use crate::recover::RecoverGame;
+use crate::registry;
+use crate::report::{self, ReportEntry};
+use crate::scan_changes::{GameSaves, SaveState};
+use crate::screenshots;
+use crate::i18n::{gettext, gettext_f};
+use crate::ui::keynav;
+use crate::ui::sort_bar::{self, SortBar};
+use crate::ui::space_page::SaveSize;
+use crate::ui::{app_id_button, assign_dialog, cloud_dialog, contribute_dialog, ignore_dialog, import_dialog, location_menu, quota_dialog, registry_dialog, rename_dialog, screenshot_gallery, search_match, trim_dialog};
 use crate::manifest::{self, ManifestData}; // Import manifest structs
 
 // Structure to hold combined game information
@@ -26,6 +38,25 @@ pub struct GameInfo {
     pub entry: manifest::GameEntry, // Store the full entry for details
     pub save_locations: Vec<SaveLocationInfo>, // Store resolved/found locations
     pub total_size_bytes: u64,      // Store calculated size
+    pub compat_tool: Option<String>, // Label of the Proton version Steam runs it with
+    pub customized: bool, // Assigned by hand or has custom save paths
+    pub on_removable_media: bool, // Prefix is in a library on an SD card
+    pub registry_keys: Vec<String>, // Registry keys the manifest lists as saves
+}
+
+// A scanned prefix that didn't match any manifest game
+pub struct UnmatchedPrefix {
+    pub game_id: String,
+    pub compat_tool: Option<String>,
+}
+
+impl From<&PrefixData> for UnmatchedPrefix {
+    fn from(prefix_data: &PrefixData) -> Self {
+        Self {
+            game_id: prefix_data.game_id.clone(),
+            compat_tool: prefix_data.compat_tool.as_ref().map(|tool| tool.label()),
+        }
+    }
 }
 
 // Structure to hold info about a specific save location for a game
@@ -35,19 +66,60 @@ pub struct SaveLocationInfo {
     pub resolved_path: PathBuf,    // The path resolved for the specific prefix
     pub size_bytes: u64,           // Size of this specific location
     pub exists: bool,              // Does the resolved path exist?
+    pub case_mismatch: bool,       // Exists, but spelled in a different case than the manifest's path
     pub tags: Option<Vec<String>>, // Tags from the manifest rule
+    pub screenshots: usize,        // Images found under it, counted with the size
 }
 
 pub struct GamesPage {
     widget: Box,
-    list_container: ListBox, // Change from Box to ListBox for consistent styling
+    // One item per matched game (its App ID), filtered by the search and the
+    // favorites toggle, then sorted. The list view only builds rows for the
+    // items on screen, so big libraries stay quick.
+    game_store: gio::ListStore,
+    game_filter: gtk::CustomFilter,
+    game_sorter: gtk::CustomSorter,
+    sorted_games: gtk::SortListModel,
+    list_view: gtk::ListView,
+    list_scroll: ScrolledWindow,
+    // Rows currently built for an item, by App ID
+    bound_rows: Rc<RefCell<HashMap<String, ExpanderRow>>>,
+    // Game to focus once its row is built, see `reveal`
+    pending_focus: Rc<RefCell<Option<String>>>,
+    placeholder: Box,
+    placeholder_label: Label,
+    placeholder_sub_label: Label,
     config: Rc<RefCell<Config>>,
     // Store the parsed manifest data
     manifest_data: Option<Rc<ManifestData>>,
+    manifest_loading: bool, // Parsing on a worker thread, see `set_manifest_loading`
     // Store the combined game info, keyed by app_id for easy lookup
     games: Rc<RefCell<HashMap<String, GameInfo>>>,
     search_entry: SearchEntry,
+    search_query: Rc<RefCell<String>>, // Lowercase text of the search entry
+    // Fuzzy score of every matching game by App ID, only while searching
+    search_scores: Rc<RefCell<HashMap<String, i64>>>,
     matcher: Rc<SkimMatcherV2>,
+    favorites_toggle: gtk::ToggleButton,
+    // Prefixes no manifest game matched, listed below the games
+    unmatched: Rc<RefCell<Vec<UnmatchedPrefix>>>,
+    unmatched_section: Box,
+    unmatched_heading: Label,
+    unmatched_list: ListBox,
+}
+
+// App ID held by an item of the games list
+fn item_app_id(item: &glib::Object) -> Option<String> {
+    item.downcast_ref::<glib::BoxedAnyObject>().map(|item| item.borrow::<String>().clone())
+}
+
+// Row title of a game, the characters the search matched in bold
+fn game_title(matcher: &SkimMatcherV2, name: &str, query: &str) -> String {
+    let markup = Some(query)
+        .filter(|query| !query.is_empty())
+        .and_then(|query| search_match::highlight(matcher, name, query))
+        .unwrap_or_else(|| glib::markup_escape_text(name).to_string());
+    format!("🎮 {}", markup)
 }
 
 impl GamesPage {
@@ -59,122 +131,350 @@ impl GamesPage {
         container.set_margin_bottom(12);
 
         // Add title and description like in compatdata_page
-        let header = Label::new(Some("Proton Game Saves"));
+        let header = Label::new(Some(&gettext("Proton Game Saves")));
         header.add_css_class("title-1");
         container.append(&header);
         
-        let description = Label::new(Some("View and manage your Steam game save files"));
+        let description = Label::new(Some(&gettext("View and manage your Steam game save files")));
         description.add_css_class("subtitle-1");
         container.append(&description);
 
         // Add search entry
         let search_entry = SearchEntry::new();
-        search_entry.set_placeholder_text(Some("🔍 Search Games or App IDs..."));
+        search_entry.set_placeholder_text(Some(&gettext("🔍 Search Games or App IDs...")));
         search_entry.set_margin_top(12);
         search_entry.set_margin_bottom(6);
         search_entry.add_css_class("emoji");
-        container.append(&search_entry);
+        search_entry.set_hexpand(true);
 
-        // Match the ScrolledWindow setup from compatdata_page
-        let scroll = ScrolledWindow::new();
-        scroll.set_vexpand(true);
-        scroll.set_hexpand(true);
-        container.append(&scroll);
-        
-        // Create ListBox exactly like in compatdata_page
-        let list_container = ListBox::new();
-        list_container.set_selection_mode(SelectionMode::None);
-        list_container.add_css_class("boxed-list");
-        scroll.set_child(Some(&list_container));
+        let sort_bar = SortBar::new(
+            config.clone(),
+            "games",
+            &[
+                ("name", gettext("Name")),
+                ("size", gettext("Total Size")),
+                ("app_id", gettext("App ID")),
+            ],
+            &search_entry,
+        );
+        let favorites_toggle = gtk::ToggleButton::builder()
+            .icon_name("starred-symbolic")
+            .tooltip_text(gettext("Show Only Favorites"))
+            .valign(Align::Center)
+            .active(config.borrow().view_state("games").favorites_only)
+            .build();
+        favorites_toggle.add_css_class("flat");
 
+        let search_box = Box::new(Orientation::Horizontal, 6);
+        search_box.append(&search_entry);
+        search_box.append(&favorites_toggle);
+        search_box.append(sort_bar.widget());
+        container.append(&search_box);
+
+        let games: Rc<RefCell<HashMap<String, GameInfo>>> = Rc::new(RefCell::new(HashMap::new()));
+        let search_query = Rc::new(RefCell::new(search_entry.text().to_lowercase()));
+        let search_scores: Rc<RefCell<HashMap<String, i64>>> = Rc::new(RefCell::new(HashMap::new()));
         let matcher = Rc::new(SkimMatcherV2::default());
 
+        // Search matches the name or the App ID (see `rescore`), the star
+        // toggle hides everything but favorites
+        let config_clone = config.clone();
+        let search_query_clone = search_query.clone();
+        let search_scores_clone = search_scores.clone();
+        let favorites_toggle_clone = favorites_toggle.clone();
+        let game_filter = gtk::CustomFilter::new(move |item| {
+            let Some(app_id) = item_app_id(item) else {
+                return false;
+            };
+            if favorites_toggle_clone.is_active() && !config_clone.borrow().is_favorite(&app_id) {
+                return false;
+            }
+            search_query_clone.borrow().is_empty() || search_scores_clone.borrow().contains_key(&app_id)
+        });
+
+        // Rows are kept in the order chosen in the sort bar, favorites first.
+        // While searching the closest matches go first, the sort bar decides
+        // between equals.
+        let games_clone = games.clone();
+        let config_clone = config.clone();
+        let sort_bar_clone = sort_bar.clone();
+        let search_scores_clone = search_scores.clone();
+        let game_sorter = gtk::CustomSorter::new(move |a, b| {
+            let games = games_clone.borrow();
+            let (Some(a), Some(b)) = (
+                item_app_id(a).and_then(|app_id| games.get(&app_id)),
+                item_app_id(b).and_then(|app_id| games.get(&app_id)),
+            ) else {
+                return gtk::Ordering::Equal;
+            };
+            let config = config_clone.borrow();
+            let favorites_first = config.is_favorite(&b.app_id).cmp(&config.is_favorite(&a.app_id));
+            let ordering = match sort_bar_clone.key() {
+                "size" => a.total_size_bytes.cmp(&b.total_size_bytes),
+                "app_id" => compatdata::compare_app_ids(&a.app_id, &b.app_id),
+                _ => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+            };
+            let ordering = if sort_bar_clone.descending() { ordering.reverse() } else { ordering };
+            let scores = search_scores_clone.borrow();
+            let relevance = scores.get(&b.app_id).cmp(&scores.get(&a.app_id));
+            favorites_first.then(relevance).then(ordering).into()
+        });
+
+        let game_store = gio::ListStore::new::<glib::BoxedAnyObject>();
+        let filtered_games = gtk::FilterListModel::new(Some(game_store.clone()), Some(game_filter.clone()));
+        let sorted_games = gtk::SortListModel::new(Some(filtered_games), Some(game_sorter.clone()));
+
+        // Each game gets its own boxed list holding its row, built when it
+        // scrolls into view and dropped again when it leaves
+        let bound_rows: Rc<RefCell<HashMap<String, ExpanderRow>>> = Rc::new(RefCell::new(HashMap::new()));
+        let pending_focus: Rc<RefCell<Option<String>>> = Rc::new(RefCell::new(None));
+        let factory = gtk::SignalListItemFactory::new();
+        factory.connect_setup(|_, list_item| {
+            if let Some(list_item) = list_item.downcast_ref::<gtk::ListItem>() {
+                list_item.set_activatable(false);
+                list_item.set_selectable(false);
+            }
+        });
+        let games_clone = games.clone();
+        let config_clone = config.clone();
+        let sorted_games_clone = sorted_games.clone();
+        let bound_rows_clone = bound_rows.clone();
+        let pending_focus_clone = pending_focus.clone();
+        let matcher_clone = matcher.clone();
+        let search_query_clone = search_query.clone();
+        factory.connect_bind(move |_, list_item| {
+            let Some(list_item) = list_item.downcast_ref::<gtk::ListItem>() else {
+                return;
+            };
+            let Some(app_id) = list_item.item().and_then(|item| item_app_id(&item)) else {
+                return;
+            };
+            let games = games_clone.borrow();
+            let Some(game_info) = games.get(&app_id) else {
+                return;
+            };
+            let expander_row = Self::build_game_row(&config_clone, game_info);
+            expander_row.set_title(&game_title(&matcher_clone, &game_info.name, &search_query_clone.borrow()));
+
+            let row_list = ListBox::new();
+            row_list.set_selection_mode(SelectionMode::None);
+            row_list.add_css_class("boxed-list");
+            row_list.append(&expander_row);
+
+            // "Favorites" and "All Games" headers, only while there are favorites
+            let config = config_clone.borrow();
+            let position = list_item.position();
+            let is_favorite = config.is_favorite(&app_id);
+            let before_is_favorite = (position > 0)
+                .then(|| sorted_games_clone.item(position - 1))
+                .flatten()
+                .and_then(|item| item_app_id(&item))
+                .map(|before| config.is_favorite(&before));
+            let title = match before_is_favorite {
+                None if is_favorite => Some(gettext("Favorites")),
+                Some(true) if !is_favorite => Some(gettext("All Games")),
+                _ => None,
+            };
+            let item_box = Box::new(Orientation::Vertical, 0);
+            if let Some(title) = title {
+                let label = Label::new(Some(&title));
+                label.set_halign(Align::Start);
+                label.set_margin_start(12);
+                label.set_margin_top(12);
+                label.set_margin_bottom(6);
+                label.add_css_class("heading");
+                item_box.append(&label);
+            }
+            item_box.append(&row_list);
+            list_item.set_child(Some(&item_box));
+
+            if pending_focus_clone.borrow().as_deref() == Some(app_id.as_str()) {
+                pending_focus_clone.borrow_mut().take();
+                // Focus after the list is done laying out its rows
+                let expander_row = expander_row.clone();
+                glib::idle_add_local_once(move || {
+                    expander_row.grab_focus();
+                });
+            }
+            bound_rows_clone.borrow_mut().insert(app_id, expander_row);
+        });
+        let bound_rows_clone = bound_rows.clone();
+        factory.connect_unbind(move |_, list_item| {
+            let Some(list_item) = list_item.downcast_ref::<gtk::ListItem>() else {
+                return;
+            };
+            // Only if the row wasn't bound to another list item in the meantime
+            if let (Some(app_id), Some(child)) = (list_item.item().and_then(|item| item_app_id(&item)), list_item.child()) {
+                let mut bound_rows = bound_rows_clone.borrow_mut();
+                if bound_rows.get(&app_id).is_some_and(|row| row.is_ancestor(&child)) {
+                    bound_rows.remove(&app_id);
+                }
+            }
+            list_item.set_child(None::<&gtk::Widget>);
+        });
+
+        let list_view = gtk::ListView::new(Some(gtk::NoSelection::new(Some(sorted_games.clone()))), Some(factory));
+        list_view.add_css_class("games-list");
+        keynav::add_expander_keys(&list_view);
+
+        // The list view scrolls on its own, it has to be the scrolled window's child
+        let list_scroll = ScrolledWindow::new();
+        list_scroll.set_vexpand(true);
+        list_scroll.set_hexpand(true);
+        list_scroll.set_child(Some(&list_view));
+        container.append(&list_scroll);
+
+        // Shown instead of the list while there are no games
+        let placeholder = Box::new(Orientation::Vertical, 10);
+        placeholder.set_vexpand(true);
+        placeholder.set_valign(Align::Center);
+        placeholder.set_visible(false);
+        let placeholder_icon = gtk::Image::from_icon_name("dialog-information-symbolic");
+        placeholder_icon.set_icon_size(gtk::IconSize::Large);
+        placeholder_icon.set_margin_bottom(10);
+        let placeholder_label = Label::new(None);
+        placeholder_label.set_wrap(true);
+        placeholder_label.set_justify(gtk::Justification::Center);
+        placeholder_label.set_css_classes(&["title-4", "emoji"]); // Add emoji class
+        let placeholder_sub_label = Label::new(None);
+        placeholder_sub_label.set_wrap(true);
+        placeholder_sub_label.set_justify(gtk::Justification::Center);
+        placeholder_sub_label.set_css_classes(&["dim-label"]);
+        placeholder.append(&placeholder_icon);
+        placeholder.append(&placeholder_label);
+        placeholder.append(&placeholder_sub_label);
+        container.append(&placeholder);
+
+        // Prefixes that matched no game would otherwise just be missing here
+        let unmatched_section = Box::new(Orientation::Vertical, 6);
+        unmatched_section.set_margin_top(12);
+        unmatched_section.set_visible(false);
+        let unmatched_heading = Label::new(None);
+        unmatched_heading.set_halign(Align::Start);
+        unmatched_heading.add_css_class("heading");
+        unmatched_section.append(&unmatched_heading);
+        let unmatched_description = Label::new(Some(&gettext("These prefixes didn't match any game in the manifest. Assign one by hand, or add the save paths yourself.")));
+        unmatched_description.set_halign(Align::Start);
+        unmatched_description.set_wrap(true);
+        unmatched_description.add_css_class("dim-label");
+        unmatched_section.append(&unmatched_description);
+        let unmatched_list = ListBox::new();
+        unmatched_list.set_selection_mode(SelectionMode::None);
+        unmatched_list.add_css_class("boxed-list");
+        // Below the games list now, so it gets a scroller of its own
+        let unmatched_scroll = ScrolledWindow::builder()
+            .hscrollbar_policy(gtk::PolicyType::Never)
+            .propagate_natural_height(true)
+            .max_content_height(240)
+            .child(&unmatched_list)
+            .build();
+        unmatched_section.append(&unmatched_scroll);
+        container.append(&unmatched_section);
+
+        // Typing anywhere on the page goes to the search entry
+        search_entry.set_key_capture_widget(Some(&container));
+
         let page = Self {
             widget: container,
-            list_container,
+            game_store,
+            game_filter,
+            game_sorter,
+            sorted_games,
+            list_view,
+            list_scroll,
+            bound_rows,
+            pending_focus,
+            placeholder,
+            placeholder_label,
+            placeholder_sub_label,
             config,
             manifest_data: None, // Initially no manifest loaded
-            games: Rc::new(RefCell::new(HashMap::new())), // Initialize empty games map
+            manifest_loading: false,
+            games,
             search_entry: search_entry.clone(),
+            search_query,
+            search_scores,
             matcher,
+            favorites_toggle: favorites_toggle.clone(),
+            unmatched: Rc::new(RefCell::new(Vec::new())),
+            unmatched_section,
+            unmatched_heading,
+            unmatched_list,
         };
 
+        let game_filter = page.game_filter.clone();
+        let config_clone = page.config.clone();
+        favorites_toggle.connect_toggled(move |toggle| {
+            game_filter.changed(gtk::FilterChange::Different);
+            let state = ViewState {
+                favorites_only: toggle.is_active(),
+                ..config_clone.borrow().view_state("games")
+            };
+            if let Err(e) = config_clone.borrow_mut().set_view_state("games", state) {
+                eprintln!("Error saving view state of games page: {}", e);
+            }
+        });
+        let game_sorter = page.game_sorter.clone();
+        sort_bar.connect_changed(move || game_sorter.changed(gtk::SorterChange::Different));
+
         // Connect search signal for filtering
-        let list_container_clone = page.list_container.clone();
-        let matcher_clone = page.matcher.clone();
+        let games = page.games.clone();
+        let search_query = page.search_query.clone();
+        let search_scores = page.search_scores.clone();
+        let matcher = page.matcher.clone();
+        let game_filter = page.game_filter.clone();
+        let game_sorter = page.game_sorter.clone();
+        let bound_rows = page.bound_rows.clone();
         page.search_entry.connect_search_changed(move |entry| {
-            let query = entry.text().to_lowercase();
-            Self::filter_game_list(&list_container_clone, &matcher_clone, &query);
+            *search_query.borrow_mut() = entry.text().to_lowercase();
+            Self::rescore(&games.borrow(), &search_query.borrow(), &matcher, &mut search_scores.borrow_mut());
+            game_filter.changed(gtk::FilterChange::Different);
+            game_sorter.changed(gtk::SorterChange::Different);
+            // Rows that stay on screen aren't rebuilt, their titles are updated here
+            let games = games.borrow();
+            for (app_id, row) in bound_rows.borrow().iter() {
+                if let Some(game) = games.get(app_id) {
+                    row.set_title(&game_title(&matcher, &game.name, &search_query.borrow()));
+                }
+            }
         });
 
         page
     }
 
-    // New function to filter the game list based on search query
-    fn filter_game_list(container: &ListBox, matcher: &SkimMatcherV2, query: &str) {
+    // Fuzzy score of each game against the search `query`, by its name or App ID
+    fn rescore(games: &HashMap<String, GameInfo>, query: &str, matcher: &SkimMatcherV2, scores: &mut HashMap<String, i64>) {
+        scores.clear();
         if query.is_empty() {
-            // When query is empty, show all items
-            let mut row = container.first_child();
-            while let Some(child) = row {
-                if let Some(widget) = child.downcast_ref::<gtk::Widget>() {
-                    widget.set_visible(true);
-                }
-                row = child.next_sibling();
-            }
             return;
         }
-
-        // Check each row against the query
-        let mut row = container.first_child();
-        while let Some(child) = row {
-            let visible;
-            
-            if let Some(expander) = child.downcast_ref::<ExpanderRow>() {
-                // Get the title text (game name)
-                let title = expander.title().to_string().to_lowercase();
-                // Get the subtitle text (contains App ID)
-                let subtitle = expander.subtitle().to_string().to_lowercase();
-                
-                // Check if query matches title or subtitle
-                visible = matcher.fuzzy_match(&title, query).is_some() || 
-                          matcher.fuzzy_match(&subtitle, query).is_some();
-            } else {
-                // For placeholder items or labels, show them with an empty query
-                visible = true;
-            }
-            
-            if let Some(widget) = child.downcast_ref::<gtk::Widget>() {
-                widget.set_visible(visible);
-            }
-            
-            row = child.next_sibling();
-        }
+        scores.extend(games.values().filter_map(|game| {
+            let score = search_match::score(matcher, &[&game.name.to_lowercase(), &game.app_id], query)?;
+            Some((game.app_id.clone(), score))
+        }));
     }
 
     pub fn widget(&self) -> &Box {
         &self.widget
     }
 
-    // Method to load/update the manifest data
-    pub fn update_manifest(&mut self) {
-        match manifest::parse_manifest(&self.config.borrow()) {
-            Ok(data) => {
-                println!("DEBUG: Manifest parsed successfully."); // Log success
-                self.manifest_data = Some(Rc::new(data));
-                // After loading, we should refresh the list based on existing compatdata
-                self.refresh_game_list();
-            }
-            Err(e) => {
-                eprintln!("DEBUG: Failed to parse manifest: {}", e);
-                // Optionally show an error message to the user
-                self.manifest_data = None; // Clear manifest data on error
-                self.refresh_game_list(); // Refresh list (will be empty)
-            }
-        }
+    // The manifest is being parsed in the background, show that instead of "not loaded"
+    pub fn set_manifest_loading(&mut self) {
+        self.manifest_loading = true;
+        self.manifest_data = None;
+        self.refresh_game_list();
+    }
+
+    // Method to load/update the manifest data, `None` when parsing it failed
+    pub fn set_manifest(&mut self, data: Option<ManifestData>) {
+        self.manifest_loading = false;
+        self.manifest_data = data.map(Rc::new);
+        // The list is rebuilt from the next scan, until then it shows the placeholder
+        self.refresh_game_list();
     }
 
     // Method to populate the list - Updated signature and logic
-    pub fn populate_games(&self, scanned_prefixes: &[PrefixData]) {
+    pub fn populate_games(&self, scanned_prefixes: &[PrefixData], options: ScanOptions) {
         if self.manifest_data.is_none() {
             println!("Manifest not loaded, cannot populate games list.");
             self.refresh_game_list(); // Update UI (shows placeholder)
@@ -188,21 +488,70 @@ impl GamesPage {
         );
 
         println!(
-            "Populating games list using path matching from {} scanned prefixes...",
+            "Populating games list using Steam IDs and path matching from {} scanned prefixes...",
             scanned_prefixes.len()
         );
 
         let mut games_map = self.games.borrow_mut();
+        // Keep the previous sizes (and screenshot counts) around in case we're asked not to recompute them
+        let previous_sizes: HashMap<(String, String), (u64, usize)> = games_map
+            .values()
+            .flat_map(|game| {
+                game.save_locations.iter().map(move |loc| {
+                    ((game.app_id.clone(), loc.manifest_path.clone()), (loc.size_bytes, loc.screenshots))
+                })
+            })
+            .collect();
         games_map.clear(); // Clear previous entries
+        let mut unmatched = self.unmatched.borrow_mut();
+        unmatched.clear();
 
         // Iterate through prefixes found by the scan
         for prefix_data in scanned_prefixes {
-            // Use the new path-matching function from manifest.rs
-            match manifest::find_game_for_prefix_by_path(&manifest, prefix_data, &config_borrow) {
+            // Hidden on the Compatdata page, e.g. Proton's own prefixes
+            if config_borrow.is_prefix_hidden(&prefix_data.game_id) {
+                continue;
+            }
+            // A game the user assigned to this prefix wins over automatic matching
+            let assigned = config_borrow
+                .game_assignment(&prefix_data.game_id)
+                .and_then(|name| manifest.games.get_key_value(name))
+                .map(|(name, entry)| (name.clone(), entry));
+            // Then the App ID, only games the manifest has no Steam ID for need path matching
+            let matched = assigned
+                .or_else(|| manifest::find_game_for_prefix_by_steam_id(manifest, &prefix_data.game_id))
+                .or_else(|| manifest::find_game_for_prefix_by_path(manifest, prefix_data))
+                .or_else(|| manifest::find_game_for_prefix_by_launcher_ids(manifest, prefix_data))
+                .or_else(|| {
+                    if options.deep_scan {
+                        manifest::find_game_for_prefix_by_existing_paths(manifest, &prefix_data.game_id, &config_borrow)
+                    } else {
+                        None
+                    }
+                })
+                .map(|(name, entry)| (name, entry.clone()));
+            // Custom save paths are added to the matched entry, or make up one of their own
+            let custom_paths = config_borrow.custom_save_paths(&prefix_data.game_id);
+            let custom_files = custom_paths
+                .iter()
+                .map(|path| (path.clone(), manifest::GameFileRule { _tags: None, when: None }));
+            let matched = match matched {
+                Some((name, mut entry)) => {
+                    entry.files.get_or_insert_with(HashMap::new).extend(custom_files);
+                    Some((name, entry))
+                }
+                None if !custom_paths.is_empty() => Some((
+                    gettext_f("Game ID: {}", &[&prefix_data.game_id]),
+                    manifest::GameEntry::with_files(custom_files.collect()),
+                )),
+                None => None,
+            };
+            let customized = config_borrow.game_assignment(&prefix_data.game_id).is_some() || !custom_paths.is_empty();
+            match matched {
                 Some((manifest_game_name, manifest_entry)) => {
                     // Found a matching game entry via path comparison
                     println!(
-                        "  Identified game via path match: '{}' for App ID: {}",
+                        "  Identified game: '{}' for App ID: {}",
                         manifest_game_name, prefix_data.game_id
                     );
 
@@ -217,28 +566,50 @@ impl GamesPage {
 
                     // Proceed to calculate size and add game info
                     let mut game_save_locations: Vec<SaveLocationInfo> = Vec::new();
+                    // Logs and the like the user set to be ignored don't count
+                    let ignored = config_borrow.ignore_patterns_for(&prefix_data.game_id);
                     let mut total_size: u64 = 0;
 
                     // Resolve paths defined in the manifest for this game
                     if let Some(files) = &manifest_entry.files {
                         for (manifest_path_str, rule) in files {
-                            if let Some(resolved_path) = manifest::resolve_manifest_path(
+                            if let Some(resolved_path) = manifest::resolve_manifest_rule(
                                 manifest_path_str,
+                                rule,
+                                &manifest_entry,
                                 &config_borrow,
                                 &prefix_data.game_id,
                             ) {
+                                // Windows paths aren't case-sensitive, look for the folder in any case
+                                let mut case_mismatch = false;
+                                let mut resolved_path = resolved_path;
+                                if !resolved_path.exists() {
+                                    if let Some(found) = fsutil::find_case_insensitive(&resolved_path) {
+                                        println!(
+                                            "  Found {} as {} (different case)",
+                                            resolved_path.display(),
+                                            found.display()
+                                        );
+                                        resolved_path = found;
+                                        case_mismatch = true;
+                                    }
+                                }
+
                                 // Calculate size for this path
                                 let mut current_size: u64 = 0;
+                                let mut screenshot_count = 0;
                                 let exists = resolved_path.exists();
-                                if exists {
-                                    match Self::calculate_path_size(&resolved_path) {
-                                        Ok(size) => current_size = size,
-                                        Err(e) => eprintln!(
-                                            "Error calculating size for {}: {}",
-                                            resolved_path.display(),
-                                            e
-                                        ),
-                                    }
+                                let previous_size = if options.recompute_sizes {
+                                    None
+                                } else {
+                                    previous_sizes.get(&(prefix_data.game_id.clone(), manifest_path_str.clone())).copied()
+                                };
+                                if let Some((size, screenshots)) = previous_size {
+                                    current_size = size;
+                                    screenshot_count = screenshots;
+                                } else if exists {
+                                    screenshot_count = screenshots::count_screenshots(&resolved_path);
+                                    current_size = ignored.disk_usage(&resolved_path);
                                 }
 
                                 let location_info = SaveLocationInfo {
@@ -246,7 +617,9 @@ impl GamesPage {
                                     resolved_path: resolved_path.clone(),
                                     size_bytes: current_size,
                                     exists,
+                                    case_mismatch,
                                     tags: rule._tags.clone(),
+                                    screenshots: screenshot_count,
                                 };
 
                                 total_size += current_size;
@@ -260,15 +633,24 @@ impl GamesPage {
                         }
                     }
 
-                    // TODO: Consider adding registry paths from manifest_entry._registry if relevant
+                    // Games keeping their progress in the registry have no files to show
+                    let registry_keys = registry::manifest_keys(&manifest_entry);
 
-                    if !game_save_locations.is_empty() {
+                    if !game_save_locations.is_empty() || !registry_keys.is_empty() {
                         let game_info = GameInfo {
                             app_id: prefix_data.game_id.clone(),
-                            name: manifest_game_name.clone(),
-                            entry: manifest_entry.clone(),
+                            // A name the user set wins over the manifest's
+                            name: config_borrow
+                                .display_name(&prefix_data.game_id)
+                                .map(str::to_string)
+                                .unwrap_or_else(|| manifest_game_name.clone()),
+                            entry: manifest_entry,
                             save_locations: game_save_locations,
                             total_size_bytes: total_size,
+                            compat_tool: prefix_data.compat_tool.as_ref().map(|tool| tool.label()),
+                            customized,
+                            on_removable_media: config_borrow.is_on_removable_media(&prefix_data._path),
+                            registry_keys,
                         };
                         games_map.insert(prefix_data.game_id.clone(), game_info);
                     } else {
@@ -277,6 +659,7 @@ impl GamesPage {
                             "  Game '{}' identified, but no resolvable save locations found?",
                             manifest_game_name
                         );
+                        unmatched.push(UnmatchedPrefix::from(prefix_data));
                     }
                 }
                 None => {
@@ -285,277 +668,595 @@ impl GamesPage {
                         "  No game identified via path matching for prefix_id: {}",
                         prefix_data.game_id
                     );
+                    unmatched.push(UnmatchedPrefix::from(prefix_data));
                 }
             }
         }
+        drop(unmatched);
 
         println!("DEBUG: Finished iterating through all prefixes."); // Add log after loop
                                                                      // Drop the mutable borrow before calling refresh_game_list
         drop(games_map);
 
         println!(
-            "Finished processing prefixes. Found {} games with manifest entries.",
-            self.games.borrow().len()
+            "Finished processing prefixes. Found {} games with manifest entries, {} prefixes unmatched.",
+            self.games.borrow().len(),
+            self.unmatched.borrow().len()
         );
         self.refresh_game_list(); // Update the UI
     }
 
-    // Clears the list container
-    fn clear_list(&self) {
-        while let Some(child) = self.list_container.first_child() {
-            self.list_container.remove(&child);
+    // Re-group and re-filter after a game was starred or unstarred
+    pub fn refresh_favorites(&self) {
+        self.game_sorter.changed(gtk::SorterChange::Different);
+        self.game_filter.changed(gtk::FilterChange::Different);
+        // The headers depend on the neighbouring rows, rebuild the visible ones
+        let count = self.sorted_games.n_items();
+        self.sorted_games.items_changed(0, count, count);
+    }
+
+    // Games with a save location over its size limit: name, biggest such
+    // location and the limit
+    pub fn over_quota(&self) -> Vec<(String, u64, u64)> {
+        let config = self.config.borrow();
+        let mut over: Vec<(String, u64, u64)> = self
+            .games
+            .borrow()
+            .values()
+            .filter_map(|game| {
+                let quota = config.save_quota_for(&game.app_id)?;
+                let biggest = game.save_locations.iter().filter(|location| location.exists).map(|location| location.size_bytes).max()?;
+                (biggest > quota).then(|| (game.name.clone(), biggest, quota))
+            })
+            .collect();
+        over.sort();
+        over
+    }
+
+    // Number of matched games, for the sidebar badge
+    pub fn is_manifest_loading(&self) -> bool {
+        self.manifest_loading
+    }
+
+    pub fn game_count(&self) -> usize {
+        self.games.borrow().len()
+    }
+
+    // Flatten the matched games into rows for an inventory report
+    pub fn report_entries(&self) -> Vec<ReportEntry> {
+        let games_map = self.games.borrow();
+        let mut sorted_games: Vec<&GameInfo> = games_map.values().collect();
+        sorted_games.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut entries = Vec::new();
+        for game_info in sorted_games {
+            for location in &game_info.save_locations {
+                entries.push(ReportEntry {
+                    game_name: game_info.name.clone(),
+                    app_id: game_info.app_id.clone(),
+                    manifest_path: location.manifest_path.clone(),
+                    save_path: location.resolved_path.display().to_string(),
+                    exists: location.exists,
+                    size_bytes: location.size_bytes,
+                    last_modified: fsutil::last_modified(&location.resolved_path)
+                        .map(report::format_timestamp_utc),
+                });
+            }
         }
+        entries
     }
 
-    // Rebuilds the list container with ExpanderRows from self.games data
-    fn refresh_game_list(&self) {
-        self.clear_list();
-        
+    // Matched games with the save locations that exist, for a full bundle export
+    pub fn export_games(&self) -> Vec<ExportGame> {
         let games_map = self.games.borrow();
+        let mut sorted_games: Vec<&GameInfo> = games_map.values().collect();
+        sorted_games.sort_by(|a, b| a.name.cmp(&b.name));
 
-        if games_map.is_empty() {
-            // Show a placeholder if no games are found or manifest isn't loaded
-            let placeholder_box = Box::new(Orientation::Vertical, 10);
-            placeholder_box.set_vexpand(true);
-            placeholder_box.set_valign(Align::Center);
-
-            let placeholder_icon = gtk::Image::from_icon_name("dialog-information-symbolic");
-            placeholder_icon.set_icon_size(gtk::IconSize::Large);
-            placeholder_icon.set_margin_bottom(10);
-
-            let placeholder_label = Label::new(Some(if self.manifest_data.is_none() {
-                "📋 Manifest Not Loaded" // More consistent styling
-            } else {
-                "🎮 No Games Found" // More consistent styling
-            }));
-            placeholder_label.set_wrap(true);
-            placeholder_label.set_justify(gtk::Justification::Center);
-            placeholder_label.set_css_classes(&["title-4", "emoji"]); // Add emoji class
-
-            let placeholder_sub_label = Label::new(Some(if self.manifest_data.is_none() {
-                "Download the manifest in Settings to see game data."
-            } else {
-                "Scan results did not match any games in the manifest.\nTry refreshing or check Steam directory setting."
-            }));
-            placeholder_sub_label.set_wrap(true);
-            placeholder_sub_label.set_justify(gtk::Justification::Center);
-            placeholder_sub_label.set_css_classes(&["dim-label"]);
-
-            placeholder_box.append(&placeholder_icon);
-            placeholder_box.append(&placeholder_label);
-            placeholder_box.append(&placeholder_sub_label);
-
-            let row = gtk::ListBoxRow::new();
-            row.set_selectable(false);
-            row.set_child(Some(&placeholder_box));
-            self.list_container.append(&row);
-            return;
+        sorted_games
+            .into_iter()
+            .map(|game_info| ExportGame {
+                app_id: game_info.app_id.clone(),
+                name: game_info.name.clone(),
+                locations: game_info
+                    .save_locations
+                    .iter()
+                    .filter(|location| location.exists)
+                    .map(|location| ExportLocation {
+                        manifest_path: location.manifest_path.clone(),
+                        path: location.resolved_path.clone(),
+                        size_bytes: location.size_bytes,
+                    })
+                    .collect(),
+                registry_keys: game_info.registry_keys.clone(),
+            })
+            .filter(|game| !game.locations.is_empty() || !game.registry_keys.is_empty())
+            .collect()
+    }
+
+    // Save size of every matched game, for the disk space page
+    pub fn save_sizes(&self) -> Vec<SaveSize> {
+        self.games
+            .borrow()
+            .values()
+            .map(|game_info| SaveSize {
+                app_id: game_info.app_id.clone(),
+                name: game_info.name.clone(),
+                size: game_info.total_size_bytes,
+            })
+            .collect()
+    }
+
+    // Size and newest file of every matched game's saves, to tell which ones
+    // were written since the last scan
+    pub fn save_states(&self) -> Vec<GameSaves> {
+        self.games
+            .borrow()
+            .values()
+            .map(|game_info| GameSaves {
+                app_id: game_info.app_id.clone(),
+                name: game_info.name.clone(),
+                state: SaveState {
+                    bytes: game_info.total_size_bytes,
+                    modified: game_info
+                        .save_locations
+                        .iter()
+                        .filter(|location| location.exists)
+                        .filter_map(|location| fsutil::last_modified(&location.resolved_path))
+                        .max(),
+                },
+            })
+            .collect()
+    }
+
+    // Clear the search and favorites filter, then expand and focus the row of
+    // `app_id` (scrolling it into view). False if no game has that App ID.
+    pub fn reveal(&self, app_id: &str) -> bool {
+        if !self.games.borrow().contains_key(app_id) {
+            return false;
+        }
+        self.search_entry.set_text("");
+        self.search_query.borrow_mut().clear();
+        self.search_scores.borrow_mut().clear();
+        self.game_filter.changed(gtk::FilterChange::LessStrict);
+        self.game_sorter.changed(gtk::SorterChange::Different);
+        if !self.config.borrow().is_favorite(app_id) {
+            self.favorites_toggle.set_active(false);
+        }
+        // Rows are built as they scroll into view, so the open state goes
+        // through the config like one the user chose
+        if let Err(e) = self.config.borrow_mut().set_row_expanded("games", app_id, true) {
+            eprintln!("Error saving open rows of games page: {}", e);
+        }
+        if let Some(row) = self.bound_rows.borrow().get(app_id) {
+            row.set_expanded(true);
+            row.grab_focus();
+            return true;
+        }
+        let position = (0..self.sorted_games.n_items())
+            .find(|&position| self.sorted_games.item(position).and_then(|item| item_app_id(&item)).as_deref() == Some(app_id));
+        if let Some(position) = position {
+            *self.pending_focus.borrow_mut() = Some(app_id.to_string());
+            if let Err(e) = self.list_view.activate_action("list.scroll-to-item", Some(&position.to_variant())) {
+                eprintln!("Failed to scroll to {}: {}", app_id, e);
+            }
         }
+        true
+    }
 
-        // Sort games by name for consistent display
+    // Every matched game with all its save locations, for the recovery wizard
+    pub fn recover_games(&self) -> Vec<RecoverGame> {
+        let games_map = self.games.borrow();
         let mut sorted_games: Vec<&GameInfo> = games_map.values().collect();
         sorted_games.sort_by(|a, b| a.name.cmp(&b.name));
 
-        // Create ExpanderRow for each game
-        for game_info in sorted_games {
-            let total_size_formatted = format_size(game_info.total_size_bytes, DECIMAL);
-            let subtitle = format!(
-                "App ID: {} | Total Size: {}",
-                game_info.app_id, total_size_formatted
-            );
-
-            let expander_row = ExpanderRow::builder()
-                .title(&format!("🎮 {}", game_info.name))
-                .subtitle(&subtitle)
-                .show_enable_switch(false)
-                .build();
-                
-            // Add styling for consistent appearance with compatdata_page
-            expander_row.add_css_class("activatable");
-            expander_row.add_css_class("emoji");
-            expander_row.set_margin_top(2);
-            expander_row.set_margin_bottom(2);
+        sorted_games
+            .into_iter()
+            .map(|game_info| RecoverGame {
+                app_id: game_info.app_id.clone(),
+                name: game_info.name.clone(),
+                locations: game_info
+                    .save_locations
+                    .iter()
+                    .map(|location| location.resolved_path.clone())
+                    .collect(),
+            })
+            .collect()
+    }
+
+    // Replaces the items of the games list with the games in self.games
+    pub fn refresh_game_list(&self) {
+        self.refresh_unmatched_list();
+        
+        let games_map = self.games.borrow();
+        Self::rescore(&games_map, &self.search_query.borrow(), &self.matcher, &mut self.search_scores.borrow_mut());
+        let app_ids: Vec<glib::BoxedAnyObject> = games_map.keys().map(|app_id| glib::BoxedAnyObject::new(app_id.clone())).collect();
+        drop(games_map);
+        self.game_store.splice(0, self.game_store.n_items(), &app_ids);
+
+        // Show a placeholder if no games are found or manifest isn't loaded
+        self.list_scroll.set_visible(!app_ids.is_empty());
+        self.placeholder.set_visible(app_ids.is_empty());
+        self.placeholder_label.set_label(&if self.manifest_loading {
+            gettext("📋 Loading Manifest…")
+        } else if self.manifest_data.is_none() {
+            gettext("📋 Manifest Not Loaded") // More consistent styling
+        } else {
+            gettext("🎮 No Games Found") // More consistent styling
+        });
+        self.placeholder_sub_label.set_label(&if self.manifest_loading {
+            gettext("Reading the game database, the first start after a download takes a moment.")
+        } else if self.manifest_data.is_none() {
+            gettext("Download the manifest in Settings to see game data.")
+        } else {
+            gettext("Scan results did not match any games in the manifest.\nTry refreshing or check Steam directory setting.")
+        });
+        println!("Games list UI refreshed with {} games.", app_ids.len());
+    }
+
+    // The ExpanderRow of one game, with its save locations inside
+    fn build_game_row(config: &Rc<RefCell<Config>>, game_info: &GameInfo) -> ExpanderRow {
+        let total_size_formatted = format_size(game_info.total_size_bytes, DECIMAL);
+        let mut subtitle = gettext_f(
+            "App ID: {} | Total Size: {}",
+            &[&game_info.app_id, &total_size_formatted],
+        );
+        if let Some(tool) = &game_info.compat_tool {
+            subtitle.push_str(&format!(" | {}", tool));
+        }
+        if game_info.on_removable_media {
+            subtitle.push_str(&format!(" | {}", gettext("💾 On SD Card")));
+        }
+
+        let expander_row = ExpanderRow::builder()
+            .title(format!("🎮 {}", glib::markup_escape_text(&game_info.name)))
+            .subtitle(&subtitle)
+            .show_enable_switch(false)
+            .build();
+        sort_bar::remember_expanded(&expander_row, config, "games", &game_info.app_id);
             
-            // Set widget name for search filtering
-            let mut searchable_text = format!("{} {}", game_info.name, game_info.app_id);
-            for location in &game_info.save_locations {
-                if let Some(tags) = &location.tags {
-                    for tag in tags {
-                        searchable_text.push_str(&format!(" {}", tag));
-                    }
-                }
-                searchable_text.push_str(&format!(" {}", location.manifest_path));
-            }
-            expander_row.set_widget_name(&searchable_text);
-
-            // --- Create content for the expanded view ---
-            let expanded_content_box = Box::new(Orientation::Vertical, 6);
-            expanded_content_box.set_margin_start(12);
-            expanded_content_box.set_margin_end(12);
-            expanded_content_box.set_margin_top(6);
-            expanded_content_box.set_margin_bottom(6);
-            expander_row.add_row(&expanded_content_box); // Use add_row for nested content
-
-            if game_info.save_locations.is_empty() {
-                let no_saves_label =
-                    Label::new(Some("🤷 No save locations defined or found"));
-                no_saves_label.set_halign(Align::Center);
-                no_saves_label.set_css_classes(&["dim-label", "emoji"]);
-                no_saves_label.set_margin_top(12);
-                no_saves_label.set_margin_bottom(12);
-                expanded_content_box.append(&no_saves_label);
-            } else {
-                // Create a ListBox for the locations within the ExpanderRow
-                let location_list_box = ListBox::new();
-                location_list_box.set_selection_mode(SelectionMode::None);
-                location_list_box.set_css_classes(&["boxed-list", "content-list"]); // Better styling
-                location_list_box.set_margin_top(6);
-                location_list_box.set_margin_bottom(6);
-                expanded_content_box.append(&location_list_box);
-
-                for location in &game_info.save_locations {
-                    // Get path and size for the location
-                    let path_display = location.resolved_path.display().to_string();
-                    let size_formatted = format_size(location.size_bytes, DECIMAL);
-
-                    // --- Create Title ---
-                    // Start with tags if available and not empty, otherwise use manifest path
-                    let title_base = location.tags
-                        .as_ref()
-                        .filter(|tags| !tags.is_empty())
-                        .map(|tags| tags.join(", "))
-                        .unwrap_or_else(|| location.manifest_path.clone());
-                    // Append size
-                    let final_title = format!("📁 {} ({})", title_base, size_formatted);
-                    
-                    // --- Create Subtitle (Abbreviated Path) ---
-                    let _app_id = &game_info.app_id; // Get app_id from the outer loop's game_info
-                    let resolved_path = &location.resolved_path;
-                    let config_borrow = self.config.borrow(); // Borrow config to get compatdata path
-                    let compatdata_base_path = config_borrow.compatdata_path();
-
-                    // Start with full path as fallback
-                    let mut subtitle_path_str = path_display.clone();
-
-                    // Try to create a shorter path display - Make it even shorter and more concise
-                    if let Ok(stripped_path) = resolved_path.strip_prefix(compatdata_base_path) {
-                        subtitle_path_str = format!("📂 [compatdata]/{}", stripped_path.display());
-                    } else {
-                        // If not in compatdata, just use the last 2-3 components of the path
-                        let path_components: Vec<_> = resolved_path.components().collect();
-                        if path_components.len() > 2 {
-                            let last_components = &path_components[path_components.len() - 2..];
-                            let short_path = last_components.iter()
-                                .map(|c| c.as_os_str().to_string_lossy())
-                                .collect::<Vec<_>>()
-                                .join("/");
-                            subtitle_path_str = format!("📂 …/{}", short_path);
-                        }
-                    }
+        let rename_button = Button::from_icon_name("document-edit-symbolic");
+        rename_button.set_tooltip_text(Some(&gettext("Rename Game")));
+        rename_button.set_valign(Align::Center);
+        rename_button.add_css_class("flat");
+        let config_clone = config.clone();
+        let app_id = game_info.app_id.clone();
+        let name = game_info.name.clone();
+        rename_button.connect_clicked(move |button| {
+            let parent = button.root().and_downcast::<gtk::Window>();
+            rename_dialog::show(parent.as_ref(), config_clone.clone(), &app_id, &name);
+        });
+        expander_row.add_suffix(&rename_button);
+        expander_row.add_suffix(&app_id_button::new(&game_info.app_id));
 
-                    // Add "Path not found" to subtitle if needed
-                    if !location.exists {
-                        subtitle_path_str = format!("{} | ⚠️ Path not found", subtitle_path_str);
-                    }
-                    
-                    // Make subtitle more visible with a prefix
-                    if subtitle_path_str.is_empty() {
-                        subtitle_path_str = String::from("No path information available");
-                    }
-                    
-                    // Escape special XML characters to prevent markup parsing errors
-                    let escaped_subtitle = subtitle_path_str.replace("<", "&lt;").replace(">", "&gt;").replace("&", "&amp;");
-                    let short_subtitle = format!("Path: {}", escaped_subtitle);
-                    
-                    // Create a simple ActionRow first
-                    let row = ActionRow::new();
-                    
-                    // Set properties after creation - sometimes this works better
-                    row.set_title(&final_title);
-                    row.set_subtitle(&short_subtitle);
-                    row.set_subtitle_lines(3);
-                    row.add_css_class("activatable");
-                    row.add_css_class("emoji");
-                    
-                    // Add dim-label class if path doesn't exist
-                    if !location.exists {
-                        row.add_css_class("dim-label"); 
-                    }
-                    
-                    // Add "Open Folder" button if the path exists
-                    if location.exists {
-                        let open_button = Button::from_icon_name("folder-open-symbolic");
-                        open_button.set_tooltip_text(Some("Open Folder"));
-                        open_button.set_valign(Align::Center);
-                        let folder_path = location.resolved_path.clone(); // Clone path for closure
-                        open_button.connect_clicked(move |_| {
-                            match Self::open_folder(&folder_path) {
-                                Ok(_) => println!("Opened folder: {}", folder_path.display()),
-                                Err(e) => eprintln!(
-                                    "Failed to open folder {}: {}",
-                                    folder_path.display(),
-                                    e
-                                ),
-                            }
-                        });
-                        row.add_suffix(&open_button);
-                        row.set_activatable_widget(Some(&open_button)); // Allow activating row clicks button
-                    } else { 
-                        // Add icon to indicate path not found
-                        let warning_icon = gtk::Image::from_icon_name("dialog-warning-symbolic");
-                        warning_icon.set_tooltip_text(Some("Path not found"));
-                        warning_icon.set_valign(Align::Center);
-                        warning_icon.add_css_class("warning");
-                        row.add_suffix(&warning_icon);
-                   }
-
-                    location_list_box.append(&row);
+        if game_info.customized {
+            let forget_button = Button::from_icon_name("edit-clear-symbolic");
+            forget_button.set_tooltip_text(Some(&gettext("Forget Assigned Game and Custom Paths")));
+            forget_button.set_valign(Align::Center);
+            forget_button.add_css_class("flat");
+            let config_clone = config.clone();
+            let app_id = game_info.app_id.clone();
+            forget_button.connect_clicked(move |_| {
+                let mut config = config_clone.borrow_mut();
+                let result = config
+                    .set_game_assignment(&app_id, None)
+                    .and_then(|_| config.set_custom_save_paths(&app_id, Vec::new()));
+                if let Err(e) = result {
+                    eprintln!("Error forgetting assignment of {}: {}", app_id, e);
                 }
+            });
+            expander_row.add_suffix(&forget_button);
+        }
+
+        // Paths added by hand may be worth sending upstream
+        let custom_paths = config.borrow().custom_save_paths(&game_info.app_id).to_vec();
+        if !custom_paths.is_empty() {
+            let contribute_button = Button::from_icon_name("mail-send-symbolic");
+            contribute_button.set_tooltip_text(Some(&gettext("Contribute Save Paths to the Manifest")));
+            contribute_button.set_valign(Align::Center);
+            contribute_button.add_css_class("flat");
+            let app_id = game_info.app_id.clone();
+            let name = contribute_dialog::initial_name(&game_info.name, &app_id);
+            contribute_button.connect_clicked(move |button| {
+                let parent = button.root().and_downcast::<gtk::Window>();
+                contribute_dialog::show(parent.as_ref(), name.as_deref(), &app_id, custom_paths.clone());
+            });
+            expander_row.add_suffix(&contribute_button);
+        }
+
+        let is_favorite = config.borrow().is_favorite(&game_info.app_id);
+        let favorite_button = gtk::ToggleButton::builder()
+            .icon_name(if is_favorite { "starred-symbolic" } else { "non-starred-symbolic" })
+            .tooltip_text(if is_favorite { gettext("Remove from Favorites") } else { gettext("Add to Favorites") })
+            .active(is_favorite)
+            .valign(Align::Center)
+            .build();
+        favorite_button.add_css_class("flat");
+        let config_clone = config.clone();
+        let app_id = game_info.app_id.clone();
+        favorite_button.connect_toggled(move |button| {
+            let favorite = button.is_active();
+            button.set_icon_name(if favorite { "starred-symbolic" } else { "non-starred-symbolic" });
+            button.set_tooltip_text(Some(&if favorite { gettext("Remove from Favorites") } else { gettext("Add to Favorites") }));
+            if let Err(e) = config_clone.borrow_mut().set_favorite(&app_id, favorite) {
+                eprintln!("Error saving favorite {}: {}", app_id, e);
             }
+        });
+        expander_row.add_prefix(&favorite_button);
 
-            self.list_container.append(&expander_row);
+        // Games using the Steam Cloud API have a second copy of their saves in userdata
+        let cloud_files = cloud::list_cloud_files(&config.borrow(), &game_info.app_id);
+        if !cloud_files.is_empty() {
+            let cloud_button = Button::from_icon_name("weather-overcast-symbolic");
+            cloud_button.set_tooltip_text(Some(&gettext("Steam Cloud Copies")));
+            cloud_button.set_valign(Align::Center);
+            cloud_button.add_css_class("flat");
+            let config_clone = config.clone();
+            let app_id = game_info.app_id.clone();
+            let name = game_info.name.clone();
+            let targets: Vec<PathBuf> = game_info
+                .save_locations
+                .iter()
+                .filter(|location| location.resolved_path.is_dir())
+                .map(|location| location.resolved_path.clone())
+                .collect();
+            cloud_button.connect_clicked(move |button| {
+                let parent = button.root().and_downcast::<gtk::Window>();
+                // Re-read so the list matches what Steam has synced since the last refresh
+                let files = cloud::list_cloud_files(&config_clone.borrow(), &app_id);
+                cloud_dialog::show(parent.as_ref(), config_clone.clone(), &app_id, &name, files, targets.clone());
+            });
+            expander_row.add_suffix(&cloud_button);
         }
 
-        println!("Games list UI refreshed with ExpanderRows.");
-    }
+        // Drop a folder or archive onto the row to import it as this game's saves
+        let targets: Vec<PathBuf> = game_info.save_locations.iter().map(|l| l.resolved_path.clone()).collect();
+        import_dialog::attach_drop_target(&expander_row, config.clone(), &game_info.app_id, &game_info.name, targets);
 
-    // Helper function to calculate directory size
-    fn calculate_path_size(path: &PathBuf) -> Result<u64, std::io::Error> {
-        let mut total_size = 0;
-        if path.is_file() {
-            total_size = fs::metadata(path)?.len();
-        } else if path.is_dir() {
-            for entry in fs::read_dir(path)? {
-                let entry = entry?;
-                let entry_path = entry.path();
-                if entry_path.is_file() {
-                    // Check if it's a symlink before getting metadata
-                    if !entry_path.is_symlink() {
-                        match fs::metadata(&entry_path) {
-                            Ok(metadata) => total_size += metadata.len(),
-                            Err(e) => eprintln!(
-                                "Failed to get metadata for file {}: {}",
-                                entry_path.display(),
-                                e
-                            ), // Log error but continue
-                        }
+        // Add styling for consistent appearance with compatdata_page
+        expander_row.add_css_class("activatable");
+        expander_row.add_css_class("emoji");
+        expander_row.set_margin_top(2);
+        expander_row.set_margin_bottom(2);
+        
+        // --- Create content for the expanded view ---
+        let expanded_content_box = Box::new(Orientation::Vertical, 6);
+        // Margins come from styles/app.css, they collapse in narrow windows
+        expanded_content_box.add_css_class("expander-content");
+        expander_row.add_row(&expanded_content_box); // Use add_row for nested content
+
+        let quota = config.borrow().save_quota_for(&game_info.app_id);
+        let over_quota = |location: &SaveLocationInfo| location.exists && quota.is_some_and(|quota| location.size_bytes > quota);
+        if game_info.save_locations.iter().any(over_quota) {
+            let quota_icon = gtk::Image::from_icon_name("dialog-warning-symbolic");
+            quota_icon.set_tooltip_text(Some(&gettext_f("Saves over the size limit of {}", &[&format_size(quota.unwrap_or_default(), DECIMAL)])));
+            quota_icon.set_valign(Align::Center);
+            quota_icon.add_css_class("warning");
+            expander_row.add_suffix(&quota_icon);
+        }
+
+        if game_info.save_locations.is_empty() && game_info.registry_keys.is_empty() {
+            let no_saves_label =
+                Label::new(Some(&gettext("🤷 No save locations defined or found")));
+            no_saves_label.set_halign(Align::Center);
+            no_saves_label.set_css_classes(&["dim-label", "emoji"]);
+            no_saves_label.set_margin_top(12);
+            no_saves_label.set_margin_bottom(12);
+            expanded_content_box.append(&no_saves_label);
+        } else {
+            // Create a ListBox for the locations within the ExpanderRow
+            let location_list_box = ListBox::new();
+            location_list_box.set_selection_mode(SelectionMode::None);
+            location_list_box.set_css_classes(&["boxed-list", "content-list"]); // Better styling
+            location_list_box.set_margin_top(6);
+            location_list_box.set_margin_bottom(6);
+            expanded_content_box.append(&location_list_box);
+
+            for location in &game_info.save_locations {
+                // Get path and size for the location
+                let path_display = location.resolved_path.display().to_string();
+                let size_formatted = format_size(location.size_bytes, DECIMAL);
+
+                // --- Create Title ---
+                // Start with tags if available and not empty, otherwise use manifest path
+                let title_base = location.tags
+                    .as_ref()
+                    .filter(|tags| !tags.is_empty())
+                    .map(|tags| tags.join(", "))
+                    .unwrap_or_else(|| location.manifest_path.clone());
+                // Append size
+                let final_title = format!("📁 {} ({})", title_base, size_formatted);
+                
+                // --- Create Subtitle (Abbreviated Path) ---
+                let _app_id = &game_info.app_id; // Get app_id from the outer loop's game_info
+                let resolved_path = &location.resolved_path;
+                let config_borrow = config.borrow(); // Borrow config to get compatdata paths
+                let compatdata_paths = config_borrow.compatdata_paths();
+
+                // Start with full path as fallback
+                let mut subtitle_path_str = path_display.clone();
+
+                // Try to create a shorter path display - Make it even shorter and more concise
+                if let Some(stripped_path) = compatdata_paths.iter().find_map(|base| resolved_path.strip_prefix(base).ok()) {
+                    subtitle_path_str = format!("📂 [compatdata]/{}", stripped_path.display());
+                } else {
+                    // If not in compatdata, just use the last 2-3 components of the path
+                    let path_components: Vec<_> = resolved_path.components().collect();
+                    if path_components.len() > 2 {
+                        let last_components = &path_components[path_components.len() - 2..];
+                        let short_path = last_components.iter()
+                            .map(|c| c.as_os_str().to_string_lossy())
+                            .collect::<Vec<_>>()
+                            .join("/");
+                        subtitle_path_str = format!("📂 …/{}", short_path);
                     }
-                } else if entry_path.is_dir() {
-                    // Avoid infinite loops with symlinks, and recursively sum size
-                    if !entry_path.is_symlink() {
-                        match Self::calculate_path_size(&entry_path) {
-                            Ok(subdir_size) => total_size += subdir_size,
+                }
+
+                // Add "Path not found" to subtitle if needed
+                if !location.exists {
+                    subtitle_path_str = gettext_f("{} | ⚠️ Path not found", &[&subtitle_path_str]);
+                } else if location.case_mismatch {
+                    subtitle_path_str = gettext_f("{} | ⚠️ Case differs from the manifest", &[&subtitle_path_str]);
+                }
+                
+                // Make subtitle more visible with a prefix
+                if subtitle_path_str.is_empty() {
+                    subtitle_path_str = gettext("No path information available");
+                }
+                
+                // Escape special XML characters to prevent markup parsing errors
+                let escaped_subtitle = subtitle_path_str.replace("<", "&lt;").replace(">", "&gt;").replace("&", "&amp;");
+                let short_subtitle = gettext_f("Path: {}", &[&escaped_subtitle]);
+                
+                // Create a simple ActionRow first
+                let row = ActionRow::new();
+                
+                // Set properties after creation - sometimes this works better
+                row.set_title(&final_title);
+                row.set_subtitle(&short_subtitle);
+                row.set_subtitle_lines(3);
+                row.add_css_class("activatable");
+                row.add_css_class("emoji");
+                
+                // Add dim-label class if path doesn't exist
+                if !location.exists {
+                    row.add_css_class("dim-label"); 
+                }
+                // Harmless for the game, but scripts or other tools using the manifest's spelling won't find it
+                if location.case_mismatch {
+                    row.set_tooltip_text(Some(&gettext_f(
+                        "The manifest names this {}, the folder on disk is spelled in a different case",
+                        &[&location.manifest_path],
+                    )));
+                }
+                
+                // Add "Open Folder" button if the path exists
+                if location.exists {
+                    let open_button = Button::from_icon_name("folder-open-symbolic");
+                    open_button.set_tooltip_text(Some(&gettext("Open Folder")));
+                    open_button.set_valign(Align::Center);
+                    let folder_path = location.resolved_path.clone(); // Clone path for closure
+                    open_button.connect_clicked(move |_| {
+                        match Self::open_folder(&folder_path) {
+                            Ok(_) => println!("Opened folder: {}", folder_path.display()),
                             Err(e) => eprintln!(
-                                "Failed to get size for subdir {}: {}",
-                                entry_path.display(),
+                                "Failed to open folder {}: {}",
+                                folder_path.display(),
                                 e
-                            ), // Log error but continue
+                            ),
                         }
-                    }
+                    });
+                    row.add_suffix(&open_button);
+                    row.set_activatable_widget(Some(&open_button)); // Allow activating row clicks button
+                } else { 
+                    // Add icon to indicate path not found
+                    let warning_icon = gtk::Image::from_icon_name("dialog-warning-symbolic");
+                    warning_icon.set_tooltip_text(Some(&gettext("Path not found")));
+                    warning_icon.set_valign(Align::Center);
+                    warning_icon.add_css_class("warning");
+                    row.add_suffix(&warning_icon);
+               }
+
+                if location.exists && location.screenshots >= screenshots::MIN_SCREENSHOTS {
+                    let gallery_button = Button::from_icon_name("image-x-generic-symbolic");
+                    gallery_button.set_tooltip_text(Some(&gettext_f("{} Screenshots", &[&location.screenshots.to_string()])));
+                    gallery_button.set_valign(Align::Center);
+                    gallery_button.add_css_class("flat");
+                    let name = game_info.name.clone();
+                    let path = location.resolved_path.clone();
+                    gallery_button.connect_clicked(move |button| {
+                        let parent = button.root().and_downcast::<gtk::Window>();
+                        screenshot_gallery::show(parent.as_ref(), &name, path.clone());
+                    });
+                    row.add_suffix(&gallery_button);
+                }
+
+                if over_quota(location) {
+                    let quota_button = Button::from_icon_name("dialog-warning-symbolic");
+                    quota_button.set_tooltip_text(Some(&gettext_f("Over the size limit of {}", &[&format_size(quota.unwrap_or_default(), DECIMAL)])));
+                    quota_button.set_valign(Align::Center);
+                    quota_button.add_css_class("flat");
+                    quota_button.add_css_class("warning");
+                    let config_clone = config.clone();
+                    let app_id = game_info.app_id.clone();
+                    let name = game_info.name.clone();
+                    let path = location.resolved_path.clone();
+                    let size = location.size_bytes;
+                    quota_button.connect_clicked(move |button| {
+                        let parent = button.root().and_downcast::<gtk::Window>();
+                        quota_dialog::show(parent.as_ref(), config_clone.clone(), &app_id, &name, path.clone(), size);
+                    });
+                    row.add_suffix(&quota_button);
+                }
+
+                // Copy path works even for missing paths, the other actions get disabled
+                if let Some(prefix_path) = location_menu::prefix_for(&compatdata_paths, &location.resolved_path) {
+                    location_menu::attach(&row, location.resolved_path.clone(), prefix_path, config, true);
                 }
+
+                location_list_box.append(&row);
+            }
+
+            if !game_info.registry_keys.is_empty() {
+                location_list_box.append(&registry_dialog::build_row(config, &game_info.app_id, &game_info.name, &game_info.registry_keys));
             }
+            if !game_info.save_locations.is_empty() {
+                location_list_box.append(&ignore_dialog::build_row(config, &game_info.app_id, &game_info.name));
+                let locations = game_info.save_locations.iter().filter(|location| location.exists).map(|location| location.resolved_path.clone()).collect();
+                location_list_box.append(&trim_dialog::build_row(&game_info.app_id, &game_info.name, locations));
+            }
+        }
+
+        expander_row
+    }
+
+    // Rows for the prefixes that matched no game, with buttons to fix that
+    fn refresh_unmatched_list(&self) {
+        while let Some(child) = self.unmatched_list.first_child() {
+            self.unmatched_list.remove(&child);
+        }
+        let unmatched = self.unmatched.borrow();
+        // Without a manifest nothing can match, the placeholder says so already
+        let Some(manifest) = self.manifest_data.clone() else {
+            self.unmatched_section.set_visible(false);
+            return;
+        };
+        self.unmatched_section.set_visible(!unmatched.is_empty());
+        self.unmatched_heading.set_label(&gettext_f("Unmatched Prefixes ({})", &[&unmatched.len().to_string()]));
+
+        let config = self.config.borrow();
+        for prefix in unmatched.iter() {
+            let title = match config.display_name(&prefix.game_id) {
+                Some(name) => format!("{} ({})", name, prefix.game_id),
+                None => gettext_f("Game ID: {}", &[&prefix.game_id]),
+            };
+            let row = ActionRow::builder()
+                .title(glib::markup_escape_text(&title).as_str())
+                .subtitle(prefix.compat_tool.as_deref().unwrap_or_default())
+                .build();
+
+            let assign_button = Button::with_label(&gettext("Assign Game…"));
+            assign_button.set_valign(Align::Center);
+            let config_clone = self.config.clone();
+            let game_id = prefix.game_id.clone();
+            let manifest = manifest.clone();
+            assign_button.connect_clicked(move |button| {
+                let parent = button.root().and_downcast::<gtk::Window>();
+                assign_dialog::show_assign(parent.as_ref(), config_clone.clone(), &game_id, manifest.clone());
+            });
+            row.add_suffix(&assign_button);
+
+            let path_button = Button::with_label(&gettext("Add Save Path…"));
+            path_button.set_valign(Align::Center);
+            let config_clone = self.config.clone();
+            let game_id = prefix.game_id.clone();
+            path_button.connect_clicked(move |button| {
+                let parent = button.root().and_downcast::<gtk::Window>();
+                assign_dialog::show_custom_path(parent.as_ref(), config_clone.clone(), &game_id);
+            });
+            row.add_suffix(&path_button);
+
+            self.unmatched_list.append(&row);
         }
-        Ok(total_size)
     }
 
     // Helper function to open a folder in the default file manager
@@ -0,0 +1,83 @@
+use adw::prelude::*;
+use adw::{ActionRow, EntryRow, MessageDialog};
+use gtk::{glib, Align, Button};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::config::Config;
+use crate::i18n::{gettext, gettext_f};
+use crate::ui::error_dialog;
+
+// Row below a game's save locations listing what's left out of them, with a
+// button to change the game's own patterns
+pub fn build_row(config: &Rc<RefCell<Config>>, app_id: &str, name: &str) -> ActionRow {
+    let (global, own) = {
+        let config = config.borrow();
+        (config.ignore_patterns().to_vec(), config.game_ignore_patterns(app_id).to_vec())
+    };
+    let subtitle = match (global.is_empty(), own.is_empty()) {
+        (true, true) => gettext("Nothing, every file is counted and backed up"),
+        (false, true) => global.join(", "),
+        (true, false) => gettext_f("{} (this game only)", &[&own.join(", ")]),
+        (false, false) => gettext_f("{}, and for this game {}", &[&global.join(", "), &own.join(", ")]),
+    };
+    let row = ActionRow::builder()
+        .title(gettext("🙈 Ignored Files"))
+        .subtitle(glib::markup_escape_text(&subtitle).as_str())
+        .subtitle_lines(2)
+        .build();
+    row.add_css_class("emoji");
+
+    let edit_button = Button::from_icon_name("document-edit-symbolic");
+    edit_button.set_tooltip_text(Some(&gettext("Edit Ignored Files for This Game")));
+    edit_button.set_valign(Align::Center);
+    edit_button.add_css_class("flat");
+    let config = config.clone();
+    let app_id = app_id.to_string();
+    let name = name.to_string();
+    edit_button.connect_clicked(move |button| {
+        let parent = button.root().and_downcast::<gtk::Window>();
+        show(parent.as_ref(), config.clone(), &app_id, &name);
+    });
+    row.add_suffix(&edit_button);
+    row.set_activatable_widget(Some(&edit_button));
+    row
+}
+
+// Patterns for one game, on top of the global ones from Settings
+pub fn show(parent: Option<&gtk::Window>, config: Rc<RefCell<Config>>, app_id: &str, name: &str) {
+    let dialog = MessageDialog::builder()
+        .heading(gettext_f("Ignored Files of {}", &[name]))
+        .body(gettext(
+            "Files matching these patterns are left out of sizes, safety snapshots and exported bundles. A pattern without a slash matches names anywhere (*.log), one with a slash starts at the top of the save folder (CrashDumps/**). The patterns in Settings apply to every game as well.",
+        ))
+        .modal(true)
+        .build();
+    dialog.set_transient_for(parent);
+
+    let patterns_row = EntryRow::builder()
+        .title(gettext("Patterns (comma separated)"))
+        .text(config.borrow().game_ignore_patterns(app_id).join(", "))
+        .build();
+    let list = gtk::ListBox::new();
+    list.set_selection_mode(gtk::SelectionMode::None);
+    list.add_css_class("boxed-list");
+    list.append(&patterns_row);
+    dialog.set_extra_child(Some(&list));
+
+    dialog.add_response("cancel", &gettext("Cancel"));
+    dialog.add_response("save", &gettext("Save"));
+    dialog.set_response_appearance("save", adw::ResponseAppearance::Suggested);
+    dialog.set_default_response(Some("save"));
+    dialog.set_close_response("cancel");
+
+    let app_id = app_id.to_string();
+    dialog.connect_response(Some("save"), move |dialog, _| {
+        let patterns = patterns_row.text().split(',').map(str::to_string).collect();
+        if let Err(e) = config.borrow_mut().set_game_ignore_patterns(&app_id, patterns) {
+            eprintln!("Error saving ignored files of {}: {}", app_id, e);
+            error_dialog::show(dialog.transient_for().as_ref(), &gettext("Invalid Entry"), &e);
+        }
+    });
+    dialog.present();
+}
@@ -0,0 +1,131 @@
+use adw::prelude::*;
+use adw::MessageDialog;
+use gtk::glib;
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use crate::config::{Config, ConfirmAction};
+use crate::i18n::{gettext, gettext_f};
+use crate::import::{self, ImportSource};
+use crate::ui::inhibit::Inhibitor;
+use crate::ui::{confirm, error_dialog};
+
+// Accept folders and archives dropped onto a game row and import them as its saves
+pub fn attach_drop_target(row: &adw::ExpanderRow, config: Rc<RefCell<Config>>, app_id: &str, name: &str, targets: Vec<PathBuf>) {
+    let drop_target = gtk::DropTarget::new(gtk::gio::File::static_type(), gtk::gdk::DragAction::COPY);
+    let app_id = app_id.to_string();
+    let name = name.to_string();
+    let row_clone = row.clone();
+    drop_target.connect_drop(move |_, value, _, _| {
+        let Some(path) = value.get::<gtk::gio::File>().ok().and_then(|file| file.path()) else {
+            return false;
+        };
+        let parent = row_clone.root().and_downcast::<gtk::Window>();
+        match import::prepare_source(&path) {
+            Ok(source) => show(parent.as_ref(), config.clone(), &app_id, &name, source, targets.clone()),
+            Err(e) => {
+                eprintln!("Error preparing dropped saves {}: {:#}", path.display(), e);
+                error_dialog::show(parent.as_ref(), &gettext("Can't Import Saves"), &e);
+            }
+        }
+        true
+    });
+    // GTK highlights the row (:drop(active)) while something is dragged over it
+    row.add_controller(drop_target);
+}
+
+// Confirm where the dropped saves go. When they don't clearly belong in one of
+// the game's save locations, the user picks the location.
+fn show(parent: Option<&gtk::Window>, config: Rc<RefCell<Config>>, app_id: &str, name: &str, source: ImportSource, targets: Vec<PathBuf>) {
+    if targets.is_empty() {
+        show_result(parent, &gettext("Can't Import Saves"), &gettext_f("No save locations are known for {}.", &[name]));
+        return;
+    }
+
+    let matched = import::match_location(&source, &targets);
+    let body = if matched.is_some() {
+        gettext_f(
+            "The contents of {} will be copied into the save folder of {}, replacing files with the same name. A safety snapshot is taken first.",
+            &[&source.name, name],
+        )
+    } else {
+        gettext_f(
+            "{} doesn't look like any of the save folders of {}. Choose where its contents should be copied; files with the same name are replaced after a safety snapshot.",
+            &[&source.name, name],
+        )
+    };
+    let dialog = MessageDialog::builder()
+        .heading(gettext("Import Saves?"))
+        .body(body)
+        .modal(true)
+        .build();
+    if let Some(parent) = parent {
+        dialog.set_transient_for(Some(parent));
+    }
+
+    let labels: Vec<String> = targets.iter().map(|p| p.display().to_string()).collect();
+    let label_refs: Vec<&str> = labels.iter().map(|s| s.as_str()).collect();
+    let target_row = adw::ComboRow::builder()
+        .title(gettext("Import Into"))
+        .model(&gtk::StringList::new(&label_refs))
+        .selected(matched.unwrap_or(0) as u32)
+        .build();
+    let list = gtk::ListBox::new();
+    list.set_selection_mode(gtk::SelectionMode::None);
+    list.add_css_class("boxed-list");
+    list.append(&target_row);
+    dialog.set_extra_child(Some(&list));
+
+    dialog.add_response("cancel", &gettext("Cancel"));
+    dialog.add_response("import", &gettext("Import"));
+    dialog.set_response_appearance("import", adw::ResponseAppearance::Destructive);
+    dialog.set_default_response(Some("cancel"));
+    dialog.set_close_response("cancel");
+
+    let app_id = app_id.to_string();
+    let parent = parent.cloned();
+    glib::MainContext::default().spawn_local(async move {
+        // Without a matching save folder the dialog is where the target gets
+        // picked, so it's only skipped when the target is known
+        let confirmed = if matched.is_some() {
+            confirm::ask(&config, dialog, ConfirmAction::Replace, "import-saves", "import").await
+        } else {
+            dialog.choose_future().await == "import"
+        };
+        if !confirmed {
+            return;
+        }
+        let Some(target) = targets.get(target_row.selected() as usize) else {
+            return;
+        };
+        import_and_report(parent.as_ref(), &config, &app_id, &source, target);
+    });
+}
+
+fn import_and_report(parent: Option<&gtk::Window>, config: &Rc<RefCell<Config>>, app_id: &str, source: &ImportSource, target: &Path) {
+    let inhibitor = parent.and_then(|parent| Inhibitor::new(parent, &gettext("Importing game saves")));
+    let imported = import::import_into(&config.borrow(), app_id, source, target);
+    drop(inhibitor);
+    match imported {
+        Ok(()) => {
+            if let Some(parent) = parent {
+                // Sizes and "Path not found" markers are out of date now
+                WidgetExt::activate_action(parent, "app.refresh", None).ok();
+            }
+        }
+        Err(e) => {
+            eprintln!("Error importing {}: {:#}", source.name, e);
+            error_dialog::show(parent, &gettext("Error Importing Saves"), &e);
+        }
+    }
+}
+
+fn show_result(parent: Option<&gtk::Window>, heading: &str, body: &str) {
+    let dialog = MessageDialog::builder().heading(heading).body(body).modal(true).build();
+    if let Some(parent) = parent {
+        dialog.set_transient_for(Some(parent));
+    }
+    dialog.add_response("ok", &gettext("OK"));
+    dialog.present();
+}
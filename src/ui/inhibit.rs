@@ -0,0 +1,34 @@
+use adw::prelude::*;
+use gtk::gio;
+
+// Keeps the machine from suspending (or the session from logging out) while an
+// operation that writes saves runs: a laptop going to sleep halfway through a
+// restore leaves a half-copied save behind. GTK goes through the inhibit
+// portal, so it works in the Flatpak as well. Released on drop.
+pub struct Inhibitor {
+    app: gtk::Application,
+    cookie: u32,
+}
+
+impl Inhibitor {
+    // `reason` is shown by the desktop, e.g. in the logout dialog. None if
+    // there's no application or the desktop refused, the operation runs anyway.
+    pub fn new(window: &impl IsA<gtk::Window>, reason: &str) -> Option<Self> {
+        // Dialogs aren't added to the application, ask the app itself
+        let app = gio::Application::default().and_downcast::<gtk::Application>()?;
+        let flags = gtk::ApplicationInhibitFlags::SUSPEND | gtk::ApplicationInhibitFlags::LOGOUT;
+        let cookie = app.inhibit(Some(window), flags, Some(reason));
+        if cookie == 0 {
+            eprintln!("Could not keep the system from suspending: {}", reason);
+            return None;
+        }
+        println!("Inhibiting suspend: {}", reason);
+        Some(Self { app, cookie })
+    }
+}
+
+impl Drop for Inhibitor {
+    fn drop(&mut self) {
+        self.app.uninhibit(self.cookie);
+    }
+}
@@ -0,0 +1,85 @@
+use adw::prelude::*;
+use adw::ExpanderRow;
+use gtk::{gdk, glib, ListBox};
+
+// Keyboard navigation, also what a controller uses: Steam Input's desktop
+// layouts send the D-pad as arrow keys and A as Return, and the shoulder
+// buttons can be bound to Ctrl+Page Up / Ctrl+Page Down.
+//
+//   Up/Down                     move between rows (GtkListBox and GtkListView do this themselves)
+//   Return/Space                expand or collapse a game (AdwExpanderRow does this itself)
+//   Right/Left                  expand/collapse the game the focus is in
+//   Ctrl+Page Down / Ctrl+Tab   next page
+//   Ctrl+Page Up / Ctrl+Shift+Tab  previous page
+//   Alt+1, Alt+2, ...           jump to a page
+
+// Page switching shortcuts for the window, `sidebar_list` has one row per page
+pub fn add_page_shortcuts(window: &adw::ApplicationWindow, sidebar_list: &ListBox, page_count: i32) {
+    let controller = gtk::ShortcutController::new();
+    controller.set_scope(gtk::ShortcutScope::Global);
+
+    let mut shortcuts: Vec<(String, PageStep)> = vec![
+        ("<Control>Page_Down".to_string(), PageStep::Next),
+        ("<Control>Tab".to_string(), PageStep::Next),
+        ("<Control>Page_Up".to_string(), PageStep::Previous),
+        ("<Control><Shift>ISO_Left_Tab".to_string(), PageStep::Previous),
+    ];
+    shortcuts.extend((0..page_count.min(9)).map(|index| (format!("<Alt>{}", index + 1), PageStep::Index(index))));
+
+    for (accel, step) in shortcuts {
+        let Some(trigger) = gtk::ShortcutTrigger::parse_string(&accel) else {
+            eprintln!("Invalid shortcut trigger {}", accel);
+            continue;
+        };
+        let sidebar_list = sidebar_list.clone();
+        let action = gtk::CallbackAction::new(move |_, _| {
+            let current = sidebar_list.selected_row().map(|row| row.index()).unwrap_or(0);
+            let index = match step {
+                PageStep::Next => (current + 1) % page_count,
+                PageStep::Previous => (current + page_count - 1) % page_count,
+                PageStep::Index(index) => index,
+            };
+            sidebar_list.select_row(sidebar_list.row_at_index(index).as_ref());
+            true
+        });
+        controller.add_shortcut(gtk::Shortcut::new(Some(trigger), Some(action)));
+    }
+    window.add_controller(controller);
+}
+
+#[derive(Clone, Copy)]
+enum PageStep {
+    Next,
+    Previous,
+    Index(i32),
+}
+
+// Right expands and Left collapses the expander row holding the keyboard focus.
+// Collapsing from a nested row moves the focus back to the game's header.
+pub fn add_expander_keys(list: &impl IsA<gtk::Widget>) {
+    let controller = gtk::EventControllerKey::new();
+    let list_clone = list.upcast_ref::<gtk::Widget>().clone();
+    controller.connect_key_pressed(move |_, key, _, modifiers| {
+        if !modifiers.is_empty() || !matches!(key, gdk::Key::Left | gdk::Key::Right) {
+            return glib::Propagation::Proceed;
+        }
+        let Some(expander) = list_clone
+            .root()
+            .and_then(|root| root.focus())
+            .and_then(|focus| focus.ancestor(ExpanderRow::static_type()))
+            .and_downcast::<ExpanderRow>()
+        else {
+            return glib::Propagation::Proceed;
+        };
+        let expand = key == gdk::Key::Right;
+        if expander.is_expanded() == expand {
+            return glib::Propagation::Proceed;
+        }
+        expander.set_expanded(expand);
+        if !expand {
+            expander.grab_focus();
+        }
+        glib::Propagation::Stop
+    });
+    list.add_controller(controller);
+}
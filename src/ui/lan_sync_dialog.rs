@@ -0,0 +1,461 @@
+use adw::prelude::*;
+use adw::{ActionRow, MessageDialog, PreferencesGroup, PreferencesPage, SwitchRow};
+use gtk::{gio, glib, Button};
+use humansize::{format_size, DECIMAL};
+use std::cell::RefCell;
+use std::fs;
+use std::net::SocketAddr;
+use std::rc::Rc;
+use std::sync::mpsc;
+use std::time::Duration;
+
+use crate::bundle::{self, ExportGame};
+use crate::config::Config;
+use crate::i18n::{gettext, gettext_f};
+use crate::lan_sync::{self, Peer, ServerEvent, ServerHandle, SyncDirection, SyncGame};
+use crate::report;
+use crate::ui::error_dialog;
+use crate::ui::inhibit::Inhibitor;
+
+// Shared state of the open dialog
+struct LanSync {
+    window: adw::Window,
+    config: Rc<RefCell<Config>>,
+    toast_overlay: adw::ToastOverlay,
+    games: Vec<ExportGame>, // Matched games with saves on this machine
+    server: RefCell<Option<ServerHandle>>,
+    peers_group: PreferencesGroup,
+    peer_rows: RefCell<Vec<gtk::Widget>>,
+    compare_group: PreferencesGroup,
+    compare_rows: RefCell<Vec<gtk::Widget>>,
+}
+
+// Sync saves with another copy of the app on the local network: share this
+// machine, or find the other one and compare games saved on both
+pub fn show(parent: &impl IsA<gtk::Window>, config: Rc<RefCell<Config>>, games: Vec<ExportGame>) {
+    let window = adw::Window::builder()
+        .modal(true)
+        .transient_for(parent)
+        .title(gettext("Sync Over LAN"))
+        .default_width(560)
+        .default_height(640)
+        .build();
+    let page = PreferencesPage::new();
+    let toast_overlay = adw::ToastOverlay::new();
+    toast_overlay.set_child(Some(&page));
+    let toolbar = adw::ToolbarView::new();
+    toolbar.add_top_bar(&adw::HeaderBar::new());
+    toolbar.set_content(Some(&toast_overlay));
+    window.set_content(Some(&toolbar));
+
+    let share_group = PreferencesGroup::builder()
+        .title(gettext("Share This Machine"))
+        .description(gettext("Lets another machine on this network find this one and sync with it while this window is open. It needs the pairing code shown here."))
+        .build();
+    page.add(&share_group);
+    let peers_group = PreferencesGroup::builder()
+        .title(gettext("Sync With Another Machine"))
+        .description(gettext("Share the other machine first, then find it here. Saves are sent unencrypted, use this on your home network only."))
+        .build();
+    page.add(&peers_group);
+    let compare_group = PreferencesGroup::builder()
+        .title(gettext("Games on Both Machines"))
+        .visible(false)
+        .build();
+    page.add(&compare_group);
+
+    let state = Rc::new(LanSync {
+        window: window.clone(),
+        config,
+        toast_overlay,
+        games,
+        server: RefCell::new(None),
+        peers_group,
+        peer_rows: RefCell::new(Vec::new()),
+        compare_group,
+        compare_rows: RefCell::new(Vec::new()),
+    });
+    build_share_group(&state, &share_group);
+    build_peers_group(&state);
+
+    // Closing the window stops sharing
+    window.connect_close_request(glib::clone!(@strong state => @default-return glib::Propagation::Proceed, move |_| {
+        state.server.replace(None);
+        glib::Propagation::Proceed
+    }));
+    window.present();
+}
+
+fn build_share_group(state: &Rc<LanSync>, group: &PreferencesGroup) {
+    let share_row = SwitchRow::builder()
+        .title(gettext("Allow Syncing With This Machine"))
+        .subtitle(gettext_f("{} games with saves", &[&state.games.len().to_string()]))
+        .build();
+    group.add(&share_row);
+    let code_row = ActionRow::builder().title(gettext("Pairing Code")).visible(false).build();
+    code_row.add_css_class("property");
+    group.add(&code_row);
+    let log_rows: Rc<RefCell<Vec<ActionRow>>> = Rc::new(RefCell::new(Vec::new()));
+
+    share_row.connect_active_notify(glib::clone!(@weak state, @weak group, @weak code_row => move |row| {
+        for log_row in log_rows.borrow_mut().drain(..) {
+            group.remove(&log_row);
+        }
+        if !row.is_active() {
+            state.server.replace(None);
+            code_row.set_visible(false);
+            return;
+        }
+
+        let (sender, receiver) = mpsc::channel();
+        let detached = state.config.borrow().detach();
+        let started = lan_sync::pairing_code()
+            .and_then(|code| lan_sync::start_server(detached, state.games.clone(), code.clone(), sender).map(|handle| (handle, code)));
+        match started {
+            Ok((handle, code)) => {
+                code_row.set_subtitle(&code);
+                code_row.set_tooltip_text(Some(&if handle.discoverable {
+                    gettext_f("{} on port {}", &[&lan_sync::machine_name(), &handle.port.to_string()])
+                } else {
+                    gettext_f("Not discoverable, another app uses the port. Connect by address, port {}", &[&handle.port.to_string()])
+                }));
+                code_row.set_visible(true);
+                state.server.replace(Some(handle));
+            }
+            Err(e) => {
+                eprintln!("Error starting LAN sync: {:#}", e);
+                error_dialog::show(Some(state.window.upcast_ref()), &gettext("Sharing Failed"), &e);
+                row.set_active(false);
+                return;
+            }
+        }
+
+        // Show what the other machine did until sharing stops
+        let log_rows = log_rows.clone();
+        glib::timeout_add_local(Duration::from_millis(200), glib::clone!(@weak state, @weak group, @weak row => @default-return glib::ControlFlow::Break, move || {
+            loop {
+                match receiver.try_recv() {
+                    Ok(ServerEvent::Stopped) => {
+                        // The server is gone already. Turning the switch off
+                        // clears the code and the log, this row stays.
+                        row.set_active(false);
+                        let log_row = ActionRow::builder().title(describe_event(&ServerEvent::Stopped)).build();
+                        group.add(&log_row);
+                        log_rows.borrow_mut().push(log_row);
+                        return glib::ControlFlow::Break;
+                    }
+                    Ok(ServerEvent::PushOffered { peer, folders, bytes, answer }) => {
+                        ask_accept_push(&state, &peer, &folders, bytes, answer);
+                    }
+                    Ok(event) => {
+                        let log_row = ActionRow::builder().title(describe_event(&event)).build();
+                        group.add(&log_row);
+                        log_rows.borrow_mut().push(log_row);
+                        // Received saves change what the pages show
+                        if matches!(event, ServerEvent::Received { .. }) {
+                            let _ = WidgetExt::activate_action(&state.window, "app.refresh", None);
+                        }
+                    }
+                    Err(mpsc::TryRecvError::Empty) => return glib::ControlFlow::Continue,
+                    Err(mpsc::TryRecvError::Disconnected) => return glib::ControlFlow::Break,
+                }
+            }
+        }));
+    }));
+}
+
+fn describe_event(event: &ServerEvent) -> String {
+    match event {
+        ServerEvent::Sent { peer, games } => gettext_f("Sent saves of {} games to {}", &[&games.to_string(), peer]),
+        ServerEvent::Received { peer, games } => gettext_f("Received saves of {} games from {}", &[&games.to_string(), peer]),
+        ServerEvent::Refused { peer } => gettext_f("Refused {}, wrong pairing code", &[peer]),
+        ServerEvent::Failed { peer, error } => gettext_f("Sync with {} failed: {}", &[peer, error]),
+        ServerEvent::Stopped => gettext("Stopped sharing after too many wrong pairing codes, turn it on again for a new code"),
+        ServerEvent::PushOffered { peer, .. } => gettext_f("{} wants to send saves", &[peer]),
+    }
+}
+
+// Saves pushed from another machine replace the ones here, so nothing is
+// taken without asking. Closing the dialog declines.
+fn ask_accept_push(state: &Rc<LanSync>, peer: &str, folders: &[String], bytes: u64, answer: mpsc::Sender<bool>) {
+    let dialog = MessageDialog::builder()
+        .transient_for(&state.window)
+        .modal(true)
+        .heading(gettext_f("Accept Saves From {}?", &[peer]))
+        .body(gettext_f(
+            "{} wants to send saves ({}) of these games. What gets replaced is kept in a safety snapshot first.\n\n{}",
+            &[peer, &format_size(bytes, DECIMAL), &folders.join("\n")],
+        ))
+        .build();
+    dialog.add_response("decline", &gettext("Decline"));
+    dialog.add_response("accept", &gettext("Accept"));
+    dialog.set_response_appearance("accept", adw::ResponseAppearance::Suggested);
+    dialog.set_close_response("decline");
+    dialog.connect_response(None, move |_, response| {
+        // Fails if the other machine gave up waiting already
+        let _ = answer.send(response == "accept");
+    });
+    dialog.present();
+}
+
+fn build_peers_group(state: &Rc<LanSync>) {
+    let find_button = Button::with_label(&gettext("Find Machines"));
+    find_button.add_css_class("flat");
+    state.peers_group.set_header_suffix(Some(&find_button));
+    // For networks that drop broadcasts
+    let address_row = adw::EntryRow::builder()
+        .title(gettext("Connect by Address (IP:Port)"))
+        .show_apply_button(true)
+        .build();
+    state.peers_group.add(&address_row);
+
+    address_row.connect_apply(glib::clone!(@weak state => move |row| {
+        match row.text().trim().parse::<SocketAddr>() {
+            Ok(address) => ask_pairing_code(&state, Peer { name: address.to_string(), address }),
+            Err(_) => error_dialog::show(Some(state.window.upcast_ref()), &gettext("Invalid Address"), &anyhow::anyhow!(gettext("Enter the address as IP:Port, e.g. 192.168.1.20:40123"))),
+        }
+    }));
+
+    find_button.connect_clicked(glib::clone!(@weak state => move |button| {
+        button.set_sensitive(false);
+        button.set_label(&gettext("Searching…"));
+        let button = button.clone();
+        let privacy = state.config.borrow().privacy();
+        glib::MainContext::default().spawn_local(async move {
+            let found = gio::spawn_blocking(move || lan_sync::discover(&privacy)).await.unwrap_or_else(|_| Err(anyhow::anyhow!("Discovery thread panicked")));
+            button.set_sensitive(true);
+            button.set_label(&gettext("Find Machines"));
+            for row in state.peer_rows.borrow_mut().drain(..) {
+                state.peers_group.remove(&row);
+            }
+            let peers = match found {
+                Ok(peers) => peers,
+                Err(e) => {
+                    eprintln!("LAN sync discovery failed: {:#}", e);
+                    error_dialog::show(Some(state.window.upcast_ref()), &gettext("Search Failed"), &e);
+                    return;
+                }
+            };
+            if peers.is_empty() {
+                let row = ActionRow::builder()
+                    .title(gettext("No machines found. Is sharing on there, and are both on the same network?"))
+                    .build();
+                row.add_css_class("dim-label");
+                state.peers_group.add(&row);
+                state.peer_rows.borrow_mut().push(row.upcast());
+            }
+            for peer in peers {
+                let row = ActionRow::builder()
+                    .title(glib::markup_escape_text(&peer.name))
+                    .subtitle(peer.address.to_string())
+                    .activatable(true)
+                    .build();
+                row.add_suffix(&gtk::Image::from_icon_name("go-next-symbolic"));
+                row.connect_activated(glib::clone!(@weak state => move |_| ask_pairing_code(&state, peer.clone())));
+                state.peers_group.add(&row);
+                state.peer_rows.borrow_mut().push(row.upcast());
+            }
+        });
+    }));
+}
+
+fn ask_pairing_code(state: &Rc<LanSync>, peer: Peer) {
+    let dialog = MessageDialog::builder()
+        .heading(gettext_f("Connect to {}?", &[&peer.name]))
+        .body(gettext("Enter the pairing code shown on the other machine"))
+        .transient_for(&state.window)
+        .modal(true)
+        .build();
+    let code_row = adw::EntryRow::builder()
+        .title(gettext("Pairing Code"))
+        .input_purpose(gtk::InputPurpose::Digits)
+        .build();
+    let list = gtk::ListBox::new();
+    list.set_selection_mode(gtk::SelectionMode::None);
+    list.add_css_class("boxed-list");
+    list.append(&code_row);
+    dialog.set_extra_child(Some(&list));
+    dialog.add_response("cancel", &gettext("Cancel"));
+    dialog.add_response("connect", &gettext("Connect"));
+    dialog.set_response_appearance("connect", adw::ResponseAppearance::Suggested);
+    dialog.set_default_response(Some("connect"));
+    dialog.set_close_response("cancel");
+    dialog.connect_response(None, glib::clone!(@weak state => move |_, response| {
+        if response != "connect" {
+            return;
+        }
+        let code = code_row.text().trim().to_string();
+        let peer = peer.clone();
+        glib::MainContext::default().spawn_local(async move {
+            let address = peer.address;
+            let code_clone = code.clone();
+            let privacy = state.config.borrow().privacy();
+            let remote = gio::spawn_blocking(move || lan_sync::list_games(&privacy, &address, &code_clone))
+                .await
+                .unwrap_or_else(|_| Err(anyhow::anyhow!("LAN sync thread panicked")));
+            match remote {
+                Ok(remote) => show_comparison(&state, peer, code, remote),
+                Err(e) => {
+                    eprintln!("Error connecting to {}: {:#}", peer.address, e);
+                    error_dialog::show(Some(state.window.upcast_ref()), &gettext("Connection Failed"), &e);
+                }
+            }
+        });
+    }));
+    dialog.present();
+}
+
+// One row per game saved on both machines, checked when they differ
+fn show_comparison(state: &Rc<LanSync>, peer: Peer, code: String, remote: Vec<SyncGame>) {
+    for row in state.compare_rows.borrow_mut().drain(..) {
+        state.compare_group.remove(&row);
+    }
+    let pairs = lan_sync::pair_games(lan_sync::describe_games(&state.games), remote);
+    state.compare_group.set_title(&gettext_f("Games on Both Machines ({})", &[&peer.name]));
+    state.compare_group.set_description(Some(&if pairs.is_empty() {
+        gettext("No game has saves on both machines. Scan with the manifest on both, games only saved on one side aren't offered.")
+    } else {
+        gettext("The side with the newer save files is copied over the other. What gets replaced is kept in a safety snapshot first.")
+    }));
+    state.compare_group.set_visible(true);
+
+    let selected: Rc<RefCell<Vec<(String, SyncDirection, gtk::CheckButton)>>> = Rc::new(RefCell::new(Vec::new()));
+    for (local, other) in &pairs {
+        let direction = lan_sync::direction(local, other);
+        let subtitle = match direction {
+            SyncDirection::Send => gettext_f("Newer here, send to {}", &[&peer.name]),
+            SyncDirection::Receive => gettext_f("Newer on {}, receive", &[&peer.name]),
+            SyncDirection::InSync => gettext("Same on both"),
+        };
+        let times = [local.modified, other.modified].map(|time| time.map(report::format_timestamp_utc).unwrap_or_else(|| "-".to_string()));
+        let row = ActionRow::builder()
+            .title(glib::markup_escape_text(&local.name))
+            .subtitle(format!("{} · {} | {}", subtitle, gettext_f("here {} ({})", &[&times[0], &format_size(local.size, DECIMAL)]), gettext_f("there {} ({})", &[&times[1], &format_size(other.size, DECIMAL)])))
+            .build();
+        let check = gtk::CheckButton::builder()
+            .active(direction != SyncDirection::InSync)
+            .sensitive(direction != SyncDirection::InSync)
+            .valign(gtk::Align::Center)
+            .build();
+        row.add_prefix(&check);
+        row.set_activatable_widget(Some(&check));
+        state.compare_group.add(&row);
+        state.compare_rows.borrow_mut().push(row.upcast());
+        selected.borrow_mut().push((local.app_id.clone(), direction, check));
+    }
+    if pairs.is_empty() {
+        return;
+    }
+
+    let sync_button = Button::with_label(&gettext("Sync Selected"));
+    sync_button.add_css_class("suggested-action");
+    sync_button.set_halign(gtk::Align::End);
+    sync_button.set_margin_top(12);
+    state.compare_group.add(&sync_button);
+    state.compare_rows.borrow_mut().push(sync_button.clone().upcast());
+    sync_button.connect_clicked(glib::clone!(@weak state => move |button| {
+        let picked: Vec<(String, SyncDirection)> = selected
+            .borrow()
+            .iter()
+            .filter(|(_, _, check)| check.is_active())
+            .map(|(app_id, direction, _)| (app_id.clone(), *direction))
+            .collect();
+        if picked.is_empty() {
+            return;
+        }
+        button.set_sensitive(false);
+        let button = button.clone();
+        let peer = peer.clone();
+        let code = code.clone();
+        glib::MainContext::default().spawn_local(async move {
+            sync_games(&state, &peer, &code, picked).await;
+            button.set_sensitive(true);
+        });
+    }));
+}
+
+// Receive what's newer there, then send what's newer here
+async fn sync_games(state: &Rc<LanSync>, peer: &Peer, code: &str, picked: Vec<(String, SyncDirection)>) {
+    let receive: Vec<String> = picked.iter().filter(|(_, d)| *d == SyncDirection::Receive).map(|(id, _)| id.clone()).collect();
+    let send: Vec<ExportGame> = state
+        .games
+        .iter()
+        .filter(|game| picked.iter().any(|(id, d)| *id == game.app_id && *d == SyncDirection::Send))
+        .cloned()
+        .collect();
+    let _inhibitor = Inhibitor::new(&state.window, &gettext("Syncing game saves"));
+    let toast = adw::Toast::builder().title(gettext_f("Syncing with {}…", &[&peer.name])).timeout(0).build();
+    state.toast_overlay.add_toast(toast.clone());
+
+    let mut received = 0;
+    let mut sent = 0;
+    let mut missing: Vec<String> = Vec::new();
+    let mut failure = None;
+
+    if !receive.is_empty() {
+        let address = peer.address;
+        let code = code.to_string();
+        let dest = lan_sync::temp_dir("fetch");
+        let fetch_dest = dest.clone();
+        let privacy = state.config.borrow().privacy();
+        let fetched = gio::spawn_blocking(move || lan_sync::fetch(&privacy, &address, &code, receive, &fetch_dest))
+            .await
+            .unwrap_or_else(|_| Err(anyhow::anyhow!("LAN sync thread panicked")));
+        let imported = fetched.and_then(|bundle_dir| {
+            let manifest = bundle::read_bundle(&bundle_dir)?;
+            bundle::import_bundle(&state.config.borrow(), &bundle_dir, &manifest)
+        });
+        let _ = fs::remove_dir_all(&dest);
+        match imported {
+            Ok(summary) => {
+                received = summary.restored_games;
+                missing.extend(summary.missing_prefixes);
+            }
+            Err(e) => failure = Some(e.context(gettext("Failed to receive saves"))),
+        }
+    }
+
+    if failure.is_none() && !send.is_empty() {
+        let temp = lan_sync::temp_dir("push");
+        let exported = bundle::export_bundle(&state.config.borrow(), &send, &temp, false);
+        match exported {
+            Ok(bundle_dir) => {
+                let address = peer.address;
+                let code = code.to_string();
+                let privacy = state.config.borrow().privacy();
+                let pushed = gio::spawn_blocking(move || lan_sync::push(&privacy, &address, &code, &bundle_dir))
+                    .await
+                    .unwrap_or_else(|_| Err(anyhow::anyhow!("LAN sync thread panicked")));
+                match pushed {
+                    Ok((games, other_missing)) => {
+                        sent = games;
+                        missing.extend(other_missing);
+                    }
+                    Err(e) => failure = Some(e.context(gettext("Failed to send saves"))),
+                }
+            }
+            Err(e) => failure = Some(e.context(gettext("Failed to send saves"))),
+        }
+        let _ = fs::remove_dir_all(&temp);
+    }
+
+    toast.dismiss();
+    if received > 0 {
+        let _ = WidgetExt::activate_action(&state.window, "app.refresh", None);
+    }
+    if let Some(e) = failure {
+        eprintln!("LAN sync with {} failed: {:#}", peer.address, e);
+        error_dialog::show(Some(state.window.upcast_ref()), &gettext("Sync Failed"), &e);
+        return;
+    }
+    state.toast_overlay.add_toast(adw::Toast::new(&gettext_f("Received {} games, sent {} games", &[&received.to_string(), &sent.to_string()])));
+    if !missing.is_empty() {
+        let dialog = MessageDialog::builder()
+            .heading(gettext("Some Games Were Skipped"))
+            .body(gettext_f("These games have no Proton prefix on the receiving machine yet. Launch them once through Steam there, then sync again:\n\n{}", &[&missing.join("\n")]))
+            .transient_for(&state.window)
+            .build();
+        dialog.add_response("ok", &gettext("OK"));
+        dialog.present();
+    }
+}
@@ -0,0 +1,188 @@
+use adw::prelude::*;
+use gtk::{gdk, gio, Align, GestureClick, GestureLongPress, MenuButton};
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use crate::compatdata;
+use crate::config::Config;
+use crate::i18n::{gettext, gettext_f};
+use crate::sync_link;
+use crate::ui::error_dialog;
+use crate::ui::{file_browser, open_with, preview_pane};
+
+// Adds a "more" menu button to a save location row, also opened by right click
+// or long press, with Preview / Browse Files / Open With / Copy Path / Open in Terminal / Open
+// Containing Prefix. With `linkable`, the row is a single game's saves and can
+// also be linked into the sync folder.
+pub fn attach(row: &adw::ActionRow, path: PathBuf, prefix_path: PathBuf, config: &Rc<RefCell<Config>>, linkable: bool) {
+    let actions = gio::SimpleActionGroup::new();
+
+    let preview_action = gio::SimpleAction::new("preview", None);
+    preview_action.set_enabled(path.exists());
+    let row_clone = row.clone();
+    let path_clone = path.clone();
+    let config_clone = config.clone();
+    preview_action.connect_activate(move |_, _| {
+        let parent = row_clone.root().and_downcast::<gtk::Window>();
+        preview_pane::show(parent.as_ref(), path_clone.clone(), &config_clone);
+    });
+    actions.add_action(&preview_action);
+
+    let browse_action = gio::SimpleAction::new("browse", None);
+    browse_action.set_enabled(path.exists());
+    let row_clone = row.clone();
+    let path_clone = path.clone();
+    let config_clone = config.clone();
+    browse_action.connect_activate(move |_, _| {
+        let parent = row_clone.root().and_downcast::<gtk::Window>();
+        file_browser::show_location(parent.as_ref(), path_clone.clone(), &config_clone);
+    });
+    actions.add_action(&browse_action);
+
+    let open_with_action = gio::SimpleAction::new("open-with", None);
+    open_with_action.set_enabled(path.exists());
+    let row_clone = row.clone();
+    let path_clone = path.clone();
+    open_with_action.connect_activate(move |_, _| {
+        let parent = row_clone.root().and_downcast::<gtk::Window>();
+        open_with::show(parent.as_ref(), &path_clone);
+    });
+    actions.add_action(&open_with_action);
+
+    let copy_action = gio::SimpleAction::new("copy-path", None);
+    let row_clone = row.clone();
+    let path_clone = path.clone();
+    copy_action.connect_activate(move |_, _| {
+        row_clone.clipboard().set_text(&path_clone.to_string_lossy());
+    });
+    actions.add_action(&copy_action);
+
+    let terminal_action = gio::SimpleAction::new("open-terminal", None);
+    terminal_action.set_enabled(path.is_dir());
+    let path_clone = path.clone();
+    terminal_action.connect_activate(move |_, _| {
+        if let Err(e) = compatdata::open_in_terminal(&path_clone) {
+            eprintln!("Failed to open terminal in {}: {}", path_clone.display(), e);
+        }
+    });
+    actions.add_action(&terminal_action);
+
+    let prefix_action = gio::SimpleAction::new("open-prefix", None);
+    prefix_action.set_enabled(prefix_path.exists());
+    let prefix_path_clone = prefix_path.clone();
+    prefix_action.connect_activate(move |_, _| {
+        if let Err(e) = compatdata::open_in_file_manager(&prefix_path_clone) {
+            eprintln!("Failed to open prefix {}: {}", prefix_path_clone.display(), e);
+        }
+    });
+    actions.add_action(&prefix_action);
+
+    let linked_target = sync_link::linked_target(&path);
+    if linkable {
+        add_sync_actions(row, &actions, config, &path, &prefix_path, linked_target.is_some());
+    }
+    if let Some(target) = &linked_target {
+        let link_icon = gtk::Image::from_icon_name("emblem-shared-symbolic");
+        link_icon.set_tooltip_text(Some(&gettext_f("Linked to {}", &[&target.display().to_string()])));
+        link_icon.set_valign(Align::Center);
+        row.add_suffix(&link_icon);
+    }
+    row.insert_action_group("location", Some(&actions));
+
+    let menu = gio::Menu::new();
+    menu.append(Some(&gettext("Preview Files")), Some("location.preview"));
+    menu.append(Some(&gettext("Browse Files")), Some("location.browse"));
+    menu.append(Some(&gettext("Open With…")), Some("location.open-with"));
+    menu.append(Some(&gettext("Copy Path")), Some("location.copy-path"));
+    menu.append(Some(&gettext("Open in Terminal")), Some("location.open-terminal"));
+    menu.append(Some(&gettext("Open Containing Prefix")), Some("location.open-prefix"));
+    if linkable {
+        let sync_section = gio::Menu::new();
+        if linked_target.is_some() {
+            sync_section.append(Some(&gettext("Unlink from Sync Folder")), Some("location.unlink-sync"));
+        } else {
+            sync_section.append(Some(&gettext("Link to Sync Folder")), Some("location.link-sync"));
+        }
+        menu.append_section(None, &sync_section);
+    }
+
+    let menu_button = MenuButton::builder()
+        .icon_name("view-more-symbolic")
+        .tooltip_text(gettext("More Actions"))
+        .menu_model(&menu)
+        .valign(Align::Center)
+        .build();
+    menu_button.add_css_class("flat");
+    row.add_suffix(&menu_button);
+
+    // Right click and long press (touch) open the same menu
+    let right_click = GestureClick::new();
+    right_click.set_button(gdk::BUTTON_SECONDARY);
+    let menu_button_clone = menu_button.clone();
+    right_click.connect_pressed(move |_, _, _, _| {
+        menu_button_clone.popup();
+    });
+    row.add_controller(right_click);
+
+    let long_press = GestureLongPress::new();
+    long_press.set_touch_only(true);
+    long_press.connect_pressed(move |_, _, _| {
+        menu_button.popup();
+    });
+    row.add_controller(long_press);
+}
+
+// Link/unlink the location into the sync folder set in Settings, refreshing the
+// lists afterwards so the row shows its new state
+fn add_sync_actions(
+    row: &adw::ActionRow,
+    actions: &gio::SimpleActionGroup,
+    config: &Rc<RefCell<Config>>,
+    path: &Path,
+    prefix_path: &Path,
+    linked: bool,
+) {
+    let link_action = gio::SimpleAction::new("link-sync", None);
+    link_action.set_enabled(!linked && path.exists());
+    let row_clone = row.clone();
+    let config_clone = config.clone();
+    let path_clone = path.to_path_buf();
+    let prefix_path = prefix_path.to_path_buf();
+    link_action.connect_activate(move |_, _| {
+        let result = sync_link::link(&config_clone.borrow(), &prefix_path, &path_clone);
+        finish_sync_action(&row_clone, result.map(|_| ()), &gettext("Linking Failed"));
+    });
+    actions.add_action(&link_action);
+
+    let unlink_action = gio::SimpleAction::new("unlink-sync", None);
+    unlink_action.set_enabled(linked);
+    let row_clone = row.clone();
+    let path_clone = path.to_path_buf();
+    unlink_action.connect_activate(move |_, _| {
+        let result = sync_link::unlink(&path_clone);
+        finish_sync_action(&row_clone, result.map(|_| ()), &gettext("Unlinking Failed"));
+    });
+    actions.add_action(&unlink_action);
+}
+
+fn finish_sync_action(row: &adw::ActionRow, result: anyhow::Result<()>, error_heading: &str) {
+    let parent = row.root().and_downcast::<gtk::Window>();
+    if let Err(e) = result {
+        eprintln!("{}: {:#}", error_heading, e);
+        error_dialog::show(parent.as_ref(), error_heading, &e);
+    }
+    if let Some(parent) = parent {
+        WidgetExt::activate_action(&parent, "app.refresh", None).ok();
+    }
+}
+
+// The prefix folder (compatdata/<App ID>) a path inside a prefix belongs to,
+// looked up in every library's compatdata
+pub fn prefix_for(compatdata_paths: &[PathBuf], path: &Path) -> Option<PathBuf> {
+    compatdata_paths.iter().find_map(|compatdata_path| {
+        let relative = path.strip_prefix(compatdata_path).ok()?;
+        let app_id = relative.components().next()?;
+        Some(compatdata_path.join(app_id))
+    })
+}
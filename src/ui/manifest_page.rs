@@ -0,0 +1,262 @@
+use adw::prelude::*;
+use adw::{ActionRow, ExpanderRow};
+use gtk::{glib, Box, Label, ListBox, Orientation, ScrolledWindow, SearchEntry, SelectionMode};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use crate::config::{Config, ViewState};
+use crate::i18n::{gettext, gettext_f};
+use crate::manifest::{ManifestData, SkippedEntry};
+
+// The manifest has tens of thousands of games, only the best matches get a row
+const MAX_RESULTS: usize = 100;
+
+// One manifest game, prepared once so searching doesn't walk the YAML structs
+struct ManifestGame {
+    name: String,
+    name_lower: String,
+    app_id: Option<String>,
+    save_paths: Vec<(String, Vec<String>)>, // Path in manifest syntax, tags
+}
+
+// Browse every game in the manifest, matched or not, with the save paths it
+// declares. Shows what the Games page would look for before a game is even
+// installed.
+pub struct ManifestPage {
+    widget: Box,
+    list: ListBox,
+    status_label: Label,
+    skipped_list: ListBox, // Entries that couldn't be read, hidden when there are none
+    search_entry: SearchEntry,
+    badge: Label, // Sidebar count of games in the manifest
+    games: RefCell<Vec<ManifestGame>>,
+    loading: Cell<bool>, // Parsing on a worker thread, see `set_loading`
+}
+
+impl ManifestPage {
+    pub fn new(config: Rc<RefCell<Config>>, badge: Label) -> Rc<Self> {
+        let container = Box::new(Orientation::Vertical, 12);
+        container.set_margin_start(12);
+        container.set_margin_end(12);
+        container.set_margin_top(12);
+        container.set_margin_bottom(12);
+
+        let header = Label::new(Some(&gettext("Game Database")));
+        header.add_css_class("title-1");
+        container.append(&header);
+        let description = Label::new(Some(&gettext("Every game in the manifest and where it keeps its saves")));
+        description.add_css_class("subtitle-1");
+        container.append(&description);
+
+        let search_entry = SearchEntry::new();
+        search_entry.set_placeholder_text(Some(&gettext("🔍 Search Games or App IDs...")));
+        search_entry.set_margin_top(12);
+        search_entry.add_css_class("emoji");
+        container.append(&search_entry);
+
+        let status_label = Label::new(None);
+        status_label.add_css_class("dim-label");
+        status_label.set_wrap(true);
+        container.append(&status_label);
+
+        let skipped_list = ListBox::new();
+        skipped_list.set_selection_mode(SelectionMode::None);
+        skipped_list.add_css_class("boxed-list");
+        skipped_list.set_visible(false);
+        container.append(&skipped_list);
+
+        let list = ListBox::new();
+        list.set_selection_mode(SelectionMode::None);
+        list.add_css_class("boxed-list");
+        list.set_valign(gtk::Align::Start);
+        let scroll = ScrolledWindow::builder()
+            .hscrollbar_policy(gtk::PolicyType::Never)
+            .vexpand(true)
+            .child(&list)
+            .build();
+        container.append(&scroll);
+        search_entry.set_key_capture_widget(Some(&container));
+
+        let page = Rc::new(Self {
+            widget: container,
+            list,
+            status_label,
+            skipped_list,
+            search_entry: search_entry.clone(),
+            badge,
+            games: RefCell::new(Vec::new()),
+            loading: Cell::new(false),
+        });
+        // Restore the last search before connecting, so restoring doesn't save it again
+        search_entry.set_text(&config.borrow().view_state("manifest").filter);
+        search_entry.connect_search_changed(glib::clone!(@weak page => move |entry| {
+            let state = ViewState {
+                filter: entry.text().to_string(),
+                ..config.borrow().view_state("manifest")
+            };
+            if let Err(e) = config.borrow_mut().set_view_state("manifest", state) {
+                eprintln!("Error saving view state of manifest page: {}", e);
+            }
+            page.refresh();
+        }));
+        page.refresh();
+        page
+    }
+
+    pub fn widget(&self) -> &Box {
+        &self.widget
+    }
+
+    // The manifest is being parsed, the old one is about to be replaced
+    pub fn set_loading(&self) {
+        self.loading.set(true);
+        self.games.borrow_mut().clear();
+        self.badge.set_label("");
+        self.refresh();
+    }
+
+    // `None` when parsing the manifest failed
+    pub fn set_manifest(&self, data: Option<&ManifestData>) {
+        self.loading.set(false);
+        let mut games: Vec<ManifestGame> = data
+            .map(|data| {
+                data.games
+                    .iter()
+                    .map(|(name, entry)| {
+                        let mut save_paths: Vec<(String, Vec<String>)> = entry
+                            .files
+                            .iter()
+                            .flatten()
+                            .map(|(path, rule)| (path.clone(), rule._tags.clone().unwrap_or_default()))
+                            .collect();
+                        save_paths.sort();
+                        ManifestGame {
+                            name: name.clone(),
+                            name_lower: name.to_lowercase(),
+                            app_id: entry.steam.as_ref().and_then(|steam| steam.id).map(|id| id.to_string()),
+                            save_paths,
+                        }
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        games.sort_by(|a, b| a.name_lower.cmp(&b.name_lower));
+        self.badge.set_label(&if games.is_empty() { String::new() } else { games.len().to_string() });
+        self.games.replace(games);
+        self.show_skipped(data.map(|data| data.skipped.as_slice()).unwrap_or_default());
+        self.refresh();
+    }
+
+    // Broken entries are left out of the manifest, list them so a game missing
+    // from the Games page can be explained
+    fn show_skipped(&self, skipped: &[SkippedEntry]) {
+        while let Some(child) = self.skipped_list.first_child() {
+            self.skipped_list.remove(&child);
+        }
+        self.skipped_list.set_visible(!skipped.is_empty());
+        if skipped.is_empty() {
+            return;
+        }
+
+        let row = ExpanderRow::builder()
+            .title(gettext_f("{} manifest entries couldn't be read and were skipped", &[&skipped.len().to_string()]))
+            .subtitle(gettext("The rest of the manifest is used without them. Updating the manifest may fix them."))
+            .build();
+        row.add_prefix(&gtk::Image::from_icon_name("dialog-warning-symbolic"));
+        for entry in skipped {
+            let title = if entry.name.is_empty() {
+                gettext_f("Whole source: {}", &[&entry.source])
+            } else {
+                entry.name.clone()
+            };
+            let entry_row = ActionRow::builder()
+                .title(glib::markup_escape_text(&title).as_str())
+                .subtitle(glib::markup_escape_text(&entry.error).as_str())
+                .subtitle_selectable(true)
+                .build();
+            row.add_row(&entry_row);
+        }
+        self.skipped_list.append(&row);
+    }
+
+    // Rebuild the list for the current search text
+    fn refresh(&self) {
+        while let Some(child) = self.list.first_child() {
+            self.list.remove(&child);
+        }
+
+        let games = self.games.borrow();
+        if games.is_empty() {
+            self.status_label.set_label(&if self.loading.get() {
+                gettext("Loading the game manifest…")
+            } else {
+                gettext("The manifest isn't loaded. Download it in Settings to browse it.")
+            });
+            self.list.set_visible(false);
+            return;
+        }
+
+        let query = self.search_entry.text().trim().to_lowercase();
+        if query.is_empty() {
+            self.status_label.set_label(&gettext_f("{} games in the manifest. Search by name or App ID to see their save paths.", &[&games.len().to_string()]));
+            self.list.set_visible(false);
+            return;
+        }
+
+        // Exact App IDs and names starting with the search text come first
+        let mut matches: Vec<&ManifestGame> = games
+            .iter()
+            .filter(|game| game.name_lower.contains(&query) || game.app_id.as_deref().is_some_and(|id| id.contains(&query)))
+            .collect();
+        matches.sort_by_key(|game| {
+            (
+                game.app_id.as_deref() != Some(query.as_str()),
+                !game.name_lower.starts_with(&query),
+            )
+        });
+        let total = matches.len();
+        matches.truncate(MAX_RESULTS);
+
+        self.status_label.set_label(&if total == 0 {
+            gettext("No games in the manifest match the search")
+        } else if total > MAX_RESULTS {
+            gettext_f("Showing {} of {} matches, refine the search to see the rest", &[&MAX_RESULTS.to_string(), &total.to_string()])
+        } else {
+            gettext_f("{} matches", &[&total.to_string()])
+        });
+        self.list.set_visible(total > 0);
+        for game in matches {
+            self.list.append(&Self::create_game_row(game));
+        }
+    }
+
+    fn create_game_row(game: &ManifestGame) -> ExpanderRow {
+        let mut subtitle = vec![match &game.app_id {
+            Some(app_id) => gettext_f("App ID: {}", &[app_id]),
+            None => gettext("Not on Steam"),
+        }];
+        subtitle.push(gettext_f("{} save paths", &[&game.save_paths.len().to_string()]));
+        let row = ExpanderRow::builder()
+            .title(glib::markup_escape_text(&game.name).as_str())
+            .subtitle(subtitle.join(" | "))
+            .build();
+
+        if game.save_paths.is_empty() {
+            let empty_row = ActionRow::builder().title(gettext("The manifest lists no save paths for this game")).build();
+            empty_row.add_css_class("dim-label");
+            row.add_row(&empty_row);
+        }
+        for (path, tags) in &game.save_paths {
+            let path_row = ActionRow::builder()
+                .title(glib::markup_escape_text(path).as_str())
+                .title_selectable(true)
+                .build();
+            path_row.add_css_class("monospace");
+            if !tags.is_empty() {
+                path_row.set_subtitle(&glib::markup_escape_text(&tags.join(", ")));
+            }
+            row.add_row(&path_row);
+        }
+        row
+    }
+}
@@ -2,5 +2,35 @@
 pub mod window;
 pub mod compatdata_page;
 pub mod settings;
+pub mod sort_bar;
+pub mod space_page;
 pub mod games_page;
+pub mod activity_page;
+pub mod app_id_button;
+pub mod assign_dialog;
+pub mod cleanup_dialog;
+pub mod cloud_dialog;
+pub mod confirm;
+pub mod contribute_dialog;
+pub mod error_dialog;
+pub mod file_browser;
+pub mod ignore_dialog;
+pub mod import_dialog;
+pub mod inhibit;
+pub mod keynav;
+pub mod lan_sync_dialog;
+pub mod location_menu;
+pub mod manifest_page;
+pub mod open_with;
+pub mod prefix_backup_dialog;
+pub mod preview_pane;
+pub mod quota_dialog;
+pub mod recover_dialog;
+pub mod registry_dialog;
+pub mod rename_dialog;
+pub mod screenshot_gallery;
+pub mod search_match;
+pub mod size_history_dialog;
+pub mod steamos_check;
+pub mod trim_dialog;
 pub mod welcome_dialog; 
\ No newline at end of file
@@ -0,0 +1,86 @@
+use adw::prelude::*;
+use adw::{ActionRow, MessageDialog};
+use gtk::{gio, glib};
+use std::path::{Path, PathBuf};
+
+use crate::i18n::{gettext, gettext_f};
+use crate::ui::error_dialog;
+
+// Picks an installed app to open a save file with, e.g. a hex editor. Apps
+// registered for the file's type come first, then every other app.
+pub fn show(parent: Option<&gtk::Window>, path: &Path) {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.display().to_string());
+    let dialog = MessageDialog::builder()
+        .heading(gettext("Open With"))
+        .body(gettext_f("Choose an app to open {} with.", &[&name]))
+        .modal(true)
+        .build();
+    dialog.set_transient_for(parent);
+
+    let list = gtk::ListBox::new();
+    list.set_selection_mode(gtk::SelectionMode::None);
+    list.add_css_class("boxed-list");
+    for app in apps_for(path) {
+        let row = ActionRow::builder()
+            .title(glib::markup_escape_text(&app.name()).as_str())
+            .activatable(true)
+            .build();
+        let icon = app
+            .icon()
+            .map(|icon| gtk::Image::from_gicon(&icon))
+            .unwrap_or_else(|| gtk::Image::from_icon_name("application-x-executable-symbolic"));
+        icon.set_pixel_size(32);
+        row.add_prefix(&icon);
+        let dialog_clone = dialog.clone();
+        let path = path.to_path_buf();
+        row.connect_activated(move |_| {
+            dialog_clone.close();
+            launch(dialog_clone.transient_for().as_ref(), &app, path.clone());
+        });
+        list.append(&row);
+    }
+    let scrolled = gtk::ScrolledWindow::builder()
+        .hscrollbar_policy(gtk::PolicyType::Never)
+        .min_content_height(320)
+        .child(&list)
+        .build();
+    dialog.set_extra_child(Some(&scrolled));
+    dialog.add_response("cancel", &gettext("Cancel"));
+    dialog.set_close_response("cancel");
+    dialog.present();
+}
+
+// Apps for the file's content type, then the rest, each listed once
+fn apps_for(path: &Path) -> Vec<gio::AppInfo> {
+    let (content_type, _) = gio::content_type_guess(Some(path), &[]);
+    let mut apps = gio::AppInfo::recommended_for_type(&content_type);
+    apps.extend(gio::AppInfo::all_for_type(&content_type));
+    let mut other: Vec<gio::AppInfo> = gio::AppInfo::all().into_iter().filter(|app| app.should_show()).collect();
+    other.sort_by_key(|app| app.name().to_lowercase());
+    apps.extend(other);
+
+    let mut seen = Vec::new();
+    apps.retain(|app| {
+        let id = app.id().map(|id| id.to_string()).unwrap_or_else(|| app.name().to_string());
+        if seen.contains(&id) {
+            false
+        } else {
+            seen.push(id);
+            true
+        }
+    });
+    apps
+}
+
+fn launch(parent: Option<&gtk::Window>, app: &gio::AppInfo, path: PathBuf) {
+    println!("Opening {} with {}", path.display(), app.name());
+    let context = gtk::gdk::Display::default().map(|display| display.app_launch_context());
+    if let Err(e) = app.launch(&[gio::File::for_path(&path)], context.as_ref()) {
+        eprintln!("Failed to open {} with {}: {}", path.display(), app.name(), e);
+        let error = anyhow::Error::new(e).context(gettext_f("{} could not open {}", &[&app.name(), &path.display().to_string()]));
+        error_dialog::show(parent, &gettext("Opening Failed"), &error);
+    }
+}
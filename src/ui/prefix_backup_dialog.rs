@@ -0,0 +1,265 @@
+use adw::prelude::*;
+use adw::{EntryRow, MessageDialog, SwitchRow};
+use gtk::{gio, glib, FileDialog, FileFilter};
+use humansize::{format_size, DECIMAL};
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use crate::activity::{self, ActivityEntry, ActivityKind};
+use crate::compatdata::{self, PrefixData};
+use crate::config::{Config, ConfirmAction, PrefixBackupOptions};
+use crate::fsutil;
+use crate::i18n::{gettext, gettext_f};
+use crate::prefix_backup;
+use crate::snapshot::Snapshot;
+use crate::ui::{confirm, error_dialog};
+
+// Ask what to leave out and where to put the archive, then back up the whole
+// prefix of `game_id` in the background
+pub fn backup(window: &adw::ApplicationWindow, config: &Rc<RefCell<Config>>, toast_overlay: &adw::ToastOverlay, game_id: &str) {
+    glib::MainContext::default().spawn_local(backup_async(window.clone(), config.clone(), toast_overlay.clone(), game_id.to_string()));
+}
+
+// Pick a full backup and put it in place of the prefix of `game_id`
+pub fn restore(window: &adw::ApplicationWindow, config: &Rc<RefCell<Config>>, toast_overlay: &adw::ToastOverlay, game_id: &str) {
+    glib::MainContext::default().spawn_local(restore_async(window.clone(), config.clone(), toast_overlay.clone(), game_id.to_string()));
+}
+
+// Back up the saves of `game_id`, then delete Wine's part of its prefix for
+// Proton to set up again on the next launch
+pub fn repair(window: &adw::ApplicationWindow, config: &Rc<RefCell<Config>>, toast_overlay: &adw::ToastOverlay, game_id: &str) {
+    glib::MainContext::default().spawn_local(repair_async(window.clone(), config.clone(), toast_overlay.clone(), game_id.to_string()));
+}
+
+fn archive_filters() -> gio::ListStore {
+    let filters = gio::ListStore::new::<FileFilter>();
+    let filter = FileFilter::new();
+    filter.set_name(Some(&gettext("Prefix Backups")));
+    for pattern in ["*.tar", "*.tar.zst", "*.tar.gz"] {
+        filter.add_pattern(pattern);
+    }
+    filters.append(&filter);
+    filters
+}
+
+async fn backup_async(window: adw::ApplicationWindow, config: Rc<RefCell<Config>>, toast_overlay: adw::ToastOverlay, game_id: String) {
+    let prefix_path = config.borrow().prefix_path(&game_id);
+    let options = config.borrow().prefix_backup_options().clone();
+    let dialog = MessageDialog::builder()
+        .transient_for(&window)
+        .heading(gettext_f("Back Up Whole Prefix for Game ID {}?", &[&game_id]))
+        .body(gettext_f(
+            "Archives everything in the prefix ({}), not only the detected saves. Meant for games that keep their saves somewhere the manifest doesn't know about.",
+            &[&format_size(fsutil::disk_usage(&prefix_path), DECIMAL)],
+        ))
+        .build();
+    let shader_row = SwitchRow::builder()
+        .title(gettext("Include Shader Caches"))
+        .subtitle(gettext("DXVK and vkd3d-proton rebuild them on their own"))
+        .active(options.include_shader_caches)
+        .build();
+    let system_row = SwitchRow::builder()
+        .title(gettext("Leave Out Wine System Folders"))
+        .subtitle(gettext("windows and Common Files, kept from the current prefix when restoring"))
+        .active(options.skip_system_dirs)
+        .build();
+    let excludes_row = EntryRow::builder()
+        .title(gettext("Also Leave Out (comma separated, e.g. *.log)"))
+        .text(options.excludes.join(", "))
+        .build();
+    let list = gtk::ListBox::new();
+    list.set_selection_mode(gtk::SelectionMode::None);
+    list.add_css_class("boxed-list");
+    list.append(&shader_row);
+    list.append(&system_row);
+    list.append(&excludes_row);
+    dialog.set_extra_child(Some(&list));
+    dialog.add_response("cancel", &gettext("Cancel"));
+    dialog.add_response("choose", &gettext("Choose File…"));
+    dialog.set_response_appearance("choose", adw::ResponseAppearance::Suggested);
+    dialog.set_default_response(Some("choose"));
+    dialog.set_close_response("cancel");
+    if dialog.choose_future().await != "choose" {
+        return;
+    }
+
+    let options = PrefixBackupOptions {
+        include_shader_caches: shader_row.is_active(),
+        skip_system_dirs: system_row.is_active(),
+        excludes: excludes_row.text().split(',').map(str::to_string).collect(),
+    };
+    if let Err(e) = config.borrow_mut().set_prefix_backup_options(options) {
+        eprintln!("Failed to save the prefix backup options: {}", e);
+    }
+    let (options, compression) = {
+        let config = config.borrow();
+        (config.prefix_backup_options().clone(), config.snapshot_compression())
+    };
+
+    let name = config.borrow().display_name(&game_id).map(fsutil::sanitize_file_name);
+    let file_name = match name {
+        Some(name) => format!("{} - {} prefix{}", game_id, name, prefix_backup::archive_extension(compression.method)),
+        None => format!("{} prefix{}", game_id, prefix_backup::archive_extension(compression.method)),
+    };
+    let file_dialog = FileDialog::new();
+    file_dialog.set_title(&gettext("Back Up Whole Prefix"));
+    file_dialog.set_initial_name(Some(&file_name));
+    file_dialog.set_filters(Some(&archive_filters()));
+    let Ok(file) = file_dialog.save_future(Some(&window)).await else {
+        return; // Cancelled
+    };
+    let Some(archive) = file.path() else {
+        return;
+    };
+
+    let progress_toast = adw::Toast::builder()
+        .title(gettext_f("Backing up prefix for Game ID {}…", &[&game_id]))
+        .timeout(0)
+        .build();
+    toast_overlay.add_toast(progress_toast.clone());
+    let archive_clone = archive.clone();
+    let source = prefix_path.clone();
+    let result = gio::spawn_blocking(move || prefix_backup::backup(&source, &archive_clone, compression, &options)).await;
+    progress_toast.dismiss();
+    match result {
+        Ok(Ok(size)) => {
+            let config = config.borrow();
+            activity::record(
+                &config,
+                ActivityEntry::new(ActivityKind::Backup, "Backed up the whole prefix")
+                    .game(&config, &game_id)
+                    .paths([archive.clone()])
+                    .bytes(size),
+            );
+            toast_overlay.add_toast(adw::Toast::new(&gettext_f("Prefix backed up to {}", &[&archive.display().to_string()])));
+        }
+        Ok(Err(e)) => {
+            eprintln!("Error backing up prefix {}: {:#}", prefix_path.display(), e);
+            error_dialog::show(Some(window.upcast_ref()), &gettext("Error Backing Up Prefix"), &e);
+        }
+        Err(_) => eprintln!("Backing up the prefix panicked"),
+    }
+}
+
+async fn restore_async(window: adw::ApplicationWindow, config: Rc<RefCell<Config>>, toast_overlay: adw::ToastOverlay, game_id: String) {
+    let file_dialog = FileDialog::new();
+    file_dialog.set_title(&gettext("Restore Whole Prefix"));
+    file_dialog.set_filters(Some(&archive_filters()));
+    let Ok(file) = file_dialog.open_future(Some(&window)).await else {
+        return; // Cancelled
+    };
+    let Some(archive) = file.path() else {
+        return;
+    };
+
+    let dialog = MessageDialog::builder()
+        .transient_for(&window)
+        .heading(gettext_f("Replace Prefix for Game ID {}?", &[&game_id]))
+        .body(gettext_f(
+            "The whole prefix is replaced with the one in {}. Detected save folders are kept in a safety snapshot first, everything else in the current prefix is removed. Close the game before restoring.",
+            &[&archive.display().to_string()],
+        ))
+        .build();
+    dialog.add_response("cancel", &gettext("Cancel"));
+    dialog.add_response("restore", &gettext("Restore"));
+    dialog.set_response_appearance("restore", adw::ResponseAppearance::Destructive);
+    dialog.set_default_response(Some("cancel"));
+    dialog.set_close_response("cancel");
+    if !confirm::ask(&config, dialog, ConfirmAction::Replace, "restore-prefix", "restore").await {
+        return;
+    }
+
+    // Only what the scan knows about, a snapshot of the whole prefix would
+    // double the disk space needed
+    let prefix_path = config.borrow().prefix_path(&game_id);
+    let mut prefix = PrefixData::at(prefix_path.clone(), &game_id);
+    let _ = prefix.scan_save_locations(&config.borrow());
+    let save_paths: Vec<PathBuf> = prefix.save_locations.iter().flat_map(|location| location.entries.iter().map(|entry| entry.path.clone())).collect();
+    if let Err(e) = Snapshot::create(&config.borrow(), &game_id, "Before restoring a full prefix backup", &save_paths) {
+        error_dialog::show(Some(window.upcast_ref()), &gettext("Prefix Not Restored"), &e.context(gettext("Could not create a safety snapshot, the prefix was not replaced")));
+        return;
+    }
+
+    let progress_toast = adw::Toast::builder()
+        .title(gettext_f("Restoring prefix for Game ID {}…", &[&game_id]))
+        .timeout(0)
+        .build();
+    toast_overlay.add_toast(progress_toast.clone());
+    let archive_clone = archive.clone();
+    let target = prefix_path.clone();
+    let result = gio::spawn_blocking(move || prefix_backup::restore(&archive_clone, &target)).await;
+    progress_toast.dismiss();
+    match result {
+        Ok(Ok(())) => {
+            let config = config.borrow();
+            activity::record(
+                &config,
+                ActivityEntry::new(ActivityKind::Restore, format!("Restored the whole prefix from {}", archive.display()))
+                    .game(&config, &game_id)
+                    .paths([prefix_path.clone()]),
+            );
+            toast_overlay.add_toast(adw::Toast::new(&gettext_f("Prefix for Game ID {} restored", &[&game_id])));
+            WidgetExt::activate_action(&window, "app.refresh", None).ok();
+        }
+        Ok(Err(e)) => {
+            eprintln!("Error restoring prefix {}: {:#}", prefix_path.display(), e);
+            error_dialog::show(Some(window.upcast_ref()), &gettext("Error Restoring Prefix"), &e);
+        }
+        Err(_) => eprintln!("Restoring the prefix panicked"),
+    }
+}
+
+async fn repair_async(window: adw::ApplicationWindow, config: Rc<RefCell<Config>>, toast_overlay: adw::ToastOverlay, game_id: String) {
+    let dialog = MessageDialog::builder()
+        .transient_for(&window)
+        .heading(gettext_f("Repair Prefix for Game ID {}?", &[&game_id]))
+        .body(gettext(
+            "Wine's part of the prefix is deleted and Steam sets it up again the next time the game starts, which fixes most prefixes that stopped working. The users folder and detected save folders are kept, and the saves and the registry go into a safety snapshot first. Installed runtimes and registry tweaks (protontricks, winecfg) have to be redone. Close the game before repairing.",
+        ))
+        .build();
+    dialog.add_response("cancel", &gettext("Cancel"));
+    dialog.add_response("repair", &gettext("Repair"));
+    dialog.set_response_appearance("repair", adw::ResponseAppearance::Destructive);
+    dialog.set_default_response(Some("cancel"));
+    dialog.set_close_response("cancel");
+    if !confirm::ask(&config, dialog, ConfirmAction::Delete, "repair-prefix", "repair").await {
+        return;
+    }
+
+    let prefix_path = config.borrow().prefix_path(&game_id);
+    let mut prefix = PrefixData::at(prefix_path.clone(), &game_id);
+    let _ = prefix.scan_save_locations(&config.borrow());
+    let save_paths: Vec<PathBuf> = prefix.save_locations.iter().flat_map(|location| location.entries.iter().map(|entry| entry.path.clone())).collect();
+    // The registry is rebuilt too, some games keep their settings or saves in it
+    let mut snapshot_paths = save_paths.clone();
+    snapshot_paths.extend(["pfx/user.reg", "pfx/system.reg"].iter().map(|name| prefix_path.join(name)).filter(|path| path.exists()));
+    if let Err(e) = Snapshot::create(&config.borrow(), &game_id, "Before repairing the prefix", &snapshot_paths) {
+        error_dialog::show(Some(window.upcast_ref()), &gettext("Prefix Not Repaired"), &e.context(gettext("Could not create a safety snapshot, nothing was deleted")));
+        return;
+    }
+
+    let target = prefix_path.clone();
+    let result = gio::spawn_blocking(move || compatdata::repair_prefix(&target, &save_paths)).await;
+    match result {
+        Ok(Ok(removed)) => {
+            let config = config.borrow();
+            activity::record(
+                &config,
+                ActivityEntry::new(ActivityKind::Delete, "Repaired the prefix")
+                    .game(&config, &game_id)
+                    .paths([prefix_path.clone()])
+                    .bytes(removed),
+            );
+            toast_overlay.add_toast(adw::Toast::new(&gettext_f("Prefix for Game ID {} repaired, Steam sets it up again on the next launch", &[&game_id])));
+            WidgetExt::activate_action(&window, "app.refresh", None).ok();
+        }
+        Ok(Err(e)) => {
+            eprintln!("Error repairing prefix {}: {:#}", prefix_path.display(), e);
+            error_dialog::show(Some(window.upcast_ref()), &gettext("Error Repairing Prefix"), &e);
+            // Part of it may be gone already
+            WidgetExt::activate_action(&window, "app.refresh", None).ok();
+        }
+        Err(_) => eprintln!("Repairing the prefix panicked"),
+    }
+}
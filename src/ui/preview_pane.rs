@@ -0,0 +1,174 @@
+use adw::prelude::*;
+use adw::{ActionRow, ExpanderRow};
+use gtk::glib;
+use humansize::{format_size, DECIMAL};
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use crate::config::Config;
+use crate::i18n::{gettext, gettext_f};
+use crate::preview::{self, PreviewContent, PreviewFile};
+use crate::report;
+use crate::save_editor;
+use crate::ui::{error_dialog, open_with};
+
+// Pane listing the files of a save with their thumbnail or contents, so save
+// slots can be told apart before restoring or exporting them. `paths` are
+// pairs of the file or folder to preview and the name shown for it. With
+// `config`, files can be opened in another app or the save editor set for them.
+pub fn build(paths: &[(PathBuf, String)], config: Option<&Config>) -> gtk::Widget {
+    let files: Vec<PreviewFile> = paths.iter().flat_map(|(path, name)| preview::preview(path, name)).collect();
+    if files.is_empty() {
+        let label = gtk::Label::new(Some(&gettext("No files to preview")));
+        label.add_css_class("dim-label");
+        label.set_margin_top(12);
+        label.set_margin_bottom(12);
+        return label.upcast();
+    }
+
+    let list = gtk::ListBox::new();
+    list.set_selection_mode(gtk::SelectionMode::None);
+    list.add_css_class("boxed-list");
+    list.set_valign(gtk::Align::Start);
+    for file in &files {
+        let row = create_file_row(file);
+        if let Some(config) = config {
+            add_open_buttons(&row, file, config.editor_command_for(&file.path));
+        }
+        list.append(&row);
+    }
+    list.upcast()
+}
+
+// A window with the preview of one save location, from its row's menu
+pub fn show(parent: Option<&gtk::Window>, path: PathBuf, config: &Rc<RefCell<Config>>) {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.display().to_string());
+    let window = adw::Window::builder()
+        .modal(true)
+        .title(gettext_f("Preview of {}", &[&name]))
+        .default_width(560)
+        .default_height(600)
+        .build();
+    if let Some(parent) = parent {
+        window.set_transient_for(Some(parent));
+    }
+
+    let content = gtk::Box::new(gtk::Orientation::Vertical, 12);
+    content.set_margin_start(12);
+    content.set_margin_end(12);
+    content.set_margin_top(12);
+    content.set_margin_bottom(12);
+    content.append(&build(&[(path, name)], Some(&config.borrow())));
+    let scrolled = gtk::ScrolledWindow::builder()
+        .hscrollbar_policy(gtk::PolicyType::Never)
+        .vexpand(true)
+        .child(&content)
+        .build();
+
+    let toolbar = adw::ToolbarView::new();
+    toolbar.add_top_bar(&adw::HeaderBar::new());
+    toolbar.set_content(Some(&scrolled));
+    window.set_content(Some(&toolbar));
+    window.present();
+}
+
+// "Open With…", and "Open in <editor>" when one is set for the file's extension
+fn add_open_buttons(row: &gtk::Widget, file: &PreviewFile, editor: Option<&str>) {
+    let mut buttons = Vec::new();
+    if let Some(editor) = editor {
+        let program = editor.split_whitespace().next().unwrap_or(editor);
+        let editor_button = gtk::Button::from_icon_name("document-edit-symbolic");
+        editor_button.set_tooltip_text(Some(&gettext_f("Open in {}", &[program])));
+        let editor = editor.to_string();
+        let path = file.path.clone();
+        editor_button.connect_clicked(move |button| {
+            if let Err(e) = save_editor::open(&editor, &path) {
+                eprintln!("Failed to open {} in {}: {:#}", path.display(), editor, e);
+                let parent = button.root().and_downcast::<gtk::Window>();
+                error_dialog::show(parent.as_ref(), &gettext("Opening Failed"), &e);
+            }
+        });
+        buttons.push(editor_button);
+    }
+    let open_with_button = gtk::Button::from_icon_name("document-open-symbolic");
+    open_with_button.set_tooltip_text(Some(&gettext("Open With…")));
+    let path = file.path.clone();
+    open_with_button.connect_clicked(move |button| {
+        let parent = button.root().and_downcast::<gtk::Window>();
+        open_with::show(parent.as_ref(), &path);
+    });
+    buttons.push(open_with_button);
+
+    for button in buttons {
+        button.set_valign(gtk::Align::Center);
+        button.add_css_class("flat");
+        if let Some(row) = row.downcast_ref::<ExpanderRow>() {
+            row.add_suffix(&button);
+        } else if let Some(row) = row.downcast_ref::<ActionRow>() {
+            row.add_suffix(&button);
+        }
+    }
+}
+
+fn create_file_row(file: &PreviewFile) -> gtk::Widget {
+    let mut subtitle = Vec::new();
+    if let Some(modified) = file.modified {
+        subtitle.push(report::format_timestamp_utc(modified));
+    }
+    subtitle.push(format_size(file.size, DECIMAL));
+    let title = glib::markup_escape_text(&file.name);
+    let subtitle = glib::markup_escape_text(&subtitle.join(" | "));
+
+    match &file.content {
+        PreviewContent::Image => {
+            let row = ExpanderRow::builder().title(title.as_str()).subtitle(subtitle.as_str()).build();
+            let thumbnail = gtk::Image::from_file(&file.path);
+            thumbnail.set_pixel_size(32);
+            row.add_prefix(&thumbnail);
+            let picture = gtk::Picture::for_filename(&file.path);
+            picture.set_content_fit(gtk::ContentFit::Contain);
+            picture.set_height_request(240);
+            picture.set_margin_top(6);
+            picture.set_margin_bottom(6);
+            row.add_row(&picture);
+            row.upcast()
+        }
+        PreviewContent::Text { text, truncated } => {
+            let row = ExpanderRow::builder().title(title.as_str()).subtitle(subtitle.as_str()).build();
+            row.add_prefix(&gtk::Image::from_icon_name("text-x-generic-symbolic"));
+            let mut text = text.clone();
+            if *truncated {
+                text.push_str(&format!("\n{}", gettext("… (file is too long to show in full)")));
+            }
+            let label = gtk::Label::builder()
+                .label(text)
+                .selectable(true)
+                .wrap(true)
+                .wrap_mode(gtk::pango::WrapMode::WordChar)
+                .xalign(0.0)
+                .margin_start(12)
+                .margin_end(12)
+                .margin_top(6)
+                .margin_bottom(6)
+                .build();
+            label.add_css_class("monospace");
+            let scrolled = gtk::ScrolledWindow::builder()
+                .hscrollbar_policy(gtk::PolicyType::Never)
+                .max_content_height(320)
+                .propagate_natural_height(true)
+                .child(&label)
+                .build();
+            row.add_row(&scrolled);
+            row.upcast()
+        }
+        PreviewContent::Other => {
+            let row = ActionRow::builder().title(title.as_str()).subtitle(subtitle.as_str()).build();
+            row.add_prefix(&gtk::Image::from_icon_name("document-save-symbolic"));
+            row.upcast()
+        }
+    }
+}
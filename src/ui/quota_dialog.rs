@@ -0,0 +1,63 @@
+use adw::prelude::*;
+use adw::{MessageDialog, SpinRow};
+use humansize::{format_size, DECIMAL};
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use crate::config::Config;
+use crate::i18n::{gettext, gettext_f};
+use crate::ui::{error_dialog, preview_pane};
+
+// Shown from the warning on a save location over its size limit: what to do
+// about it, and the limit for this one game
+pub fn show(parent: Option<&gtk::Window>, config: Rc<RefCell<Config>>, app_id: &str, name: &str, path: PathBuf, size: u64) {
+    let (limit, global_mb, current_mb) = {
+        let config = config.borrow();
+        let global_mb = config.save_quota().max_mb;
+        let current_mb = config.save_quota_overrides().get(app_id).copied().unwrap_or(global_mb);
+        (config.save_quota_for(app_id).unwrap_or_default(), global_mb, current_mb)
+    };
+    let dialog = MessageDialog::builder()
+        .heading(gettext("Save Folder Over Size Limit"))
+        .body(gettext_f(
+            "A save folder of {} takes {}, more than its limit of {}. Games that write a screenshot or a new file with every save fill up folders like this. Preview the files to see what takes the space, then back them up and delete old ones, or raise the limit for this game.",
+            &[name, &format_size(size, DECIMAL), &format_size(limit, DECIMAL)],
+        ))
+        .modal(true)
+        .build();
+    dialog.set_transient_for(parent);
+
+    let limit_row = SpinRow::with_range(0.0, 1_000_000.0, 10.0);
+    limit_row.set_title(&gettext("Limit for This Game (MB)"));
+    limit_row.set_subtitle(&gettext_f("0 switches the warning off for this game. The global limit is {} MB.", &[&global_mb.to_string()]));
+    limit_row.set_value(current_mb as f64);
+    let list = gtk::ListBox::new();
+    list.set_selection_mode(gtk::SelectionMode::None);
+    list.add_css_class("boxed-list");
+    list.append(&limit_row);
+    dialog.set_extra_child(Some(&list));
+
+    dialog.add_response("close", &gettext("Close"));
+    dialog.add_response("preview", &gettext("Preview Files"));
+    dialog.add_response("save", &gettext("Set Limit"));
+    dialog.set_response_appearance("save", adw::ResponseAppearance::Suggested);
+    dialog.set_default_response(Some("close"));
+    dialog.set_close_response("close");
+
+    let app_id = app_id.to_string();
+    dialog.connect_response(None, move |dialog, response| match response {
+        "preview" => preview_pane::show(dialog.transient_for().as_ref(), path.clone(), &config),
+        "save" => {
+            let max_mb = limit_row.value() as u64;
+            // Back at the global limit, the game doesn't need its own
+            let max_mb = (max_mb != global_mb).then_some(max_mb);
+            if let Err(e) = config.borrow_mut().set_save_quota_override(&app_id, max_mb) {
+                eprintln!("Error saving size limit of {}: {}", app_id, e);
+                error_dialog::show(dialog.transient_for().as_ref(), &gettext("Error Saving Settings"), &e);
+            }
+        }
+        _ => {}
+    });
+    dialog.present();
+}
@@ -0,0 +1,506 @@
+use adw::prelude::*;
+use adw::{ActionRow, MessageDialog, NavigationPage, NavigationView, PreferencesGroup, PreferencesPage, StatusPage};
+use gtk::glib;
+use humansize::{format_size, DECIMAL};
+use std::cell::RefCell;
+use std::path::Path;
+use std::rc::Rc;
+
+use crate::config::{Config, ConfirmAction};
+use crate::i18n::{gettext, gettext_f};
+use crate::recover::{self, RecoverGame, RecoveryCandidate, RecoverySource};
+use crate::report;
+use crate::snapshot::{self, SnapshotEntry};
+use crate::steam;
+use crate::ui::inhibit::Inhibitor;
+use crate::ui::{confirm, preview_pane};
+
+// "Recover a save" wizard for when a game lost its save: pick the game, pick
+// one of the copies found for it, confirm where it goes. Every restore takes a
+// safety snapshot of the current files first.
+pub fn show(parent: &impl IsA<gtk::Window>, config: Rc<RefCell<Config>>, games: Vec<RecoverGame>) {
+    let window = adw::Window::builder()
+        .transient_for(parent)
+        .modal(true)
+        .title(gettext("Recover a Save"))
+        .default_width(560)
+        .default_height(640)
+        .build();
+    let navigation = NavigationView::new();
+    navigation.add(&game_page(&window, &navigation, config, games));
+    window.set_content(Some(&navigation));
+    window.present();
+}
+
+// Wraps page content in a header bar
+fn wizard_page(title: &str, content: &impl IsA<gtk::Widget>) -> NavigationPage {
+    let toolbar = adw::ToolbarView::new();
+    toolbar.add_top_bar(&adw::HeaderBar::new());
+    toolbar.set_content(Some(content));
+    NavigationPage::builder().title(title).child(&toolbar).build()
+}
+
+// Step 1: which game
+fn game_page(window: &adw::Window, navigation: &NavigationView, config: Rc<RefCell<Config>>, games: Vec<RecoverGame>) -> NavigationPage {
+    let content = gtk::Box::new(gtk::Orientation::Vertical, 12);
+    content.set_margin_start(12);
+    content.set_margin_end(12);
+    content.set_margin_top(12);
+    content.set_margin_bottom(12);
+
+    let intro = gtk::Label::new(Some(&gettext("Pick the game that lost its save. Nothing is changed until you confirm a restore.")));
+    intro.set_wrap(true);
+    intro.set_xalign(0.0);
+    content.append(&intro);
+
+    let search_entry = gtk::SearchEntry::new();
+    search_entry.set_placeholder_text(Some(&gettext("Search Games or App IDs...")));
+    content.append(&search_entry);
+
+    let list = gtk::ListBox::new();
+    list.set_selection_mode(gtk::SelectionMode::None);
+    list.add_css_class("boxed-list");
+    list.set_valign(gtk::Align::Start);
+    let scrolled = gtk::ScrolledWindow::builder()
+        .hscrollbar_policy(gtk::PolicyType::Never)
+        .vexpand(true)
+        .child(&list)
+        .build();
+    content.append(&scrolled);
+
+    let games: Vec<Rc<RecoverGame>> = games.into_iter().map(Rc::new).collect();
+    if games.is_empty() {
+        let hint = gtk::Label::new(Some(&gettext("No games with known save locations were found. Refresh the lists and try again.")));
+        hint.set_wrap(true);
+        hint.add_css_class("dim-label");
+        content.append(&hint);
+    }
+    for game in &games {
+        let row = ActionRow::builder()
+            .title(glib::markup_escape_text(&game.name).as_str())
+            .subtitle(gettext_f("App ID: {}", &[&game.app_id]))
+            .activatable(true)
+            .build();
+        row.add_suffix(&gtk::Image::from_icon_name("go-next-symbolic"));
+        let window = window.clone();
+        let navigation = navigation.clone();
+        let config = config.clone();
+        let game = game.clone();
+        row.connect_activated(move |_| {
+            let candidates = recover::find_candidates(&config.borrow(), &game);
+            navigation.push(&candidate_page(&window, &navigation, config.clone(), game.clone(), candidates));
+        });
+        list.append(&row);
+    }
+
+    // Rows are in the same order as `games`
+    let query = Rc::new(RefCell::new(String::new()));
+    let query_clone = query.clone();
+    list.set_filter_func(move |row| {
+        let query = query_clone.borrow();
+        let Some(game) = games.get(row.index() as usize) else {
+            return true;
+        };
+        query.is_empty() || game.name.to_lowercase().contains(query.as_str()) || game.app_id.contains(query.as_str())
+    });
+    search_entry.connect_search_changed(glib::clone!(@weak list => move |entry| {
+        query.replace(entry.text().to_lowercase());
+        list.invalidate_filter();
+    }));
+    search_entry.set_key_capture_widget(Some(window));
+
+    wizard_page(&gettext("Recover a Save"), &content)
+}
+
+// Step 2: which copy, grouped by where it was found
+fn candidate_page(
+    window: &adw::Window,
+    navigation: &NavigationView,
+    config: Rc<RefCell<Config>>,
+    game: Rc<RecoverGame>,
+    candidates: Vec<RecoveryCandidate>,
+) -> NavigationPage {
+    if candidates.is_empty() {
+        let status = StatusPage::builder()
+            .icon_name("edit-find-symbolic")
+            .title(gettext("Nothing Found"))
+            .description(gettext_f(
+                "There are no safety snapshots, Steam Cloud copies or backup files for {}.",
+                &[&glib::markup_escape_text(&game.name)],
+            ))
+            .build();
+        return wizard_page(&game.name, &status);
+    }
+
+    let page = PreferencesPage::new();
+    let groups = [
+        (gettext("Safety Snapshots"), gettext("Copies this app took before deleting or replacing saves")),
+        (gettext("Steam Cloud Copies"), gettext("Files Steam last synced for this game")),
+        (gettext("Backup Files"), gettext("Backups and autosaves the game wrote into its save folders")),
+        (gettext("Recently Changed Files"), gettext("Files elsewhere in the prefix written in the last week, in case the game saved somewhere else")),
+    ]
+    .map(|(title, description)| PreferencesGroup::builder().title(title).description(description).build());
+    let mut group_sizes = [0; 4];
+
+    for candidate in candidates {
+        let index = match candidate.source {
+            RecoverySource::Snapshot(_) => 0,
+            RecoverySource::Cloud(_) => 1,
+            RecoverySource::BackupFile { .. } => 2,
+            RecoverySource::PrefixFile { .. } if candidate_in_locations(&candidate, &game) => 2,
+            RecoverySource::PrefixFile { .. } => 3,
+        };
+        group_sizes[index] += 1;
+        let row = ActionRow::builder()
+            .title(glib::markup_escape_text(&candidate.label()).as_str())
+            .subtitle(glib::markup_escape_text(&candidate_subtitle(&candidate)).as_str())
+            .activatable(true)
+            .build();
+        row.add_suffix(&gtk::Image::from_icon_name("go-next-symbolic"));
+        let candidate = Rc::new(candidate);
+        let window = window.clone();
+        let navigation_clone = navigation.clone();
+        let config = config.clone();
+        let game = game.clone();
+        row.connect_activated(move |_| {
+            navigation_clone.push(&confirm_page(&window, &navigation_clone, config.clone(), game.clone(), candidate.clone()));
+        });
+        groups[index].add(&row);
+    }
+    // Only show the kinds of copies that were found
+    for (group, size) in groups.iter().zip(group_sizes) {
+        if size > 0 {
+            page.add(group);
+        }
+    }
+    wizard_page(&game.name, &page)
+}
+
+fn candidate_in_locations(candidate: &RecoveryCandidate, game: &RecoverGame) -> bool {
+    let RecoverySource::PrefixFile { path } = &candidate.source else {
+        return false;
+    };
+    game.locations.iter().any(|location| path.starts_with(location))
+}
+
+fn candidate_subtitle(candidate: &RecoveryCandidate) -> String {
+    let mut parts = Vec::new();
+    if let Some(modified) = candidate.modified {
+        parts.push(report::format_timestamp_utc(modified));
+    }
+    parts.push(format_size(candidate.size, DECIMAL));
+    match &candidate.source {
+        RecoverySource::Snapshot(snapshot) => {
+            parts.push(gettext_f("{} items", &[&snapshot.items.len().to_string()]));
+            if let (Some(name), Some(ratio)) = (snapshot.compression.name(), snapshot.compression_ratio()) {
+                parts.push(gettext_f("{}, {}× smaller", &[name, &format!("{:.1}", ratio)]));
+            }
+        }
+        RecoverySource::Cloud(file) => parts.push(gettext_f("Account {}", &[&file.account_id])),
+        RecoverySource::BackupFile { path, .. } | RecoverySource::PrefixFile { path } => {
+            if let Some(parent) = path.parent() {
+                parts.push(parent.display().to_string());
+            }
+        }
+    }
+    parts.join(" | ")
+}
+
+// Step 3: what gets replaced, then restore
+fn confirm_page(
+    window: &adw::Window,
+    navigation: &NavigationView,
+    config: Rc<RefCell<Config>>,
+    game: Rc<RecoverGame>,
+    candidate: Rc<RecoveryCandidate>,
+) -> NavigationPage {
+    let page = PreferencesPage::new();
+    let group = PreferencesGroup::builder()
+        .title(glib::markup_escape_text(&candidate.label()).as_str())
+        .description(gettext("The files below are replaced. A safety snapshot of them is taken first, so the restore can itself be undone from this wizard."))
+        .build();
+    page.add(&group);
+
+    let destination_row = ActionRow::builder().title(gettext("Replaces")).build();
+    destination_row.add_css_class("property");
+
+    // Copies without a fixed destination need one of the game's save folders
+    let target_row = adw::ComboRow::builder().title(gettext("Restore Into")).build();
+    let locations: Vec<String> = game.locations.iter().map(|p| p.display().to_string()).collect();
+    let location_refs: Vec<&str> = locations.iter().map(|s| s.as_str()).collect();
+    target_row.set_model(Some(&gtk::StringList::new(&location_refs)));
+    if candidate.needs_target() {
+        group.add(&target_row);
+    }
+    group.add(&destination_row);
+
+    // Some games can't load saves written by an older version of them
+    if let RecoverySource::Snapshot(snapshot) = &candidate.source {
+        // Counted from the stored files against the ones there now
+        let impact_row = ActionRow::builder().title(gettext("Changes")).build();
+        impact_row.add_css_class("property");
+        match snapshot.restore_impact() {
+            Ok(impact) => {
+                let change = format_size(impact.size_change.unsigned_abs(), DECIMAL);
+                let change = if impact.size_change < 0 { format!("−{}", change) } else { format!("+{}", change) };
+                impact_row.set_subtitle(&gettext_f(
+                    "{} files replaced, {} files added, {} in size",
+                    &[&impact.overwritten.to_string(), &impact.added.to_string(), &change],
+                ));
+            }
+            Err(e) => {
+                eprintln!("Failed to preview the restore of {}: {:#}", snapshot.dir.display(), e);
+                impact_row.set_subtitle(&gettext("Unknown, the snapshot couldn't be read"));
+            }
+        }
+        group.add(&impact_row);
+
+
+        let current_build = steam::build_id(&config.borrow(), &game.app_id);
+        if snapshot.taken_under_older_build(current_build) {
+            let build_row = ActionRow::builder()
+                .title(gettext_f(
+                    "Game Build {} Instead of {}",
+                    &[&snapshot.build_id.unwrap_or_default().to_string(), &current_build.unwrap_or_default().to_string()],
+                ))
+                .subtitle(gettext("The game was updated since this snapshot was taken. Games usually load saves of older versions, but some don't, or convert them so the old version can't read them anymore."))
+                .build();
+            let warning_icon = gtk::Image::from_icon_name("dialog-warning-symbolic");
+            warning_icon.add_css_class("warning");
+            build_row.add_prefix(&warning_icon);
+            group.add(&build_row);
+        }
+    }
+
+    let update_destinations = glib::clone!(@weak destination_row, @weak target_row, @strong game, @strong candidate => move || {
+        let target_dir = game.locations.get(target_row.selected() as usize).map(|p| p.as_path());
+        let destinations: Vec<String> = candidate.destinations(target_dir).iter().map(|p| p.display().to_string()).collect();
+        let text = if destinations.is_empty() { gettext("Nothing") } else { destinations.join("\n") };
+        destination_row.set_subtitle(&glib::markup_escape_text(&text));
+    });
+    update_destinations();
+    target_row.connect_selected_notify(move |_| update_destinations());
+
+    // What's in the copy, to tell it apart from the other candidates
+    let preview_group = PreferencesGroup::builder().title(gettext("Contents")).build();
+    preview_group.add(&preview_pane::build(&candidate.preview_paths(), None));
+    page.add(&preview_group);
+    if let Some(single_files_group) = single_files_group(window, navigation, &config, &game, &candidate) {
+        page.add(&single_files_group);
+    }
+
+    let restore_button = gtk::Button::builder()
+        .label(gettext("Restore"))
+        .halign(gtk::Align::Center)
+        .margin_top(12)
+        .sensitive(!candidate.needs_target() || !game.locations.is_empty())
+        .build();
+    restore_button.add_css_class("suggested-action");
+    restore_button.add_css_class("pill");
+    let button_group = PreferencesGroup::new();
+    button_group.add(&restore_button);
+    page.add(&button_group);
+
+    let window = window.clone();
+    let navigation = navigation.clone();
+    restore_button.connect_clicked(move |_| {
+        let target_dir = if candidate.needs_target() {
+            game.locations.get(target_row.selected() as usize).map(|p| p.as_path())
+        } else {
+            None
+        };
+        let inhibitor = Inhibitor::new(&window, &gettext("Restoring a game save"));
+        let restored = candidate.restore(&config.borrow(), &game.app_id, target_dir).map(|restored| restored.len());
+        drop(inhibitor);
+        push_result_page(&window, &navigation, &game, restored);
+    });
+
+    wizard_page(&gettext("Confirm Restore"), &page)
+}
+
+// Result of a restore, `restored` being the number of paths put back
+fn push_result_page(window: &adw::Window, navigation: &NavigationView, game: &RecoverGame, restored: anyhow::Result<usize>) {
+    let status = match restored {
+        Ok(restored) => StatusPage::builder()
+            .icon_name("emblem-ok-symbolic")
+            .title(gettext("Save Restored"))
+            .description(gettext_f(
+                "Restored {} paths for {}. Launch the game to check the save; the replaced files are in a new safety snapshot.",
+                &[&restored.to_string(), &glib::markup_escape_text(&game.name)],
+            ))
+            .build(),
+        Err(e) => {
+            eprintln!("Error recovering save of {}: {:#}", game.app_id, e);
+            StatusPage::builder()
+                .icon_name("dialog-error-symbolic")
+                .title(gettext("Restore Failed"))
+                .description(glib::markup_escape_text(&format!("{:#}", e)).as_str())
+                .build()
+        }
+    };
+    let done_button = gtk::Button::builder()
+        .label(gettext("Done"))
+        .halign(gtk::Align::Center)
+        .build();
+    done_button.add_css_class("pill");
+    done_button.connect_clicked(glib::clone!(@weak window => move |_| window.close()));
+    status.set_child(Some(&done_button));
+    // Going back would offer the same restore again
+    let result_page = wizard_page(&gettext("Recover a Save"), &status);
+    result_page.set_can_pop(false);
+    navigation.push(&result_page);
+    WidgetExt::activate_action(window, "app.refresh", None).ok();
+}
+
+// Snapshots can also give back just one file or folder, when only one save
+// slot got corrupted and the others are fine as they are
+fn single_files_group(
+    window: &adw::Window,
+    navigation: &NavigationView,
+    config: &Rc<RefCell<Config>>,
+    game: &Rc<RecoverGame>,
+    candidate: &Rc<RecoveryCandidate>,
+) -> Option<PreferencesGroup> {
+    let RecoverySource::Snapshot(snapshot) = &candidate.source else {
+        return None;
+    };
+    let group = PreferencesGroup::builder()
+        .title(gettext("Restore Single Files"))
+        .description(gettext("Browse the snapshot and restore one file or folder, leaving the rest of the save as it is."))
+        .build();
+    // Read from the archive's headers for compressed snapshots, only
+    // restoring unpacks them
+    let stored = match snapshot.stored_entries() {
+        Ok(stored) => stored,
+        Err(e) => {
+            eprintln!("Failed to list snapshot {}: {:#}", snapshot.dir.display(), e);
+            return None;
+        }
+    };
+    for (index, item) in snapshot.items.iter().enumerate() {
+        let Some(mut entry) = snapshot::stored_entry(&stored, index, Path::new("")) else {
+            continue;
+        };
+        entry.name = item.original_path.display().to_string();
+        group.add(&entry_row(window, navigation, config, game, candidate, index, entry));
+    }
+    Some(group)
+}
+
+// The folders and files at one level of a snapshot item
+fn browse_page(
+    window: &adw::Window,
+    navigation: &NavigationView,
+    config: &Rc<RefCell<Config>>,
+    game: &Rc<RecoverGame>,
+    candidate: &Rc<RecoveryCandidate>,
+    item_index: usize,
+    relative: &Path,
+) -> NavigationPage {
+    let RecoverySource::Snapshot(snapshot) = &candidate.source else {
+        unreachable!("only snapshots are browsed");
+    };
+    let item = &snapshot.items[item_index];
+    let title = relative
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| recover::file_name(&item.original_path));
+    let entries = snapshot.entries(item, relative);
+    if entries.is_empty() {
+        let status = StatusPage::builder()
+            .icon_name("folder-symbolic")
+            .title(gettext("Empty Folder"))
+            .build();
+        return wizard_page(&title, &status);
+    }
+
+    let page = PreferencesPage::new();
+    let group = PreferencesGroup::builder()
+        .title(glib::markup_escape_text(&snapshot.original_path_of(item, relative).display().to_string()).as_str())
+        .build();
+    for entry in entries {
+        group.add(&entry_row(window, navigation, config, game, candidate, item_index, entry));
+    }
+    page.add(&group);
+    wizard_page(&title, &page)
+}
+
+// A file or folder with its own Restore button; folders open to show what's inside
+fn entry_row(
+    window: &adw::Window,
+    navigation: &NavigationView,
+    config: &Rc<RefCell<Config>>,
+    game: &Rc<RecoverGame>,
+    candidate: &Rc<RecoveryCandidate>,
+    item_index: usize,
+    entry: SnapshotEntry,
+) -> ActionRow {
+    let mut subtitle = Vec::new();
+    if let Some(modified) = entry.modified {
+        subtitle.push(report::format_timestamp_utc(modified));
+    }
+    subtitle.push(format_size(entry.size, DECIMAL));
+    let row = ActionRow::builder()
+        .title(glib::markup_escape_text(&entry.name).as_str())
+        .subtitle(subtitle.join(" | "))
+        .build();
+    row.add_prefix(&gtk::Image::from_icon_name(if entry.is_dir { "folder-symbolic" } else { "text-x-generic-symbolic" }));
+
+    let restore_button = gtk::Button::builder()
+        .label(gettext("Restore"))
+        .valign(gtk::Align::Center)
+        .build();
+    restore_button.add_css_class("flat");
+    row.add_suffix(&restore_button);
+    let relative = Rc::new(entry.relative);
+    restore_button.connect_clicked(glib::clone!(@weak window, @weak navigation, @strong config, @strong game, @strong candidate, @strong relative => move |_| {
+        confirm_single_restore(&window, &navigation, &config, &game, &candidate, item_index, &relative);
+    }));
+
+    if entry.is_dir {
+        row.add_suffix(&gtk::Image::from_icon_name("go-next-symbolic"));
+        row.set_activatable(true);
+        row.connect_activated(glib::clone!(@weak window, @weak navigation, @strong config, @strong game, @strong candidate => move |_| {
+            navigation.push(&browse_page(&window, &navigation, &config, &game, &candidate, item_index, &relative));
+        }));
+    }
+    row
+}
+
+fn confirm_single_restore(
+    window: &adw::Window,
+    navigation: &NavigationView,
+    config: &Rc<RefCell<Config>>,
+    game: &Rc<RecoverGame>,
+    candidate: &Rc<RecoveryCandidate>,
+    item_index: usize,
+    relative: &Path,
+) {
+    let RecoverySource::Snapshot(snapshot) = &candidate.source else {
+        return;
+    };
+    let destination = snapshot.original_path_of(&snapshot.items[item_index], relative);
+    let dialog = MessageDialog::builder()
+        .transient_for(window)
+        .modal(true)
+        .heading(gettext_f("Restore {}?", &[&recover::file_name(&destination)]))
+        .body(gettext_f(
+            "Replaces {}. A safety snapshot of it is taken first, the rest of the save is left alone.",
+            &[&destination.display().to_string()],
+        ))
+        .build();
+    dialog.add_response("cancel", &gettext("Cancel"));
+    dialog.add_response("restore", &gettext("Restore"));
+    dialog.set_response_appearance("restore", adw::ResponseAppearance::Suggested);
+    dialog.set_close_response("cancel");
+    let relative = relative.to_path_buf();
+    confirm::run(config, dialog, ConfirmAction::Replace, "restore-file", "restore", glib::clone!(@weak window, @weak navigation, @strong config, @strong game, @strong candidate => move || {
+        let RecoverySource::Snapshot(snapshot) = &candidate.source else {
+            return;
+        };
+        let inhibitor = Inhibitor::new(&window, &gettext("Restoring a game save"));
+        let restored = recover::restore_snapshot_path(&config.borrow(), &game.app_id, snapshot, &snapshot.items[item_index], &relative).map(|_| 1);
+        drop(inhibitor);
+        push_result_page(&window, &navigation, &game, restored);
+    }));
+}
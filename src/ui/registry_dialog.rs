@@ -0,0 +1,144 @@
+use adw::prelude::*;
+use adw::{ActionRow, MessageDialog};
+use gtk::{gio, glib, Align, Button, FileDialog, FileFilter};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::config::{Config, ConfirmAction};
+use crate::fsutil;
+use crate::i18n::{gettext, gettext_f};
+use crate::registry;
+use crate::ui::{confirm, error_dialog};
+
+// Row for the registry keys a game keeps its saves in, with buttons to export
+// them into a .reg file and to import one back
+pub fn build_row(config: &Rc<RefCell<Config>>, app_id: &str, name: &str, keys: &[String]) -> ActionRow {
+    let row = ActionRow::builder()
+        .title(gettext_f("🗝️ Windows Registry ({} keys)", &[&keys.len().to_string()]))
+        .subtitle(glib::markup_escape_text(&keys.join("\n")).as_str())
+        .subtitle_lines(3)
+        .build();
+    row.add_css_class("emoji");
+
+    let export_button = Button::from_icon_name("document-save-symbolic");
+    export_button.set_tooltip_text(Some(&gettext("Export Registry Saves…")));
+    export_button.set_valign(Align::Center);
+    export_button.add_css_class("flat");
+    let config_clone = config.clone();
+    let app_id_clone = app_id.to_string();
+    let name_clone = name.to_string();
+    let keys_clone = keys.to_vec();
+    export_button.connect_clicked(move |button| {
+        let Some(parent) = button.root().and_downcast::<gtk::Window>() else {
+            return;
+        };
+        glib::MainContext::default().spawn_local(export_async(parent, config_clone.clone(), app_id_clone.clone(), name_clone.clone(), keys_clone.clone()));
+    });
+    row.add_suffix(&export_button);
+
+    let import_button = Button::from_icon_name("document-open-symbolic");
+    import_button.set_tooltip_text(Some(&gettext("Import Registry Saves…")));
+    import_button.set_valign(Align::Center);
+    import_button.add_css_class("flat");
+    let config_clone = config.clone();
+    let app_id = app_id.to_string();
+    let name = name.to_string();
+    let keys = keys.to_vec();
+    import_button.connect_clicked(move |button| {
+        let Some(parent) = button.root().and_downcast::<gtk::Window>() else {
+            return;
+        };
+        glib::MainContext::default().spawn_local(import_async(parent, config_clone.clone(), app_id.clone(), name.clone(), keys.clone()));
+    });
+    row.add_suffix(&import_button);
+    row
+}
+
+fn reg_filters() -> gio::ListStore {
+    let filters = gio::ListStore::new::<FileFilter>();
+    let filter = FileFilter::new();
+    filter.set_name(Some(&gettext("Registry Files")));
+    filter.add_pattern("*.reg");
+    filters.append(&filter);
+    filters
+}
+
+async fn export_async(parent: gtk::Window, config: Rc<RefCell<Config>>, app_id: String, name: String, keys: Vec<String>) {
+    let pfx = config.borrow().prefix_path(&app_id).join("pfx");
+    let reg = match registry::export(&pfx, &keys) {
+        Ok(Some(reg)) => reg,
+        Ok(None) => {
+            show_result(&parent, &gettext("Nothing to Export"), &gettext_f("The prefix has none of the registry keys of {} yet. Play the game once so it writes them.", &[&name]));
+            return;
+        }
+        Err(e) => {
+            eprintln!("Error exporting registry saves of {}: {:#}", name, e);
+            error_dialog::show(Some(&parent), &gettext("Error Exporting Registry Saves"), &e);
+            return;
+        }
+    };
+
+    let file_dialog = FileDialog::new();
+    file_dialog.set_title(&gettext("Export Registry Saves"));
+    file_dialog.set_initial_name(Some(&format!("{} - {}.reg", app_id, fsutil::sanitize_file_name(&name))));
+    file_dialog.set_filters(Some(&reg_filters()));
+    let Ok(file) = file_dialog.save_future(Some(&parent)).await else {
+        return; // Cancelled
+    };
+    let Some(path) = file.path() else {
+        return;
+    };
+    match std::fs::write(&path, reg) {
+        Ok(()) => println!("Exported registry saves of {} to {}", name, path.display()),
+        Err(e) => {
+            eprintln!("Error writing {}: {}", path.display(), e);
+            error_dialog::show(Some(&parent), &gettext("Error Exporting Registry Saves"), &anyhow::Error::new(e));
+        }
+    }
+}
+
+async fn import_async(parent: gtk::Window, config: Rc<RefCell<Config>>, app_id: String, name: String, keys: Vec<String>) {
+    let file_dialog = FileDialog::new();
+    file_dialog.set_title(&gettext("Import Registry Saves"));
+    file_dialog.set_filters(Some(&reg_filters()));
+    let Ok(file) = file_dialog.open_future(Some(&parent)).await else {
+        return; // Cancelled
+    };
+    let Some(path) = file.path() else {
+        return;
+    };
+
+    let dialog = MessageDialog::builder()
+        .heading(gettext("Import Registry Saves?"))
+        .body(gettext_f(
+            "The registry keys of {} in its prefix are replaced with the ones in {}. A safety snapshot of the prefix's registry is taken first. Close the game before importing, Wine writes the registry back when it exits.",
+            &[&name, &path.display().to_string()],
+        ))
+        .modal(true)
+        .build();
+    dialog.set_transient_for(Some(&parent));
+    dialog.add_response("cancel", &gettext("Cancel"));
+    dialog.add_response("import", &gettext("Import"));
+    dialog.set_response_appearance("import", adw::ResponseAppearance::Destructive);
+    dialog.set_default_response(Some("cancel"));
+    dialog.set_close_response("cancel");
+    if !confirm::ask(&config, dialog, ConfirmAction::Replace, "import-registry", "import").await {
+        return;
+    }
+
+    let imported = registry::import_file(&config.borrow(), &app_id, &keys, &path);
+    match imported {
+        Ok(count) => show_result(&parent, &gettext("Registry Saves Imported"), &gettext_f("{} registry keys of {} were imported.", &[&count.to_string(), &name])),
+        Err(e) => {
+            eprintln!("Error importing registry saves of {}: {:#}", name, e);
+            error_dialog::show(Some(&parent), &gettext("Error Importing Registry Saves"), &e);
+        }
+    }
+}
+
+fn show_result(parent: &gtk::Window, heading: &str, body: &str) {
+    let dialog = MessageDialog::builder().heading(heading).body(body).modal(true).build();
+    dialog.set_transient_for(Some(parent));
+    dialog.add_response("ok", &gettext("OK"));
+    dialog.present();
+}
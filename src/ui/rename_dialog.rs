@@ -0,0 +1,51 @@
+use adw::prelude::*;
+use adw::MessageDialog;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::config::Config;
+use crate::i18n::{gettext, gettext_f};
+
+// Ask for a custom display name for an App ID. Saving goes through the config,
+// whose change notification refreshes the lists.
+pub fn show(parent: Option<&gtk::Window>, config: Rc<RefCell<Config>>, app_id: &str, current_name: &str) {
+    let dialog = MessageDialog::builder()
+        .heading(gettext("Rename Game"))
+        .body(gettext_f("Display name for App ID {}, used in lists, search, snapshots and exports.", &[app_id]))
+        .modal(true)
+        .build();
+    if let Some(parent) = parent {
+        dialog.set_transient_for(Some(parent));
+    }
+
+    let entry = gtk::Entry::builder()
+        .text(current_name)
+        .activates_default(true)
+        .build();
+    dialog.set_extra_child(Some(&entry));
+
+    dialog.add_response("cancel", &gettext("Cancel"));
+    let has_override = config.borrow().display_name(app_id).is_some();
+    if has_override {
+        dialog.add_response("reset", &gettext("Use Default Name"));
+    }
+    dialog.add_response("save", &gettext("Rename"));
+    dialog.set_response_appearance("save", adw::ResponseAppearance::Suggested);
+    dialog.set_default_response(Some("save"));
+    dialog.set_close_response("cancel");
+
+    let app_id = app_id.to_string();
+    dialog.connect_response(None, move |dialog, response| {
+        let name = match response {
+            "save" => Some(entry.text().to_string()),
+            "reset" => None,
+            _ => return,
+        };
+        if let Err(e) = config.borrow_mut().set_display_name(&app_id, name) {
+            eprintln!("Error saving display name: {}", e);
+        }
+        dialog.close();
+    });
+
+    dialog.present();
+}
@@ -0,0 +1,154 @@
+use adw::prelude::*;
+use gtk::{gio, glib};
+use humansize::{format_size, DECIMAL};
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use crate::compatdata;
+use crate::i18n::{gettext, gettext_f};
+use crate::report;
+use crate::screenshots::{self, Screenshot};
+use crate::ui::error_dialog;
+
+// Every thumbnail is a full image load, big folders only show the newest ones
+const MAX_THUMBNAILS: usize = 120;
+
+// A window with the screenshots found under one save location and a button to
+// copy them all to ~/Pictures, apart from the save backups
+pub fn show(parent: Option<&gtk::Window>, game_name: &str, location: PathBuf) {
+    let window = adw::Window::builder()
+        .modal(true)
+        .title(gettext_f("Screenshots of {}", &[game_name]))
+        .default_width(720)
+        .default_height(600)
+        .build();
+    if let Some(parent) = parent {
+        window.set_transient_for(Some(parent));
+    }
+
+    let export_dir = screenshots::default_export_dir(game_name);
+    let export_button = gtk::Button::builder()
+        .label(gettext("Export to Pictures"))
+        .tooltip_text(gettext_f("Copy every screenshot to {}", &[&export_dir.display().to_string()]))
+        .sensitive(false)
+        .build();
+    export_button.add_css_class("suggested-action");
+    let header = adw::HeaderBar::new();
+    header.pack_end(&export_button);
+
+    let content = gtk::Box::new(gtk::Orientation::Vertical, 12);
+    content.set_margin_start(12);
+    content.set_margin_end(12);
+    content.set_margin_top(12);
+    content.set_margin_bottom(12);
+    let status_label = gtk::Label::new(Some(&gettext("Looking for screenshots…")));
+    status_label.add_css_class("dim-label");
+    status_label.set_wrap(true);
+    content.append(&status_label);
+    let flow_box = gtk::FlowBox::builder()
+        .selection_mode(gtk::SelectionMode::None)
+        .homogeneous(true)
+        .min_children_per_line(2)
+        .max_children_per_line(6)
+        .row_spacing(6)
+        .column_spacing(6)
+        .valign(gtk::Align::Start)
+        .build();
+    content.append(&flow_box);
+
+    let scrolled = gtk::ScrolledWindow::builder()
+        .hscrollbar_policy(gtk::PolicyType::Never)
+        .vexpand(true)
+        .child(&content)
+        .build();
+    let toast_overlay = adw::ToastOverlay::new();
+    toast_overlay.set_child(Some(&scrolled));
+    let toolbar = adw::ToolbarView::new();
+    toolbar.add_top_bar(&header);
+    toolbar.set_content(Some(&toast_overlay));
+    window.set_content(Some(&toolbar));
+    window.present();
+
+    // Walking the folder and reading the timestamps happens off the main thread
+    let export_dir = Rc::new(export_dir);
+    glib::MainContext::default().spawn_local(glib::clone!(@weak window => async move {
+        let found = gio::spawn_blocking(move || screenshots::find_screenshots(&location)).await.unwrap_or_default();
+        let total: u64 = found.iter().map(|screenshot| screenshot.size).sum();
+        status_label.set_label(&if found.is_empty() {
+            gettext("No screenshots in this save location")
+        } else if found.len() > MAX_THUMBNAILS {
+            gettext_f("{} screenshots ({}), showing the newest {}", &[&found.len().to_string(), &format_size(total, DECIMAL), &MAX_THUMBNAILS.to_string()])
+        } else {
+            gettext_f("{} screenshots ({})", &[&found.len().to_string(), &format_size(total, DECIMAL)])
+        });
+        for screenshot in found.iter().take(MAX_THUMBNAILS) {
+            flow_box.append(&create_thumbnail(screenshot));
+        }
+
+        export_button.set_sensitive(!found.is_empty());
+        let found = Rc::new(found);
+        export_button.connect_clicked(move |button| {
+            button.set_sensitive(false);
+            let found = found.clone();
+            let export_dir = export_dir.clone();
+            let button = button.clone();
+            let window = window.clone();
+            let toast_overlay = toast_overlay.clone();
+            glib::MainContext::default().spawn_local(async move {
+                let list = found.as_ref().clone();
+                let dest = export_dir.as_ref().clone();
+                let result = gio::spawn_blocking(move || screenshots::export(&list, &dest))
+                    .await
+                    .unwrap_or_else(|_| Err(anyhow::anyhow!("Screenshot export thread panicked")));
+                button.set_sensitive(true);
+                match result {
+                    Ok(copied) => {
+                        let toast = adw::Toast::new(&if copied == 0 {
+                            gettext("Every screenshot was already exported")
+                        } else {
+                            gettext_f("Exported {} screenshots to {}", &[&copied.to_string(), &export_dir.display().to_string()])
+                        });
+                        toast.set_button_label(Some(&gettext("Open Folder")));
+                        let export_dir = export_dir.clone();
+                        toast.connect_button_clicked(move |_| {
+                            if let Err(e) = compatdata::open_in_file_manager(&export_dir) {
+                                eprintln!("Failed to open {}: {}", export_dir.display(), e);
+                            }
+                        });
+                        toast_overlay.add_toast(toast);
+                    }
+                    Err(e) => {
+                        eprintln!("Failed to export screenshots: {:#}", e);
+                        error_dialog::show(Some(window.upcast_ref()), &gettext("Export Failed"),
+                            &e.context(gettext("Failed to export the screenshots")));
+                    }
+                }
+            });
+        });
+    }));
+}
+
+// A thumbnail that opens the image in the default viewer when clicked
+fn create_thumbnail(screenshot: &Screenshot) -> gtk::Widget {
+    let picture = gtk::Picture::for_filename(&screenshot.path);
+    picture.set_content_fit(gtk::ContentFit::Cover);
+    picture.set_size_request(160, 100);
+    let mut tooltip = vec![screenshot.relative.display().to_string()];
+    if let Some(modified) = screenshot.modified {
+        tooltip.push(report::format_timestamp_utc(modified));
+    }
+    tooltip.push(format_size(screenshot.size, DECIMAL));
+
+    let button = gtk::Button::builder()
+        .child(&picture)
+        .tooltip_text(tooltip.join("\n"))
+        .build();
+    button.add_css_class("flat");
+    let path = screenshot.path.clone();
+    button.connect_clicked(move |_| {
+        if let Err(e) = compatdata::open_in_file_manager(&path) {
+            eprintln!("Failed to open {}: {}", path.display(), e);
+        }
+    });
+    button.upcast()
+}
@@ -0,0 +1,30 @@
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use gtk::glib;
+
+// Best score of `query` against any of `texts`, higher is a closer match.
+// None if none of them matches.
+pub fn score(matcher: &SkimMatcherV2, texts: &[&str], query: &str) -> Option<i64> {
+    texts.iter().filter_map(|text| matcher.fuzzy_match(text, query)).max()
+}
+
+// Pango markup of `text` with the characters matching the search `query` in
+// bold, for row titles. None if it doesn't match at all.
+pub fn highlight(matcher: &SkimMatcherV2, text: &str, query: &str) -> Option<String> {
+    let (_, indices) = matcher.fuzzy_indices(text, query)?;
+    let mut markup = String::new();
+    let mut bold = false;
+    let mut buffer = [0; 4];
+    for (index, character) in text.chars().enumerate() {
+        let matched = indices.contains(&index);
+        if matched != bold {
+            markup.push_str(if matched { "<b>" } else { "</b>" });
+            bold = matched;
+        }
+        markup.push_str(&glib::markup_escape_text(character.encode_utf8(&mut buffer)));
+    }
+    if bold {
+        markup.push_str("</b>");
+    }
+    Some(markup)
+}
@@ -1,27 +1,37 @@
 use adw::prelude::*;
-use adw::{ActionRow, PreferencesGroup, PreferencesPage, PreferencesWindow, MessageDialog, EntryRow};
+use adw::{ActionRow, ExpanderRow, PreferencesGroup, PreferencesPage, PreferencesWindow, MessageDialog, EntryRow, SpinRow, SwitchRow};
 use gtk::{Button, glib, Align, FileDialog, Window, gio};
+use anyhow::Result;
 use std::cell::RefCell;
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 
-use crate::config::Config;
+use crate::config::{BackupTargetSettings, Compression, Config, ConfirmAction, ConfirmationLevel, IoLimits, PrivacySettings, RetentionPolicy, SaveQuota, SnapshotCompression, SteamAccountChoice, MAX_IO_THREADS};
+use crate::i18n::{gettext, gettext_f};
 use crate::manifest;
+use crate::snapshot;
+use crate::steam;
+use crate::ui::{confirm, error_dialog, file_browser, steamos_check};
+use humansize::{format_size, DECIMAL};
 
-// Callback type for when the manifest needs updating
-type OnManifestUpdate = Rc<RefCell<dyn FnMut()>>; // Use Rc<RefCell<dyn FnMut>> for flexibility
+// Reads or stores one of the editable string lists in the config
+type ListGetter = fn(&Config) -> Vec<String>;
+type ListSetter = fn(&mut Config, Vec<String>) -> Result<()>;
+
+// Switch row for one network feature and how it updates the privacy settings
+type PrivacyToggle = (SwitchRow, fn(&mut PrivacySettings, bool));
 
 pub struct SettingsDialog {
     dialog: PreferencesWindow,
     _config: Rc<RefCell<Config>>,
-    // Callback to trigger updates in the main window
-    _on_update: OnManifestUpdate,
 }
 
 impl SettingsDialog {
-    pub fn new(parent: adw::ApplicationWindow, config: Rc<RefCell<Config>>, on_update: OnManifestUpdate) -> Self {
+    pub fn new(parent: adw::ApplicationWindow, config: Rc<RefCell<Config>>) -> Self {
         let dialog = PreferencesWindow::builder()
             .transient_for(&parent)
-            .title("Settings")
+            .title(gettext("Settings"))
             .modal(true)
             .build();
             
@@ -30,16 +40,16 @@ impl SettingsDialog {
 
         // --- Steam Settings Group --- 
         let steam_group = PreferencesGroup::builder()
-            .title("Steam Settings")
-            .description("Configure Steam directory locations")
+            .title(gettext("Steam Settings"))
+            .description(gettext("Configure Steam directory locations"))
             .build();
         page.add(&steam_group);
             
         let path_row = ActionRow::builder()
-            .title("Steam Directory")
+            .title(gettext("Steam Directory"))
             .subtitle(&*config.borrow().steam_path().to_string_lossy())
             .build();
-        let browse_button = Button::with_label("Browse");
+        let browse_button = Button::with_label(&gettext("Browse"));
         browse_button.set_valign(Align::Center);
         path_row.add_suffix(&browse_button);
         let dialog_clone = dialog.clone(); // Clone for closure
@@ -56,15 +66,90 @@ impl SettingsDialog {
         });
         steam_group.add(&path_row);
 
+        // Whose cloud copies, Steam settings and play times are shown, for
+        // machines several people use with their own Steam accounts
+        let accounts = steam::accounts(&config.borrow());
+        if accounts.len() > 1 {
+            let mut choices = vec![SteamAccountChoice::All, SteamAccountChoice::SignedIn];
+            let mut names = vec![gettext("All Accounts"), gettext("Signed-In Account")];
+            if let Some(signed_in) = accounts.iter().find(|account| account.signed_in) {
+                names[1] = gettext_f("Signed-In Account, now {}", &[&signed_in.label()]);
+            }
+            for account in &accounts {
+                choices.push(SteamAccountChoice::Account(account.id.clone()));
+                names.push(account.label());
+            }
+            let name_refs: Vec<&str> = names.iter().map(String::as_str).collect();
+            let current = config.borrow().steam_account().clone();
+            let account_row = adw::ComboRow::builder()
+                .title(gettext("Steam Account"))
+                .subtitle(gettext("Whose Steam Cloud copies, Steam settings and play times are used"))
+                .model(&gtk::StringList::new(&name_refs))
+                .selected(choices.iter().position(|choice| *choice == current).unwrap_or(0) as u32)
+                .build();
+            account_row.connect_selected_notify(glib::clone!(@strong config, @weak dialog => move |row| {
+                let choice = choices.get(row.selected() as usize).cloned().unwrap_or_default();
+                if let Err(e) = config.borrow_mut().set_steam_account(choice) {
+                    eprintln!("Error saving Steam account: {}", e);
+                    error_dialog::show(Some(dialog.upcast_ref()), &gettext("Error Saving Settings"), &e);
+                }
+            }));
+            steam_group.add(&account_row);
+        }
+
+        // Folder a sync tool shares between machines, save locations get linked into it
+        let sync_row = ActionRow::builder()
+            .title(gettext("Sync Folder"))
+            .subtitle(Self::sync_folder_subtitle(config.borrow().sync_folder()))
+            .build();
+        let sync_clear_button = Button::from_icon_name("edit-clear-symbolic");
+        sync_clear_button.set_tooltip_text(Some(&gettext("Stop Using a Sync Folder")));
+        sync_clear_button.set_valign(Align::Center);
+        sync_clear_button.add_css_class("flat");
+        sync_clear_button.set_sensitive(config.borrow().sync_folder().is_some());
+        let sync_browse_button = Button::with_label(&gettext("Browse"));
+        sync_browse_button.set_valign(Align::Center);
+        sync_row.add_suffix(&sync_clear_button);
+        sync_row.add_suffix(&sync_browse_button);
+        sync_browse_button.connect_clicked(glib::clone!(@weak dialog, @strong config, @weak sync_row, @weak sync_clear_button => move |_| {
+            let config = config.clone();
+            glib::MainContext::default().spawn_local(async move {
+                let file_dialog = FileDialog::new();
+                file_dialog.set_title(&gettext("Select Sync Folder"));
+                let Ok(folder) = file_dialog.select_folder_future(Some(&dialog)).await else {
+                    return;
+                };
+                let Some(path) = folder.path() else {
+                    return;
+                };
+                if let Err(e) = config.borrow_mut().set_sync_folder(Some(path)) {
+                    eprintln!("Error setting sync folder: {}", e);
+                    error_dialog::show(Some(dialog.upcast_ref()), &gettext("Error Setting Path"), &e);
+                    return;
+                }
+                sync_row.set_subtitle(&Self::sync_folder_subtitle(config.borrow().sync_folder()));
+                sync_clear_button.set_sensitive(true);
+            });
+        }));
+        sync_clear_button.connect_clicked(glib::clone!(@strong config, @weak sync_row => move |button| {
+            if let Err(e) = config.borrow_mut().set_sync_folder(None) {
+                eprintln!("Error clearing sync folder: {}", e);
+                return;
+            }
+            sync_row.set_subtitle(&Self::sync_folder_subtitle(None));
+            button.set_sensitive(false);
+        }));
+        steam_group.add(&sync_row);
+
         // --- Manifest Settings Group --- 
         let manifest_group = PreferencesGroup::builder()
-            .title("Game Data Manifest")
-            .description("Configure the source for game save/config definitions (Ludusavi format)")
+            .title(gettext("Game Data Manifest"))
+            .description(gettext("Configure the source for game save/config definitions (Ludusavi format)"))
             .build();
         page.add(&manifest_group);
 
         let url_row = EntryRow::builder()
-            .title("Manifest URL")
+            .title(gettext("Manifest URL"))
             .text(config.borrow().manifest_url())
             .show_apply_button(true)
             .build();
@@ -78,10 +163,10 @@ impl SettingsDialog {
         manifest_group.add(&url_row);
         
         let update_row = ActionRow::builder()
-            .title("Update Manifest Now")
-            .subtitle(&format!("Cached at: {}", config.borrow().manifest_cache_path().display()))
+            .title(gettext("Update Manifest Now"))
+            .subtitle(gettext_f("Cached at: {}", &[&config.borrow().manifest_cache_path().display().to_string()]))
             .build();
-        let update_button = Button::with_label("Download/Update");
+        let update_button = Button::with_label(&gettext("Download/Update"));
         update_button.set_valign(Align::Center);
         update_row.add_suffix(&update_button);
         let config_clone_update = config.clone();
@@ -92,32 +177,1001 @@ impl SettingsDialog {
                 Ok(_) => {
                     println!("Manifest downloaded successfully.");
                     // Update subtitle on success
-                    update_row_clone.set_subtitle(&format!("Cached at: {}", config_clone_update.borrow().manifest_cache_path().display()));
+                    update_row_clone.set_subtitle(&gettext_f("Cached at: {}", &[&config_clone_update.borrow().manifest_cache_path().display().to_string()]));
                      // Optionally show success message
                     let success_dialog = MessageDialog::builder()
                          .transient_for(&dialog_clone_update)
-                         .heading("Manifest Updated")
-                         .body("Successfully downloaded the latest manifest.")
+                         .heading(gettext("Manifest Updated"))
+                         .body(gettext("Successfully downloaded the latest manifest."))
                          .build();
-                     success_dialog.add_response("ok", "OK");
+                     success_dialog.add_response("ok", &gettext("OK"));
                      success_dialog.present();
                 }
                 Err(e) => {
                      eprintln!("Error downloading manifest: {}", e);
-                    // Show error message
-                    let error_dialog = MessageDialog::builder()
-                         .transient_for(&dialog_clone_update)
-                         .heading("Error Updating Manifest")
-                         .body(&format!("Failed to download manifest: {}\n\nCheck the URL and your internet connection.", e))
-                         .build();
-                     error_dialog.add_response("ok", "OK");
-                     error_dialog.present();
+                    error_dialog::show(Some(dialog_clone_update.upcast_ref()), &gettext("Error Updating Manifest"),
+                        &e.context(gettext("Failed to download the manifest. Check the URL and your internet connection.")));
                 }
             }
         });
         manifest_group.add(&update_row);
-        
-        Self { dialog, _config: config, _on_update: on_update }
+
+        // Reuse ludusavi's copy of the manifest instead of keeping a second one.
+        // While the file is missing (e.g. ludusavi uninstalled) our own cache is used.
+        let shared_path = config.borrow().shared_manifest_path().map(Path::to_path_buf)
+            .or_else(manifest::ludusavi_manifest_path);
+        let shared_row = SwitchRow::builder()
+            .title(gettext("Use ludusavi's Manifest"))
+            .active(config.borrow().shared_manifest_path().is_some())
+            .build();
+        match &shared_path {
+            Some(path) => shared_row.set_subtitle(&Self::shared_manifest_subtitle(path)),
+            None => {
+                shared_row.set_subtitle(&gettext("ludusavi's manifest was not found"));
+                shared_row.set_sensitive(false);
+            }
+        }
+        let config_clone_shared = config.clone();
+        let dialog_clone_shared = dialog.clone();
+        let update_row_clone = update_row.clone();
+        shared_row.connect_active_notify(move |row| {
+            let path = if row.is_active() { shared_path.clone() } else { None };
+            if config_clone_shared.borrow().shared_manifest_path() == path.as_deref() {
+                return;
+            }
+            if let Err(e) = config_clone_shared.borrow_mut().set_shared_manifest_path(path) {
+                eprintln!("Error setting shared manifest: {}", e);
+                error_dialog::show(Some(dialog_clone_shared.upcast_ref()), &gettext("Error Saving Settings"), &e);
+                row.set_active(config_clone_shared.borrow().shared_manifest_path().is_some());
+                return;
+            }
+            if let Some(path) = &shared_path {
+                row.set_subtitle(&Self::shared_manifest_subtitle(path));
+            }
+            update_row_clone.set_subtitle(&gettext_f("Cached at: {}", &[&config_clone_shared.borrow().manifest_cache_path().display().to_string()]));
+        });
+        manifest_group.add(&shared_row);
+
+        // --- Additional Manifest Sources Group ---
+        let sources_group = PreferencesGroup::builder()
+            .title(gettext("Additional Manifest Sources"))
+            .description(gettext("Extra manifest URLs or local YAML files (ludusavi custom games supported). Later sources override earlier ones."))
+            .build();
+        page.add(&sources_group);
+
+        let add_source_row = EntryRow::builder()
+            .title(gettext("Add URL or file path"))
+            .show_apply_button(true)
+            .build();
+        let browse_source_button = Button::from_icon_name("document-open-symbolic");
+        browse_source_button.set_tooltip_text(Some(&gettext("Choose Local YAML File")));
+        browse_source_button.set_valign(Align::Center);
+        add_source_row.add_suffix(&browse_source_button);
+        sources_group.add(&add_source_row);
+
+        for source in config.borrow().manifest_sources() {
+            Self::add_source_row(&sources_group, config.clone(), source);
+        }
+
+        let config_clone_source = config.clone();
+        let sources_group_clone = sources_group.clone();
+        let dialog_clone_source = dialog.clone();
+        add_source_row.connect_apply(move |row| {
+            let source = row.text().trim().to_string();
+            let result = config_clone_source.borrow_mut().add_manifest_source(source.clone());
+            match result {
+                Ok(_) => {
+                    row.set_text("");
+                    Self::add_source_row(&sources_group_clone, config_clone_source.clone(), &source);
+                }
+                Err(e) => {
+                    eprintln!("Error adding manifest source: {}", e);
+                    error_dialog::show(Some(dialog_clone_source.upcast_ref()), &gettext("Invalid Manifest Source"), &e);
+                }
+            }
+        });
+
+        let dialog_clone_browse = dialog.clone();
+        let add_source_row_clone = add_source_row.clone();
+        browse_source_button.connect_clicked(move |_| {
+            let parent_window = dialog_clone_browse.clone().upcast::<Window>();
+            let row = add_source_row_clone.clone();
+            glib::MainContext::default().spawn_local(async move {
+                let file_dialog = FileDialog::new();
+                file_dialog.set_title(&gettext("Select Manifest File"));
+                if let Ok(file) = file_dialog.open_future(Some(&parent_window)).await {
+                    if let Some(path) = file.path() {
+                        row.set_text(&path.to_string_lossy());
+                        row.emit_by_name::<()>("apply", &[]);
+                    }
+                }
+            });
+        });
+
+        // --- Scan Location Groups ---
+        Self::build_string_list_group(
+            &page,
+            &dialog,
+            config.clone(),
+            gettext("Save Folders"),
+            gettext("Folders inside each prefix's steamuser folder that are scanned for game saves, e.g. Documents/My Games. Start with drive_c/ for folders elsewhere on the C: drive, e.g. drive_c/ProgramData"),
+            gettext("Add folder"),
+            crate::config::DEFAULT_SAVE_PATHS,
+            |c| c.save_paths().to_vec(),
+            |c, v| c.set_save_paths(v),
+        );
+        Self::build_string_list_group(
+            &page,
+            &dialog,
+            config.clone(),
+            gettext("Ignored Folders"),
+            gettext("Folder names skipped while scanning, for vendor or system folders that aren't games"),
+            gettext("Add folder name"),
+            crate::config::DEFAULT_IGNORE_DIRS,
+            |c| c.ignore_dirs().to_vec(),
+            |c, v| c.set_ignore_dirs(v),
+        );
+        Self::build_string_list_group(
+            &page,
+            &dialog,
+            config.clone(),
+            gettext("Ignored Files"),
+            gettext("Patterns for junk inside save folders, left out of sizes, safety snapshots and bundles. Without a slash they match names anywhere, e.g. *.log; with one they start at the top of the save folder, e.g. CrashDumps/**. Games can add their own from their row on the Games page"),
+            gettext("Add pattern"),
+            crate::ignore_patterns::DEFAULT_IGNORE_PATTERNS,
+            |c| c.ignore_patterns().to_vec(),
+            |c, v| c.set_ignore_patterns(v),
+        );
+        Self::build_string_list_group(
+            &page,
+            &dialog,
+            config.clone(),
+            gettext("Save Editors"),
+            gettext("Programs that open save files by extension, from a file's preview, e.g. \"sav: imhex\". {file} in the command is replaced by the file, otherwise it is added at the end"),
+            gettext("Add extension: command"),
+            &[],
+            |c| c.editor_command_entries(),
+            |c, v| c.set_editor_command_entries(v),
+        );
+
+        // --- Safety Snapshots Group ---
+        Self::build_retention_group(&page, config.clone(), &dialog);
+        Self::build_compression_group(&page, config.clone(), &dialog);
+        Self::build_quota_group(&page, config.clone(), &dialog);
+        Self::build_io_limits_group(&page, config.clone(), &dialog);
+        Self::build_backup_targets_group(&page, config.clone(), &dialog);
+
+        // --- Confirmations Group ---
+        Self::build_confirmations_group(&page, config.clone(), &dialog);
+
+        // --- Privacy Group ---
+        Self::build_privacy_group(&page, config.clone(), &dialog);
+
+        // --- Export/Import Group ---
+        Self::build_transfer_group(&page, config.clone(), &dialog, &parent);
+
+        Self { dialog, _config: config }
+    }
+
+    // A group editing a list of strings in the config: an entry row to add items,
+    // one row per item with a remove button, and a reset button in the header
+    #[allow(clippy::too_many_arguments)]
+    fn build_string_list_group(
+        page: &PreferencesPage,
+        dialog: &PreferencesWindow,
+        config: Rc<RefCell<Config>>,
+        title: String,
+        description: String,
+        add_title: String,
+        defaults: &'static [&'static str],
+        get: ListGetter,
+        set: ListSetter,
+    ) {
+        let group = PreferencesGroup::builder()
+            .title(title)
+            .description(description)
+            .build();
+        page.add(&group);
+
+        let reset_button = Button::with_label(&gettext("Reset"));
+        reset_button.set_tooltip_text(Some(&gettext("Restore the default list")));
+        reset_button.add_css_class("flat");
+        group.set_header_suffix(Some(&reset_button));
+
+        let add_row = EntryRow::builder()
+            .title(add_title)
+            .show_apply_button(true)
+            .build();
+        group.add(&add_row);
+
+        let rows: Rc<RefCell<Vec<ActionRow>>> = Rc::new(RefCell::new(Vec::new()));
+        Self::fill_string_list(&group, &rows, dialog, config.clone(), get, set);
+
+        let group_clone = group.clone();
+        let rows_clone = rows.clone();
+        let dialog_clone = dialog.clone();
+        let config_clone = config.clone();
+        add_row.connect_apply(move |row| {
+            let mut items = get(&config_clone.borrow());
+            items.push(row.text().to_string());
+            let result = set(&mut config_clone.borrow_mut(), items);
+            match result {
+                Ok(_) => {
+                    row.set_text("");
+                    Self::fill_string_list(&group_clone, &rows_clone, &dialog_clone, config_clone.clone(), get, set);
+                }
+                Err(e) => {
+                    eprintln!("Error adding scan location: {}", e);
+                    error_dialog::show(Some(dialog_clone.upcast_ref()), &gettext("Invalid Entry"), &e);
+                }
+            }
+        });
+
+        let dialog_clone = dialog.clone();
+        reset_button.connect_clicked(move |_| {
+            let defaults = defaults.iter().map(|s| s.to_string()).collect();
+            if let Err(e) = set(&mut config.borrow_mut(), defaults) {
+                eprintln!("Error resetting scan locations: {}", e);
+                return;
+            }
+            Self::fill_string_list(&group, &rows, &dialog_clone, config.clone(), get, set);
+        });
+    }
+
+    // (Re)create the item rows of a string list group from the config
+    fn fill_string_list(
+        group: &PreferencesGroup,
+        rows: &Rc<RefCell<Vec<ActionRow>>>,
+        dialog: &PreferencesWindow,
+        config: Rc<RefCell<Config>>,
+        get: ListGetter,
+        set: ListSetter,
+    ) {
+        for row in rows.borrow_mut().drain(..) {
+            group.remove(&row);
+        }
+
+        let items = get(&config.borrow());
+        for item in items {
+            let row = ActionRow::builder().title(item.as_str()).build();
+            let remove_button = Button::from_icon_name("user-trash-symbolic");
+            remove_button.set_tooltip_text(Some(&gettext("Remove")));
+            remove_button.set_valign(Align::Center);
+            remove_button.add_css_class("flat");
+            row.add_suffix(&remove_button);
+
+            let group_clone = group.clone();
+            let rows_clone = rows.clone();
+            let dialog_clone = dialog.clone();
+            let config_clone = config.clone();
+            remove_button.connect_clicked(move |_| {
+                let mut items = get(&config_clone.borrow());
+                items.retain(|i| *i != item);
+                if let Err(e) = set(&mut config_clone.borrow_mut(), items) {
+                    eprintln!("Error removing scan location: {}", e);
+                    return;
+                }
+                // Deferred, this button's row is one of the ones being removed
+                let (group, rows, dialog, config) = (group_clone.clone(), rows_clone.clone(), dialog_clone.clone(), config_clone.clone());
+                glib::idle_add_local_once(move || Self::fill_string_list(&group, &rows, &dialog, config, get, set));
+            });
+
+            group.add(&row);
+            rows.borrow_mut().push(row);
+        }
+    }
+
+    // Global snapshot retention, per-game overrides and a manual prune
+    fn build_retention_group(page: &PreferencesPage, config: Rc<RefCell<Config>>, dialog: &PreferencesWindow) {
+        let retention_group = PreferencesGroup::builder()
+            .title(gettext("Safety Snapshots"))
+            .description(gettext("Copies taken automatically before saves are deleted. Old ones are pruned after each new snapshot."))
+            .build();
+        page.add(&retention_group);
+
+        let prune_row = ActionRow::builder()
+            .title(gettext("Prune Now"))
+            .build();
+        let prune_button = Button::with_label(&gettext("Prune"));
+        prune_button.set_valign(Align::Center);
+        prune_row.add_suffix(&prune_button);
+        Self::update_prune_row(&prune_row, &config.borrow());
+
+        // Global policy
+        let (max_row, age_row) = Self::retention_spin_rows(config.borrow().retention());
+        for row in [&max_row, &age_row] {
+            let config_clone = config.clone();
+            let dialog_clone = dialog.clone();
+            let prune_row_clone = prune_row.clone();
+            let (max_row, age_row) = (max_row.clone(), age_row.clone());
+            row.connect_value_notify(move |_| {
+                let policy = Self::retention_from_rows(&max_row, &age_row);
+                if let Err(e) = config_clone.borrow_mut().set_retention(policy) {
+                    eprintln!("Error saving retention policy: {}", e);
+                    error_dialog::show(Some(dialog_clone.upcast_ref()), &gettext("Error Saving Settings"), &e);
+                }
+                Self::update_prune_row(&prune_row_clone, &config_clone.borrow());
+            });
+            retention_group.add(row);
+        }
+
+        // Per-game overrides, for every game with snapshots or an existing override
+        let mut game_ids: Vec<String> = snapshot::list_snapshots(&config.borrow())
+            .into_iter()
+            .map(|s| s.game_id)
+            .chain(config.borrow().retention_overrides().keys().cloned())
+            .collect();
+        game_ids.sort_by_key(|id| (id.parse::<u64>().unwrap_or(u64::MAX), id.clone()));
+        game_ids.dedup();
+
+        for game_id in game_ids {
+            let override_policy = config.borrow().retention_overrides().get(&game_id).copied();
+            let expander = ExpanderRow::builder()
+                .title(match config.borrow().display_name(&game_id) {
+                    Some(name) => gettext_f("Custom Retention for {} ({})", &[&glib::markup_escape_text(name), &game_id]),
+                    None => gettext_f("Custom Retention for {}", &[&game_id]),
+                })
+                .subtitle(gettext("Override the limits above for this game"))
+                .show_enable_switch(true)
+                .enable_expansion(override_policy.is_some())
+                .expanded(false)
+                .build();
+            let (game_max_row, game_age_row) =
+                Self::retention_spin_rows(override_policy.unwrap_or(config.borrow().retention()));
+            expander.add_row(&game_max_row);
+            expander.add_row(&game_age_row);
+
+            // Saves the override if enabled, removes it otherwise
+            let apply_override: Rc<dyn Fn()> = {
+                let config = config.clone();
+                let dialog = dialog.clone();
+                let prune_row = prune_row.clone();
+                let expander = expander.clone();
+                let (max_row, age_row) = (game_max_row.clone(), game_age_row.clone());
+                Rc::new(move || {
+                    let policy = expander
+                        .enables_expansion()
+                        .then(|| Self::retention_from_rows(&max_row, &age_row));
+                    if let Err(e) = config.borrow_mut().set_retention_override(&game_id, policy) {
+                        eprintln!("Error saving retention override: {}", e);
+                        error_dialog::show(Some(dialog.upcast_ref()), &gettext("Error Saving Settings"), &e);
+                    }
+                    Self::update_prune_row(&prune_row, &config.borrow());
+                })
+            };
+            let apply_clone = apply_override.clone();
+            expander.connect_enable_expansion_notify(move |_| apply_clone());
+            for row in [&game_max_row, &game_age_row] {
+                let apply_clone = apply_override.clone();
+                row.connect_value_notify(move |_| apply_clone());
+            }
+            retention_group.add(&expander);
+        }
+
+        let browse_row = ActionRow::builder()
+            .title(gettext("Browse Snapshots"))
+            .subtitle(gettext("Look through the files kept in each snapshot"))
+            .build();
+        let browse_button = Button::with_label(&gettext("Browse…"));
+        browse_button.set_valign(Align::Center);
+        browse_row.add_suffix(&browse_button);
+        browse_row.set_activatable_widget(Some(&browse_button));
+        browse_button.connect_clicked(glib::clone!(@strong config, @weak dialog => move |_| {
+            file_browser::show_snapshots(Some(dialog.upcast_ref()), &config);
+        }));
+
+        // Where snapshots are kept, movable e.g. to an SD card or off a
+        // folder SteamOS updates wipe
+        let folder_row = ActionRow::builder()
+            .title(gettext("Snapshot Folder"))
+            .subtitle(config.borrow().safety_snapshots_path().display().to_string())
+            .build();
+        let move_button = Button::with_label(&gettext("Move"));
+        move_button.set_valign(Align::Center);
+        folder_row.add_suffix(&move_button);
+        move_button.connect_clicked(glib::clone!(@weak dialog, @strong config, @weak folder_row => move |_| {
+            let config = config.clone();
+            glib::MainContext::default().spawn_local(async move {
+                if steamos_check::choose_snapshot_folder(dialog.upcast_ref(), &config).await {
+                    folder_row.set_subtitle(&config.borrow().safety_snapshots_path().display().to_string());
+                }
+            });
+        }));
+        let dialog_clone = dialog.clone();
+        let prune_row_clone = prune_row.clone();
+        prune_button.connect_clicked(move |_| {
+            let prunable = snapshot::prunable_snapshots(&config.borrow());
+            if prunable.is_empty() {
+                return;
+            }
+            let size: u64 = prunable.iter().map(|s| s.size()).sum();
+            let confirm = MessageDialog::builder()
+                .transient_for(&dialog_clone)
+                .modal(true)
+                .heading(gettext("Prune Safety Snapshots?"))
+                .body(gettext_f(
+                    "{} snapshots outside the retention limits will be deleted, freeing {}.",
+                    &[&prunable.len().to_string(), &format_size(size, DECIMAL)],
+                ))
+                .build();
+            confirm.add_response("cancel", &gettext("Cancel"));
+            confirm.add_response("prune", &gettext("Prune"));
+            confirm.set_response_appearance("prune", adw::ResponseAppearance::Destructive);
+            confirm.set_default_response(Some("cancel"));
+            confirm.set_close_response("cancel");
+
+            let config_clone = config.clone();
+            let dialog_clone_inner = dialog_clone.clone();
+            let prune_row_inner = prune_row_clone.clone();
+            confirm::run(&config, confirm, ConfirmAction::Delete, "prune-snapshots", "prune", move || {
+                if let Err(e) = snapshot::prune(&config_clone.borrow()) {
+                    eprintln!("Error pruning safety snapshots: {}", e);
+                    error_dialog::show(Some(dialog_clone_inner.upcast_ref()), &gettext("Error Pruning Snapshots"), &e);
+                }
+                Self::update_prune_row(&prune_row_inner, &config_clone.borrow());
+            });
+        });
+        retention_group.add(&prune_row);
+        retention_group.add(&browse_row);
+        retention_group.add(&folder_row);
+    }
+
+    // Compressor and level for new safety snapshots
+    fn build_confirmations_group(page: &PreferencesPage, config: Rc<RefCell<Config>>, dialog: &PreferencesWindow) {
+        let group = PreferencesGroup::builder()
+            .title(gettext("Confirmations"))
+            .description(gettext("Deleted prefixes and replaced saves are kept in safety snapshots either way, so skipped confirmations can still be undone."))
+            .build();
+        page.add(&group);
+
+        let names = [gettext("Every Destructive Action"), gettext("Deletions Only"), gettext("Never")];
+        let name_refs: Vec<&str> = names.iter().map(String::as_str).collect();
+        let level = config.borrow().confirmation_level();
+        let level_row = adw::ComboRow::builder()
+            .title(gettext("Ask Before"))
+            .subtitle(gettext("Replacing saves with imports or restores only asks with the first option"))
+            .model(&gtk::StringList::new(&name_refs))
+            .selected(ConfirmationLevel::ALL.iter().position(|l| *l == level).unwrap_or(0) as u32)
+            .build();
+        group.add(&level_row);
+
+        level_row.connect_selected_notify(glib::clone!(@weak dialog => move |row| {
+            let level = ConfirmationLevel::ALL.get(row.selected() as usize).copied().unwrap_or_default();
+            if let Err(e) = config.borrow_mut().set_confirmation_level(level) {
+                eprintln!("Error saving confirmation setting: {}", e);
+                error_dialog::show(Some(dialog.upcast_ref()), &gettext("Error Saving Settings"), &e);
+            }
+        }));
+    }
+
+    fn build_compression_group(page: &PreferencesPage, config: Rc<RefCell<Config>>, dialog: &PreferencesWindow) {
+        let compression_group = PreferencesGroup::builder()
+            .title(gettext("Snapshot Compression"))
+            .description(gettext("Compressing keeps snapshots of big saves small, but they have to be unpacked to be browsed or restored. Applies to new snapshots only."))
+            .build();
+        page.add(&compression_group);
+
+        let compression = config.borrow().snapshot_compression();
+        let names: Vec<String> = Compression::ALL
+            .iter()
+            .map(|method| method.name().map(str::to_string).unwrap_or_else(|| gettext("None")))
+            .collect();
+        let name_refs: Vec<&str> = names.iter().map(String::as_str).collect();
+        let method_row = adw::ComboRow::builder()
+            .title(gettext("Compression"))
+            .subtitle(gettext("zstd is faster at the same size, gzip works everywhere"))
+            .model(&gtk::StringList::new(&name_refs))
+            .selected(Compression::ALL.iter().position(|method| *method == compression.method).unwrap_or(0) as u32)
+            .build();
+        compression_group.add(&method_row);
+
+        // Higher is smaller but slower, the range depends on the compressor
+        let level_scale = gtk::Scale::builder()
+            .orientation(gtk::Orientation::Horizontal)
+            .digits(0)
+            .draw_value(true)
+            .hexpand(true)
+            .width_request(200)
+            .valign(Align::Center)
+            .build();
+        let level_row = ActionRow::builder()
+            .title(gettext("Compression Level"))
+            .subtitle(gettext("Higher is smaller but slower"))
+            .build();
+        level_row.add_suffix(&level_scale);
+        compression_group.add(&level_row);
+        Self::update_level_scale(&level_scale, &level_row, compression);
+
+        let save: Rc<dyn Fn(SnapshotCompression)> = Rc::new(glib::clone!(@strong config, @weak dialog => move |compression| {
+            if let Err(e) = config.borrow_mut().set_snapshot_compression(compression) {
+                eprintln!("Error saving snapshot compression: {}", e);
+                error_dialog::show(Some(dialog.upcast_ref()), &gettext("Error Saving Settings"), &e);
+            }
+        }));
+
+        let save_clone = save.clone();
+        method_row.connect_selected_notify(glib::clone!(@weak level_scale, @weak level_row => move |row| {
+            let method = Compression::ALL.get(row.selected() as usize).copied().unwrap_or_default();
+            // A level for one compressor means nothing to the other
+            let compression = SnapshotCompression { method, level: method.default_level() };
+            Self::update_level_scale(&level_scale, &level_row, compression);
+            save_clone(compression);
+        }));
+        level_scale.connect_value_changed(move |scale| {
+            let method = Compression::ALL.get(method_row.selected() as usize).copied().unwrap_or_default();
+            if method != Compression::None {
+                save(SnapshotCompression { method, level: scale.value().round() as u32 });
+            }
+        });
+    }
+
+    fn update_level_scale(scale: &gtk::Scale, row: &ActionRow, compression: SnapshotCompression) {
+        let (min, max) = compression.method.level_range();
+        row.set_sensitive(compression.method != Compression::None);
+        scale.set_range(min as f64, max.max(min + 1) as f64);
+        scale.set_increments(1.0, 1.0);
+        scale.set_value(compression.level.clamp(min, max) as f64);
+    }
+
+    // "Keep at most" and "keep for" rows, pre-filled from `policy`
+    fn retention_spin_rows(policy: RetentionPolicy) -> (SpinRow, SpinRow) {
+        let max_row = SpinRow::with_range(0.0, 100.0, 1.0);
+        max_row.set_title(&gettext("Snapshots to Keep per Game"));
+        max_row.set_subtitle(&gettext("0 keeps all of them"));
+        max_row.set_value(policy.max_snapshots as f64);
+
+        let age_row = SpinRow::with_range(0.0, 365.0, 1.0);
+        age_row.set_title(&gettext("Days to Keep Snapshots"));
+        age_row.set_subtitle(&gettext("0 never expires them"));
+        age_row.set_value(policy.max_age_days as f64);
+
+        (max_row, age_row)
+    }
+
+    fn retention_from_rows(max_row: &SpinRow, age_row: &SpinRow) -> RetentionPolicy {
+        RetentionPolicy {
+            max_snapshots: max_row.value() as u32,
+            max_age_days: age_row.value() as u32,
+        }
+    }
+
+    // Show how much the current policy would reclaim
+    fn update_prune_row(row: &ActionRow, config: &Config) {
+        let prunable = snapshot::prunable_snapshots(config);
+        if prunable.is_empty() {
+            row.set_subtitle(&gettext("Nothing to prune"));
+        } else {
+            let size: u64 = prunable.iter().map(|s| s.size()).sum();
+            row.set_subtitle(&gettext_f(
+                "{} snapshots can be removed, freeing {}",
+                &[&prunable.len().to_string(), &format_size(size, DECIMAL)],
+            ));
+        }
+    }
+
+    // Offline mode plus one switch per network feature (see `net`)
+    // Global save size limit, and the per-game ones set from the Games page
+    fn build_quota_group(page: &PreferencesPage, config: Rc<RefCell<Config>>, dialog: &PreferencesWindow) {
+        let quota_group = PreferencesGroup::builder()
+            .title(gettext("Save Size Warnings"))
+            .description(gettext("Warn about save folders bigger than this, like games writing a screenshot with every save. Click the warning on the Games page to give a game its own limit."))
+            .build();
+        page.add(&quota_group);
+
+        let quota = config.borrow().save_quota();
+        let limit_row = SpinRow::with_range(0.0, 1_000_000.0, 10.0);
+        limit_row.set_title(&gettext("Warn Above (MB)"));
+        limit_row.set_subtitle(&gettext("For each save folder, 0 switches the warning off"));
+        limit_row.set_value(quota.max_mb as f64);
+        quota_group.add(&limit_row);
+        let notify_row = SwitchRow::builder()
+            .title(gettext("Desktop Notification"))
+            .subtitle(gettext("Also notify when a scan finds saves over the limit"))
+            .active(quota.notify)
+            .build();
+        quota_group.add(&notify_row);
+
+        let save = glib::clone!(@strong config, @weak dialog, @weak limit_row, @weak notify_row => move || {
+            let quota = SaveQuota { max_mb: limit_row.value() as u64, notify: notify_row.is_active() };
+            if let Err(e) = config.borrow_mut().set_save_quota(quota) {
+                eprintln!("Error saving save size limit: {}", e);
+                error_dialog::show(Some(dialog.upcast_ref()), &gettext("Error Saving Settings"), &e);
+            }
+        });
+        let save = Rc::new(save);
+        let save_clone = save.clone();
+        limit_row.connect_value_notify(move |_| save_clone());
+        notify_row.connect_active_notify(move |_| save());
+
+        // Games with their own limit, removing one puts it back on the global limit
+        let mut overrides: Vec<(String, u64)> = config.borrow().save_quota_overrides().iter().map(|(id, mb)| (id.clone(), *mb)).collect();
+        overrides.sort_by_key(|(id, _)| (id.parse::<u64>().unwrap_or(u64::MAX), id.clone()));
+        for (game_id, max_mb) in overrides {
+            let title = match config.borrow().display_name(&game_id) {
+                Some(name) => format!("{} ({})", glib::markup_escape_text(name), game_id),
+                None => gettext_f("Game ID: {}", &[&game_id]),
+            };
+            let row = ActionRow::builder()
+                .title(title)
+                .subtitle(if max_mb == 0 {
+                    gettext("No warning for this game")
+                } else {
+                    gettext_f("Own limit: {} MB", &[&max_mb.to_string()])
+                })
+                .build();
+            let remove_button = Button::from_icon_name("edit-clear-symbolic");
+            remove_button.set_tooltip_text(Some(&gettext("Use the Global Limit")));
+            remove_button.set_valign(Align::Center);
+            remove_button.add_css_class("flat");
+            row.add_suffix(&remove_button);
+            let config_clone = config.clone();
+            let dialog_clone = dialog.clone();
+            let group_clone = quota_group.clone();
+            let row_clone = row.clone();
+            remove_button.connect_clicked(move |_| {
+                if let Err(e) = config_clone.borrow_mut().set_save_quota_override(&game_id, None) {
+                    eprintln!("Error removing size limit of {}: {}", game_id, e);
+                    error_dialog::show(Some(dialog_clone.upcast_ref()), &gettext("Error Saving Settings"), &e);
+                    return;
+                }
+                group_clone.remove(&row_clone);
+            });
+            quota_group.add(&row);
+        }
+    }
+
+    // How many prefixes are scanned and save folders copied at once
+    fn build_io_limits_group(page: &PreferencesPage, config: Rc<RefCell<Config>>, dialog: &PreferencesWindow) {
+        let group = PreferencesGroup::builder()
+            .title(gettext("Disk Activity"))
+            .description(gettext("Set both to 1 for hard drives and SD cards, which slow down when read in several places at once. Fast SSDs can take more."))
+            .build();
+        page.add(&group);
+
+        let limits = config.borrow().io_limits();
+        let scan_row = SpinRow::with_range(1.0, MAX_IO_THREADS as f64, 1.0);
+        scan_row.set_title(&gettext("Prefixes Scanned at Once"));
+        scan_row.set_subtitle(&gettext("When refreshing and measuring prefix sizes"));
+        scan_row.set_value(limits.scan_threads as f64);
+        group.add(&scan_row);
+        let copy_row = SpinRow::with_range(1.0, MAX_IO_THREADS as f64, 1.0);
+        copy_row.set_title(&gettext("Save Folders Copied at Once"));
+        copy_row.set_subtitle(&gettext("For safety snapshots and save bundles"));
+        copy_row.set_value(limits.copy_threads as f64);
+        group.add(&copy_row);
+
+        let save = glib::clone!(@strong config, @weak dialog, @weak scan_row, @weak copy_row => move || {
+            let limits = IoLimits { scan_threads: scan_row.value() as usize, copy_threads: copy_row.value() as usize };
+            if let Err(e) = config.borrow_mut().set_io_limits(limits) {
+                eprintln!("Error saving disk activity limits: {}", e);
+                error_dialog::show(Some(dialog.upcast_ref()), &gettext("Error Saving Settings"), &e);
+            }
+        });
+        let save = Rc::new(save);
+        let save_clone = save.clone();
+        scan_row.connect_value_notify(move |_| save_clone());
+        copy_row.connect_value_notify(move |_| save());
+    }
+
+    // Destinations offered when exporting a save bundle, next to local folders and mounts
+    fn build_backup_targets_group(page: &PreferencesPage, config: Rc<RefCell<Config>>, dialog: &PreferencesWindow) {
+        let group = PreferencesGroup::builder()
+            .title(gettext("Backup Destinations"))
+            .description(gettext("Folders and servers \"Export All Saves\" can send bundles to. SFTP logs in with your SSH key or agent, WebDAV passwords are stored in a file only you can read, use an app password."))
+            .build();
+        page.add(&group);
+
+        let add_button = Button::with_label(&gettext("Add…"));
+        add_button.add_css_class("flat");
+        group.set_header_suffix(Some(&add_button));
+        for target in config.borrow().backup_targets() {
+            Self::add_backup_target_row(&group, config.clone(), dialog, target);
+        }
+        add_button.connect_clicked(glib::clone!(@strong config, @weak dialog, @weak group => move |_| {
+            Self::show_add_backup_target_dialog(&dialog, config.clone(), &group);
+        }));
+    }
+
+    fn add_backup_target_row(group: &PreferencesGroup, config: Rc<RefCell<Config>>, dialog: &PreferencesWindow, target: &BackupTargetSettings) {
+        let subtitle = match target {
+            BackupTargetSettings::Local { path, .. } => path.display().to_string(),
+            BackupTargetSettings::Sftp { host, port, user, path, .. } => {
+                let login = if user.is_empty() { host.clone() } else { format!("{}@{}", user, host) };
+                format!("sftp://{}:{}/{}", login, port, path.trim_start_matches('/'))
+            }
+            BackupTargetSettings::WebDav { url, .. } => url.clone(),
+        };
+        let row = ActionRow::builder()
+            .title(glib::markup_escape_text(target.name()))
+            .subtitle(glib::markup_escape_text(&subtitle))
+            .build();
+        let remove_button = Button::from_icon_name("user-trash-symbolic");
+        remove_button.set_tooltip_text(Some(&gettext("Remove Destination")));
+        remove_button.set_valign(Align::Center);
+        remove_button.add_css_class("flat");
+        row.add_suffix(&remove_button);
+
+        let name = target.name().to_string();
+        remove_button.connect_clicked(glib::clone!(@weak group, @weak row, @weak dialog => move |_| {
+            if let Err(e) = config.borrow_mut().remove_backup_target(&name) {
+                eprintln!("Error removing backup destination {}: {}", name, e);
+                error_dialog::show(Some(dialog.upcast_ref()), &gettext("Error Saving Settings"), &e);
+                return;
+            }
+            group.remove(&row);
+        }));
+        group.add(&row);
+    }
+
+    // Kind of destination, then only the fields that kind needs
+    fn show_add_backup_target_dialog(parent: &PreferencesWindow, config: Rc<RefCell<Config>>, group: &PreferencesGroup) {
+        let dialog = MessageDialog::builder()
+            .heading(gettext("Add Backup Destination"))
+            .transient_for(parent)
+            .modal(true)
+            .build();
+        let kind_row = adw::ComboRow::builder()
+            .title(gettext("Type"))
+            .model(&gtk::StringList::new(&[&gettext("Local Folder"), "SFTP", "WebDAV"]))
+            .build();
+        let name_row = EntryRow::builder().title(gettext("Name")).build();
+        let folder_row = EntryRow::builder().title(gettext("Folder")).build();
+        let host_row = EntryRow::builder().title(gettext("Server")).build();
+        let port_row = SpinRow::with_range(1.0, 65535.0, 1.0);
+        port_row.set_title(&gettext("Port"));
+        port_row.set_value(22.0);
+        let user_row = EntryRow::builder().title(gettext("User")).build();
+        let url_row = EntryRow::builder().title(gettext("Folder URL")).input_purpose(gtk::InputPurpose::Url).build();
+        let password_row = adw::PasswordEntryRow::builder().title(gettext("Password")).build();
+
+        let list = gtk::ListBox::new();
+        list.set_selection_mode(gtk::SelectionMode::None);
+        list.add_css_class("boxed-list");
+        list.append(&kind_row);
+        list.append(&name_row);
+        list.append(&host_row);
+        list.append(&port_row);
+        list.append(&url_row);
+        list.append(&user_row);
+        list.append(&password_row);
+        list.append(&folder_row);
+        dialog.set_extra_child(Some(&list));
+
+        let update_rows = glib::clone!(@weak host_row, @weak port_row, @weak url_row, @weak user_row, @weak password_row, @weak folder_row => move |kind: u32| {
+            host_row.set_visible(kind == 1);
+            port_row.set_visible(kind == 1);
+            url_row.set_visible(kind == 2);
+            user_row.set_visible(kind != 0);
+            password_row.set_visible(kind == 2);
+            folder_row.set_visible(kind != 2);
+            folder_row.set_title(&if kind == 0 { gettext("Folder") } else { gettext("Folder on the Server") });
+        });
+        update_rows(0);
+        kind_row.connect_selected_notify(move |row| update_rows(row.selected()));
+
+        dialog.add_response("cancel", &gettext("Cancel"));
+        dialog.add_response("add", &gettext("Add"));
+        dialog.set_response_appearance("add", adw::ResponseAppearance::Suggested);
+        dialog.set_default_response(Some("add"));
+        dialog.set_close_response("cancel");
+
+        let parent = parent.clone();
+        let group = group.clone();
+        dialog.connect_response(None, move |_, response| {
+            if response != "add" {
+                return;
+            }
+            let name = name_row.text().trim().to_string();
+            let user = user_row.text().trim().to_string();
+            let folder = folder_row.text().trim().to_string();
+            let target = match kind_row.selected() {
+                0 => BackupTargetSettings::Local { name, path: PathBuf::from(folder) },
+                1 => BackupTargetSettings::Sftp { name, host: host_row.text().trim().to_string(), port: port_row.value() as u16, user, path: folder },
+                _ => BackupTargetSettings::WebDav { name, url: url_row.text().trim().to_string(), user, password: password_row.text().to_string() },
+            };
+            if let Err(e) = config.borrow_mut().add_backup_target(target.clone()) {
+                eprintln!("Error adding backup destination: {}", e);
+                error_dialog::show(Some(parent.upcast_ref()), &gettext("Invalid Backup Destination"), &e);
+                return;
+            }
+            Self::add_backup_target_row(&group, config.clone(), &parent, &target);
+        });
+        dialog.present();
+    }
+
+    fn build_privacy_group(page: &PreferencesPage, config: Rc<RefCell<Config>>, dialog: &PreferencesWindow) {
+        let privacy_group = PreferencesGroup::builder()
+            .title(gettext("Privacy"))
+            .description(gettext("Choose which features may connect to the network. Each one is off until switched on here, except manifest downloads: without the manifest the Games page can't match anything."))
+            .build();
+        page.add(&privacy_group);
+
+        let privacy = config.borrow().privacy();
+        let offline_row = SwitchRow::builder()
+            .title(gettext("Offline Mode"))
+            .subtitle(gettext("Never connect to the internet, overrides the switches below"))
+            .active(privacy.offline_mode)
+            .build();
+        privacy_group.add(&offline_row);
+
+        let feature_rows: Vec<PrivacyToggle> = vec![(
+            SwitchRow::builder()
+                .title(gettext("Manifest Downloads"))
+                .subtitle(gettext("Download the game data manifest and remote manifest sources"))
+                .active(privacy.manifest_updates)
+                .sensitive(!privacy.offline_mode)
+                .build(),
+            |p, v| p.manifest_updates = v,
+        ), (
+            SwitchRow::builder()
+                .title(gettext("Backup Uploads"))
+                .subtitle(gettext("Send save bundles to the SFTP and WebDAV destinations set up under Backup Destinations"))
+                .active(privacy.backup_uploads)
+                .sensitive(!privacy.offline_mode)
+                .build(),
+            |p, v| p.backup_uploads = v,
+        ), (
+            SwitchRow::builder()
+                .title(gettext("LAN Sync"))
+                .subtitle(gettext("Share saves with and sync them from other machines on the local network"))
+                .active(privacy.lan_sync)
+                .sensitive(!privacy.offline_mode)
+                .build(),
+            |p, v| p.lan_sync = v,
+        )];
+
+        for (row, apply) in &feature_rows {
+            let config_clone = config.clone();
+            let dialog_clone = dialog.clone();
+            let apply = *apply;
+            row.connect_active_notify(move |row| {
+                let mut privacy = config_clone.borrow().privacy();
+                apply(&mut privacy, row.is_active());
+                if let Err(e) = config_clone.borrow_mut().set_privacy(privacy) {
+                    eprintln!("Error saving privacy settings: {}", e);
+                    error_dialog::show(Some(dialog_clone.upcast_ref()), &gettext("Error Saving Settings"), &e);
+                }
+            });
+            privacy_group.add(row);
+        }
+
+        let dialog_clone = dialog.clone();
+        let rows: Vec<SwitchRow> = feature_rows.into_iter().map(|(row, _)| row).collect();
+        offline_row.connect_active_notify(move |row| {
+            let mut privacy = config.borrow().privacy();
+            privacy.offline_mode = row.is_active();
+            if let Err(e) = config.borrow_mut().set_privacy(privacy) {
+                eprintln!("Error saving privacy settings: {}", e);
+                error_dialog::show(Some(dialog_clone.upcast_ref()), &gettext("Error Saving Settings"), &e);
+            }
+            for feature_row in &rows {
+                feature_row.set_sensitive(!row.is_active());
+            }
+        });
+    }
+
+    // Carry the settings over to another machine, e.g. desktop and Steam Deck
+    fn build_transfer_group(page: &PreferencesPage, config: Rc<RefCell<Config>>, dialog: &PreferencesWindow, parent: &adw::ApplicationWindow) {
+        let group = PreferencesGroup::builder()
+            .title(gettext("Other Machines"))
+            .description(gettext("Export these settings with custom save paths, manual matches, names, favorites and backup destinations to a file, and import it on another machine to set it up the same way. Passwords of WebDAV destinations are left out unless included."))
+            .build();
+        page.add(&group);
+
+        let steam_path_row = SwitchRow::builder()
+            .title(gettext("Include Steam Folder"))
+            .subtitle(gettext("Only if it's the same on the other machine, it's ignored there if it doesn't exist"))
+            .build();
+        group.add(&steam_path_row);
+
+        let passwords_row = SwitchRow::builder()
+            .title(gettext("Include Passwords"))
+            .subtitle(gettext("Puts the WebDAV passwords in the file as plain text, anyone who gets the file can log in with them"))
+            .build();
+        group.add(&passwords_row);
+
+        let export_row = ActionRow::builder()
+            .title(gettext("Export Settings"))
+            .build();
+        let export_button = Button::with_label(&gettext("Export…"));
+        export_button.set_valign(Align::Center);
+        export_row.add_suffix(&export_button);
+        export_row.set_activatable_widget(Some(&export_button));
+        group.add(&export_row);
+        export_button.connect_clicked(glib::clone!(@strong config, @weak dialog, @weak steam_path_row, @weak passwords_row => move |_| {
+            let config = config.clone();
+            let include_steam_path = steam_path_row.is_active();
+            let include_passwords = passwords_row.is_active();
+            glib::MainContext::default().spawn_local(async move {
+                let file_dialog = FileDialog::new();
+                file_dialog.set_title(&gettext("Export Settings"));
+                file_dialog.set_initial_name(Some("proton_game_saves settings.json"));
+                let Ok(file) = file_dialog.save_future(Some(&dialog)).await else {
+                    return; // Cancelled
+                };
+                let Some(path) = file.path() else {
+                    return;
+                };
+                let result = config.borrow().export_settings(&path, include_steam_path, include_passwords);
+                if let Err(e) = result {
+                    eprintln!("Error exporting settings: {}", e);
+                    error_dialog::show(Some(dialog.upcast_ref()), &gettext("Error Exporting Settings"), &e);
+                }
+            });
+        }));
+
+        let import_row = ActionRow::builder()
+            .title(gettext("Import Settings"))
+            .subtitle(gettext("Replaces the settings here and closes this dialog"))
+            .build();
+        let import_button = Button::with_label(&gettext("Import…"));
+        import_button.set_valign(Align::Center);
+        import_row.add_suffix(&import_button);
+        import_row.set_activatable_widget(Some(&import_button));
+        group.add(&import_row);
+        import_button.connect_clicked(glib::clone!(@strong config, @weak dialog, @weak parent => move |_| {
+            let config = config.clone();
+            glib::MainContext::default().spawn_local(async move {
+                let file_dialog = FileDialog::new();
+                file_dialog.set_title(&gettext("Import Settings"));
+                let Ok(file) = file_dialog.open_future(Some(&dialog)).await else {
+                    return; // Cancelled
+                };
+                let Some(path) = file.path() else {
+                    return;
+                };
+                let confirm_dialog = MessageDialog::builder()
+                    .transient_for(&dialog)
+                    .heading(gettext("Replace Settings?"))
+                    .body(gettext_f("The settings here are replaced with the ones in {}.", &[&path.display().to_string()]))
+                    .build();
+                confirm_dialog.add_response("cancel", &gettext("Cancel"));
+                confirm_dialog.add_response("import", &gettext("Import"));
+                confirm_dialog.set_response_appearance("import", adw::ResponseAppearance::Destructive);
+                confirm_dialog.set_default_response(Some("cancel"));
+                confirm_dialog.set_close_response("cancel");
+                if !confirm::ask(&config, confirm_dialog, ConfirmAction::Replace, "import-settings", "import").await {
+                    return;
+                }
+                let result = config.borrow_mut().import_settings(&path);
+                match result {
+                    Ok(()) => {
+                        // The rows here still show the old values
+                        dialog.close();
+                        WidgetExt::activate_action(&parent, "app.refresh", None).ok();
+                    }
+                    Err(e) => {
+                        eprintln!("Error importing settings: {}", e);
+                        error_dialog::show(Some(dialog.upcast_ref()), &gettext("Error Importing Settings"), &e);
+                    }
+                }
+            });
+        }));
+    }
+
+    // Adds a row for an existing manifest source with a button to remove it
+    fn add_source_row(group: &PreferencesGroup, config: Rc<RefCell<Config>>, source: &str) {
+        let row = ActionRow::builder()
+            .title(source)
+            .subtitle(if crate::config::is_remote_source(source) { gettext("Remote manifest") } else { gettext("Local file") })
+            .build();
+        let remove_button = Button::from_icon_name("user-trash-symbolic");
+        remove_button.set_tooltip_text(Some(&gettext("Remove Source")));
+        remove_button.set_valign(Align::Center);
+        remove_button.add_css_class("flat");
+        row.add_suffix(&remove_button);
+
+        let source = source.to_string();
+        let group_clone = group.clone();
+        let row_clone = row.clone();
+        remove_button.connect_clicked(move |_| {
+            if let Err(e) = config.borrow_mut().remove_manifest_source(&source) {
+                eprintln!("Error removing manifest source: {}", e);
+                return;
+            }
+            group_clone.remove(&row_clone);
+        });
+        group.add(&row);
     }
     
     pub fn present(&self) {
@@ -127,7 +1181,7 @@ impl SettingsDialog {
     // Renamed for clarity and made async helper
     async fn show_steam_folder_chooser_async(parent: Window, config: Rc<RefCell<Config>>, row: ActionRow) {
         let file_dialog = FileDialog::new();
-        file_dialog.set_title("Select Steam Directory");
+        file_dialog.set_title(&gettext("Select Steam Directory"));
         // FileChooserAction::SelectFolder isn't directly used, instead use appropriate method
 
         // Use select_folder_future
@@ -137,7 +1191,7 @@ impl SettingsDialog {
                     println!("Selected folder: {}", path.display());
                     if let Err(e) = config.borrow_mut().set_steam_path(path.clone()) {
                          eprintln!("Error setting steam path: {}", e);
-                         Self::show_error_dialog_transient(&parent, "Error Setting Path", &format!("Failed to set Steam path: {}", e));
+                         error_dialog::show(Some(&parent), &gettext("Error Setting Path"), &e.context(gettext("Failed to set Steam path")));
                     } else {
                         row.set_subtitle(&path.to_string_lossy());
                     }
@@ -149,23 +1203,25 @@ impl SettingsDialog {
                      println!("Folder selection cancelled.");
                 } else {
                     eprintln!("Error selecting folder: {}", e);
-                    Self::show_error_dialog_transient(&parent, "Selection Error", &format!("Failed to select folder: {}", e));
+                    error_dialog::show(Some(&parent), &gettext("Selection Error"), &anyhow::Error::from(e).context(gettext("Failed to select folder")));
                 }
             }
         }
     }
     
-    // Helper to show error dialog, requires parent window
-    fn show_error_dialog_transient(parent: &impl IsA<Window>, title: &str, message: &str) {
-        // Ensure this runs on the main thread if called from async context
-        // glib::MainContext::default().spawn_local might be needed if calling from non-main thread
-        let dialog = MessageDialog::builder()
-            .transient_for(parent)
-            .modal(true)
-            .heading(title)
-            .body(message)
-            .build();
-        dialog.add_response("ok", "OK");
-        dialog.present();
+    // Path of the other tool's manifest, and whether our downloads may update it
+    fn shared_manifest_subtitle(path: &Path) -> String {
+        if fs::OpenOptions::new().write(true).open(path).is_ok() {
+            path.display().to_string()
+        } else {
+            gettext_f("{} (read-only, updated by ludusavi)", &[&path.display().to_string()])
+        }
+    }
+
+    fn sync_folder_subtitle(path: Option<&Path>) -> String {
+        match path {
+            Some(path) => path.display().to_string(),
+            None => gettext("Not set. Choose a folder shared by a sync tool such as Syncthing, then use \"Link to Sync Folder\" in a save location's menu"),
+        }
     }
 } 
\ No newline at end of file
@@ -0,0 +1,101 @@
+use adw::prelude::*;
+use adw::MessageDialog;
+use humansize::{format_size, DECIMAL};
+
+use crate::i18n::{gettext, gettext_f};
+use crate::report;
+use crate::size_history::SizePoint;
+
+// Plot of a game's save size at every scan, to spot saves that balloon
+pub fn show(parent: Option<&gtk::Window>, name: &str, points: Vec<SizePoint>) {
+    let (Some(first), Some(last)) = (points.first().copied(), points.last().copied()) else {
+        return;
+    };
+    let dialog = MessageDialog::builder()
+        .heading(gettext("Save Size History"))
+        .body(gettext_f("Size of the saves of {} at each scan", &[name]))
+        .modal(true)
+        .build();
+    dialog.set_transient_for(parent);
+
+    let content = gtk::Box::new(gtk::Orientation::Vertical, 6);
+    let max_bytes = points.iter().map(|point| point.bytes).max().unwrap_or(0);
+    let min_bytes = points.iter().map(|point| point.bytes).min().unwrap_or(0);
+
+    let range_label = gtk::Label::new(Some(&gettext_f(
+        "From {} to {}",
+        &[&format_size(min_bytes, DECIMAL), &format_size(max_bytes, DECIMAL)],
+    )));
+    range_label.add_css_class("dim-label");
+    range_label.set_halign(gtk::Align::Start);
+    content.append(&range_label);
+
+    let plot = gtk::DrawingArea::builder().content_width(460).content_height(200).build();
+    plot.set_draw_func(move |area, cr, width, height| draw_plot(area, cr, width, height, &points));
+    content.append(&plot);
+
+    // Dates at both ends of the time axis
+    let dates = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+    let start_label = gtk::Label::new(Some(&date_of(first.time)));
+    start_label.set_hexpand(true);
+    start_label.set_halign(gtk::Align::Start);
+    let end_label = gtk::Label::new(Some(&date_of(last.time)));
+    for label in [&start_label, &end_label] {
+        label.add_css_class("dim-label");
+        label.add_css_class("caption");
+        dates.append(label);
+    }
+    content.append(&dates);
+
+    dialog.set_extra_child(Some(&content));
+    dialog.add_response("close", &gettext("Close"));
+    dialog.present();
+}
+
+// Line from the oldest point to the newest, sizes scaled from 0 to the biggest
+fn draw_plot(area: &gtk::DrawingArea, cr: &gtk::cairo::Context, width: i32, height: i32, points: &[SizePoint]) {
+    let color = area.color();
+    let (width, height) = (width as f64, height as f64);
+    let margin = 6.0;
+
+    // Baseline, dimmed like the labels
+    cr.set_source_rgba(color.red() as f64, color.green() as f64, color.blue() as f64, 0.25);
+    cr.set_line_width(1.0);
+    cr.move_to(margin, height - margin);
+    cr.line_to(width - margin, height - margin);
+    cr.stroke().ok();
+
+    let (Some(first), Some(last)) = (points.first(), points.last()) else {
+        return;
+    };
+    let max_bytes = points.iter().map(|point| point.bytes).max().unwrap_or(0).max(1) as f64;
+    let span = (last.time - first.time).max(1) as f64;
+    let x_of = |point: &SizePoint| {
+        if points.len() == 1 {
+            width / 2.0
+        } else {
+            margin + (point.time - first.time) as f64 / span * (width - 2.0 * margin)
+        }
+    };
+    let y_of = |point: &SizePoint| height - margin - point.bytes as f64 / max_bytes * (height - 2.0 * margin);
+
+    cr.set_source_rgba(color.red() as f64, color.green() as f64, color.blue() as f64, 1.0);
+    cr.set_line_width(2.0);
+    for (index, point) in points.iter().enumerate() {
+        if index == 0 {
+            cr.move_to(x_of(point), y_of(point));
+        } else {
+            cr.line_to(x_of(point), y_of(point));
+        }
+    }
+    cr.stroke().ok();
+    for point in points {
+        cr.arc(x_of(point), y_of(point), 3.0, 0.0, std::f64::consts::TAU);
+        cr.fill().ok();
+    }
+}
+
+// Just the day of an ISO timestamp, the time of the scan doesn't matter here
+fn date_of(secs: u64) -> String {
+    report::format_timestamp_utc(secs).chars().take(10).collect()
+}
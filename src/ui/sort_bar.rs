@@ -0,0 +1,125 @@
+use adw::prelude::*;
+use gtk::SearchEntry;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::config::{Config, ViewState};
+use crate::i18n::gettext;
+
+// Sort dropdown and direction toggle shown next to a page's search entry. The
+// choice and the search text are remembered per page in the config, like the
+// open rows with `remember_expanded`.
+#[derive(Clone)]
+pub struct SortBar {
+    widget: gtk::Box,
+    dropdown: gtk::DropDown,
+    direction: gtk::ToggleButton,
+    keys: Rc<Vec<&'static str>>,
+}
+
+impl SortBar {
+    // `options` are (sort key, label) pairs, the first one is the default
+    pub fn new(config: Rc<RefCell<Config>>, page: &'static str, options: &[(&'static str, String)], search_entry: &SearchEntry) -> Self {
+        let labels: Vec<&str> = options.iter().map(|(_, label)| label.as_str()).collect();
+        let dropdown = gtk::DropDown::from_strings(&labels);
+        dropdown.set_tooltip_text(Some(&gettext("Sort By")));
+        dropdown.set_valign(gtk::Align::Center);
+
+        let direction = gtk::ToggleButton::new();
+        direction.set_valign(gtk::Align::Center);
+        direction.add_css_class("flat");
+
+        let widget = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+        widget.append(&dropdown);
+        widget.append(&direction);
+
+        let bar = Self {
+            widget,
+            dropdown,
+            direction,
+            keys: Rc::new(options.iter().map(|(key, _)| *key).collect()),
+        };
+
+        // Restore the last state before connecting, so restoring doesn't save it again
+        let state = config.borrow().view_state(page);
+        if let Some(index) = bar.keys.iter().position(|key| *key == state.sort_key) {
+            bar.dropdown.set_selected(index as u32);
+        }
+        bar.direction.set_active(state.descending);
+        bar.update_direction_icon();
+        search_entry.set_text(&state.filter);
+
+        let save = {
+            let bar = bar.clone();
+            let search_entry = search_entry.clone();
+            move || {
+                // Keep whatever else the page stores in its state
+                let state = ViewState {
+                    sort_key: bar.key().to_string(),
+                    descending: bar.descending(),
+                    filter: search_entry.text().to_string(),
+                    ..config.borrow().view_state(page)
+                };
+                if let Err(e) = config.borrow_mut().set_view_state(page, state) {
+                    eprintln!("Error saving view state of {} page: {}", page, e);
+                }
+            }
+        };
+        let save = Rc::new(save);
+        let save_clone = save.clone();
+        bar.dropdown.connect_selected_notify(move |_| save_clone());
+        let save_clone = save.clone();
+        let bar_clone = bar.clone();
+        bar.direction.connect_toggled(move |_| {
+            bar_clone.update_direction_icon();
+            save_clone();
+        });
+        search_entry.connect_search_changed(move |_| save());
+
+        bar
+    }
+
+    pub fn widget(&self) -> &gtk::Box {
+        &self.widget
+    }
+
+    pub fn key(&self) -> &'static str {
+        self.keys.get(self.dropdown.selected() as usize).copied().unwrap_or(self.keys[0])
+    }
+
+    pub fn descending(&self) -> bool {
+        self.direction.is_active()
+    }
+
+    // Run `f` whenever the sort key or direction changes
+    pub fn connect_changed<F: Fn() + 'static>(&self, f: F) {
+        let f = Rc::new(f);
+        let f_clone = f.clone();
+        self.dropdown.connect_selected_notify(move |_| f_clone());
+        self.direction.connect_toggled(move |_| f());
+    }
+
+    fn update_direction_icon(&self) {
+        if self.descending() {
+            self.direction.set_icon_name("view-sort-descending-symbolic");
+            self.direction.set_tooltip_text(Some(&gettext("Descending")));
+        } else {
+            self.direction.set_icon_name("view-sort-ascending-symbolic");
+            self.direction.set_tooltip_text(Some(&gettext("Ascending")));
+        }
+    }
+}
+
+// Opens `row` if it was open when the page was last shown and remembers when
+// it's opened or closed, so the list keeps its open rows across refreshes and
+// restarts
+pub fn remember_expanded(row: &adw::ExpanderRow, config: &Rc<RefCell<Config>>, page: &'static str, app_id: &str) {
+    row.set_expanded(config.borrow().view_state(page).expanded.iter().any(|id| id == app_id));
+    let config = config.clone();
+    let app_id = app_id.to_string();
+    row.connect_expanded_notify(move |row| {
+        if let Err(e) = config.borrow_mut().set_row_expanded(page, &app_id, row.is_expanded()) {
+            eprintln!("Error saving open rows of {} page: {}", page, e);
+        }
+    });
+}
@@ -0,0 +1,289 @@
+use adw::prelude::*;
+use adw::ActionRow;
+use gtk::{gio, glib, Box, Label, ListBox, Orientation, ScrolledWindow, SelectionMode};
+use humansize::{format_size, DECIMAL};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use crate::compatdata::PrefixData;
+use crate::config::Config;
+use crate::fsutil;
+use crate::i18n::{gettext, gettext_f};
+use crate::prefix_sizes;
+use crate::size_history::{self, SizeHistory};
+use crate::ui::size_history_dialog;
+
+// How many of the biggest prefixes and saves get a bar
+const TOP_ENTRIES: usize = 10;
+// Growth of the saves is compared over this many days
+const GROWTH_DAYS: u64 = 30;
+
+// A game's save size, as matched on the Games page
+pub struct SaveSize {
+    pub app_id: String,
+    pub name: String,
+    pub size: u64,
+}
+
+// Sizes measured off the main thread, whole prefixes can hold tens of
+// thousands of files
+struct Measured {
+    prefixes: Vec<(String, u64)>, // App ID, size; biggest first
+    snapshots: u64,
+}
+
+pub struct SpacePage {
+    widget: Box,
+    config: Rc<RefCell<Config>>,
+    summary_list: ListBox,
+    prefix_list: ListBox,
+    saves_list: ListBox,
+    growth_list: ListBox,
+    spinner: gtk::Spinner,
+    // Bumped on every update, so a slow measurement can't overwrite a newer one
+    generation: Rc<Cell<u32>>,
+}
+
+impl SpacePage {
+    pub fn new(config: Rc<RefCell<Config>>) -> Self {
+        let container = Box::new(Orientation::Vertical, 12);
+        container.set_margin_start(12);
+        container.set_margin_end(12);
+        container.set_margin_top(12);
+        container.set_margin_bottom(12);
+
+        let header = Label::new(Some(&gettext("Disk Space")));
+        header.add_css_class("title-1");
+        container.append(&header);
+        let description = Label::new(Some(&gettext("See what takes up space, click a bar to jump to it")));
+        description.add_css_class("subtitle-1");
+        container.append(&description);
+
+        let spinner = gtk::Spinner::new();
+        spinner.set_halign(gtk::Align::Center);
+        container.append(&spinner);
+
+        let content = Box::new(Orientation::Vertical, 12);
+        let scroll = ScrolledWindow::builder()
+            .hscrollbar_policy(gtk::PolicyType::Never)
+            .vexpand(true)
+            .child(&content)
+            .build();
+        container.append(&scroll);
+
+        let summary_list = Self::add_section(&content, &gettext("Summary"));
+        let prefix_list = Self::add_section(&content, &gettext("Biggest Prefixes"));
+        let saves_list = Self::add_section(&content, &gettext("Biggest Saves"));
+        let growth_list = Self::add_section(&content, &gettext("Fastest Growing Saves"));
+
+        Self {
+            widget: container,
+            config,
+            summary_list,
+            prefix_list,
+            saves_list,
+            growth_list,
+            spinner,
+            generation: Rc::new(Cell::new(0)),
+        }
+    }
+
+    pub fn widget(&self) -> &Box {
+        &self.widget
+    }
+
+    // Show the sizes of a new scan. Save sizes are already known from the Games
+    // page, prefixes and snapshots are measured in the background; `badge` gets
+    // the total once that's done.
+    pub fn update(&self, prefixes: &[PrefixData], saves: Vec<SaveSize>, badge: &Label) {
+        let generation = self.generation.get() + 1;
+        self.generation.set(generation);
+
+        let names: HashMap<String, String> = saves.iter().map(|save| (save.app_id.clone(), save.name.clone())).collect();
+        let total_saves: u64 = saves.iter().map(|save| save.size).sum();
+        self.show_growth(&saves);
+        let mut saves = saves;
+        saves.sort_by_key(|save| std::cmp::Reverse(save.size));
+        saves.truncate(TOP_ENTRIES);
+        let max_save = saves.first().map(|save| save.size).unwrap_or(0);
+        Self::clear(&self.saves_list);
+        for save in &saves {
+            self.saves_list.append(&Self::create_bar_row(&save.name, &save.app_id, save.size, max_save, "app.show-game"));
+        }
+        if saves.is_empty() {
+            self.saves_list.append(&Self::create_placeholder_row(&gettext("No matched games")));
+        }
+
+        Self::clear(&self.summary_list);
+        Self::clear(&self.prefix_list);
+        self.prefix_list.append(&Self::create_placeholder_row(&gettext("Calculating…")));
+        self.spinner.set_spinning(true);
+        self.spinner.set_visible(true);
+
+        let prefix_paths: Vec<(String, PathBuf)> = prefixes.iter().map(|p| (p.game_id.clone(), p._path.clone())).collect();
+        let snapshots_path = self.config.borrow().safety_snapshots_path();
+        let sizes_cache_path = self.config.borrow().prefix_sizes_cache_path();
+        let threads = self.config.borrow().io_limits().scan_threads;
+        let config = self.config.clone();
+        let current_generation = self.generation.clone();
+        let summary_list = self.summary_list.clone();
+        let prefix_list = self.prefix_list.clone();
+        let spinner = self.spinner.clone();
+        let badge = badge.clone();
+        glib::MainContext::default().spawn_local(async move {
+            let measured = gio::spawn_blocking(move || {
+                let mut prefixes = prefix_sizes::measure_all(&sizes_cache_path, &prefix_paths, threads);
+                prefixes.sort_by_key(|(_, size)| std::cmp::Reverse(*size));
+                Measured { prefixes, snapshots: fsutil::disk_usage(&snapshots_path) }
+            })
+            .await;
+            if current_generation.get() != generation {
+                return;
+            }
+            spinner.set_spinning(false);
+            spinner.set_visible(false);
+            let Ok(measured) = measured else {
+                eprintln!("Measuring disk space failed");
+                return;
+            };
+
+            let total_prefixes: u64 = measured.prefixes.iter().map(|(_, size)| size).sum();
+            println!("Disk space: {} in prefixes, {} in safety snapshots", total_prefixes, measured.snapshots);
+            badge.set_label(&format_size(total_prefixes, DECIMAL));
+            summary_list.append(&Self::create_summary_row(
+                &gettext("Proton Prefixes"),
+                &gettext_f("{} prefixes", &[&measured.prefixes.len().to_string()]),
+                total_prefixes,
+            ));
+            summary_list.append(&Self::create_summary_row(
+                &gettext("Game Saves"),
+                &gettext("Save locations of matched games, part of the prefixes above"),
+                total_saves,
+            ));
+            summary_list.append(&Self::create_summary_row(
+                &gettext("Safety Snapshots"),
+                &gettext("Copies kept before deleting or replacing saves"),
+                measured.snapshots,
+            ));
+
+            Self::clear(&prefix_list);
+            let max_prefix = measured.prefixes.first().map(|(_, size)| *size).unwrap_or(0);
+            let config = config.borrow();
+            for (game_id, size) in measured.prefixes.iter().take(TOP_ENTRIES) {
+                let name = config
+                    .display_name(game_id)
+                    .or_else(|| names.get(game_id).map(String::as_str))
+                    .map(str::to_string)
+                    .unwrap_or_else(|| gettext_f("Game ID: {}", &[game_id]));
+                prefix_list.append(&Self::create_bar_row(&name, game_id, *size, max_prefix, "app.show-prefix"));
+            }
+            if measured.prefixes.is_empty() {
+                prefix_list.append(&Self::create_placeholder_row(&gettext("No Proton prefixes found")));
+            }
+        });
+    }
+
+    // Games whose saves grew the most lately, from the sizes of past scans
+    fn show_growth(&self, saves: &[SaveSize]) {
+        Self::clear(&self.growth_list);
+        let history = SizeHistory::load(&self.config.borrow().size_history_path());
+        let since = size_history::now_secs().saturating_sub(GROWTH_DAYS * 86_400);
+        let mut growing: Vec<(&SaveSize, u64)> = saves
+            .iter()
+            .filter_map(|save| {
+                let growth = history.growth_since(&save.app_id, since)?;
+                (growth > 0).then_some((save, growth as u64))
+            })
+            .collect();
+        growing.sort_by_key(|(_, growth)| std::cmp::Reverse(*growth));
+        growing.truncate(TOP_ENTRIES);
+
+        for (save, growth) in growing.iter().copied() {
+            let row = ActionRow::builder()
+                .title(glib::markup_escape_text(&save.name).as_str())
+                .subtitle(gettext_f(
+                    "+{} in the last {} days, {} now",
+                    &[&format_size(growth, DECIMAL), &GROWTH_DAYS.to_string(), &format_size(save.size, DECIMAL)],
+                ))
+                .activatable(true)
+                .tooltip_text(gettext("Show Save Size History"))
+                .build();
+            row.add_suffix(&gtk::Image::from_icon_name("go-next-symbolic"));
+            let name = save.name.clone();
+            let points = history.points(&save.app_id).to_vec();
+            row.connect_activated(move |row| {
+                let parent = row.root().and_downcast::<gtk::Window>();
+                size_history_dialog::show(parent.as_ref(), &name, points.clone());
+            });
+            self.growth_list.append(&row);
+        }
+        if growing.is_empty() {
+            self.growth_list.append(&Self::create_placeholder_row(&gettext("No saves grew lately, or there are no earlier scans to compare with yet")));
+        }
+    }
+
+    // Heading plus a boxed list for one part of the page
+    fn add_section(content: &Box, title: &str) -> ListBox {
+        let heading = Label::new(Some(title));
+        heading.add_css_class("heading");
+        heading.set_halign(gtk::Align::Start);
+        heading.set_margin_top(6);
+        content.append(&heading);
+
+        let list = ListBox::new();
+        list.set_selection_mode(SelectionMode::None);
+        list.add_css_class("boxed-list");
+        content.append(&list);
+        list
+    }
+
+    fn clear(list: &ListBox) {
+        while let Some(child) = list.first_child() {
+            list.remove(&child);
+        }
+    }
+
+    fn create_summary_row(title: &str, subtitle: &str, size: u64) -> ActionRow {
+        let row = ActionRow::builder().title(title).subtitle(subtitle).build();
+        let size_label = Label::new(Some(&format_size(size, DECIMAL)));
+        size_label.add_css_class("numeric");
+        row.add_suffix(&size_label);
+        row
+    }
+
+    // A row with a bar relative to the biggest entry. Activating it runs
+    // `action` with the App ID, which jumps to the game on its page.
+    fn create_bar_row(name: &str, app_id: &str, size: u64, max_size: u64, action: &'static str) -> ActionRow {
+        let row = ActionRow::builder()
+            .title(glib::markup_escape_text(name).as_str())
+            .subtitle(format_size(size, DECIMAL))
+            .activatable(true)
+            .build();
+        let bar = gtk::LevelBar::builder()
+            .min_value(0.0)
+            .max_value(1.0)
+            .value(if max_size > 0 { size as f64 / max_size as f64 } else { 0.0 })
+            .valign(gtk::Align::Center)
+            .width_request(160)
+            .build();
+        // Offsets would color the big ones like a warning, all bars look the same
+        bar.remove_offset_value(Some(gtk::LEVEL_BAR_OFFSET_LOW));
+        bar.remove_offset_value(Some(gtk::LEVEL_BAR_OFFSET_HIGH));
+        bar.remove_offset_value(Some(gtk::LEVEL_BAR_OFFSET_FULL));
+        row.add_suffix(&bar);
+        row.add_suffix(&gtk::Image::from_icon_name("go-next-symbolic"));
+        let app_id = app_id.to_string();
+        row.connect_activated(move |row| {
+            WidgetExt::activate_action(row, action, Some(&app_id.to_variant())).ok();
+        });
+        row
+    }
+
+    fn create_placeholder_row(text: &str) -> ActionRow {
+        let row = ActionRow::builder().title(text).build();
+        row.add_css_class("dim-label");
+        row
+    }
+}
@@ -0,0 +1,112 @@
+use adw::prelude::*;
+use adw::MessageDialog;
+use gtk::{glib, FileDialog};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::config::Config;
+use crate::i18n::{gettext, gettext_f};
+use crate::snapshot;
+use crate::steamos::{self, AtRisk, Location};
+use crate::ui::error_dialog;
+
+// Made inside the folder picked for snapshots, so they don't spill all over
+// an SD card
+const SNAPSHOT_FOLDER_NAME: &str = "proton_game_saves-snapshots";
+
+// On SteamOS, a toast at startup when settings, snapshots or backups are kept
+// where the next update wipes them, with the details and a way to move the
+// snapshots behind its button
+pub fn check(window: &adw::ApplicationWindow, config: &Rc<RefCell<Config>>, toast_overlay: &adw::ToastOverlay) {
+    if !steamos::is_steamos() {
+        return;
+    }
+    let at_risk = steamos::locations_at_risk(&config.borrow());
+    if at_risk.is_empty() {
+        return;
+    }
+    for risk in &at_risk {
+        println!("{:?} at {} is wiped by SteamOS updates", risk.location, risk.path.display());
+    }
+    let toast = adw::Toast::builder()
+        .title(gettext("Some data is kept where SteamOS updates delete it"))
+        .button_label(gettext("Details"))
+        .timeout(0)
+        .build();
+    toast.connect_button_clicked(glib::clone!(@weak window, @strong config => move |_| {
+        show_details(&window, &config, &at_risk);
+    }));
+    toast_overlay.add_toast(toast);
+}
+
+fn show_details(window: &adw::ApplicationWindow, config: &Rc<RefCell<Config>>, at_risk: &[AtRisk]) {
+    let places: Vec<String> = at_risk
+        .iter()
+        .map(|risk| format!("{}: {}", describe(&risk.location), risk.path.display()))
+        .collect();
+    let mut body = gettext_f(
+        "SteamOS updates replace everything outside the home folder and other drives, so these would be lost with the next one:\n\n{}",
+        &[&places.join("\n")],
+    );
+    if at_risk.iter().any(|risk| matches!(risk.location, Location::SyncFolder | Location::BackupTarget(_))) {
+        body.push_str("\n\n");
+        body.push_str(&gettext("Pick another sync folder or backup destination in Settings."));
+    }
+    if at_risk.iter().any(|risk| risk.location == Location::Config) {
+        body.push_str("\n\n");
+        body.push_str(&gettext("Settings are kept in the folder XDG_CONFIG_HOME points at, start the app with it set to a folder in your home folder."));
+    }
+    let dialog = MessageDialog::builder()
+        .heading(gettext("Data Won't Survive SteamOS Updates"))
+        .body(&body)
+        .transient_for(window)
+        .modal(true)
+        .build();
+    dialog.add_response("close", &gettext("Close"));
+    if at_risk.iter().any(|risk| risk.location == Location::Snapshots) {
+        dialog.add_response("move", &gettext("Move Snapshots…"));
+        dialog.set_response_appearance("move", adw::ResponseAppearance::Suggested);
+        dialog.set_default_response(Some("move"));
+    }
+    dialog.connect_response(None, glib::clone!(@weak window, @strong config => move |_, response| {
+        if response != "move" {
+            return;
+        }
+        let config = config.clone();
+        glib::MainContext::default().spawn_local(async move {
+            choose_snapshot_folder(window.upcast_ref(), &config).await;
+        });
+    }));
+    dialog.present();
+}
+
+fn describe(location: &Location) -> String {
+    match location {
+        Location::Config => gettext("Settings"),
+        Location::Snapshots => gettext("Safety snapshots"),
+        Location::SyncFolder => gettext("Sync folder"),
+        Location::BackupTarget(name) => gettext_f("Backup destination \"{}\"", &[name]),
+    }
+}
+
+// Ask for a folder, e.g. on the SD card, and move the safety snapshots into
+// it. True once they're moved. Also used by the Settings row.
+pub async fn choose_snapshot_folder(parent: &gtk::Window, config: &Rc<RefCell<Config>>) -> bool {
+    let file_dialog = FileDialog::new();
+    file_dialog.set_title(&gettext("Select a Folder for Snapshots"));
+    let Ok(folder) = file_dialog.select_folder_future(Some(parent)).await else {
+        return false;
+    };
+    let Some(path) = folder.path() else {
+        return false;
+    };
+    let result = snapshot::move_snapshots(&mut config.borrow_mut(), &path.join(SNAPSHOT_FOLDER_NAME));
+    match result {
+        Ok(_) => true,
+        Err(e) => {
+            eprintln!("Error moving snapshots: {:#}", e);
+            error_dialog::show(Some(parent), &gettext("Error Moving Snapshots"), &e);
+            false
+        }
+    }
+}
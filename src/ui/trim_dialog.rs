@@ -0,0 +1,141 @@
+use adw::prelude::*;
+use adw::{ActionRow, MessageDialog};
+use gtk::{gio, glib, Align, Button, CheckButton};
+use humansize::{format_size, DECIMAL};
+use std::path::PathBuf;
+
+use crate::config::Config;
+use crate::i18n::{gettext, gettext_f};
+use crate::trim::{self, TrimKind, TrimSuggestion};
+use crate::ui::error_dialog;
+
+// Row below a game's save locations that looks for crash dumps, logs, caches
+// and old autosaves in them and offers to delete what the user picks
+pub fn build_row(app_id: &str, name: &str, locations: Vec<PathBuf>) -> ActionRow {
+    let row = ActionRow::builder()
+        .title(gettext("🧹 Trim Save Folders"))
+        .subtitle(gettext_f("Crash dumps, logs, caches and autosaves beyond the newest {}", &[&trim::KEEP_AUTOSAVES.to_string()]))
+        .build();
+    row.add_css_class("emoji");
+
+    let find_button = Button::with_label(&gettext("Find Junk…"));
+    find_button.set_valign(Align::Center);
+    let app_id = app_id.to_string();
+    let name = name.to_string();
+    find_button.connect_clicked(move |button| {
+        let parent = button.root().and_downcast::<gtk::Window>();
+        glib::MainContext::default().spawn_local(show(parent, button.clone(), app_id.clone(), name.clone(), locations.clone()));
+    });
+    row.add_suffix(&find_button);
+    row.set_activatable_widget(Some(&find_button));
+    row
+}
+
+fn kind_label(kind: TrimKind) -> String {
+    match kind {
+        TrimKind::CrashDump => gettext("Crash dump"),
+        TrimKind::Log => gettext("Log"),
+        TrimKind::Cache => gettext("Cache"),
+        TrimKind::OldAutosave => gettext("Old autosave, kept in a safety snapshot"),
+    }
+}
+
+// Look for junk in the background, then preview it with a checkbox per item
+async fn show(parent: Option<gtk::Window>, button: Button, app_id: String, name: String, locations: Vec<PathBuf>) {
+    button.set_sensitive(false);
+    let scanned = locations.clone();
+    let suggestions = gio::spawn_blocking(move || trim::suggest(&scanned, trim::KEEP_AUTOSAVES)).await.unwrap_or_default();
+    button.set_sensitive(true);
+
+    let dialog = MessageDialog::builder().modal(true).build();
+    dialog.set_transient_for(parent.as_ref());
+    if suggestions.is_empty() {
+        dialog.set_heading(Some(&gettext("Nothing to Trim")));
+        dialog.set_body(&gettext_f("No crash dumps, logs, caches or old autosaves were found in the save folders of {}.", &[&name]));
+        dialog.add_response("close", &gettext("Close"));
+        dialog.present();
+        return;
+    }
+
+    let total: u64 = suggestions.iter().map(|suggestion| suggestion.size).sum();
+    dialog.set_heading(Some(&gettext_f("Trim Save Folders of {}?", &[&name])));
+    dialog.set_body(&gettext_f(
+        "These look like they aren't saves ({} in total). Only the checked items are deleted, old autosaves are put in a safety snapshot first. Close the game before trimming.",
+        &[&format_size(total, DECIMAL)],
+    ));
+
+    let list = gtk::ListBox::new();
+    list.set_selection_mode(gtk::SelectionMode::None);
+    list.add_css_class("boxed-list");
+    let mut checks = Vec::new();
+    for suggestion in &suggestions {
+        // Shown relative to its save location, the full path is in the tooltip
+        let relative = locations
+            .iter()
+            .find_map(|location| suggestion.path.strip_prefix(location).ok())
+            .filter(|relative| !relative.as_os_str().is_empty())
+            .unwrap_or(&suggestion.path);
+        let row = ActionRow::builder()
+            .title(glib::markup_escape_text(&relative.display().to_string()).as_str())
+            .subtitle(format!("{} · {}", kind_label(suggestion.kind), format_size(suggestion.size, DECIMAL)))
+            .tooltip_text(suggestion.path.display().to_string())
+            .build();
+        let check = CheckButton::new();
+        check.set_active(true);
+        check.set_valign(Align::Center);
+        row.add_prefix(&check);
+        row.set_activatable_widget(Some(&check));
+        list.append(&row);
+        checks.push(check);
+    }
+    let scroller = gtk::ScrolledWindow::builder()
+        .hscrollbar_policy(gtk::PolicyType::Never)
+        .propagate_natural_height(true)
+        .max_content_height(320)
+        .child(&list)
+        .build();
+    dialog.set_extra_child(Some(&scroller));
+
+    dialog.add_response("cancel", &gettext("Cancel"));
+    dialog.add_response("trim", &gettext("Trim"));
+    dialog.set_response_appearance("trim", adw::ResponseAppearance::Destructive);
+    dialog.set_default_response(Some("cancel"));
+    dialog.set_close_response("cancel");
+    if dialog.choose_future().await != "trim" {
+        return;
+    }
+
+    let picked: Vec<TrimSuggestion> = suggestions
+        .into_iter()
+        .zip(&checks)
+        .filter(|(_, check)| check.is_active())
+        .map(|(suggestion, _)| suggestion)
+        .collect();
+    if picked.is_empty() {
+        return;
+    }
+    let trimmed_id = app_id.clone();
+    // Config isn't Send, the worker loads its own
+    let result = gio::spawn_blocking(move || trim::trim(&Config::new(), &trimmed_id, &picked)).await;
+    match result {
+        Ok(Ok(freed)) => {
+            let done = MessageDialog::builder()
+                .heading(gettext("Save Folders Trimmed"))
+                .body(gettext_f("Freed {} in the save folders of {}.", &[&format_size(freed, DECIMAL), &name]))
+                .modal(true)
+                .build();
+            done.set_transient_for(parent.as_ref());
+            done.add_response("close", &gettext("Close"));
+            done.present();
+        }
+        Ok(Err(e)) => {
+            eprintln!("Error trimming save folders of {}: {:#}", app_id, e);
+            error_dialog::show(parent.as_ref(), &gettext("Error Trimming Save Folders"), &e);
+        }
+        Err(_) => eprintln!("Trimming the save folders panicked"),
+    }
+    // Sizes changed, and the activity log has a new entry
+    if let Some(parent) = parent {
+        WidgetExt::activate_action(&parent, "app.refresh", None).ok();
+    }
+}
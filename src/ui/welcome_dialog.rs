@@ -1,225 +1,514 @@
 use adw::prelude::*;
-use adw::{ActionRow, PreferencesGroup, PreferencesPage, PreferencesWindow, MessageDialog};
-use gtk::{Button, glib, gdk, Align, FileDialog, Window, gio, Box, Orientation, Label, Image};
-use std::cell::RefCell;
+use adw::{ActionRow, Carousel, CarouselIndicatorDots, HeaderBar, PreferencesGroup, StatusPage, ToolbarView};
+use gtk::{Button, glib, gdk, Align, FileDialog, Window, gio, Box, Orientation, Label, Image, ProgressBar};
+use std::cell::{Cell, RefCell};
+use std::path::PathBuf;
 use std::rc::Rc;
+use std::sync::mpsc;
+use std::time::Duration;
 
-use crate::config::Config;
+use crate::compatdata;
+use crate::config::{Config, ConfigKey};
+use crate::i18n::{gettext, gettext_f};
+use crate::manifest::{DownloadProgress, ManifestDownload};
+use crate::ui::error_dialog;
+use humansize::{format_size, DECIMAL};
 
+// Position of the scan page in the carousel, it starts scanning when reached
+const PAGE_SCAN: u32 = 3;
+
+type OnComplete = Rc<RefCell<Option<std::boxed::Box<dyn FnOnce() + 'static>>>>;
+
+// First launch onboarding: one carousel page per step, so the main window
+// opens with a Steam directory, a manifest and prefixes already in place
+// instead of empty pages.
 pub struct WelcomeDialog {
-    dialog: PreferencesWindow,
-    config: Rc<RefCell<Config>>,
-    on_complete: Rc<RefCell<Option<std::boxed::Box<dyn FnOnce() + 'static>>>>,
+    dialog: adw::Window,
 }
 
 impl WelcomeDialog {
-    pub fn new<F>(parent: Option<&adw::Application>, config: Rc<RefCell<Config>>, on_complete: F) -> Self 
-    where 
+    pub fn new<F>(parent: Option<&adw::Application>, config: Rc<RefCell<Config>>, on_complete: F) -> Self
+    where
         F: FnOnce() + 'static,
     {
-        let dialog = PreferencesWindow::builder()
-            .title("Welcome to Proton Game Saves Manager")
+        let dialog = adw::Window::builder()
+            .title(gettext("Welcome to Proton Game Saves Manager"))
             .modal(true)
             .default_width(600)
-            .default_height(500)
+            .default_height(560)
             .build();
 
         if let Some(app) = parent {
             dialog.set_application(Some(app));
         }
-            
-        let page = PreferencesPage::new();
-        dialog.add(&page);
-
-        // Welcome header group
-        let welcome_group = PreferencesGroup::builder()
-            .title("Welcome!")
-            .description("Let's set up your Steam directory to get started")
-            .build();
-        page.add(&welcome_group);
 
-        // Add a welcome message
-        let welcome_box = Box::new(Orientation::Vertical, 12);
-        let welcome_label = Label::builder()
-            .label("This application helps you manage your Steam Proton game save files.\n\nTo get started, please select your Steam directory.")
-            .wrap(true)
-            .justify(gtk::Justification::Center)
+        let back_button = Button::with_label(&gettext("Back"));
+        let next_button = Button::with_label(&gettext("Next"));
+        next_button.add_css_class("suggested-action");
+        let header = HeaderBar::new();
+        header.pack_start(&back_button);
+        header.pack_end(&next_button);
+
+        let carousel = Carousel::builder()
+            .vexpand(true)
+            .hexpand(true)
+            .allow_scroll_wheel(false)
             .build();
-        welcome_label.add_css_class("dim-label");
-        welcome_box.append(&welcome_label);
-        
-        welcome_group.add(&welcome_box);
+        let dots = CarouselIndicatorDots::builder().carousel(&carousel).margin_bottom(12).build();
+        let content = Box::new(Orientation::Vertical, 0);
+        content.append(&carousel);
+        content.append(&dots);
+
+        let toolbar = ToolbarView::new();
+        toolbar.add_top_bar(&header);
+        toolbar.set_content(Some(&content));
+        dialog.set_content(Some(&toolbar));
+
+        // Set by the scan page once it has run for the current Steam directory
+        let scanned = Rc::new(Cell::new(false));
+
+        carousel.append(&Self::welcome_page());
+        carousel.append(&Self::steam_page(&dialog, &config, &scanned));
+        carousel.append(&Self::manifest_page(&dialog, &config));
+        let (scan_page, scan_label) = Self::scan_page(&config, &scanned);
+        carousel.append(&scan_page);
+        carousel.append(&Self::tour_page());
+
+        let on_complete_callback: OnComplete = Rc::new(RefCell::new(Some(std::boxed::Box::new(on_complete))));
+
+        // Keep the header buttons in step with the page that's shown
+        let update_buttons = glib::clone!(@weak carousel, @weak back_button, @weak next_button => move || {
+            let position = carousel.position().round() as u32;
+            back_button.set_sensitive(position > 0);
+            next_button.set_label(&if position + 1 >= carousel.n_pages() {
+                gettext("Get Started")
+            } else {
+                gettext("Next")
+            });
+        });
+        update_buttons();
+        let config_scan = config.clone();
+        let scanned_clone = scanned.clone();
+        carousel.connect_page_changed(move |_, index| {
+            update_buttons();
+            if index == PAGE_SCAN && !scanned_clone.get() {
+                Self::run_scan(&config_scan, &scanned_clone, &scan_label);
+            }
+        });
+
+        back_button.connect_clicked(glib::clone!(@weak carousel => move |_| {
+            let position = carousel.position().round() as u32;
+            if position > 0 {
+                carousel.scroll_to(&carousel.nth_page(position - 1), true);
+            }
+        }));
+
+        let dialog_complete = dialog.clone();
+        next_button.connect_clicked(glib::clone!(@weak carousel => move |_| {
+            let position = carousel.position().round() as u32;
+            if position + 1 < carousel.n_pages() {
+                carousel.scroll_to(&carousel.nth_page(position + 1), true);
+                return;
+            }
+
+            // Mark first run as complete
+            if let Err(e) = config.borrow_mut().mark_first_run_complete() {
+                eprintln!("Failed to save configuration: {}", e);
+                error_dialog::show(Some(dialog_complete.upcast_ref()), &gettext("Configuration Error"),
+                    &e.context(gettext("Failed to save configuration")));
+                return;
+            }
+
+            // Close the dialog
+            dialog_complete.close();
+
+            // Execute the callback
+            if let Some(callback) = on_complete_callback.borrow_mut().take() {
+                callback();
+            }
+        }));
+
+        Self { dialog }
+    }
+
+    pub fn present(&self) {
+        self.dialog.present();
+    }
+
+    fn welcome_page() -> StatusPage {
+        StatusPage::builder()
+            .icon_name("io.github.rahatzamancse.ProtonGameSaves")
+            .title(gettext("Welcome!"))
+            .description(gettext("This application helps you manage your Steam Proton game save files.\n\nA few quick steps will set it up: pick your Steam directory, download the game database and look for your games."))
+            .vexpand(true)
+            .build()
+    }
+
+    fn steam_page(dialog: &adw::Window, config: &Rc<RefCell<Config>>, scanned: &Rc<Cell<bool>>) -> gtk::ScrolledWindow {
+        let page = Self::page_box();
 
         // Steam directory selection group
         let steam_group = PreferencesGroup::builder()
-            .title("Steam Directory")
-            .description("Select your Steam installation directory")
+            .title(gettext("Steam Directory"))
+            .description(gettext("Select your Steam installation directory"))
             .build();
-        page.add(&steam_group);
-            
+        page.append(&steam_group);
+
         let steam_path_text = {
             let config_borrow = config.borrow();
             config_borrow.steam_path().to_string_lossy().to_string()
         };
-        
+
         let path_row = ActionRow::builder()
-            .title("Steam Directory")
+            .title(gettext("Steam Directory"))
             .subtitle(&steam_path_text)
             .build();
-        let browse_button = Button::with_label("Browse");
+        let browse_button = Button::with_label(&gettext("Browse"));
         browse_button.set_valign(Align::Center);
         path_row.add_suffix(&browse_button);
-        
+
         let dialog_clone = dialog.clone();
         let config_clone = config.clone();
         let path_row_clone = path_row.clone();
+        let scanned_clone = scanned.clone();
         browse_button.connect_clicked(move |_| {
             let config_clone_inner = config_clone.clone();
             let path_row_clone_inner = path_row_clone.clone();
+            let scanned_inner = scanned_clone.clone();
             let parent_window = dialog_clone.clone().upcast::<Window>();
             glib::MainContext::default().spawn_local(async move {
-                Self::show_steam_folder_chooser_async(parent_window, config_clone_inner, path_row_clone_inner).await;
+                if Self::show_steam_folder_chooser_async(parent_window, config_clone_inner, path_row_clone_inner).await {
+                    // A different directory needs a fresh scan
+                    scanned_inner.set(false);
+                }
             });
         });
         steam_group.add(&path_row);
 
         // Flatpak permissions group - only show if running in Flatpak
         if Self::is_running_in_flatpak() {
-            let flatpak_group = PreferencesGroup::builder()
-                .title("Flatpak Permissions")
-                .description("Since you're using the Flatpak version, you need to grant filesystem access")
-                .build();
-            page.add(&flatpak_group);
-
-            // Warning message
-            let warning_box = Box::new(Orientation::Vertical, 8);
-            let warning_icon = Image::from_icon_name("dialog-warning-symbolic");
-            warning_icon.set_icon_size(gtk::IconSize::Large);
-            warning_icon.add_css_class("warning");
-            warning_box.append(&warning_icon);
-
-            let warning_label = Label::builder()
-                .label("⚠️ Important: Flatpak Permission Required")
-                .wrap(true)
-                .justify(gtk::Justification::Center)
-                .build();
-            warning_label.add_css_class("heading");
-            warning_box.append(&warning_label);
-
-            let info_label = Label::builder()
-                .label("Flatpak applications run in a sandbox and need explicit permission to access your Steam directory.\n\nPlease run the following commands in a terminal to grant the necessary permissions:")
-                .wrap(true)
-                .justify(gtk::Justification::Left)
-                .build();
-            warning_box.append(&info_label);
-
-            flatpak_group.add(&warning_box);
-
-            // Command instructions
-            let cmd_row1 = ActionRow::builder()
-                .title("Grant filesystem access")
-                .subtitle("flatpak override --user --filesystem=home io.github.rahatzamancse.ProtonGameSaves")
-                .build();
-            let copy_btn1 = Button::with_label("Copy");
-            copy_btn1.set_valign(Align::Center);
-            let cmd1 = "flatpak override --user --filesystem=home io.github.rahatzamancse.ProtonGameSaves";
-            copy_btn1.connect_clicked(glib::clone!(@strong cmd1 => move |_| {
-                let clipboard = gdk::Display::default().unwrap().clipboard();
-                clipboard.set_text(&cmd1);
-            }));
-            cmd_row1.add_suffix(&copy_btn1);
-            flatpak_group.add(&cmd_row1);
-
-            let cmd_row2 = ActionRow::builder()
-                .title("Grant network access (for manifest downloads)")
-                .subtitle("flatpak override --user --share=network io.github.rahatzamancse.ProtonGameSaves")
-                .build();
-            let copy_btn2 = Button::with_label("Copy");
-            copy_btn2.set_valign(Align::Center);
-            let cmd2 = "flatpak override --user --share=network io.github.rahatzamancse.ProtonGameSaves";
-            copy_btn2.connect_clicked(glib::clone!(@strong cmd2 => move |_| {
-                let clipboard = gdk::Display::default().unwrap().clipboard();
-                clipboard.set_text(&cmd2);
-            }));
-            cmd_row2.add_suffix(&copy_btn2);
-            flatpak_group.add(&cmd_row2);
-
-            let restart_label = Label::builder()
-                .label("After running these commands, you may need to restart the application.")
-                .wrap(true)
-                .justify(gtk::Justification::Center)
-                .build();
-            restart_label.add_css_class("dim-label");
-            let restart_box = Box::new(Orientation::Vertical, 6);
-            restart_box.append(&restart_label);
-            flatpak_group.add(&restart_box);
+            page.append(&Self::flatpak_group());
         }
 
-        // Completion group
-        let complete_group = PreferencesGroup::builder()
-            .title("Ready to Go")
-            .description("Click 'Get Started' when you're ready")
+        Self::scrolled(&page)
+    }
+
+    fn flatpak_group() -> PreferencesGroup {
+        let flatpak_group = PreferencesGroup::builder()
+            .title(gettext("Flatpak Permissions"))
+            .description(gettext("Since you're using the Flatpak version, you need to grant filesystem access"))
             .build();
-        page.add(&complete_group);
 
-        let complete_row = ActionRow::builder()
-            .title("Complete Setup")
-            .subtitle("Save configuration and start using the application")
+        // Warning message
+        let warning_box = Box::new(Orientation::Vertical, 8);
+        let warning_icon = Image::from_icon_name("dialog-warning-symbolic");
+        warning_icon.set_icon_size(gtk::IconSize::Large);
+        warning_icon.add_css_class("warning");
+        warning_box.append(&warning_icon);
+
+        let warning_label = Label::builder()
+            .label(gettext("⚠️ Important: Flatpak Permission Required"))
+            .wrap(true)
+            .justify(gtk::Justification::Center)
             .build();
-        let complete_button = Button::builder()
-            .label("Get Started")
-            .css_classes(vec!["suggested-action".to_string()])
-            .valign(Align::Center)
+        warning_label.add_css_class("heading");
+        warning_box.append(&warning_label);
+
+        let info_label = Label::builder()
+            .label(gettext("Flatpak applications run in a sandbox and need explicit permission to access your Steam directory.\n\nPlease run the following commands in a terminal to grant the necessary permissions:"))
+            .wrap(true)
+            .justify(gtk::Justification::Left)
             .build();
-        
-        complete_row.add_suffix(&complete_button);
-        complete_group.add(&complete_row);
+        warning_box.append(&info_label);
 
-        // Handle completion
-        let config_complete = config.clone();
-        let dialog_complete = dialog.clone();
-        let on_complete_callback = Rc::new(RefCell::new(Some(std::boxed::Box::new(on_complete) as std::boxed::Box<dyn FnOnce() + 'static>)));
-        let on_complete_for_click = on_complete_callback.clone();
-        complete_button.connect_clicked(move |_| {
-            // Mark first run as complete
-            if let Err(e) = config_complete.borrow_mut().mark_first_run_complete() {
-                eprintln!("Failed to save configuration: {}", e);
-                Self::show_error_dialog(&dialog_complete.clone().upcast::<Window>(), 
-                    "Configuration Error", 
-                    &format!("Failed to save configuration: {}", e));
-                return;
+        flatpak_group.add(&warning_box);
+
+        // Command instructions
+        let cmd_row1 = ActionRow::builder()
+            .title(gettext("Grant filesystem access"))
+            .subtitle("flatpak override --user --filesystem=home io.github.rahatzamancse.ProtonGameSaves")
+            .build();
+        let copy_btn1 = Button::with_label(&gettext("Copy"));
+        copy_btn1.set_valign(Align::Center);
+        let cmd1 = "flatpak override --user --filesystem=home io.github.rahatzamancse.ProtonGameSaves";
+        copy_btn1.connect_clicked(glib::clone!(@strong cmd1 => move |_| {
+            let clipboard = gdk::Display::default().unwrap().clipboard();
+            clipboard.set_text(&cmd1);
+        }));
+        cmd_row1.add_suffix(&copy_btn1);
+        flatpak_group.add(&cmd_row1);
+
+        let cmd_row2 = ActionRow::builder()
+            .title(gettext("Grant network access (for manifest downloads)"))
+            .subtitle("flatpak override --user --share=network io.github.rahatzamancse.ProtonGameSaves")
+            .build();
+        let copy_btn2 = Button::with_label(&gettext("Copy"));
+        copy_btn2.set_valign(Align::Center);
+        let cmd2 = "flatpak override --user --share=network io.github.rahatzamancse.ProtonGameSaves";
+        copy_btn2.connect_clicked(glib::clone!(@strong cmd2 => move |_| {
+            let clipboard = gdk::Display::default().unwrap().clipboard();
+            clipboard.set_text(&cmd2);
+        }));
+        cmd_row2.add_suffix(&copy_btn2);
+        flatpak_group.add(&cmd_row2);
+
+        let restart_label = Label::builder()
+            .label(gettext("After running these commands, you may need to restart the application."))
+            .wrap(true)
+            .justify(gtk::Justification::Center)
+            .build();
+        restart_label.add_css_class("dim-label");
+        let restart_box = Box::new(Orientation::Vertical, 6);
+        restart_box.append(&restart_label);
+        flatpak_group.add(&restart_box);
+        flatpak_group
+    }
+
+    // The Games page matches prefixes against the manifest, without it only
+    // the Compatdata page has anything to show
+    fn manifest_page(dialog: &adw::Window, config: &Rc<RefCell<Config>>) -> StatusPage {
+        let already_cached = config.borrow().manifest_cache_path().is_file();
+        let progress_bar = ProgressBar::builder().show_text(true).visible(false).build();
+        let status_label = Label::builder()
+            .label(if already_cached {
+                gettext("A manifest is already downloaded. Download it again to get the latest version.")
+            } else {
+                gettext("You can also download it later in Settings.")
+            })
+            .wrap(true)
+            .justify(gtk::Justification::Center)
+            .build();
+        status_label.add_css_class("dim-label");
+        let download_button = Button::builder()
+            .label(gettext("Download"))
+            .halign(Align::Center)
+            .css_classes(vec!["pill".to_string()])
+            .build();
+
+        let content = Box::new(Orientation::Vertical, 12);
+        content.append(&progress_bar);
+        content.append(&status_label);
+        content.append(&download_button);
+
+        let dialog_clone = dialog.clone();
+        let config_clone = config.clone();
+        download_button.connect_clicked(move |button| {
+            button.set_sensitive(false);
+            progress_bar.set_visible(true);
+            progress_bar.set_fraction(0.0);
+            progress_bar.set_text(Some(&gettext("Connecting…")));
+            status_label.set_label("");
+
+            // Progress arrives from the worker thread, pick it up from the main loop
+            let (sender, receiver) = mpsc::channel::<DownloadProgress>();
+            glib::timeout_add_local(Duration::from_millis(100), glib::clone!(@weak progress_bar => @default-return glib::ControlFlow::Break, move || {
+                loop {
+                    match receiver.try_recv() {
+                        Ok(progress) => Self::show_download_progress(&progress_bar, &progress),
+                        Err(mpsc::TryRecvError::Empty) => return glib::ControlFlow::Continue,
+                        Err(mpsc::TryRecvError::Disconnected) => return glib::ControlFlow::Break,
+                    }
+                }
+            }));
+
+            let download = ManifestDownload::new(&config_clone.borrow());
+            let config = config_clone.clone();
+            let dialog = dialog_clone.clone();
+            let button = button.clone();
+            let progress_bar = progress_bar.clone();
+            let status_label = status_label.clone();
+            glib::MainContext::default().spawn_local(async move {
+                let result = gio::spawn_blocking(move || {
+                    download.run(|progress| {
+                        sender.send(progress).ok();
+                    })
+                })
+                .await
+                .unwrap_or_else(|_| Err(anyhow::anyhow!("Manifest download thread panicked")));
+                button.set_sensitive(true);
+                match result {
+                    Ok(()) => {
+                        println!("Manifest downloaded during onboarding");
+                        config.borrow().notify(ConfigKey::ManifestCache);
+                        progress_bar.set_fraction(1.0);
+                        progress_bar.set_text(Some(&gettext("Done")));
+                        status_label.set_label(&gettext("The game database is ready."));
+                        button.set_label(&gettext("Download Again"));
+                    }
+                    Err(e) => {
+                        eprintln!("Failed to download manifest: {:#}", e);
+                        progress_bar.set_visible(false);
+                        status_label.set_label(&gettext("The download failed. You can try again, or skip this step and download it later in Settings."));
+                        error_dialog::show(Some(dialog.upcast_ref()), &gettext("Download Failed"),
+                            &e.context(gettext("Failed to download the game manifest")));
+                    }
+                }
+            });
+        });
+
+        StatusPage::builder()
+            .icon_name("system-search-symbolic")
+            .title(gettext("Game Database"))
+            .description(gettext("The Ludusavi manifest tells the app where thousands of games keep their saves. It's a download of a few megabytes."))
+            .child(&content)
+            .vexpand(true)
+            .build()
+    }
+
+    fn show_download_progress(progress_bar: &ProgressBar, progress: &DownloadProgress) {
+        match progress {
+            DownloadProgress::Started { index, count, .. } => {
+                progress_bar.set_fraction(0.0);
+                progress_bar.set_text(Some(&if *count > 1 {
+                    gettext_f("Downloading source {} of {}…", &[&(index + 1).to_string(), &count.to_string()])
+                } else {
+                    gettext("Downloading…")
+                }));
             }
-            
-            // Close the dialog
-            dialog_complete.close();
-            
-            // Execute the callback
-            if let Some(callback) = on_complete_for_click.borrow_mut().take() {
-                callback();
+            DownloadProgress::Received { bytes, total: Some(total) } if *total > 0 => {
+                progress_bar.set_fraction((*bytes as f64 / *total as f64).min(1.0));
+                progress_bar.set_text(Some(&gettext_f("{} of {}", &[&format_size(*bytes, DECIMAL), &format_size(*total, DECIMAL)])));
             }
+            DownloadProgress::Received { bytes, .. } => {
+                progress_bar.pulse();
+                progress_bar.set_text(Some(&format_size(*bytes, DECIMAL)));
+            }
+        }
+    }
+
+    // Runs by itself when the page is reached, the label says what was found
+    fn scan_page(config: &Rc<RefCell<Config>>, scanned: &Rc<Cell<bool>>) -> (StatusPage, Label) {
+        let result_label = Label::builder()
+            .wrap(true)
+            .justify(gtk::Justification::Center)
+            .build();
+        let rescan_button = Button::builder()
+            .label(gettext("Scan Again"))
+            .halign(Align::Center)
+            .css_classes(vec!["pill".to_string()])
+            .build();
+        let content = Box::new(Orientation::Vertical, 12);
+        content.append(&result_label);
+        content.append(&rescan_button);
+
+        let config_clone = config.clone();
+        let scanned_clone = scanned.clone();
+        rescan_button.connect_clicked(glib::clone!(@weak result_label => move |_| {
+            Self::run_scan(&config_clone, &scanned_clone, &result_label);
+        }));
+
+        let page = StatusPage::builder()
+            .icon_name("drive-harddisk-symbolic")
+            .title(gettext("Looking for Games"))
+            .child(&content)
+            .vexpand(true)
+            .build();
+        (page, result_label)
+    }
+
+    // Counts the prefixes in every Steam library, the full scan with save
+    // detection happens when the main window opens
+    fn run_scan(config: &Rc<RefCell<Config>>, scanned: &Rc<Cell<bool>>, label: &Label) {
+        scanned.set(true);
+        label.set_label(&gettext("Scanning your Steam libraries…"));
+        let paths: Vec<PathBuf> = config.borrow().compatdata_paths();
+        let label = label.clone();
+        glib::MainContext::default().spawn_local(async move {
+            let (libraries, prefixes) = gio::spawn_blocking(move || {
+                let mut libraries = 0;
+                let mut prefixes = 0;
+                for path in &paths {
+                    match compatdata::list_game_ids(path) {
+                        Ok(game_ids) => {
+                            libraries += 1;
+                            prefixes += game_ids.len();
+                        }
+                        Err(e) => println!("Skipping {}: {}", path.display(), e),
+                    }
+                }
+                (libraries, prefixes)
+            })
+            .await
+            .unwrap_or((0, 0));
+            println!("Onboarding scan found {} prefixes in {} libraries", prefixes, libraries);
+            label.set_label(&if prefixes == 0 {
+                gettext("No Proton prefixes were found. Check the Steam directory on the previous pages, or launch a Windows game with Proton once so Steam creates its prefix.")
+            } else {
+                gettext_f("Found {} Proton prefixes in {} Steam libraries.", &[&prefixes.to_string(), &libraries.to_string()])
+            });
         });
+    }
 
-        Self {
-            dialog,
-            config,
-            on_complete: on_complete_callback,
+    // What each page of the main window is for
+    fn tour_page() -> gtk::ScrolledWindow {
+        let page = Self::page_box();
+        let group = PreferencesGroup::builder()
+            .title(gettext("Finding Your Way Around"))
+            .description(gettext("The sidebar has four pages"))
+            .build();
+        let pages = [
+            ("input-gaming-symbolic", gettext("Games"), gettext("Games matched with the manifest and their save files. Back up, restore and sync saves here.")),
+            ("drive-harddisk-symbolic", gettext("Compatdata"), gettext("Every Proton prefix Steam created, matched with a game or not. Open, clean up or delete prefixes here.")),
+            ("drive-multidisk-symbolic", gettext("Disk Space"), gettext("How much space prefixes, saves and snapshots take.")),
+            ("system-search-symbolic", gettext("Game Database"), gettext("Every game in the manifest and where it keeps its saves, installed or not.")),
+        ];
+        for (icon_name, title, description) in pages {
+            let row = ActionRow::builder().title(title).subtitle(description).build();
+            row.add_prefix(&Image::from_icon_name(icon_name));
+            group.add(&row);
         }
+        page.append(&group);
+
+        // The lists don't update by themselves while the app is open
+        let header_group = PreferencesGroup::builder()
+            .title(gettext("Keeping Lists Up to Date"))
+            .build();
+        let refresh_row = ActionRow::builder()
+            .title(gettext("Refresh Lists"))
+            .subtitle(gettext("The button at the top left scans the Steam libraries again. Use it after installing, playing or removing games."))
+            .build();
+        refresh_row.add_prefix(&Image::from_icon_name("view-refresh-symbolic"));
+        header_group.add(&refresh_row);
+        let recover_row = ActionRow::builder()
+            .title(gettext("Recover a Save"))
+            .subtitle(gettext("The button next to it brings back saves from the safety snapshots taken before they were overwritten or deleted."))
+            .build();
+        recover_row.add_prefix(&Image::from_icon_name("document-revert-symbolic"));
+        header_group.add(&recover_row);
+        page.append(&header_group);
+        Self::scrolled(&page)
     }
-    
-    pub fn present(&self) {
-        self.dialog.present();
+
+    fn page_box() -> Box {
+        let page = Box::new(Orientation::Vertical, 24);
+        page.set_margin_start(24);
+        page.set_margin_end(24);
+        page.set_margin_top(24);
+        page.set_margin_bottom(24);
+        page
+    }
+
+    fn scrolled(child: &Box) -> gtk::ScrolledWindow {
+        let clamp = adw::Clamp::builder().child(child).build();
+        gtk::ScrolledWindow::builder()
+            .hscrollbar_policy(gtk::PolicyType::Never)
+            .vexpand(true)
+            .hexpand(true)
+            .child(&clamp)
+            .build()
     }
 
     fn is_running_in_flatpak() -> bool {
         // Check for common Flatpak environment indicators
-        std::env::var("FLATPAK_ID").is_ok() || 
+        std::env::var("FLATPAK_ID").is_ok() ||
         std::env::var("FLATPAK_DEST").is_ok() ||
         std::path::Path::new("/.flatpak-info").exists()
     }
-    
-    async fn show_steam_folder_chooser_async(parent: Window, config: Rc<RefCell<Config>>, row: ActionRow) {
+
+    // Whether the Steam path was changed
+    async fn show_steam_folder_chooser_async(parent: Window, config: Rc<RefCell<Config>>, row: ActionRow) -> bool {
         let file_dialog = FileDialog::new();
-        file_dialog.set_title("Select Steam Directory");
+        file_dialog.set_title(&gettext("Select Steam Directory"));
 
         match file_dialog.select_folder_future(Some(&parent)).await {
             Ok(folder) => {
@@ -227,10 +516,11 @@ impl WelcomeDialog {
                     println!("Selected folder: {}", path.display());
                     if let Err(e) = config.borrow_mut().set_steam_path(path.clone()) {
                         eprintln!("Error setting steam path: {}", e);
-                        Self::show_error_dialog(&parent, "Error Setting Path", 
-                            &format!("Failed to set Steam path: {}", e));
+                        error_dialog::show(Some(&parent), &gettext("Error Setting Path"),
+                            &e.context(gettext("Failed to set Steam path")));
                     } else {
                         row.set_subtitle(&path.to_string_lossy());
+                        return true;
                     }
                 }
             },
@@ -239,21 +529,11 @@ impl WelcomeDialog {
                     println!("Folder selection cancelled.");
                 } else {
                     eprintln!("Error selecting folder: {}", e);
-                    Self::show_error_dialog(&parent, "Selection Error", 
-                        &format!("Failed to select folder: {}", e));
+                    error_dialog::show(Some(&parent), &gettext("Selection Error"),
+                        &anyhow::Error::from(e).context(gettext("Failed to select folder")));
                 }
             }
         }
-    }
-    
-    fn show_error_dialog(parent: &Window, title: &str, message: &str) {
-        let dialog = MessageDialog::builder()
-            .transient_for(parent)
-            .modal(true)
-            .heading(title)
-            .body(message)
-            .build();
-        dialog.add_response("ok", "OK");
-        dialog.present();
+        false
     }
 }
@@ -1,149 +1,779 @@
 use adw::prelude::*;
 use adw::{ApplicationWindow, HeaderBar, MessageDialog};
-use gtk::{gio, glib, Box, Button, Orientation, Stack, StackSwitcher};
-use std::cell::RefCell;
+use gtk::{gio, glib, Box, FileDialog, FileFilter, Label, ListBox, Orientation, Stack};
+use humansize::{format_size, DECIMAL};
+use std::cell::{Cell, RefCell};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
+use std::sync::mpsc;
+use std::time::{Duration, SystemTime};
 
-use crate::config::Config;
+use crate::activity::{self, ActivityEntry, ActivityKind};
+use crate::anonymize;
+use crate::backup_target::{self, BackupTarget, LocalTarget};
+use crate::bundle::{self, ExportGame};
+use crate::compatdata::{self, ScanResult};
+use crate::config::{Config, ConfigKey, ConfirmAction, ScanOptions, WindowState};
+use crate::i18n::{gettext, gettext_f};
+use crate::manifest::{self, ManifestInputs, ParseProgress};
+use crate::mounts;
+use crate::report::{self, ReportEntry};
+use crate::scan_changes::{GameSaves, ScanChanges, ScanRecord};
+use crate::size_history;
 use crate::ui::compatdata_page::CompatDataPage;
+use crate::ui::confirm;
+use crate::ui::error_dialog;
+use crate::ui::inhibit::Inhibitor;
 use crate::ui::games_page::GamesPage;
+use crate::ui::keynav;
+use crate::ui::lan_sync_dialog;
+use crate::ui::manifest_page::ManifestPage;
+use crate::ui::cleanup_dialog;
+use crate::ui::recover_dialog;
 use crate::ui::settings::SettingsDialog;
+use crate::ui::activity_page::ActivityPage;
+use crate::ui::space_page::SpacePage;
+use crate::ui::steamos_check;
+
+// Label, current value and setter for one check button in the scan options popover
+type ScanToggle = (String, bool, fn(&mut ScanOptions, bool));
+
+// Below this width the sidebar folds away and pages are navigated like on a phone
+const SIDEBAR_BREAKPOINT: &str = "max-width: 600sp";
+// Pages don't stretch wider than this, long rows get hard to read on big screens
+const PAGE_MAX_WIDTH: i32 = 1000;
+
+// The manifest cache as last loaded, to notice when another tool (e.g. a
+// ludusavi run or a script) replaces the file
+struct ManifestCacheState {
+    path: RefCell<PathBuf>, // Changes when switching to or from ludusavi's manifest
+    loaded_mtime: Cell<Option<SystemTime>>,
+    pending_toast: RefCell<Option<adw::Toast>>,
+    monitor: RefCell<Option<gio::FileMonitor>>,
+    // Bumped on every load, so a slow parse can't overwrite a newer one
+    load_generation: Cell<u32>,
+}
+
+impl ManifestCacheState {
+    fn current_mtime(&self) -> Option<SystemTime> {
+        fs::metadata(&*self.path.borrow()).and_then(|m| m.modified()).ok()
+    }
+
+    // The file on disk is what's loaded now, drop any reload prompt
+    fn mark_loaded(&self) {
+        self.loaded_mtime.set(self.current_mtime());
+        if let Some(toast) = self.pending_toast.take() {
+            toast.dismiss();
+        }
+    }
+}
 
 pub struct ProtonSavesWindow {
     window: ApplicationWindow,
+    _manifest_state: Rc<ManifestCacheState>,
     _config: Rc<RefCell<Config>>,
     _compat_page: Rc<CompatDataPage>,
     _games_page: Rc<RefCell<GamesPage>>,
+    _space_page: Rc<SpacePage>,
 }
 
 impl ProtonSavesWindow {
-    pub fn new(app: &adw::Application) -> Self {
-        // Create config
-        let config = Rc::new(RefCell::new(Config::new()));
-        
+    pub fn new(app: &adw::Application, config: Rc<RefCell<Config>>) -> Self {
+
         // Create the main window
         let window = ApplicationWindow::builder()
             .application(app)
-            .title("Proton Game Saves Manager")
-            .default_width(900)
-            .default_height(700)
+            .title(gettext("Proton Game Saves Manager"))
+            .default_width(config.borrow().window_state().width)
+            .default_height(config.borrow().window_state().height)
+            .maximized(config.borrow().window_state().maximized)
             .build();
             
         // Create header bar
         let header_bar = HeaderBar::new();
         
-        // Add Refresh button to HeaderBar start
-        let refresh_button = Button::from_icon_name("view-refresh-symbolic");
-        refresh_button.set_tooltip_text(Some("Refresh Lists"));
+        // Add Refresh button to HeaderBar start, its dropdown holds the scan options
+        let refresh_button = adw::SplitButton::builder()
+            .icon_name("view-refresh-symbolic")
+            .tooltip_text(gettext("Refresh Lists"))
+            .dropdown_tooltip(gettext("Scan Options"))
+            .build();
+        refresh_button.set_popover(Some(&Self::build_scan_options_popover(config.clone())));
         header_bar.pack_start(&refresh_button);
 
-        // Create StackSwitcher for page navigation
-        let stack_switcher = StackSwitcher::new();
-        header_bar.set_title_widget(Some(&stack_switcher));
-        
+        // Kept next to Refresh so it's easy to find when a save just went missing
+        let recover_button = gtk::Button::builder()
+            .icon_name("document-revert-symbolic")
+            .tooltip_text(gettext("Recover a Save"))
+            .action_name("app.recover")
+            .build();
+        header_bar.pack_start(&recover_button);
+
         // Create menu button
         let menu_button = gtk::MenuButton::new();
         menu_button.set_icon_name("open-menu-symbolic");
         
         // Create menu
         let menu = gio::Menu::new();
-        menu.append(Some("About"), Some("app.about"));
-        menu.append(Some("Settings"), Some("app.settings"));
-        menu.append(Some("Quit"), Some("app.quit"));
+        menu.append(Some(&gettext("Recover a Save…")), Some("app.recover"));
+        menu.append(Some(&gettext("Clean Up Prefixes…")), Some("app.cleanup"));
+        menu.append(Some(&gettext("Create Empty Prefix…")), Some("app.create-prefix"));
+        menu.append(Some(&gettext("Export All Saves…")), Some("app.export-bundle"));
+        menu.append(Some(&gettext("Import Save Bundle…")), Some("app.import-bundle"));
+        menu.append(Some(&gettext("Sync Over LAN…")), Some("app.lan-sync"));
+        menu.append(Some(&gettext("Export Report…")), Some("app.export-report"));
+        menu.append(Some(&gettext("About")), Some("app.about"));
+        menu.append(Some(&gettext("Settings")), Some("app.settings"));
+        menu.append(Some(&gettext("Quit")), Some("app.quit"));
         
         menu_button.set_menu_model(Some(&menu));
         header_bar.pack_end(&menu_button);
         
-        // Create main box
-        let main_box = Box::new(Orientation::Vertical, 0);
-        main_box.append(&header_bar);
+        // Content side of the window: header bar, banner and the current page
+        let content_view = adw::ToolbarView::new();
+        content_view.add_top_bar(&header_bar);
+
+        // Make it obvious the data shown isn't the user's real library
+        if config.borrow().is_demo() {
+            let banner = adw::Banner::new(&gettext("Demo mode: showing a generated sample library"));
+            banner.set_revealed(true);
+            content_view.add_top_bar(&banner);
+        }
+        // Progress of parsing the manifest, which happens in the background
+        let manifest_banner = adw::Banner::new("");
+        // Only has a button while it warns about skipped entries
+        manifest_banner.connect_button_clicked(|banner| banner.set_revealed(false));
+        content_view.add_top_bar(&manifest_banner);
+        // What changed since the previous scan, until dismissed or a scan finds nothing new
+        let changes_banner = adw::Banner::new("");
+        changes_banner.set_button_label(Some(&gettext("Dismiss")));
+        changes_banner.connect_button_clicked(|banner| banner.set_revealed(false));
+        content_view.add_top_bar(&changes_banner);
         
         // Create the Stack to hold pages
         let stack = Stack::new();
         stack.set_vexpand(true);
-        main_box.append(&stack);
-        
-        // Set the main box as the content of the window
-        window.set_content(Some(&main_box));
+
+        // Toast overlay around the pages for transient notifications (e.g. undo)
+        let toast_overlay = adw::ToastOverlay::new();
+        toast_overlay.set_child(Some(&stack));
+        content_view.set_content(Some(&toast_overlay));
+
+        let content_page = adw::NavigationPage::builder()
+            .title(gettext("Compatdata"))
+            .child(&content_view)
+            .build();
+
+        // Sidebar listing the pages, with a count badge on each
+        let sidebar_list = ListBox::new();
+        sidebar_list.add_css_class("navigation-sidebar");
+        let sidebar_scroll = gtk::ScrolledWindow::builder()
+            .hscrollbar_policy(gtk::PolicyType::Never)
+            .child(&sidebar_list)
+            .build();
+        let sidebar_view = adw::ToolbarView::new();
+        sidebar_view.add_top_bar(&HeaderBar::new());
+        sidebar_view.set_content(Some(&sidebar_scroll));
+        let sidebar_page = adw::NavigationPage::builder()
+            .title(gettext("Proton Game Saves"))
+            .child(&sidebar_view)
+            .build();
+
+        let split_view = adw::NavigationSplitView::builder()
+            .sidebar(&sidebar_page)
+            .content(&content_page)
+            .build();
+        window.set_content(Some(&split_view));
+        window.set_size_request(360, 294);
+
+        let breakpoint = adw::Breakpoint::new(
+            adw::BreakpointCondition::parse(SIDEBAR_BREAKPOINT).expect("Invalid breakpoint condition"),
+        );
+        breakpoint.add_setter(&split_view, "collapsed", &true.to_value());
+        // styles/app.css tightens margins and headings under .narrow
+        breakpoint.connect_apply(glib::clone!(@weak window => move |_| window.add_css_class("narrow")));
+        breakpoint.connect_unapply(glib::clone!(@weak window => move |_| window.remove_css_class("narrow")));
+        window.add_breakpoint(breakpoint);
+
+        // Bigger buttons and rows on touch screens like the Steam Deck's
+        let has_touch = gtk::gdk::Display::default()
+            .and_then(|display| display.default_seat())
+            .map(|seat| seat.capabilities().contains(gtk::gdk::SeatCapabilities::TOUCH))
+            .unwrap_or(false);
+        if has_touch {
+            window.add_css_class("touch");
+        }
 
         // Create the CompatDataPage
-        let compat_page = Rc::new(CompatDataPage::new(window.clone(), config.clone()));
-        stack.add_titled(compat_page.widget(), Some("compatdata"), "Compatdata");
+        let compat_page = Rc::new(CompatDataPage::new(window.clone(), config.clone(), toast_overlay.clone()));
+        stack.add_named(&Self::clamp_page(compat_page.widget()), Some("compatdata"));
 
         // Create the GamesPage (using RefCell for interior mutability needed for update_manifest)
         let games_page = Rc::new(RefCell::new(GamesPage::new(config.clone())));
-        stack.add_titled(games_page.borrow().widget(), Some("games"), "Games");
-        
-        // Connect StackSwitcher
-        stack_switcher.set_stack(Some(&stack));
+        stack.add_named(&Self::clamp_page(games_page.borrow().widget()), Some("games"));
+
+        let space_page = Rc::new(SpacePage::new(config.clone()));
+        stack.add_named(&Self::clamp_page(space_page.widget()), Some("space"));
+
+        let compat_badge = Self::add_sidebar_row(&sidebar_list, "drive-harddisk-symbolic", &gettext("Compatdata"));
+        let games_badge = Self::add_sidebar_row(&sidebar_list, "input-gaming-symbolic", &gettext("Games"));
+        let space_badge = Self::add_sidebar_row(&sidebar_list, "drive-multidisk-symbolic", &gettext("Disk Space"));
+        let manifest_badge = Self::add_sidebar_row(&sidebar_list, "system-search-symbolic", &gettext("Game Database"));
+        // Created after the sidebar rows, it keeps its badge up to date itself
+        let manifest_page = ManifestPage::new(config.clone(), manifest_badge);
+        stack.add_named(&Self::clamp_page(manifest_page.widget()), Some("manifest"));
+        let activity_badge = Self::add_sidebar_row(&sidebar_list, "document-open-recent-symbolic", &gettext("Activity"));
+        let activity_page = ActivityPage::new(config.clone(), activity_badge);
+        stack.add_named(&Self::clamp_page(activity_page.widget()), Some("activity"));
+        let pages = [
+            ("compatdata", gettext("Compatdata")),
+            ("games", gettext("Games")),
+            ("space", gettext("Disk Space")),
+            ("manifest", gettext("Game Database")),
+            ("activity", gettext("Activity")),
+        ];
+        keynav::add_page_shortcuts(&window, &sidebar_list, pages.len() as i32);
+        // Open the page that was shown last time
+        let last_page = pages.iter().position(|(name, _)| *name == config.borrow().window_state().page).unwrap_or(0);
+        sidebar_list.connect_row_selected(glib::clone!(@weak stack, @weak content_page, @weak split_view, @strong activity_page => move |_, row| {
+            let Some((name, title)) = row.and_then(|row| pages.get(row.index() as usize)) else {
+                return;
+            };
+            // Operations run from dialogs on any page, pick up what they logged
+            if *name == "activity" {
+                activity_page.reload();
+            }
+            stack.set_visible_child_name(name);
+            content_page.set_title(title);
+            split_view.set_show_content(true);
+        }));
+        sidebar_list.select_row(sidebar_list.row_at_index(last_page as i32).as_ref());
+
+        // Size and page are saved on close, resizes would save the config too often
+        window.connect_close_request(glib::clone!(@strong config, @weak stack => @default-return glib::Propagation::Proceed, move |window| {
+            let (width, height) = window.default_size();
+            let state = WindowState {
+                width,
+                height,
+                maximized: window.is_maximized(),
+                page: stack.visible_child_name().map(|name| name.to_string()).unwrap_or_default(),
+            };
+            if let Err(e) = config.borrow_mut().set_window_state(state) {
+                eprintln!("Error saving window state: {}", e);
+            }
+            glib::Propagation::Proceed
+        }));
 
         // Initial manifest load for GamesPage
         // Moved initial populate call to after connect_clicked setup
         // games_page.borrow_mut().update_manifest(); 
 
+        // Last scan, kept so a single library can be rescanned without scanning the others
+        let scan_result = Rc::new(RefCell::new(ScanResult::default()));
+        // Each scan is compared with the previous one once, when it's first shown
+        let scan_compared = Rc::new(Cell::new(false));
+
+        // Games already notified about being over their size limit, so every
+        // refresh doesn't notify about them again
+        let quota_notified: Rc<RefCell<HashSet<String>>> = Rc::new(RefCell::new(HashSet::new()));
+
+        // A game to open from a proton-game-saves:// link or `--appid`. It's
+        // opened once the manifest is matched, on the Games page if it's a
+        // known game and on the Compatdata page otherwise.
+        let pending_game: Rc<RefCell<Option<String>>> = Rc::new(RefCell::new(None));
+        let open_pending_game: Rc<dyn Fn()> = Rc::new(glib::clone!(@strong pending_game, @strong compat_page, @strong games_page, @weak sidebar_list, @weak toast_overlay => move || {
+            if games_page.borrow().is_manifest_loading() {
+                return;
+            }
+            let Some(app_id) = pending_game.take() else {
+                return;
+            };
+            println!("Opening game {} from a link", app_id);
+            if games_page.borrow().reveal(&app_id) {
+                sidebar_list.select_row(sidebar_list.row_at_index(1).as_ref());
+            } else if compat_page.reveal(&app_id) {
+                sidebar_list.select_row(sidebar_list.row_at_index(0).as_ref());
+            } else {
+                toast_overlay.add_toast(adw::Toast::new(&gettext_f("No game or prefix with App ID {} was found", &[&app_id])));
+            }
+        }));
+        let open_game_action = gio::SimpleAction::new("open-game", Some(glib::VariantTy::STRING));
+        open_game_action.connect_activate(glib::clone!(@strong pending_game, @strong open_pending_game => move |_, parameter| {
+            let Some(app_id) = parameter.and_then(|p| p.get::<String>()) else {
+                return;
+            };
+            pending_game.replace(Some(app_id));
+            open_pending_game();
+        }));
+        app.add_action(&open_game_action);
+
+        // Show the current scan result on both pages
+        let show_scan: Rc<dyn Fn()> = Rc::new(glib::clone!(@strong compat_page, @strong games_page, @strong space_page, @strong config, @strong scan_result, @strong scan_compared, @strong open_pending_game, @weak window, @weak changes_banner => move || {
+            let result = scan_result.borrow();
+            println!("Compatdata scan found {} prefixes, {} libraries failed.", result.prefixes.len(), result.failed.len());
+            // Update CompatDataPage UI
+            compat_page.update_listbox(&result.prefixes, &result.failed);
+
+            // Populate GamesPage with the scanned data, unless manifest matching is switched off
+            let options = config.borrow().scan_options();
+            if options.match_manifest {
+                games_page.borrow().populate_games(&result.prefixes, options);
+            } else {
+                println!("Manifest matching disabled in scan options, keeping previous games list.");
+            }
+
+            let hidden = result.prefixes.iter().filter(|prefix| config.borrow().is_prefix_hidden(&prefix.game_id)).count();
+            compat_badge.set_label(&(result.prefixes.len() - hidden).to_string());
+            games_badge.set_label(&games_page.borrow().game_count().to_string());
+            // Only fresh measurements go into the size history
+            if options.match_manifest && options.recompute_sizes {
+                let sizes: Vec<(String, u64)> = games_page.borrow().save_sizes().into_iter().map(|save| (save.app_id, save.size)).collect();
+                if let Err(e) = size_history::record_scan(&config.borrow().size_history_path(), &sizes) {
+                    eprintln!("Error recording save sizes: {:#}", e);
+                }
+            }
+            space_page.update(&result.prefixes, games_page.borrow().save_sizes(), &space_badge);
+            // Games can only be compared once the manifest is there to match
+            // them, without any matched the last record's are kept
+            if !scan_compared.get() && !games_page.borrow().is_manifest_loading() {
+                scan_compared.set(true);
+                let saves = Some(games_page.borrow().save_states()).filter(|saves| options.match_manifest && !saves.is_empty());
+                Self::show_scan_changes(&config.borrow(), &result, saves, &changes_banner);
+            }
+            if config.borrow().save_quota().notify {
+                Self::notify_over_quota(&window, &games_page.borrow().over_quota(), &mut quota_notified.borrow_mut());
+            }
+            open_pending_game();
+        }));
+
+        let show_scan_weak = Rc::downgrade(&show_scan);
+
         // Refresh button action - Refactored
-        let compat_page_clone = compat_page.clone();
-        let games_page_clone = games_page.clone();
-        let window_clone = window.clone(); // Clone window for error dialog
+        let config_clone = config.clone();
+        let scan_result_clone = scan_result.clone();
+        let scan_compared_clone = scan_compared.clone();
+        let show_scan_clone = show_scan.clone();
         refresh_button.connect_clicked(move |_| {
             println!("Refresh button clicked.");
-            // Scan compatdata first
-            match compat_page_clone.scan_compatdata() {
-                Ok(prefixes) => {
-                    println!("Compatdata scan successful, found {} prefixes.", prefixes.len());
-                    // Update CompatDataPage UI
-                    compat_page_clone.update_listbox(&prefixes);
-                    
-                    // Populate GamesPage with the scanned data
-                    // Need to update populate_games signature to accept Vec<PrefixData>
-                    games_page_clone.borrow().populate_games(&prefixes); 
-                }
-                Err(e) => {
-                    eprintln!("Error scanning compatdata: {}", e);
-                    // Show error dialog using Adwaita MessageDialog
-                    let error_dialog = MessageDialog::new(
-                        Some(&window_clone), // Parent window
-                        Some("Error Scanning Compatdata"), // Title - WRAP IN SOME()
-                        Some(&format!("{}", e)) // Body text
-                    );
-                    error_dialog.add_response("ok", "OK"); // Add an "OK" button
-                    error_dialog.set_default_response(Some("ok")); // Make OK the default
-                    error_dialog.set_close_response("ok"); // Close when OK is clicked
-                    
-                    // Connect response to destroy the dialog
-                    error_dialog.connect_response(Some("ok"), |dialog, _response| {
-                        dialog.close(); // Use close() instead of destroy()
-                    });
-                    
-                    error_dialog.present();
+            // Pick up libraries added in Steam since the last scan
+            config_clone.borrow_mut().reload_libraries();
+            // Libraries that fail (e.g. an unplugged drive) show up as rows with a retry button
+            let result = compatdata::scan_libraries(&config_clone.borrow());
+            Self::save_scan_cache(&config_clone.borrow(), &result);
+            *scan_result_clone.borrow_mut() = result;
+            scan_compared_clone.set(false);
+            show_scan_clone();
+        });
+
+        // Rescan just one library, from the retry button on its error row
+        let retry_action = gio::SimpleAction::new("retry-library", Some(glib::VariantTy::STRING));
+        retry_action.connect_activate(glib::clone!(@strong config, @strong scan_result, @strong scan_compared, @strong toast_overlay, @strong show_scan => move |_, parameter| {
+            let Some(compatdata_path) = parameter.and_then(|p| p.get::<String>()).map(PathBuf::from) else {
+                return;
+            };
+            let is_main = compatdata_path == config.borrow().compatdata_path();
+            let scanned = compatdata::scan_library(&config.borrow(), &compatdata_path, is_main);
+            {
+                let mut result = scan_result.borrow_mut();
+                match scanned {
+                    Ok(mut prefixes) => {
+                        scan_compared.set(false);
+                        result.failed.retain(|error| error.compatdata_path != compatdata_path);
+                        result.prefixes.retain(|prefix| !prefix._path.starts_with(&compatdata_path));
+                        result.prefixes.append(&mut prefixes);
+                        result.prefixes.sort_by(|a, b| compatdata::compare_app_ids(&a.game_id, &b.game_id));
+                        Self::save_scan_cache(&config.borrow(), &result);
+                    }
+                    Err(e) => {
+                        if let Some(error) = result.failed.iter_mut().find(|error| error.compatdata_path == compatdata_path) {
+                            error.message = e.to_string();
+                        }
+                        toast_overlay.add_toast(adw::Toast::new(&gettext("Library is still unavailable")));
+                    }
                 }
             }
-        });
+            show_scan();
+        }));
+        app.add_action(&retry_action);
+
+        // Jump to a prefix or game row, from the bars on the disk space page
+        let show_prefix_action = gio::SimpleAction::new("show-prefix", Some(glib::VariantTy::STRING));
+        show_prefix_action.connect_activate(glib::clone!(@strong compat_page, @weak sidebar_list => move |_, parameter| {
+            let Some(game_id) = parameter.and_then(|p| p.get::<String>()) else {
+                return;
+            };
+            sidebar_list.select_row(sidebar_list.row_at_index(0).as_ref());
+            compat_page.reveal(&game_id);
+        }));
+        app.add_action(&show_prefix_action);
+        let show_game_action = gio::SimpleAction::new("show-game", Some(glib::VariantTy::STRING));
+        show_game_action.connect_activate(glib::clone!(@strong games_page, @weak sidebar_list => move |_, parameter| {
+            let Some(app_id) = parameter.and_then(|p| p.get::<String>()) else {
+                return;
+            };
+            sidebar_list.select_row(sidebar_list.row_at_index(1).as_ref());
+            games_page.borrow().reveal(&app_id);
+        }));
+        app.add_action(&show_game_action);
         
-        // Initial manifest load happens here now
-        games_page.borrow_mut().update_manifest(); 
-        // Trigger initial refresh to populate lists on startup
-        refresh_button.emit_clicked(); 
+        let manifest_state = Rc::new(ManifestCacheState {
+            path: RefCell::new(config.borrow().manifest_cache_path().to_path_buf()),
+            loaded_mtime: Cell::new(None),
+            pending_toast: RefCell::new(None),
+            monitor: RefCell::new(None),
+            load_generation: Cell::new(0),
+        });
+
+        // A new manifest only changes the matching, the scan itself is still current
+        let show_scan_when_loaded = move || {
+            if let Some(show_scan) = show_scan_weak.upgrade() {
+                show_scan();
+            }
+        };
+
+        // Rescan when settings that affect the lists change, wherever they were changed
+        let config_weak = Rc::downgrade(&config);
+        let show_scan_when_loaded_clone = show_scan_when_loaded.clone();
+        config.borrow_mut().connect_changed(glib::clone!(@weak refresh_button, @weak games_page, @weak manifest_page, @strong manifest_state, @weak toast_overlay, @weak manifest_banner => move |key| {
+            match key {
+                // Play times and cloud copies come from the picked Steam account
+                ConfigKey::SteamPath | ConfigKey::SteamAccount | ConfigKey::ScanLocations | ConfigKey::DisplayNames | ConfigKey::GameAssignments => refresh_button.emit_clicked(),
+                ConfigKey::Favorites => games_page.borrow().refresh_favorites(),
+                // The scan is still current, only which prefixes are listed changes
+                ConfigKey::HiddenPrefixes => show_scan_when_loaded_clone(),
+                // Sizes are still current, only the warnings change
+                ConfigKey::SaveQuota => games_page.borrow().refresh_game_list(),
+                ConfigKey::ManifestSources | ConfigKey::ManifestCache => {
+                    println!("Manifest changed, reloading it...");
+                    let Some(config) = config_weak.upgrade() else {
+                        return;
+                    };
+                    if config.borrow().manifest_cache_path() != *manifest_state.path.borrow() {
+                        manifest_state.path.replace(config.borrow().manifest_cache_path().to_path_buf());
+                        Self::watch_manifest_cache(&config, &manifest_state, &toast_overlay);
+                    }
+                    Self::load_manifest(&config.borrow(), &manifest_state, &games_page, &manifest_page, &manifest_banner, show_scan_when_loaded_clone.clone());
+                }
+                _ => {}
+            }
+        }));
+
+        // Initial manifest load happens here now, the scan runs meanwhile and the
+        // Games page fills in once parsing is done
+        Self::load_manifest(&config.borrow(), &manifest_state, &games_page, &manifest_page, &manifest_banner, show_scan_when_loaded);
+        Self::watch_manifest_cache(&config, &manifest_state, &toast_overlay);
+        // The last scan shows right away, without waiting for the disks. It's
+        // not compared with the scan record, the fresh scan started below is.
+        if let Some(cached) = ScanResult::load_cached(&config.borrow().scan_cache_path()) {
+            println!("Showing the cached scan ({} prefixes) while scanning again...", cached.prefixes.len());
+            *scan_result.borrow_mut() = cached;
+            scan_compared.set(true);
+            show_scan();
+        }
+        Self::scan_in_background(&config, &scan_result, &scan_compared, &refresh_button, show_scan.clone());
 
         // Create the application actions
-        Self::create_actions(app, window.clone(), config.clone(), compat_page.clone(), games_page.clone(), refresh_button.clone());
+        Self::create_actions(app, window.clone(), config.clone(), scan_result.clone(), games_page.clone(), refresh_button.clone(), toast_overlay.clone());
+
+        // Settings or backups in places the next SteamOS update wipes
+        steamos_check::check(&window, &config, &toast_overlay);
         
         Self {
             window,
+            _manifest_state: manifest_state,
             _config: config,
             _compat_page: compat_page,
             _games_page: games_page,
+            _space_page: space_page,
         }
     }
     
     pub fn present(&self) {
         self.window.present();
     }
+
+    fn clamp_page(page: &Box) -> adw::Clamp {
+        adw::Clamp::builder()
+            .maximum_size(PAGE_MAX_WIDTH)
+            .tightening_threshold(PAGE_MAX_WIDTH * 3 / 4)
+            .child(page)
+            .build()
+    }
+
+    // Parse the manifest on a worker thread, it's tens of thousands of games of
+    // YAML. The banner shows how far along it is, `on_loaded` runs once the Games
+    // page has the new data.
+    fn load_manifest(
+        config: &Config,
+        state: &Rc<ManifestCacheState>,
+        games_page: &Rc<RefCell<GamesPage>>,
+        manifest_page: &Rc<ManifestPage>,
+        banner: &adw::Banner,
+        on_loaded: impl Fn() + 'static,
+    ) {
+        let generation = state.load_generation.get() + 1;
+        state.load_generation.set(generation);
+        // The version on disk now is the one being read, don't offer to reload it
+        state.mark_loaded();
+        games_page.borrow_mut().set_manifest_loading();
+        manifest_page.set_loading();
+        banner.set_title(&gettext("Loading the game manifest…"));
+        banner.set_button_label(None);
+        banner.set_revealed(true);
+
+        // Progress arrives from the worker thread, pick it up from the main loop
+        let (sender, receiver) = mpsc::channel::<ParseProgress>();
+        let state_weak = Rc::downgrade(state);
+        glib::timeout_add_local(Duration::from_millis(100), glib::clone!(@weak banner => @default-return glib::ControlFlow::Break, move || {
+            if state_weak.upgrade().map(|state| state.load_generation.get()) != Some(generation) {
+                return glib::ControlFlow::Break;
+            }
+            loop {
+                match receiver.try_recv() {
+                    Ok(progress) => banner.set_title(&Self::parse_progress_label(&progress)),
+                    Err(mpsc::TryRecvError::Empty) => return glib::ControlFlow::Continue,
+                    Err(mpsc::TryRecvError::Disconnected) => return glib::ControlFlow::Break,
+                }
+            }
+        }));
+
+        let inputs = ManifestInputs::new(config);
+        let state = state.clone();
+        let games_page = games_page.clone();
+        let manifest_page = manifest_page.clone();
+        let banner = banner.clone();
+        glib::MainContext::default().spawn_local(async move {
+            let result = gio::spawn_blocking(move || {
+                manifest::parse_manifest_inputs(&inputs, |progress| {
+                    sender.send(progress).ok();
+                })
+            })
+            .await;
+            if state.load_generation.get() != generation {
+                return;
+            }
+            banner.set_revealed(false);
+            let data = match result {
+                Ok(Ok(data)) => {
+                    println!("Manifest parsed successfully, {} games.", data.games.len());
+                    if !data.skipped.is_empty() {
+                        banner.set_title(&gettext_f("{} broken manifest entries were skipped, the Game Database page lists them", &[&data.skipped.len().to_string()]));
+                        banner.set_button_label(Some(&gettext("Dismiss")));
+                        banner.set_revealed(true);
+                    }
+                    Some(data)
+                }
+                Ok(Err(e)) => {
+                    eprintln!("Failed to parse manifest: {:#}", e);
+                    None
+                }
+                Err(_) => {
+                    eprintln!("Manifest parsing thread panicked");
+                    None
+                }
+            };
+            manifest_page.set_manifest(data.as_ref());
+            games_page.borrow_mut().set_manifest(data);
+            on_loaded();
+        });
+    }
+
+    // Compares the scan with the one recorded last time (maybe in an earlier
+    // session) and says what's different in the banner. Without `saves` the
+    // games weren't matched, only prefixes are compared then.
+    fn show_scan_changes(config: &Config, result: &ScanResult, saves: Option<Vec<GameSaves>>, banner: &adw::Banner) {
+        let path = config.last_scan_path();
+        let previous = ScanRecord::load(&path);
+        let prefixes: Vec<String> = result.prefixes.iter().map(|prefix| prefix.game_id.clone()).collect();
+        let current = ScanRecord::new(&prefixes, saves.as_deref(), previous.as_ref());
+        if let Err(e) = current.save(&path) {
+            eprintln!("Error recording the scan: {:#}", e);
+        }
+        let changes = previous
+            .map(|previous| previous.changes(&current, saves.as_deref().unwrap_or_default()))
+            .unwrap_or_default();
+        if changes.is_empty() {
+            banner.set_revealed(false);
+            return;
+        }
+        println!("Changed since the last scan: {:?}", changes);
+        banner.set_title(&glib::markup_escape_text(&Self::changes_summary(&changes)));
+        banner.set_revealed(true);
+    }
+
+    fn changes_summary(changes: &ScanChanges) -> String {
+        let mut parts = Vec::new();
+        if !changes.new_prefixes.is_empty() {
+            parts.push(gettext_f("{} new prefixes", &[&changes.new_prefixes.len().to_string()]));
+        }
+        if !changes.removed_prefixes.is_empty() {
+            parts.push(gettext_f("{} prefixes removed", &[&changes.removed_prefixes.len().to_string()]));
+        }
+        // A few names, the banner is a single line
+        let games = &changes.changed_saves;
+        if !games.is_empty() {
+            let names = if games.len() > 3 {
+                gettext_f("{} and {} more", &[&games[..3].join(", "), &(games.len() - 3).to_string()])
+            } else {
+                games.join(", ")
+            };
+            parts.push(gettext_f("saves changed for {}", &[&names]));
+        }
+        gettext_f("Since the last scan: {}", &[&parts.join(", ")])
+    }
+
+    // Desktop notification about games whose saves grew past their size limit
+    // since the last one
+    fn notify_over_quota(window: &ApplicationWindow, over_quota: &[(String, u64, u64)], notified: &mut HashSet<String>) {
+        let new: Vec<&(String, u64, u64)> = over_quota.iter().filter(|(name, _, _)| !notified.contains(name)).collect();
+        notified.retain(|name| over_quota.iter().any(|(over, _, _)| over == name));
+        let Some(app) = window.application() else {
+            return;
+        };
+        let body = match new.as_slice() {
+            [] => return,
+            [(name, size, limit)] => gettext_f(
+                "A save folder of {} takes {}, over its limit of {}. Check it on the Games page, then back it up and delete old files.",
+                &[name, &format_size(*size, DECIMAL), &format_size(*limit, DECIMAL)],
+            ),
+            games => gettext_f(
+                "{} games have save folders over their size limit: {}. Check them on the Games page, then back them up and delete old files.",
+                &[&games.len().to_string(), &games.iter().map(|(name, _, _)| name.as_str()).collect::<Vec<_>>().join(", ")],
+            ),
+        };
+        let notification = gio::Notification::new(&gettext("Saves Over Size Limit"));
+        notification.set_body(Some(&body));
+        app.send_notification(Some("save-quota"), &notification);
+        notified.extend(new.into_iter().map(|(name, _, _)| name.clone()));
+    }
+
+    fn parse_progress_label(progress: &ParseProgress) -> String {
+        match progress {
+            ParseProgress::LoadingParsedCache => gettext("Loading the game manifest…"),
+            ParseProgress::ParsingManifest => gettext("Parsing the game manifest, this takes a moment after a download…"),
+            ParseProgress::MergingSource { index, count } => gettext_f(
+                "Merging manifest source {} of {}…",
+                &[&(index + 1).to_string(), &count.to_string()],
+            ),
+            ParseProgress::SavingParsedCache => gettext("Saving the parsed manifest for faster startups…"),
+        }
+    }
+
+    // Offer to reload the manifest when the cache file is replaced by something
+    // else. Our own downloads go through ConfigKey::ManifestCache, which marks
+    // the new file as loaded and dismisses the prompt.
+    // The monitor lives in `state` and holds the state and config weakly, the
+    // config's listeners already keep the state alive.
+    fn watch_manifest_cache(config: &Rc<RefCell<Config>>, state: &Rc<ManifestCacheState>, toast_overlay: &adw::ToastOverlay) {
+        let path = state.path.borrow().clone();
+        let file = gio::File::for_path(&path);
+        let monitor = match file.monitor_file(gio::FileMonitorFlags::WATCH_MOVES, gio::Cancellable::NONE) {
+            Ok(monitor) => monitor,
+            Err(e) => {
+                eprintln!("Can't watch manifest cache {}: {}", path.display(), e);
+                state.monitor.replace(None);
+                return;
+            }
+        };
+        let state_weak = Rc::downgrade(state);
+        let config_weak = Rc::downgrade(config);
+        let toast_overlay = toast_overlay.clone();
+        monitor.connect_changed(move |_, _, _, event| {
+            let (Some(state), Some(config)) = (state_weak.upgrade(), config_weak.upgrade()) else {
+                return;
+            };
+            if !matches!(
+                event,
+                gio::FileMonitorEvent::ChangesDoneHint
+                    | gio::FileMonitorEvent::Created
+                    | gio::FileMonitorEvent::MovedIn
+                    | gio::FileMonitorEvent::Renamed
+            ) {
+                return;
+            }
+            // Several events arrive for one replacement, only prompt once per version
+            let mtime = state.current_mtime();
+            if mtime.is_none() || mtime == state.loaded_mtime.get() {
+                return;
+            }
+            state.loaded_mtime.set(mtime);
+            println!("Manifest cache {} changed on disk", state.path.borrow().display());
+
+            if let Some(toast) = state.pending_toast.take() {
+                toast.dismiss();
+            }
+            let toast = adw::Toast::builder()
+                .title(gettext("The cached manifest was changed by another program"))
+                .button_label(gettext("Reload"))
+                .timeout(0)
+                .build();
+            toast.connect_button_clicked(glib::clone!(@strong config => move |_| {
+                config.borrow().notify(ConfigKey::ManifestCache);
+            }));
+            toast_overlay.add_toast(toast.clone());
+            state.pending_toast.replace(Some(toast));
+        });
+        println!("Watching manifest cache {}", path.display());
+        state.monitor.replace(Some(monitor));
+    }
+
+    // Sidebar entry for one page, returns the badge label showing its item count
+    fn add_sidebar_row(sidebar_list: &ListBox, icon_name: &str, title: &str) -> Label {
+        let row_box = Box::new(Orientation::Horizontal, 12);
+        row_box.set_margin_top(6);
+        row_box.set_margin_bottom(6);
+        row_box.append(&gtk::Image::from_icon_name(icon_name));
+
+        let title_label = Label::new(Some(title));
+        title_label.set_halign(gtk::Align::Start);
+        title_label.set_hexpand(true);
+        row_box.append(&title_label);
+
+        let badge = Label::new(None);
+        badge.add_css_class("dim-label");
+        badge.add_css_class("numeric");
+        row_box.append(&badge);
+
+        sidebar_list.append(&row_box);
+        badge
+    }
     
-    fn create_actions(app: &adw::Application, window: ApplicationWindow, config: Rc<RefCell<Config>>, _compat_page: Rc<CompatDataPage>, games_page: Rc<RefCell<GamesPage>>, refresh_button: Button) {
+    // The startup scan, on a worker thread with its own config so a slow or
+    // sleeping drive doesn't block the window. Refresh stays off meanwhile.
+    fn scan_in_background(config: &Rc<RefCell<Config>>, scan_result: &Rc<RefCell<ScanResult>>, scan_compared: &Rc<Cell<bool>>, refresh_button: &adw::SplitButton, show_scan: Rc<dyn Fn()>) {
+        refresh_button.set_sensitive(false);
+        glib::MainContext::default().spawn_local(glib::clone!(@strong config, @strong scan_result, @strong scan_compared, @weak refresh_button => async move {
+            let scanned = gio::spawn_blocking(|| compatdata::scan_libraries(&Config::new())).await;
+            refresh_button.set_sensitive(true);
+            let Ok(result) = scanned else {
+                eprintln!("Background scan panicked, scanning again on the main thread");
+                refresh_button.emit_clicked();
+                return;
+            };
+            // Libraries Steam added meanwhile, the worker already saw them
+            config.borrow_mut().reload_libraries();
+            Self::save_scan_cache(&config.borrow(), &result);
+            *scan_result.borrow_mut() = result;
+            scan_compared.set(false);
+            show_scan();
+        }));
+    }
+
+    fn save_scan_cache(config: &Config, result: &ScanResult) {
+        let path = config.scan_cache_path();
+        if let Err(e) = result.save_cached(&path) {
+            eprintln!("Failed to save the scan cache to {}: {:#}", path.display(), e);
+        }
+    }
+
+    fn create_actions(app: &adw::Application, window: ApplicationWindow, config: Rc<RefCell<Config>>, scan_result: Rc<RefCell<ScanResult>>, games_page: Rc<RefCell<GamesPage>>, refresh_button: adw::SplitButton, toast_overlay: adw::ToastOverlay) {
         // Quit action
         let quit_action = gio::SimpleAction::new("quit", None);
-        quit_action.connect_activate(glib::clone!(@weak app => move |_, _| {
+        quit_action.connect_activate(glib::clone!(@weak app, @weak window => move |_, _| {
+            // Closing first so the window state gets saved, quitting skips close-request
+            window.close();
             app.quit();
         }));
         app.add_action(&quit_action);
@@ -154,43 +784,515 @@ impl ProtonSavesWindow {
             Self::show_about_dialog(&window);
         }));
         app.add_action(&about_action);
+
+        // Export report action
+        let export_action = gio::SimpleAction::new("export-report", None);
+        export_action.connect_activate(glib::clone!(@weak window, @strong games_page, @weak toast_overlay => move |_, _| {
+            let entries = games_page.borrow().report_entries();
+            glib::MainContext::default().spawn_local(async move {
+                Self::export_report_async(window, toast_overlay, entries).await;
+            });
+        }));
+        app.add_action(&export_action);
+
+        // Bundle actions, for moving every save to another machine
+        let export_bundle_action = gio::SimpleAction::new("export-bundle", None);
+        export_bundle_action.connect_activate(glib::clone!(@weak window, @strong config, @strong games_page, @weak toast_overlay => move |_, _| {
+            let games = games_page.borrow().export_games();
+            let config = config.clone();
+            glib::MainContext::default().spawn_local(async move {
+                Self::export_bundle_async(window, config, toast_overlay, games).await;
+            });
+        }));
+        app.add_action(&export_bundle_action);
+
+        let import_bundle_action = gio::SimpleAction::new("import-bundle", None);
+        import_bundle_action.connect_activate(glib::clone!(@weak window, @strong config, @weak toast_overlay => move |_, _| {
+            let config = config.clone();
+            glib::MainContext::default().spawn_local(async move {
+                Self::import_bundle_async(window, config, toast_overlay).await;
+            });
+        }));
+        app.add_action(&import_bundle_action);
+
+        // Direct sync with another copy of the app, e.g. desktop and Steam Deck
+        let lan_sync_action = gio::SimpleAction::new("lan-sync", None);
+        lan_sync_action.connect_activate(glib::clone!(@weak window, @strong config, @strong games_page => move |_, _| {
+            let games = games_page.borrow().export_games();
+            lan_sync_dialog::show(&window, config.clone(), games);
+        }));
+        app.add_action(&lan_sync_action);
+
+        // Recovery wizard over the games matched in the last scan
+        let recover_action = gio::SimpleAction::new("recover", None);
+        recover_action.connect_activate(glib::clone!(@weak window, @strong config, @strong games_page => move |_, _| {
+            let games = games_page.borrow().recover_games();
+            recover_dialog::show(&window, config.clone(), games);
+        }));
+        app.add_action(&recover_action);
+
+        // Cleanup wizard over the prefixes found in the last scan
+        let cleanup_action = gio::SimpleAction::new("cleanup", None);
+        cleanup_action.connect_activate(glib::clone!(@weak window, @strong config, @strong scan_result, @strong games_page => move |_, _| {
+            let games = games_page.borrow().recover_games();
+            cleanup_dialog::show(&window, config.clone(), scan_result.clone(), games);
+        }));
+        app.add_action(&cleanup_action);
+
+        // Empty prefix for a game not run under Proton yet, to put saves in first
+        let create_prefix_action = gio::SimpleAction::new("create-prefix", None);
+        create_prefix_action.connect_activate(glib::clone!(@weak window, @strong config, @weak toast_overlay => move |_, _| {
+            Self::show_create_prefix_dialog(&window, &config, &toast_overlay);
+        }));
+        app.add_action(&create_prefix_action);
+
+        // Refresh action, lets pages trigger a rescan after changing files on disk
+        let refresh_action = gio::SimpleAction::new("refresh", None);
+        refresh_action.connect_activate(glib::clone!(@weak refresh_button => move |_, _| {
+            refresh_button.emit_clicked();
+        }));
+        app.add_action(&refresh_action);
         
         // Settings action
         let settings_action = gio::SimpleAction::new("settings", None);
-        // Clone Rc for the games_page to be used in the outer closure
-        let games_page_for_settings = games_page.clone(); 
-        // Clone refresh_button for the outer closure (and potentially inner)
-        let refresh_button_clone = refresh_button.clone(); 
-        settings_action.connect_activate(glib::clone!(@weak window, @strong config, @strong games_page_for_settings, @strong refresh_button_clone => move |_, _| {
-            // Clone again for the inner FnMut closure
-            let games_page_for_callback = games_page_for_settings.clone();
-            // Clone refresh button again for inner closure
-            let refresh_button_for_callback = refresh_button_clone.clone(); 
-            // Create the callback closure 
-            let on_update_callback = Rc::new(RefCell::new(move || { 
-                println!("Settings updated, triggering manifest refresh...");
-                games_page_for_callback.borrow_mut().update_manifest();
-                println!("Manifest updated via settings, triggering full refresh...");
-                // Now trigger the main refresh button
-                refresh_button_for_callback.emit_clicked(); 
-                // TODO: Consider if only populating games is needed vs full refresh
-            }));
-            
-            // Pass the callback to SettingsDialog::new
-            let dialog = SettingsDialog::new(window.clone(), config.clone(), on_update_callback);
+        settings_action.connect_activate(glib::clone!(@weak window, @strong config => move |_, _| {
+            // Changes made in the dialog reach the pages through config change notifications
+            let dialog = SettingsDialog::new(window.clone(), config.clone());
             dialog.present(); 
         }));
         app.add_action(&settings_action);
+
+        app.set_accels_for_action("app.refresh", &["F5", "<Control>r"]);
+        app.set_accels_for_action("app.settings", &["<Control>comma"]);
+        app.set_accels_for_action("app.recover", &["<Control><Shift>r"]);
+        app.set_accels_for_action("app.quit", &["<Control>q"]);
     }
     
+    // Popover with toggles deciding how thorough a refresh is
+    fn build_scan_options_popover(config: Rc<RefCell<Config>>) -> gtk::Popover {
+        let content = Box::new(Orientation::Vertical, 6);
+        content.set_margin_start(6);
+        content.set_margin_end(6);
+        content.set_margin_top(6);
+        content.set_margin_bottom(6);
+
+        let heading = gtk::Label::new(Some(&gettext("Scan Options")));
+        heading.add_css_class("heading");
+        heading.set_halign(gtk::Align::Start);
+        content.append(&heading);
+
+        let options = config.borrow().scan_options();
+        let toggles: [ScanToggle; 3] = [
+            (gettext("Match games against the manifest"), options.match_manifest, |o, v| o.match_manifest = v),
+            (gettext("Recalculate save sizes"), options.recompute_sizes, |o, v| o.recompute_sizes = v),
+            (gettext("Deep scan (check every manifest path, slow)"), options.deep_scan, |o, v| o.deep_scan = v),
+        ];
+        for (label, active, apply) in toggles {
+            let check = gtk::CheckButton::with_label(&label);
+            check.set_active(active);
+            let config_clone = config.clone();
+            check.connect_toggled(move |check| {
+                let mut options = config_clone.borrow().scan_options();
+                apply(&mut options, check.is_active());
+                if let Err(e) = config_clone.borrow_mut().set_scan_options(options) {
+                    eprintln!("Error saving scan options: {}", e);
+                }
+            });
+            content.append(&check);
+        }
+
+        // Libraries and manifest sources can change while the app runs, the
+        // checkboxes for them are made each time the popover opens
+        let scope = Box::new(Orientation::Vertical, 6);
+        content.append(&scope);
+        let popover = gtk::Popover::new();
+        popover.set_child(Some(&content));
+        popover.connect_show(move |_| {
+            while let Some(child) = scope.first_child() {
+                scope.remove(&child);
+            }
+            Self::append_scan_scope(&scope, &config);
+        });
+        popover
+    }
+
+    // One checkbox per library and per additional manifest source
+    fn append_scan_scope(scope: &Box, config: &Rc<RefCell<Config>>) {
+        let section_heading = |title: &str| {
+            let label = Label::new(Some(title));
+            label.add_css_class("heading");
+            label.set_halign(gtk::Align::Start);
+            label.set_margin_top(6);
+            label
+        };
+
+        scope.append(&section_heading(&gettext("Libraries")));
+        let libraries = config.borrow().compatdata_paths();
+        for compatdata_path in libraries {
+            // steamapps/compatdata -> the library folder itself
+            let library = compatdata_path.parent().and_then(Path::parent).unwrap_or(&compatdata_path).display().to_string();
+            let check = gtk::CheckButton::with_label(&library);
+            check.set_active(config.borrow().is_library_scanned(&compatdata_path));
+            let config_clone = config.clone();
+            check.connect_toggled(move |check| {
+                if let Err(e) = config_clone.borrow_mut().set_library_scanned(&compatdata_path, check.is_active()) {
+                    eprintln!("Error saving scan options: {}", e);
+                }
+            });
+            scope.append(&check);
+        }
+
+        let sources = config.borrow().manifest_sources().to_vec();
+        if sources.is_empty() {
+            return;
+        }
+        scope.append(&section_heading(&gettext("Manifest Sources")));
+        for source in sources {
+            let check = gtk::CheckButton::with_label(&source);
+            check.set_active(config.borrow().is_manifest_source_scanned(&source));
+            let config_clone = config.clone();
+            check.connect_toggled(move |check| {
+                if let Err(e) = config_clone.borrow_mut().set_manifest_source_scanned(&source, check.is_active()) {
+                    eprintln!("Error saving scan options: {}", e);
+                }
+            });
+            scope.append(&check);
+        }
+    }
+
+    // Ask where to save the inventory report and write it as CSV or JSON (by extension)
+    async fn export_report_async(window: ApplicationWindow, toast_overlay: adw::ToastOverlay, entries: Vec<ReportEntry>) {
+        let file_dialog = FileDialog::new();
+        file_dialog.set_title(&gettext("Export Report"));
+        file_dialog.set_initial_name(Some("proton-game-saves-report.csv"));
+
+        let filters = gio::ListStore::new::<FileFilter>();
+        for (name, pattern) in [(gettext("CSV"), "*.csv"), (gettext("JSON"), "*.json")] {
+            let filter = FileFilter::new();
+            filter.set_name(Some(&name));
+            filter.add_pattern(pattern);
+            filters.append(&filter);
+        }
+        file_dialog.set_filters(Some(&filters));
+
+        let Ok(file) = file_dialog.save_future(Some(&window)).await else {
+            return; // Cancelled
+        };
+        let Some(path) = file.path() else {
+            return;
+        };
+
+        match report::write_report(&entries, &path) {
+            Ok(_) => toast_overlay.add_toast(adw::Toast::new(&gettext_f("Report saved to {}", &[&path.display().to_string()]))),
+            Err(e) => {
+                eprintln!("Error exporting report: {:#}", e);
+                error_dialog::show(Some(window.upcast_ref()), &gettext("Error Exporting Report"), &e);
+            }
+        }
+    }
+
+    // Show the size estimate, ask for a destination folder and write the bundle there
+    async fn export_bundle_async(window: ApplicationWindow, config: Rc<RefCell<Config>>, toast_overlay: adw::ToastOverlay, games: Vec<ExportGame>) {
+        if games.is_empty() {
+            Self::show_message(&window, &gettext("Nothing to Export"), &gettext("No saves were found for any matched game. Refresh the lists first."));
+            return;
+        }
+
+        let confirm = MessageDialog::new(
+            Some(&window),
+            Some(&gettext("Export All Saves?")),
+            Some(&gettext_f(
+                "Saves of {} games (about {}) will be copied into a new bundle folder for moving them to another machine.",
+                &[&games.len().to_string(), &format_size(bundle::estimate_size(&games), DECIMAL)],
+            )),
+        );
+        // Mounts (cloud accounts from GNOME Online Accounts, network shares,
+        // removable drives) are offered next to local folders, then the
+        // destinations set up in Settings
+        let mounts = mounts::mounted_locations();
+        let (targets, privacy) = {
+            let config = config.borrow();
+            (config.backup_targets().to_vec(), config.privacy())
+        };
+        let mut destination_names = vec![gettext("Local Folder")];
+        destination_names.extend(mounts.iter().map(|(name, _)| name.clone()));
+        destination_names.extend(targets.iter().map(|target| backup_target::from_settings(target, privacy).describe()));
+        let destination_refs: Vec<&str> = destination_names.iter().map(String::as_str).collect();
+        let destination_row = adw::ComboRow::builder()
+            .title(gettext("Destination"))
+            .model(&gtk::StringList::new(&destination_refs))
+            .build();
+        let destination_list = gtk::ListBox::new();
+        destination_list.set_selection_mode(gtk::SelectionMode::None);
+        destination_list.add_css_class("boxed-list");
+        destination_list.append(&destination_row);
+        // Off by default, most moves only need the saves
+        let settings_row = adw::SwitchRow::builder()
+            .title(gettext("Include Steam Settings"))
+            .subtitle(gettext("Controller layouts and launch options of each game"))
+            .active(config.borrow().bundle_steam_settings())
+            .build();
+        destination_list.append(&settings_row);
+        // Only for this export, bundles moved between own machines need the IDs
+        let anonymize_row = adw::SwitchRow::builder()
+            .title(gettext("Replace Account IDs"))
+            .subtitle(gettext_f("For sharing saves publicly. Folders and files named after Steam or launcher accounts are renamed, {} in the bundle lists them", &[anonymize::REPORT_FILE_NAME]))
+            .build();
+        destination_list.append(&anonymize_row);
+        confirm.set_extra_child(Some(&destination_list));
+
+        confirm.add_response("cancel", &gettext("Cancel"));
+        confirm.add_response("choose", &gettext("Choose Folder…"));
+        confirm.set_response_appearance("choose", adw::ResponseAppearance::Suggested);
+        confirm.set_default_response(Some("choose"));
+        confirm.set_close_response("cancel");
+        // Destinations from Settings already know their folder
+        let first_target = 1 + mounts.len() as u32;
+        destination_row.connect_selected_notify(glib::clone!(@weak confirm => move |row| {
+            confirm.set_response_label("choose", &if row.selected() >= first_target { gettext("Export") } else { gettext("Choose Folder…") });
+        }));
+        if confirm.choose_future().await != "choose" {
+            return;
+        }
+        if let Err(e) = config.borrow_mut().set_bundle_steam_settings(settings_row.is_active()) {
+            eprintln!("Failed to save the Steam settings choice: {}", e);
+        }
+
+        let anonymize = anonymize_row.is_active();
+        let selected = destination_row.selected() as usize;
+        let target: std::boxed::Box<dyn BackupTarget> = match selected.checked_sub(first_target as usize).and_then(|index| targets.get(index)) {
+            Some(settings) => backup_target::from_settings(settings, privacy),
+            None => {
+                let file_dialog = FileDialog::new();
+                file_dialog.set_title(&gettext("Choose Where to Save the Bundle"));
+                // Start inside the chosen mount, any folder in it can be picked
+                if let Some((_, root)) = selected.checked_sub(1).and_then(|index| mounts.get(index)) {
+                    file_dialog.set_initial_folder(Some(root));
+                }
+                let Ok(folder) = file_dialog.select_folder_future(Some(&window)).await else {
+                    return; // Cancelled
+                };
+                match folder.path() {
+                    Some(path) => std::boxed::Box::new(LocalTarget::new(&gettext("Local Folder"), path)),
+                    None => std::boxed::Box::new(mounts::GioTarget::new(folder)),
+                }
+            }
+        };
+
+        let _inhibitor = Inhibitor::new(&window, &gettext("Exporting game saves"));
+        // Local folders (mounts with a FUSE path too) are written to directly,
+        // the others get the bundle built in a temporary folder and stored
+        if let Some(parent) = target.local_path() {
+            if !parent.is_dir() {
+                Self::show_message(&window, &gettext("Destination Not Available"), &gettext_f("The folder {} does not exist. Is the drive connected?", &[&parent.display().to_string()]));
+                return;
+            }
+            match bundle::export_bundle(&config.borrow(), &games, parent, anonymize) {
+                Ok(bundle_dir) => toast_overlay.add_toast(adw::Toast::new(&gettext_f("Saves exported to {}", &[&bundle_dir.display().to_string()]))),
+                Err(e) => {
+                    eprintln!("Error exporting bundle: {:#}", e);
+                    error_dialog::show(Some(window.upcast_ref()), &gettext("Error Exporting Saves"), &e);
+                }
+            }
+            return;
+        }
+
+        let stamp = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).map(|d| d.as_millis()).unwrap_or_default();
+        let temp_dir = std::env::temp_dir().join(format!("proton_game_saves_export_{}", stamp));
+        let bundle_dir = match bundle::export_bundle(&config.borrow(), &games, &temp_dir, anonymize) {
+            Ok(bundle_dir) => bundle_dir,
+            Err(e) => {
+                eprintln!("Error exporting bundle: {:#}", e);
+                let _ = fs::remove_dir_all(&temp_dir);
+                error_dialog::show(Some(window.upcast_ref()), &gettext("Error Exporting Saves"), &e);
+                return;
+            }
+        };
+        let toast = adw::Toast::builder()
+            .title(gettext_f("Uploading saves to {}…", &[&target.describe()]))
+            .timeout(0)
+            .build();
+        toast_overlay.add_toast(toast.clone());
+        let size = bundle::estimate_size(&games);
+        let uploaded = gio::spawn_blocking(move || {
+            let result = target.store(&bundle_dir);
+            let _ = fs::remove_dir_all(&temp_dir);
+            result
+        })
+        .await;
+        toast.dismiss();
+        match uploaded {
+            Ok(Ok(uri)) => {
+                activity::record(
+                    &config.borrow(),
+                    ActivityEntry::new(ActivityKind::Backup, format!("Uploaded a save bundle to {}", uri)).bytes(size),
+                );
+                toast_overlay.add_toast(adw::Toast::new(&gettext_f("Saves exported to {}", &[&uri])));
+            }
+            Ok(Err(e)) => {
+                eprintln!("Error uploading bundle: {:#}", e);
+                error_dialog::show(Some(window.upcast_ref()), &gettext("Error Exporting Saves"), &e);
+            }
+            Err(_) => eprintln!("Uploading the bundle panicked"),
+        }
+    }
+
+    // Pick a bundle folder, confirm, and copy its saves into the matching prefixes
+    async fn import_bundle_async(window: ApplicationWindow, config: Rc<RefCell<Config>>, toast_overlay: adw::ToastOverlay) {
+        let file_dialog = FileDialog::new();
+        file_dialog.set_title(&gettext("Select Save Bundle Folder"));
+        let Ok(folder) = file_dialog.select_folder_future(Some(&window)).await else {
+            return; // Cancelled
+        };
+        let Some(bundle_dir) = folder.path() else {
+            return;
+        };
+
+        let manifest = match bundle::read_bundle(&bundle_dir) {
+            Ok(manifest) => manifest,
+            Err(e) => {
+                error_dialog::show(Some(window.upcast_ref()), &gettext("Not a Save Bundle"), &e);
+                return;
+            }
+        };
+
+        let size: u64 = manifest.games.iter().flat_map(|g| g.locations.iter()).map(|l| l.size_bytes).sum();
+        let confirm = MessageDialog::new(
+            Some(&window),
+            Some(&gettext("Import Save Bundle?")),
+            Some(&gettext_f(
+                "Saves of {} games ({}) will be copied into their prefixes, replacing the files there. A safety snapshot of the current saves is taken first.",
+                &[&manifest.games.len().to_string(), &format_size(size, DECIMAL)],
+            )),
+        );
+        confirm.add_response("cancel", &gettext("Cancel"));
+        confirm.add_response("import", &gettext("Import"));
+        confirm.set_response_appearance("import", adw::ResponseAppearance::Destructive);
+        confirm.set_default_response(Some("cancel"));
+        confirm.set_close_response("cancel");
+        if !confirm::ask(&config, confirm, ConfirmAction::Replace, "import-bundle", "import").await {
+            return;
+        }
+
+        let inhibitor = Inhibitor::new(&window, &gettext("Importing game saves"));
+        let result = bundle::import_bundle(&config.borrow(), &bundle_dir, &manifest);
+        drop(inhibitor);
+        match result {
+            Ok(summary) => {
+                toast_overlay.add_toast(adw::Toast::new(&gettext_f("Imported saves of {} games", &[&summary.restored_games.to_string()])));
+                if !summary.missing_prefixes.is_empty() {
+                    Self::show_message(
+                        &window,
+                        &gettext("Some Games Were Skipped"),
+                        &gettext_f(
+                            "These games have no Proton prefix on this machine yet. Launch them once through Steam, then import the bundle again:\n\n{}",
+                            &[&summary.missing_prefixes.join("\n")],
+                        ),
+                    );
+                }
+                if !summary.unlisted_registry.is_empty() {
+                    Self::show_message(
+                        &window,
+                        &gettext("Registry Saves Were Skipped"),
+                        &gettext_f(
+                            "The game manifest on this machine doesn't list these games, so their registry saves weren't imported. Update the manifest, then import the bundle again:\n\n{}",
+                            &[&summary.unlisted_registry.join("\n")],
+                        ),
+                    );
+                }
+                if !summary.launch_options.is_empty() {
+                    Self::show_message(
+                        &window,
+                        &gettext("Set Launch Options Again"),
+                        &gettext_f(
+                            "Steam keeps launch options to itself, they can't be imported. Set them again in each game's properties in Steam:\n\n{}",
+                            &[&summary.launch_options.join("\n")],
+                        ),
+                    );
+                }
+            }
+            Err(e) => {
+                eprintln!("Error importing bundle: {:#}", e);
+                error_dialog::show(Some(window.upcast_ref()), &gettext("Error Importing Saves"), &e);
+            }
+        }
+        WidgetExt::activate_action(&window, "app.refresh", None).ok();
+    }
+
+    // Ask for the App ID and library of the new prefix, then create it
+    fn show_create_prefix_dialog(window: &ApplicationWindow, config: &Rc<RefCell<Config>>, toast_overlay: &adw::ToastOverlay) {
+        let libraries = config.borrow().compatdata_paths();
+        let dialog = MessageDialog::new(
+            Some(window),
+            Some(&gettext("Create Empty Prefix?")),
+            Some(&gettext("Creates the folders of a Proton prefix for a game that hasn't been launched yet, so saves can be put in place first. Proton sets up the rest on the game's first launch and keeps the files already there.")),
+        );
+
+        let app_id_row = adw::EntryRow::builder()
+            .title(gettext("App ID"))
+            .input_purpose(gtk::InputPurpose::Digits)
+            .build();
+        let library_names: Vec<String> = libraries.iter().map(|path| path.display().to_string()).collect();
+        let library_refs: Vec<&str> = library_names.iter().map(String::as_str).collect();
+        let library_row = adw::ComboRow::builder()
+            .title(gettext("Library"))
+            .model(&gtk::StringList::new(&library_refs))
+            .build();
+        let list = ListBox::new();
+        list.set_selection_mode(gtk::SelectionMode::None);
+        list.add_css_class("boxed-list");
+        list.append(&app_id_row);
+        // Proton puts the prefix into the library the game is installed in
+        if libraries.len() > 1 {
+            list.append(&library_row);
+        }
+        dialog.set_extra_child(Some(&list));
+
+        dialog.add_response("cancel", &gettext("Cancel"));
+        dialog.add_response("create", &gettext("Create"));
+        dialog.set_response_appearance("create", adw::ResponseAppearance::Suggested);
+        dialog.set_default_response(Some("create"));
+        dialog.set_close_response("cancel");
+        dialog.set_response_enabled("create", false);
+        app_id_row.connect_changed(glib::clone!(@weak dialog => move |row| {
+            let text = row.text();
+            dialog.set_response_enabled("create", !text.is_empty() && text.chars().all(|c| c.is_ascii_digit()));
+        }));
+
+        dialog.connect_response(Some("create"), glib::clone!(@weak window, @weak toast_overlay => move |_, _| {
+            let app_id = app_id_row.text().to_string();
+            let Some(compatdata_path) = libraries.get(library_row.selected() as usize) else {
+                return;
+            };
+            match compatdata::create_empty_prefix(compatdata_path, &app_id) {
+                Ok(user_path) => {
+                    toast_overlay.add_toast(adw::Toast::new(&gettext_f("Created an empty prefix for {}", &[&app_id])));
+                    println!("Saves for {} can go into {}", app_id, user_path.display());
+                    WidgetExt::activate_action(&window, "app.refresh", None).ok();
+                }
+                Err(e) => {
+                    eprintln!("Error creating prefix for {}: {:#}", app_id, e);
+                    error_dialog::show(Some(window.upcast_ref()), &gettext("Error Creating Prefix"), &e);
+                }
+            }
+        }));
+        dialog.present();
+    }
+
+    fn show_message(window: &ApplicationWindow, heading: &str, body: &str) {
+        let dialog = MessageDialog::new(Some(window), Some(heading), Some(body));
+        dialog.add_response("ok", &gettext("OK"));
+        dialog.present();
+    }
+
     fn show_about_dialog(window: &ApplicationWindow) {
         let about = adw::AboutWindow::builder()
             .transient_for(window)
-            .application_name("Proton Game Saves Manager")
+            .application_name(gettext("Proton Game Saves Manager"))
             .version("0.1.0")
             .developer_name("Rahat Zaman")
             .license_type(gtk::License::Gpl30)
-            .comments("Manage your Steam Proton game save files")
+            .comments(gettext("Manage your Steam Proton game save files"))
             .website("https://github.com/rahatzamancse/protongamesaves")
             .issue_url("https://github.com/rahatzamancse/protongamesaves/issues")
             .build();